@@ -0,0 +1,40 @@
+//! Build metadata of the controller, read by `the_league::version`.
+
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+fn main() {
+    // GIT_SHA and SOURCE_DATE_EPOCH let image builds without a .git directory set both.
+    let git_sha = std::env::var("GIT_SHA").ok().or_else(git_sha);
+    println!(
+        "cargo:rustc-env=GIT_SHA={}",
+        git_sha.as_deref().unwrap_or("unknown")
+    );
+
+    let timestamp = std::env::var("SOURCE_DATE_EPOCH").unwrap_or_else(|_| {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default()
+            .to_string()
+    });
+    println!("cargo:rustc-env=BUILD_TIMESTAMP={}", timestamp);
+
+    println!("cargo:rerun-if-env-changed=GIT_SHA");
+    println!("cargo:rerun-if-env-changed=SOURCE_DATE_EPOCH");
+    println!("cargo:rerun-if-changed=.git/HEAD");
+    if let Ok(head) = std::fs::read_to_string(".git/HEAD")
+        && let Some(reference) = head.trim().strip_prefix("ref: ")
+    {
+        println!("cargo:rerun-if-changed=.git/{}", reference);
+    }
+}
+
+fn git_sha() -> Option<String> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--short=12", "HEAD"])
+        .output()
+        .ok()?;
+    let sha = String::from_utf8(output.stdout).ok()?;
+    (output.status.success() && !sha.trim().is_empty()).then(|| sha.trim().to_string())
+}
@@ -0,0 +1,194 @@
+//! Status conditions of the league resources.
+//!
+//! Controllers load the conditions of a status into [`Conditions`], set the ones they own
+//! and patch the status back only when something changed.
+
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::{Condition, Time};
+use k8s_openapi::chrono;
+use serde::Serialize;
+use std::ops::Deref;
+
+/// ConditionStatus is the status of a condition.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConditionStatus {
+    True,
+    False,
+    Unknown,
+}
+
+impl ConditionStatus {
+    /// The status as written to `metav1.Condition`
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ConditionStatus::True => "True",
+            ConditionStatus::False => "False",
+            ConditionStatus::Unknown => "Unknown",
+        }
+    }
+}
+
+/// Conditions are the conditions of a resource status being reconciled at a generation.
+///
+/// Every condition set is stamped with that generation as its observedGeneration, and its
+/// lastTransitionTime is only bumped when the status flips. Serializes as the plain list.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(transparent)]
+pub struct Conditions {
+    conditions: Vec<Condition>,
+
+    #[serde(skip)]
+    generation: Option<i64>,
+
+    #[serde(skip)]
+    changed: bool,
+}
+
+impl Conditions {
+    /// The `conditions` of a status, reconciled at `generation`
+    pub fn new(conditions: &[Condition], generation: Option<i64>) -> Self {
+        Self {
+            conditions: conditions.to_vec(),
+            generation,
+            changed: false,
+        }
+    }
+
+    /// Insert or update the condition of type `type_`. Returns whether anything changed.
+    pub fn set(
+        &mut self,
+        type_: &str,
+        status: ConditionStatus,
+        reason: &str,
+        message: &str,
+    ) -> bool {
+        let status = status.as_str();
+        let existing = self.conditions.iter().position(|c| c.type_ == type_);
+        if let Some(i) = existing {
+            let c = &self.conditions[i];
+            if c.status == status
+                && c.reason == reason
+                && c.message == message
+                && c.observed_generation == self.generation
+            {
+                return false;
+            }
+        }
+
+        let condition = Condition {
+            type_: type_.to_string(),
+            status: status.to_string(),
+            reason: reason.to_string(),
+            message: message.to_string(),
+            last_transition_time: match existing {
+                Some(i) if self.conditions[i].status == status => {
+                    self.conditions[i].last_transition_time.clone()
+                }
+                _ => Time(chrono::Utc::now()),
+            },
+            observed_generation: self.generation,
+        };
+        match existing {
+            Some(i) => self.conditions[i] = condition,
+            None => self.conditions.push(condition),
+        }
+        self.changed = true;
+        true
+    }
+
+    /// Remove the condition of type `type_`. Returns whether it was set.
+    pub fn remove(&mut self, type_: &str) -> bool {
+        let before = self.conditions.len();
+        self.conditions.retain(|c| c.type_ != type_);
+        let removed = self.conditions.len() != before;
+        self.changed |= removed;
+        removed
+    }
+
+    /// The condition of type `type_`, if set
+    pub fn get(&self, type_: &str) -> Option<&Condition> {
+        self.conditions.iter().find(|c| c.type_ == type_)
+    }
+
+    /// Whether the condition of type `type_` is set
+    pub fn has(&self, type_: &str) -> bool {
+        self.get(type_).is_some()
+    }
+
+    /// Whether the condition of type `type_` is set and True
+    pub fn is_true(&self, type_: &str) -> bool {
+        self.get(type_).is_some_and(|c| c.status == "True")
+    }
+
+    /// Whether the condition of type `type_` is set and False
+    pub fn is_false(&self, type_: &str) -> bool {
+        self.get(type_).is_some_and(|c| c.status == "False")
+    }
+
+    /// Whether the condition of type `type_` was set at the generation being reconciled
+    pub fn is_current(&self, type_: &str) -> bool {
+        self.get(type_)
+            .is_some_and(|c| c.observed_generation == self.generation)
+    }
+
+    /// Whether any condition changed since they were loaded, i.e. the status needs a patch
+    pub fn changed(&self) -> bool {
+        self.changed
+    }
+
+    /// The conditions as stored in the status
+    pub fn into_vec(self) -> Vec<Condition> {
+        self.conditions
+    }
+}
+
+impl Deref for Conditions {
+    type Target = [Condition];
+
+    fn deref(&self) -> &[Condition] {
+        &self.conditions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_stamps_generation_and_keeps_transition_time() {
+        let mut conditions = Conditions::new(&[], Some(1));
+        assert!(conditions.set("Ready", ConditionStatus::False, "Starting", "starting"));
+        assert!(!conditions.is_true("Ready"));
+        assert!(conditions.changed());
+        let first = conditions
+            .get("Ready")
+            .unwrap()
+            .last_transition_time
+            .clone();
+
+        // Same status at a new generation: stamped, but no transition.
+        let mut conditions = Conditions::new(&conditions, Some(2));
+        assert!(!conditions.changed());
+        assert!(conditions.set("Ready", ConditionStatus::False, "Waiting", "waiting"));
+        let ready = conditions.get("Ready").unwrap();
+        assert_eq!(ready.observed_generation, Some(2));
+        assert_eq!(ready.reason, "Waiting");
+        assert_eq!(ready.last_transition_time, first);
+
+        // Setting the same condition again is a no-op.
+        let mut conditions = Conditions::new(&conditions, Some(2));
+        assert!(!conditions.set("Ready", ConditionStatus::False, "Waiting", "waiting"));
+        assert!(!conditions.changed());
+
+        assert!(conditions.set("Ready", ConditionStatus::True, "Ready", "ready"));
+        assert!(conditions.is_true("Ready"));
+        assert!(conditions.is_current("Ready"));
+        assert!(!Conditions::new(&conditions, Some(3)).is_current("Ready"));
+        assert!(!conditions.has("Degraded"));
+        assert!(!conditions.is_false("Degraded"));
+        assert_eq!(conditions.len(), 1);
+        assert_eq!(
+            serde_json::to_value(&conditions).unwrap()[0]["status"],
+            serde_json::json!("True")
+        );
+    }
+}
@@ -1 +1,3 @@
-pub mod v1alpha1;
\ No newline at end of file
+pub mod conditions;
+pub mod v1alpha1;
+pub mod v1alpha2;
@@ -1,49 +1,340 @@
-use k8s_openapi::apimachinery::pkg::apis::meta::v1::Time;
-use kube::CustomResource;
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::{Condition, Time};
+use kube::{CustomResource, KubeSchema};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
 /// GameResult is the Schema for the GameResult API.
 /// Each instance records the outcome of a single match.
-#[derive(CustomResource, Deserialize, Serialize, Debug, Clone, JsonSchema)]
+#[derive(CustomResource, KubeSchema, Deserialize, Serialize, Debug, Clone)]
 #[kube(
     group = "bexxmodd.com",
     version = "v1alpha1",
     kind = "GameResult",
     plural = "gameresults",
+    status = "GameResultStatus",
     namespaced,
+    printcolumn = r#"{"name":"Round", "type":"integer", "jsonPath":".spec.roundNumber"}"#,
+    printcolumn = r#"{"name":"Teams", "type":"string", "jsonPath":".spec.teams[*]"}"#,
+    printcolumn = r#"{"name":"Score", "type":"string", "jsonPath":".status.score"}"#,
+    printcolumn = r#"{"name":"Age", "type":"date", "jsonPath":".metadata.creationTimestamp"}"#,
+)]
+#[x_kube(
+    validation = Rule::new("has(self.leagueNamespace) == has(oldSelf.leagueNamespace)")
+        .message("leagueNamespace can't be added or removed after creation")
 )]
 pub struct GameResultSpec {
     /// LeagueName references the parent TheLeague resource this game belongs to.
+    /// It can't be changed after creation.
     #[serde(rename = "leagueName")]
+    #[x_kube(validation = Rule::new("self == oldSelf").message("leagueName is immutable"))]
     pub league_name: String,
 
+    /// LeagueNamespace is the namespace of the referenced TheLeague.
+    /// Defaults to the namespace of this resource.
+    /// It can't be changed, added or removed after creation.
+    #[serde(rename = "leagueNamespace", default, skip_serializing_if = "Option::is_none")]
+    #[x_kube(validation = Rule::new("self == oldSelf").message("leagueNamespace is immutable"))]
+    pub league_namespace: Option<String>,
+
     /// RoundNumber indicates which round of the league schedule this game belongs to.
+    /// It can't be changed after creation.
     #[serde(rename = "roundNumber")]
+    #[x_kube(validation = Rule::new("self == oldSelf").message("roundNumber is immutable"))]
     pub round_number: u32,
 
+    /// Season is the number of the league season the game belongs to, for leagues with a
+    /// season policy. The controller sets it to the current season when it's omitted;
+    /// results counted before the league ran in seasons count in the first season.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub season: Option<u32>,
+
+    /// Playoff is the name of the Playoff (in the league namespace) this game is part of.
+    /// Playoff games don't count for the league table; `roundNumber` is the playoff round.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub playoff: Option<String>,
+
+    /// Tournament is the name of the Tournament (in the league namespace) this game is part of.
+    /// Tournament games don't count for the league table; `roundNumber` is the tournament round.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tournament: Option<String>,
+
     /// Teams contains the names of the two teams that played the game.
+    /// They can't be changed after creation.
+    #[x_kube(validation = Rule::new("self == oldSelf").message("teams is immutable"))]
     pub teams: [String; 2],
 
     /// Time is the time the game was played, preferably in RFC3339 format.
-    pub time: Time,
+    /// For games still to be played it is the scheduled kickoff, unset until it's known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time: Option<Time>,
+
+    /// OriginallyScheduledAt is the kickoff the game was first scheduled for.
+    /// Set by the controller the first time `time` is moved, so rescheduled games remain traceable.
+    #[serde(rename = "originallyScheduledAt", default, skip_serializing_if = "Option::is_none")]
+    pub originally_scheduled_at: Option<Time>,
 
     /// Result specifies the outcome and scores of the game.
     pub result: GameOutcome,
+
+    /// Scorers lists the goals of the game by player.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub scorers: Vec<PlayerStat>,
+
+    /// Assists lists the assists of the game by player.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub assists: Vec<PlayerStat>,
+
+    /// Cards lists the disciplinary cards shown during the game.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub cards: Vec<Card>,
+
+    /// Venue is the optional name of the ground the game was played at.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub venue: Option<String>,
+
+    /// Attendance is the optional number of spectators at the game.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attendance: Option<u32>,
+}
+
+impl GameResultSpec {
+    /// Whether the game is a league game rather than part of a Playoff or Tournament
+    pub fn is_league_game(&self) -> bool {
+        self.playoff.is_none() && self.tournament.is_none()
+    }
+}
+
+/// Builder of the specs of test results: `GameResultSpec::game` starts from a goalless draw in
+/// round 1 of league `premier`, played now, and the other methods adjust it.
+#[cfg(test)]
+impl GameResultSpec {
+    /// A goalless draw of `home` against `away` in round 1 of league `premier`, played now
+    pub fn game(home: &str, away: &str) -> Self {
+        crate::import::result_spec(
+            "premier",
+            1,
+            [home.to_string(), away.to_string()],
+            GameOutcome::Draw { score: 0 },
+            k8s_openapi::chrono::Utc::now(),
+        )
+    }
+
+    /// The game in league `league_name`
+    pub fn in_league(mut self, league_name: &str) -> Self {
+        self.league_name = league_name.to_string();
+        self
+    }
+
+    /// The game in round `round_number`
+    pub fn in_round(mut self, round_number: u32) -> Self {
+        self.round_number = round_number;
+        self
+    }
+
+    /// The game in `season`
+    pub fn in_season(mut self, season: Option<u32>) -> Self {
+        self.season = season;
+        self
+    }
+
+    /// The game in the series of Playoff `playoff`
+    pub fn in_playoff(mut self, playoff: &str) -> Self {
+        self.playoff = Some(playoff.to_string());
+        self
+    }
+
+    /// The game kicking off at `time`
+    pub fn at(mut self, time: k8s_openapi::chrono::DateTime<k8s_openapi::chrono::Utc>) -> Self {
+        self.time = Some(Time(time));
+        self
+    }
+
+    /// The game ending `score_home`-`score_away` in regulation
+    pub fn with_score(self, score_home: u32, score_away: u32) -> Self {
+        let outcome = crate::import::outcome(score_home, score_away, DecidedBy::Regulation, None);
+        self.with_result(outcome)
+    }
+
+    /// The game ending in `result`
+    pub fn with_result(mut self, result: GameOutcome) -> Self {
+        self.result = result;
+        self
+    }
+
+    /// The game with `cards` shown
+    pub fn with_cards(mut self, cards: Vec<Card>) -> Self {
+        self.cards = cards;
+        self
+    }
+}
+
+/// PlayerStat attributes a single goal or assist to a player on a team's roster.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
+pub struct PlayerStat {
+    /// Team is the name of the player's team; one of the game's `teams`.
+    pub team: String,
+
+    /// FirstName is the first name of the player as listed in the league roster.
+    #[serde(rename = "firstName")]
+    pub first_name: String,
+
+    /// LastName is the last name of the player as listed in the league roster.
+    #[serde(rename = "lastName")]
+    pub last_name: String,
+
+    /// Minute is the optional minute of the game the event happened in.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub minute: Option<u32>,
+}
+
+/// Card is a disciplinary card shown to a player on a team's roster.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
+pub struct Card {
+    /// Team is the name of the player's team; one of the game's `teams`.
+    pub team: String,
+
+    /// FirstName is the first name of the player as listed in the league roster.
+    #[serde(rename = "firstName")]
+    pub first_name: String,
+
+    /// LastName is the last name of the player as listed in the league roster.
+    #[serde(rename = "lastName")]
+    pub last_name: String,
+
+    /// Minute is the optional minute of the game the card was shown in.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub minute: Option<u32>,
+
+    /// Color is the color of the card.
+    pub color: CardColor,
+}
+
+/// CardColor is the color of a disciplinary card.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
+pub enum CardColor {
+    Yellow,
+    Red,
+}
+
+/// DecidedBy defines which period of the game produced the winner.
+#[derive(Deserialize, Serialize, Debug, Default, Clone, Copy, PartialEq, Eq, JsonSchema, ToSchema)]
+pub enum DecidedBy {
+    /// Regulation means the game was won in regular time.
+    #[default]
+    Regulation,
+
+    /// Overtime means the game was won in overtime; the scores include overtime goals.
+    Overtime,
+
+    /// Shootout means the game was level after overtime and won in a shootout.
+    /// The scores are the level game score, the shootout score is recorded separately.
+    Shootout,
+}
+
+/// ShootoutScore is the score of a shootout deciding a game.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq, JsonSchema, ToSchema)]
+pub struct ShootoutScore {
+    /// Home is the number of shootout goals of the home team.
+    pub home: u32,
+
+    /// Away is the number of shootout goals of the away team.
+    pub away: u32,
+}
+
+/// GameResultStatus defines the observed state of the GameResult.
+/// This field is managed by the controller.
+#[derive(Deserialize, Serialize, Debug, Default, Clone, JsonSchema)]
+pub struct GameResultStatus {
+    /// Conditions represent the latest available observations of the GameResult's state.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub conditions: Vec<Condition>,
+
+    /// AppliedGeneration is the generation of the spec counted in the standings.
+    /// Once set, the spec may only change together with the `bexxmodd.com/recompute` annotation.
+    #[serde(rename = "appliedGeneration", default, skip_serializing_if = "Option::is_none")]
+    pub applied_generation: Option<i64>,
+
+    /// AppliedPoints is the points the applied spec awards the home and away team, used to
+    /// report the change in the standings when a correction is applied.
+    #[serde(rename = "appliedPoints", default, skip_serializing_if = "Option::is_none")]
+    pub applied_points: Option<[u32; 2]>,
+
+    /// CorrectedRound is the scheduled round the game counts in, for a result of a league
+    /// without a strict schedule whose `roundNumber` doesn't schedule its pairing.
+    #[serde(rename = "correctedRound", default, skip_serializing_if = "Option::is_none")]
+    pub corrected_round: Option<u32>,
+
+    /// ScheduledTime is the kickoff `time` last observed by the controller, used to detect
+    /// rescheduled games.
+    #[serde(rename = "scheduledTime", default, skip_serializing_if = "Option::is_none")]
+    pub scheduled_time: Option<Time>,
+
+    /// Score is the final score of a completed game as `home-away`, e.g. `2-1`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub score: Option<String>,
+
+    /// LastReconciled is the time the controller last successfully reconciled the GameResult.
+    #[serde(rename = "lastReconciled", default, skip_serializing_if = "Option::is_none")]
+    pub last_reconciled: Option<Time>,
+
+    /// ControllerVersion is the version of the controller that last reconciled the GameResult.
+    #[serde(rename = "controllerVersion", default, skip_serializing_if = "Option::is_none")]
+    pub controller_version: Option<String>,
+}
+
+impl GameResult {
+    /// Round the game counts in: the round the controller corrected it to, if any, otherwise
+    /// `spec.roundNumber`
+    pub fn round(&self) -> u32 {
+        self.status
+            .as_ref()
+            .and_then(|s| s.corrected_round)
+            .unwrap_or(self.spec.round_number)
+    }
 }
 
 /// GameOutcome defines the outcome and point distribution for the match.
-/// (Winner: 3 points, Loser: 0 points, Draw: 1 point each)
+/// (By default Winner: 3 points, Loser: 0 points, Draw: 1 point each; see TheLeague pointsPolicy)
 #[derive(Deserialize, Serialize, Debug, Clone, JsonSchema)]
 pub enum GameOutcome {
     /// WinnerHomeTeam indicates the team whose name is the FIRST element
     /// in the `teams` array won (the 'Home' team).
-    WinnerHomeTeam { score_home: u32, score_away: u32 },
+    WinnerHomeTeam {
+        score_home: u32,
+        score_away: u32,
+        #[serde(rename = "decidedBy", default)]
+        decided_by: DecidedBy,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        shootout: Option<ShootoutScore>,
+    },
 
     /// WinnerAwayTeam indicates the team whose name is the SECOND element
     /// in the `teams` array won (the 'Away' team).
-    WinnerAwayTeam { score_home: u32, score_away: u32 },
+    WinnerAwayTeam {
+        score_home: u32,
+        score_away: u32,
+        #[serde(rename = "decidedBy", default)]
+        decided_by: DecidedBy,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        shootout: Option<ShootoutScore>,
+    },
 
     /// Draw indicates a tie game.
     Draw { score: u32 },
+
+    /// Postponed indicates the game did not take place on the scheduled date.
+    /// It doesn't count in the standings and its fixture stays open for a replay.
+    Postponed {
+        #[serde(rename = "rescheduledTo", default, skip_serializing_if = "Option::is_none")]
+        rescheduled_to: Option<Time>,
+    },
+
+    /// Abandoned indicates the game was stopped at the given minute before completion.
+    /// It doesn't count in the standings and its fixture stays open for a replay.
+    Abandoned { minute: u32 },
+
+    /// Pending indicates the game has not been played yet. Used for results pre-created
+    /// from the schedule that the scorekeeper fills in later.
+    Pending {},
 }
@@ -0,0 +1,104 @@
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::Time;
+use kube::CustomResource;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Match is the Schema for the Match API.
+/// Each instance is one fixture of a league schedule. Matches are generated by the controller
+/// from the round-robin schedule and linked to the GameResult recording the game.
+#[derive(CustomResource, Deserialize, Serialize, Debug, Clone, JsonSchema)]
+#[kube(
+    group = "bexxmodd.com",
+    version = "v1alpha1",
+    kind = "Match",
+    plural = "matches",
+    status = "MatchStatus",
+    namespaced,
+    printcolumn = r#"{"name":"League", "type":"string", "jsonPath":".spec.leagueName"}"#,
+    printcolumn = r#"{"name":"Round", "type":"integer", "jsonPath":".spec.round"}"#,
+    printcolumn = r#"{"name":"Home", "type":"string", "jsonPath":".spec.home"}"#,
+    printcolumn = r#"{"name":"Away", "type":"string", "jsonPath":".spec.away"}"#,
+    printcolumn = r#"{"name":"Phase", "type":"string", "jsonPath":".status.phase"}"#,
+    printcolumn = r#"{"name":"Referee", "type":"string", "jsonPath":".status.referee"}"#,
+    printcolumn = r#"{"name":"Venue", "type":"string", "jsonPath":".status.venue"}"#,
+    printcolumn = r#"{"name":"Age", "type":"date", "jsonPath":".metadata.creationTimestamp"}"#
+)]
+pub struct MatchSpec {
+    /// LeagueName references the TheLeague this match is scheduled in.
+    #[serde(rename = "leagueName")]
+    pub league_name: String,
+
+    /// Season is the number of the league season, for leagues with a season policy.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub season: Option<u32>,
+
+    /// Round is the round of the schedule the match is played in.
+    pub round: u32,
+
+    /// Home is the name of the home team.
+    pub home: String,
+
+    /// Away is the name of the away team.
+    pub away: String,
+
+    /// Kickoff is the time the match is scheduled for by the calendar of the league.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kickoff: Option<Time>,
+}
+
+/// MatchStatus defines the observed state of the Match.
+/// This field is managed by the controller.
+#[derive(Deserialize, Serialize, Debug, Default, Clone, PartialEq, JsonSchema)]
+pub struct MatchStatus {
+    /// Phase tells whether the match has been played.
+    #[serde(default)]
+    pub phase: MatchPhase,
+
+    /// Kickoff is the time of the match as recorded by its GameResult, or the date a
+    /// postponed match was rescheduled to. Matches without a GameResult kick off at the
+    /// scheduled `spec.kickoff`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kickoff: Option<Time>,
+
+    /// Result is the name of the GameResult recording the match.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<String>,
+
+    /// Referee is the name of the Referee assigned to officiate the match.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub referee: Option<String>,
+
+    /// Venue is the name of the Venue booked for the match.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub venue: Option<String>,
+
+    /// LastReconciled is the time the controller last updated the match from its results.
+    #[serde(
+        rename = "lastReconciled",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub last_reconciled: Option<Time>,
+
+    /// ControllerVersion is the version of the controller that last updated the match.
+    #[serde(
+        rename = "controllerVersion",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub controller_version: Option<String>,
+}
+
+/// MatchPhase is the lifecycle phase of a match.
+#[derive(Deserialize, Serialize, Debug, Default, Clone, Copy, PartialEq, Eq, JsonSchema)]
+pub enum MatchPhase {
+    /// Scheduled means the match has not been played yet.
+    #[default]
+    Scheduled,
+
+    /// Played means a GameResult with a final score counts for the match.
+    Played,
+
+    /// Postponed means the match did not take place and waits for a replay.
+    Postponed,
+}
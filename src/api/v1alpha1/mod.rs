@@ -1,3 +1,13 @@
 pub mod the_league_types;
 pub mod game_result_types;
-pub mod standing_types;
\ No newline at end of file
+pub mod match_types;
+pub mod player_types;
+pub mod playoff_types;
+pub mod referee_types;
+pub mod season_types;
+pub mod standing_types;
+pub mod suspension_types;
+pub mod team_types;
+pub mod tournament_types;
+pub mod transfer_types;
+pub mod venue_types;
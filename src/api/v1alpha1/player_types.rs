@@ -0,0 +1,105 @@
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::Time;
+use kube::CustomResource;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Player is the Schema for the Player API.
+/// Each instance is a player independent of any team; roster entries reference it through
+/// `playerRef`. The controller aggregates the career statistics of the player across the
+/// leagues and seasons of its namespace.
+#[derive(CustomResource, Deserialize, Serialize, Debug, Clone, JsonSchema)]
+#[kube(
+    group = "bexxmodd.com",
+    version = "v1alpha1",
+    kind = "Player",
+    plural = "players",
+    status = "PlayerStatus",
+    namespaced,
+    printcolumn = r#"{"name":"First", "type":"string", "jsonPath":".spec.firstName"}"#,
+    printcolumn = r#"{"name":"Last", "type":"string", "jsonPath":".spec.lastName"}"#,
+    printcolumn = r#"{"name":"Goals", "type":"integer", "jsonPath":".status.goals"}"#,
+    printcolumn = r#"{"name":"Assists", "type":"integer", "jsonPath":".status.assists"}"#,
+    printcolumn = r#"{"name":"Age", "type":"date", "jsonPath":".metadata.creationTimestamp"}"#
+)]
+pub struct PlayerSpec {
+    /// FirstName is the first name of the player.
+    #[serde(rename = "firstName")]
+    #[schemars(regex(pattern = r"^[a-zA-Z]+(['-][a-zA-Z]+)*$"))]
+    pub first_name: String,
+
+    /// LastName is the last name of the player.
+    #[serde(rename = "lastName")]
+    #[schemars(regex(pattern = r"^[a-zA-Z]+(['-][a-zA-Z]+)*$"))]
+    pub last_name: String,
+}
+
+/// PlayerStatus defines the observed state of the Player.
+/// This field is managed by the controller.
+#[derive(Deserialize, Serialize, Debug, Default, Clone, PartialEq, JsonSchema)]
+pub struct PlayerStatus {
+    /// Goals is the number of goals scored over the career of the player.
+    #[serde(default)]
+    pub goals: u32,
+
+    /// Assists is the number of assists over the career of the player.
+    #[serde(default)]
+    pub assists: u32,
+
+    /// YellowCards is the number of yellow cards shown to the player.
+    #[serde(rename = "yellowCards", default)]
+    pub yellow_cards: u32,
+
+    /// RedCards is the number of red cards shown to the player.
+    #[serde(rename = "redCards", default)]
+    pub red_cards: u32,
+
+    /// Seasons breaks the career statistics down per league, season and team.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub seasons: Vec<PlayerSeasonStats>,
+
+    /// LastReconciled is the time the controller last updated the statistics of the player.
+    #[serde(
+        rename = "lastReconciled",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub last_reconciled: Option<Time>,
+
+    /// ControllerVersion is the version of the controller that last updated the statistics.
+    #[serde(
+        rename = "controllerVersion",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub controller_version: Option<String>,
+}
+
+/// PlayerSeasonStats are the statistics of a player for one team in one league season.
+#[derive(Deserialize, Serialize, Debug, Default, Clone, PartialEq, Eq, JsonSchema)]
+pub struct PlayerSeasonStats {
+    /// League is the name of the TheLeague.
+    pub league: String,
+
+    /// Season is the number of the league season, for leagues with a season policy.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub season: Option<u32>,
+
+    /// Team is the name of the team the player played for.
+    pub team: String,
+
+    /// Goals is the number of goals scored.
+    #[serde(default)]
+    pub goals: u32,
+
+    /// Assists is the number of assists.
+    #[serde(default)]
+    pub assists: u32,
+
+    /// YellowCards is the number of yellow cards shown.
+    #[serde(rename = "yellowCards", default)]
+    pub yellow_cards: u32,
+
+    /// RedCards is the number of red cards shown.
+    #[serde(rename = "redCards", default)]
+    pub red_cards: u32,
+}
@@ -0,0 +1,125 @@
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::Time;
+use kube::CustomResource;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Playoff is the Schema for the Playoff API.
+/// Each instance is a single-elimination playoff following a league. The controller seeds the
+/// bracket from the final standings once the league completes and advances the winners of
+/// every series from the GameResults referencing the playoff.
+#[derive(CustomResource, Deserialize, Serialize, Debug, Clone, JsonSchema)]
+#[kube(
+    group = "bexxmodd.com",
+    version = "v1alpha1",
+    kind = "Playoff",
+    plural = "playoffs",
+    status = "PlayoffStatus",
+    namespaced,
+    printcolumn = r#"{"name":"League", "type":"string", "jsonPath":".spec.leagueName"}"#,
+    printcolumn = r#"{"name":"Phase", "type":"string", "jsonPath":".status.phase"}"#,
+    printcolumn = r#"{"name":"Champion", "type":"string", "jsonPath":".status.champion"}"#,
+    printcolumn = r#"{"name":"Age", "type":"date", "jsonPath":".metadata.creationTimestamp"}"#
+)]
+pub struct PlayoffSpec {
+    /// LeagueName references the TheLeague (in the same namespace) whose standings seed the playoff.
+    #[serde(rename = "leagueName")]
+    pub league_name: String,
+
+    /// Qualifiers is the number of teams from the top of the final table taking part (default 4).
+    /// Top seeds get a bye when it isn't a power of two.
+    #[serde(default = "default_qualifiers")]
+    #[schemars(range(min = 2))]
+    pub qualifiers: u32,
+
+    /// BestOf is the maximum number of games of a series (default 1); the first team to win
+    /// a majority of them advances.
+    #[serde(rename = "bestOf", default = "default_best_of")]
+    #[schemars(range(min = 1))]
+    pub best_of: u32,
+}
+
+fn default_qualifiers() -> u32 {
+    4
+}
+
+fn default_best_of() -> u32 {
+    1
+}
+
+/// PlayoffStatus defines the observed state of the Playoff.
+/// This field is managed by the controller.
+#[derive(Deserialize, Serialize, Debug, Default, Clone, PartialEq, JsonSchema)]
+pub struct PlayoffStatus {
+    /// Phase is the lifecycle phase of the playoff.
+    #[serde(default)]
+    pub phase: PlayoffPhase,
+
+    /// Seeds lists the qualified teams, best seed first, as taken from the final table.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub seeds: Vec<String>,
+
+    /// Series lists the series of the bracket, round by round.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub series: Vec<Series>,
+
+    /// Champion is the winner of the final.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub champion: Option<String>,
+
+    /// LastReconciled is the time the controller last successfully reconciled the Playoff.
+    #[serde(
+        rename = "lastReconciled",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub last_reconciled: Option<Time>,
+
+    /// ControllerVersion is the version of the controller that last reconciled the Playoff.
+    #[serde(
+        rename = "controllerVersion",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub controller_version: Option<String>,
+}
+
+/// PlayoffPhase is the lifecycle phase of a playoff.
+#[derive(Deserialize, Serialize, Debug, Default, Clone, Copy, PartialEq, Eq, JsonSchema)]
+pub enum PlayoffPhase {
+    /// Pending means the league hasn't completed yet, so the bracket isn't seeded.
+    #[default]
+    Pending,
+
+    /// InProgress means the bracket is seeded and series are being played.
+    InProgress,
+
+    /// Completed means the final has been decided.
+    Completed,
+}
+
+/// Series is a pairing of two teams in a round of a knockout bracket.
+#[derive(Deserialize, Serialize, Debug, Default, Clone, PartialEq, Eq, JsonSchema)]
+pub struct Series {
+    /// Round is the 1-based round of the bracket.
+    pub round: u32,
+
+    /// Home is the better seeded team, unknown until the previous round is decided.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub home: Option<String>,
+
+    /// Away is the other team, unknown until the previous round is decided.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub away: Option<String>,
+
+    /// HomeWins is the number of games of the series won by the home team.
+    #[serde(rename = "homeWins", default)]
+    pub home_wins: u32,
+
+    /// AwayWins is the number of games of the series won by the away team.
+    #[serde(rename = "awayWins", default)]
+    pub away_wins: u32,
+
+    /// Winner is the team advancing from the series once it's decided.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub winner: Option<String>,
+}
@@ -0,0 +1,53 @@
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::Time;
+use kube::CustomResource;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Referee is the Schema for the Referee API.
+/// Each instance is a referee the controller can assign to the Matches of the leagues in its
+/// namespace. Assignments are shown in `status.referee` of the Matches.
+#[derive(CustomResource, Deserialize, Serialize, Debug, Clone, JsonSchema)]
+#[kube(
+    group = "bexxmodd.com",
+    version = "v1alpha1",
+    kind = "Referee",
+    plural = "referees",
+    namespaced,
+    printcolumn = r#"{"name":"First", "type":"string", "jsonPath":".spec.firstName"}"#,
+    printcolumn = r#"{"name":"Last", "type":"string", "jsonPath":".spec.lastName"}"#,
+    printcolumn = r#"{"name":"Age", "type":"date", "jsonPath":".metadata.creationTimestamp"}"#
+)]
+pub struct RefereeSpec {
+    /// FirstName is the first name of the referee.
+    #[serde(rename = "firstName")]
+    #[schemars(regex(pattern = r"^[a-zA-Z]+(['-][a-zA-Z]+)*$"))]
+    pub first_name: String,
+
+    /// LastName is the last name of the referee.
+    #[serde(rename = "lastName")]
+    #[schemars(regex(pattern = r"^[a-zA-Z]+(['-][a-zA-Z]+)*$"))]
+    pub last_name: String,
+
+    /// Availability lists the periods the referee can officiate in. A referee without
+    /// availability windows is always available; otherwise the referee is only assigned to
+    /// matches with a known kickoff inside one of the windows.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub availability: Vec<AvailabilityWindow>,
+}
+
+/// AvailabilityWindow is a period a referee is available in.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
+pub struct AvailabilityWindow {
+    /// Start is the beginning of the window.
+    pub start: Time,
+
+    /// End is the end of the window, exclusive.
+    pub end: Time,
+}
+
+impl AvailabilityWindow {
+    /// Whether `at` falls within the window
+    pub fn contains(&self, at: &Time) -> bool {
+        self.start.0 <= at.0 && at.0 < self.end.0
+    }
+}
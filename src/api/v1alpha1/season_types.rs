@@ -0,0 +1,91 @@
+use crate::api::v1alpha1::the_league_types::TableRow;
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::Time;
+use kube::CustomResource;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Season is the Schema for the Season API.
+/// Each instance is one season of a league; the controller creates them according to the
+/// league's season policy and archives the final table when the season is over.
+#[derive(CustomResource, Deserialize, Serialize, Debug, Clone, JsonSchema)]
+#[kube(
+    group = "bexxmodd.com",
+    version = "v1alpha1",
+    kind = "Season",
+    plural = "seasons",
+    status = "SeasonStatus",
+    namespaced,
+    printcolumn = r#"{"name":"League", "type":"string", "jsonPath":".spec.leagueName"}"#,
+    printcolumn = r#"{"name":"Season", "type":"integer", "jsonPath":".spec.number"}"#,
+    printcolumn = r#"{"name":"Phase", "type":"string", "jsonPath":".status.phase"}"#,
+    printcolumn = r#"{"name":"Champion", "type":"string", "jsonPath":".status.champion"}"#,
+    printcolumn = r#"{"name":"Age", "type":"date", "jsonPath":".metadata.creationTimestamp"}"#
+)]
+pub struct SeasonSpec {
+    /// LeagueName references the TheLeague this season belongs to.
+    #[serde(rename = "leagueName")]
+    pub league_name: String,
+
+    /// Number is the 1-based number of the season within the league.
+    pub number: u32,
+
+    /// StartDate is the time the season started.
+    #[serde(rename = "startDate")]
+    pub start_date: Time,
+
+    /// EndDate is the optional time the season ends even if fixtures are still outstanding.
+    #[serde(rename = "endDate", default, skip_serializing_if = "Option::is_none")]
+    pub end_date: Option<Time>,
+}
+
+/// SeasonStatus defines the observed state of the Season.
+/// This field is managed by the controller.
+#[derive(Deserialize, Serialize, Debug, Default, Clone, JsonSchema)]
+pub struct SeasonStatus {
+    /// Phase is the lifecycle phase of the season.
+    #[serde(default)]
+    pub phase: SeasonPhase,
+
+    /// Table is the final league table, archived when the season completes.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub table: Vec<TableRow>,
+
+    /// Champion is the team finishing first in the archived table.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub champion: Option<String>,
+
+    /// CompletedAt is the time the season was archived.
+    #[serde(
+        rename = "completedAt",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub completed_at: Option<Time>,
+
+    /// LastReconciled is the time the controller archived the season.
+    #[serde(
+        rename = "lastReconciled",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub last_reconciled: Option<Time>,
+
+    /// ControllerVersion is the version of the controller that archived the season.
+    #[serde(
+        rename = "controllerVersion",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub controller_version: Option<String>,
+}
+
+/// SeasonPhase is the lifecycle phase of a season.
+#[derive(Deserialize, Serialize, Debug, Default, Clone, Copy, PartialEq, Eq, JsonSchema)]
+pub enum SeasonPhase {
+    /// Active means results are currently recorded for this season.
+    #[default]
+    Active,
+
+    /// Completed means the season is over and its table is archived.
+    Completed,
+}
@@ -1,11 +1,12 @@
-use k8s_openapi::apimachinery::pkg::apis::meta::v1::Condition;
-use kube::CustomResource;
+use crate::api::v1alpha1::the_league_types::TeamBranding;
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::{Condition, Time};
+use kube::{CustomResource, KubeSchema};
 use serde::{Deserialize, Serialize};
 use schemars::JsonSchema;
 
 /// Standing is the Schema for the Standing API.
 /// This resource tracks the calculated performance for a single Team.
-#[derive(CustomResource, Deserialize, Serialize, Debug, Clone, JsonSchema)]
+#[derive(CustomResource, KubeSchema, Deserialize, Serialize, Debug, Clone)]
 #[kube(
     group = "bexxmodd.com",
     version = "v1alpha1",
@@ -13,24 +14,55 @@ use schemars::JsonSchema;
     plural = "standings",
     status = "StandingStatus",
     namespaced,
+    printcolumn = r#"{"name":"Points", "type":"integer", "jsonPath":".status.points"}"#,
+    printcolumn = r#"{"name":"W", "type":"integer", "jsonPath":".status.wins"}"#,
+    printcolumn = r#"{"name":"D", "type":"integer", "jsonPath":".status.draws"}"#,
+    printcolumn = r#"{"name":"L", "type":"integer", "jsonPath":".status.losses"}"#,
+    printcolumn = r#"{"name":"Rank", "type":"integer", "jsonPath":".status.rank"}"#,
+    printcolumn = r#"{"name":"Age", "type":"date", "jsonPath":".metadata.creationTimestamp"}"#,
+)]
+#[x_kube(
+    validation = Rule::new("has(self.leagueNamespace) == has(oldSelf.leagueNamespace)")
+        .message("leagueNamespace can't be added or removed after creation")
 )]
 pub struct StandingSpec {
     /// LeagueName references the parent TheLeague resource this standing belongs to.
+    /// It can't be changed after creation.
     #[serde(rename = "leagueName")]
+    #[x_kube(validation = Rule::new("self == oldSelf").message("leagueName is immutable"))]
     pub league_name: String,
 
+    /// LeagueNamespace is the namespace of the referenced TheLeague.
+    /// Defaults to the namespace of this resource.
+    /// It can't be changed, added or removed after creation.
+    #[serde(rename = "leagueNamespace", default, skip_serializing_if = "Option::is_none")]
+    #[x_kube(validation = Rule::new("self == oldSelf").message("leagueNamespace is immutable"))]
+    pub league_namespace: Option<String>,
+
     /// TeamName is the name of the team this standing corresponds to.
+    /// It can't be changed after creation.
     #[serde(rename = "teamName")]
+    #[x_kube(validation = Rule::new("self == oldSelf").message("teamName is immutable"))]
     pub team_name: String,
 
     /// Resolution defines the tie-breaking method used for calculating the standing.
+    /// Defaults to GoalDifference when omitted.
+    #[serde(default)]
     pub resolution: StandingResolution
 }
 
 /// StandingStatus defines the observed and computed state of the Standing.
 /// This field is managed by the controller.
-#[derive(Deserialize, Serialize, Debug, Default, Clone, JsonSchema)]
+#[derive(Deserialize, Serialize, Debug, Default, Clone, PartialEq, JsonSchema)]
 pub struct StandingStatus {
+    /// Rank is the position of the team in the league table (1 is the leader).
+    #[serde(default)]
+    pub rank: u32,
+
+    /// Played is the number of games the team has played.
+    #[serde(default)]
+    pub played: u32,
+
     /// Points is the total accumulated points for the team.
     pub points: u32,
 
@@ -43,17 +75,46 @@ pub struct StandingStatus {
     /// Draws is the total number of draws.
     pub draws: u32,
 
+    /// GoalsFor is the total number of goals scored by the team.
+    #[serde(rename = "goalsFor", default)]
+    pub goals_for: u32,
+
+    /// GoalsAgainst is the total number of goals conceded by the team.
+    #[serde(rename = "goalsAgainst", default)]
+    pub goals_against: u32,
+
+    /// TotalAttendance is the number of spectators over the team's home games.
+    #[serde(rename = "totalAttendance", default)]
+    pub total_attendance: u64,
+
+    /// AverageAttendance is the mean attendance of the team's home games with a reported attendance.
+    #[serde(rename = "averageAttendance", default)]
+    pub average_attendance: u64,
+
+    /// Branding is the club identity of the team as defined by its league.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branding: Option<TeamBranding>,
+
     /// Conditions represent the latest available observations of the Standing's state.
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub conditions: Vec<Condition>,
+
+    /// LastReconciled is the time the controller last successfully reconciled the Standing.
+    #[serde(rename = "lastReconciled", default, skip_serializing_if = "Option::is_none")]
+    pub last_reconciled: Option<Time>,
+
+    /// ControllerVersion is the version of the controller that last reconciled the Standing.
+    #[serde(rename = "controllerVersion", default, skip_serializing_if = "Option::is_none")]
+    pub controller_version: Option<String>,
 }
 
 /// StandingResolution defines the tie-breaking method used for the standings.
-#[derive(Deserialize, Serialize, Debug, Clone, JsonSchema)]
+#[derive(Deserialize, Serialize, Debug, Default, Clone, PartialEq, Eq, JsonSchema)]
 pub enum StandingResolution {
     /// Head2Head resolution prioritizes the outcome of direct matches between tied teams.
     Head2Head,
     
     /// GoalDifference resolution prioritizes the overall goal difference across all matches.
+    #[default]
     GoalDifference,
 }
\ No newline at end of file
@@ -0,0 +1,53 @@
+use kube::CustomResource;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Suspension is the Schema for the Suspension API.
+/// Each instance flags a player who reached the card threshold of the league's suspension policy.
+/// Suspensions are created by the controller; organizers mark them as served.
+#[derive(CustomResource, Deserialize, Serialize, Debug, Clone, JsonSchema)]
+#[kube(
+    group = "bexxmodd.com",
+    version = "v1alpha1",
+    kind = "Suspension",
+    plural = "suspensions",
+    namespaced,
+    printcolumn = r#"{"name":"League", "type":"string", "jsonPath":".spec.leagueName"}"#,
+    printcolumn = r#"{"name":"Team", "type":"string", "jsonPath":".spec.team"}"#,
+    printcolumn = r#"{"name":"Reason", "type":"string", "jsonPath":".spec.reason"}"#,
+    printcolumn = r#"{"name":"Served", "type":"boolean", "jsonPath":".spec.served"}"#,
+    printcolumn = r#"{"name":"Age", "type":"date", "jsonPath":".metadata.creationTimestamp"}"#
+)]
+pub struct SuspensionSpec {
+    /// LeagueName references the TheLeague the suspension applies in.
+    #[serde(rename = "leagueName")]
+    pub league_name: String,
+
+    /// Team is the name of the player's team.
+    pub team: String,
+
+    /// FirstName is the first name of the suspended player as listed in the league roster.
+    #[serde(rename = "firstName")]
+    pub first_name: String,
+
+    /// LastName is the last name of the suspended player as listed in the league roster.
+    #[serde(rename = "lastName")]
+    pub last_name: String,
+
+    /// Reason is the kind of cards that triggered the suspension.
+    pub reason: SuspensionReason,
+
+    /// Served marks a suspension the player has sat out.
+    #[serde(default)]
+    pub served: bool,
+}
+
+/// SuspensionReason defines what triggered a suspension.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, JsonSchema)]
+pub enum SuspensionReason {
+    /// YellowCards means the player collected the yellow card limit of the suspension policy.
+    YellowCards,
+
+    /// RedCard means the player was shown a red card.
+    RedCard,
+}
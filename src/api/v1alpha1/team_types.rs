@@ -0,0 +1,98 @@
+use crate::api::v1alpha1::the_league_types::Player;
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::Time;
+use kube::CustomResource;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Team is the Schema for the Team API.
+/// Each instance holds the roster of a team independently of any league; leagues reference it
+/// by name from `spec.teams[].teamRef` instead of embedding the roster.
+#[derive(CustomResource, Deserialize, Serialize, Debug, Clone, JsonSchema)]
+#[kube(
+    group = "bexxmodd.com",
+    version = "v1alpha1",
+    kind = "Team",
+    plural = "teams",
+    status = "TeamStatus",
+    namespaced,
+    printcolumn = r#"{"name":"Venue", "type":"string", "jsonPath":".spec.homeVenue"}"#,
+    printcolumn = r#"{"name":"Age", "type":"date", "jsonPath":".metadata.creationTimestamp"}"#
+)]
+pub struct TeamSpec {
+    /// Description provides an optional short description for the team.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    /// HomeVenue is the optional stadium or field the team plays its home games at.
+    #[serde(rename = "homeVenue", default, skip_serializing_if = "Option::is_none")]
+    pub home_venue: Option<String>,
+
+    /// Abbreviation is the optional 2-4 character short name of the team, e.g. `TIG`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(length(min = 2, max = 4))]
+    pub abbreviation: Option<String>,
+
+    /// Colors lists the team colors, e.g. `["#ff6600", "black"]`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub colors: Vec<String>,
+
+    /// LogoUrl is the optional URL of the team logo.
+    #[serde(rename = "logoUrl", default, skip_serializing_if = "Option::is_none")]
+    pub logo_url: Option<String>,
+
+    /// Captain is the optional captain of the team, given as the `First Last` name or the
+    /// `playerRef` of a player on the roster.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub captain: Option<String>,
+
+    /// Players is the roster of players on this team.
+    #[serde(default)]
+    pub players: Vec<Player>,
+}
+
+/// TeamStatus defines the observed state of the Team.
+/// This field is managed by the controller.
+#[derive(Deserialize, Serialize, Debug, Default, Clone, PartialEq, JsonSchema)]
+pub struct TeamStatus {
+    /// History lists the transfers of players joining or leaving the team, oldest first.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub history: Vec<RosterMove>,
+
+    /// LastReconciled is the time the controller last recorded a roster move of the team.
+    #[serde(
+        rename = "lastReconciled",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub last_reconciled: Option<Time>,
+
+    /// ControllerVersion is the version of the controller that last recorded a roster move.
+    #[serde(
+        rename = "controllerVersion",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub controller_version: Option<String>,
+}
+
+/// RosterMove records a player moving from one team to another.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
+pub struct RosterMove {
+    /// Transfer is the name of the Transfer that moved the player.
+    pub transfer: String,
+
+    /// Player is the name of the Player that moved.
+    pub player: String,
+
+    /// FromTeam is the Team the player left.
+    #[serde(rename = "fromTeam")]
+    pub from_team: String,
+
+    /// ToTeam is the Team the player joined.
+    #[serde(rename = "toTeam")]
+    pub to_team: String,
+
+    /// EffectiveDate is the time the move took effect.
+    #[serde(rename = "effectiveDate")]
+    pub effective_date: Time,
+}
@@ -1,11 +1,13 @@
-use k8s_openapi::apimachinery::pkg::apis::meta::v1::Condition;
-use kube::CustomResource;
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::{Condition, Time};
+use k8s_openapi::chrono::{NaiveDate, Weekday};
+use kube::{CustomResource, KubeSchema};
 use serde::{Deserialize, Serialize};
 use schemars::JsonSchema;
+use utoipa::ToSchema;
 
 /// TheLeague is the Schema for the TheLeague API.
 /// This defines the configuration and participating teams.
-#[derive(CustomResource, Deserialize, Serialize, Debug, Clone, JsonSchema)]
+#[derive(CustomResource, KubeSchema, Deserialize, Serialize, Debug, Clone)]
 #[kube(
     group = "bexxmodd.com",
     version = "v1alpha1",
@@ -13,6 +15,20 @@ use schemars::JsonSchema;
     plural = "theleagues",
     status = "TheLeagueStatus",
     namespaced,
+    printcolumn = r#"{"name":"Teams", "type":"string", "jsonPath":".spec.teams[*].name"}"#,
+    printcolumn = r#"{"name":"Phase", "type":"string", "jsonPath":".status.phase"}"#,
+    printcolumn = r#"{"name":"Live", "type":"boolean", "jsonPath":".status.live"}"#,
+    printcolumn = r#"{"name":"Age", "type":"date", "jsonPath":".metadata.creationTimestamp"}"#,
+)]
+#[x_kube(
+    validation = Rule::new("size(self.teams) <= self.maxTeams")
+        .message("the league has more teams than maxTeams"),
+    validation = Rule::new(
+        "!has(self.minPlayersPerTeam) || !has(self.maxPlayersPerTeam) || self.minPlayersPerTeam <= self.maxPlayersPerTeam"
+    ).message("minPlayersPerTeam is greater than maxPlayersPerTeam"),
+    validation = Rule::new(
+        "!has(self.maxPlayersPerTeam) || self.teams.all(t, !has(t.players) || size(t.players) <= self.maxPlayersPerTeam)"
+    ).message("a team has more players than maxPlayersPerTeam")
 )]
 pub struct TheLeagueSpec {
     /// MaxTeams specifies the maximum number of teams allowed in the league (currently 8).
@@ -20,23 +36,588 @@ pub struct TheLeagueSpec {
     #[schemars(length(min = 2, max = 8))]
     pub max_teams: u8,
 
-    /// Matchups defines the number of times any two teams must play each other.
+    /// Matchups defines the number of times any two teams must play each other (default 1, at
+    /// most 10).
+    #[serde(default = "default_matchups")]
+    #[schemars(range(min = 1, max = 10))]
     pub matchups: u32,
 
     /// Teams is the list of teams currently registered in the league.
+    #[schemars(length(max = 8))]
     pub teams: Vec<Team>,
+
+    /// MinPlayersPerTeam is the optional minimum roster size. The league doesn't go live
+    /// while a team has fewer players.
+    #[serde(rename = "minPlayersPerTeam", default, skip_serializing_if = "Option::is_none")]
+    pub min_players_per_team: Option<u32>,
+
+    /// MaxPlayersPerTeam is the optional maximum roster size.
+    #[serde(rename = "maxPlayersPerTeam", default, skip_serializing_if = "Option::is_none")]
+    pub max_players_per_team: Option<u32>,
+
+    /// WithdrawalPolicy defines how the results of a team leaving the league are treated.
+    #[serde(rename = "withdrawalPolicy", default)]
+    pub withdrawal_policy: WithdrawalPolicy,
+
+    /// ExportConfigMap makes the controller maintain a `<league>-standings` ConfigMap
+    /// with the current table as JSON and as plain text.
+    #[serde(rename = "exportConfigMap", default)]
+    pub export_config_map: bool,
+
+    /// AllowedNamespaces lists the namespaces whose Standings and GameResults may reference
+    /// this league through `leagueNamespace`. By default only same-namespace references are allowed.
+    #[serde(rename = "allowedNamespaces", default, skip_serializing_if = "Vec::is_empty")]
+    pub allowed_namespaces: Vec<String>,
+
+    /// StrictSchedule rejects GameResults whose round doesn't match the generated schedule.
+    /// When false, the controller moves such results to the round their pairing is scheduled in.
+    #[serde(rename = "strictSchedule", default = "default_strict_schedule")]
+    pub strict_schedule: bool,
+
+    /// BalanceHomeAway generates the schedule so no team plays more than two consecutive
+    /// home or away games. Changing it reshuffles the rounds of the schedule.
+    #[serde(rename = "balanceHomeAway", default)]
+    pub balance_home_away: bool,
+
+    /// PointsPolicy defines the points awarded per game outcome.
+    #[serde(rename = "pointsPolicy", default)]
+    pub points_policy: PointsPolicy,
+
+    /// PrePopulateResults makes the controller create a GameResult with a Pending outcome for
+    /// every fixture of the schedule, so scorekeepers only need to fill in the outcome.
+    #[serde(rename = "prePopulateResults", default)]
+    pub pre_populate_results: bool,
+
+    /// SuspensionPolicy enables automatic Suspensions for players collecting too many cards.
+    #[serde(rename = "suspensionPolicy", default, skip_serializing_if = "Option::is_none")]
+    pub suspension_policy: Option<SuspensionPolicy>,
+
+    /// Notifications lists the webhooks notified about recorded results, rank changes and
+    /// the completion of the league.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub notifications: Vec<NotificationTarget>,
+
+    /// SeasonPolicy makes the league run in Seasons; results then only count in their season.
+    #[serde(rename = "seasonPolicy", default, skip_serializing_if = "Option::is_none")]
+    pub season_policy: Option<SeasonPolicy>,
+
+    /// TransferPolicy defines when Transfers between the teams of the league are allowed.
+    #[serde(rename = "transferPolicy", default, skip_serializing_if = "Option::is_none")]
+    pub transfer_policy: Option<TransferPolicy>,
+
+    /// Propagation selects the labels and annotations of the league copied onto its Standings,
+    /// Matches and exported ConfigMap.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub propagation: Option<Propagation>,
+
+    /// Eligibility defines the age limits and registration deadline players of the league must
+    /// meet. Ineligible players are reported in the PlayersEligible condition.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub eligibility: Option<Eligibility>,
+
+    /// RosterLock rejects changes to the teams while a round is in progress, i.e. once a match
+    /// of the round is played or due and until every match of it is played.
+    #[serde(rename = "rosterLock", default, skip_serializing_if = "Option::is_none")]
+    pub roster_lock: Option<RosterLock>,
+
+    /// Divisions groups the teams of the league. Teams of the same division meet more often,
+    /// and the status holds a table per division next to the overall table.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub divisions: Vec<Division>,
+
+    /// IntraDivisionRatio is how many times more often teams of the same division meet than
+    /// other teams (default 2, at most 10). Only used when the league has divisions.
+    #[serde(rename = "intraDivisionRatio", default = "default_intra_division_ratio")]
+    #[schemars(range(min = 1, max = 10))]
+    pub intra_division_ratio: u32,
+
+    /// Schedule assigns kickoff times to the fixtures of the round-robin schedule.
+    /// Without it fixtures only have a round and a pairing.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schedule: Option<ScheduleCalendar>,
+}
+
+/// Builder of the specs of test leagues: `TheLeagueSpec::with_teams` starts from a strictly
+/// scheduled single round robin of teams with empty rosters, and the other methods adjust it.
+#[cfg(test)]
+impl TheLeagueSpec {
+    /// A league of `teams` with empty rosters meeting once, with a strict schedule
+    pub fn with_teams(teams: &[&str]) -> Self {
+        TheLeagueSpec {
+            max_teams: 8,
+            min_players_per_team: None,
+            max_players_per_team: None,
+            matchups: 1,
+            teams: teams.iter().map(|name| Team::named(name)).collect(),
+            withdrawal_policy: Default::default(),
+            export_config_map: false,
+            allowed_namespaces: Vec::new(),
+            strict_schedule: true,
+            points_policy: Default::default(),
+            pre_populate_results: false,
+            suspension_policy: None,
+            notifications: Vec::new(),
+            season_policy: None,
+            transfer_policy: None,
+            propagation: None,
+            eligibility: None,
+            roster_lock: None,
+            schedule: None,
+            divisions: Vec::new(),
+            intra_division_ratio: 2,
+            balance_home_away: false,
+        }
+    }
+
+    /// The league with the team entries `teams`
+    pub fn with_team_entries(mut self, teams: Vec<Team>) -> Self {
+        self.teams = teams;
+        self
+    }
+
+    /// The league with a roster of the single player `Alex <team name>` for every team
+    pub fn with_one_player_rosters(mut self) -> Self {
+        for team in &mut self.teams {
+            team.players = vec![Player::named("Alex", &team.name)];
+        }
+        self
+    }
+
+    /// The league moving results to the round their pairing is scheduled in
+    pub fn lenient(mut self) -> Self {
+        self.strict_schedule = false;
+        self
+    }
+}
+
+fn default_matchups() -> u32 {
+    1
+}
+
+fn default_strict_schedule() -> bool {
+    true
+}
+
+fn default_intra_division_ratio() -> u32 {
+    2
+}
+
+/// Division is a group of teams of the league.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
+pub struct Division {
+    /// Name is the name of the division.
+    pub name: String,
+
+    /// Teams lists the names of the teams in the division. A team belongs to one division.
+    pub teams: Vec<String>,
+}
+
+/// PointsPolicy defines the points a team earns per game.
+/// Games decided in overtime or by a shootout use the overtime values, so hockey-style
+/// leagues can e.g. award a point for an overtime loss.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
+#[serde(default)]
+pub struct PointsPolicy {
+    /// Win is awarded for a win in regulation (default 3).
+    pub win: u32,
+
+    /// Draw is awarded to each team for a draw (default 1).
+    pub draw: u32,
+
+    /// Loss is awarded for a loss in regulation (default 0).
+    pub loss: u32,
+
+    /// OvertimeWin is awarded for a win in overtime or by shootout (default 3).
+    #[serde(rename = "overtimeWin")]
+    pub overtime_win: u32,
+
+    /// OvertimeLoss is awarded for a loss in overtime or by shootout (default 0).
+    #[serde(rename = "overtimeLoss")]
+    pub overtime_loss: u32,
+}
+
+impl Default for PointsPolicy {
+    fn default() -> Self {
+        Self {
+            win: 3,
+            draw: 1,
+            loss: 0,
+            overtime_win: 3,
+            overtime_loss: 0,
+        }
+    }
+}
+
+/// SuspensionPolicy defines when a player is suspended.
+/// Every red card suspends the player, as does every `yellowCardLimit` yellow cards.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
+pub struct SuspensionPolicy {
+    /// YellowCardLimit is the number of yellow cards that leads to a suspension (default 5).
+    #[serde(rename = "yellowCardLimit", default = "default_yellow_card_limit")]
+    #[schemars(range(min = 1))]
+    pub yellow_card_limit: u32,
+}
+
+fn default_yellow_card_limit() -> u32 {
+    5
+}
+
+/// SeasonPolicy defines how the seasons of a league follow each other.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
+pub struct SeasonPolicy {
+    /// DurationDays is the optional length of a season in days. A season ends when every
+    /// fixture has a result or, when set, once its duration has passed.
+    #[serde(rename = "durationDays", default, skip_serializing_if = "Option::is_none")]
+    pub duration_days: Option<u32>,
+
+    /// Next defines whether the next season starts when the current one ends.
+    #[serde(default)]
+    pub next: NextSeason,
+}
+
+/// TransferPolicy defines when players may move between the teams of a league.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
+pub struct TransferPolicy {
+    /// ClosedPeriods lists the periods the transfer window is closed. Transfers taking effect
+    /// during one of them are rejected.
+    #[serde(rename = "closedPeriods", default)]
+    pub closed_periods: Vec<ClosedPeriod>,
+}
+
+/// Eligibility defines which players may play in a league.
+///
+/// Ages are taken on the start date of the schedule, or on the current day for leagues
+/// without a schedule calendar.
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq, Eq, JsonSchema)]
+pub struct Eligibility {
+    /// MinAge is the optional minimum age of a player. Players need a `dateOfBirth` when set.
+    #[serde(rename = "minAge", default, skip_serializing_if = "Option::is_none")]
+    pub min_age: Option<u32>,
+
+    /// MaxAge is the optional maximum age of a player. Players need a `dateOfBirth` when set.
+    #[serde(rename = "maxAge", default, skip_serializing_if = "Option::is_none")]
+    pub max_age: Option<u32>,
+
+    /// RegistrationDeadline is the optional last day (`YYYY-MM-DD`) players may be registered.
+    #[serde(rename = "registrationDeadline", default, skip_serializing_if = "Option::is_none")]
+    pub registration_deadline: Option<Date>,
+}
+
+/// RosterLock freezes the teams of a league while a round is in progress.
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq, Eq, JsonSchema)]
+pub struct RosterLock {
+    /// Enforcement tells whether changes are rejected (Enforce), only answered with a
+    /// warning (Warn) or not checked at all (Off). Defaults to Enforce.
+    #[serde(default)]
+    pub enforcement: Enforcement,
+}
+
+/// Enforcement defines how a violated admission rule is handled.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default, PartialEq, Eq, JsonSchema)]
+pub enum Enforcement {
+    /// Enforce rejects the request.
+    #[default]
+    Enforce,
+
+    /// Warn admits the request with a warning.
+    Warn,
+
+    /// Off skips the rule.
+    Off,
+}
+
+/// Propagation selects the metadata a league passes on to its child resources.
+///
+/// Keys ending in `*` select every key with that prefix, e.g. `example.com/*`. Labels and
+/// annotations removed from the league or the policy are removed from the children again.
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq, Eq, JsonSchema)]
+pub struct Propagation {
+    /// Labels lists the keys of the league labels to copy.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub labels: Vec<String>,
+
+    /// Annotations lists the keys of the league annotations to copy.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub annotations: Vec<String>,
+}
+
+/// ScheduleCalendar defines the days and times the fixtures of a league are played on.
+///
+/// Rounds are played one after the other on match days from the start date. A round with more
+/// games than `gamesPerDay` takes several days, and games on the same day kick off two hours
+/// apart from the time of day of the start date.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
+pub struct ScheduleCalendar {
+    /// StartDate is the kickoff of the first game of the league.
+    #[serde(rename = "startDate")]
+    pub start_date: Time,
+
+    /// MatchDays lists the weekdays games are played on. Every day is a match day when empty.
+    #[serde(rename = "matchDays", default, skip_serializing_if = "Vec::is_empty")]
+    pub match_days: Vec<MatchDay>,
+
+    /// GamesPerDay is the optional maximum number of games played on one day (at most 12, as
+    /// games kick off two hours apart). By default every game of a round is played on the
+    /// same day.
+    #[serde(rename = "gamesPerDay", default, skip_serializing_if = "Option::is_none")]
+    #[schemars(range(min = 1, max = 12))]
+    pub games_per_day: Option<u32>,
+
+    /// MinRestDays is the optional number of days without games between two rounds (at most
+    /// 365).
+    #[serde(rename = "minRestDays", default, skip_serializing_if = "Option::is_none")]
+    #[schemars(range(max = 365))]
+    pub min_rest_days: Option<u32>,
+
+    /// BlackoutDates lists dates (`YYYY-MM-DD`) no games are played on.
+    #[serde(rename = "blackoutDates", default, skip_serializing_if = "Vec::is_empty")]
+    pub blackout_dates: Vec<Date>,
+}
+
+/// Date is a calendar date in `YYYY-MM-DD` format.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
+#[serde(transparent)]
+pub struct Date(#[schemars(regex(pattern = r"^\d{4}-\d{2}-\d{2}$"))] pub String);
+
+impl Date {
+    /// The date, or None if it isn't a valid date
+    pub fn date(&self) -> Option<NaiveDate> {
+        NaiveDate::parse_from_str(&self.0, "%Y-%m-%d").ok()
+    }
+}
+
+/// MatchDay is a weekday games are played on.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, JsonSchema)]
+pub enum MatchDay {
+    Monday,
+    Tuesday,
+    Wednesday,
+    Thursday,
+    Friday,
+    Saturday,
+    Sunday,
+}
+
+impl From<MatchDay> for Weekday {
+    fn from(day: MatchDay) -> Self {
+        match day {
+            MatchDay::Monday => Weekday::Mon,
+            MatchDay::Tuesday => Weekday::Tue,
+            MatchDay::Wednesday => Weekday::Wed,
+            MatchDay::Thursday => Weekday::Thu,
+            MatchDay::Friday => Weekday::Fri,
+            MatchDay::Saturday => Weekday::Sat,
+            MatchDay::Sunday => Weekday::Sun,
+        }
+    }
+}
+
+/// ClosedPeriod is a period during which the transfer window is closed.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
+pub struct ClosedPeriod {
+    /// Start is the time the window closes.
+    pub start: Time,
+
+    /// End is the time the window opens again.
+    pub end: Time,
+}
+
+/// NextSeason defines how a new season is started.
+#[derive(Deserialize, Serialize, Debug, Default, Clone, Copy, PartialEq, Eq, JsonSchema)]
+pub enum NextSeason {
+    /// Automatic starts the next season as soon as the current one is archived.
+    #[default]
+    Automatic,
+
+    /// Manual leaves starting the next season to the organizers, who create its Season.
+    Manual,
+}
+
+/// NotificationTarget is a webhook receiving league notifications as JSON POST requests.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
+pub struct NotificationTarget {
+    /// Url is the HTTPS endpoint the notifications are posted to. Its host must be allowed
+    /// by the controller's `NOTIFICATION_ALLOWED_HOSTS`, the Slack and Discord hosts by default.
+    pub url: String,
+
+    /// SecretRef references a key of a Secret in the league namespace holding a token,
+    /// sent as `Authorization: Bearer <token>`. The Secret must be labelled
+    /// `bexxmodd.com/notification-token: "true"`.
+    #[serde(rename = "secretRef", default, skip_serializing_if = "Option::is_none")]
+    pub secret_ref: Option<SecretKeySelector>,
+
+    /// Format is the payload format expected by the endpoint.
+    #[serde(default)]
+    pub format: NotificationFormat,
+
+    /// Events limits the notifications sent to this target. All events are sent by default.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub events: Vec<NotificationEvent>,
+}
+
+/// SecretKeySelector selects a key of a Secret.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
+pub struct SecretKeySelector {
+    /// Name is the name of the Secret.
+    pub name: String,
+
+    /// Key is the key of the Secret's data holding the value.
+    pub key: String,
+}
+
+/// NotificationFormat defines the JSON payload of a notification.
+#[derive(Deserialize, Serialize, Debug, Default, Clone, Copy, PartialEq, Eq, JsonSchema)]
+pub enum NotificationFormat {
+    /// Generic posts the event, league, message and event details.
+    #[default]
+    Generic,
+
+    /// Slack posts the message as an incoming webhook `text`.
+    Slack,
+
+    /// Discord posts the message as a webhook `content`.
+    Discord,
+}
+
+/// NotificationEvent is a league event that can be notified.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, JsonSchema)]
+pub enum NotificationEvent {
+    /// ResultRecorded is sent when a result is applied to the standings.
+    ResultRecorded,
+
+    /// RanksChanged is sent when teams move in the league table.
+    RanksChanged,
+
+    /// LeagueCompleted is sent once every fixture of the schedule has a result.
+    LeagueCompleted,
+}
+
+/// WithdrawalPolicy defines how games played against a withdrawn team are treated.
+#[derive(Deserialize, Serialize, Debug, Default, Clone, PartialEq, Eq, JsonSchema)]
+pub enum WithdrawalPolicy {
+    /// ExpungeResults removes every game against the withdrawn team from its opponents' records.
+    #[default]
+    ExpungeResults,
+
+    /// KeepResults lets opponents keep the points and goals of games already played against the withdrawn team.
+    KeepResults,
 }
 
 /// TheLeagueStatus defines the observed state of TheLeague.
 #[derive(Deserialize, Serialize, Debug, Default, Clone, JsonSchema)]
 pub struct TheLeagueStatus {
     /// Live indicates if the league is configured and the controller is running.
+    /// It stays false while a roster is outside the roster size limits.
+    #[serde(default)]
     pub live: bool,
 
+    /// Phase summarizes the progress of the league through its schedule.
+    #[serde(default)]
+    pub phase: LeaguePhase,
+
+    /// Table is the current league table ordered by rank, maintained by the Standing controller.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub table: Vec<TableRow>,
+
+    /// Divisions holds the table of each division of the league, ranking its teams by their
+    /// overall record.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub divisions: Vec<DivisionTable>,
+
+    /// WithdrawnTeams lists the teams whose Standing was deleted from the league.
+    #[serde(rename = "withdrawnTeams", default, skip_serializing_if = "Vec::is_empty")]
+    pub withdrawn_teams: Vec<String>,
+
+    /// Completed indicates every fixture of the schedule has a result counted in the table.
+    #[serde(default)]
+    pub completed: bool,
+
+    /// CurrentSeason is the number of the season results are recorded for,
+    /// when the league has a season policy.
+    #[serde(rename = "currentSeason", default, skip_serializing_if = "Option::is_none")]
+    pub current_season: Option<u32>,
+
+    /// TotalAttendance is the number of spectators over all counted games of the league.
+    #[serde(rename = "totalAttendance", default)]
+    pub total_attendance: u64,
+
+    /// AverageAttendance is the mean attendance of the counted games with a reported attendance.
+    #[serde(rename = "averageAttendance", default)]
+    pub average_attendance: u64,
+
     /// Conditions represent the latest available observations of the resource's state.
     /// This is the standard field for status reporting.
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub conditions: Vec<Condition>,
+
+    /// LastReconciled is the time the controller last successfully reconciled the league.
+    #[serde(rename = "lastReconciled", default, skip_serializing_if = "Option::is_none")]
+    pub last_reconciled: Option<Time>,
+
+    /// ControllerVersion is the version of the controller that last reconciled the league.
+    #[serde(rename = "controllerVersion", default, skip_serializing_if = "Option::is_none")]
+    pub controller_version: Option<String>,
+}
+
+/// LeaguePhase is the lifecycle phase of a league.
+#[derive(Deserialize, Serialize, Debug, Default, Clone, Copy, PartialEq, Eq, JsonSchema, ToSchema)]
+pub enum LeaguePhase {
+    /// Pending means no result has been counted in the table yet.
+    #[default]
+    Pending,
+
+    /// InProgress means results are being counted and fixtures are outstanding.
+    InProgress,
+
+    /// Completed means every fixture of the schedule has a result counted in the table.
+    Completed,
+}
+
+/// DivisionTable is the table of a single division embedded in TheLeagueStatus.
+#[derive(Deserialize, Serialize, Debug, Default, Clone, PartialEq, JsonSchema)]
+pub struct DivisionTable {
+    /// Name is the name of the division.
+    pub name: String,
+
+    /// Table is the table of the division ordered by rank within the division.
+    pub table: Vec<TableRow>,
+}
+
+/// TableRow is a single row of the league table embedded in TheLeagueStatus.
+#[derive(Deserialize, Serialize, Debug, Default, Clone, PartialEq, JsonSchema)]
+pub struct TableRow {
+    /// Rank is the position of the team in the table (1 is the leader).
+    pub rank: u32,
+
+    /// Team is the name of the team.
+    pub team: String,
+
+    /// Played is the number of games the team has played.
+    pub played: u32,
+
+    /// Wins is the total number of wins.
+    pub wins: u32,
+
+    /// Draws is the total number of draws.
+    pub draws: u32,
+
+    /// Losses is the total number of losses.
+    pub losses: u32,
+
+    /// GoalsFor is the total number of goals scored by the team.
+    #[serde(rename = "goalsFor")]
+    pub goals_for: u32,
+
+    /// GoalsAgainst is the total number of goals conceded by the team.
+    #[serde(rename = "goalsAgainst")]
+    pub goals_against: u32,
+
+    /// Points is the total accumulated points for the team.
+    pub points: u32,
+
+    /// Branding is the club identity of the team, when the league defines one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branding: Option<TeamBranding>,
 }
 
 /// Team represents an individual team participating in the league.
@@ -54,20 +635,142 @@ pub struct Team {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub location: Option<String>,
 
-    /// Players is the roster of players on this team.
+    /// Abbreviation is the optional 2-4 character short name of the team, e.g. `TIG`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(length(min = 2, max = 4))]
+    pub abbreviation: Option<String>,
+
+    /// Colors lists the team colors, e.g. `["#ff6600", "black"]`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub colors: Vec<String>,
+
+    /// LogoUrl is the optional URL of the team logo.
+    #[serde(rename = "logoUrl", default, skip_serializing_if = "Option::is_none")]
+    pub logo_url: Option<String>,
+
+    /// Captain is the optional captain of the team, given as the `First Last` name or the
+    /// `playerRef` of a player on the roster.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub captain: Option<String>,
+
+    /// TeamRef is the optional name of a Team in the league namespace. When set, the roster is
+    /// taken from the Team, whose description, home venue, branding and captain fill in the
+    /// fields left empty here.
+    #[serde(rename = "teamRef", default, skip_serializing_if = "Option::is_none")]
+    pub team_ref: Option<String>,
+
+    /// Players is the roster of players on this team. Left empty for referenced teams.
+    #[serde(default)]
     pub players: Vec<Player>,
 }
 
+#[cfg(test)]
+impl Team {
+    /// A team `name` with an empty roster, for tests
+    pub fn named(name: &str) -> Self {
+        Team {
+            name: name.to_string(),
+            description: None,
+            location: None,
+            abbreviation: None,
+            colors: Vec::new(),
+            logo_url: None,
+            captain: None,
+            team_ref: None,
+            players: Vec::new(),
+        }
+    }
+}
+
+impl Team {
+    /// The branding of the team, if it has any
+    pub fn branding(&self) -> Option<TeamBranding> {
+        if self.abbreviation.is_none() && self.colors.is_empty() && self.logo_url.is_none() {
+            return None;
+        }
+        Some(TeamBranding {
+            abbreviation: self.abbreviation.clone(),
+            colors: self.colors.clone(),
+            logo_url: self.logo_url.clone(),
+        })
+    }
+}
+
+/// TeamBranding is the club identity of a team, copied into the standings so rendered
+/// tables can show it without looking up the league.
+#[derive(Deserialize, Serialize, Debug, Default, Clone, PartialEq, Eq, JsonSchema)]
+pub struct TeamBranding {
+    /// Abbreviation is the short name of the team.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub abbreviation: Option<String>,
+
+    /// Colors lists the team colors.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub colors: Vec<String>,
+
+    /// LogoUrl is the URL of the team logo.
+    #[serde(rename = "logoUrl", default, skip_serializing_if = "Option::is_none")]
+    pub logo_url: Option<String>,
+}
+
 /// Player represents an individual player on a team's roster.
 #[derive(Deserialize, Serialize, Debug, Clone, JsonSchema)]
 pub struct Player {
     /// FirstName is the first name of a player.
     #[serde(rename = "firstName")]
-    #[schemars(regex(pattern = r"^[a-zA-Z]+$"))]
+    #[schemars(regex(pattern = r"^[a-zA-Z]+(['-][a-zA-Z]+)*$"))]
     pub first_name: String,
 
     /// LastName is the last name of a player.
     #[serde(rename = "lastName")]
-    #[schemars(regex(pattern = r"^[a-zA-Z]+$"))]
+    #[schemars(regex(pattern = r"^[a-zA-Z]+(['-][a-zA-Z]+)*$"))]
     pub last_name: String,
+
+    /// PlayerRef is the optional name of the Player in the league namespace this roster
+    /// entry stands for. Entries without a reference are matched to Players by name.
+    #[serde(rename = "playerRef", default, skip_serializing_if = "Option::is_none")]
+    pub player_ref: Option<String>,
+
+    /// Position is the optional position the player plays on the team.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub position: Option<Position>,
+
+    /// JerseyNumber is the optional shirt number of the player, unique within the team.
+    #[serde(rename = "jerseyNumber", default, skip_serializing_if = "Option::is_none")]
+    #[schemars(range(min = 1, max = 99))]
+    pub jersey_number: Option<u8>,
+
+    /// DateOfBirth is the optional date of birth (`YYYY-MM-DD`) of the player.
+    #[serde(rename = "dateOfBirth", default, skip_serializing_if = "Option::is_none")]
+    pub date_of_birth: Option<Date>,
+
+    /// RegistrationDate is the optional date (`YYYY-MM-DD`) the player was registered with
+    /// the team.
+    #[serde(rename = "registrationDate", default, skip_serializing_if = "Option::is_none")]
+    pub registration_date: Option<Date>,
+}
+
+#[cfg(test)]
+impl Player {
+    /// The roster entry of `first_name last_name`, for tests
+    pub fn named(first_name: &str, last_name: &str) -> Self {
+        Player {
+            first_name: first_name.to_string(),
+            last_name: last_name.to_string(),
+            player_ref: None,
+            position: None,
+            jersey_number: None,
+            date_of_birth: None,
+            registration_date: None,
+        }
+    }
+}
+
+/// Position is the position a player plays on the team.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, JsonSchema)]
+pub enum Position {
+    Goalkeeper,
+    Defender,
+    Midfielder,
+    Forward,
 }
\ No newline at end of file
@@ -0,0 +1,122 @@
+use crate::api::v1alpha1::playoff_types::Series;
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::{Condition, Time};
+use kube::CustomResource;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Tournament is the Schema for the Tournament API.
+/// Each instance is a knockout cup played alongside a league by its teams. The controller
+/// makes the draw and advances the winners of every tie from the GameResults referencing
+/// the tournament.
+#[derive(CustomResource, Deserialize, Serialize, Debug, Clone, JsonSchema)]
+#[kube(
+    group = "bexxmodd.com",
+    version = "v1alpha1",
+    kind = "Tournament",
+    plural = "tournaments",
+    status = "TournamentStatus",
+    namespaced,
+    printcolumn = r#"{"name":"League", "type":"string", "jsonPath":".spec.leagueName"}"#,
+    printcolumn = r#"{"name":"Phase", "type":"string", "jsonPath":".status.phase"}"#,
+    printcolumn = r#"{"name":"Champion", "type":"string", "jsonPath":".status.champion"}"#,
+    printcolumn = r#"{"name":"Age", "type":"date", "jsonPath":".metadata.creationTimestamp"}"#
+)]
+pub struct TournamentSpec {
+    /// LeagueName references the TheLeague (in the same namespace) whose teams take part.
+    #[serde(rename = "leagueName")]
+    pub league_name: String,
+
+    /// Teams optionally limits the tournament to some of the league teams. All league teams
+    /// take part by default.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub teams: Vec<String>,
+
+    /// Draw defines how the teams are placed in the bracket.
+    #[serde(default)]
+    pub draw: DrawMethod,
+
+    /// Tiebreak defines how games level after regulation are decided.
+    #[serde(default)]
+    pub tiebreak: KnockoutTiebreak,
+}
+
+/// DrawMethod defines how the teams of a tournament are placed in the bracket.
+#[derive(Deserialize, Serialize, Debug, Default, Clone, Copy, PartialEq, Eq, JsonSchema)]
+pub enum DrawMethod {
+    /// Seeded places the teams by their rank in the league table, keeping the best teams
+    /// apart until the late rounds.
+    #[default]
+    Seeded,
+
+    /// Random places the teams in a random order.
+    Random,
+}
+
+/// KnockoutTiebreak defines how a knockout game level after regulation is decided.
+#[derive(Deserialize, Serialize, Debug, Default, Clone, Copy, PartialEq, Eq, JsonSchema)]
+pub enum KnockoutTiebreak {
+    /// ExtraTimeAndShootout plays overtime, followed by a shootout if still level.
+    #[default]
+    ExtraTimeAndShootout,
+
+    /// Shootout goes straight to a shootout.
+    Shootout,
+
+    /// Replay records the game as a draw and replays it.
+    Replay,
+}
+
+/// TournamentStatus defines the observed state of the Tournament.
+/// This field is managed by the controller.
+#[derive(Deserialize, Serialize, Debug, Default, Clone, PartialEq, JsonSchema)]
+pub struct TournamentStatus {
+    /// Phase is the lifecycle phase of the tournament.
+    #[serde(default)]
+    pub phase: TournamentPhase,
+
+    /// Entrants lists the teams in the order of the draw, which seeds the bracket.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub entrants: Vec<String>,
+
+    /// Series lists the ties of the bracket, round by round.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub series: Vec<Series>,
+
+    /// Champion is the winner of the final.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub champion: Option<String>,
+
+    /// Conditions represent the latest available observations of the Tournament's state.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub conditions: Vec<Condition>,
+
+    /// LastReconciled is the time the controller last successfully reconciled the Tournament.
+    #[serde(
+        rename = "lastReconciled",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub last_reconciled: Option<Time>,
+
+    /// ControllerVersion is the version of the controller that last reconciled the Tournament.
+    #[serde(
+        rename = "controllerVersion",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub controller_version: Option<String>,
+}
+
+/// TournamentPhase is the lifecycle phase of a tournament.
+#[derive(Deserialize, Serialize, Debug, Default, Clone, Copy, PartialEq, Eq, JsonSchema)]
+pub enum TournamentPhase {
+    /// Pending means the draw hasn't been made yet, e.g. because the league doesn't exist.
+    #[default]
+    Pending,
+
+    /// InProgress means the draw is made and ties are being played.
+    InProgress,
+
+    /// Completed means the final has been decided.
+    Completed,
+}
@@ -0,0 +1,90 @@
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::Time;
+use kube::CustomResource;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Transfer is the Schema for the Transfer API.
+/// Each instance moves a player from the roster of one Team to another once it takes effect.
+/// Transfers taking effect while the transfer window of a league of either team is closed
+/// are rejected.
+#[derive(CustomResource, Deserialize, Serialize, Debug, Clone, JsonSchema)]
+#[kube(
+    group = "bexxmodd.com",
+    version = "v1alpha1",
+    kind = "Transfer",
+    plural = "transfers",
+    status = "TransferStatus",
+    namespaced,
+    printcolumn = r#"{"name":"Player", "type":"string", "jsonPath":".spec.player"}"#,
+    printcolumn = r#"{"name":"From", "type":"string", "jsonPath":".spec.fromTeam"}"#,
+    printcolumn = r#"{"name":"To", "type":"string", "jsonPath":".spec.toTeam"}"#,
+    printcolumn = r#"{"name":"Phase", "type":"string", "jsonPath":".status.phase"}"#,
+    printcolumn = r#"{"name":"Age", "type":"date", "jsonPath":".metadata.creationTimestamp"}"#
+)]
+pub struct TransferSpec {
+    /// Player is the name of the Player moving between the teams.
+    pub player: String,
+
+    /// FromTeam is the name of the Team the player leaves.
+    #[serde(rename = "fromTeam")]
+    pub from_team: String,
+
+    /// ToTeam is the name of the Team the player joins.
+    #[serde(rename = "toTeam")]
+    pub to_team: String,
+
+    /// EffectiveDate is the time the transfer takes effect; the rosters change from then on.
+    #[serde(rename = "effectiveDate")]
+    pub effective_date: Time,
+}
+
+/// TransferStatus defines the observed state of the Transfer.
+/// This field is managed by the controller.
+#[derive(Deserialize, Serialize, Debug, Default, Clone, PartialEq, JsonSchema)]
+pub struct TransferStatus {
+    /// Phase is the lifecycle phase of the transfer.
+    #[serde(default)]
+    pub phase: TransferPhase,
+
+    /// Message explains the phase, e.g. why the transfer was rejected.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+
+    /// CompletedAt is the time the rosters were updated.
+    #[serde(
+        rename = "completedAt",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub completed_at: Option<Time>,
+
+    /// LastReconciled is the time the controller last successfully reconciled the Transfer.
+    #[serde(
+        rename = "lastReconciled",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub last_reconciled: Option<Time>,
+
+    /// ControllerVersion is the version of the controller that last reconciled the Transfer.
+    #[serde(
+        rename = "controllerVersion",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub controller_version: Option<String>,
+}
+
+/// TransferPhase is the lifecycle phase of a transfer.
+#[derive(Deserialize, Serialize, Debug, Default, Clone, Copy, PartialEq, Eq, JsonSchema)]
+pub enum TransferPhase {
+    /// Pending means the transfer hasn't taken effect yet.
+    #[default]
+    Pending,
+
+    /// Completed means the player moved to the new team.
+    Completed,
+
+    /// Rejected means the transfer can't take place; see the message.
+    Rejected,
+}
@@ -0,0 +1,30 @@
+use crate::api::v1alpha1::referee_types::AvailabilityWindow;
+use kube::CustomResource;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Venue is the Schema for the Venue API.
+/// Each instance is a ground the Matches of the leagues in its namespace can be played at.
+/// A match is played at the venue of its GameResult, or else at the home venue of its home
+/// team; a venue named "Riverside Park" there stands for the Venue `riverside-park`.
+/// The controller books the venue of every match and refuses double bookings.
+#[derive(CustomResource, Deserialize, Serialize, Debug, Clone, JsonSchema)]
+#[kube(
+    group = "bexxmodd.com",
+    version = "v1alpha1",
+    kind = "Venue",
+    plural = "venues",
+    namespaced,
+    printcolumn = r#"{"name":"Address", "type":"string", "jsonPath":".spec.address"}"#,
+    printcolumn = r#"{"name":"Age", "type":"date", "jsonPath":".metadata.creationTimestamp"}"#
+)]
+pub struct VenueSpec {
+    /// Address is the optional postal address of the venue.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub address: Option<String>,
+
+    /// Availability lists the periods the venue can host matches in. A venue without
+    /// availability windows is always available.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub availability: Vec<AvailabilityWindow>,
+}
@@ -0,0 +1,470 @@
+//! v1alpha2 of the GameResult API.
+//!
+//! The score lives in a single structured `score` object instead of being embedded in the
+//! outcome variants; the winner follows from the score. Games that were not completed carry an
+//! `interruption` instead of a score, and draws are level scores carrying both teams' goals.
+//! Enum values use stable camelCase wire tags (`regulation`, `postponed`, ...) instead of the
+//! Rust variant names. Both versions convert losslessly into each other for results that pass
+//! validation.
+//!
+//! v1alpha1 stays the storage version; both versions are served and converted by the
+//! conversion webhook. Results that fail validation may not convert back to v1alpha1.
+
+use crate::api::v1alpha1::game_result_types as v1alpha1;
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::Time;
+use kube::{CustomResource, KubeSchema};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+pub use v1alpha1::{Card, GameResultStatus, PlayerStat, ShootoutScore};
+
+/// GameResult is the Schema for the GameResult API.
+/// Each instance records the outcome of a single match.
+#[derive(CustomResource, KubeSchema, Deserialize, Serialize, Debug, Clone)]
+#[kube(
+    group = "bexxmodd.com",
+    version = "v1alpha2",
+    kind = "GameResult",
+    plural = "gameresults",
+    status = "GameResultStatus",
+    namespaced,
+    printcolumn = r#"{"name":"Round", "type":"integer", "jsonPath":".spec.roundNumber"}"#,
+    printcolumn = r#"{"name":"Teams", "type":"string", "jsonPath":".spec.teams[*]"}"#,
+    printcolumn = r#"{"name":"Score", "type":"string", "jsonPath":".status.score"}"#,
+    printcolumn = r#"{"name":"Age", "type":"date", "jsonPath":".metadata.creationTimestamp"}"#
+)]
+#[x_kube(
+    validation = Rule::new("has(self.leagueNamespace) == has(oldSelf.leagueNamespace)")
+        .message("leagueNamespace can't be added or removed after creation")
+)]
+pub struct GameResultSpec {
+    /// LeagueName references the parent TheLeague resource this game belongs to.
+    #[serde(rename = "leagueName")]
+    #[x_kube(validation = Rule::new("self == oldSelf").message("leagueName is immutable"))]
+    pub league_name: String,
+
+    /// LeagueNamespace is the namespace of the referenced TheLeague.
+    /// Defaults to the namespace of this resource.
+    /// It can't be changed, added or removed after creation.
+    #[serde(
+        rename = "leagueNamespace",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    #[x_kube(validation = Rule::new("self == oldSelf").message("leagueNamespace is immutable"))]
+    pub league_namespace: Option<String>,
+
+    /// RoundNumber indicates which round of the league schedule this game belongs to.
+    #[serde(rename = "roundNumber")]
+    #[x_kube(validation = Rule::new("self == oldSelf").message("roundNumber is immutable"))]
+    pub round_number: u32,
+
+    /// Season is the number of the league season the game belongs to, for leagues with a
+    /// season policy.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub season: Option<u32>,
+
+    /// Playoff is the name of the Playoff (in the league namespace) this game is part of.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub playoff: Option<String>,
+
+    /// Tournament is the name of the Tournament (in the league namespace) this game is part of.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tournament: Option<String>,
+
+    /// Teams contains the names of the two teams that played the game, home team first.
+    #[x_kube(validation = Rule::new("self == oldSelf").message("teams is immutable"))]
+    pub teams: [String; 2],
+
+    /// Time is the time the game was played, preferably in RFC3339 format.
+    /// For games still to be played it is the scheduled kickoff, unset until it's known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time: Option<Time>,
+
+    /// OriginallyScheduledAt is the kickoff the game was first scheduled for.
+    #[serde(
+        rename = "originallyScheduledAt",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub originally_scheduled_at: Option<Time>,
+
+    /// Score is the final score of a completed game. Games without a score or an interruption
+    /// have not been played yet.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub score: Option<Score>,
+
+    /// Interruption is set instead of a score for games that were not completed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub interruption: Option<Interruption>,
+
+    /// Scorers lists the goals of the game by player.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub scorers: Vec<PlayerStat>,
+
+    /// Assists lists the assists of the game by player.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub assists: Vec<PlayerStat>,
+
+    /// Cards lists the disciplinary cards shown during the game.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub cards: Vec<Card>,
+
+    /// Venue is the optional name of the ground the game was played at.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub venue: Option<String>,
+
+    /// Attendance is the optional number of spectators at the game.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attendance: Option<u32>,
+}
+
+/// Score is the final score of a game. A draw is a level score decided in regulation; a level
+/// score decided in overtime or by shootout needs a shootout winner.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq, KubeSchema)]
+#[x_kube(
+    validation = Rule::new(
+        "self.home != self.away || self.decidedBy == 'regulation' || (has(self.shootout) && self.shootout.home != self.shootout.away)"
+    ).message("a level score is a draw and must be decided in regulation, or have a shootout winner"),
+    validation = Rule::new(
+        "!has(self.shootout) || (self.decidedBy == 'shootout' && self.home == self.away)"
+    ).message("shootout requires a level score decided by shootout"),
+)]
+pub struct Score {
+    /// Home is the number of goals of the home team (the first of `teams`).
+    pub home: u32,
+
+    /// Away is the number of goals of the away team (the second of `teams`).
+    pub away: u32,
+
+    /// DecidedBy is the period of the game that produced the winner.
+    #[serde(rename = "decidedBy", default)]
+    pub decided_by: DecidedBy,
+
+    /// Shootout is the shootout score of a game decided by shootout.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shootout: Option<ShootoutScore>,
+}
+
+/// DecidedBy defines which period of the game produced the winner.
+/// It is serialized as `regulation`, `overtime` or `shootout`.
+#[derive(Deserialize, Serialize, Debug, Default, Clone, Copy, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum DecidedBy {
+    /// Regulation means the game was won in regular time.
+    #[default]
+    Regulation,
+
+    /// Overtime means the game was won in overtime; the scores include overtime goals.
+    Overtime,
+
+    /// Shootout means the game was level after overtime and won in a shootout.
+    Shootout,
+}
+
+impl From<v1alpha1::DecidedBy> for DecidedBy {
+    fn from(decided_by: v1alpha1::DecidedBy) -> Self {
+        match decided_by {
+            v1alpha1::DecidedBy::Regulation => Self::Regulation,
+            v1alpha1::DecidedBy::Overtime => Self::Overtime,
+            v1alpha1::DecidedBy::Shootout => Self::Shootout,
+        }
+    }
+}
+
+impl From<DecidedBy> for v1alpha1::DecidedBy {
+    fn from(decided_by: DecidedBy) -> Self {
+        match decided_by {
+            DecidedBy::Regulation => Self::Regulation,
+            DecidedBy::Overtime => Self::Overtime,
+            DecidedBy::Shootout => Self::Shootout,
+        }
+    }
+}
+
+/// Interruption describes why a game was not completed.
+/// It is serialized as a `postponed` or `abandoned` object.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum Interruption {
+    /// Postponed indicates the game did not take place on the scheduled date.
+    Postponed {
+        #[serde(
+            rename = "rescheduledTo",
+            default,
+            skip_serializing_if = "Option::is_none"
+        )]
+        rescheduled_to: Option<Time>,
+    },
+
+    /// Abandoned indicates the game was stopped at the given minute before completion.
+    Abandoned { minute: u32 },
+}
+
+/// ConversionError is returned for v1alpha2 results that have no v1alpha1 representation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConversionError(pub String);
+
+impl fmt::Display for ConversionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "cannot convert GameResult to v1alpha1: {}", self.0)
+    }
+}
+
+impl std::error::Error for ConversionError {}
+
+/// The v1alpha2 score or interruption of a v1alpha1 outcome.
+pub fn from_v1alpha1_outcome(
+    outcome: v1alpha1::GameOutcome,
+) -> (Option<Score>, Option<Interruption>) {
+    use v1alpha1::GameOutcome::*;
+    match outcome {
+        WinnerHomeTeam {
+            score_home,
+            score_away,
+            decided_by,
+            shootout,
+        }
+        | WinnerAwayTeam {
+            score_home,
+            score_away,
+            decided_by,
+            shootout,
+        } => (
+            Some(Score {
+                home: score_home,
+                away: score_away,
+                decided_by: decided_by.into(),
+                shootout,
+            }),
+            None,
+        ),
+        Draw { score } => (
+            Some(Score {
+                home: score,
+                away: score,
+                decided_by: DecidedBy::Regulation,
+                shootout: None,
+            }),
+            None,
+        ),
+        Postponed { rescheduled_to } => (None, Some(Interruption::Postponed { rescheduled_to })),
+        Abandoned { minute } => (None, Some(Interruption::Abandoned { minute })),
+        Pending {} => (None, None),
+    }
+}
+
+/// The v1alpha1 outcome of a v1alpha2 score or interruption.
+pub fn to_v1alpha1_outcome(
+    score: &Option<Score>,
+    interruption: &Option<Interruption>,
+) -> Result<v1alpha1::GameOutcome, ConversionError> {
+    use v1alpha1::GameOutcome::*;
+    match (score, interruption) {
+        (Some(_), Some(_)) => Err(ConversionError(
+            "score and interruption are mutually exclusive".to_string(),
+        )),
+        (None, None) => Ok(Pending {}),
+        (None, Some(Interruption::Postponed { rescheduled_to })) => Ok(Postponed {
+            rescheduled_to: rescheduled_to.clone(),
+        }),
+        (None, Some(Interruption::Abandoned { minute })) => Ok(Abandoned { minute: *minute }),
+        (Some(score), None) => {
+            let home_won = match (score.home.cmp(&score.away), &score.shootout) {
+                (std::cmp::Ordering::Greater, _) => true,
+                (std::cmp::Ordering::Less, _) => false,
+                (std::cmp::Ordering::Equal, Some(s)) if s.home != s.away => s.home > s.away,
+                (std::cmp::Ordering::Equal, None) if score.decided_by == DecidedBy::Regulation => {
+                    return Ok(Draw { score: score.home });
+                }
+                _ => {
+                    return Err(ConversionError(format!(
+                        "level score {}-{} decided by {:?} has no winner",
+                        score.home, score.away, score.decided_by
+                    )));
+                }
+            };
+            let (score_home, score_away, decided_by, shootout) = (
+                score.home,
+                score.away,
+                score.decided_by.into(),
+                score.shootout.clone(),
+            );
+            Ok(if home_won {
+                WinnerHomeTeam {
+                    score_home,
+                    score_away,
+                    decided_by,
+                    shootout,
+                }
+            } else {
+                WinnerAwayTeam {
+                    score_home,
+                    score_away,
+                    decided_by,
+                    shootout,
+                }
+            })
+        }
+    }
+}
+
+impl From<v1alpha1::GameResultSpec> for GameResultSpec {
+    fn from(spec: v1alpha1::GameResultSpec) -> Self {
+        let (score, interruption) = from_v1alpha1_outcome(spec.result);
+        Self {
+            league_name: spec.league_name,
+            league_namespace: spec.league_namespace,
+            round_number: spec.round_number,
+            season: spec.season,
+            playoff: spec.playoff,
+            tournament: spec.tournament,
+            teams: spec.teams,
+            time: spec.time,
+            originally_scheduled_at: spec.originally_scheduled_at,
+            score,
+            interruption,
+            scorers: spec.scorers,
+            assists: spec.assists,
+            cards: spec.cards,
+            venue: spec.venue,
+            attendance: spec.attendance,
+        }
+    }
+}
+
+impl TryFrom<GameResultSpec> for v1alpha1::GameResultSpec {
+    type Error = ConversionError;
+
+    fn try_from(spec: GameResultSpec) -> Result<Self, Self::Error> {
+        Ok(Self {
+            result: to_v1alpha1_outcome(&spec.score, &spec.interruption)?,
+            league_name: spec.league_name,
+            league_namespace: spec.league_namespace,
+            round_number: spec.round_number,
+            season: spec.season,
+            playoff: spec.playoff,
+            tournament: spec.tournament,
+            teams: spec.teams,
+            time: spec.time,
+            originally_scheduled_at: spec.originally_scheduled_at,
+            scorers: spec.scorers,
+            assists: spec.assists,
+            cards: spec.cards,
+            venue: spec.venue,
+            attendance: spec.attendance,
+        })
+    }
+}
+
+impl From<v1alpha1::GameResult> for GameResult {
+    fn from(result: v1alpha1::GameResult) -> Self {
+        let mut converted = GameResult::new("", result.spec.into());
+        converted.metadata = result.metadata;
+        converted.status = result.status;
+        converted
+    }
+}
+
+impl TryFrom<GameResult> for v1alpha1::GameResult {
+    type Error = ConversionError;
+
+    fn try_from(result: GameResult) -> Result<Self, Self::Error> {
+        let mut converted = v1alpha1::GameResult::new("", result.spec.try_into()?);
+        converted.metadata = result.metadata;
+        converted.status = result.status;
+        Ok(converted)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kube::CustomResourceExt;
+    use serde_json::json;
+    use v1alpha1::GameOutcome;
+
+    fn spec(result: GameOutcome) -> v1alpha1::GameResultSpec {
+        v1alpha1::GameResultSpec::game("Tigers", "Sharks")
+            .in_round(2)
+            .with_result(result)
+    }
+
+    #[test]
+    fn test_round_trip_through_v1alpha2() {
+        let outcomes = [
+            GameOutcome::WinnerHomeTeam {
+                score_home: 2,
+                score_away: 1,
+                decided_by: v1alpha1::DecidedBy::Regulation,
+                shootout: None,
+            },
+            GameOutcome::WinnerAwayTeam {
+                score_home: 1,
+                score_away: 1,
+                decided_by: v1alpha1::DecidedBy::Shootout,
+                shootout: Some(ShootoutScore { home: 3, away: 4 }),
+            },
+            GameOutcome::Draw { score: 2 },
+            GameOutcome::Postponed {
+                rescheduled_to: None,
+            },
+            GameOutcome::Abandoned { minute: 70 },
+            GameOutcome::Pending {},
+        ];
+        for outcome in outcomes {
+            let original = spec(outcome);
+            let v2: GameResultSpec = original.clone().into();
+            let back: v1alpha1::GameResultSpec = v2.try_into().unwrap();
+            assert_eq!(
+                serde_json::to_value(&back).unwrap(),
+                serde_json::to_value(&original).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test_draw_is_a_level_score() {
+        let v2: GameResultSpec = spec(GameOutcome::Draw { score: 1 }).into();
+        assert_eq!(
+            serde_json::to_value(&v2.score).unwrap(),
+            json!({"home": 1, "away": 1, "decidedBy": "regulation"})
+        );
+    }
+
+    #[test]
+    fn test_score_rules_are_in_the_schema() {
+        let crd = serde_json::to_value(GameResult::crd()).unwrap();
+        let score = &crd["spec"]["versions"][0]["schema"]["openAPIV3Schema"]["properties"]["spec"]
+            ["properties"]["score"];
+        let rules: Vec<&str> = score["x-kubernetes-validations"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter_map(|v| v["rule"].as_str())
+            .collect();
+        assert_eq!(rules.len(), 2);
+        assert!(rules[0].starts_with("self.home != self.away"));
+    }
+
+    #[test]
+    fn test_interruptions_use_camel_case_tags() {
+        let v2: GameResultSpec = spec(GameOutcome::Abandoned { minute: 70 }).into();
+        assert_eq!(
+            serde_json::to_value(&v2.interruption).unwrap(),
+            json!({"abandoned": {"minute": 70}})
+        );
+    }
+
+    #[test]
+    fn test_level_overtime_score_has_no_v1alpha1_representation() {
+        let score = Some(Score {
+            home: 1,
+            away: 1,
+            decided_by: DecidedBy::Overtime,
+            shootout: None,
+        });
+        assert!(to_v1alpha1_outcome(&score, &None).is_err());
+    }
+}
@@ -0,0 +1,3 @@
+pub mod game_result_types;
+pub mod standing_types;
+pub mod the_league_types;
@@ -0,0 +1,168 @@
+//! v1alpha2 of the Standing API.
+//!
+//! The tie-breaking `resolution` uses stable camelCase wire values (`headToHead`,
+//! `goalDifference`) instead of the Rust variant names. Both versions convert losslessly into
+//! each other.
+//!
+//! v1alpha1 stays the storage version; v1alpha2 is served through the conversion webhook.
+
+use crate::api::v1alpha1::standing_types as v1alpha1;
+use kube::{CustomResource, KubeSchema};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+pub use v1alpha1::StandingStatus;
+
+/// Standing is the Schema for the Standing API.
+/// This resource tracks the calculated performance for a single Team.
+#[derive(CustomResource, KubeSchema, Deserialize, Serialize, Debug, Clone)]
+#[kube(
+    group = "bexxmodd.com",
+    version = "v1alpha2",
+    kind = "Standing",
+    plural = "standings",
+    status = "StandingStatus",
+    namespaced,
+    printcolumn = r#"{"name":"Points", "type":"integer", "jsonPath":".status.points"}"#,
+    printcolumn = r#"{"name":"W", "type":"integer", "jsonPath":".status.wins"}"#,
+    printcolumn = r#"{"name":"D", "type":"integer", "jsonPath":".status.draws"}"#,
+    printcolumn = r#"{"name":"L", "type":"integer", "jsonPath":".status.losses"}"#,
+    printcolumn = r#"{"name":"Rank", "type":"integer", "jsonPath":".status.rank"}"#,
+    printcolumn = r#"{"name":"Age", "type":"date", "jsonPath":".metadata.creationTimestamp"}"#
+)]
+#[x_kube(
+    validation = Rule::new("has(self.leagueNamespace) == has(oldSelf.leagueNamespace)")
+        .message("leagueNamespace can't be added or removed after creation")
+)]
+pub struct StandingSpec {
+    /// LeagueName references the parent TheLeague resource this standing belongs to.
+    #[serde(rename = "leagueName")]
+    #[x_kube(validation = Rule::new("self == oldSelf").message("leagueName is immutable"))]
+    pub league_name: String,
+
+    /// LeagueNamespace is the namespace of the referenced TheLeague.
+    /// Defaults to the namespace of this resource.
+    /// It can't be changed, added or removed after creation.
+    #[serde(
+        rename = "leagueNamespace",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    #[x_kube(validation = Rule::new("self == oldSelf").message("leagueNamespace is immutable"))]
+    pub league_namespace: Option<String>,
+
+    /// TeamName is the name of the team this standing corresponds to.
+    #[serde(rename = "teamName")]
+    #[x_kube(validation = Rule::new("self == oldSelf").message("teamName is immutable"))]
+    pub team_name: String,
+
+    /// Resolution defines the tie-breaking method used for calculating the standing.
+    /// Defaults to `goalDifference` when omitted.
+    #[serde(default)]
+    pub resolution: StandingResolution,
+}
+
+/// StandingResolution defines the tie-breaking method used for the standings.
+/// It is serialized as `headToHead` or `goalDifference`.
+#[derive(Deserialize, Serialize, Debug, Default, Clone, Copy, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum StandingResolution {
+    /// HeadToHead resolution prioritizes the outcome of direct matches between tied teams.
+    HeadToHead,
+
+    /// GoalDifference resolution prioritizes the overall goal difference across all matches.
+    #[default]
+    GoalDifference,
+}
+
+impl From<v1alpha1::StandingResolution> for StandingResolution {
+    fn from(resolution: v1alpha1::StandingResolution) -> Self {
+        match resolution {
+            v1alpha1::StandingResolution::Head2Head => Self::HeadToHead,
+            v1alpha1::StandingResolution::GoalDifference => Self::GoalDifference,
+        }
+    }
+}
+
+impl From<StandingResolution> for v1alpha1::StandingResolution {
+    fn from(resolution: StandingResolution) -> Self {
+        match resolution {
+            StandingResolution::HeadToHead => Self::Head2Head,
+            StandingResolution::GoalDifference => Self::GoalDifference,
+        }
+    }
+}
+
+impl From<v1alpha1::StandingSpec> for StandingSpec {
+    fn from(spec: v1alpha1::StandingSpec) -> Self {
+        Self {
+            league_name: spec.league_name,
+            league_namespace: spec.league_namespace,
+            team_name: spec.team_name,
+            resolution: spec.resolution.into(),
+        }
+    }
+}
+
+impl From<StandingSpec> for v1alpha1::StandingSpec {
+    fn from(spec: StandingSpec) -> Self {
+        Self {
+            league_name: spec.league_name,
+            league_namespace: spec.league_namespace,
+            team_name: spec.team_name,
+            resolution: spec.resolution.into(),
+        }
+    }
+}
+
+impl From<v1alpha1::Standing> for Standing {
+    fn from(standing: v1alpha1::Standing) -> Self {
+        let mut converted = Standing::new("", standing.spec.into());
+        converted.metadata = standing.metadata;
+        converted.status = standing.status;
+        converted
+    }
+}
+
+impl From<Standing> for v1alpha1::Standing {
+    fn from(standing: Standing) -> Self {
+        let mut converted = v1alpha1::Standing::new("", standing.spec.into());
+        converted.metadata = standing.metadata;
+        converted.status = standing.status;
+        converted
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_resolution_wire_values() {
+        let original: v1alpha1::StandingSpec = serde_json::from_value(json!({
+            "leagueName": "premier",
+            "teamName": "Tigers",
+            "resolution": "Head2Head"
+        }))
+        .unwrap();
+
+        let v2: StandingSpec = original.clone().into();
+        assert_eq!(
+            serde_json::to_value(&v2).unwrap()["resolution"],
+            json!("headToHead")
+        );
+        let parsed: StandingSpec = serde_json::from_value(json!({
+            "leagueName": "premier",
+            "teamName": "Tigers"
+        }))
+        .unwrap();
+        assert_eq!(parsed.resolution, StandingResolution::GoalDifference);
+
+        let back: v1alpha1::StandingSpec = v2.into();
+        assert_eq!(
+            serde_json::to_value(&back).unwrap(),
+            serde_json::to_value(&original).unwrap()
+        );
+    }
+}
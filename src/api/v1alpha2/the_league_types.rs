@@ -0,0 +1,565 @@
+//! v1alpha2 of the TheLeague API.
+//!
+//! Fields drop their `Policy` suffixes, the schedule settings are grouped in a `schedule`
+//! block, the points policy separates regulation from overtime points and teams name their
+//! ground `homeVenue` like Teams do. Both versions convert losslessly into each other.
+//!
+//! v1alpha1 stays the storage version; both versions are served and converted by the
+//! conversion webhook.
+
+use crate::api::v1alpha1::the_league_types as v1alpha1;
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::Time;
+use kube::{CustomResource, KubeSchema};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+pub use v1alpha1::{
+    Date, Division, Eligibility, Enforcement, MatchDay, NotificationTarget, Player, Propagation,
+    RosterLock, SeasonPolicy, SuspensionPolicy, TheLeagueStatus, TransferPolicy, WithdrawalPolicy,
+};
+
+/// TheLeague is the Schema for the TheLeague API.
+/// This defines the configuration and participating teams.
+#[derive(CustomResource, KubeSchema, Deserialize, Serialize, Debug, Clone)]
+#[kube(
+    group = "bexxmodd.com",
+    version = "v1alpha2",
+    kind = "TheLeague",
+    plural = "theleagues",
+    status = "TheLeagueStatus",
+    namespaced,
+    printcolumn = r#"{"name":"Teams", "type":"string", "jsonPath":".spec.teams[*].name"}"#,
+    printcolumn = r#"{"name":"Phase", "type":"string", "jsonPath":".status.phase"}"#,
+    printcolumn = r#"{"name":"Live", "type":"boolean", "jsonPath":".status.live"}"#,
+    printcolumn = r#"{"name":"Age", "type":"date", "jsonPath":".metadata.creationTimestamp"}"#
+)]
+#[x_kube(
+    validation = Rule::new("size(self.teams) <= self.maxTeams")
+        .message("the league has more teams than maxTeams"),
+    validation = Rule::new(
+        "!has(self.minPlayersPerTeam) || !has(self.maxPlayersPerTeam) || self.minPlayersPerTeam <= self.maxPlayersPerTeam"
+    ).message("minPlayersPerTeam is greater than maxPlayersPerTeam"),
+    validation = Rule::new(
+        "!has(self.maxPlayersPerTeam) || self.teams.all(t, !has(t.players) || size(t.players) <= self.maxPlayersPerTeam)"
+    ).message("a team has more players than maxPlayersPerTeam")
+)]
+pub struct TheLeagueSpec {
+    /// MaxTeams specifies the maximum number of teams allowed in the league (currently 8).
+    #[serde(rename = "maxTeams")]
+    #[schemars(range(min = 2, max = 8))]
+    pub max_teams: u8,
+
+    /// Teams is the list of teams currently registered in the league.
+    #[schemars(length(max = 8))]
+    pub teams: Vec<Team>,
+
+    /// MinPlayersPerTeam is the optional minimum roster size. The league doesn't go live
+    /// while a team has fewer players.
+    #[serde(
+        rename = "minPlayersPerTeam",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub min_players_per_team: Option<u32>,
+
+    /// MaxPlayersPerTeam is the optional maximum roster size.
+    #[serde(
+        rename = "maxPlayersPerTeam",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub max_players_per_team: Option<u32>,
+
+    /// Schedule defines how the fixtures of the league are generated and enforced.
+    pub schedule: Schedule,
+
+    /// Points defines the points awarded per game outcome.
+    #[serde(default)]
+    pub points: Points,
+
+    /// Divisions groups the teams of the league. Teams of the same division meet more often,
+    /// and the status holds a table per division next to the overall table.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub divisions: Vec<Division>,
+
+    /// Withdrawal defines how the results of a team leaving the league are treated.
+    #[serde(default)]
+    pub withdrawal: WithdrawalPolicy,
+
+    /// ExportStandings makes the controller maintain a `<league>-standings` ConfigMap
+    /// with the current table as JSON and as plain text.
+    #[serde(rename = "exportStandings", default)]
+    pub export_standings: bool,
+
+    /// AllowedNamespaces lists the namespaces whose Standings and GameResults may reference
+    /// this league through `leagueNamespace`. By default only same-namespace references are
+    /// allowed.
+    #[serde(
+        rename = "allowedNamespaces",
+        default,
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub allowed_namespaces: Vec<String>,
+
+    /// Suspensions enables automatic Suspensions for players collecting too many cards.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub suspensions: Option<SuspensionPolicy>,
+
+    /// Notifications lists the webhooks notified about recorded results, rank changes and
+    /// the completion of the league.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub notifications: Vec<NotificationTarget>,
+
+    /// Seasons makes the league run in Seasons; results then only count in their season.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seasons: Option<SeasonPolicy>,
+
+    /// Transfers defines when Transfers between the teams of the league are allowed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transfers: Option<TransferPolicy>,
+
+    /// Propagation selects the labels and annotations of the league copied onto its Standings,
+    /// Matches and exported ConfigMap.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub propagation: Option<Propagation>,
+
+    /// Eligibility defines the age limits and registration deadline players of the league must
+    /// meet. Ineligible players are reported in the PlayersEligible condition.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub eligibility: Option<Eligibility>,
+
+    /// RosterLock rejects changes to the teams while a round is in progress, i.e. once a match
+    /// of the round is played or due and until every match of it is played.
+    #[serde(
+        rename = "rosterLock",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub roster_lock: Option<RosterLock>,
+}
+
+/// Schedule defines the round-robin schedule of a league and the calendar it is played on.
+/// The calendar settings only apply once `startDate` is set.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq, KubeSchema)]
+#[x_kube(
+    validation = Rule::new(
+        "has(self.startDate) || !(has(self.matchDays) || has(self.gamesPerDay) || has(self.minRestDays) || has(self.blackoutDates))"
+    ).message("the calendar settings of the schedule require startDate")
+)]
+pub struct Schedule {
+    /// Matchups defines the number of times any two teams must play each other (default 1, at
+    /// most 10).
+    #[serde(default = "default_matchups")]
+    #[schemars(range(min = 1, max = 10))]
+    pub matchups: u32,
+
+    /// Strict rejects GameResults whose round doesn't match the generated schedule.
+    /// When false, the controller moves such results to the round their pairing is scheduled in.
+    #[serde(default = "default_strict")]
+    pub strict: bool,
+
+    /// PrePopulateResults makes the controller create a GameResult with a Pending outcome for
+    /// every fixture of the schedule, so scorekeepers only need to fill in the outcome.
+    #[serde(rename = "prePopulateResults", default)]
+    pub pre_populate_results: bool,
+
+    /// BalanceHomeAway generates the schedule so no team plays more than two consecutive
+    /// home or away games. Changing it reshuffles the rounds of the schedule.
+    #[serde(rename = "balanceHomeAway", default)]
+    pub balance_home_away: bool,
+
+    /// IntraDivisionRatio is how many times more often teams of the same division meet than
+    /// other teams (default 2, at most 10). Only used when the league has divisions.
+    #[serde(
+        rename = "intraDivisionRatio",
+        default = "default_intra_division_ratio"
+    )]
+    #[schemars(range(min = 1, max = 10))]
+    pub intra_division_ratio: u32,
+
+    /// StartDate is the kickoff of the first game of the league. When set, the fixtures are
+    /// assigned kickoff times following the calendar settings.
+    #[serde(rename = "startDate", default, skip_serializing_if = "Option::is_none")]
+    pub start_date: Option<Time>,
+
+    /// MatchDays lists the weekdays games are played on. Every day is a match day when empty.
+    #[serde(rename = "matchDays", default, skip_serializing_if = "Vec::is_empty")]
+    pub match_days: Vec<MatchDay>,
+
+    /// GamesPerDay is the optional maximum number of games played on one day (at most 12, as
+    /// games kick off two hours apart). By default every game of a round is played on the
+    /// same day.
+    #[serde(
+        rename = "gamesPerDay",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    #[schemars(range(min = 1, max = 12))]
+    pub games_per_day: Option<u32>,
+
+    /// MinRestDays is the optional number of days without games between two rounds (at most
+    /// 365).
+    #[serde(
+        rename = "minRestDays",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    #[schemars(range(max = 365))]
+    pub min_rest_days: Option<u32>,
+
+    /// BlackoutDates lists dates (`YYYY-MM-DD`) no games are played on.
+    #[serde(
+        rename = "blackoutDates",
+        default,
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub blackout_dates: Vec<Date>,
+}
+
+fn default_matchups() -> u32 {
+    1
+}
+
+fn default_strict() -> bool {
+    true
+}
+
+fn default_intra_division_ratio() -> u32 {
+    2
+}
+
+impl Schedule {
+    fn new(
+        matchups: u32,
+        strict: bool,
+        pre_populate_results: bool,
+        balance_home_away: bool,
+        intra_division_ratio: u32,
+        calendar: Option<v1alpha1::ScheduleCalendar>,
+    ) -> Self {
+        let mut schedule = Self {
+            matchups,
+            strict,
+            pre_populate_results,
+            balance_home_away,
+            intra_division_ratio,
+            start_date: None,
+            match_days: Vec::new(),
+            games_per_day: None,
+            min_rest_days: None,
+            blackout_dates: Vec::new(),
+        };
+        if let Some(calendar) = calendar {
+            schedule.start_date = Some(calendar.start_date);
+            schedule.match_days = calendar.match_days;
+            schedule.games_per_day = calendar.games_per_day;
+            schedule.min_rest_days = calendar.min_rest_days;
+            schedule.blackout_dates = calendar.blackout_dates;
+        }
+        schedule
+    }
+
+    /// The v1alpha1 calendar of the schedule, if it has a start date
+    fn calendar(&self) -> Option<v1alpha1::ScheduleCalendar> {
+        Some(v1alpha1::ScheduleCalendar {
+            start_date: self.start_date.clone()?,
+            match_days: self.match_days.clone(),
+            games_per_day: self.games_per_day,
+            min_rest_days: self.min_rest_days,
+            blackout_dates: self.blackout_dates.clone(),
+        })
+    }
+}
+
+/// Points defines the points a team earns per game.
+#[derive(Deserialize, Serialize, Debug, Default, Clone, PartialEq, Eq, JsonSchema)]
+pub struct Points {
+    /// Regulation holds the points for games decided in regulation time.
+    #[serde(default)]
+    pub regulation: RegulationPoints,
+
+    /// Overtime holds the points for games decided in overtime or by a shootout, so
+    /// hockey-style leagues can e.g. award a point for an overtime loss.
+    #[serde(default)]
+    pub overtime: OvertimePoints,
+}
+
+/// RegulationPoints are the points for games decided in regulation time.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
+#[serde(default)]
+pub struct RegulationPoints {
+    /// Win is awarded for a win (default 3).
+    pub win: u32,
+
+    /// Draw is awarded to each team for a draw (default 1).
+    pub draw: u32,
+
+    /// Loss is awarded for a loss (default 0).
+    pub loss: u32,
+}
+
+impl Default for RegulationPoints {
+    fn default() -> Self {
+        Self {
+            win: 3,
+            draw: 1,
+            loss: 0,
+        }
+    }
+}
+
+/// OvertimePoints are the points for games decided in overtime or by a shootout.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
+#[serde(default)]
+pub struct OvertimePoints {
+    /// Win is awarded for a win (default 3).
+    pub win: u32,
+
+    /// Loss is awarded for a loss (default 0).
+    pub loss: u32,
+}
+
+impl Default for OvertimePoints {
+    fn default() -> Self {
+        Self { win: 3, loss: 0 }
+    }
+}
+
+/// Team represents an individual team participating in the league.
+#[derive(Deserialize, Serialize, Debug, Clone, JsonSchema)]
+pub struct Team {
+    /// Name is the unique identifier for the team.
+    #[schemars(regex(pattern = r"^[a-zA-Z0-9 ]+$"))]
+    pub name: String,
+
+    /// Description provides an optional short description for the team.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    /// HomeVenue is the optional stadium or field the team plays its home games at.
+    #[serde(rename = "homeVenue", default, skip_serializing_if = "Option::is_none")]
+    pub home_venue: Option<String>,
+
+    /// Abbreviation is the optional 2-4 character short name of the team, e.g. `TIG`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(length(min = 2, max = 4))]
+    pub abbreviation: Option<String>,
+
+    /// Colors lists the team colors, e.g. `["#ff6600", "black"]`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub colors: Vec<String>,
+
+    /// LogoUrl is the optional URL of the team logo.
+    #[serde(rename = "logoUrl", default, skip_serializing_if = "Option::is_none")]
+    pub logo_url: Option<String>,
+
+    /// Captain is the optional captain of the team, given as the `First Last` name or the
+    /// `playerRef` of a player on the roster.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub captain: Option<String>,
+
+    /// TeamRef is the optional name of a Team in the league namespace. When set, the roster is
+    /// taken from the Team, whose description, home venue, branding and captain fill in the
+    /// fields left empty here.
+    #[serde(rename = "teamRef", default, skip_serializing_if = "Option::is_none")]
+    pub team_ref: Option<String>,
+
+    /// Players is the roster of players on this team. Left empty for referenced teams.
+    #[serde(default)]
+    pub players: Vec<Player>,
+}
+
+impl From<v1alpha1::PointsPolicy> for Points {
+    fn from(policy: v1alpha1::PointsPolicy) -> Self {
+        Self {
+            regulation: RegulationPoints {
+                win: policy.win,
+                draw: policy.draw,
+                loss: policy.loss,
+            },
+            overtime: OvertimePoints {
+                win: policy.overtime_win,
+                loss: policy.overtime_loss,
+            },
+        }
+    }
+}
+
+impl From<Points> for v1alpha1::PointsPolicy {
+    fn from(points: Points) -> Self {
+        Self {
+            win: points.regulation.win,
+            draw: points.regulation.draw,
+            loss: points.regulation.loss,
+            overtime_win: points.overtime.win,
+            overtime_loss: points.overtime.loss,
+        }
+    }
+}
+
+impl From<v1alpha1::Team> for Team {
+    fn from(team: v1alpha1::Team) -> Self {
+        Self {
+            name: team.name,
+            description: team.description,
+            home_venue: team.location,
+            abbreviation: team.abbreviation,
+            colors: team.colors,
+            logo_url: team.logo_url,
+            captain: team.captain,
+            team_ref: team.team_ref,
+            players: team.players,
+        }
+    }
+}
+
+impl From<Team> for v1alpha1::Team {
+    fn from(team: Team) -> Self {
+        Self {
+            name: team.name,
+            description: team.description,
+            location: team.home_venue,
+            abbreviation: team.abbreviation,
+            colors: team.colors,
+            logo_url: team.logo_url,
+            captain: team.captain,
+            team_ref: team.team_ref,
+            players: team.players,
+        }
+    }
+}
+
+impl From<v1alpha1::TheLeagueSpec> for TheLeagueSpec {
+    fn from(spec: v1alpha1::TheLeagueSpec) -> Self {
+        Self {
+            max_teams: spec.max_teams,
+            teams: spec.teams.into_iter().map(Team::from).collect(),
+            min_players_per_team: spec.min_players_per_team,
+            max_players_per_team: spec.max_players_per_team,
+            schedule: Schedule::new(
+                spec.matchups,
+                spec.strict_schedule,
+                spec.pre_populate_results,
+                spec.balance_home_away,
+                spec.intra_division_ratio,
+                spec.schedule,
+            ),
+            divisions: spec.divisions,
+            points: spec.points_policy.into(),
+            withdrawal: spec.withdrawal_policy,
+            export_standings: spec.export_config_map,
+            allowed_namespaces: spec.allowed_namespaces,
+            suspensions: spec.suspension_policy,
+            notifications: spec.notifications,
+            seasons: spec.season_policy,
+            transfers: spec.transfer_policy,
+            propagation: spec.propagation,
+            eligibility: spec.eligibility,
+            roster_lock: spec.roster_lock,
+        }
+    }
+}
+
+impl From<TheLeagueSpec> for v1alpha1::TheLeagueSpec {
+    fn from(spec: TheLeagueSpec) -> Self {
+        Self {
+            max_teams: spec.max_teams,
+            matchups: spec.schedule.matchups,
+            teams: spec.teams.into_iter().map(v1alpha1::Team::from).collect(),
+            min_players_per_team: spec.min_players_per_team,
+            max_players_per_team: spec.max_players_per_team,
+            withdrawal_policy: spec.withdrawal,
+            export_config_map: spec.export_standings,
+            allowed_namespaces: spec.allowed_namespaces,
+            strict_schedule: spec.schedule.strict,
+            points_policy: spec.points.into(),
+            pre_populate_results: spec.schedule.pre_populate_results,
+            suspension_policy: spec.suspensions,
+            notifications: spec.notifications,
+            season_policy: spec.seasons,
+            transfer_policy: spec.transfers,
+            propagation: spec.propagation,
+            eligibility: spec.eligibility,
+            roster_lock: spec.roster_lock,
+            schedule: spec.schedule.calendar(),
+            divisions: spec.divisions,
+            intra_division_ratio: spec.schedule.intra_division_ratio,
+            balance_home_away: spec.schedule.balance_home_away,
+        }
+    }
+}
+
+impl From<v1alpha1::TheLeague> for TheLeague {
+    fn from(league: v1alpha1::TheLeague) -> Self {
+        let mut converted = TheLeague::new("", league.spec.into());
+        converted.metadata = league.metadata;
+        converted.status = league.status;
+        converted
+    }
+}
+
+impl From<TheLeague> for v1alpha1::TheLeague {
+    fn from(league: TheLeague) -> Self {
+        let mut converted = v1alpha1::TheLeague::new("", league.spec.into());
+        converted.metadata = league.metadata;
+        converted.status = league.status;
+        converted
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_round_trip_through_v1alpha2() {
+        let original: v1alpha1::TheLeagueSpec = serde_json::from_value(json!({
+            "maxTeams": 4,
+            "matchups": 2,
+            "strictSchedule": false,
+            "prePopulateResults": true,
+            "exportConfigMap": true,
+            "withdrawalPolicy": "KeepResults",
+            "pointsPolicy": {"win": 2, "draw": 1, "loss": 0, "overtimeWin": 2, "overtimeLoss": 1},
+            "suspensionPolicy": {"yellowCardLimit": 3},
+            "schedule": {"startDate": "2025-03-01T15:00:00Z", "matchDays": ["Saturday"]},
+            "teams": [{
+                "name": "Tigers",
+                "location": "Riverside Park",
+                "abbreviation": "TIG",
+                "colors": ["orange", "black"],
+                "players": [{"firstName": "Alex", "lastName": "Morgan"}]
+            }]
+        }))
+        .unwrap();
+
+        let v2: TheLeagueSpec = original.clone().into();
+        assert_eq!(
+            serde_json::to_value(&v2).unwrap()["schedule"],
+            json!({
+                "matchups": 2,
+                "strict": false,
+                "prePopulateResults": true,
+                "balanceHomeAway": false,
+                "intraDivisionRatio": 2,
+                "startDate": "2025-03-01T15:00:00Z",
+                "matchDays": ["Saturday"]
+            })
+        );
+        assert_eq!(v2.points.overtime, OvertimePoints { win: 2, loss: 1 });
+        assert_eq!(v2.teams[0].home_venue.as_deref(), Some("Riverside Park"));
+
+        let back: v1alpha1::TheLeagueSpec = v2.into();
+        assert_eq!(
+            serde_json::to_value(&back).unwrap(),
+            serde_json::to_value(&original).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_points_default_like_v1alpha1() {
+        let points: v1alpha1::PointsPolicy = Points::default().into();
+        assert_eq!(points, v1alpha1::PointsPolicy::default());
+    }
+}
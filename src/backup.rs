@@ -0,0 +1,430 @@
+//! Backup and restore of every league object of a cluster, for the `backup` binary.
+//!
+//! A backup snapshots the objects of every resource of the `bexxmodd.com` group, found with API
+//! discovery, in all namespaces, into a gzip compressed JSON archive. The objects are stripped
+//! of the metadata the API server assigns, UIDs included: owner references are restored by the
+//! kind and name of the owner, pointing to the UID it is restored as, so a restored league
+//! still owns its Standings, Matches and results and deleting it cascades again.
+//!
+//! Restoring creates the missing namespaces, then applies the objects owners first, and the
+//! statuses of every object but the GameResults, which the controller validates again. The
+//! statuses hold the history, like archived Seasons, that can't be recomputed from the specs.
+
+use crate::api::v1alpha1::game_result_types::GameResult;
+
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use k8s_openapi::api::core::v1::Namespace;
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::OwnerReference;
+use k8s_openapi::chrono::{DateTime, Utc};
+use kube::api::{DynamicObject, ListParams, ObjectMeta, Patch, PatchParams, PostParams};
+use kube::core::GroupVersionKind;
+use kube::discovery::{Discovery, Scope};
+use kube::error::DiscoveryError;
+use kube::{Api, Client, Resource, ResourceExt};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::io::{self, Read, Write};
+
+/// API group of the league resources
+pub const GROUP: &str = "bexxmodd.com";
+
+/// Kind of the backup documents
+pub const BACKUP_KIND: &str = "TheLeagueBackup";
+
+/// Version of the backup format, raised on changes older releases can't restore
+pub const BACKUP_VERSION: u32 = 1;
+
+/// Field manager of the restored objects
+pub const RESTORE_FIELD_MANAGER: &str = "theleague-backup";
+
+/// Backup is a snapshot of the league objects of a cluster.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Backup {
+    /// Kind is always `TheLeagueBackup`.
+    pub kind: String,
+
+    /// Version is the version of the backup format.
+    pub version: u32,
+
+    /// CreatedAt is the time the snapshot was taken.
+    pub created_at: DateTime<Utc>,
+
+    /// Objects are the league objects of every namespace.
+    #[serde(default)]
+    pub objects: Vec<DynamicObject>,
+}
+
+/// Restored counts the objects applied by a restore.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Restored {
+    /// Objects is the number of objects applied, per kind.
+    pub objects: BTreeMap<String, usize>,
+
+    /// Namespaces lists the namespaces created for the objects.
+    pub namespaces: Vec<String>,
+
+    /// OrphanedOwners lists the owner references dropped as their owner is neither in the
+    /// backup nor restored before, as `kind namespace/name`.
+    pub orphaned_owners: Vec<String>,
+}
+
+/// Kind of `object`, empty when it has no type information
+fn kind(object: &DynamicObject) -> &str {
+    object.types.as_ref().map_or("", |t| t.kind.as_str())
+}
+
+/// Key of the object of `kind` named `name` in `namespace`, to find owners by
+fn key(kind: &str, namespace: Option<&str>, name: &str) -> String {
+    format!("{} {}/{}", kind, namespace.unwrap_or_default(), name)
+}
+
+/// Strip `object` of the metadata the API server assigns, keeping the owner references
+/// without their UIDs
+fn portable(object: &mut DynamicObject) {
+    let meta = &mut object.metadata;
+    let owners = meta.owner_references.take().map(|owners| {
+        owners
+            .into_iter()
+            .map(|o| OwnerReference {
+                uid: String::new(),
+                ..o
+            })
+            .collect()
+    });
+    *meta = ObjectMeta {
+        name: meta.name.take(),
+        namespace: meta.namespace.take(),
+        labels: meta.labels.take(),
+        annotations: meta.annotations.take(),
+        owner_references: owners,
+        ..ObjectMeta::default()
+    };
+}
+
+impl Backup {
+    /// Backup of `objects`, taken at `created_at`
+    pub fn new(mut objects: Vec<DynamicObject>, created_at: DateTime<Utc>) -> Self {
+        objects.iter_mut().for_each(portable);
+        objects.sort_by_key(|o| key(kind(o), o.metadata.namespace.as_deref(), &o.name_any()));
+        Backup {
+            kind: BACKUP_KIND.to_string(),
+            version: BACKUP_VERSION,
+            created_at,
+            objects,
+        }
+    }
+
+    /// Number of objects per kind
+    pub fn counts(&self) -> BTreeMap<String, usize> {
+        let mut counts = BTreeMap::new();
+        for object in &self.objects {
+            *counts.entry(kind(object).to_string()).or_default() += 1;
+        }
+        counts
+    }
+
+    /// Write the backup to `writer` as gzip compressed JSON
+    pub fn write_archive<W: Write>(&self, writer: W) -> io::Result<()> {
+        let mut encoder = GzEncoder::new(writer, Compression::default());
+        serde_json::to_writer(&mut encoder, self)?;
+        encoder.finish()?.flush()
+    }
+
+    /// Read a backup written by [`Backup::write_archive`] from `reader`, refusing other
+    /// documents and newer backup versions
+    pub fn read_archive<R: Read>(reader: R) -> io::Result<Self> {
+        let invalid = |message: String| io::Error::new(io::ErrorKind::InvalidData, message);
+        let document: serde_json::Value = serde_json::from_reader(GzDecoder::new(reader))?;
+        if document["kind"] != BACKUP_KIND {
+            return Err(invalid(format!("not a {} archive", BACKUP_KIND)));
+        }
+        match document["version"].as_u64() {
+            Some(version) if version <= u64::from(BACKUP_VERSION) => {}
+            Some(version) => {
+                return Err(invalid(format!(
+                    "backup version {} is newer than the supported version {}",
+                    version, BACKUP_VERSION
+                )));
+            }
+            None => return Err(invalid("the backup has no version".to_string())),
+        }
+        Ok(serde_json::from_value(document)?)
+    }
+
+    /// The objects in the order to restore them: owners before the objects they own, and
+    /// within the same depth of ownership GameResults last, as the webhook looks up their league
+    pub fn restore_order(&self) -> Vec<&DynamicObject> {
+        let index: HashMap<String, &DynamicObject> = self
+            .objects
+            .iter()
+            .map(|o| {
+                (
+                    key(kind(o), o.metadata.namespace.as_deref(), &o.name_any()),
+                    o,
+                )
+            })
+            .collect();
+        // Longest chain of owners above `object` in the backup, bounded against ownership cycles
+        fn depth(
+            object: &DynamicObject,
+            index: &HashMap<String, &DynamicObject>,
+            bound: usize,
+        ) -> usize {
+            let namespace = object.metadata.namespace.as_deref();
+            object
+                .owner_references()
+                .iter()
+                .filter_map(|o| index.get(&key(&o.kind, namespace, &o.name)))
+                .map(|owner| match bound {
+                    0 => 0,
+                    _ => 1 + depth(owner, index, bound - 1),
+                })
+                .max()
+                .unwrap_or(0)
+        }
+        let mut ordered: Vec<&DynamicObject> = self.objects.iter().collect();
+        ordered.sort_by_key(|o| {
+            let bound = self.objects.len();
+            (depth(o, &index, bound), kind(o) == GameResult::kind(&()))
+        });
+        ordered
+    }
+}
+
+/// Snapshot the objects of every `bexxmodd.com` resource in all namespaces
+pub async fn snapshot(client: &Client) -> Result<Backup, kube::Error> {
+    let discovery = Discovery::new(client.clone())
+        .filter(&[GROUP])
+        .run()
+        .await?;
+    let mut objects = Vec::new();
+    for group in discovery.groups() {
+        for (resource, _) in group.recommended_resources() {
+            let api: Api<DynamicObject> = Api::all_with(client.clone(), &resource);
+            for mut object in api.list(&ListParams::default()).await?.items {
+                // Lists leave the type of their items out
+                object.types = Some(kube::core::TypeMeta {
+                    api_version: resource.api_version.clone(),
+                    kind: resource.kind.clone(),
+                });
+                objects.push(object);
+            }
+        }
+    }
+    Ok(Backup::new(objects, Utc::now()))
+}
+
+/// Create the namespaces of `backup` missing in the cluster. Returns the created namespaces.
+async fn create_namespaces(
+    client: &Client,
+    backup: &Backup,
+    dry_run: bool,
+) -> Result<Vec<String>, kube::Error> {
+    let mut namespaces: Vec<&str> = backup
+        .objects
+        .iter()
+        .filter_map(|o| o.metadata.namespace.as_deref())
+        .collect();
+    namespaces.sort();
+    namespaces.dedup();
+
+    let api: Api<Namespace> = Api::all(client.clone());
+    let params = PostParams {
+        dry_run,
+        field_manager: Some(RESTORE_FIELD_MANAGER.to_string()),
+    };
+    let mut created = Vec::new();
+    for name in namespaces {
+        if api.get_opt(name).await?.is_none() {
+            let namespace = Namespace {
+                metadata: ObjectMeta {
+                    name: Some(name.to_string()),
+                    ..ObjectMeta::default()
+                },
+                ..Namespace::default()
+            };
+            api.create(&params, &namespace).await?;
+            created.push(name.to_string());
+        }
+    }
+    Ok(created)
+}
+
+/// Restore the objects of `backup` in its [`restore_order`](Backup::restore_order), with
+/// server-side apply forcing the fields of the backup over the ones of the objects the
+/// controller recreated meanwhile
+pub async fn restore(
+    client: &Client,
+    backup: &Backup,
+    dry_run: bool,
+) -> Result<Restored, kube::Error> {
+    let discovery = Discovery::new(client.clone())
+        .filter(&[GROUP])
+        .run()
+        .await?;
+    let mut params = PatchParams::apply(RESTORE_FIELD_MANAGER).force();
+    params.dry_run = dry_run;
+    let mut restored = Restored {
+        namespaces: create_namespaces(client, backup, dry_run).await?,
+        ..Restored::default()
+    };
+
+    // UIDs of the restored objects, by their key
+    let mut uids: HashMap<String, String> = HashMap::new();
+    for object in backup.restore_order() {
+        let mut object = object.clone();
+        let types = object.types.clone().unwrap_or_default();
+        let (group, version) = types
+            .api_version
+            .split_once('/')
+            .unwrap_or(("", &types.api_version));
+        let gvk = GroupVersionKind::gvk(group, version, &types.kind);
+        let Some((resource, capabilities)) = discovery.resolve_gvk(&gvk) else {
+            let missing = format!("{} {}", types.api_version, types.kind);
+            return Err(kube::Error::Discovery(DiscoveryError::MissingResource(
+                missing,
+            )));
+        };
+        let namespace = object.metadata.namespace.clone();
+        let name = object.name_any();
+
+        if let Some(owners) = object.metadata.owner_references.take() {
+            let mut kept = Vec::new();
+            for mut owner in owners {
+                let owner_key = key(&owner.kind, namespace.as_deref(), &owner.name);
+                match uids.get(&owner_key) {
+                    Some(uid) => {
+                        owner.uid = uid.clone();
+                        kept.push(owner);
+                    }
+                    None => restored.orphaned_owners.push(owner_key),
+                }
+            }
+            object.metadata.owner_references = (!kept.is_empty()).then_some(kept);
+        }
+
+        let api: Api<DynamicObject> = match (&capabilities.scope, &namespace) {
+            (Scope::Namespaced, Some(namespace)) => {
+                Api::namespaced_with(client.clone(), namespace, &resource)
+            }
+            _ => Api::all_with(client.clone(), &resource),
+        };
+        let applied = api.patch(&name, &params, &Patch::Apply(&object)).await?;
+        let status = object.data.get("status").filter(|s| !s.is_null());
+        if let Some(status) = status
+            && types.kind != GameResult::kind(&())
+        {
+            let patch = serde_json::json!({ "status": status });
+            api.patch_status(&name, &params, &Patch::Merge(&patch))
+                .await?;
+        }
+
+        uids.insert(
+            key(&types.kind, namespace.as_deref(), &name),
+            applied.uid().unwrap_or_default(),
+        );
+        *restored.objects.entry(types.kind).or_default() += 1;
+    }
+    restored.orphaned_owners.sort();
+    restored.orphaned_owners.dedup();
+    Ok(restored)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn object(kind: &str, name: &str, owner: Option<(&str, &str)>) -> DynamicObject {
+        let owners: Vec<_> = owner
+            .into_iter()
+            .map(|(kind, name)| {
+                json!({"apiVersion": "bexxmodd.com/v1alpha1", "kind": kind, "name": name,
+                       "uid": format!("{}-uid", name)})
+            })
+            .collect();
+        serde_json::from_value(json!({
+            "apiVersion": "bexxmodd.com/v1alpha1",
+            "kind": kind,
+            "metadata": {
+                "name": name,
+                "namespace": "default",
+                "uid": format!("{}-uid", name),
+                "resourceVersion": "7",
+                "finalizers": ["bexxmodd.com/standing"],
+                "ownerReferences": owners,
+            },
+            "spec": {"leagueName": "premier"},
+            "status": {"phase": "Completed"},
+        }))
+        .unwrap()
+    }
+
+    fn backup() -> Backup {
+        Backup::new(
+            vec![
+                object("GameResult", "premier-1", Some(("TheLeague", "premier"))),
+                object("Standing", "premier-tigers", Some(("TheLeague", "premier"))),
+                object("Season", "premier-2", Some(("TheLeague", "premier"))),
+                object("TheLeague", "premier", None),
+                object("Standing", "orphan", Some(("TheLeague", "gone"))),
+            ],
+            Utc::now(),
+        )
+    }
+
+    #[test]
+    fn test_backup_strips_the_uids_and_keeps_the_owners() {
+        let backup = backup();
+        for object in &backup.objects {
+            assert_eq!(object.metadata.uid, None);
+            assert_eq!(object.metadata.resource_version, None);
+            assert_eq!(object.metadata.finalizers, None);
+            assert_eq!(object.metadata.namespace.as_deref(), Some("default"));
+            assert!(object.owner_references().iter().all(|o| o.uid.is_empty()));
+            assert_eq!(object.data["status"]["phase"], "Completed");
+        }
+        assert_eq!(backup.counts()["Standing"], 2);
+
+        let order: Vec<String> = backup
+            .restore_order()
+            .iter()
+            .map(|o| o.name_any())
+            .collect();
+        let position = |name: &str| order.iter().position(|n| n == name).unwrap();
+        assert!(position("premier") < position("premier-tigers"));
+        assert!(position("premier-tigers") < position("premier-1"));
+        assert!(position("premier-2") < position("premier-1"));
+        // Owned by a league missing from the backup, restored among the objects without owner
+        assert!(position("orphan") < position("premier-tigers"));
+    }
+
+    #[test]
+    fn test_archive_round_trips_compressed() {
+        let backup = backup();
+        let mut archive = Vec::new();
+        backup.write_archive(&mut archive).unwrap();
+        assert_eq!(archive[..2], [0x1f, 0x8b]);
+
+        let read = Backup::read_archive(archive.as_slice()).unwrap();
+        assert_eq!(read.version, BACKUP_VERSION);
+        assert_eq!(read.counts(), backup.counts());
+        assert_eq!(
+            read.objects[0].types.as_ref().unwrap().api_version,
+            "bexxmodd.com/v1alpha1"
+        );
+
+        let mut newer = Vec::new();
+        let mut document = serde_json::to_value(&backup).unwrap();
+        document["version"] = json!(BACKUP_VERSION + 1);
+        let mut encoder = GzEncoder::new(&mut newer, Compression::default());
+        serde_json::to_writer(&mut encoder, &document).unwrap();
+        encoder.finish().unwrap();
+        let error = Backup::read_archive(newer.as_slice()).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        assert!(Backup::read_archive(&b"not gzip"[..]).is_err());
+    }
+}
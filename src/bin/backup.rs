@@ -0,0 +1,162 @@
+//! Binary to back up the league objects of the cluster of the current kubeconfig, and restore
+//! them after a disaster or into another cluster.
+//!
+//! `create` snapshots every `bexxmodd.com` object of all namespaces into a gzip compressed
+//! archive, `inspect` prints what an archive holds and `restore` applies it, recreating the
+//! ownership of the objects under their new UIDs. The CRDs must be installed before a restore.
+//!
+//! Run with: `cargo run --bin backup -- create`, or
+//! `cargo run --bin backup -- restore theleague-backup-20261015T120000Z.json.gz --dry-run`
+
+use anyhow::Context;
+use clap::{Parser, Subcommand};
+use k8s_openapi::chrono::Utc;
+use kube::Client;
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::path::{Path, PathBuf};
+
+use the_league::backup::{self, Backup};
+
+/// Back up and restore the league objects of the cluster
+#[derive(Parser, Debug)]
+#[command(name = "backup")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Snapshot the league objects of all namespaces into an archive
+    Create {
+        /// Archive to write; defaults to `theleague-backup-<time>.json.gz`
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Print the objects an archive holds, per kind
+    Inspect {
+        /// Archive to read
+        file: PathBuf,
+    },
+
+    /// Restore the league objects of an archive
+    Restore {
+        /// Archive to restore
+        file: PathBuf,
+
+        /// Send the objects with server-side dry-run only
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+fn read(file: &Path) -> anyhow::Result<Backup> {
+    let reader = File::open(file).with_context(|| format!("Unable to open {}", file.display()))?;
+    Backup::read_archive(BufReader::new(reader))
+        .with_context(|| format!("Unable to read {}", file.display()))
+}
+
+fn print_counts(backup: &Backup) {
+    for (kind, count) in backup.counts() {
+        println!("  {:<12} {}", kind, count);
+    }
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let cli = Cli::parse();
+
+    match cli.command {
+        Command::Create { output } => {
+            let client = Client::try_default().await?;
+            let snapshot = backup::snapshot(&client).await?;
+            let output = output.unwrap_or_else(|| {
+                let time = snapshot.created_at.format("%Y%m%dT%H%M%SZ");
+                PathBuf::from(format!("theleague-backup-{}.json.gz", time))
+            });
+            let writer = File::create(&output)
+                .with_context(|| format!("Unable to create {}", output.display()))?;
+            snapshot.write_archive(BufWriter::new(writer))?;
+            println!(
+                "✓ Backed up {} object(s) to {}",
+                snapshot.objects.len(),
+                output.display()
+            );
+            print_counts(&snapshot);
+        }
+        Command::Inspect { file } => {
+            let archive = read(&file)?;
+            println!(
+                "{}: {} object(s) backed up at {} ({} ago)",
+                file.display(),
+                archive.objects.len(),
+                archive.created_at.to_rfc3339(),
+                humanize(Utc::now() - archive.created_at)
+            );
+            print_counts(&archive);
+        }
+        Command::Restore { file, dry_run } => {
+            let archive = read(&file)?;
+            let client = Client::try_default().await?;
+            let restored = backup::restore(&client, &archive, dry_run).await?;
+            for namespace in &restored.namespaces {
+                println!("✓ Created namespace {}", namespace);
+            }
+            for owner in &restored.orphaned_owners {
+                eprintln!(
+                    "warning: owner {} is not in the backup, reference dropped",
+                    owner
+                );
+            }
+            let total: usize = restored.objects.values().sum();
+            println!(
+                "✓ {} {} object(s) from {}",
+                if dry_run { "Validated" } else { "Restored" },
+                total,
+                file.display()
+            );
+            for (kind, count) in &restored.objects {
+                println!("  {:<12} {}", kind, count);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// `age` rounded to its largest unit, e.g. `3d` or `5m`
+fn humanize(age: k8s_openapi::chrono::TimeDelta) -> String {
+    match age {
+        age if age.num_days() > 0 => format!("{}d", age.num_days()),
+        age if age.num_hours() > 0 => format!("{}h", age.num_hours()),
+        age if age.num_minutes() > 0 => format!("{}m", age.num_minutes()),
+        age => format!("{}s", age.num_seconds().max(0)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use k8s_openapi::chrono::TimeDelta;
+
+    #[test]
+    fn test_humanize_uses_the_largest_unit() {
+        assert_eq!(humanize(TimeDelta::days(3) + TimeDelta::hours(4)), "3d");
+        assert_eq!(humanize(TimeDelta::minutes(90)), "1h");
+        assert_eq!(humanize(TimeDelta::seconds(42)), "42s");
+        assert_eq!(humanize(TimeDelta::seconds(-1)), "0s");
+    }
+
+    #[test]
+    fn test_inspect_reads_an_archive() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("backup.json.gz");
+        Backup::new(Vec::new(), Utc::now())
+            .write_archive(File::create(&file).unwrap())
+            .unwrap();
+        assert!(read(&file).unwrap().objects.is_empty());
+        assert!(read(&dir.path().join("missing.json.gz")).is_err());
+    }
+}
@@ -1,15 +1,65 @@
 //! Binary to generate CRD YAML files from Rust CustomResource definitions.
 //!
-//! Run with: `cargo run --bin generate-crds`
-
-use kube::CustomResourceExt;
+//! Run with: `cargo run --bin generate-crds`, or `cargo run --bin generate-crds -- --help` for
+//! the output modes.
+
+use clap::Parser;
+use k8s_openapi::ByteString;
+use k8s_openapi::apiextensions_apiserver::pkg::apis::apiextensions::v1::{
+    CustomResourceConversion, CustomResourceDefinition, ServiceReference, WebhookClientConfig,
+    WebhookConversion,
+};
+use kube::core::crd::merge_crds;
+use kube::{Api, Client, CustomResourceExt, ResourceExt};
+use serde::Serialize;
+use similar::TextDiff;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
-use the_league::{GameResult, Standing, TheLeague};
+use the_league::api::v1alpha2;
+use the_league::webhook;
+use the_league::{
+    GameResult, Match, Player, Playoff, Referee, Season, Standing, Suspension, Team, TheLeague,
+    Tournament, Transfer, Venue,
+};
 
 const LEAGUE_NAME: &str = "league";
 
+/// Directory the CRDs are written to by default, listed by `config/crds/kustomization.yaml`
+const DEFAULT_OUT_DIR: &str = "config/crds/standard";
+
+/// File all CRDs are written to with `--single-file`
+const SINGLE_FILE_NAME: &str = "crds.yaml";
+
+/// Kustomization written alongside the CRDs
+const KUSTOMIZATION_FILE_NAME: &str = "kustomization.yaml";
+
+/// Where the API server sends conversion reviews: the webhook Service in `namespace`, trusting
+/// `ca_bundle` when given.
+#[derive(Debug, Clone)]
+struct ConversionConfig {
+    namespace: String,
+    ca_bundle: Option<Vec<u8>>,
+}
+
+impl ConversionConfig {
+    /// Namespace from the NAMESPACE environment variable and the PEM CA bundle from the file
+    /// named by CA_BUNDLE_FILE. Without a bundle, the controller injects the CA of its serving
+    /// certificate, as it does into the webhook configurations.
+    fn from_env() -> anyhow::Result<Self> {
+        let namespace =
+            std::env::var("NAMESPACE").unwrap_or_else(|_| webhook::DEFAULT_NAMESPACE.to_string());
+        let ca_bundle = match std::env::var("CA_BUNDLE_FILE") {
+            Ok(path) => Some(fs::read(path)?),
+            Err(_) => None,
+        };
+        Ok(Self {
+            namespace,
+            ca_bundle,
+        })
+    }
+}
+
 /// Generate filename for a CRD using the pattern: league.<group>.<plural>.yaml
 fn generate_crd_filename(group: &str, plural: &str) -> String {
     format!(
@@ -21,57 +71,380 @@ fn generate_crd_filename(group: &str, plural: &str) -> String {
 }
 
 /// Generate and write a CRD to the specified directory
+#[cfg(test)]
 fn generate_crd_file<T: CustomResourceExt>(
     _crd_type: std::marker::PhantomData<T>,
     output_dir: &Path,
 ) -> anyhow::Result<String> {
+    write_crd_file(&T::crd(), output_dir)
+}
+
+/// `crd` converted between its versions by the conversion webhook configured by `config`
+fn with_conversion_webhook(
+    mut crd: CustomResourceDefinition,
+    config: &ConversionConfig,
+) -> CustomResourceDefinition {
+    crd.spec.conversion = Some(CustomResourceConversion {
+        strategy: "Webhook".to_string(),
+        webhook: Some(WebhookConversion {
+            conversion_review_versions: vec!["v1".to_string()],
+            client_config: Some(WebhookClientConfig {
+                service: Some(ServiceReference {
+                    name: webhook::WEBHOOK_SERVICE_NAME.to_string(),
+                    namespace: config.namespace.clone(),
+                    path: Some(webhook::CONVERT_PATH.to_string()),
+                    port: Some(443),
+                }),
+                ca_bundle: config.ca_bundle.clone().map(ByteString),
+                url: None,
+            }),
+        }),
+    });
+    crd
+}
+
+/// Write `crd` to the specified directory
+fn write_crd_file(crd: &CustomResourceDefinition, output_dir: &Path) -> anyhow::Result<String> {
     // Ensure output directory exists
     if !output_dir.exists() {
         fs::create_dir_all(output_dir)?;
     }
 
-    let crd = T::crd();
-    let yaml = serde_yaml::to_string(&crd)?;
+    let yaml = serde_yaml::to_string(crd)?;
     let filename = generate_crd_filename(&crd.spec.group, &crd.spec.names.plural);
     let file_path = output_dir.join(&filename);
     fs::write(&file_path, yaml)?;
     Ok(filename)
 }
 
-/// Generate all CRD files
-fn generate_all_crds(output_dir: &Path) -> anyhow::Result<Vec<String>> {
-    let mut generated_files = Vec::new();
+/// All CRDs of the API. The multi-version CRDs are converted by the webhook configured by
+/// `conversion`.
+fn all_crds(conversion: &ConversionConfig) -> anyhow::Result<Vec<CustomResourceDefinition>> {
+    // TheLeague, Standing and GameResult serve v1alpha1 and v1alpha2, storing v1alpha1
+    let multi_version = |crds, storage_version| -> anyhow::Result<_> {
+        Ok(with_conversion_webhook(
+            merge_crds(crds, storage_version)?,
+            conversion,
+        ))
+    };
+    Ok(vec![
+        multi_version(
+            vec![
+                TheLeague::crd(),
+                v1alpha2::the_league_types::TheLeague::crd(),
+            ],
+            "v1alpha1",
+        )?,
+        multi_version(
+            vec![Standing::crd(), v1alpha2::standing_types::Standing::crd()],
+            "v1alpha1",
+        )?,
+        multi_version(
+            vec![
+                GameResult::crd(),
+                v1alpha2::game_result_types::GameResult::crd(),
+            ],
+            "v1alpha1",
+        )?,
+        Suspension::crd(),
+        Season::crd(),
+        Match::crd(),
+        Team::crd(),
+        Player::crd(),
+        Playoff::crd(),
+        Tournament::crd(),
+        Transfer::crd(),
+        Referee::crd(),
+        Venue::crd(),
+    ])
+}
+
+/// Generate all CRD files, one per CRD. The multi-version CRDs are converted by the webhook
+/// configured by `conversion`.
+fn generate_all_crds(
+    output_dir: &Path,
+    conversion: &ConversionConfig,
+) -> anyhow::Result<Vec<String>> {
+    all_crds(conversion)?
+        .iter()
+        .map(|crd| write_crd_file(crd, output_dir))
+        .collect()
+}
+
+/// `crds` as a single multi-document YAML stream
+fn multi_document_yaml(crds: &[CustomResourceDefinition]) -> anyhow::Result<String> {
+    let mut yaml = String::new();
+    for crd in crds {
+        yaml.push_str("---\n");
+        yaml.push_str(&serde_yaml::to_string(crd)?);
+    }
+    Ok(yaml)
+}
+
+/// Generate all CRDs into the file `SINGLE_FILE_NAME` of `output_dir`
+fn generate_single_file(
+    output_dir: &Path,
+    conversion: &ConversionConfig,
+) -> anyhow::Result<String> {
+    fs::create_dir_all(output_dir)?;
+    fs::write(
+        output_dir.join(SINGLE_FILE_NAME),
+        multi_document_yaml(&all_crds(conversion)?)?,
+    )?;
+    Ok(SINGLE_FILE_NAME.to_string())
+}
+
+/// Kustomization listing the generated CRDs, so the output directory can be applied with
+/// `kubectl apply -k`
+#[derive(Serialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct Kustomization {
+    api_version: &'static str,
+    kind: &'static str,
+    resources: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    patches: Vec<KustomizationPatch>,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+struct KustomizationPatch {
+    path: String,
+}
+
+/// Write the kustomization of the CRD files `resources` and the patches `patches` to
+/// `output_dir`
+fn write_kustomization(
+    output_dir: &Path,
+    resources: &[String],
+    patches: &[String],
+) -> anyhow::Result<String> {
+    let kustomization = Kustomization {
+        api_version: "kustomize.config.k8s.io/v1beta1",
+        kind: "Kustomization",
+        resources: resources.to_vec(),
+        patches: patches
+            .iter()
+            .map(|path| KustomizationPatch { path: path.clone() })
+            .collect(),
+    };
+    fs::write(
+        output_dir.join(KUSTOMIZATION_FILE_NAME),
+        serde_yaml::to_string(&kustomization)?,
+    )?;
+    Ok(KUSTOMIZATION_FILE_NAME.to_string())
+}
+
+/// Write a patch stub of the conversion webhook of every multi-version CRD to the `patches`
+/// directory of `output_dir`, for overlays pointing the conversion at another Service or CA.
+/// Returns the paths of the patches relative to `output_dir`.
+fn write_conversion_patches(
+    output_dir: &Path,
+    conversion: &ConversionConfig,
+) -> anyhow::Result<Vec<String>> {
+    let patches_dir = output_dir.join("patches");
+    let mut paths = Vec::new();
+    for crd in all_crds(conversion)? {
+        let Some(webhook_conversion) = crd.spec.conversion.clone() else {
+            continue;
+        };
+        let patch = serde_json::json!({
+            "apiVersion": "apiextensions.k8s.io/v1",
+            "kind": "CustomResourceDefinition",
+            "metadata": {
+                "name": crd.metadata.name,
+                "annotations": crd.metadata.annotations,
+            },
+            "spec": { "conversion": webhook_conversion },
+        });
+        fs::create_dir_all(&patches_dir)?;
+        let filename = format!("webhook_in_{}.yaml", crd.spec.names.plural);
+        fs::write(patches_dir.join(&filename), serde_yaml::to_string(&patch)?)?;
+        paths.push(format!("patches/{}", filename));
+    }
+    Ok(paths)
+}
+
+/// Drift is a CRD whose generated manifest differs from the one verified against.
+#[derive(Debug, Clone, PartialEq)]
+struct Drift {
+    name: String,
+    /// Diff is the unified diff from the verified manifest to the generated one.
+    diff: String,
+}
 
-    // Generate CRD for TheLeague
-    let filename = generate_crd_file(std::marker::PhantomData::<TheLeague>, output_dir)?;
-    println!("✓ Generated {}/{}", output_dir.display(), filename);
-    generated_files.push(filename);
+/// Drift of `name` if `actual` differs from `expected`, labelling `actual` with `source`
+fn drift(name: &str, source: &str, actual: &str, expected: &str) -> Option<Drift> {
+    if actual == expected {
+        return None;
+    }
+    let diff = TextDiff::from_lines(actual, expected)
+        .unified_diff()
+        .context_radius(3)
+        .header(source, "generated")
+        .to_string();
+    Some(Drift {
+        name: name.to_string(),
+        diff,
+    })
+}
 
-    // Generate CRD for Standing
-    let filename = generate_crd_file(std::marker::PhantomData::<Standing>, output_dir)?;
-    println!("✓ Generated {}/{}", output_dir.display(), filename);
-    generated_files.push(filename);
+/// Drifts of `crds` from the files of `output_dir`, one per CRD or the single `crds.yaml`
+/// if `single_file`. Missing files are drifts from an empty file.
+fn verify_files(
+    crds: &[CustomResourceDefinition],
+    output_dir: &Path,
+    single_file: bool,
+) -> anyhow::Result<Vec<Drift>> {
+    let read = |filename: &str| -> anyhow::Result<(String, String)> {
+        let path = output_dir.join(filename);
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(e.into()),
+        };
+        Ok((path.display().to_string(), content))
+    };
+    if single_file {
+        let (source, actual) = read(SINGLE_FILE_NAME)?;
+        let expected = multi_document_yaml(crds)?;
+        return Ok(drift(SINGLE_FILE_NAME, &source, &actual, &expected)
+            .into_iter()
+            .collect());
+    }
+    let mut drifts = Vec::new();
+    for crd in crds {
+        let filename = generate_crd_filename(&crd.spec.group, &crd.spec.names.plural);
+        let (source, actual) = read(&filename)?;
+        let expected = serde_yaml::to_string(crd)?;
+        drifts.extend(drift(&crd.name_any(), &source, &actual, &expected));
+    }
+    Ok(drifts)
+}
 
-    // Generate CRD for GameResult
-    let filename = generate_crd_file(std::marker::PhantomData::<GameResult>, output_dir)?;
-    println!("✓ Generated {}/{}", output_dir.display(), filename);
-    generated_files.push(filename);
+/// The versions of `crd` with their schemas, as compared with the installed CRDs. The rest of
+/// the manifest, e.g. the injected CA bundle, is left to the deployment.
+fn versions_yaml(crd: &CustomResourceDefinition) -> anyhow::Result<String> {
+    let versions: Vec<serde_json::Value> = crd
+        .spec
+        .versions
+        .iter()
+        .map(|v| {
+            serde_json::json!({
+                "name": v.name,
+                "served": v.served,
+                "storage": v.storage,
+                "schema": v.schema,
+            })
+        })
+        .collect();
+    Ok(serde_yaml::to_string(&versions)?)
+}
 
-    Ok(generated_files)
+/// Drifts of the schemas of `crds` from those of the CRDs installed in the cluster of the
+/// current kubeconfig context. CRDs not installed are drifts from an empty schema.
+async fn verify_cluster(crds: &[CustomResourceDefinition]) -> anyhow::Result<Vec<Drift>> {
+    let client = Client::try_default().await?;
+    let api: Api<CustomResourceDefinition> = Api::all(client);
+    let mut drifts = Vec::new();
+    for crd in crds {
+        let name = crd.name_any();
+        let actual = match api.get_opt(&name).await? {
+            Some(installed) => versions_yaml(&installed)?,
+            None => String::new(),
+        };
+        let source = format!("cluster/{}", name);
+        drifts.extend(drift(&name, &source, &actual, &versions_yaml(crd)?));
+    }
+    Ok(drifts)
+}
+
+/// Generate the CRD manifests of TheLeague API
+#[derive(Parser, Debug)]
+#[command(name = "generate-crds")]
+struct Cli {
+    /// Directory to write the CRDs to
+    #[arg(long, default_value = DEFAULT_OUT_DIR)]
+    out_dir: PathBuf,
+
+    /// Print all CRDs to stdout as one multi-document YAML instead of writing files
+    #[arg(long, conflicts_with_all = ["out_dir", "single_file"])]
+    stdout: bool,
+
+    /// Write all CRDs to a single multi-document YAML file, `crds.yaml`, in the output
+    /// directory
+    #[arg(long)]
+    single_file: bool,
+
+    /// Also write a patch stub of the conversion webhook of each multi-version CRD and list
+    /// it in the kustomization
+    #[arg(long)]
+    conversion_patches: bool,
+
+    /// Write nothing, but diff the generated CRDs against those of the output directory and
+    /// fail if they drifted
+    #[arg(long, conflicts_with_all = ["stdout", "conversion_patches"])]
+    verify: bool,
+
+    /// With --verify, diff the schemas against the CRDs installed in the cluster of the
+    /// current kubeconfig context instead
+    #[arg(long, requires = "verify")]
+    cluster: bool,
 }
 
 fn main() -> anyhow::Result<()> {
-    // Ensure standard directory exists (GatewayAPI-style structure)
-    let standard_dir = Path::new("config/crds/standard");
-    if !standard_dir.exists() {
-        fs::create_dir_all(standard_dir)?;
+    let cli = Cli::parse();
+    let conversion = ConversionConfig::from_env()?;
+
+    if cli.stdout {
+        print!("{}", multi_document_yaml(&all_crds(&conversion)?)?);
+        return Ok(());
     }
 
-    generate_all_crds(standard_dir)?;
+    if cli.verify {
+        let crds = all_crds(&conversion)?;
+        let drifts = if cli.cluster {
+            tokio::runtime::Runtime::new()?.block_on(verify_cluster(&crds))?
+        } else {
+            verify_files(&crds, &cli.out_dir, cli.single_file)?
+        };
+        for drift in &drifts {
+            println!("✗ {} drifted:\n{}", drift.name, drift.diff);
+        }
+        if !drifts.is_empty() {
+            anyhow::bail!(
+                "{} of {} CRDs drifted, regenerate them with `make generate-crds`",
+                drifts.len(),
+                crds.len()
+            );
+        }
+        println!("✓ All {} CRDs are up to date", crds.len());
+        return Ok(());
+    }
+
+    let generated_files = if cli.single_file {
+        vec![generate_single_file(&cli.out_dir, &conversion)?]
+    } else {
+        generate_all_crds(&cli.out_dir, &conversion)?
+    };
+    let patches = if cli.conversion_patches {
+        write_conversion_patches(&cli.out_dir, &conversion)?
+    } else {
+        Vec::new()
+    };
+    let kustomization = write_kustomization(&cli.out_dir, &generated_files, &patches)?;
+    for filename in generated_files
+        .iter()
+        .chain(&patches)
+        .chain([&kustomization])
+    {
+        println!("✓ Generated {}/{}", cli.out_dir.display(), filename);
+    }
 
     println!("\nAll CRDs generated successfully!");
-    println!("Apply them with: kubectl apply -k config/crds/");
-    println!("Or directly: kubectl apply -f config/crds/standard/");
+    println!(
+        "Apply them with: kubectl apply -k {}/",
+        cli.out_dir.display()
+    );
 
     Ok(())
 }
@@ -79,9 +452,17 @@ fn main() -> anyhow::Result<()> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use serde::Deserialize;
     use std::fs;
     use tempfile::TempDir;
 
+    fn conversion() -> ConversionConfig {
+        ConversionConfig {
+            namespace: webhook::DEFAULT_NAMESPACE.to_string(),
+            ca_bundle: None,
+        }
+    }
+
     #[test]
     fn test_generate_crd_filename() {
         let filename = generate_crd_filename("bexxmodd.com", "theleagues");
@@ -129,10 +510,10 @@ mod tests {
         let temp_dir = TempDir::new().unwrap();
         let output_dir = temp_dir.path();
 
-        let generated_files = generate_all_crds(output_dir).unwrap();
+        let generated_files = generate_all_crds(output_dir, &conversion()).unwrap();
 
-        // Should generate 3 files
-        assert_eq!(generated_files.len(), 3);
+        // Should generate 13 files
+        assert_eq!(generated_files.len(), 13);
 
         // Check all files exist
         for filename in &generated_files {
@@ -162,6 +543,31 @@ mod tests {
             filenames_str.contains("gameresults"),
             "Should contain gameresults"
         );
+        assert!(
+            filenames_str.contains("suspensions"),
+            "Should contain suspensions"
+        );
+        assert!(filenames_str.contains("seasons"), "Should contain seasons");
+        assert!(filenames_str.contains("matches"), "Should contain matches");
+        assert!(filenames_str.contains("teams"), "Should contain teams");
+        assert!(filenames_str.contains("players"), "Should contain players");
+        assert!(
+            filenames_str.contains("playoffs"),
+            "Should contain playoffs"
+        );
+        assert!(
+            filenames_str.contains("tournaments"),
+            "Should contain tournaments"
+        );
+        assert!(
+            filenames_str.contains("transfers"),
+            "Should contain transfers"
+        );
+        assert!(
+            filenames_str.contains("referees"),
+            "Should contain referees"
+        );
+        assert!(filenames_str.contains("venues"), "Should contain venues");
     }
 
     #[test]
@@ -170,10 +576,24 @@ mod tests {
         let output_dir = temp_dir.path();
 
         // Generate all CRDs and get the actual filenames
-        let generated_files = generate_all_crds(output_dir).unwrap();
+        let generated_files = generate_all_crds(output_dir, &conversion()).unwrap();
 
         // Verify each CRD has required fields
-        let expected_kinds = vec!["TheLeague", "Standing", "GameResult"];
+        let expected_kinds = [
+            "TheLeague",
+            "Standing",
+            "GameResult",
+            "Suspension",
+            "Season",
+            "Match",
+            "Team",
+            "Player",
+            "Playoff",
+            "Tournament",
+            "Transfer",
+            "Referee",
+            "Venue",
+        ];
 
         for (i, filename) in generated_files.iter().enumerate() {
             let file_path = output_dir.join(filename);
@@ -216,6 +636,296 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_theleague_serves_both_versions_storing_v1alpha1() {
+        let temp_dir = TempDir::new().unwrap();
+        let output_dir = temp_dir.path();
+
+        let generated_files = generate_all_crds(output_dir, &conversion()).unwrap();
+        let content = fs::read_to_string(output_dir.join(&generated_files[0])).unwrap();
+        let crd: serde_yaml::Value = serde_yaml::from_str(&content).unwrap();
+
+        let versions: Vec<(&str, bool, bool)> = crd["spec"]["versions"]
+            .as_sequence()
+            .unwrap()
+            .iter()
+            .map(|v| {
+                (
+                    v["name"].as_str().unwrap(),
+                    v["served"].as_bool().unwrap(),
+                    v["storage"].as_bool().unwrap(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            versions,
+            vec![("v1alpha1", true, true), ("v1alpha2", true, false)]
+        );
+    }
+
+    #[test]
+    fn test_multi_version_crds_use_the_conversion_webhook() {
+        let temp_dir = TempDir::new().unwrap();
+        let output_dir = temp_dir.path();
+
+        let generated_files = generate_all_crds(output_dir, &conversion()).unwrap();
+        for filename in &generated_files[..3] {
+            let content = fs::read_to_string(output_dir.join(filename)).unwrap();
+            let crd: serde_yaml::Value = serde_yaml::from_str(&content).unwrap();
+            assert_eq!(crd["spec"]["versions"][1]["served"].as_bool(), Some(true));
+
+            let conversion = &crd["spec"]["conversion"];
+            assert_eq!(conversion["strategy"].as_str(), Some("Webhook"));
+            let service = &conversion["webhook"]["clientConfig"]["service"];
+            assert_eq!(
+                service["name"].as_str(),
+                Some(webhook::WEBHOOK_SERVICE_NAME)
+            );
+            assert_eq!(service["path"].as_str(), Some(webhook::CONVERT_PATH));
+            // Injected by the controller, not by an annotation pointing at a Certificate
+            assert!(conversion["webhook"]["clientConfig"]["caBundle"].is_null());
+            assert!(crd["metadata"]["annotations"].is_null());
+        }
+
+        let bundled = ConversionConfig {
+            ca_bundle: Some(b"-----BEGIN CERTIFICATE-----".to_vec()),
+            ..conversion()
+        };
+        let crd = with_conversion_webhook(TheLeague::crd(), &bundled);
+        let client_config = crd
+            .spec
+            .conversion
+            .unwrap()
+            .webhook
+            .unwrap()
+            .client_config
+            .unwrap();
+        assert!(client_config.ca_bundle.is_some());
+        assert!(crd.metadata.annotations.is_none());
+    }
+
+    #[test]
+    fn test_printer_columns() {
+        let columns = |crd: CustomResourceDefinition| -> Vec<String> {
+            crd.spec.versions[0]
+                .additional_printer_columns
+                .iter()
+                .flatten()
+                .map(|c| c.name.clone())
+                .collect()
+        };
+        assert_eq!(columns(TheLeague::crd()), ["Teams", "Phase", "Live", "Age"]);
+        assert_eq!(
+            columns(Standing::crd()),
+            ["Points", "W", "D", "L", "Rank", "Age"]
+        );
+        assert_eq!(
+            columns(GameResult::crd()),
+            ["Round", "Teams", "Score", "Age"]
+        );
+    }
+
+    #[test]
+    fn test_theleague_limits_teams_to_max_teams() {
+        let crd = serde_json::to_value(TheLeague::crd()).unwrap();
+        let spec = &crd["spec"]["versions"][0]["schema"]["openAPIV3Schema"]["properties"]["spec"];
+        assert_eq!(
+            spec["x-kubernetes-validations"][0]["rule"].as_str(),
+            Some("size(self.teams) <= self.maxTeams")
+        );
+    }
+
+    #[test]
+    fn test_theleague_defaults_are_in_the_schema() {
+        let crd = serde_json::to_value(TheLeague::crd()).unwrap();
+        let spec = &crd["spec"]["versions"][0]["schema"]["openAPIV3Schema"]["properties"]["spec"];
+        assert_eq!(spec["properties"]["matchups"]["default"], 1);
+        assert_eq!(
+            spec["properties"]["pointsPolicy"]["default"],
+            serde_json::json!({"win": 3, "draw": 1, "loss": 0, "overtimeWin": 3, "overtimeLoss": 0})
+        );
+        let required = spec["required"].as_array().unwrap();
+        assert!(!required.contains(&serde_json::json!("matchups")));
+        assert!(!required.contains(&serde_json::json!("pointsPolicy")));
+    }
+
+    #[test]
+    fn test_reference_fields_are_immutable() {
+        let rule = |crd: CustomResourceDefinition, field: &str| {
+            let crd = serde_json::to_value(crd).unwrap();
+            let spec =
+                &crd["spec"]["versions"][0]["schema"]["openAPIV3Schema"]["properties"]["spec"];
+            spec["properties"][field]["x-kubernetes-validations"][0]["rule"]
+                .as_str()
+                .map(str::to_string)
+        };
+        for field in ["leagueName", "leagueNamespace", "teamName"] {
+            assert_eq!(
+                rule(Standing::crd(), field).as_deref(),
+                Some("self == oldSelf")
+            );
+        }
+        for field in ["leagueName", "leagueNamespace", "teams"] {
+            assert_eq!(
+                rule(GameResult::crd(), field).as_deref(),
+                Some("self == oldSelf")
+            );
+        }
+        // Self-equality rules only run when the field is set on both sides.
+        for crd in [
+            Standing::crd(),
+            v1alpha2::standing_types::Standing::crd(),
+            GameResult::crd(),
+            v1alpha2::game_result_types::GameResult::crd(),
+        ] {
+            let crd = serde_json::to_value(crd).unwrap();
+            let spec =
+                &crd["spec"]["versions"][0]["schema"]["openAPIV3Schema"]["properties"]["spec"];
+            assert_eq!(
+                spec["x-kubernetes-validations"][0]["rule"].as_str(),
+                Some("has(self.leagueNamespace) == has(oldSelf.leagueNamespace)")
+            );
+        }
+    }
+
+    #[test]
+    fn test_standing_resolution_defaults_to_goal_difference() {
+        let temp_dir = TempDir::new().unwrap();
+        let output_dir = temp_dir.path();
+
+        let filename = generate_crd_file(std::marker::PhantomData::<Standing>, output_dir).unwrap();
+        let content = fs::read_to_string(output_dir.join(&filename)).unwrap();
+        let crd: serde_yaml::Value = serde_yaml::from_str(&content).unwrap();
+
+        let spec = &crd["spec"]["versions"][0]["schema"]["openAPIV3Schema"]["properties"]["spec"];
+        assert_eq!(
+            spec["properties"]["resolution"]["default"].as_str(),
+            Some("GoalDifference"),
+            "resolution should default to GoalDifference"
+        );
+        let required: Vec<&str> = spec["required"]
+            .as_sequence()
+            .unwrap()
+            .iter()
+            .filter_map(|v| v.as_str())
+            .collect();
+        assert!(
+            !required.contains(&"resolution"),
+            "resolution should be optional"
+        );
+    }
+
+    #[test]
+    fn test_single_file_holds_every_crd() {
+        let temp_dir = TempDir::new().unwrap();
+        let output_dir = temp_dir.path();
+
+        let filename = generate_single_file(output_dir, &conversion()).unwrap();
+        assert_eq!(filename, SINGLE_FILE_NAME);
+        let content = fs::read_to_string(output_dir.join(&filename)).unwrap();
+        let kinds: Vec<String> = serde_yaml::Deserializer::from_str(&content)
+            .map(|document| {
+                let crd = serde_yaml::Value::deserialize(document).unwrap();
+                crd["spec"]["names"]["kind"].as_str().unwrap().to_string()
+            })
+            .collect();
+        assert_eq!(kinds.len(), 13);
+        assert_eq!(kinds[0], "TheLeague");
+        assert_eq!(kinds[12], "Venue");
+    }
+
+    #[test]
+    fn test_kustomization_lists_the_generated_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let output_dir = temp_dir.path();
+
+        let generated_files = generate_all_crds(output_dir, &conversion()).unwrap();
+        let patches = write_conversion_patches(output_dir, &conversion()).unwrap();
+        assert_eq!(
+            patches,
+            [
+                "patches/webhook_in_theleagues.yaml",
+                "patches/webhook_in_standings.yaml",
+                "patches/webhook_in_gameresults.yaml"
+            ]
+        );
+        let filename = write_kustomization(output_dir, &generated_files, &patches).unwrap();
+
+        let content = fs::read_to_string(output_dir.join(filename)).unwrap();
+        let kustomization: serde_yaml::Value = serde_yaml::from_str(&content).unwrap();
+        assert_eq!(kustomization["kind"].as_str(), Some("Kustomization"));
+        let resources: Vec<&str> = kustomization["resources"]
+            .as_sequence()
+            .unwrap()
+            .iter()
+            .filter_map(|r| r.as_str())
+            .collect();
+        assert_eq!(resources, generated_files);
+        assert_eq!(
+            kustomization["patches"][0]["path"].as_str(),
+            Some("patches/webhook_in_theleagues.yaml")
+        );
+
+        let content = fs::read_to_string(output_dir.join(&patches[0])).unwrap();
+        let patch: serde_yaml::Value = serde_yaml::from_str(&content).unwrap();
+        assert_eq!(
+            patch["metadata"]["name"].as_str(),
+            Some("theleagues.bexxmodd.com")
+        );
+        assert_eq!(
+            patch["spec"]["conversion"]["strategy"].as_str(),
+            Some("Webhook")
+        );
+    }
+
+    #[test]
+    fn test_verify_files_reports_drifted_crds() {
+        let temp_dir = TempDir::new().unwrap();
+        let output_dir = temp_dir.path();
+        let crds = all_crds(&conversion()).unwrap();
+
+        generate_all_crds(output_dir, &conversion()).unwrap();
+        assert_eq!(verify_files(&crds, output_dir, false).unwrap(), []);
+        // Nothing was written in a single file yet
+        let drifts = verify_files(&crds, output_dir, true).unwrap();
+        assert_eq!(drifts.len(), 1);
+
+        let filename = generate_crd_filename("bexxmodd.com", "venues");
+        let path = output_dir.join(&filename);
+        let content = fs::read_to_string(&path).unwrap();
+        fs::write(&path, content.replace("address", "street")).unwrap();
+        let drifts = verify_files(&crds, output_dir, false).unwrap();
+        assert_eq!(drifts.len(), 1);
+        assert_eq!(drifts[0].name, "venues.bexxmodd.com");
+        assert!(drifts[0].diff.contains("+++ generated"));
+        let changed = |sign: char, word: &str| {
+            drifts[0]
+                .diff
+                .lines()
+                .any(|line| line.starts_with(sign) && line.ends_with(&format!("{}:", word)))
+        };
+        assert!(changed('-', "street") && changed('+', "address"));
+    }
+
+    #[test]
+    fn test_cli_output_modes() {
+        let cli = Cli::try_parse_from(["generate-crds"]).unwrap();
+        assert_eq!(cli.out_dir, PathBuf::from(DEFAULT_OUT_DIR));
+        assert!(!cli.stdout && !cli.single_file);
+
+        let cli = Cli::try_parse_from(["generate-crds", "--out-dir", "out", "--single-file"]);
+        assert_eq!(cli.unwrap().out_dir, PathBuf::from("out"));
+        assert!(
+            Cli::try_parse_from(["generate-crds", "--stdout"])
+                .unwrap()
+                .stdout
+        );
+        assert!(Cli::try_parse_from(["generate-crds", "--stdout", "--out-dir", "out"]).is_err());
+        assert!(Cli::try_parse_from(["generate-crds", "--cluster"]).is_err());
+        let cli = Cli::try_parse_from(["generate-crds", "--verify", "--cluster"]).unwrap();
+        assert!(cli.verify && cli.cluster);
+    }
+
     #[test]
     fn test_directory_creation() {
         let temp_dir = TempDir::new().unwrap();
@@ -225,7 +935,7 @@ mod tests {
         assert!(!output_dir.exists());
 
         // Generate CRDs (should create directory)
-        generate_all_crds(&output_dir).unwrap();
+        generate_all_crds(&output_dir, &conversion()).unwrap();
 
         // Directory should now exist
         assert!(output_dir.exists(), "Output directory should be created");
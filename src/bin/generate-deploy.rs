@@ -0,0 +1,416 @@
+//! Binary to generate the deployment manifests of the TheLeague controller: its Namespace,
+//! Deployment and the Service of its probe and metrics ports, and the kustomization of
+//! `config/` tying them to the generated CRDs, RBAC and webhooks, so
+//! `kubectl apply -k config/` installs the controller with nothing written by hand.
+//!
+//! The ports, probes and names are taken from the controller, so the manifests can't drift
+//! from what the binary serves. The webhook certificate isn't mounted: the controller issues a
+//! self-signed one into its Secret on startup and injects its CA into the webhooks.
+//!
+//! Run with: `cargo run --bin generate-deploy`, e.g.
+//! `cargo run --bin generate-deploy -- --image ghcr.io/bexxmodd/theleague --tag v0.2.0`
+
+use clap::Parser;
+use k8s_openapi::api::apps::v1::{Deployment, DeploymentSpec};
+use k8s_openapi::api::core::v1::{
+    Capabilities, Container, ContainerPort, EmptyDirVolumeSource, EnvVar, EnvVarSource,
+    HTTPGetAction, Namespace, ObjectFieldSelector, PodSecurityContext, PodSpec, PodTemplateSpec,
+    Probe, ResourceRequirements, SeccompProfile, SecurityContext, Service, ServicePort,
+    ServiceSpec, Volume, VolumeMount,
+};
+use k8s_openapi::apimachinery::pkg::api::resource::Quantity;
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::{LabelSelector, ObjectMeta};
+use k8s_openapi::apimachinery::pkg::util::intstr::IntOrString;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use the_league::controller;
+use the_league::webhook;
+
+const APP_NAME: &str = "theleague";
+const DEPLOYMENT_NAME: &str = "theleague-controller-manager";
+const SERVICE_NAME: &str = "theleague-controller-manager";
+/// ServiceAccount generated by generate-rbac
+const SERVICE_ACCOUNT_NAME: &str = "theleague-controller-manager";
+/// Directories of `config/` generated by the other generators, installed with the controller
+const GENERATED_DIRS: [&str; 3] = ["crds", "rbac", "webhook"];
+const MANAGER_DIR: &str = "manager";
+/// Component of the kube-rbac-proxy sidecar generated by `generate-rbac --metrics-proxy`
+const METRICS_PROXY_DIR: &str = "metrics-proxy";
+
+/// Labels selecting the controller pods, the same the webhook Service selects
+fn selector_labels() -> BTreeMap<String, String> {
+    BTreeMap::from([("app.kubernetes.io/name".to_string(), APP_NAME.to_string())])
+}
+
+/// Generate the deployment manifests of the controller
+#[derive(Parser, Debug, Clone)]
+#[command(name = "generate-deploy")]
+struct Cli {
+    /// Directory of the kustomization of the install; the manifests go to its manager/
+    #[arg(long, default_value = "config")]
+    out_dir: PathBuf,
+
+    /// Namespace the controller is deployed to
+    #[arg(long, default_value = webhook::DEFAULT_NAMESPACE)]
+    namespace: String,
+
+    /// Image of the controller, without its tag
+    #[arg(long, default_value = "theleague-controller")]
+    image: String,
+
+    /// Tag of the image
+    #[arg(long, default_value = "v1")]
+    tag: String,
+
+    /// Replicas of the Deployment; more than one elect a leader to run the controllers
+    #[arg(long, default_value_t = 1)]
+    replicas: i32,
+
+    /// CPU requested by the controller
+    #[arg(long, default_value = "100m")]
+    cpu_request: String,
+
+    /// Memory requested by the controller
+    #[arg(long, default_value = "128Mi")]
+    memory_request: String,
+
+    /// CPU limit of the controller
+    #[arg(long, default_value = "500m")]
+    cpu_limit: String,
+
+    /// Memory limit of the controller
+    #[arg(long, default_value = "512Mi")]
+    memory_limit: String,
+
+    /// Serve the metrics through the kube-rbac-proxy sidecar generated by
+    /// `generate-rbac --metrics-proxy`
+    #[arg(long)]
+    metrics_proxy: bool,
+}
+
+/// Generate the Namespace of the controller
+fn generate_namespace(cli: &Cli) -> Namespace {
+    Namespace {
+        metadata: ObjectMeta {
+            name: Some(cli.namespace.clone()),
+            labels: Some(selector_labels()),
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+/// Port of the controller container named `name`
+fn container_port(name: &str, port: u16) -> ContainerPort {
+    ContainerPort {
+        name: Some(name.to_string()),
+        container_port: i32::from(port),
+        protocol: Some("TCP".to_string()),
+        ..Default::default()
+    }
+}
+
+/// Probe of `path` on the probe port
+fn http_probe(path: &str, initial_delay_seconds: i32) -> Probe {
+    Probe {
+        http_get: Some(HTTPGetAction {
+            path: Some(path.to_string()),
+            port: IntOrString::String("probes".to_string()),
+            ..Default::default()
+        }),
+        initial_delay_seconds: Some(initial_delay_seconds),
+        period_seconds: Some(10),
+        ..Default::default()
+    }
+}
+
+/// Generate the Deployment of the controller
+///
+/// Following kube.rs security guidelines the container runs as non-root on a read-only root
+/// filesystem with every capability dropped; `/tmp`, where the self-signed webhook certificate
+/// is written, is an emptyDir.
+fn generate_deployment(cli: &Cli) -> Deployment {
+    let resources = |cpu: &str, memory: &str| {
+        BTreeMap::from([
+            ("cpu".to_string(), Quantity(cpu.to_string())),
+            ("memory".to_string(), Quantity(memory.to_string())),
+        ])
+    };
+    let mut env = vec![EnvVar {
+        name: "POD_NAMESPACE".to_string(),
+        value_from: Some(EnvVarSource {
+            field_ref: Some(ObjectFieldSelector {
+                field_path: "metadata.namespace".to_string(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }),
+        ..Default::default()
+    }];
+    // Replicas elect the one running the controllers; the others stand by
+    if cli.replicas > 1 {
+        env.push(EnvVar {
+            name: "LEADER_ELECT".to_string(),
+            value: Some("true".to_string()),
+            ..Default::default()
+        });
+    }
+    let container = Container {
+        name: "manager".to_string(),
+        image: Some(format!("{}:{}", cli.image, cli.tag)),
+        image_pull_policy: Some("IfNotPresent".to_string()),
+        env: Some(env),
+        ports: Some(vec![
+            container_port("probes", controller::PROBE_PORT),
+            container_port("metrics", controller::METRICS_PORT),
+            container_port("webhook", webhook::WEBHOOK_PORT),
+        ]),
+        liveness_probe: Some(http_probe("/healthz", 15)),
+        readiness_probe: Some(http_probe("/readyz", 5)),
+        resources: Some(ResourceRequirements {
+            requests: Some(resources(&cli.cpu_request, &cli.memory_request)),
+            limits: Some(resources(&cli.cpu_limit, &cli.memory_limit)),
+            ..Default::default()
+        }),
+        security_context: Some(SecurityContext {
+            allow_privilege_escalation: Some(false),
+            read_only_root_filesystem: Some(true),
+            capabilities: Some(Capabilities {
+                drop: Some(vec!["ALL".to_string()]),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }),
+        volume_mounts: Some(vec![VolumeMount {
+            name: "tmp".to_string(),
+            mount_path: "/tmp".to_string(),
+            ..Default::default()
+        }]),
+        ..Default::default()
+    };
+
+    Deployment {
+        metadata: ObjectMeta {
+            name: Some(DEPLOYMENT_NAME.to_string()),
+            namespace: Some(cli.namespace.clone()),
+            labels: Some(selector_labels()),
+            ..Default::default()
+        },
+        spec: Some(DeploymentSpec {
+            replicas: Some(cli.replicas),
+            selector: LabelSelector {
+                match_labels: Some(selector_labels()),
+                ..Default::default()
+            },
+            template: PodTemplateSpec {
+                metadata: Some(ObjectMeta {
+                    labels: Some(selector_labels()),
+                    ..Default::default()
+                }),
+                spec: Some(PodSpec {
+                    service_account_name: Some(SERVICE_ACCOUNT_NAME.to_string()),
+                    security_context: Some(PodSecurityContext {
+                        run_as_non_root: Some(true),
+                        seccomp_profile: Some(SeccompProfile {
+                            type_: "RuntimeDefault".to_string(),
+                            ..Default::default()
+                        }),
+                        ..Default::default()
+                    }),
+                    termination_grace_period_seconds: Some(10),
+                    containers: vec![container],
+                    volumes: Some(vec![Volume {
+                        name: "tmp".to_string(),
+                        empty_dir: Some(EmptyDirVolumeSource::default()),
+                        ..Default::default()
+                    }]),
+                    ..Default::default()
+                }),
+            },
+            ..Default::default()
+        }),
+        ..Default::default()
+    }
+}
+
+/// Service port named `name`, targeting the container port of the same name
+fn service_port(name: &str, port: u16) -> ServicePort {
+    ServicePort {
+        name: Some(name.to_string()),
+        port: i32::from(port),
+        protocol: Some("TCP".to_string()),
+        target_port: Some(IntOrString::String(name.to_string())),
+        ..Default::default()
+    }
+}
+
+/// Generate the Service of the probe server, serving the HTTP API, and of the metrics server
+fn generate_service(cli: &Cli) -> Service {
+    Service {
+        metadata: ObjectMeta {
+            name: Some(SERVICE_NAME.to_string()),
+            namespace: Some(cli.namespace.clone()),
+            labels: Some(selector_labels()),
+            ..Default::default()
+        },
+        spec: Some(ServiceSpec {
+            selector: Some(selector_labels()),
+            ports: Some(vec![
+                service_port("http", controller::PROBE_PORT),
+                service_port("metrics", controller::METRICS_PORT),
+            ]),
+            ..Default::default()
+        }),
+        ..Default::default()
+    }
+}
+
+/// Write a Kubernetes resource to a YAML file
+fn write_resource<T: serde::Serialize>(
+    resource: &T,
+    filename: &str,
+    output_dir: &Path,
+) -> anyhow::Result<()> {
+    fs::create_dir_all(output_dir)?;
+    fs::write(output_dir.join(filename), serde_yaml::to_string(resource)?)?;
+    println!("✓ Generated {}/{}", output_dir.display(), filename);
+    Ok(())
+}
+
+/// Write the kustomization of `resources` and `components` to `output_dir`, moving them to
+/// `namespace` if set
+fn write_kustomization(
+    output_dir: &Path,
+    namespace: Option<&str>,
+    resources: &[&str],
+    components: &[&str],
+) -> anyhow::Result<()> {
+    let mut kustomization = serde_json::json!({
+        "apiVersion": "kustomize.config.k8s.io/v1beta1",
+        "kind": "Kustomization",
+    });
+    if let Some(namespace) = namespace {
+        kustomization["namespace"] = namespace.into();
+    }
+    kustomization["resources"] = resources.into();
+    if !components.is_empty() {
+        kustomization["components"] = components.into();
+    }
+    write_resource(&kustomization, "kustomization.yaml", output_dir)
+}
+
+/// Generate all deployment manifests
+///
+/// Generates, in `manager/` of the output directory:
+/// - Namespace of the controller
+/// - Deployment of the controller
+/// - Service of the HTTP API and metrics
+///
+/// and the kustomization of the output directory installing them with the CRDs, RBAC and
+/// webhooks in the namespace, and with the kube-rbac-proxy component if `--metrics-proxy`.
+fn generate_all_deploy(cli: &Cli) -> anyhow::Result<()> {
+    let manager_dir = cli.out_dir.join(MANAGER_DIR);
+    write_resource(&generate_namespace(cli), "namespace.yaml", &manager_dir)?;
+    write_resource(&generate_deployment(cli), "deployment.yaml", &manager_dir)?;
+    write_resource(&generate_service(cli), "service.yaml", &manager_dir)?;
+    write_kustomization(
+        &manager_dir,
+        None,
+        &["namespace.yaml", "deployment.yaml", "service.yaml"],
+        &[],
+    )?;
+
+    let mut resources = GENERATED_DIRS.to_vec();
+    resources.push(MANAGER_DIR);
+    let components: &[&str] = if cli.metrics_proxy {
+        &[METRICS_PROXY_DIR]
+    } else {
+        &[]
+    };
+    write_kustomization(&cli.out_dir, Some(&cli.namespace), &resources, components)
+}
+
+fn main() -> anyhow::Result<()> {
+    let cli = Cli::parse();
+
+    generate_all_deploy(&cli)?;
+
+    println!("\nAll deployment manifests generated successfully!");
+    println!(
+        "Install the controller with: kubectl apply -k {}/",
+        cli.out_dir.display()
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn cli(args: &[&str]) -> Cli {
+        Cli::parse_from(std::iter::once("generate-deploy").chain(args.iter().copied()))
+    }
+
+    #[test]
+    fn test_deployment_follows_the_flags() {
+        let flags = cli(&["--image", "ghcr.io/bexxmodd/theleague", "--tag", "v0.2.0"]);
+        let deployment = generate_deployment(&flags);
+        let spec = deployment.spec.unwrap().template.spec.unwrap();
+        let container = &spec.containers[0];
+        assert_eq!(
+            container.image.as_deref(),
+            Some("ghcr.io/bexxmodd/theleague:v0.2.0")
+        );
+        assert_eq!(
+            spec.service_account_name.as_deref(),
+            Some(SERVICE_ACCOUNT_NAME)
+        );
+        let limits = container
+            .resources
+            .as_ref()
+            .unwrap()
+            .limits
+            .as_ref()
+            .unwrap();
+        assert_eq!(limits["memory"], Quantity("512Mi".to_string()));
+        let ports: Vec<i32> = container
+            .ports
+            .iter()
+            .flatten()
+            .map(|p| p.container_port)
+            .collect();
+        assert_eq!(ports, [8080, 8081, 9443]);
+        assert_eq!(container.env.as_ref().unwrap().len(), 1);
+
+        let deployment = generate_deployment(&cli(&["--replicas", "2"]));
+        let spec = deployment.spec.unwrap().template.spec.unwrap();
+        let env = spec.containers[0].env.clone().unwrap();
+        assert!(env.iter().any(|e| e.name == "LEADER_ELECT"));
+    }
+
+    #[test]
+    fn test_generate_all_deploy() {
+        let temp_dir = TempDir::new().unwrap();
+        let out_dir = temp_dir.path().to_str().unwrap();
+        generate_all_deploy(&cli(&["--out-dir", out_dir, "--namespace", "leagues"])).unwrap();
+
+        let content = fs::read_to_string(temp_dir.path().join("kustomization.yaml")).unwrap();
+        let kustomization: serde_yaml::Value = serde_yaml::from_str(&content).unwrap();
+        assert_eq!(kustomization["namespace"].as_str(), Some("leagues"));
+        assert_eq!(kustomization["resources"][3].as_str(), Some("manager"));
+        assert!(kustomization.get("components").is_none());
+        for file in [
+            "namespace.yaml",
+            "deployment.yaml",
+            "service.yaml",
+            "kustomization.yaml",
+        ] {
+            let path = temp_dir.path().join("manager").join(file);
+            assert!(path.exists(), "{} should exist", file);
+        }
+    }
+}
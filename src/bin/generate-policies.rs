@@ -0,0 +1,313 @@
+//! Binary to generate ValidatingAdmissionPolicies for the TheLeague resources.
+//!
+//! The policies cover the subset of the admission checks that can be expressed in CEL, for
+//! clusters where running the webhook server is undesirable: the score consistency and the
+//! self-match check of GameResults, and the maxTeams limit of TheLeagues. Everything else is
+//! still reported by the reconcilers through status conditions.
+//!
+//! Run with: `cargo run --bin generate-policies`, or
+//! `cargo run --bin generate-policies -- --stdout | kubectl apply -f -`
+
+use clap::Parser;
+use k8s_openapi::api::admissionregistration::v1::{
+    MatchResources, NamedRuleWithOperations, ValidatingAdmissionPolicy,
+    ValidatingAdmissionPolicyBinding, ValidatingAdmissionPolicyBindingSpec,
+    ValidatingAdmissionPolicySpec, Validation,
+};
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const GROUP: &str = "bexxmodd.com";
+const VERSION: &str = "v1alpha1";
+
+/// Generate the ValidatingAdmissionPolicies of the TheLeague resources
+#[derive(Parser, Debug)]
+#[command(name = "generate-policies")]
+struct Cli {
+    /// Directory to write the manifests to
+    #[arg(long, default_value = "config/policy", conflicts_with = "stdout")]
+    out_dir: PathBuf,
+
+    /// Print all manifests to stdout as one multi-document YAML instead of writing files
+    #[arg(long)]
+    stdout: bool,
+}
+
+/// A resource and the CEL validations of its policy
+struct PolicyTarget {
+    resource: &'static str,
+    validations: Vec<Validation>,
+}
+
+/// A validation denying with `message`, prefixed with `reason` like the webhook denials are
+fn validation(expression: String, reason: &str, message: &str) -> Validation {
+    Validation {
+        expression,
+        message: Some(format!("{}: {}", reason, message)),
+        reason: Some("Invalid".to_string()),
+        ..Default::default()
+    }
+}
+
+/// The score checks of a winner variant. `winner` and `loser` name the score fields and
+/// `shots` the shootout fields of (winner, loser), mirroring `validate_result_consistency`.
+fn winner_validations(
+    variant: &str,
+    winner: &str,
+    loser: &str,
+    shots: (&str, &str),
+) -> [Validation; 2] {
+    let result = format!("object.spec.result.{}", variant);
+    let shootout = format!(
+        "has({r}.decidedBy) && {r}.decidedBy == 'Shootout'",
+        r = result
+    );
+    [
+        validation(
+            format!(
+                "!has({r}) || ({s}) || ({r}.{w} > {r}.{l} && !has({r}.shootout))",
+                r = result,
+                s = shootout,
+                w = winner,
+                l = loser
+            ),
+            "InconsistentScore",
+            &format!(
+                "{} requires the winner to have the higher score and no shootout score",
+                variant
+            ),
+        ),
+        validation(
+            format!(
+                "!has({r}) || !({s}) || (has({r}.shootout) && {r}.{w} == {r}.{l} && \
+                 {r}.shootout.{sw} > {r}.shootout.{sl})",
+                r = result,
+                s = shootout,
+                w = winner,
+                l = loser,
+                sw = shots.0,
+                sl = shots.1
+            ),
+            "InconsistentScore",
+            &format!(
+                "{} decided by Shootout requires a level score and the winner to lead the shootout",
+                variant
+            ),
+        ),
+    ]
+}
+
+/// The resources guarded by a policy
+fn policy_targets() -> Vec<PolicyTarget> {
+    let mut game_result = vec![validation(
+        "object.spec.teams[0] != object.spec.teams[1]".to_string(),
+        "IdenticalTeams",
+        "a team cannot play against itself",
+    )];
+    game_result.extend(winner_validations(
+        "WinnerHomeTeam",
+        "score_home",
+        "score_away",
+        ("home", "away"),
+    ));
+    game_result.extend(winner_validations(
+        "WinnerAwayTeam",
+        "score_away",
+        "score_home",
+        ("away", "home"),
+    ));
+
+    vec![
+        PolicyTarget {
+            resource: "gameresults",
+            validations: game_result,
+        },
+        PolicyTarget {
+            resource: "theleagues",
+            validations: vec![validation(
+                "size(object.spec.teams) <= object.spec.maxTeams".to_string(),
+                "TooManyTeams",
+                "the league has more teams than maxTeams",
+            )],
+        },
+    ]
+}
+
+/// Name of the policy and binding of `target`
+fn policy_name(target: &PolicyTarget) -> String {
+    format!("{}.{}", target.resource, GROUP)
+}
+
+/// Generate the ValidatingAdmissionPolicy of `target`. Requests for other served versions are
+/// converted to v1alpha1 before the expressions are evaluated.
+fn generate_policy(target: &PolicyTarget) -> ValidatingAdmissionPolicy {
+    ValidatingAdmissionPolicy {
+        metadata: ObjectMeta {
+            name: Some(policy_name(target)),
+            ..Default::default()
+        },
+        spec: Some(ValidatingAdmissionPolicySpec {
+            failure_policy: Some("Fail".to_string()),
+            match_constraints: Some(MatchResources {
+                match_policy: Some("Equivalent".to_string()),
+                resource_rules: Some(vec![NamedRuleWithOperations {
+                    api_groups: Some(vec![GROUP.to_string()]),
+                    api_versions: Some(vec![VERSION.to_string()]),
+                    operations: Some(vec!["CREATE".to_string(), "UPDATE".to_string()]),
+                    resources: Some(vec![target.resource.to_string()]),
+                    ..Default::default()
+                }]),
+                ..Default::default()
+            }),
+            validations: Some(target.validations.clone()),
+            ..Default::default()
+        }),
+        ..Default::default()
+    }
+}
+
+/// Generate the binding enforcing the policy of `target` in all namespaces
+fn generate_binding(target: &PolicyTarget) -> ValidatingAdmissionPolicyBinding {
+    ValidatingAdmissionPolicyBinding {
+        metadata: ObjectMeta {
+            name: Some(policy_name(target)),
+            ..Default::default()
+        },
+        spec: Some(ValidatingAdmissionPolicyBindingSpec {
+            policy_name: Some(policy_name(target)),
+            validation_actions: Some(vec!["Deny".to_string()]),
+            ..Default::default()
+        }),
+    }
+}
+
+/// All policy manifests with the files they are written to, per guarded resource:
+/// - ValidatingAdmissionPolicy
+/// - ValidatingAdmissionPolicyBinding
+fn all_policies() -> anyhow::Result<Vec<(String, String)>> {
+    let mut manifests = Vec::new();
+    for target in policy_targets() {
+        let resource = target.resource.trim_end_matches('s');
+        manifests.push((
+            format!("{}_policy.yaml", resource),
+            serde_yaml::to_string(&generate_policy(&target))?,
+        ));
+        manifests.push((
+            format!("{}_policy_binding.yaml", resource),
+            serde_yaml::to_string(&generate_binding(&target))?,
+        ));
+    }
+    Ok(manifests)
+}
+
+/// The manifests as one multi-document YAML, e.g. for `kubectl apply -f -`
+fn multi_document_yaml(manifests: &[(String, String)]) -> String {
+    let mut yaml = String::new();
+    for (_, document) in manifests {
+        yaml.push_str("---\n");
+        yaml.push_str(document);
+    }
+    yaml
+}
+
+/// Write all policy manifests to `output_dir`
+fn generate_all_policies(output_dir: &Path) -> anyhow::Result<()> {
+    fs::create_dir_all(output_dir)?;
+    for (filename, yaml) in all_policies()? {
+        fs::write(output_dir.join(&filename), yaml)?;
+        println!("✓ Generated {}/{}", output_dir.display(), filename);
+    }
+    Ok(())
+}
+
+fn main() -> anyhow::Result<()> {
+    let cli = Cli::parse();
+
+    if cli.stdout {
+        print!("{}", multi_document_yaml(&all_policies()?));
+        return Ok(());
+    }
+
+    generate_all_policies(&cli.out_dir)?;
+
+    println!("\nAll admission policies generated successfully!");
+    println!(
+        "Apply them instead of the webhooks with: kubectl apply -k {}/",
+        cli.out_dir.display()
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_policies_cover_the_cel_expressible_checks() {
+        let targets = policy_targets();
+        let messages: Vec<_> = targets
+            .iter()
+            .flat_map(|t| &t.validations)
+            .map(|v| v.message.clone().unwrap())
+            .collect();
+        for reason in ["IdenticalTeams", "InconsistentScore", "TooManyTeams"] {
+            assert!(
+                messages.iter().any(|m| m.starts_with(reason)),
+                "{} should be covered",
+                reason
+            );
+        }
+
+        let policy = generate_policy(&targets[0]);
+        let binding = generate_binding(&targets[0]);
+        assert_eq!(
+            policy.metadata.name.as_deref(),
+            Some("gameresults.bexxmodd.com")
+        );
+        assert_eq!(binding.spec.unwrap().policy_name, policy.metadata.name);
+        let rule = &policy
+            .spec
+            .unwrap()
+            .match_constraints
+            .unwrap()
+            .resource_rules
+            .unwrap()[0];
+        assert_eq!(rule.operations.as_ref().unwrap(), &["CREATE", "UPDATE"]);
+    }
+
+    #[test]
+    fn test_generate_all_policies() {
+        let temp_dir = TempDir::new().unwrap();
+        generate_all_policies(temp_dir.path()).unwrap();
+
+        let content = fs::read_to_string(temp_dir.path().join("theleague_policy.yaml")).unwrap();
+        let policy: serde_yaml::Value = serde_yaml::from_str(&content).unwrap();
+        assert_eq!(
+            policy["spec"]["validations"][0]["expression"].as_str(),
+            Some("size(object.spec.teams) <= object.spec.maxTeams")
+        );
+        for file in [
+            "gameresult_policy.yaml",
+            "gameresult_policy_binding.yaml",
+            "theleague_policy_binding.yaml",
+        ] {
+            assert!(temp_dir.path().join(file).exists(), "{} should exist", file);
+        }
+    }
+
+    #[test]
+    fn test_cli_rejects_unknown_arguments() {
+        let cli = Cli::try_parse_from(["generate-policies", "--out-dir", "out"]).unwrap();
+        assert_eq!(cli.out_dir, PathBuf::from("out"));
+        assert!(Cli::try_parse_from(["generate-policies", "config/policy"]).is_err());
+        assert!(
+            Cli::try_parse_from(["generate-policies", "--stdout", "--out-dir", "out"]).is_err()
+        );
+
+        let yaml = multi_document_yaml(&all_policies().unwrap());
+        assert_eq!(yaml.matches("---\n").count(), 4);
+    }
+}
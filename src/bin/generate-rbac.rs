@@ -5,24 +5,86 @@
 //! - Least-privilege principle
 //! - Proper RBAC declarations
 //!
-//! Run with: `cargo run --bin generate-rbac`
+//! Run with: `cargo run --bin generate-rbac`, or `cargo run --bin generate-rbac -- --namespaced`
+//! for a controller watching a single namespace. `--stdout` prints the manifests for a pipeline
+//! instead, e.g. `cargo run --bin generate-rbac -- --stdout | kubectl apply -f -`
 
-use k8s_openapi::api::core::v1::ServiceAccount;
-use k8s_openapi::api::rbac::v1::{ClusterRole, ClusterRoleBinding, PolicyRule, RoleRef, Subject};
+use clap::Parser;
+use k8s_openapi::api::core::v1::{Service, ServiceAccount, ServicePort, ServiceSpec};
+use k8s_openapi::api::rbac::v1::{
+    ClusterRole, ClusterRoleBinding, PolicyRule, Role, RoleBinding, RoleRef, Subject,
+};
 use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
-use serde_yaml;
+use k8s_openapi::apimachinery::pkg::util::intstr::IntOrString;
 use std::collections::BTreeMap;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+
+use the_league::controller::{self, leader_election};
+use the_league::webhook::{self, certs};
 
 const GROUP: &str = "bexxmodd.com";
 const SERVICE_ACCOUNT_NAME: &str = "theleague-controller-manager";
 const ROLE_NAME: &str = "manager-role";
+const CLUSTER_ROLE_NAME: &str = "manager-cluster-role";
 const LEADER_ELECTION_ROLE_NAME: &str = "leader-election-role";
 const ADMIN_ROLE_NAME: &str = "theleague-admin-role";
 const EDITOR_ROLE_NAME: &str = "theleague-editor-role";
 const VIEWER_ROLE_NAME: &str = "theleague-viewer-role";
+const METRICS_READER_ROLE_NAME: &str = "theleague-metrics-reader";
+const PROXY_ROLE_NAME: &str = "theleague-proxy-role";
+const WEBHOOK_CERTS_ROLE_NAME: &str = "webhook-certs-role";
+const NOTIFICATION_SECRETS_ROLE_NAME: &str = "theleague-notification-secrets-reader";
+const METRICS_SERVICE_NAME: &str = "theleague-metrics-service";
 const APP_NAME: &str = "theleague";
+/// Deployment generated by generate-deploy, which the kube-rbac-proxy sidecar is patched into
+const DEPLOYMENT_NAME: &str = "theleague-controller-manager";
+const PROXY_IMAGE: &str = "quay.io/brancz/kube-rbac-proxy:v0.18.1";
+const PROXY_PORT: u16 = 8443;
+
+/// Cluster-scoped resources of the manager role, which only a ClusterRole can grant
+const CLUSTER_SCOPED_RESOURCES: [&str; 5] = [
+    "customresourcedefinitions",
+    "mutatingwebhookconfigurations",
+    "validatingwebhookconfigurations",
+    "tokenreviews",
+    "subjectaccessreviews",
+];
+
+/// Scope of the permissions of the controller
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Scope {
+    /// The controller watches all namespaces, with ClusterRoles and ClusterRoleBindings.
+    Cluster,
+    /// The controller watches a single namespace, `WATCH_NAMESPACE`, with Roles and
+    /// RoleBindings there. None is the namespace the manifests are deployed to.
+    Namespaced { watch_namespace: Option<String> },
+}
+
+/// Names of the generated RBAC resources
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Names {
+    /// Prefix of the names of the Roles, ClusterRoles and their bindings
+    role_prefix: String,
+    /// ServiceAccount the controller runs as
+    service_account: String,
+}
+
+impl Default for Names {
+    fn default() -> Self {
+        Names {
+            role_prefix: String::new(),
+            service_account: SERVICE_ACCOUNT_NAME.to_string(),
+        }
+    }
+}
+
+impl Names {
+    /// Name of the role, or binding, `name`
+    fn role(&self, name: &str) -> String {
+        format!("{}{}", self.role_prefix, name)
+    }
+}
 
 /// Generate the main ClusterRole with permissions for CRDs
 ///
@@ -30,10 +92,10 @@ const APP_NAME: &str = "theleague";
 /// - ClusterRole is used because the controller can watch all namespaces
 /// - Least-privilege: only the exact verbs needed for each resource
 /// - Status subresources are explicitly scoped
-fn generate_manager_role() -> ClusterRole {
+fn generate_manager_role(names: &Names) -> ClusterRole {
     ClusterRole {
         metadata: ObjectMeta {
-            name: Some(ROLE_NAME.to_string()),
+            name: Some(names.role(ROLE_NAME)),
             ..Default::default()
         },
         rules: Some(vec![
@@ -96,13 +158,237 @@ fn generate_manager_role() -> ClusterRole {
                 ],
                 ..Default::default()
             },
+            // GameResult status permissions
+            PolicyRule {
+                api_groups: Some(vec![GROUP.to_string()]),
+                resources: Some(vec!["gameresults/status".to_string()]),
+                verbs: vec!["get".to_string(), "update".to_string(), "patch".to_string()],
+                ..Default::default()
+            },
+            // Suspension CRD permissions
+            PolicyRule {
+                api_groups: Some(vec![GROUP.to_string()]),
+                resources: Some(vec!["suspensions".to_string()]),
+                verbs: vec![
+                    "get".to_string(),
+                    "list".to_string(),
+                    "watch".to_string(),
+                    "create".to_string(),
+                    "update".to_string(),
+                    "patch".to_string(),
+                    "delete".to_string(),
+                ],
+                ..Default::default()
+            },
+            // Season CRD permissions
+            PolicyRule {
+                api_groups: Some(vec![GROUP.to_string()]),
+                resources: Some(vec!["seasons".to_string()]),
+                verbs: vec![
+                    "get".to_string(),
+                    "list".to_string(),
+                    "watch".to_string(),
+                    "create".to_string(),
+                    "update".to_string(),
+                    "patch".to_string(),
+                    "delete".to_string(),
+                ],
+                ..Default::default()
+            },
+            // Season status permissions
+            PolicyRule {
+                api_groups: Some(vec![GROUP.to_string()]),
+                resources: Some(vec!["seasons/status".to_string()]),
+                verbs: vec!["get".to_string(), "update".to_string(), "patch".to_string()],
+                ..Default::default()
+            },
+            // Match CRD permissions
+            PolicyRule {
+                api_groups: Some(vec![GROUP.to_string()]),
+                resources: Some(vec!["matches".to_string()]),
+                verbs: vec![
+                    "get".to_string(),
+                    "list".to_string(),
+                    "watch".to_string(),
+                    "create".to_string(),
+                    "update".to_string(),
+                    "patch".to_string(),
+                    "delete".to_string(),
+                ],
+                ..Default::default()
+            },
+            // Match status permissions
+            PolicyRule {
+                api_groups: Some(vec![GROUP.to_string()]),
+                resources: Some(vec!["matches/status".to_string()]),
+                verbs: vec!["get".to_string(), "update".to_string(), "patch".to_string()],
+                ..Default::default()
+            },
+            // Team CRD permissions
+            PolicyRule {
+                api_groups: Some(vec![GROUP.to_string()]),
+                resources: Some(vec!["teams".to_string()]),
+                verbs: vec![
+                    "get".to_string(),
+                    "list".to_string(),
+                    "watch".to_string(),
+                    "update".to_string(),
+                    "patch".to_string(),
+                ],
+                ..Default::default()
+            },
+            // Team status permissions
+            PolicyRule {
+                api_groups: Some(vec![GROUP.to_string()]),
+                resources: Some(vec!["teams/status".to_string()]),
+                verbs: vec!["get".to_string(), "update".to_string(), "patch".to_string()],
+                ..Default::default()
+            },
+            // Player CRD permissions
+            PolicyRule {
+                api_groups: Some(vec![GROUP.to_string()]),
+                resources: Some(vec!["players".to_string()]),
+                verbs: vec!["get".to_string(), "list".to_string(), "watch".to_string()],
+                ..Default::default()
+            },
+            // Player status permissions
+            PolicyRule {
+                api_groups: Some(vec![GROUP.to_string()]),
+                resources: Some(vec!["players/status".to_string()]),
+                verbs: vec!["get".to_string(), "update".to_string(), "patch".to_string()],
+                ..Default::default()
+            },
+            // Playoff CRD permissions
+            PolicyRule {
+                api_groups: Some(vec![GROUP.to_string()]),
+                resources: Some(vec!["playoffs".to_string()]),
+                verbs: vec![
+                    "get".to_string(),
+                    "list".to_string(),
+                    "watch".to_string(),
+                    "create".to_string(),
+                    "update".to_string(),
+                    "patch".to_string(),
+                    "delete".to_string(),
+                ],
+                ..Default::default()
+            },
+            // Playoff status permissions
+            PolicyRule {
+                api_groups: Some(vec![GROUP.to_string()]),
+                resources: Some(vec!["playoffs/status".to_string()]),
+                verbs: vec!["get".to_string(), "update".to_string(), "patch".to_string()],
+                ..Default::default()
+            },
+            // Tournament CRD permissions
+            PolicyRule {
+                api_groups: Some(vec![GROUP.to_string()]),
+                resources: Some(vec!["tournaments".to_string()]),
+                verbs: vec![
+                    "get".to_string(),
+                    "list".to_string(),
+                    "watch".to_string(),
+                    "create".to_string(),
+                    "update".to_string(),
+                    "patch".to_string(),
+                    "delete".to_string(),
+                ],
+                ..Default::default()
+            },
+            // Tournament status permissions
+            PolicyRule {
+                api_groups: Some(vec![GROUP.to_string()]),
+                resources: Some(vec!["tournaments/status".to_string()]),
+                verbs: vec!["get".to_string(), "update".to_string(), "patch".to_string()],
+                ..Default::default()
+            },
+            // Transfer CRD permissions
+            PolicyRule {
+                api_groups: Some(vec![GROUP.to_string()]),
+                resources: Some(vec!["transfers".to_string()]),
+                verbs: vec![
+                    "get".to_string(),
+                    "list".to_string(),
+                    "watch".to_string(),
+                    "create".to_string(),
+                    "update".to_string(),
+                    "patch".to_string(),
+                    "delete".to_string(),
+                ],
+                ..Default::default()
+            },
+            // Transfer status permissions
+            PolicyRule {
+                api_groups: Some(vec![GROUP.to_string()]),
+                resources: Some(vec!["transfers/status".to_string()]),
+                verbs: vec!["get".to_string(), "update".to_string(), "patch".to_string()],
+                ..Default::default()
+            },
+            // Referee CRD permissions
+            PolicyRule {
+                api_groups: Some(vec![GROUP.to_string()]),
+                resources: Some(vec!["referees".to_string()]),
+                verbs: vec!["get".to_string(), "list".to_string(), "watch".to_string()],
+                ..Default::default()
+            },
+            // Venue CRD permissions
+            PolicyRule {
+                api_groups: Some(vec![GROUP.to_string()]),
+                resources: Some(vec!["venues".to_string()]),
+                verbs: vec!["get".to_string(), "list".to_string(), "watch".to_string()],
+                ..Default::default()
+            },
             // Events permissions (for controller events)
             PolicyRule {
-                api_groups: Some(vec!["".to_string()]),
+                api_groups: Some(vec!["".to_string(), "events.k8s.io".to_string()]),
                 resources: Some(vec!["events".to_string()]),
                 verbs: vec!["create".to_string(), "patch".to_string()],
                 ..Default::default()
             },
+            // ConfigMap permissions (for exported standings and the validation policy)
+            PolicyRule {
+                api_groups: Some(vec!["".to_string()]),
+                resources: Some(vec!["configmaps".to_string()]),
+                verbs: vec![
+                    "get".to_string(),
+                    "list".to_string(),
+                    "watch".to_string(),
+                    "create".to_string(),
+                    "patch".to_string(),
+                    "delete".to_string(),
+                ],
+                ..Default::default()
+            },
+            // Webhook configuration permissions (for injecting the self-signed caBundle)
+            PolicyRule {
+                api_groups: Some(vec!["admissionregistration.k8s.io".to_string()]),
+                resources: Some(vec![
+                    "mutatingwebhookconfigurations".to_string(),
+                    "validatingwebhookconfigurations".to_string(),
+                ]),
+                verbs: vec!["get".to_string(), "update".to_string()],
+                ..Default::default()
+            },
+            // CRD permissions (for injecting the self-signed caBundle into conversion webhooks)
+            PolicyRule {
+                api_groups: Some(vec!["apiextensions.k8s.io".to_string()]),
+                resources: Some(vec!["customresourcedefinitions".to_string()]),
+                verbs: vec!["get".to_string(), "patch".to_string()],
+                ..Default::default()
+            },
+            // Token and access review permissions (for authenticating submitted results)
+            PolicyRule {
+                api_groups: Some(vec!["authentication.k8s.io".to_string()]),
+                resources: Some(vec!["tokenreviews".to_string()]),
+                verbs: vec!["create".to_string()],
+                ..Default::default()
+            },
+            PolicyRule {
+                api_groups: Some(vec!["authorization.k8s.io".to_string()]),
+                resources: Some(vec!["subjectaccessreviews".to_string()]),
+                verbs: vec!["create".to_string()],
+                ..Default::default()
+            },
         ]),
         ..Default::default()
     }
@@ -110,40 +396,81 @@ fn generate_manager_role() -> ClusterRole {
 
 /// Generate leader election ClusterRole
 ///
-/// Required for controller coordination when multiple replicas run.
-/// Uses coordination.k8s.io/leases for leader election.
-fn generate_leader_election_role() -> ClusterRole {
+/// Required for controller coordination when multiple replicas run with LEADER_ELECT=true.
+/// The rule grants exactly the requests the leader election of the controller makes on its
+/// Lease, taken from the same constants.
+fn generate_leader_election_role(names: &Names) -> ClusterRole {
+    ClusterRole {
+        metadata: ObjectMeta {
+            name: Some(names.role(LEADER_ELECTION_ROLE_NAME)),
+            ..Default::default()
+        },
+        rules: Some(vec![PolicyRule {
+            api_groups: Some(vec![leader_election::LEASE_GROUP.to_string()]),
+            resources: Some(vec![leader_election::LEASE_RESOURCE.to_string()]),
+            verbs: leader_election::LEASE_VERBS
+                .iter()
+                .map(|v| v.to_string())
+                .collect(),
+            ..Default::default()
+        }]),
+        ..Default::default()
+    }
+}
+
+/// Generate the metrics reader ClusterRole
+///
+/// Bound by cluster admins to the ServiceAccount of Prometheus, to scrape the metrics served
+/// behind kube-rbac-proxy.
+fn generate_metrics_reader_role(names: &Names) -> ClusterRole {
     ClusterRole {
         metadata: ObjectMeta {
-            name: Some(LEADER_ELECTION_ROLE_NAME.to_string()),
+            name: Some(names.role(METRICS_READER_ROLE_NAME)),
             ..Default::default()
         },
         rules: Some(vec![PolicyRule {
-            api_groups: Some(vec!["coordination.k8s.io".to_string()]),
-            resources: Some(vec!["leases".to_string()]),
-            verbs: vec![
-                "get".to_string(),
-                "list".to_string(),
-                "watch".to_string(),
-                "create".to_string(),
-                "update".to_string(),
-                "patch".to_string(),
-                "delete".to_string(),
-            ],
+            non_resource_urls: Some(vec!["/metrics".to_string()]),
+            verbs: vec!["get".to_string()],
             ..Default::default()
         }]),
         ..Default::default()
     }
 }
 
+/// Generate the ClusterRole of kube-rbac-proxy, which reviews the token and access of every
+/// scrape of the metrics
+fn generate_proxy_role(names: &Names) -> ClusterRole {
+    ClusterRole {
+        metadata: ObjectMeta {
+            name: Some(names.role(PROXY_ROLE_NAME)),
+            ..Default::default()
+        },
+        rules: Some(vec![
+            PolicyRule {
+                api_groups: Some(vec!["authentication.k8s.io".to_string()]),
+                resources: Some(vec!["tokenreviews".to_string()]),
+                verbs: vec!["create".to_string()],
+                ..Default::default()
+            },
+            PolicyRule {
+                api_groups: Some(vec!["authorization.k8s.io".to_string()]),
+                resources: Some(vec!["subjectaccessreviews".to_string()]),
+                verbs: vec!["create".to_string()],
+                ..Default::default()
+            },
+        ]),
+        ..Default::default()
+    }
+}
+
 /// Generate ServiceAccount
 ///
 /// The ServiceAccount that the controller pods will use.
-/// Namespace can be specified via NAMESPACE environment variable.
-fn generate_service_account(namespace: Option<&str>) -> ServiceAccount {
+/// Namespace can be specified with --namespace.
+fn generate_service_account(names: &Names, namespace: Option<&str>) -> ServiceAccount {
     ServiceAccount {
         metadata: ObjectMeta {
-            name: Some(SERVICE_ACCOUNT_NAME.to_string()),
+            name: Some(names.service_account.clone()),
             namespace: namespace.map(|s| s.to_string()),
             ..Default::default()
         },
@@ -152,141 +479,639 @@ fn generate_service_account(namespace: Option<&str>) -> ServiceAccount {
 }
 
 /// Generate ClusterRoleBinding for the manager role
-fn generate_role_binding(namespace: Option<&str>) -> ClusterRoleBinding {
+fn generate_role_binding(names: &Names, namespace: Option<&str>) -> ClusterRoleBinding {
     ClusterRoleBinding {
         metadata: ObjectMeta {
-            name: Some(ROLE_NAME.to_string()),
+            name: Some(names.role(ROLE_NAME)),
             ..Default::default()
         },
         role_ref: RoleRef {
             api_group: "rbac.authorization.k8s.io".to_string(),
             kind: "ClusterRole".to_string(),
-            name: ROLE_NAME.to_string(),
+            name: names.role(ROLE_NAME),
         },
         subjects: Some(vec![Subject {
             kind: "ServiceAccount".to_string(),
-            name: SERVICE_ACCOUNT_NAME.to_string(),
+            name: names.service_account.clone(),
             namespace: namespace.map(|s| s.to_string()),
             ..Default::default()
         }]),
-        ..Default::default()
     }
 }
 
 /// Generate ClusterRoleBinding for leader election
-fn generate_leader_election_role_binding(namespace: Option<&str>) -> ClusterRoleBinding {
+fn generate_leader_election_role_binding(
+    names: &Names,
+    namespace: Option<&str>,
+) -> ClusterRoleBinding {
     ClusterRoleBinding {
         metadata: ObjectMeta {
-            name: Some(LEADER_ELECTION_ROLE_NAME.to_string()),
+            name: Some(names.role(LEADER_ELECTION_ROLE_NAME)),
             ..Default::default()
         },
         role_ref: RoleRef {
             api_group: "rbac.authorization.k8s.io".to_string(),
             kind: "ClusterRole".to_string(),
-            name: LEADER_ELECTION_ROLE_NAME.to_string(),
+            name: names.role(LEADER_ELECTION_ROLE_NAME),
         },
         subjects: Some(vec![Subject {
             kind: "ServiceAccount".to_string(),
-            name: SERVICE_ACCOUNT_NAME.to_string(),
+            name: names.service_account.clone(),
             namespace: namespace.map(|s| s.to_string()),
             ..Default::default()
         }]),
+    }
+}
+
+/// Whether `rule` grants cluster-scoped resources
+fn is_cluster_scoped(rule: &PolicyRule) -> bool {
+    rule.resources
+        .iter()
+        .flatten()
+        .any(|resource| CLUSTER_SCOPED_RESOURCES.contains(&resource.as_str()))
+}
+
+/// Subject of the controller's ServiceAccount in `namespace`
+fn service_account_subject(names: &Names, namespace: Option<&str>) -> Subject {
+    Subject {
+        kind: "ServiceAccount".to_string(),
+        name: names.service_account.clone(),
+        namespace: namespace.map(|s| s.to_string()),
         ..Default::default()
     }
 }
 
-/// Generate admin ClusterRole
-///
-/// This rule is not used by the project theleague itself.
-/// It is provided to allow the cluster admin to help manage permissions for users.
-///
-/// Grants full permissions ('*') over bexxmodd.com resources.
-/// This role is intended for users authorized to modify roles and bindings within the cluster,
-/// enabling them to delegate specific permissions to other users or groups as needed.
-fn generate_admin_role() -> ClusterRole {
+/// Generate the manager Role of a controller watching `watch_namespace`, with the namespaced
+/// rules of the manager ClusterRole
+fn generate_namespaced_manager_role(names: &Names, watch_namespace: Option<&str>) -> Role {
+    let rules = generate_manager_role(names).rules.unwrap_or_default();
+    Role {
+        metadata: ObjectMeta {
+            name: Some(names.role(ROLE_NAME)),
+            namespace: watch_namespace.map(|s| s.to_string()),
+            ..Default::default()
+        },
+        rules: Some(
+            rules
+                .into_iter()
+                .filter(|r| !is_cluster_scoped(r))
+                .collect(),
+        ),
+    }
+}
+
+/// Generate the ClusterRole of the cluster-scoped rules of the manager ClusterRole, which a
+/// controller watching a single namespace still needs
+fn generate_manager_cluster_role(names: &Names) -> ClusterRole {
+    let rules = generate_manager_role(names).rules.unwrap_or_default();
     ClusterRole {
         metadata: ObjectMeta {
-            name: Some(ADMIN_ROLE_NAME.to_string()),
-            labels: Some({
-                let mut labels = BTreeMap::new();
-                labels.insert("app.kubernetes.io/name".to_string(), APP_NAME.to_string());
-                labels.insert(
-                    "app.kubernetes.io/managed-by".to_string(),
-                    "kustomize".to_string(),
-                );
-                labels
-            }),
+            name: Some(names.role(CLUSTER_ROLE_NAME)),
             ..Default::default()
         },
-        rules: Some(vec![
-            // TheLeague full permissions
-            PolicyRule {
-                api_groups: Some(vec![GROUP.to_string()]),
-                resources: Some(vec!["theleagues".to_string()]),
-                verbs: vec!["*".to_string()],
+        rules: Some(rules.into_iter().filter(is_cluster_scoped).collect()),
+        ..Default::default()
+    }
+}
+
+/// Generate a RoleBinding in `role_namespace` of the Role `role_name` to the ServiceAccount
+/// in `namespace`
+fn generate_namespaced_role_binding(
+    names: &Names,
+    role_name: &str,
+    role_namespace: Option<&str>,
+    namespace: Option<&str>,
+) -> RoleBinding {
+    RoleBinding {
+        metadata: ObjectMeta {
+            name: Some(names.role(role_name)),
+            namespace: role_namespace.map(|s| s.to_string()),
+            ..Default::default()
+        },
+        role_ref: RoleRef {
+            api_group: "rbac.authorization.k8s.io".to_string(),
+            kind: "Role".to_string(),
+            name: names.role(role_name),
+        },
+        subjects: Some(vec![service_account_subject(names, namespace)]),
+    }
+}
+
+/// Generate the ClusterRoleBinding of the ClusterRole `role_name` to the controller
+fn generate_cluster_role_binding(
+    names: &Names,
+    role_name: &str,
+    namespace: Option<&str>,
+) -> ClusterRoleBinding {
+    ClusterRoleBinding {
+        metadata: ObjectMeta {
+            name: Some(names.role(role_name)),
+            ..Default::default()
+        },
+        role_ref: RoleRef {
+            api_group: "rbac.authorization.k8s.io".to_string(),
+            kind: "ClusterRole".to_string(),
+            name: names.role(role_name),
+        },
+        subjects: Some(vec![service_account_subject(names, namespace)]),
+    }
+}
+
+/// Generate leader election Role, the namespaced counterpart of the leader election
+/// ClusterRole for the leases in the controller's namespace
+fn generate_namespaced_leader_election_role(names: &Names, namespace: Option<&str>) -> Role {
+    let cluster_role = generate_leader_election_role(names);
+    Role {
+        metadata: ObjectMeta {
+            name: cluster_role.metadata.name,
+            namespace: namespace.map(|s| s.to_string()),
+            ..Default::default()
+        },
+        rules: cluster_role.rules,
+    }
+}
+
+/// Generate the Role of the self-signed webhook certificate in the controller's namespace
+///
+/// Only the certificate Secret can be read and updated. Creating it can't be restricted to a
+/// name, so the create rule is still limited to the controller's own namespace.
+fn generate_webhook_certs_role(names: &Names, namespace: Option<&str>) -> Role {
+    Role {
+        metadata: ObjectMeta {
+            name: Some(names.role(WEBHOOK_CERTS_ROLE_NAME)),
+            namespace: namespace.map(|s| s.to_string()),
+            ..Default::default()
+        },
+        rules: Some(vec![
+            PolicyRule {
+                api_groups: Some(vec!["".to_string()]),
+                resources: Some(vec!["secrets".to_string()]),
+                resource_names: Some(vec![certs::CERT_SECRET_NAME.to_string()]),
+                verbs: vec!["get".to_string(), "update".to_string()],
+                ..Default::default()
+            },
+            PolicyRule {
+                api_groups: Some(vec!["".to_string()]),
+                resources: Some(vec!["secrets".to_string()]),
+                verbs: vec!["create".to_string()],
+                ..Default::default()
+            },
+        ]),
+    }
+}
+
+/// Generate the ClusterRole reading the notification token Secrets of leagues
+///
+/// It is not bound by default: cluster admins bind it with a RoleBinding to the controller in
+/// each namespace whose leagues send notifications with a token. The controller only uses
+/// Secrets labelled `bexxmodd.com/notification-token: "true"` even there.
+fn generate_notification_secrets_role(names: &Names) -> ClusterRole {
+    ClusterRole {
+        metadata: ObjectMeta {
+            name: Some(names.role(NOTIFICATION_SECRETS_ROLE_NAME)),
+            ..Default::default()
+        },
+        rules: Some(vec![PolicyRule {
+            api_groups: Some(vec!["".to_string()]),
+            resources: Some(vec!["secrets".to_string()]),
+            verbs: vec!["get".to_string()],
+            ..Default::default()
+        }]),
+        ..Default::default()
+    }
+}
+
+/// Generate admin ClusterRole
+///
+/// This rule is not used by the project theleague itself.
+/// It is provided to allow the cluster admin to help manage permissions for users.
+///
+/// Grants full permissions ('*') over bexxmodd.com resources.
+/// This role is intended for users authorized to modify roles and bindings within the cluster,
+/// enabling them to delegate specific permissions to other users or groups as needed.
+fn generate_admin_role(names: &Names) -> ClusterRole {
+    ClusterRole {
+        metadata: ObjectMeta {
+            name: Some(names.role(ADMIN_ROLE_NAME)),
+            labels: Some({
+                let mut labels = BTreeMap::new();
+                labels.insert("app.kubernetes.io/name".to_string(), APP_NAME.to_string());
+                labels.insert(
+                    "app.kubernetes.io/managed-by".to_string(),
+                    "kustomize".to_string(),
+                );
+                labels
+            }),
+            ..Default::default()
+        },
+        rules: Some(vec![
+            // TheLeague full permissions
+            PolicyRule {
+                api_groups: Some(vec![GROUP.to_string()]),
+                resources: Some(vec!["theleagues".to_string()]),
+                verbs: vec!["*".to_string()],
+                ..Default::default()
+            },
+            // TheLeague status permissions
+            PolicyRule {
+                api_groups: Some(vec![GROUP.to_string()]),
+                resources: Some(vec!["theleagues/status".to_string()]),
+                verbs: vec!["get".to_string()],
+                ..Default::default()
+            },
+            // Standing full permissions
+            PolicyRule {
+                api_groups: Some(vec![GROUP.to_string()]),
+                resources: Some(vec!["standings".to_string()]),
+                verbs: vec!["*".to_string()],
+                ..Default::default()
+            },
+            // Standing status permissions
+            PolicyRule {
+                api_groups: Some(vec![GROUP.to_string()]),
+                resources: Some(vec!["standings/status".to_string()]),
+                verbs: vec!["get".to_string()],
+                ..Default::default()
+            },
+            // GameResult full permissions
+            PolicyRule {
+                api_groups: Some(vec![GROUP.to_string()]),
+                resources: Some(vec!["gameresults".to_string()]),
+                verbs: vec!["*".to_string()],
+                ..Default::default()
+            },
+            // GameResult status permissions
+            PolicyRule {
+                api_groups: Some(vec![GROUP.to_string()]),
+                resources: Some(vec!["gameresults/status".to_string()]),
+                verbs: vec!["get".to_string()],
+                ..Default::default()
+            },
+            // Suspension full permissions
+            PolicyRule {
+                api_groups: Some(vec![GROUP.to_string()]),
+                resources: Some(vec!["suspensions".to_string()]),
+                verbs: vec!["*".to_string()],
+                ..Default::default()
+            },
+            // Season full permissions
+            PolicyRule {
+                api_groups: Some(vec![GROUP.to_string()]),
+                resources: Some(vec!["seasons".to_string()]),
+                verbs: vec!["*".to_string()],
+                ..Default::default()
+            },
+            // Season status permissions
+            PolicyRule {
+                api_groups: Some(vec![GROUP.to_string()]),
+                resources: Some(vec!["seasons/status".to_string()]),
+                verbs: vec!["get".to_string()],
+                ..Default::default()
+            },
+            // Match full permissions
+            PolicyRule {
+                api_groups: Some(vec![GROUP.to_string()]),
+                resources: Some(vec!["matches".to_string()]),
+                verbs: vec!["*".to_string()],
+                ..Default::default()
+            },
+            // Match status permissions
+            PolicyRule {
+                api_groups: Some(vec![GROUP.to_string()]),
+                resources: Some(vec!["matches/status".to_string()]),
+                verbs: vec!["get".to_string()],
+                ..Default::default()
+            },
+            // Team full permissions
+            PolicyRule {
+                api_groups: Some(vec![GROUP.to_string()]),
+                resources: Some(vec!["teams".to_string()]),
+                verbs: vec!["*".to_string()],
+                ..Default::default()
+            },
+            // Team status permissions
+            PolicyRule {
+                api_groups: Some(vec![GROUP.to_string()]),
+                resources: Some(vec!["teams/status".to_string()]),
+                verbs: vec!["get".to_string()],
+                ..Default::default()
+            },
+            // Player full permissions
+            PolicyRule {
+                api_groups: Some(vec![GROUP.to_string()]),
+                resources: Some(vec!["players".to_string()]),
+                verbs: vec!["*".to_string()],
+                ..Default::default()
+            },
+            // Player status permissions
+            PolicyRule {
+                api_groups: Some(vec![GROUP.to_string()]),
+                resources: Some(vec!["players/status".to_string()]),
+                verbs: vec!["get".to_string()],
+                ..Default::default()
+            },
+            // Playoff full permissions
+            PolicyRule {
+                api_groups: Some(vec![GROUP.to_string()]),
+                resources: Some(vec!["playoffs".to_string()]),
+                verbs: vec!["*".to_string()],
+                ..Default::default()
+            },
+            // Playoff status permissions
+            PolicyRule {
+                api_groups: Some(vec![GROUP.to_string()]),
+                resources: Some(vec!["playoffs/status".to_string()]),
+                verbs: vec!["get".to_string()],
+                ..Default::default()
+            },
+            // Tournament full permissions
+            PolicyRule {
+                api_groups: Some(vec![GROUP.to_string()]),
+                resources: Some(vec!["tournaments".to_string()]),
+                verbs: vec!["*".to_string()],
+                ..Default::default()
+            },
+            // Tournament status permissions
+            PolicyRule {
+                api_groups: Some(vec![GROUP.to_string()]),
+                resources: Some(vec!["tournaments/status".to_string()]),
+                verbs: vec!["get".to_string()],
+                ..Default::default()
+            },
+            // Transfer full permissions
+            PolicyRule {
+                api_groups: Some(vec![GROUP.to_string()]),
+                resources: Some(vec!["transfers".to_string()]),
+                verbs: vec!["*".to_string()],
+                ..Default::default()
+            },
+            // Transfer status permissions
+            PolicyRule {
+                api_groups: Some(vec![GROUP.to_string()]),
+                resources: Some(vec!["transfers/status".to_string()]),
+                verbs: vec!["get".to_string()],
+                ..Default::default()
+            },
+            // Referee full permissions
+            PolicyRule {
+                api_groups: Some(vec![GROUP.to_string()]),
+                resources: Some(vec!["referees".to_string()]),
+                verbs: vec!["*".to_string()],
+                ..Default::default()
+            },
+            // Venue full permissions
+            PolicyRule {
+                api_groups: Some(vec![GROUP.to_string()]),
+                resources: Some(vec!["venues".to_string()]),
+                verbs: vec!["*".to_string()],
+                ..Default::default()
+            },
+        ]),
+        ..Default::default()
+    }
+}
+
+/// Generate editor ClusterRole
+///
+/// This rule is not used by the project theleague itself.
+/// It is provided to allow the cluster admin to help manage permissions for users.
+///
+/// Grants permissions to create, update, and delete resources within the bexxmodd.com.
+/// This role is intended for users who need to manage these resources
+/// but should not control RBAC or manage permissions for others.
+fn generate_editor_role(names: &Names) -> ClusterRole {
+    ClusterRole {
+        metadata: ObjectMeta {
+            name: Some(names.role(EDITOR_ROLE_NAME)),
+            labels: Some({
+                let mut labels = BTreeMap::new();
+                labels.insert("app.kubernetes.io/name".to_string(), APP_NAME.to_string());
+                labels.insert(
+                    "app.kubernetes.io/managed-by".to_string(),
+                    "kustomize".to_string(),
+                );
+                labels
+            }),
+            ..Default::default()
+        },
+        rules: Some(vec![
+            // TheLeague editor permissions
+            PolicyRule {
+                api_groups: Some(vec![GROUP.to_string()]),
+                resources: Some(vec!["theleagues".to_string()]),
+                verbs: vec![
+                    "create".to_string(),
+                    "delete".to_string(),
+                    "get".to_string(),
+                    "list".to_string(),
+                    "patch".to_string(),
+                    "update".to_string(),
+                    "watch".to_string(),
+                ],
+                ..Default::default()
+            },
+            // TheLeague status permissions
+            PolicyRule {
+                api_groups: Some(vec![GROUP.to_string()]),
+                resources: Some(vec!["theleagues/status".to_string()]),
+                verbs: vec!["get".to_string()],
+                ..Default::default()
+            },
+            // Standing editor permissions
+            PolicyRule {
+                api_groups: Some(vec![GROUP.to_string()]),
+                resources: Some(vec!["standings".to_string()]),
+                verbs: vec![
+                    "create".to_string(),
+                    "delete".to_string(),
+                    "get".to_string(),
+                    "list".to_string(),
+                    "patch".to_string(),
+                    "update".to_string(),
+                    "watch".to_string(),
+                ],
+                ..Default::default()
+            },
+            // Standing status permissions
+            PolicyRule {
+                api_groups: Some(vec![GROUP.to_string()]),
+                resources: Some(vec!["standings/status".to_string()]),
+                verbs: vec!["get".to_string()],
+                ..Default::default()
+            },
+            // GameResult editor permissions
+            PolicyRule {
+                api_groups: Some(vec![GROUP.to_string()]),
+                resources: Some(vec!["gameresults".to_string()]),
+                verbs: vec![
+                    "create".to_string(),
+                    "delete".to_string(),
+                    "get".to_string(),
+                    "list".to_string(),
+                    "patch".to_string(),
+                    "update".to_string(),
+                    "watch".to_string(),
+                ],
+                ..Default::default()
+            },
+            // GameResult status permissions
+            PolicyRule {
+                api_groups: Some(vec![GROUP.to_string()]),
+                resources: Some(vec!["gameresults/status".to_string()]),
+                verbs: vec!["get".to_string()],
+                ..Default::default()
+            },
+            // Suspension editor permissions
+            PolicyRule {
+                api_groups: Some(vec![GROUP.to_string()]),
+                resources: Some(vec!["suspensions".to_string()]),
+                verbs: vec![
+                    "create".to_string(),
+                    "delete".to_string(),
+                    "get".to_string(),
+                    "list".to_string(),
+                    "patch".to_string(),
+                    "update".to_string(),
+                    "watch".to_string(),
+                ],
+                ..Default::default()
+            },
+            // Season editor permissions
+            PolicyRule {
+                api_groups: Some(vec![GROUP.to_string()]),
+                resources: Some(vec!["seasons".to_string()]),
+                verbs: vec![
+                    "create".to_string(),
+                    "delete".to_string(),
+                    "get".to_string(),
+                    "list".to_string(),
+                    "patch".to_string(),
+                    "update".to_string(),
+                    "watch".to_string(),
+                ],
+                ..Default::default()
+            },
+            // Season status permissions
+            PolicyRule {
+                api_groups: Some(vec![GROUP.to_string()]),
+                resources: Some(vec!["seasons/status".to_string()]),
+                verbs: vec!["get".to_string()],
+                ..Default::default()
+            },
+            // Match editor permissions
+            PolicyRule {
+                api_groups: Some(vec![GROUP.to_string()]),
+                resources: Some(vec!["matches".to_string()]),
+                verbs: vec![
+                    "create".to_string(),
+                    "delete".to_string(),
+                    "get".to_string(),
+                    "list".to_string(),
+                    "patch".to_string(),
+                    "update".to_string(),
+                    "watch".to_string(),
+                ],
+                ..Default::default()
+            },
+            // Match status permissions
+            PolicyRule {
+                api_groups: Some(vec![GROUP.to_string()]),
+                resources: Some(vec!["matches/status".to_string()]),
+                verbs: vec!["get".to_string()],
+                ..Default::default()
+            },
+            // Team editor permissions
+            PolicyRule {
+                api_groups: Some(vec![GROUP.to_string()]),
+                resources: Some(vec!["teams".to_string()]),
+                verbs: vec![
+                    "create".to_string(),
+                    "delete".to_string(),
+                    "get".to_string(),
+                    "list".to_string(),
+                    "patch".to_string(),
+                    "update".to_string(),
+                    "watch".to_string(),
+                ],
+                ..Default::default()
+            },
+            // Team status permissions
+            PolicyRule {
+                api_groups: Some(vec![GROUP.to_string()]),
+                resources: Some(vec!["teams/status".to_string()]),
+                verbs: vec!["get".to_string()],
+                ..Default::default()
+            },
+            // Player editor permissions
+            PolicyRule {
+                api_groups: Some(vec![GROUP.to_string()]),
+                resources: Some(vec!["players".to_string()]),
+                verbs: vec![
+                    "create".to_string(),
+                    "delete".to_string(),
+                    "get".to_string(),
+                    "list".to_string(),
+                    "patch".to_string(),
+                    "update".to_string(),
+                    "watch".to_string(),
+                ],
+                ..Default::default()
+            },
+            // Player status permissions
+            PolicyRule {
+                api_groups: Some(vec![GROUP.to_string()]),
+                resources: Some(vec!["players/status".to_string()]),
+                verbs: vec!["get".to_string()],
+                ..Default::default()
+            },
+            // Playoff editor permissions
+            PolicyRule {
+                api_groups: Some(vec![GROUP.to_string()]),
+                resources: Some(vec!["playoffs".to_string()]),
+                verbs: vec![
+                    "create".to_string(),
+                    "delete".to_string(),
+                    "get".to_string(),
+                    "list".to_string(),
+                    "patch".to_string(),
+                    "update".to_string(),
+                    "watch".to_string(),
+                ],
                 ..Default::default()
             },
-            // TheLeague status permissions
+            // Playoff status permissions
             PolicyRule {
                 api_groups: Some(vec![GROUP.to_string()]),
-                resources: Some(vec!["theleagues/status".to_string()]),
+                resources: Some(vec!["playoffs/status".to_string()]),
                 verbs: vec!["get".to_string()],
                 ..Default::default()
             },
-            // Standing full permissions
+            // Tournament editor permissions
             PolicyRule {
                 api_groups: Some(vec![GROUP.to_string()]),
-                resources: Some(vec!["standings".to_string()]),
-                verbs: vec!["*".to_string()],
+                resources: Some(vec!["tournaments".to_string()]),
+                verbs: vec![
+                    "create".to_string(),
+                    "delete".to_string(),
+                    "get".to_string(),
+                    "list".to_string(),
+                    "patch".to_string(),
+                    "update".to_string(),
+                    "watch".to_string(),
+                ],
                 ..Default::default()
             },
-            // Standing status permissions
+            // Tournament status permissions
             PolicyRule {
                 api_groups: Some(vec![GROUP.to_string()]),
-                resources: Some(vec!["standings/status".to_string()]),
+                resources: Some(vec!["tournaments/status".to_string()]),
                 verbs: vec!["get".to_string()],
                 ..Default::default()
             },
-            // GameResult full permissions
-            PolicyRule {
-                api_groups: Some(vec![GROUP.to_string()]),
-                resources: Some(vec!["gameresults".to_string()]),
-                verbs: vec!["*".to_string()],
-                ..Default::default()
-            },
-        ]),
-        ..Default::default()
-    }
-}
-
-/// Generate editor ClusterRole
-///
-/// This rule is not used by the project theleague itself.
-/// It is provided to allow the cluster admin to help manage permissions for users.
-///
-/// Grants permissions to create, update, and delete resources within the bexxmodd.com.
-/// This role is intended for users who need to manage these resources
-/// but should not control RBAC or manage permissions for others.
-fn generate_editor_role() -> ClusterRole {
-    ClusterRole {
-        metadata: ObjectMeta {
-            name: Some(EDITOR_ROLE_NAME.to_string()),
-            labels: Some({
-                let mut labels = BTreeMap::new();
-                labels.insert("app.kubernetes.io/name".to_string(), APP_NAME.to_string());
-                labels.insert(
-                    "app.kubernetes.io/managed-by".to_string(),
-                    "kustomize".to_string(),
-                );
-                labels
-            }),
-            ..Default::default()
-        },
-        rules: Some(vec![
-            // TheLeague editor permissions
+            // Transfer editor permissions
             PolicyRule {
                 api_groups: Some(vec![GROUP.to_string()]),
-                resources: Some(vec!["theleagues".to_string()]),
+                resources: Some(vec!["transfers".to_string()]),
                 verbs: vec![
                     "create".to_string(),
                     "delete".to_string(),
@@ -298,17 +1123,17 @@ fn generate_editor_role() -> ClusterRole {
                 ],
                 ..Default::default()
             },
-            // TheLeague status permissions
+            // Transfer status permissions
             PolicyRule {
                 api_groups: Some(vec![GROUP.to_string()]),
-                resources: Some(vec!["theleagues/status".to_string()]),
+                resources: Some(vec!["transfers/status".to_string()]),
                 verbs: vec!["get".to_string()],
                 ..Default::default()
             },
-            // Standing editor permissions
+            // Referee editor permissions
             PolicyRule {
                 api_groups: Some(vec![GROUP.to_string()]),
-                resources: Some(vec!["standings".to_string()]),
+                resources: Some(vec!["referees".to_string()]),
                 verbs: vec![
                     "create".to_string(),
                     "delete".to_string(),
@@ -320,17 +1145,10 @@ fn generate_editor_role() -> ClusterRole {
                 ],
                 ..Default::default()
             },
-            // Standing status permissions
-            PolicyRule {
-                api_groups: Some(vec![GROUP.to_string()]),
-                resources: Some(vec!["standings/status".to_string()]),
-                verbs: vec!["get".to_string()],
-                ..Default::default()
-            },
-            // GameResult editor permissions
+            // Venue editor permissions
             PolicyRule {
                 api_groups: Some(vec![GROUP.to_string()]),
-                resources: Some(vec!["gameresults".to_string()]),
+                resources: Some(vec!["venues".to_string()]),
                 verbs: vec![
                     "create".to_string(),
                     "delete".to_string(),
@@ -355,10 +1173,10 @@ fn generate_editor_role() -> ClusterRole {
 /// Grants read-only access to bexxmodd.com resources.
 /// This role is intended for users who need visibility into these resources
 /// without permissions to modify them. It is ideal for monitoring purposes and limited-access viewing.
-fn generate_viewer_role() -> ClusterRole {
+fn generate_viewer_role(names: &Names) -> ClusterRole {
     ClusterRole {
         metadata: ObjectMeta {
-            name: Some(VIEWER_ROLE_NAME.to_string()),
+            name: Some(names.role(VIEWER_ROLE_NAME)),
             labels: Some({
                 let mut labels = BTreeMap::new();
                 labels.insert("app.kubernetes.io/name".to_string(), APP_NAME.to_string());
@@ -406,6 +1224,132 @@ fn generate_viewer_role() -> ClusterRole {
                 verbs: vec!["get".to_string(), "list".to_string(), "watch".to_string()],
                 ..Default::default()
             },
+            // GameResult status permissions
+            PolicyRule {
+                api_groups: Some(vec![GROUP.to_string()]),
+                resources: Some(vec!["gameresults/status".to_string()]),
+                verbs: vec!["get".to_string()],
+                ..Default::default()
+            },
+            // Suspension viewer permissions
+            PolicyRule {
+                api_groups: Some(vec![GROUP.to_string()]),
+                resources: Some(vec!["suspensions".to_string()]),
+                verbs: vec!["get".to_string(), "list".to_string(), "watch".to_string()],
+                ..Default::default()
+            },
+            // Season viewer permissions
+            PolicyRule {
+                api_groups: Some(vec![GROUP.to_string()]),
+                resources: Some(vec!["seasons".to_string()]),
+                verbs: vec!["get".to_string(), "list".to_string(), "watch".to_string()],
+                ..Default::default()
+            },
+            // Season status permissions
+            PolicyRule {
+                api_groups: Some(vec![GROUP.to_string()]),
+                resources: Some(vec!["seasons/status".to_string()]),
+                verbs: vec!["get".to_string()],
+                ..Default::default()
+            },
+            // Match viewer permissions
+            PolicyRule {
+                api_groups: Some(vec![GROUP.to_string()]),
+                resources: Some(vec!["matches".to_string()]),
+                verbs: vec!["get".to_string(), "list".to_string(), "watch".to_string()],
+                ..Default::default()
+            },
+            // Match status permissions
+            PolicyRule {
+                api_groups: Some(vec![GROUP.to_string()]),
+                resources: Some(vec!["matches/status".to_string()]),
+                verbs: vec!["get".to_string()],
+                ..Default::default()
+            },
+            // Team viewer permissions
+            PolicyRule {
+                api_groups: Some(vec![GROUP.to_string()]),
+                resources: Some(vec!["teams".to_string()]),
+                verbs: vec!["get".to_string(), "list".to_string(), "watch".to_string()],
+                ..Default::default()
+            },
+            // Team status permissions
+            PolicyRule {
+                api_groups: Some(vec![GROUP.to_string()]),
+                resources: Some(vec!["teams/status".to_string()]),
+                verbs: vec!["get".to_string()],
+                ..Default::default()
+            },
+            // Player viewer permissions
+            PolicyRule {
+                api_groups: Some(vec![GROUP.to_string()]),
+                resources: Some(vec!["players".to_string()]),
+                verbs: vec!["get".to_string(), "list".to_string(), "watch".to_string()],
+                ..Default::default()
+            },
+            // Player status permissions
+            PolicyRule {
+                api_groups: Some(vec![GROUP.to_string()]),
+                resources: Some(vec!["players/status".to_string()]),
+                verbs: vec!["get".to_string()],
+                ..Default::default()
+            },
+            // Playoff viewer permissions
+            PolicyRule {
+                api_groups: Some(vec![GROUP.to_string()]),
+                resources: Some(vec!["playoffs".to_string()]),
+                verbs: vec!["get".to_string(), "list".to_string(), "watch".to_string()],
+                ..Default::default()
+            },
+            // Playoff status permissions
+            PolicyRule {
+                api_groups: Some(vec![GROUP.to_string()]),
+                resources: Some(vec!["playoffs/status".to_string()]),
+                verbs: vec!["get".to_string()],
+                ..Default::default()
+            },
+            // Tournament viewer permissions
+            PolicyRule {
+                api_groups: Some(vec![GROUP.to_string()]),
+                resources: Some(vec!["tournaments".to_string()]),
+                verbs: vec!["get".to_string(), "list".to_string(), "watch".to_string()],
+                ..Default::default()
+            },
+            // Tournament status permissions
+            PolicyRule {
+                api_groups: Some(vec![GROUP.to_string()]),
+                resources: Some(vec!["tournaments/status".to_string()]),
+                verbs: vec!["get".to_string()],
+                ..Default::default()
+            },
+            // Transfer viewer permissions
+            PolicyRule {
+                api_groups: Some(vec![GROUP.to_string()]),
+                resources: Some(vec!["transfers".to_string()]),
+                verbs: vec!["get".to_string(), "list".to_string(), "watch".to_string()],
+                ..Default::default()
+            },
+            // Transfer status permissions
+            PolicyRule {
+                api_groups: Some(vec![GROUP.to_string()]),
+                resources: Some(vec!["transfers/status".to_string()]),
+                verbs: vec!["get".to_string()],
+                ..Default::default()
+            },
+            // Referee viewer permissions
+            PolicyRule {
+                api_groups: Some(vec![GROUP.to_string()]),
+                resources: Some(vec!["referees".to_string()]),
+                verbs: vec!["get".to_string(), "list".to_string(), "watch".to_string()],
+                ..Default::default()
+            },
+            // Venue viewer permissions
+            PolicyRule {
+                api_groups: Some(vec![GROUP.to_string()]),
+                resources: Some(vec!["venues".to_string()]),
+                verbs: vec!["get".to_string(), "list".to_string(), "watch".to_string()],
+                ..Default::default()
+            },
         ]),
         ..Default::default()
     }
@@ -427,92 +1371,503 @@ fn write_resource<T: serde::Serialize>(
     Ok(())
 }
 
+/// Manifest is a resource rendered to YAML.
+trait Manifest {
+    fn to_yaml(&self) -> anyhow::Result<String>;
+}
+
+impl<T: serde::Serialize> Manifest for T {
+    fn to_yaml(&self) -> anyhow::Result<String> {
+        Ok(serde_yaml::to_string(self)?)
+    }
+}
+
+/// Manifests rendered to YAML with the files they go to, in the order they were generated
+#[derive(Default)]
+struct Manifests {
+    rendered: Vec<(String, String)>,
+}
+
+impl Manifests {
+    fn add(&mut self, resource: &dyn Manifest, filename: &str) -> anyhow::Result<()> {
+        self.rendered
+            .push((filename.to_string(), resource.to_yaml()?));
+        Ok(())
+    }
+
+    fn filenames(&self) -> Vec<String> {
+        self.rendered.iter().map(|(f, _)| f.clone()).collect()
+    }
+
+    /// Write every manifest to its file in `output_dir`
+    fn write(&self, output_dir: &Path) -> anyhow::Result<()> {
+        fs::create_dir_all(output_dir)?;
+        for (filename, yaml) in &self.rendered {
+            fs::write(output_dir.join(filename), yaml)?;
+            println!("✓ Generated {}/{}", output_dir.display(), filename);
+        }
+        Ok(())
+    }
+
+    /// The manifests as one multi-document YAML, e.g. for `kubectl apply -f -`
+    fn multi_document_yaml(&self) -> String {
+        let mut yaml = String::new();
+        for (_, document) in &self.rendered {
+            yaml.push_str("---\n");
+            yaml.push_str(document);
+        }
+        yaml
+    }
+}
+
+/// Write the kustomization of the manifests `resources` to `output_dir`
+fn write_kustomization(output_dir: &Path, resources: &[String]) -> anyhow::Result<()> {
+    let kustomization = serde_json::json!({
+        "apiVersion": "kustomize.config.k8s.io/v1beta1",
+        "kind": "Kustomization",
+        "resources": resources,
+    });
+    write_resource(&kustomization, "kustomization.yaml", output_dir)
+}
+
+/// Generate the Service of the metrics served by kube-rbac-proxy over HTTPS
+fn generate_metrics_service(namespace: Option<&str>) -> Service {
+    let labels = BTreeMap::from([("app.kubernetes.io/name".to_string(), APP_NAME.to_string())]);
+    Service {
+        metadata: ObjectMeta {
+            name: Some(METRICS_SERVICE_NAME.to_string()),
+            namespace: namespace.map(|s| s.to_string()),
+            labels: Some(labels.clone()),
+            ..Default::default()
+        },
+        spec: Some(ServiceSpec {
+            selector: Some(labels),
+            ports: Some(vec![ServicePort {
+                name: Some("https".to_string()),
+                port: i32::from(PROXY_PORT),
+                protocol: Some("TCP".to_string()),
+                target_port: Some(IntOrString::String("https".to_string())),
+                ..Default::default()
+            }]),
+            ..Default::default()
+        }),
+        ..Default::default()
+    }
+}
+
+/// Generate the patch of the controller Deployment adding the kube-rbac-proxy sidecar in
+/// front of the metrics server, which then only listens on localhost
+fn generate_proxy_patch(namespace: Option<&str>) -> serde_json::Value {
+    let metrics_addr = format!("127.0.0.1:{}", controller::METRICS_PORT);
+    serde_json::json!({
+        "apiVersion": "apps/v1",
+        "kind": "Deployment",
+        "metadata": {
+            "name": DEPLOYMENT_NAME,
+            "namespace": namespace.unwrap_or(webhook::DEFAULT_NAMESPACE),
+        },
+        "spec": {"template": {"spec": {"containers": [
+            {
+                "name": "kube-rbac-proxy",
+                "image": PROXY_IMAGE,
+                "args": [
+                    format!("--secure-listen-address=0.0.0.0:{}", PROXY_PORT),
+                    format!("--upstream=http://{}/", metrics_addr),
+                    "--v=0",
+                ],
+                "ports": [{"containerPort": PROXY_PORT, "name": "https", "protocol": "TCP"}],
+                "resources": {
+                    "limits": {"cpu": "500m", "memory": "128Mi"},
+                    "requests": {"cpu": "5m", "memory": "64Mi"},
+                },
+                "securityContext": {
+                    "allowPrivilegeEscalation": false,
+                    "capabilities": {"drop": ["ALL"]},
+                },
+            },
+            {
+                "name": "manager",
+                "env": [{"name": "METRICS_ADDR", "value": metrics_addr}],
+            },
+        ]}}},
+    })
+}
+
+/// Generate the kube-rbac-proxy manifests, a kustomize component adding the sidecar to the
+/// controller Deployment with its RBAC and the Service of the authenticated metrics
+fn generate_metrics_proxy(
+    output_dir: &Path,
+    names: &Names,
+    namespace: Option<&str>,
+) -> anyhow::Result<()> {
+    let mut manifests = Manifests::default();
+    manifests.add(&generate_proxy_role(names), "proxy_role.yaml")?;
+    manifests.add(
+        &generate_cluster_role_binding(names, PROXY_ROLE_NAME, namespace),
+        "proxy_role_binding.yaml",
+    )?;
+    manifests.add(&generate_metrics_service(namespace), "metrics_service.yaml")?;
+    manifests.write(output_dir)?;
+
+    let patch = "manager_auth_proxy_patch.yaml";
+    write_resource(&generate_proxy_patch(namespace), patch, output_dir)?;
+    println!("✓ Generated {}/{}", output_dir.display(), patch);
+
+    let component = serde_json::json!({
+        "apiVersion": "kustomize.config.k8s.io/v1alpha1",
+        "kind": "Component",
+        "resources": manifests.filenames(),
+        "patches": [{"path": patch}],
+    });
+    write_resource(&component, "kustomization.yaml", output_dir)?;
+    println!("✓ Generated {}/kustomization.yaml", output_dir.display());
+    Ok(())
+}
+
 /// Generate all RBAC manifests
 ///
-/// Generates:
+/// Generates, for a controller watching all namespaces:
 /// - ClusterRole with CRD permissions
 /// - ClusterRole for leader election
+/// - Role for the webhook certificate in the controller's namespace
 /// - ServiceAccount
-/// - ClusterRoleBindings
-fn generate_all_rbac(output_dir: &Path, namespace: Option<&str>) -> anyhow::Result<()> {
-    // Generate ClusterRole
-    let role = generate_manager_role();
-    write_resource(&role, "role.yaml", output_dir)?;
-    println!("✓ Generated {}/role.yaml", output_dir.display());
-
-    // Generate leader election ClusterRole
-    let leader_role = generate_leader_election_role();
-    write_resource(&leader_role, "leader_election_role.yaml", output_dir)?;
-    println!(
-        "✓ Generated {}/leader_election_role.yaml",
-        output_dir.display()
-    );
+/// - ClusterRoleBindings and a RoleBinding
+///
+/// and for a controller watching a single namespace:
+/// - Role with the namespaced CRD permissions in the watched namespace
+/// - ClusterRole with the cluster-scoped permissions
+/// - Role for leader election in the controller's namespace
+/// - Role for the webhook certificate in the controller's namespace
+/// - ServiceAccount
+/// - RoleBindings and a ClusterRoleBinding
+///
+/// The admin, editor, viewer, metrics reader and notification secrets ClusterRoles are
+/// generated in both scopes.
+fn all_rbac(names: &Names, namespace: Option<&str>, scope: &Scope) -> anyhow::Result<Manifests> {
+    let mut manifests = Manifests::default();
+    let mut write = |resource: &dyn Manifest, filename: &str| manifests.add(resource, filename);
+
+    match scope {
+        Scope::Cluster => {
+            write(&generate_manager_role(names), "role.yaml")?;
+            write(
+                &generate_leader_election_role(names),
+                "leader_election_role.yaml",
+            )?;
+            write(
+                &generate_service_account(names, namespace),
+                "service_account.yaml",
+            )?;
+            write(
+                &generate_role_binding(names, namespace),
+                "role_binding.yaml",
+            )?;
+            write(
+                &generate_leader_election_role_binding(names, namespace),
+                "leader_election_role_binding.yaml",
+            )?;
+        }
+        Scope::Namespaced { watch_namespace } => {
+            let watch_namespace = watch_namespace.as_deref().or(namespace);
+            write(
+                &generate_namespaced_manager_role(names, watch_namespace),
+                "role.yaml",
+            )?;
+            write(&generate_manager_cluster_role(names), "cluster_role.yaml")?;
+            write(
+                &generate_namespaced_leader_election_role(names, namespace),
+                "leader_election_role.yaml",
+            )?;
+            write(
+                &generate_service_account(names, namespace),
+                "service_account.yaml",
+            )?;
+            write(
+                &generate_namespaced_role_binding(names, ROLE_NAME, watch_namespace, namespace),
+                "role_binding.yaml",
+            )?;
+            write(
+                &generate_cluster_role_binding(names, CLUSTER_ROLE_NAME, namespace),
+                "cluster_role_binding.yaml",
+            )?;
+            write(
+                &generate_namespaced_role_binding(
+                    names,
+                    LEADER_ELECTION_ROLE_NAME,
+                    namespace,
+                    namespace,
+                ),
+                "leader_election_role_binding.yaml",
+            )?;
+        }
+    }
 
-    // Generate ServiceAccount
-    let sa = generate_service_account(namespace);
-    write_resource(&sa, "service_account.yaml", output_dir)?;
-    println!("✓ Generated {}/service_account.yaml", output_dir.display());
-
-    // Generate ClusterRoleBinding
-    let binding = generate_role_binding(namespace);
-    write_resource(&binding, "role_binding.yaml", output_dir)?;
-    println!("✓ Generated {}/role_binding.yaml", output_dir.display());
-
-    // Generate leader election ClusterRoleBinding
-    let leader_binding = generate_leader_election_role_binding(namespace);
-    write_resource(
-        &leader_binding,
-        "leader_election_role_binding.yaml",
-        output_dir,
+    write(
+        &generate_webhook_certs_role(names, namespace),
+        "webhook_certs_role.yaml",
+    )?;
+    write(
+        &generate_namespaced_role_binding(names, WEBHOOK_CERTS_ROLE_NAME, namespace, namespace),
+        "webhook_certs_role_binding.yaml",
     )?;
-    println!(
-        "✓ Generated {}/leader_election_role_binding.yaml",
-        output_dir.display()
-    );
 
-    // Generate admin role (for cluster admins to delegate permissions)
-    let admin_role = generate_admin_role();
-    write_resource(&admin_role, "theleague_admin_role.yaml", output_dir)?;
-    println!(
-        "✓ Generated {}/theleague_admin_role.yaml",
-        output_dir.display()
-    );
+    // Generate admin, editor and viewer roles (for cluster admins to delegate permissions)
+    write(&generate_admin_role(names), "theleague_admin_role.yaml")?;
+    write(&generate_editor_role(names), "theleague_editor_role.yaml")?;
+    write(&generate_viewer_role(names), "theleague_viewer_role.yaml")?;
+    write(
+        &generate_metrics_reader_role(names),
+        "metrics_reader_role.yaml",
+    )?;
+    write(
+        &generate_notification_secrets_role(names),
+        "notification_secrets_role.yaml",
+    )?;
 
-    // Generate editor role (for cluster admins to delegate permissions)
-    let editor_role = generate_editor_role();
-    write_resource(&editor_role, "theleague_editor_role.yaml", output_dir)?;
-    println!(
-        "✓ Generated {}/theleague_editor_role.yaml",
-        output_dir.display()
-    );
+    Ok(manifests)
+}
 
-    // Generate viewer role (for cluster admins to delegate permissions)
-    let viewer_role = generate_viewer_role();
-    write_resource(&viewer_role, "theleague_viewer_role.yaml", output_dir)?;
-    println!(
-        "✓ Generated {}/theleague_viewer_role.yaml",
-        output_dir.display()
-    );
+/// Write all RBAC manifests to `output_dir` with a kustomization listing them, and the
+/// files written
+fn generate_all_rbac(
+    output_dir: &Path,
+    names: &Names,
+    namespace: Option<&str>,
+    scope: &Scope,
+) -> anyhow::Result<Vec<String>> {
+    let manifests = all_rbac(names, namespace, scope)?;
+    manifests.write(output_dir)?;
+    write_kustomization(output_dir, &manifests.filenames())?;
+    println!("✓ Generated {}/kustomization.yaml", output_dir.display());
+    Ok(manifests.filenames())
+}
 
-    Ok(())
+/// Generate the RBAC manifests of the controller
+#[derive(Parser, Debug)]
+#[command(name = "generate-rbac")]
+struct Cli {
+    /// Namespace the controller is deployed to; left to the kustomization if omitted
+    #[arg(long)]
+    namespace: Option<String>,
+
+    /// Directory to write the manifests and their kustomization to
+    #[arg(long, default_value = "config/rbac", conflicts_with = "stdout")]
+    out_dir: PathBuf,
+
+    /// ServiceAccount the controller runs as
+    #[arg(long, default_value = SERVICE_ACCOUNT_NAME)]
+    service_account: String,
+
+    /// Prefix of the names of the Roles, ClusterRoles and their bindings, e.g. to install
+    /// several controllers in one cluster
+    #[arg(long, default_value = "")]
+    role_prefix: String,
+
+    /// Print all manifests to stdout as one multi-document YAML instead of writing files
+    #[arg(long)]
+    stdout: bool,
+
+    /// Grant the controller Roles in the namespace it watches instead of ClusterRoles, for
+    /// controllers deployed with WATCH_NAMESPACE set
+    #[arg(long)]
+    namespaced: bool,
+
+    /// Namespace the controller watches with --namespaced; the controller's namespace if
+    /// omitted
+    #[arg(long, requires = "namespaced")]
+    watch_namespace: Option<String>,
+
+    /// Also generate the kube-rbac-proxy sidecar serving the metrics over HTTPS to callers
+    /// granted the metrics reader role, as a kustomize component in metrics-proxy/ next to
+    /// the output directory
+    #[arg(long, conflicts_with = "stdout")]
+    metrics_proxy: bool,
 }
 
 fn main() -> anyhow::Result<()> {
-    let output_dir = Path::new("config/rbac");
-
-    // Get namespace from environment or use default
+    let cli = Cli::parse();
+    let names = Names {
+        role_prefix: cli.role_prefix,
+        service_account: cli.service_account,
+    };
     // Following kube.rs best practice: deploy controller to its own namespace
-    let namespace = std::env::var("NAMESPACE").ok();
+    let namespace = cli.namespace.as_deref();
+    let scope = if cli.namespaced {
+        Scope::Namespaced {
+            watch_namespace: cli.watch_namespace,
+        }
+    } else {
+        Scope::Cluster
+    };
 
-    generate_all_rbac(output_dir, namespace.as_deref())?;
+    if cli.stdout {
+        print!(
+            "{}",
+            all_rbac(&names, namespace, &scope)?.multi_document_yaml()
+        );
+        return Ok(());
+    }
+
+    generate_all_rbac(&cli.out_dir, &names, namespace, &scope)?;
+    if cli.metrics_proxy {
+        let proxy_dir = cli.out_dir.with_file_name("metrics-proxy");
+        generate_metrics_proxy(&proxy_dir, &names, namespace)?;
+        println!("Enable it with: cargo run --bin generate-deploy -- --metrics-proxy");
+    }
 
     println!("\nAll RBAC manifests generated successfully!");
-    println!("Apply them with: kubectl apply -k config/rbac/");
+    println!(
+        "Apply them with: kubectl apply -k {}/",
+        cli.out_dir.display()
+    );
     println!("\nNote: These manifests follow kube.rs security best practices:");
     println!("  - Least-privilege principle");
-    println!("  - ClusterRole used because controller can watch all namespaces");
+    match scope {
+        Scope::Cluster => {
+            println!("  - ClusterRole used because controller can watch all namespaces")
+        }
+        Scope::Namespaced { .. } => {
+            println!("  - Role used because controller watches a single namespace")
+        }
+    }
     println!("  - Explicit status subresource permissions");
     println!("  - Leader election permissions for controller coordination");
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn resources(rules: &[PolicyRule]) -> Vec<&str> {
+        rules
+            .iter()
+            .flat_map(|r| r.resources.iter().flatten())
+            .map(String::as_str)
+            .collect()
+    }
+
+    #[test]
+    fn test_namespaced_mode_splits_the_manager_role() {
+        let names = Names::default();
+        let cluster_rules = generate_manager_role(&names).rules.unwrap();
+        let role = generate_namespaced_manager_role(&names, Some("leagues"));
+        let cluster_role = generate_manager_cluster_role(&names);
+        let (namespaced, cluster_scoped) = (role.rules.unwrap(), cluster_role.rules.unwrap());
+
+        assert_eq!(role.metadata.namespace.as_deref(), Some("leagues"));
+        assert_eq!(namespaced.len() + cluster_scoped.len(), cluster_rules.len());
+        assert!(resources(&namespaced).contains(&"theleagues"));
+        assert!(!resources(&namespaced).contains(&"customresourcedefinitions"));
+        assert_eq!(
+            resources(&cluster_scoped).len(),
+            CLUSTER_SCOPED_RESOURCES.len()
+        );
+
+        let binding =
+            generate_namespaced_role_binding(&names, ROLE_NAME, Some("leagues"), Some("ops"));
+        assert_eq!(binding.role_ref.kind, "Role");
+        assert_eq!(binding.metadata.namespace.as_deref(), Some("leagues"));
+        assert_eq!(
+            binding.subjects.unwrap()[0].namespace.as_deref(),
+            Some("ops")
+        );
+    }
+
+    #[test]
+    fn test_leader_election_role_follows_the_elector() {
+        let rules = generate_leader_election_role(&Names::default())
+            .rules
+            .unwrap();
+        assert_eq!(rules.len(), 1);
+        assert_eq!(
+            rules[0].resources.as_deref(),
+            Some(&[leader_election::LEASE_RESOURCE.to_string()][..])
+        );
+        assert_eq!(rules[0].verbs, leader_election::LEASE_VERBS);
+    }
+
+    #[test]
+    fn test_secrets_are_scoped_to_the_webhook_certificate() {
+        let names = Names::default();
+        let manager_rules = generate_manager_role(&names).rules.unwrap();
+        assert!(!resources(&manager_rules).contains(&"secrets"));
+
+        let role = generate_webhook_certs_role(&names, Some("ops"));
+        assert_eq!(role.metadata.namespace.as_deref(), Some("ops"));
+        let rules = role.rules.unwrap();
+        assert_eq!(
+            rules[0].resource_names.as_deref(),
+            Some(&[certs::CERT_SECRET_NAME.to_string()][..])
+        );
+        assert!(
+            rules
+                .iter()
+                .all(|r| r.resource_names.is_some() || r.verbs == ["create"])
+        );
+
+        let manifests = all_rbac(&names, Some("ops"), &Scope::Cluster).unwrap();
+        let yaml = manifests.multi_document_yaml();
+        // Generated for cluster admins to bind per namespace, never bound to the controller
+        assert_eq!(yaml.matches(NOTIFICATION_SECRETS_ROLE_NAME).count(), 1);
+    }
+
+    #[test]
+    fn test_generate_metrics_proxy_component() {
+        let temp_dir = TempDir::new().unwrap();
+        generate_metrics_proxy(temp_dir.path(), &Names::default(), Some("leagues")).unwrap();
+
+        let content = fs::read_to_string(temp_dir.path().join("kustomization.yaml")).unwrap();
+        let component: serde_yaml::Value = serde_yaml::from_str(&content).unwrap();
+        assert_eq!(component["kind"].as_str(), Some("Component"));
+        assert_eq!(component["resources"].as_sequence().unwrap().len(), 3);
+        assert_eq!(
+            component["patches"][0]["path"].as_str(),
+            Some("manager_auth_proxy_patch.yaml")
+        );
+
+        let patch = generate_proxy_patch(Some("leagues"));
+        let containers = &patch["spec"]["template"]["spec"]["containers"];
+        assert_eq!(
+            containers[0]["args"][1],
+            "--upstream=http://127.0.0.1:8081/"
+        );
+        assert_eq!(containers[1]["env"][0]["value"], "127.0.0.1:8081");
+    }
+
+    #[test]
+    fn test_flags_name_the_manifests() {
+        let cli = Cli::try_parse_from([
+            "generate-rbac",
+            "--namespace",
+            "leagues",
+            "--service-account",
+            "league-bot",
+            "--role-prefix",
+            "blue-",
+            "--stdout",
+        ])
+        .unwrap();
+        assert!(cli.stdout);
+        assert!(Cli::try_parse_from(["generate-rbac", "--stdout", "--out-dir", "out"]).is_err());
+
+        let names = Names {
+            role_prefix: cli.role_prefix,
+            service_account: cli.service_account,
+        };
+        let manifests = all_rbac(&names, cli.namespace.as_deref(), &Scope::Cluster).unwrap();
+        let binding = generate_role_binding(&names, cli.namespace.as_deref());
+        assert_eq!(binding.metadata.name.as_deref(), Some("blue-manager-role"));
+        assert_eq!(binding.role_ref.name, "blue-manager-role");
+        let subject = &binding.subjects.unwrap()[0];
+        assert_eq!(subject.name, "league-bot");
+        assert_eq!(subject.namespace.as_deref(), Some("leagues"));
+
+        let yaml = manifests.multi_document_yaml();
+        assert_eq!(yaml.matches("---\n").count(), manifests.filenames().len());
+        assert!(yaml.contains("name: blue-theleague-viewer-role"));
+        assert!(!yaml.contains(SERVICE_ACCOUNT_NAME));
+    }
+}
@@ -0,0 +1,320 @@
+//! Binary to generate the admission webhook manifests for the TheLeague controller.
+//!
+//! The webhook configurations are derived from the endpoints the webhook server serves, so the
+//! rules and paths can't drift from the router. The caBundles are left empty: the controller
+//! injects the CA of its self-signed certificate, or cert-manager the CA of a mounted one.
+//!
+//! The Service is generated in `--namespace`, the default namespace of the controller when
+//! omitted; the environment the generator runs in doesn't change the manifests.
+//!
+//! Run with: `cargo run --bin generate-webhooks`, or
+//! `cargo run --bin generate-webhooks -- --namespace leagues --stdout | kubectl apply -f -`
+
+use clap::Parser;
+use k8s_openapi::api::admissionregistration::v1::{
+    MutatingWebhook, MutatingWebhookConfiguration, RuleWithOperations, ServiceReference,
+    ValidatingWebhook, ValidatingWebhookConfiguration, WebhookClientConfig,
+};
+use k8s_openapi::api::core::v1::{Secret, Service, ServicePort, ServiceSpec};
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+use k8s_openapi::apimachinery::pkg::util::intstr::IntOrString;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use the_league::webhook::{self, AdmissionEndpoint, certs};
+
+const GROUP: &str = "bexxmodd.com";
+const VERSION: &str = "v1alpha1";
+const APP_NAME: &str = "theleague";
+
+/// Generate the admission webhook manifests of the controller
+#[derive(Parser, Debug)]
+#[command(name = "generate-webhooks")]
+struct Cli {
+    /// Namespace of the webhook Service
+    #[arg(long, default_value = webhook::DEFAULT_NAMESPACE)]
+    namespace: String,
+
+    /// Directory to write the manifests to
+    #[arg(long, default_value = "config/webhook", conflicts_with = "stdout")]
+    out_dir: PathBuf,
+
+    /// Print all manifests to stdout as one multi-document YAML instead of writing files
+    #[arg(long)]
+    stdout: bool,
+}
+
+/// Labels selecting the controller pods
+fn selector_labels() -> BTreeMap<String, String> {
+    BTreeMap::from([("app.kubernetes.io/name".to_string(), APP_NAME.to_string())])
+}
+
+/// Client config sending the reviews of `endpoint` to the webhook Service in `namespace`
+fn client_config(endpoint: &AdmissionEndpoint, namespace: &str) -> WebhookClientConfig {
+    WebhookClientConfig {
+        service: Some(ServiceReference {
+            name: webhook::WEBHOOK_SERVICE_NAME.to_string(),
+            namespace: namespace.to_string(),
+            path: Some(endpoint.path.to_string()),
+            port: Some(443),
+        }),
+        ..Default::default()
+    }
+}
+
+/// Rule matching the operations of `endpoint` on its resource
+fn rules(endpoint: &AdmissionEndpoint) -> Vec<RuleWithOperations> {
+    vec![RuleWithOperations {
+        api_groups: Some(vec![GROUP.to_string()]),
+        api_versions: Some(vec![VERSION.to_string()]),
+        operations: Some(endpoint.operations.iter().map(|o| o.to_string()).collect()),
+        resources: Some(vec![endpoint.resource.to_string()]),
+        scope: Some("Namespaced".to_string()),
+    }]
+}
+
+/// Name of the webhook of `endpoint`, prefixed with `prefix` (`v` or `m`) like kubebuilder does
+fn webhook_name(prefix: &str, endpoint: &AdmissionEndpoint) -> String {
+    format!("{}{}.{}", prefix, endpoint.kind.to_lowercase(), GROUP)
+}
+
+/// Generate the ValidatingWebhookConfiguration of the validating endpoints
+fn generate_validating_webhooks(namespace: &str) -> ValidatingWebhookConfiguration {
+    ValidatingWebhookConfiguration {
+        metadata: ObjectMeta {
+            name: Some(webhook::VALIDATING_WEBHOOK_NAME.to_string()),
+            ..Default::default()
+        },
+        webhooks: Some(
+            webhook::VALIDATING_ENDPOINTS
+                .iter()
+                .map(|endpoint| ValidatingWebhook {
+                    name: webhook_name("v", endpoint),
+                    admission_review_versions: vec!["v1".to_string()],
+                    client_config: client_config(endpoint, namespace),
+                    failure_policy: Some("Fail".to_string()),
+                    rules: Some(rules(endpoint)),
+                    side_effects: "None".to_string(),
+                    ..Default::default()
+                })
+                .collect(),
+        ),
+    }
+}
+
+/// Generate the MutatingWebhookConfiguration of the mutating endpoints
+fn generate_mutating_webhooks(namespace: &str) -> MutatingWebhookConfiguration {
+    MutatingWebhookConfiguration {
+        metadata: ObjectMeta {
+            name: Some(webhook::MUTATING_WEBHOOK_NAME.to_string()),
+            ..Default::default()
+        },
+        webhooks: Some(
+            webhook::MUTATING_ENDPOINTS
+                .iter()
+                .map(|endpoint| MutatingWebhook {
+                    name: webhook_name("m", endpoint),
+                    admission_review_versions: vec!["v1".to_string()],
+                    client_config: client_config(endpoint, namespace),
+                    failure_policy: Some("Fail".to_string()),
+                    rules: Some(rules(endpoint)),
+                    side_effects: "None".to_string(),
+                    reinvocation_policy: Some("IfNeeded".to_string()),
+                    ..Default::default()
+                })
+                .collect(),
+        ),
+    }
+}
+
+/// Generate the Service fronting the webhook server of the controller pods
+fn generate_service(namespace: &str) -> Service {
+    Service {
+        metadata: ObjectMeta {
+            name: Some(webhook::WEBHOOK_SERVICE_NAME.to_string()),
+            namespace: Some(namespace.to_string()),
+            labels: Some(selector_labels()),
+            ..Default::default()
+        },
+        spec: Some(ServiceSpec {
+            selector: Some(selector_labels()),
+            ports: Some(vec![ServicePort {
+                name: Some("webhook".to_string()),
+                port: 443,
+                protocol: Some("TCP".to_string()),
+                target_port: Some(IntOrString::Int(i32::from(webhook::WEBHOOK_PORT))),
+                ..Default::default()
+            }]),
+            ..Default::default()
+        }),
+        ..Default::default()
+    }
+}
+
+/// Generate the placeholder of the Secret holding the serving certificate. The controller
+/// fills it with its self-signed certificate on startup.
+fn generate_cert_secret(namespace: &str) -> Secret {
+    Secret {
+        metadata: ObjectMeta {
+            name: Some(certs::CERT_SECRET_NAME.to_string()),
+            namespace: Some(namespace.to_string()),
+            ..Default::default()
+        },
+        type_: Some("kubernetes.io/tls".to_string()),
+        string_data: Some(BTreeMap::from([
+            ("tls.crt".to_string(), String::new()),
+            ("tls.key".to_string(), String::new()),
+        ])),
+        ..Default::default()
+    }
+}
+
+/// All webhook manifests with the files they are written to:
+/// - ValidatingWebhookConfiguration
+/// - MutatingWebhookConfiguration
+/// - Service of the webhook server
+/// - Secret placeholder for the serving certificate
+fn all_webhooks(namespace: &str) -> anyhow::Result<Vec<(&'static str, String)>> {
+    Ok(vec![
+        (
+            "validating_webhook_configuration.yaml",
+            serde_yaml::to_string(&generate_validating_webhooks(namespace))?,
+        ),
+        (
+            "mutating_webhook_configuration.yaml",
+            serde_yaml::to_string(&generate_mutating_webhooks(namespace))?,
+        ),
+        (
+            "service.yaml",
+            serde_yaml::to_string(&generate_service(namespace))?,
+        ),
+        (
+            "secret.yaml",
+            serde_yaml::to_string(&generate_cert_secret(namespace))?,
+        ),
+    ])
+}
+
+/// The manifests as one multi-document YAML, e.g. for `kubectl apply -f -`
+fn multi_document_yaml(manifests: &[(&str, String)]) -> String {
+    let mut yaml = String::new();
+    for (_, document) in manifests {
+        yaml.push_str("---\n");
+        yaml.push_str(document);
+    }
+    yaml
+}
+
+/// Write all webhook manifests to `output_dir`
+fn generate_all_webhooks(output_dir: &Path, namespace: &str) -> anyhow::Result<()> {
+    fs::create_dir_all(output_dir)?;
+    for (filename, yaml) in all_webhooks(namespace)? {
+        fs::write(output_dir.join(filename), yaml)?;
+        println!("✓ Generated {}/{}", output_dir.display(), filename);
+    }
+    Ok(())
+}
+
+fn main() -> anyhow::Result<()> {
+    let cli = Cli::parse();
+
+    if cli.stdout {
+        print!("{}", multi_document_yaml(&all_webhooks(&cli.namespace)?));
+        return Ok(());
+    }
+
+    generate_all_webhooks(&cli.out_dir, &cli.namespace)?;
+
+    println!("\nAll webhook manifests generated successfully!");
+    println!(
+        "Apply them with: kubectl apply -k {}/",
+        cli.out_dir.display()
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_webhooks_follow_the_served_endpoints() {
+        let validating = generate_validating_webhooks("league-system");
+        let webhooks = validating.webhooks.unwrap();
+        assert_eq!(webhooks.len(), webhook::VALIDATING_ENDPOINTS.len());
+        assert_eq!(webhooks[0].name, "vgameresult.bexxmodd.com");
+        let service = webhooks[0].client_config.service.as_ref().unwrap();
+        assert_eq!(
+            service.path.as_deref(),
+            Some(webhook::VALIDATE_GAMERESULT_PATH)
+        );
+        assert_eq!(service.namespace, "league-system");
+
+        let mutating = generate_mutating_webhooks("league-system");
+        let paths: Vec<_> = mutating
+            .webhooks
+            .unwrap()
+            .iter()
+            .map(|w| {
+                w.client_config
+                    .service
+                    .as_ref()
+                    .unwrap()
+                    .path
+                    .clone()
+                    .unwrap()
+            })
+            .collect();
+        assert_eq!(
+            paths,
+            [
+                webhook::MUTATE_GAMERESULT_PATH,
+                webhook::MUTATE_STANDING_PATH,
+                webhook::MUTATE_THELEAGUE_PATH
+            ]
+        );
+    }
+
+    #[test]
+    fn test_generate_all_webhooks() {
+        let temp_dir = TempDir::new().unwrap();
+        generate_all_webhooks(temp_dir.path(), "league-system").unwrap();
+
+        let content = fs::read_to_string(temp_dir.path().join("service.yaml")).unwrap();
+        let service: serde_yaml::Value = serde_yaml::from_str(&content).unwrap();
+        assert_eq!(
+            service["metadata"]["name"].as_str(),
+            Some(webhook::WEBHOOK_SERVICE_NAME)
+        );
+        assert_eq!(
+            service["spec"]["ports"][0]["targetPort"].as_u64(),
+            Some(9443)
+        );
+        for file in [
+            "validating_webhook_configuration.yaml",
+            "mutating_webhook_configuration.yaml",
+            "secret.yaml",
+        ] {
+            assert!(temp_dir.path().join(file).exists(), "{} should exist", file);
+        }
+    }
+
+    #[test]
+    fn test_cli_rejects_unknown_arguments() {
+        let cli = Cli::try_parse_from(["generate-webhooks", "--stdout"]).unwrap();
+        assert!(cli.stdout);
+        assert_eq!(cli.namespace, webhook::DEFAULT_NAMESPACE);
+        let cli = Cli::try_parse_from(["generate-webhooks", "--namespace", "leagues"]).unwrap();
+        assert_eq!(cli.namespace, "leagues");
+        assert!(Cli::try_parse_from(["generate-webhooks", "config/webhook"]).is_err());
+        assert!(
+            Cli::try_parse_from(["generate-webhooks", "--stdout", "--out-dir", "out"]).is_err()
+        );
+
+        let yaml = multi_document_yaml(&all_webhooks("league-system").unwrap());
+        assert_eq!(yaml.matches("---\n").count(), 4);
+    }
+}
@@ -0,0 +1,193 @@
+//! Binary to install the TheLeague controller into the cluster of the current kubeconfig
+//! without kubectl: it applies the CRDs, waits until the API server established them, then
+//! applies the RBAC and, with `--deploy`, the webhooks and the Deployment of the controller.
+//!
+//! The manifests are read from the directories of `config/` in the order their kustomizations
+//! list them, so the installer applies what the generators wrote and `kubectl apply -k config/`
+//! would. Every object is server-side applied into `--namespace`, the way the kustomization of
+//! `config/` moves them. Kustomize components, such as metrics-proxy, aren't applied.
+//!
+//! An install fails before applying anything if some of the objects exist already; `--upgrade`
+//! applies over them. `--dry-run` sends every request as a server-side dry run.
+//!
+//! Run with: `cargo run --bin install`, or `cargo run --bin install -- --deploy --upgrade`
+
+use anyhow::Context;
+use clap::Parser;
+use k8s_openapi::api::core::v1::Namespace;
+use k8s_openapi::apiextensions_apiserver::pkg::apis::apiextensions::v1::CustomResourceDefinition;
+use kube::api::{Patch, PatchParams};
+use kube::runtime::wait::{await_condition, conditions};
+use kube::{Api, Client, ResourceExt};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use the_league::manifests::{CRD_DIR, DEPLOY_DIRS, Prepared, RBAC_DIR, Resolver, read_manifests};
+use the_league::webhook;
+
+/// Field manager of the objects applied by the installer
+const FIELD_MANAGER: &str = "theleague-install";
+
+/// Install the controller into the cluster
+#[derive(Parser, Debug)]
+#[command(name = "install")]
+struct Cli {
+    /// Directory of the generated manifests
+    #[arg(long, default_value = "config")]
+    config_dir: PathBuf,
+
+    /// Namespace to install the controller to
+    #[arg(long, default_value = webhook::DEFAULT_NAMESPACE)]
+    namespace: String,
+
+    /// Also apply the webhooks and the Deployment, starting the controller
+    #[arg(long)]
+    deploy: bool,
+
+    /// Apply over the objects already installed instead of failing
+    #[arg(long)]
+    upgrade: bool,
+
+    /// Send every request as a server-side dry run, persisting nothing
+    #[arg(long)]
+    dry_run: bool,
+
+    /// Seconds to wait for the CRDs to be established
+    #[arg(long, default_value_t = 60)]
+    timeout: u64,
+}
+
+/// Installer applies the manifests to the cluster of its resolver.
+struct Installer {
+    resolver: Resolver,
+    dry_run: bool,
+}
+
+impl Installer {
+    /// Server-side apply `prepared`, taking over the fields of other managers on upgrades
+    async fn apply(&self, prepared: &Prepared, namespace_exists: bool) -> anyhow::Result<()> {
+        let suffix = if self.dry_run { " (dry run)" } else { "" };
+        if self.dry_run && prepared.namespaced && !namespace_exists {
+            // A dry run doesn't create the namespace, so the API server can't admit its objects
+            println!("✓ Would apply {}{}", prepared.describe(), suffix);
+            return Ok(());
+        }
+        let mut params = PatchParams::apply(FIELD_MANAGER).force();
+        params.dry_run = self.dry_run;
+        prepared
+            .api
+            .patch(
+                &prepared.object.name_any(),
+                &params,
+                &Patch::Apply(&prepared.object),
+            )
+            .await
+            .with_context(|| format!("applying {}", prepared.describe()))?;
+        println!("✓ Applied {}{}", prepared.describe(), suffix);
+        Ok(())
+    }
+}
+
+/// Wait until the API server established the CRDs `names`, serving their resources
+async fn await_established(
+    client: &Client,
+    names: &[String],
+    timeout: Duration,
+) -> anyhow::Result<()> {
+    let crds: Api<CustomResourceDefinition> = Api::all(client.clone());
+    let established = async {
+        for name in names {
+            await_condition(crds.clone(), name, conditions::is_crd_established()).await?;
+        }
+        anyhow::Ok(())
+    };
+    tokio::time::timeout(timeout, established)
+        .await
+        .with_context(|| format!("CRDs not established within {:?}", timeout))??;
+    println!("✓ {} CRDs established", names.len());
+    Ok(())
+}
+
+async fn install(cli: &Cli) -> anyhow::Result<()> {
+    let client = Client::try_default().await?;
+    let mut installer = Installer {
+        resolver: Resolver::new(client.clone(), &cli.namespace),
+        dry_run: cli.dry_run,
+    };
+
+    let mut dirs = vec![CRD_DIR, RBAC_DIR];
+    if cli.deploy {
+        dirs.extend(DEPLOY_DIRS);
+    }
+    let mut phases = Vec::new();
+    for dir in dirs {
+        let mut prepared = Vec::new();
+        for object in read_manifests(&cli.config_dir.join(dir))? {
+            prepared.push(installer.resolver.prepare(object).await?);
+        }
+        phases.push((dir, prepared));
+    }
+
+    if !cli.upgrade {
+        let mut installed = Vec::new();
+        for prepared in phases.iter().flat_map(|(_, p)| p) {
+            if prepared
+                .api
+                .get_opt(&prepared.object.name_any())
+                .await?
+                .is_some()
+            {
+                installed.push(prepared.describe());
+            }
+        }
+        if !installed.is_empty() {
+            anyhow::bail!(
+                "already installed: {}; rerun with --upgrade to apply over them",
+                installed.join(", ")
+            );
+        }
+    }
+
+    let namespaces: Api<Namespace> = Api::all(client.clone());
+    let namespace_exists = namespaces.get_opt(&cli.namespace).await?.is_some();
+    let namespace = serde_json::from_value(serde_json::json!({
+        "apiVersion": "v1",
+        "kind": "Namespace",
+        "metadata": {"name": cli.namespace},
+    }))?;
+    let namespace = installer.resolver.prepare(namespace).await?;
+
+    for (dir, prepared) in &phases {
+        if *dir == RBAC_DIR {
+            installer.apply(&namespace, namespace_exists).await?;
+        }
+        for object in prepared {
+            installer.apply(object, namespace_exists).await?;
+        }
+        if *dir == CRD_DIR && !cli.dry_run {
+            let names: Vec<String> = prepared.iter().map(|p| p.object.name_any()).collect();
+            await_established(&client, &names, Duration::from_secs(cli.timeout)).await?;
+        }
+    }
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let cli = Cli::parse();
+
+    install(&cli).await?;
+
+    if cli.dry_run {
+        println!("\nDry run complete, nothing was changed.");
+    } else if cli.deploy {
+        println!(
+            "\nThe controller is installed in namespace {}.",
+            cli.namespace
+        );
+    } else {
+        println!("\nCRDs and RBAC installed; rerun with --deploy to start the controller.");
+    }
+
+    Ok(())
+}
@@ -0,0 +1,906 @@
+//! Command line tool for working with TheLeague resources.
+//!
+//! It also runs as the kubectl plugin `kubectl league` when installed as `kubectl-league` on
+//! the PATH, e.g. with `make install-plugin`, and selects the cluster like kubectl does: from
+//! `--kubeconfig`, `$KUBECONFIG` or `~/.kube/config`, with `--context`, `--cluster` and
+//! `--user` overriding the current context.
+//!
+//! Run with: `cargo run --bin leaguectl -- <command>`, or `kubectl league <command>`
+
+use anyhow::{Context, bail};
+use clap::{CommandFactory, FromArgMatches, Parser, Subcommand, ValueEnum};
+use futures::{StreamExt, TryStreamExt};
+use k8s_openapi::chrono::{DateTime, Utc};
+use kube::api::{ListParams, ObjectMeta, Patch, PatchParams, PostParams};
+use kube::config::{KubeConfigOptions, Kubeconfig};
+use kube::runtime::watcher;
+use kube::{Api, Client, Config, ResourceExt};
+use std::ffi::OsStr;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use the_league::api::v1alpha1::game_result_types::{
+    DecidedBy, GameResultSpec, PlayerStat, ShootoutScore,
+};
+use the_league::api::v1alpha1::match_types::MatchPhase;
+use the_league::bundle::{self, LeagueBundle};
+use the_league::controller::{matches, teams, validation_policy};
+use the_league::create::{self, Prompter};
+use the_league::http::standings_api::{self, LeagueStandings};
+use the_league::webhook::normalize_team_name;
+use the_league::{GameResult, Match, Standing, TheLeague};
+use the_league::{import, schedule, standings, validation};
+
+#[derive(Parser)]
+#[command(name = "leaguectl", about = "Manage TheLeague resources")]
+struct Cli {
+    /// Namespace of the league; defaults to the namespace of the current kubeconfig context
+    #[arg(short, long, global = true)]
+    namespace: Option<String>,
+
+    /// Path to the kubeconfig file; defaults to $KUBECONFIG or ~/.kube/config
+    #[arg(long, global = true)]
+    kubeconfig: Option<PathBuf>,
+
+    /// Name of the kubeconfig context to use instead of the current one
+    #[arg(long, global = true)]
+    context: Option<String>,
+
+    /// Name of the kubeconfig cluster to use
+    #[arg(long, global = true)]
+    cluster: Option<String>,
+
+    /// Name of the kubeconfig user to use
+    #[arg(long, global = true)]
+    user: Option<String>,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+/// Name of the executable kubectl runs for `kubectl league`
+const PLUGIN_BINARY: &str = "kubectl-league";
+
+/// Name the CLI is invoked as, from the path `arg0` it was run with
+fn program_name(arg0: Option<&OsStr>) -> &'static str {
+    match arg0.map(Path::new).and_then(Path::file_stem) {
+        Some(stem) if stem == PLUGIN_BINARY => "kubectl league",
+        _ => "leaguectl",
+    }
+}
+
+impl Cli {
+    /// Parse the arguments, with usage and help naming the CLI as it was invoked
+    fn parse_invoked() -> Self {
+        let arg0 = std::env::args_os().next();
+        let matches = Cli::command()
+            .bin_name(program_name(arg0.as_deref()))
+            .get_matches();
+        Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit())
+    }
+
+    /// Client of the cluster selected by the kubeconfig flags, in-cluster or from the current
+    /// context of the default kubeconfig without them
+    async fn client(&self) -> anyhow::Result<Client> {
+        let options = KubeConfigOptions {
+            context: self.context.clone(),
+            cluster: self.cluster.clone(),
+            user: self.user.clone(),
+        };
+        let overridden =
+            options.context.is_some() || options.cluster.is_some() || options.user.is_some();
+        let config = match &self.kubeconfig {
+            Some(path) => {
+                let kubeconfig = Kubeconfig::read_from(path)
+                    .with_context(|| format!("Unable to read kubeconfig {}", path.display()))?;
+                Config::from_custom_kubeconfig(kubeconfig, &options).await?
+            }
+            None if overridden => Config::from_kubeconfig(&options).await?,
+            None => Config::infer().await?,
+        };
+        Ok(Client::try_from(config)?)
+    }
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Create GameResults from a CSV file of `round,home,away,home_score,away_score,time` rows
+    ImportResults {
+        /// Name of the TheLeague the results belong to
+        #[arg(short, long)]
+        league: String,
+
+        /// Validate and send the results with server-side dry-run only
+        #[arg(long)]
+        dry_run: bool,
+
+        /// CSV file to import
+        file: PathBuf,
+    },
+
+    /// Record the result of a league game, e.g. `result -l premier -r 3 --home Tigers
+    /// --away Sharks --score 2-1`
+    Result {
+        /// Name of the TheLeague the game belongs to
+        #[arg(short, long)]
+        league: String,
+
+        /// Round of the league schedule the game belongs to
+        #[arg(short, long)]
+        round: u32,
+
+        /// Home team
+        #[arg(long)]
+        home: String,
+
+        /// Away team
+        #[arg(long)]
+        away: String,
+
+        /// Score of the game as `home-away`, e.g. 2-1
+        #[arg(long, value_parser = import::parse_score)]
+        score: (u32, u32),
+
+        /// The game was decided in overtime
+        #[arg(long, conflicts_with = "shootout")]
+        overtime: bool,
+
+        /// Score of the shootout deciding a level game, as `home-away`
+        #[arg(long, value_parser = import::parse_score)]
+        shootout: Option<(u32, u32)>,
+
+        /// Time the game was played, in RFC3339; defaults to now
+        #[arg(long, value_parser = import::parse_time)]
+        time: Option<DateTime<Utc>>,
+
+        /// Scorer of a goal as `Team:First Last[:minute]`, repeated per goal
+        #[arg(long = "scorer", value_parser = import::parse_player_stat)]
+        scorers: Vec<PlayerStat>,
+
+        /// Ground the game was played at
+        #[arg(long)]
+        venue: Option<String>,
+
+        /// Number of spectators
+        #[arg(long)]
+        attendance: Option<u32>,
+
+        /// Validate and send the result with server-side dry-run only
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Print the table of a league from the statuses of its Standings
+    Standings {
+        /// Name of the TheLeague
+        league: String,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value_t = Output::Table)]
+        output: Output,
+
+        /// Print the table again whenever the Standings change
+        #[arg(short, long)]
+        watch: bool,
+    },
+
+    /// Print the fixtures of a league grouped by round, with their results once played
+    Schedule {
+        /// Name of the TheLeague
+        league: String,
+
+        /// Only print the fixtures of this team
+        #[arg(short, long)]
+        team: Option<String>,
+    },
+
+    /// Export a league with its Standings, fixtures and results to a JSON bundle
+    Export {
+        /// Name of the TheLeague
+        league: String,
+
+        /// File to write the bundle to; defaults to standard output
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Import a league from a JSON bundle written by `export`
+    Import {
+        /// Validate and send the objects with server-side dry-run only
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Bundle to import
+        file: PathBuf,
+    },
+
+    /// Create a resource
+    Create {
+        #[command(subcommand)]
+        resource: CreateCommand,
+    },
+}
+
+/// Output formats of the tables
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Output {
+    Table,
+    Json,
+    Yaml,
+    Csv,
+}
+
+/// Time the Standings must stay unchanged before a watched table is printed again, so a result
+/// updating every Standing of the league prints the table once
+const WATCH_SETTLE: Duration = Duration::from_millis(500);
+
+#[derive(Subcommand)]
+enum CreateCommand {
+    /// Create a TheLeague, prompting for its teams, players, matchups and points policy
+    League {
+        /// Name of the TheLeague
+        name: String,
+
+        /// Read the league from a YAML or JSON file, a TheLeague manifest or only its spec,
+        /// instead of prompting for it
+        #[arg(short, long)]
+        file: Option<PathBuf>,
+
+        /// Validate and send the league with server-side dry-run only
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let cli = Cli::parse_invoked();
+    let client = cli.client().await?;
+    let namespace = cli
+        .namespace
+        .unwrap_or_else(|| client.default_namespace().to_string());
+
+    match cli.command {
+        Command::ImportResults {
+            league,
+            dry_run,
+            file,
+        } => import_results(&client, &namespace, &league, dry_run, &file).await,
+        Command::Result {
+            league,
+            round,
+            home,
+            away,
+            score,
+            overtime,
+            shootout,
+            time,
+            scorers,
+            venue,
+            attendance,
+            dry_run,
+        } => {
+            let decided_by = match (overtime, shootout) {
+                (_, Some(_)) => DecidedBy::Shootout,
+                (true, None) => DecidedBy::Overtime,
+                (false, None) => DecidedBy::Regulation,
+            };
+            let shootout = shootout.map(|(home, away)| ShootoutScore { home, away });
+            let teams = [normalize_team_name(&home), normalize_team_name(&away)];
+            let mut spec = import::result_spec(
+                &league,
+                round,
+                teams,
+                import::outcome(score.0, score.1, decided_by, shootout),
+                time.unwrap_or_else(Utc::now),
+            );
+            spec.scorers = scorers;
+            spec.venue = venue;
+            spec.attendance = attendance;
+            record_result(&client, &namespace, spec, dry_run).await
+        }
+        Command::Standings {
+            league,
+            output,
+            watch,
+        } => print_standings(&client, &namespace, &league, output, watch).await,
+        Command::Schedule { league, team } => {
+            print_schedule(&client, &namespace, &league, team.as_deref()).await
+        }
+        Command::Export { league, output } => {
+            export_league(&client, &namespace, &league, output.as_deref()).await
+        }
+        Command::Import { dry_run, file } => {
+            import_league(&client, &namespace, &file, dry_run).await
+        }
+        Command::Create {
+            resource:
+                CreateCommand::League {
+                    name,
+                    file,
+                    dry_run,
+                },
+        } => create_league(&client, &namespace, &name, file.as_deref(), dry_run).await,
+    }
+}
+
+/// The table of the league `name` from the statuses of its Standings
+async fn standings_table(
+    client: &Client,
+    namespace: &str,
+    name: &str,
+) -> anyhow::Result<LeagueStandings> {
+    let league_api: Api<TheLeague> = Api::namespaced(client.clone(), namespace);
+    let Some(league) = league_api.get_opt(name).await? else {
+        bail!("TheLeague '{}/{}' not found", namespace, name);
+    };
+    let standing_api: Api<Standing> = Api::namespaced(client.clone(), namespace);
+    let standings = standing_api.list(&ListParams::default()).await?.items;
+    let result_api: Api<GameResult> = Api::namespaced(client.clone(), namespace);
+    let results: Vec<Arc<GameResult>> = result_api
+        .list(&ListParams::default())
+        .await?
+        .items
+        .into_iter()
+        .map(Arc::new)
+        .collect();
+    Ok(standings_api::standings_from_statuses(
+        &league, &standings, &results,
+    ))
+}
+
+/// Render `standings` as aligned text, one team per line
+fn render_standings(standings: &LeagueStandings) -> String {
+    let width = standings
+        .table
+        .iter()
+        .map(|r| r.team.chars().count())
+        .max()
+        .unwrap_or(0)
+        .max("Team".len());
+    let mut out = format!(
+        "{:>4}  {:<width$}  {:>3} {:>3} {:>3} {:>3} {:>4} {:>4}  {}\n",
+        "Rank", "Team", "P", "W", "D", "L", "GD", "Pts", "Form"
+    );
+    for r in &standings.table {
+        out.push_str(&format!(
+            "{:>4}  {:<width$}  {:>3} {:>3} {:>3} {:>3} {:>4} {:>4}  {}\n",
+            r.rank,
+            r.team,
+            r.played,
+            r.wins,
+            r.draws,
+            r.losses,
+            format!("{:+}", r.goal_difference),
+            r.points,
+            r.form
+        ));
+    }
+    out
+}
+
+/// Render `standings` as CSV with a header line
+fn standings_csv(standings: &LeagueStandings) -> String {
+    let mut out = String::from(
+        "rank,team,played,wins,draws,losses,goalsFor,goalsAgainst,goalDifference,points,form\n",
+    );
+    for r in &standings.table {
+        out.push_str(&format!(
+            "{},{},{},{},{},{},{},{},{},{},{}\n",
+            r.rank,
+            r.team,
+            r.played,
+            r.wins,
+            r.draws,
+            r.losses,
+            r.goals_for,
+            r.goals_against,
+            r.goal_difference,
+            r.points,
+            r.form
+        ));
+    }
+    out
+}
+
+fn render(standings: &LeagueStandings, output: Output) -> anyhow::Result<String> {
+    Ok(match output {
+        Output::Table => render_standings(standings),
+        Output::Json => serde_json::to_string_pretty(standings)? + "\n",
+        Output::Yaml => serde_yaml::to_string(standings)?,
+        Output::Csv => standings_csv(standings),
+    })
+}
+
+async fn print_standings(
+    client: &Client,
+    namespace: &str,
+    name: &str,
+    output: Output,
+    watch: bool,
+) -> anyhow::Result<()> {
+    if !watch {
+        let standings = standings_table(client, namespace, name).await?;
+        print!("{}", render(&standings, output)?);
+        return Ok(());
+    }
+
+    // The initial list of the watcher prints the table a first time
+    let standing_api: Api<Standing> = Api::namespaced(client.clone(), namespace);
+    let mut events = watcher(standing_api, watcher::Config::default()).boxed();
+    while events.try_next().await?.is_some() {
+        while let Ok(event) = tokio::time::timeout(WATCH_SETTLE, events.try_next()).await {
+            if event?.is_none() {
+                return Ok(());
+            }
+        }
+        let standings = standings_table(client, namespace, name).await?;
+        match output {
+            // Redraw the table in place
+            Output::Table => print!("\x1b[2J\x1b[H{}", render_standings(&standings)),
+            Output::Yaml => print!("---\n{}", render(&standings, output)?),
+            Output::Json | Output::Csv => print!("{}", render(&standings, output)?),
+        }
+    }
+    Ok(())
+}
+
+/// Render the `matches` grouped by round, with the score of the `results` recording them,
+/// only keeping the fixtures of `team` if given
+fn render_schedule(matches: &[Match], results: &[GameResult], team: Option<&str>) -> String {
+    let mut fixtures: Vec<&Match> = matches
+        .iter()
+        .filter(|m| team.is_none_or(|team| m.spec.home == team || m.spec.away == team))
+        .collect();
+    let kickoff = |m: &Match| {
+        m.status
+            .as_ref()
+            .and_then(|s| s.kickoff.clone())
+            .or_else(|| m.spec.kickoff.clone())
+            .map(|k| k.0)
+    };
+    fixtures.sort_by(|a, b| {
+        (a.spec.round, kickoff(a), &a.spec.home).cmp(&(b.spec.round, kickoff(b), &b.spec.home))
+    });
+    let width = fixtures
+        .iter()
+        .flat_map(|m| [m.spec.home.chars().count(), m.spec.away.chars().count()])
+        .max()
+        .unwrap_or(0);
+
+    let mut out = String::new();
+    let mut round = None;
+    for m in fixtures {
+        if round != Some(m.spec.round) {
+            if round.is_some() {
+                out.push('\n');
+            }
+            out.push_str(&format!("Round {}\n", m.spec.round));
+            round = Some(m.spec.round);
+        }
+        let status = m.status.clone().unwrap_or_default();
+        let result = status
+            .result
+            .as_ref()
+            .and_then(|name| results.iter().find(|r| &r.name_any() == name));
+        let score = match (status.phase, result) {
+            (MatchPhase::Played, Some(result)) => {
+                let (home, away) = standings::outcome_scores(&result.spec.result).unwrap_or((0, 0));
+                // The result may list the teams the other way around
+                if result.spec.teams[0] == m.spec.home {
+                    format!("{}-{}", home, away)
+                } else {
+                    format!("{}-{}", away, home)
+                }
+            }
+            (MatchPhase::Postponed, _) => "P-P".to_string(),
+            _ => "vs".to_string(),
+        };
+        let when = kickoff(m).map_or("TBD".to_string(), |k| {
+            k.format("%Y-%m-%d %H:%M").to_string()
+        });
+        let venue = status
+            .venue
+            .or_else(|| result.and_then(|r| r.spec.venue.clone()))
+            .unwrap_or_default();
+        let line = format!(
+            "  {:<16}  {:>width$}  {:^5}  {:<width$}  {}",
+            when, m.spec.home, score, m.spec.away, venue
+        );
+        out.push_str(line.trim_end());
+        out.push('\n');
+    }
+    out
+}
+
+async fn print_schedule(
+    client: &Client,
+    namespace: &str,
+    name: &str,
+    team: Option<&str>,
+) -> anyhow::Result<()> {
+    let league_api: Api<TheLeague> = Api::namespaced(client.clone(), namespace);
+    let Some(league) = league_api.get_opt(name).await? else {
+        bail!("TheLeague '{}/{}' not found", namespace, name);
+    };
+    let team = team.map(normalize_team_name);
+    if let Some(team) = &team
+        && !league.spec.teams.iter().any(|t| &t.name == team)
+    {
+        bail!("team '{}' is not registered in league '{}'", team, name);
+    }
+
+    let match_api: Api<Match> = Api::namespaced(client.clone(), namespace);
+    let season = league.status.as_ref().and_then(|s| s.current_season);
+    let mut matches = matches::league_matches(&match_api, name).await?;
+    // Leagues running in seasons only show the fixtures of the current one
+    matches.retain(|m| season.is_none() || m.spec.season == season);
+    let result_api: Api<GameResult> = Api::namespaced(client.clone(), namespace);
+    let results = result_api.list(&ListParams::default()).await?.items;
+
+    if matches.is_empty() {
+        println!("No fixtures scheduled in league '{}/{}'", namespace, name);
+        return Ok(());
+    }
+    print!("{}", render_schedule(&matches, &results, team.as_deref()));
+    Ok(())
+}
+
+async fn record_result(
+    client: &Client,
+    namespace: &str,
+    spec: GameResultSpec,
+    dry_run: bool,
+) -> anyhow::Result<()> {
+    let league_api: Api<TheLeague> = Api::namespaced(client.clone(), namespace);
+    let Some(league) = league_api.get_opt(&spec.league_name).await? else {
+        bail!("TheLeague '{}/{}' not found", namespace, spec.league_name);
+    };
+    let (league_spec, missing) = teams::resolve_teams(client, &league).await?;
+    if !missing.is_empty() {
+        eprintln!(
+            "warning: Team(s) {} not found, their rosters are unknown",
+            missing.join(", ")
+        );
+    }
+
+    let violations = validation::validate_game_result(&spec, &league_spec);
+    for violation in &violations {
+        eprintln!("error: {}: {}", violation.reason, violation.message);
+    }
+    if !violations.is_empty() {
+        bail!("the result is invalid");
+    }
+
+    let [home, away] = &spec.teams;
+    let name = schedule::result_name(&spec.league_name, spec.round_number, home, away);
+    let result = GameResult::new(&name, spec);
+    let api: Api<GameResult> = Api::namespaced(client.clone(), namespace);
+    let mut params = PatchParams::apply(import::IMPORT_FIELD_MANAGER);
+    params.dry_run = dry_run;
+    api.patch(&name, &params, &Patch::Apply(&result))
+        .await
+        .with_context(|| format!("Unable to apply GameResult '{}/{}'", namespace, name))?;
+    println!(
+        "{} GameResult '{}/{}'",
+        if dry_run { "Validated" } else { "Recorded" },
+        namespace,
+        name
+    );
+    Ok(())
+}
+
+async fn create_league(
+    client: &Client,
+    namespace: &str,
+    name: &str,
+    file: Option<&Path>,
+    dry_run: bool,
+) -> anyhow::Result<()> {
+    let mut prompter = Prompter::new(io::stdin().lock(), io::stdout());
+    let spec = match file {
+        Some(file) => {
+            let input = std::fs::read_to_string(file)
+                .with_context(|| format!("Unable to read {}", file.display()))?;
+            create::parse_league(&input)
+                .with_context(|| format!("Unable to parse {}", file.display()))?
+        }
+        None => create::prompt_league(&mut prompter)?,
+    };
+
+    let policy = validation_policy::load(client).await;
+    let (violations, warnings) =
+        validation::validate_league(&spec, &policy, Utc::now().date_naive());
+    for warning in &warnings {
+        eprintln!("warning: {}: {}", warning.reason, warning.message);
+    }
+    for violation in &violations {
+        eprintln!("error: {}: {}", violation.reason, violation.message);
+    }
+    if !violations.is_empty() {
+        bail!("league '{}/{}' is invalid", namespace, name);
+    }
+
+    let league = TheLeague {
+        metadata: ObjectMeta {
+            name: Some(name.to_string()),
+            namespace: Some(namespace.to_string()),
+            ..Default::default()
+        },
+        spec,
+        status: None,
+    };
+    if file.is_none() {
+        println!("\n{}", serde_yaml::to_string(&league)?);
+        let question = format!("Create league '{}/{}'?", namespace, name);
+        if !prompter.confirm(&question, true)? {
+            bail!("league '{}/{}' not created", namespace, name);
+        }
+    }
+
+    let api: Api<TheLeague> = Api::namespaced(client.clone(), namespace);
+    let params = PostParams {
+        dry_run,
+        field_manager: Some(import::IMPORT_FIELD_MANAGER.to_string()),
+    };
+    match api.create(&params, &league).await {
+        Ok(_) => {}
+        Err(kube::Error::Api(e)) if e.code == 409 => {
+            bail!("league '{}/{}' already exists", namespace, name)
+        }
+        Err(e) => return Err(e).context("Unable to create the league"),
+    }
+    println!(
+        "{} league '{}/{}' with {} team(s)",
+        if dry_run { "Validated" } else { "Created" },
+        namespace,
+        name,
+        league.spec.teams.len()
+    );
+    Ok(())
+}
+
+async fn export_league(
+    client: &Client,
+    namespace: &str,
+    name: &str,
+    output: Option<&Path>,
+) -> anyhow::Result<()> {
+    let Some(bundle) = bundle::export_league(client, namespace, name).await? else {
+        bail!("TheLeague '{}/{}' not found", namespace, name);
+    };
+    let json = serde_json::to_string_pretty(&bundle)?;
+    let Some(output) = output else {
+        println!("{}", json);
+        return Ok(());
+    };
+    std::fs::write(output, json + "\n")
+        .with_context(|| format!("Unable to write {}", output.display()))?;
+    println!(
+        "Exported league '{}/{}' with {} Standing(s), {} Match(es) and {} GameResult(s) to {}",
+        namespace,
+        name,
+        bundle.standings.len(),
+        bundle.matches.len(),
+        bundle.results.len(),
+        output.display()
+    );
+    Ok(())
+}
+
+async fn import_league(
+    client: &Client,
+    namespace: &str,
+    file: &Path,
+    dry_run: bool,
+) -> anyhow::Result<()> {
+    let content = std::fs::read_to_string(file)
+        .with_context(|| format!("Unable to read {}", file.display()))?;
+    let bundle =
+        LeagueBundle::parse(&content).map_err(|e| anyhow::anyhow!("{}: {}", file.display(), e))?;
+    let name = bundle.league.name_any();
+    let summary = bundle::import_league(client, namespace, &bundle, dry_run)
+        .await
+        .with_context(|| format!("Unable to import league '{}/{}'", namespace, name))?;
+    println!(
+        "{} league '{}/{}' with {} Standing(s), {} Match(es) and {} GameResult(s)",
+        if dry_run { "Validated" } else { "Imported" },
+        namespace,
+        name,
+        summary.standings,
+        summary.matches,
+        summary.results
+    );
+    Ok(())
+}
+
+async fn import_results(
+    client: &Client,
+    namespace: &str,
+    league_name: &str,
+    dry_run: bool,
+    file: &PathBuf,
+) -> anyhow::Result<()> {
+    let input = std::fs::read_to_string(file)
+        .with_context(|| format!("Unable to read {}", file.display()))?;
+
+    let league_api: Api<TheLeague> = Api::namespaced(client.clone(), namespace);
+    let league = league_api.get_opt(league_name).await?;
+    if league.is_none() {
+        eprintln!(
+            "warning: TheLeague '{}/{}' not found, only checking the rows themselves",
+            namespace, league_name
+        );
+    }
+
+    let (rows, mut errors) =
+        import::parse_results_csv(&input, league_name, league.as_ref().map(|l| &l.spec));
+    errors.extend(import::apply_results(client, namespace, &rows, dry_run).await);
+    errors.sort_by_key(|e| e.line);
+
+    for error in &errors {
+        eprintln!("{}: {}", file.display(), error);
+    }
+    let failed_lines: Vec<usize> = errors.iter().map(|e| e.line).collect();
+    let imported = rows
+        .iter()
+        .filter(|r| !failed_lines.contains(&r.line))
+        .count();
+    println!(
+        "{} {} GameResult(s) into league '{}/{}', {} row(s) failed",
+        if dry_run { "Validated" } else { "Imported" },
+        imported,
+        namespace,
+        league_name,
+        errors.len()
+    );
+
+    if !errors.is_empty() {
+        bail!("{} row(s) could not be imported", errors.len());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use k8s_openapi::apimachinery::pkg::apis::meta::v1::Time;
+    use the_league::api::v1alpha1::match_types::{MatchSpec, MatchStatus};
+    use the_league::http::standings_api::StandingsRow;
+
+    fn standings() -> LeagueStandings {
+        let row = |rank: u32, team: &str, wins: u32, goals: (u32, u32), form: &str| StandingsRow {
+            rank,
+            team: team.to_string(),
+            played: 2,
+            wins,
+            draws: 2 - wins,
+            losses: 0,
+            goals_for: goals.0,
+            goals_against: goals.1,
+            goal_difference: i64::from(goals.0) - i64::from(goals.1),
+            points: wins * 3 + 2 - wins,
+            form: form.to_string(),
+        };
+        LeagueStandings {
+            namespace: "default".to_string(),
+            name: "premier".to_string(),
+            season: None,
+            table: vec![
+                row(1, "Red Tigers", 1, (3, 1), "DW"),
+                row(2, "Sharks", 0, (1, 3), "DD"),
+            ],
+            continue_token: None,
+        }
+    }
+
+    #[test]
+    fn test_render_standings_aligns_the_columns() {
+        let rendered = render_standings(&standings());
+        let lines: Vec<&str> = rendered.lines().collect();
+        assert_eq!(
+            lines,
+            [
+                "Rank  Team          P   W   D   L   GD  Pts  Form",
+                "   1  Red Tigers    2   1   1   0   +2    4  DW",
+                "   2  Sharks        2   0   2   0   -2    2  DD",
+            ]
+        );
+
+        let csv = standings_csv(&standings());
+        assert_eq!(csv.lines().nth(2), Some("2,Sharks,2,0,2,0,1,3,-2,2,DD"));
+    }
+
+    #[test]
+    fn test_render_schedule_groups_the_fixtures_by_round() {
+        let time = |day: u32| {
+            DateTime::parse_from_rfc3339(&format!("2025-03-{:02}T15:00:00Z", day))
+                .unwrap()
+                .with_timezone(&Utc)
+        };
+        let fixture = |round: u32, home: &str, away: &str, day: u32, status: MatchStatus| {
+            let mut m = Match::new(
+                &format!("premier-{}-{}", round, home.to_lowercase()),
+                MatchSpec {
+                    league_name: "premier".to_string(),
+                    season: None,
+                    round,
+                    home: home.to_string(),
+                    away: away.to_string(),
+                    kickoff: Some(Time(time(day))),
+                },
+            );
+            m.status = Some(status);
+            m
+        };
+        let played = MatchStatus {
+            phase: MatchPhase::Played,
+            result: Some("round-1".to_string()),
+            venue: Some("Arena".to_string()),
+            ..Default::default()
+        };
+        let postponed = MatchStatus {
+            phase: MatchPhase::Postponed,
+            ..Default::default()
+        };
+        let matches = [
+            fixture(2, "Sharks", "Tigers", 8, MatchStatus::default()),
+            fixture(1, "Eagles", "Bears", 1, postponed),
+            fixture(1, "Tigers", "Sharks", 1, played),
+        ];
+        // The result lists the teams the other way around
+        let spec = import::result_spec(
+            "premier",
+            1,
+            ["Sharks".to_string(), "Tigers".to_string()],
+            import::outcome(1, 2, DecidedBy::Regulation, None),
+            time(1),
+        );
+        let results = [GameResult::new("round-1", spec)];
+
+        let rendered = render_schedule(&matches, &results, None);
+        let lines: Vec<&str> = rendered.lines().collect();
+        assert_eq!(
+            lines,
+            [
+                "Round 1",
+                "  2025-03-01 15:00  Eagles   P-P   Bears",
+                "  2025-03-01 15:00  Tigers   2-1   Sharks  Arena",
+                "",
+                "Round 2",
+                "  2025-03-08 15:00  Sharks   vs    Tigers",
+            ]
+        );
+
+        let tigers = render_schedule(&matches, &results, Some("Tigers"));
+        assert!(!tigers.contains("Eagles"));
+    }
+
+    #[test]
+    fn test_runs_as_a_kubectl_plugin() {
+        let name = |arg0: &str| program_name(Some(OsStr::new(arg0)));
+        assert_eq!(name("/usr/local/bin/kubectl-league"), "kubectl league");
+        assert_eq!(name("kubectl-league.exe"), "kubectl league");
+        assert_eq!(name("target/debug/leaguectl"), "leaguectl");
+        assert_eq!(program_name(None), "leaguectl");
+
+        // kubectl places its flags before or after the command alike
+        let cli = Cli::try_parse_from([
+            "kubectl-league",
+            "--context",
+            "staging",
+            "standings",
+            "premier",
+            "-n",
+            "leagues",
+            "--kubeconfig",
+            "/tmp/config",
+        ])
+        .unwrap();
+        assert_eq!(cli.context.as_deref(), Some("staging"));
+        assert_eq!(cli.namespace.as_deref(), Some("leagues"));
+        assert_eq!(cli.kubeconfig, Some(PathBuf::from("/tmp/config")));
+        assert!(matches!(cli.command, Command::Standings { .. }));
+    }
+}
@@ -0,0 +1,351 @@
+//! Binary to simulate the rest of a league: it generates a random GameResult for every
+//! fixture of the league schedule still to be played and applies them, to load-test the
+//! standings controller or demo the operator with a league that fills its table.
+//!
+//! Scores are drawn per team from a Poisson distribution around `--home-mean` and
+//! `--away-mean` goals, or uniformly up to `--max-goals` with `--distribution uniform`, and
+//! capped at `--max-goals`. A run is reproducible: the seed is printed and `--seed` replays it.
+//! Pending results pre-populated by the controller are overwritten, other fixtures get a new
+//! GameResult named like the pre-populated ones.
+//!
+//! Run with: `cargo run --bin simulate -- premier`, or
+//! `cargo run --bin simulate -- premier --rounds 2 --seed 42 --dry-run`
+
+use anyhow::bail;
+use clap::{Parser, ValueEnum};
+use futures::{StreamExt, stream};
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::Time;
+use k8s_openapi::chrono::{DateTime, Duration, Utc};
+use kube::api::{ListParams, Patch, PatchParams};
+use kube::{Api, Client, ResourceExt};
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use the_league::api::v1alpha1::game_result_types::DecidedBy;
+use the_league::http::standings_api;
+use the_league::import::{self, IMPORT_BATCH_SIZE};
+use the_league::schedule::{self, Fixture};
+use the_league::{GameResult, TheLeague};
+
+/// Field manager of the simulated GameResults
+const FIELD_MANAGER: &str = "theleague-simulate";
+
+/// Simulate the remaining fixtures of a league
+#[derive(Parser, Debug)]
+#[command(name = "simulate")]
+struct Cli {
+    /// Name of the TheLeague
+    league: String,
+
+    /// Namespace of the league; defaults to the namespace of the current kubeconfig context
+    #[arg(short, long)]
+    namespace: Option<String>,
+
+    /// Seed of the random scores; a random one is picked and printed by default
+    #[arg(long)]
+    seed: Option<u64>,
+
+    /// Distribution the goals of each team are drawn from
+    #[arg(long, value_enum, default_value_t = Distribution::Poisson)]
+    distribution: Distribution,
+
+    /// Mean goals of the home team, for the Poisson distribution
+    #[arg(long, default_value_t = 1.5)]
+    home_mean: f64,
+
+    /// Mean goals of the away team, for the Poisson distribution
+    #[arg(long, default_value_t = 1.1)]
+    away_mean: f64,
+
+    /// Most goals a team scores in a game
+    #[arg(long, default_value_t = 6)]
+    max_goals: u32,
+
+    /// Only simulate the next ROUNDS rounds with fixtures still to be played
+    #[arg(long)]
+    rounds: Option<usize>,
+
+    /// Send the results with server-side dry-run only
+    #[arg(long)]
+    dry_run: bool,
+}
+
+/// Distributions of the goals of a team in a game
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum Distribution {
+    Poisson,
+    Uniform,
+}
+
+/// Scoring draws the goals of the teams of a game.
+struct Scoring {
+    distribution: Distribution,
+    home_mean: f64,
+    away_mean: f64,
+    max_goals: u32,
+}
+
+impl Scoring {
+    /// The score of a game, as (home, away)
+    fn score(&self, rng: &mut Rng) -> (u32, u32) {
+        let mut goals = |mean: f64| match self.distribution {
+            Distribution::Poisson => rng.poisson(mean).min(self.max_goals),
+            Distribution::Uniform => rng.below(self.max_goals + 1),
+        };
+        (goals(self.home_mean), goals(self.away_mean))
+    }
+}
+
+/// SplitMix64 generator: small and stable across releases, so a seed always simulates the
+/// same scores.
+struct Rng(u64);
+
+impl Rng {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform number in `[0, 1)`
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Uniform number in `0..n`
+    fn below(&mut self, n: u32) -> u32 {
+        (self.next_f64() * f64::from(n)) as u32
+    }
+
+    /// Poisson distributed number around `mean`, with Knuth's multiplication method
+    fn poisson(&mut self, mean: f64) -> u32 {
+        let limit = (-mean).exp();
+        let mut product = self.next_f64();
+        let mut count = 0;
+        while product > limit {
+            product *= self.next_f64();
+            count += 1;
+        }
+        count
+    }
+}
+
+/// A random GameResult of `league_name` for each of `fixtures`, in their order. Fixtures are
+/// played at their kickoff when it is past, else a second apart before `now`, so the results
+/// keep the order of the rounds.
+fn simulate(
+    league_name: &str,
+    season: Option<u32>,
+    fixtures: &[Fixture],
+    scoring: &Scoring,
+    rng: &mut Rng,
+    now: DateTime<Utc>,
+) -> Vec<GameResult> {
+    fixtures
+        .iter()
+        .enumerate()
+        .map(|(i, fixture)| {
+            let (home, away) = scoring.score(rng);
+            let played = fixture
+                .rescheduled_to
+                .as_ref()
+                .or(fixture.kickoff.as_ref())
+                .map(|k| k.0)
+                .filter(|k| *k <= now)
+                .unwrap_or_else(|| now - Duration::seconds((fixtures.len() - i) as i64));
+            let mut spec = import::result_spec(
+                league_name,
+                fixture.round,
+                [fixture.home.clone(), fixture.away.clone()],
+                import::outcome(home, away, DecidedBy::Regulation, None),
+                played,
+            );
+            spec.season = season;
+            spec.time = Some(Time(played));
+            let name = schedule::fixture_name(league_name, season, fixture);
+            GameResult::new(&name, spec)
+        })
+        .collect()
+}
+
+/// The fixtures of `league` still to be played, in round order, limited to the next `rounds`
+async fn remaining_fixtures(
+    client: &Client,
+    league: &TheLeague,
+    rounds: Option<usize>,
+) -> anyhow::Result<Vec<Fixture>> {
+    let result_api: Api<GameResult> =
+        Api::namespaced(client.clone(), &league.namespace().unwrap_or_default());
+    let results: Vec<Arc<GameResult>> = result_api
+        .list(&ListParams::default())
+        .await?
+        .items
+        .into_iter()
+        .map(Arc::new)
+        .collect();
+    let status = league.status.clone().unwrap_or_default();
+    let counted = standings_api::counted_results(league, &status, &results);
+    let mut fixtures = schedule::league_schedule(&league.spec)?.outstanding_fixtures(&counted);
+    if let Some(rounds) = rounds {
+        let mut kept: Vec<u32> = fixtures.iter().map(|f| f.round).collect();
+        kept.dedup();
+        kept.truncate(rounds);
+        fixtures.retain(|f| kept.contains(&f.round));
+    }
+    Ok(fixtures)
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let cli = Cli::parse();
+    let client = Client::try_default().await?;
+    let namespace = cli
+        .namespace
+        .clone()
+        .unwrap_or_else(|| client.default_namespace().to_string());
+
+    let league_api: Api<TheLeague> = Api::namespaced(client.clone(), &namespace);
+    let Some(league) = league_api.get_opt(&cli.league).await? else {
+        bail!("TheLeague '{}/{}' not found", namespace, cli.league);
+    };
+    let fixtures = remaining_fixtures(&client, &league, cli.rounds).await?;
+    if fixtures.is_empty() {
+        println!(
+            "League '{}/{}' has no fixtures left to play",
+            namespace, cli.league
+        );
+        return Ok(());
+    }
+
+    let seed = cli.seed.unwrap_or_else(|| {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_nanos() as u64)
+    });
+    println!(
+        "Simulating {} fixture(s) with seed {}",
+        fixtures.len(),
+        seed
+    );
+    let scoring = Scoring {
+        distribution: cli.distribution,
+        home_mean: cli.home_mean,
+        away_mean: cli.away_mean,
+        max_goals: cli.max_goals,
+    };
+    let season = league.status.as_ref().and_then(|s| s.current_season);
+    let results = simulate(
+        &cli.league,
+        season,
+        &fixtures,
+        &scoring,
+        &mut Rng(seed),
+        Utc::now(),
+    );
+
+    let result_api: Api<GameResult> = Api::namespaced(client.clone(), &namespace);
+    // Forced, to take the outcome of pre-populated results over from the controller
+    let mut params = PatchParams::apply(FIELD_MANAGER).force();
+    params.dry_run = cli.dry_run;
+    let applied: Vec<_> = stream::iter(&results)
+        .map(|result| {
+            let (api, params) = (&result_api, &params);
+            async move {
+                let outcome = api
+                    .patch(&result.name_any(), params, &Patch::Apply(result))
+                    .await;
+                (result, outcome)
+            }
+        })
+        .buffered(IMPORT_BATCH_SIZE)
+        .collect()
+        .await;
+
+    let mut failed = 0;
+    for (result, outcome) in applied {
+        let [home, away] = &result.spec.teams;
+        let (score_home, score_away) =
+            the_league::standings::outcome_scores(&result.spec.result).unwrap_or_default();
+        match outcome {
+            Ok(_) => println!(
+                "Round {}: {} {}-{} {}",
+                result.spec.round_number, home, score_home, score_away, away
+            ),
+            Err(e) => {
+                failed += 1;
+                eprintln!("{}: {}", result.name_any(), e);
+            }
+        }
+    }
+    println!(
+        "{} {} GameResult(s) in league '{}/{}', {} failed",
+        if cli.dry_run { "Validated" } else { "Applied" },
+        results.len() - failed,
+        namespace,
+        cli.league,
+        failed
+    );
+    if failed > 0 {
+        bail!("{} GameResult(s) could not be applied", failed);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture(round: u32, home: &str, away: &str) -> Fixture {
+        Fixture {
+            round,
+            home: home.to_string(),
+            away: away.to_string(),
+            rescheduled_to: None,
+            kickoff: None,
+        }
+    }
+
+    #[test]
+    fn test_poisson_goals_average_the_mean() {
+        let mut rng = Rng(7);
+        let games = 10_000;
+        let goals: u32 = (0..games).map(|_| rng.poisson(1.5)).sum();
+        let mean = f64::from(goals) / f64::from(games);
+        assert!((mean - 1.5).abs() < 0.05, "mean {}", mean);
+        assert!((0..games).all(|_| rng.below(6) < 6));
+    }
+
+    #[test]
+    fn test_simulate_is_reproducible() {
+        let fixtures = [
+            fixture(1, "Tigers", "Sharks"),
+            fixture(2, "Sharks", "Tigers"),
+        ];
+        let scoring = Scoring {
+            distribution: Distribution::Poisson,
+            home_mean: 1.5,
+            away_mean: 1.1,
+            max_goals: 6,
+        };
+        let now = Utc::now();
+        let run = |seed| simulate("premier", Some(2), &fixtures, &scoring, &mut Rng(seed), now);
+        let scores = |results: &[GameResult]| -> Vec<Option<(u32, u32)>> {
+            results
+                .iter()
+                .map(|r| the_league::standings::outcome_scores(&r.spec.result))
+                .collect()
+        };
+
+        let results = run(42);
+        assert_eq!(scores(&results), scores(&run(42)));
+        assert_eq!(
+            results[0].name_any(),
+            schedule::fixture_name("premier", Some(2), &fixtures[0])
+        );
+        assert_eq!(results[1].spec.season, Some(2));
+        assert!(results[0].spec.time < results[1].spec.time);
+        assert!(results[1].spec.time.as_ref().is_some_and(|t| t.0 < now));
+    }
+}
@@ -0,0 +1,349 @@
+//! Binary to uninstall the TheLeague controller from the cluster of the current kubeconfig
+//! without kubectl: it removes what the install binary applied, the Deployment, webhooks and
+//! RBAC of the controller, and the CRDs with every league object stored as their resources.
+//!
+//! `--keep-data` keeps the CRDs, and so the leagues, teams, results and standings, in the
+//! cluster, for a reinstall to pick them up again. Without it the CRDs are deleted first, while
+//! the controller still runs to release the finalizers of its objects, and the rest once the
+//! API server removed them.
+//!
+//! The Standings kept carry the finalizer of the controller, so deleting one of them, or their
+//! CRD later on, hangs until the controller is reinstalled. `--strip-finalizers` removes the
+//! finalizer from the Standings once the controller is gone, giving up the withdrawal of their
+//! teams on deletion.
+//!
+//! The objects to delete are printed, with the number of league objects deleted along with
+//! each CRD, and the uninstall asks for confirmation unless `--yes` is given. `--dry-run` only
+//! prints them. The namespace of the install is left in place.
+//!
+//! Run with: `cargo run --bin uninstall`, or `cargo run --bin uninstall -- --keep-data --yes`
+
+use anyhow::Context;
+use clap::Parser;
+use k8s_openapi::apiextensions_apiserver::pkg::apis::apiextensions::v1::CustomResourceDefinition;
+use kube::api::{DeleteParams, DynamicObject, ListParams, Patch, PatchParams};
+use kube::core::GroupVersionKind;
+use kube::discovery::ApiResource;
+use kube::runtime::wait::{await_condition, conditions};
+use kube::{Api, Client, ResourceExt};
+use std::io::{self, BufRead, Write};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use the_league::Standing;
+use the_league::controller::standing_controller::STANDING_FINALIZER;
+use the_league::manifests::{
+    CRD_DIR, DEPLOY_DIRS, Prepared, RBAC_DIR, Resolver, kind, read_manifests,
+};
+use the_league::webhook;
+
+const CRD_KIND: &str = "CustomResourceDefinition";
+
+/// Uninstall the controller from the cluster
+#[derive(Parser, Debug)]
+#[command(name = "uninstall")]
+struct Cli {
+    /// Directory of the generated manifests
+    #[arg(long, default_value = "config")]
+    config_dir: PathBuf,
+
+    /// Namespace the controller is installed to
+    #[arg(long, default_value = webhook::DEFAULT_NAMESPACE)]
+    namespace: String,
+
+    /// Keep the CRDs and the league objects, only removing the controller
+    #[arg(long)]
+    keep_data: bool,
+
+    /// Remove the finalizer of the controller from the kept Standings, so they and their CRD
+    /// can be deleted without the controller
+    #[arg(long, requires = "keep_data")]
+    strip_finalizers: bool,
+
+    /// Delete without asking for confirmation
+    #[arg(long, short)]
+    yes: bool,
+
+    /// Only print what would be deleted
+    #[arg(long)]
+    dry_run: bool,
+
+    /// Seconds to wait for the CRDs to be deleted
+    #[arg(long, default_value_t = 120)]
+    timeout: u64,
+}
+
+/// Directories of the config directory to delete the objects of, in the order to delete them:
+/// the CRDs while the controller runs, then the controller and its RBAC
+fn deletion_order(keep_data: bool) -> Vec<&'static str> {
+    let mut dirs = Vec::new();
+    if !keep_data {
+        dirs.push(CRD_DIR);
+    }
+    dirs.extend(DEPLOY_DIRS.iter().rev());
+    dirs.push(RBAC_DIR);
+    dirs
+}
+
+/// Resource stored by the CRD `crd`, in the version it is stored as
+fn stored_resource(crd: &DynamicObject) -> Option<ApiResource> {
+    let spec = crd.data.get("spec")?;
+    let group = spec["group"].as_str()?;
+    let version = spec["versions"]
+        .as_array()?
+        .iter()
+        .find(|v| v["storage"] == true)?["name"]
+        .as_str()?;
+    let kind = spec["names"]["kind"].as_str()?;
+    let plural = spec["names"]["plural"].as_str()?;
+    let gvk = GroupVersionKind::gvk(group, version, kind);
+    Some(ApiResource::from_gvk_with_plural(&gvk, plural))
+}
+
+/// Whether `answer` confirms the uninstall
+fn confirmed(answer: &str) -> bool {
+    matches!(answer.trim().to_lowercase().as_str(), "y" | "yes")
+}
+
+/// An installed object to delete, with the UID it is installed as
+struct Installed {
+    prepared: Prepared,
+    uid: String,
+}
+
+/// The installed objects of the manifests of `dirs`, in their order
+async fn installed(
+    resolver: &mut Resolver,
+    cli: &Cli,
+    dirs: &[&str],
+) -> anyhow::Result<Vec<Installed>> {
+    let mut objects = Vec::new();
+    for dir in dirs {
+        for object in read_manifests(&cli.config_dir.join(dir))? {
+            let prepared = resolver.prepare(object).await?;
+            let name = prepared.object.name_any();
+            if let Some(existing) = prepared.api.get_opt(&name).await? {
+                objects.push(Installed {
+                    uid: existing.uid().unwrap_or_default(),
+                    prepared,
+                });
+            }
+        }
+    }
+    Ok(objects)
+}
+
+/// Print the objects to delete, counting the league objects deleted along with each CRD
+async fn print_plan(client: &Client, objects: &[Installed]) -> anyhow::Result<()> {
+    println!("The following will be deleted:");
+    for installed in objects {
+        let object = &installed.prepared.object;
+        let Some(resource) = stored_resource(object).filter(|_| kind(object) == CRD_KIND) else {
+            println!("  {}", installed.prepared.describe());
+            continue;
+        };
+        let api: Api<DynamicObject> = Api::all_with(client.clone(), &resource);
+        let stored = api.list_metadata(&ListParams::default()).await?.items.len();
+        println!(
+            "  {}, with {} {} object(s) in all namespaces",
+            installed.prepared.describe(),
+            stored,
+            resource.kind
+        );
+    }
+    Ok(())
+}
+
+/// Remove the finalizer of the controller from every Standing carrying it. Returns the number
+/// of Standings released.
+async fn strip_finalizers(client: &Client) -> anyhow::Result<usize> {
+    let all: Api<Standing> = Api::all(client.clone());
+    let mut stripped = 0;
+    for standing in all.list_metadata(&ListParams::default()).await?.items {
+        if !standing
+            .finalizers()
+            .iter()
+            .any(|f| f == STANDING_FINALIZER)
+        {
+            continue;
+        }
+        let finalizers: Vec<&String> = standing
+            .finalizers()
+            .iter()
+            .filter(|f| *f != STANDING_FINALIZER)
+            .collect();
+        // The resourceVersion fails the patch if the finalizers changed since the list.
+        let patch = serde_json::json!({
+            "metadata": {
+                "finalizers": finalizers,
+                "resourceVersion": standing.resource_version(),
+            }
+        });
+        let api: Api<Standing> =
+            Api::namespaced(client.clone(), &standing.namespace().unwrap_or_default());
+        api.patch(
+            &standing.name_any(),
+            &PatchParams::default(),
+            &Patch::Merge(&patch),
+        )
+        .await
+        .with_context(|| format!("releasing Standing {}", standing.name_any()))?;
+        stripped += 1;
+    }
+    Ok(stripped)
+}
+
+/// Delete the CRDs `objects` and wait until the API server removed them and their resources
+async fn delete_crds(
+    client: &Client,
+    objects: &[Installed],
+    timeout: Duration,
+) -> anyhow::Result<()> {
+    for installed in objects {
+        delete(&installed.prepared).await?;
+    }
+    let crds: Api<CustomResourceDefinition> = Api::all(client.clone());
+    let deleted = async {
+        for installed in objects {
+            let name = installed.prepared.object.name_any();
+            await_condition(crds.clone(), &name, conditions::is_deleted(&installed.uid)).await?;
+        }
+        anyhow::Ok(())
+    };
+    tokio::time::timeout(timeout, deleted)
+        .await
+        .with_context(|| format!("CRDs not deleted within {:?}", timeout))??;
+    println!("✓ {} CRDs and their objects removed", objects.len());
+    Ok(())
+}
+
+async fn delete(prepared: &Prepared) -> anyhow::Result<()> {
+    let name = prepared.object.name_any();
+    match prepared
+        .api
+        .delete(&name, &DeleteParams::background())
+        .await
+    {
+        Ok(_) => {}
+        // Deleted in the meantime, by a cascade or another client
+        Err(kube::Error::Api(e)) if e.code == 404 => {}
+        Err(e) => return Err(e).with_context(|| format!("deleting {}", prepared.describe())),
+    }
+    println!("✓ Deleted {}", prepared.describe());
+    Ok(())
+}
+
+async fn uninstall(cli: &Cli) -> anyhow::Result<bool> {
+    let client = Client::try_default().await?;
+    let mut resolver = Resolver::new(client.clone(), &cli.namespace);
+    let dirs = deletion_order(cli.keep_data);
+    let objects = installed(&mut resolver, cli, &dirs).await?;
+    if objects.is_empty() {
+        println!("Nothing to uninstall in namespace {}.", cli.namespace);
+        return Ok(false);
+    }
+
+    print_plan(&client, &objects).await?;
+    if cli.keep_data {
+        println!("The CRDs and the league objects are kept (--keep-data).");
+        if cli.strip_finalizers {
+            println!(
+                "The {} finalizer is removed from the Standings (--strip-finalizers).",
+                STANDING_FINALIZER
+            );
+        } else {
+            println!(
+                "Warning: the Standings keep the {} finalizer; deleting them or their CRD \
+                 hangs until the controller is reinstalled, or use --strip-finalizers.",
+                STANDING_FINALIZER
+            );
+        }
+    }
+    if cli.dry_run {
+        return Ok(false);
+    }
+    if !cli.yes {
+        print!("Proceed? [y/N] ");
+        io::stdout().flush()?;
+        let mut answer = String::new();
+        io::stdin().lock().read_line(&mut answer)?;
+        if !confirmed(&answer) {
+            anyhow::bail!("uninstall aborted");
+        }
+    }
+
+    let (crds, rest): (Vec<_>, Vec<_>) = objects
+        .into_iter()
+        .partition(|i| kind(&i.prepared.object) == CRD_KIND);
+    if !crds.is_empty() {
+        delete_crds(&client, &crds, Duration::from_secs(cli.timeout)).await?;
+    }
+    for installed in &rest {
+        delete(&installed.prepared).await?;
+    }
+    // Only once the controller is gone, as it would add the finalizer back.
+    if cli.strip_finalizers {
+        let stripped = strip_finalizers(&client).await?;
+        println!("✓ Removed the finalizer from {} Standing(s)", stripped);
+    }
+    Ok(true)
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let cli = Cli::parse();
+
+    if uninstall(&cli).await? {
+        println!(
+            "\nThe controller is uninstalled; namespace {} is left in place.",
+            cli.namespace
+        );
+    } else if cli.dry_run {
+        println!("\nDry run complete, nothing was changed.");
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    #[test]
+    fn test_crds_are_deleted_before_the_controller() {
+        assert_eq!(
+            deletion_order(false),
+            [CRD_DIR, "manager", "webhook", RBAC_DIR]
+        );
+        assert_eq!(deletion_order(true), ["manager", "webhook", RBAC_DIR]);
+        assert!(confirmed("y\n"));
+        assert!(confirmed(" Yes "));
+        assert!(!confirmed("\n"));
+        assert!(!confirmed("no"));
+    }
+
+    #[test]
+    fn test_finalizers_are_only_stripped_when_keeping_data() {
+        let cli = Cli::try_parse_from(["uninstall", "--keep-data", "--strip-finalizers"]).unwrap();
+        assert!(cli.strip_finalizers);
+        assert!(Cli::try_parse_from(["uninstall", "--strip-finalizers"]).is_err());
+    }
+
+    #[test]
+    fn test_stored_resource_of_the_committed_crds() {
+        let crds = read_manifests(&Path::new("config").join(CRD_DIR)).unwrap();
+        assert!(!crds.is_empty());
+        for crd in crds
+            .iter()
+            .filter(|o| kind(o) == "CustomResourceDefinition")
+        {
+            let resource = stored_resource(crd).unwrap();
+            assert_eq!(resource.group, "bexxmodd.com");
+            assert_eq!(
+                format!("{}.{}", resource.plural, resource.group),
+                crd.name_any()
+            );
+        }
+    }
+}
@@ -0,0 +1,219 @@
+//! Single-elimination brackets: seeding and advancing winners from game results.
+//!
+//! Everything in here is pure so it can be shared by the playoff and tournament controllers.
+
+use crate::api::v1alpha1::game_result_types::{GameOutcome, GameResultSpec};
+use crate::api::v1alpha1::playoff_types::Series;
+use crate::api::v1alpha1::the_league_types::TableRow;
+
+/// A slot of a bracket round
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Slot {
+    /// A team with its 1-based seed
+    Team(u32, String),
+    /// No opponent: the other team of the pairing advances
+    Bye,
+    /// The winner of a series that isn't decided yet
+    Undecided,
+}
+
+/// Seeds in bracket order for a bracket of `size` teams (a power of two), so that the best
+/// seeds only meet in the late rounds, e.g. `[1, 4, 2, 3]` for 4 teams.
+pub fn seed_order(size: u32) -> Vec<u32> {
+    let mut order = vec![1];
+    while (order.len() as u32) < size {
+        let next = order.len() as u32 * 2;
+        order = order.iter().flat_map(|&s| [s, next + 1 - s]).collect();
+    }
+    order
+}
+
+/// `teams` ordered by their rank in the league `table`, best first. Teams missing from the
+/// table keep their order after the ranked ones.
+pub fn seeded_draw(teams: &[String], table: &[TableRow]) -> Vec<String> {
+    let mut draw = teams.to_vec();
+    draw.sort_by_key(|team| {
+        table
+            .iter()
+            .find(|row| &row.team == team)
+            .map_or(u32::MAX, |row| row.rank)
+    });
+    draw
+}
+
+/// The team winning `result`, if it has a winner
+fn winner(result: &GameResultSpec) -> Option<&str> {
+    match &result.result {
+        GameOutcome::WinnerHomeTeam { .. } => Some(&result.teams[0]),
+        GameOutcome::WinnerAwayTeam { .. } => Some(&result.teams[1]),
+        _ => None,
+    }
+}
+
+/// Series of the bracket of `seeds` (best seed first) and its champion, given the `results`
+/// of the knockout games. A series is won by the first team winning a majority of `best_of`
+/// games of its round against the other team; byes are not listed.
+pub fn bracket(
+    seeds: &[String],
+    results: &[&GameResultSpec],
+    best_of: u32,
+) -> (Vec<Series>, Option<String>) {
+    if seeds.len() < 2 {
+        return (Vec::new(), seeds.first().cloned());
+    }
+    let needed = best_of.max(1) / 2 + 1;
+    let size = (seeds.len() as u32).next_power_of_two();
+    let mut slots: Vec<Slot> = seed_order(size)
+        .into_iter()
+        .map(|seed| match seeds.get(seed as usize - 1) {
+            Some(team) => Slot::Team(seed, team.clone()),
+            None => Slot::Bye,
+        })
+        .collect();
+
+    let mut series = Vec::new();
+    let mut round = 1;
+    while slots.len() > 1 {
+        let mut next = Vec::new();
+        for pair in slots.chunks(2) {
+            let advancing = match (&pair[0], &pair[1]) {
+                (Slot::Team(..), Slot::Bye) => pair[0].clone(),
+                (Slot::Bye, Slot::Team(..)) => pair[1].clone(),
+                (Slot::Team(a_seed, a), Slot::Team(b_seed, b)) => {
+                    let ((home_seed, home), (away_seed, away)) = if a_seed < b_seed {
+                        ((*a_seed, a), (*b_seed, b))
+                    } else {
+                        ((*b_seed, b), (*a_seed, a))
+                    };
+                    let won_by = |team: &str| {
+                        results
+                            .iter()
+                            .filter(|r| r.round_number == round)
+                            .filter(|r| r.teams.contains(home) && r.teams.contains(away))
+                            .filter(|r| winner(r) == Some(team))
+                            .count() as u32
+                    };
+                    let (home_wins, away_wins) = (won_by(home), won_by(away));
+                    let winner = if home_wins >= needed {
+                        Some((home_seed, home))
+                    } else if away_wins >= needed {
+                        Some((away_seed, away))
+                    } else {
+                        None
+                    };
+                    series.push(Series {
+                        round,
+                        home: Some(home.clone()),
+                        away: Some(away.clone()),
+                        home_wins,
+                        away_wins,
+                        winner: winner.map(|(_, team)| team.clone()),
+                    });
+                    match winner {
+                        Some((seed, team)) => Slot::Team(seed, team.clone()),
+                        None => Slot::Undecided,
+                    }
+                }
+                (a, b) => {
+                    let known = |slot: &Slot| match slot {
+                        Slot::Team(_, team) => Some(team.clone()),
+                        _ => None,
+                    };
+                    series.push(Series {
+                        round,
+                        home: known(a),
+                        away: known(b),
+                        ..Default::default()
+                    });
+                    Slot::Undecided
+                }
+            };
+            next.push(advancing);
+        }
+        slots = next;
+        round += 1;
+    }
+
+    let champion = match &slots[0] {
+        Slot::Team(_, team) => Some(team.clone()),
+        _ => None,
+    };
+    (series, champion)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn win(round: u32, home: &str, away: &str) -> GameResultSpec {
+        GameResultSpec::game(home, away)
+            .in_round(round)
+            .in_playoff("premier-playoff")
+            .with_score(2, 1)
+    }
+
+    fn seeds(teams: &[&str]) -> Vec<String> {
+        teams.iter().map(|t| t.to_string()).collect()
+    }
+
+    #[test]
+    fn test_seed_order_keeps_top_seeds_apart() {
+        assert_eq!(seed_order(2), vec![1, 2]);
+        assert_eq!(seed_order(4), vec![1, 4, 2, 3]);
+        assert_eq!(seed_order(8), vec![1, 8, 4, 5, 2, 7, 3, 6]);
+    }
+
+    #[test]
+    fn test_bracket_advances_series_winners_to_a_champion() {
+        let seeds = seeds(&["Tigers", "Sharks", "Eagles", "Bears"]);
+        let (series, champion) = bracket(&seeds, &[], 3);
+        assert_eq!(series.len(), 3);
+        assert_eq!(series[0].home.as_deref(), Some("Tigers"));
+        assert_eq!(series[0].away.as_deref(), Some("Bears"));
+        assert_eq!(series[2].home, None);
+        assert_eq!(champion, None);
+
+        let results = [
+            win(1, "Tigers", "Bears"),
+            win(1, "Bears", "Tigers"),
+            win(1, "Tigers", "Bears"),
+            win(1, "Eagles", "Sharks"),
+            win(1, "Eagles", "Sharks"),
+            win(2, "Eagles", "Tigers"),
+            win(2, "Eagles", "Tigers"),
+        ];
+        let results: Vec<&GameResultSpec> = results.iter().collect();
+        let (series, champion) = bracket(&seeds, &results, 3);
+        assert_eq!((series[0].home_wins, series[0].away_wins), (2, 1));
+        assert_eq!(series[1].winner.as_deref(), Some("Eagles"));
+        assert_eq!(series[2].home.as_deref(), Some("Tigers"));
+        assert_eq!(series[2].away.as_deref(), Some("Eagles"));
+        assert_eq!(champion.as_deref(), Some("Eagles"));
+    }
+
+    #[test]
+    fn test_top_seeds_get_byes() {
+        let seeds = seeds(&["Tigers", "Sharks", "Eagles"]);
+        let results = [win(1, "Sharks", "Eagles")];
+        let results: Vec<&GameResultSpec> = results.iter().collect();
+        let (series, champion) = bracket(&seeds, &results, 1);
+        assert_eq!(series.len(), 2);
+        assert_eq!(series[1].home.as_deref(), Some("Tigers"));
+        assert_eq!(series[1].away.as_deref(), Some("Sharks"));
+        assert_eq!(champion, None);
+    }
+
+    #[test]
+    fn test_seeded_draw_follows_the_table() {
+        let row = |team: &str, rank| TableRow {
+            rank,
+            team: team.to_string(),
+            ..Default::default()
+        };
+        let table = [row("Sharks", 1), row("Tigers", 2)];
+        assert_eq!(
+            seeded_draw(&seeds(&["Eagles", "Tigers", "Sharks"]), &table),
+            seeds(&["Sharks", "Tigers", "Eagles"])
+        );
+    }
+}
@@ -0,0 +1,360 @@
+//! Export and import of a whole league as a single JSON bundle, for `leaguectl export` and
+//! `leaguectl import`.
+//!
+//! A bundle holds the TheLeague with its Standings, Matches and GameResults, statuses
+//! included, stripped of the metadata the API server assigns. Importing applies them to a
+//! namespace of any cluster: objects owned by the league are adopted by the TheLeague created
+//! there, and the statuses of the league, its Standings and Matches are restored. The statuses
+//! of the GameResults are not, as the controller validates the results again.
+
+use crate::api::v1alpha1::game_result_types::GameResult;
+use crate::api::v1alpha1::match_types::Match;
+use crate::api::v1alpha1::standing_types::Standing;
+use crate::api::v1alpha1::the_league_types::TheLeague;
+use crate::controller::matches;
+use crate::import::IMPORT_FIELD_MANAGER;
+
+use k8s_openapi::chrono::{DateTime, Utc};
+use kube::api::{ListParams, ObjectMeta, Patch, PatchParams};
+use kube::{Api, Client, Resource, ResourceExt};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::fmt::Debug;
+
+/// Kind of the bundle documents
+pub const BUNDLE_KIND: &str = "TheLeagueBundle";
+
+/// Version of the bundle format, raised on changes older releases can't import
+pub const BUNDLE_VERSION: u32 = 1;
+
+/// LeagueBundle is a league with every object belonging to it.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LeagueBundle {
+    /// Kind is always `TheLeagueBundle`.
+    pub kind: String,
+
+    /// Version is the version of the bundle format.
+    pub version: u32,
+
+    /// ExportedAt is the time the bundle was exported.
+    pub exported_at: DateTime<Utc>,
+
+    /// League is the exported TheLeague.
+    pub league: TheLeague,
+
+    /// Standings are the Standings of the teams of the league.
+    #[serde(default)]
+    pub standings: Vec<Standing>,
+
+    /// Matches are the scheduled fixtures of the league.
+    #[serde(default)]
+    pub matches: Vec<Match>,
+
+    /// Results are the GameResults of the league.
+    #[serde(default)]
+    pub results: Vec<GameResult>,
+}
+
+/// Number of objects applied by an import, per kind
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ImportSummary {
+    pub standings: usize,
+    pub matches: usize,
+    pub results: usize,
+}
+
+/// Strip `object` of the metadata the API server assigns and of the owners other than the
+/// league `league_name`, whose UID is only known once imported
+fn portable<K: Resource>(object: &mut K, league_name: &str) {
+    let meta = object.meta_mut();
+    let owners = meta
+        .owner_references
+        .take()
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|o| o.kind == TheLeague::kind(&()) && o.name == league_name)
+        .collect::<Vec<_>>();
+    *meta = ObjectMeta {
+        name: meta.name.take(),
+        labels: meta.labels.take(),
+        annotations: meta.annotations.take(),
+        owner_references: (!owners.is_empty()).then_some(owners),
+        ..ObjectMeta::default()
+    };
+}
+
+/// Point the owner references of `object` to the league at its UID `uid`
+fn adopt<K: Resource>(object: &mut K, league_name: &str, uid: &str) {
+    for owner in object.owner_references_mut() {
+        if owner.name == league_name {
+            owner.uid = uid.to_string();
+        }
+    }
+}
+
+impl LeagueBundle {
+    /// Bundle `league` with its objects in `standings`, `matches` and `results`, which may hold
+    /// objects of other leagues
+    pub fn new(
+        mut league: TheLeague,
+        standings: Vec<Standing>,
+        matches: Vec<Match>,
+        results: Vec<GameResult>,
+        exported_at: DateTime<Utc>,
+    ) -> Self {
+        let name = league.name_any();
+        let namespace = league.namespace();
+        let of_league = |league_name: &str, league_namespace: &Option<String>| {
+            league_name == name && (league_namespace.is_none() || *league_namespace == namespace)
+        };
+        let mut standings: Vec<Standing> = standings
+            .into_iter()
+            .filter(|s| of_league(&s.spec.league_name, &s.spec.league_namespace))
+            .collect();
+        let mut matches: Vec<Match> = matches
+            .into_iter()
+            .filter(|m| m.spec.league_name == name)
+            .collect();
+        let mut results: Vec<GameResult> = results
+            .into_iter()
+            .filter(|r| of_league(&r.spec.league_name, &r.spec.league_namespace))
+            .collect();
+
+        portable(&mut league, &name);
+        standings.iter_mut().for_each(|s| portable(s, &name));
+        matches.iter_mut().for_each(|m| portable(m, &name));
+        results.iter_mut().for_each(|r| portable(r, &name));
+        standings.sort_by_key(|s| s.name_any());
+        matches.sort_by_key(|m| m.name_any());
+        results.sort_by_key(|r| r.name_any());
+
+        LeagueBundle {
+            kind: BUNDLE_KIND.to_string(),
+            version: BUNDLE_VERSION,
+            exported_at,
+            league,
+            standings,
+            matches,
+            results,
+        }
+    }
+
+    /// Parse the JSON bundle `content`, refusing other documents and newer bundle versions
+    pub fn parse(content: &str) -> Result<Self, String> {
+        let document: serde_json::Value =
+            serde_json::from_str(content).map_err(|e| format!("not a JSON document: {}", e))?;
+        if document["kind"] != BUNDLE_KIND {
+            return Err(format!("not a {} document", BUNDLE_KIND));
+        }
+        match document["version"].as_u64() {
+            Some(version) if version <= u64::from(BUNDLE_VERSION) => {}
+            Some(version) => {
+                return Err(format!(
+                    "bundle version {} is newer than the supported version {}",
+                    version, BUNDLE_VERSION
+                ));
+            }
+            None => return Err("the bundle has no version".to_string()),
+        }
+        serde_json::from_value(document).map_err(|e| format!("invalid bundle: {}", e))
+    }
+}
+
+/// Export the league `name` of `namespace` with its objects. Returns None when it doesn't exist.
+pub async fn export_league(
+    client: &Client,
+    namespace: &str,
+    name: &str,
+) -> Result<Option<LeagueBundle>, kube::Error> {
+    let league_api: Api<TheLeague> = Api::namespaced(client.clone(), namespace);
+    let Some(league) = league_api.get_opt(name).await? else {
+        return Ok(None);
+    };
+    let standing_api: Api<Standing> = Api::namespaced(client.clone(), namespace);
+    let standings = standing_api.list(&ListParams::default()).await?.items;
+    let match_api: Api<Match> = Api::namespaced(client.clone(), namespace);
+    let matches = matches::league_matches(&match_api, name).await?;
+    let result_api: Api<GameResult> = Api::namespaced(client.clone(), namespace);
+    let results = result_api.list(&ListParams::default()).await?.items;
+    Ok(Some(LeagueBundle::new(
+        league,
+        standings,
+        matches,
+        results,
+        Utc::now(),
+    )))
+}
+
+/// Apply `objects` with server-side apply, adopted by the league `league_name` at its UID `uid`,
+/// and restore their statuses when `with_status`
+async fn apply_all<K>(
+    api: &Api<K>,
+    params: &PatchParams,
+    objects: &[K],
+    league_name: &str,
+    uid: &str,
+    with_status: bool,
+) -> Result<usize, kube::Error>
+where
+    K: Resource + Clone + Serialize + DeserializeOwned + Debug,
+{
+    for object in objects {
+        let mut object = object.clone();
+        adopt(&mut object, league_name, uid);
+        let name = object.name_any();
+        api.patch(&name, params, &Patch::Apply(&object)).await?;
+        if with_status {
+            let status =
+                serde_json::to_value(&object).map_err(kube::Error::SerdeError)?["status"].take();
+            if !status.is_null() {
+                let patch = serde_json::json!({ "status": status });
+                api.patch_status(&name, params, &Patch::Merge(&patch))
+                    .await?;
+            }
+        }
+    }
+    Ok(objects.len())
+}
+
+/// Import `bundle` into `namespace`: the league first, then its Standings, Matches and
+/// GameResults, created or updated with server-side apply.
+pub async fn import_league(
+    client: &Client,
+    namespace: &str,
+    bundle: &LeagueBundle,
+    dry_run: bool,
+) -> Result<ImportSummary, kube::Error> {
+    let name = bundle.league.name_any();
+    let mut params = PatchParams::apply(IMPORT_FIELD_MANAGER);
+    params.dry_run = dry_run;
+
+    let league_api: Api<TheLeague> = Api::namespaced(client.clone(), namespace);
+    let league = league_api
+        .patch(&name, &params, &Patch::Apply(&bundle.league))
+        .await?;
+    if let Some(status) = &bundle.league.status {
+        let patch = serde_json::json!({ "status": status });
+        league_api
+            .patch_status(&name, &params, &Patch::Merge(&patch))
+            .await?;
+    }
+    let uid = league.uid().unwrap_or_default();
+
+    let standing_api: Api<Standing> = Api::namespaced(client.clone(), namespace);
+    let match_api: Api<Match> = Api::namespaced(client.clone(), namespace);
+    let result_api: Api<GameResult> = Api::namespaced(client.clone(), namespace);
+    Ok(ImportSummary {
+        standings: apply_all(&standing_api, &params, &bundle.standings, &name, &uid, true).await?,
+        matches: apply_all(&match_api, &params, &bundle.matches, &name, &uid, true).await?,
+        results: apply_all(&result_api, &params, &bundle.results, &name, &uid, false).await?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn object<K: DeserializeOwned>(kind: &str, name: &str, spec: serde_json::Value) -> K {
+        serde_json::from_value(json!({
+            "apiVersion": "bexxmodd.com/v1alpha1",
+            "kind": kind,
+            "metadata": {
+                "name": name,
+                "namespace": "default",
+                "uid": format!("{}-uid", name),
+                "resourceVersion": "42",
+                "labels": {"tier": "top"},
+                "ownerReferences": [
+                    {"apiVersion": "bexxmodd.com/v1alpha1", "kind": "TheLeague",
+                     "name": "premier", "uid": "premier-uid"},
+                    {"apiVersion": "bexxmodd.com/v1alpha1", "kind": "Season",
+                     "name": "premier-1", "uid": "season-uid"},
+                ],
+            },
+            "spec": spec,
+        }))
+        .unwrap()
+    }
+
+    fn result(name: &str, league: &str) -> GameResult {
+        object(
+            "GameResult",
+            name,
+            json!({
+                "leagueName": league,
+                "roundNumber": 1,
+                "teams": ["Tigers", "Sharks"],
+                "time": "2026-09-01T18:00:00Z",
+                "result": {"Draw": {"score": 1}},
+            }),
+        )
+    }
+
+    fn bundle() -> LeagueBundle {
+        let mut league: TheLeague = object(
+            "TheLeague",
+            "premier",
+            json!({"maxTeams": 2, "teams": [{"name": "Tigers"}, {"name": "Sharks"}]}),
+        );
+        league.meta_mut().owner_references = None;
+        let standing: Standing = object(
+            "Standing",
+            "premier-tigers",
+            json!({"leagueName": "premier", "teamName": "Tigers"}),
+        );
+        LeagueBundle::new(
+            league,
+            vec![standing],
+            Vec::new(),
+            vec![
+                result("b", "premier"),
+                result("other", "cup"),
+                result("a", "premier"),
+            ],
+            Utc::now(),
+        )
+    }
+
+    #[test]
+    fn test_bundle_keeps_the_objects_of_the_league_without_server_metadata() {
+        let bundle = bundle();
+        let names: Vec<String> = bundle.results.iter().map(|r| r.name_any()).collect();
+        assert_eq!(names, ["a", "b"]);
+        assert_eq!(bundle.standings.len(), 1);
+
+        let meta = bundle.standings[0].meta();
+        assert_eq!(meta.uid, None);
+        assert_eq!(meta.resource_version, None);
+        assert_eq!(meta.namespace, None);
+        assert_eq!(meta.labels.as_ref().unwrap()["tier"], "top");
+        let owners = meta.owner_references.as_ref().unwrap();
+        assert_eq!(owners.len(), 1);
+        assert_eq!(owners[0].kind, "TheLeague");
+        assert_eq!(bundle.league.meta().owner_references, None);
+
+        let mut standing = bundle.standings[0].clone();
+        adopt(&mut standing, "premier", "new-uid");
+        assert_eq!(standing.owner_references()[0].uid, "new-uid");
+    }
+
+    #[test]
+    fn test_bundle_round_trips_through_json() {
+        let json = serde_json::to_string(&bundle()).unwrap();
+        let parsed = LeagueBundle::parse(&json).unwrap();
+        assert_eq!(parsed.version, BUNDLE_VERSION);
+        assert_eq!(parsed.league.spec.teams.len(), 2);
+        assert_eq!(parsed.results.len(), 2);
+
+        let mut newer: serde_json::Value = serde_json::from_str(&json).unwrap();
+        newer["version"] = json!(BUNDLE_VERSION + 1);
+        assert!(
+            LeagueBundle::parse(&newer.to_string())
+                .unwrap_err()
+                .contains("newer")
+        );
+        assert!(LeagueBundle::parse(r#"{"kind": "TheLeague"}"#).is_err());
+        assert!(LeagueBundle::parse("teams: []").is_err());
+    }
+}
@@ -0,0 +1,216 @@
+use crate::api::v1alpha1::the_league_types::{TableRow, TheLeague};
+use crate::controller::{apply_params, propagation};
+
+use k8s_openapi::api::core::v1::ConfigMap;
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+use k8s_openapi::chrono::{DateTime, SecondsFormat, Utc};
+use kube::api::Patch;
+use kube::{Api, Client, Resource, ResourceExt};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use tracing::info;
+
+/// Key of the audit trail in the audit ConfigMap, one JSON entry per line
+pub const AUDIT_KEY: &str = "audit.jsonl";
+
+/// Entries kept in the audit ConfigMap; the oldest are dropped beyond it to stay well within
+/// the 1MiB limit of a ConfigMap
+pub const MAX_AUDIT_ENTRIES: usize = 2000;
+
+/// Name of the ConfigMap holding the audit trail of a league's standings
+pub fn audit_configmap_name(league_name: &str) -> String {
+    format!("{}-standings-audit", league_name)
+}
+
+/// What changed the row of a team in the table
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuditCause {
+    /// The team entered the table
+    Joined,
+    /// The team left the table
+    Withdrawn,
+    /// A result of the team was applied
+    ResultApplied,
+    /// A result of the team was withdrawn or stopped counting
+    ResultRemoved,
+    /// The row was recomputed without a change in played games, e.g. after a change of the
+    /// points policy, a tie-break or a withdrawal from the league
+    Recomputed,
+}
+
+/// AuditEntry is one change of the row of a team in the league table.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditEntry {
+    /// Time is when the change was recorded, in RFC 3339.
+    pub time: String,
+    /// Team is the name of the team whose row changed.
+    pub team: String,
+    /// Cause is what changed the row.
+    pub cause: AuditCause,
+    /// PointsBefore is the points of the team before the change, if it was in the table.
+    pub points_before: Option<u32>,
+    /// PointsAfter is the points of the team after the change, if it is in the table.
+    pub points_after: Option<u32>,
+    /// RankBefore is the rank of the team before the change, if it was in the table.
+    pub rank_before: Option<u32>,
+    /// RankAfter is the rank of the team after the change, if it is in the table.
+    pub rank_after: Option<u32>,
+    /// PlayedBefore is the games played by the team before the change.
+    pub played_before: Option<u32>,
+    /// PlayedAfter is the games played by the team after the change.
+    pub played_after: Option<u32>,
+}
+
+/// Changes between the `old` and `new` table at `time`, one entry per team whose points,
+/// rank or played games changed, or that joined or left the table
+pub fn audit_entries(old: &[TableRow], new: &[TableRow], time: DateTime<Utc>) -> Vec<AuditEntry> {
+    let time = time.to_rfc3339_opts(SecondsFormat::Secs, true);
+    let old_rows: HashMap<&str, &TableRow> = old.iter().map(|r| (r.team.as_str(), r)).collect();
+    let new_rows: HashMap<&str, &TableRow> = new.iter().map(|r| (r.team.as_str(), r)).collect();
+    let entry =
+        |team: &str, cause, before: Option<&TableRow>, after: Option<&TableRow>| AuditEntry {
+            time: time.clone(),
+            team: team.to_string(),
+            cause,
+            points_before: before.map(|r| r.points),
+            points_after: after.map(|r| r.points),
+            rank_before: before.map(|r| r.rank),
+            rank_after: after.map(|r| r.rank),
+            played_before: before.map(|r| r.played),
+            played_after: after.map(|r| r.played),
+        };
+
+    let mut entries = Vec::new();
+    for row in new {
+        let Some(before) = old_rows.get(row.team.as_str()).copied() else {
+            entries.push(entry(&row.team, AuditCause::Joined, None, Some(row)));
+            continue;
+        };
+        let cause = if row.played > before.played {
+            AuditCause::ResultApplied
+        } else if row.played < before.played {
+            AuditCause::ResultRemoved
+        } else if row.points != before.points || row.rank != before.rank {
+            AuditCause::Recomputed
+        } else {
+            continue;
+        };
+        entries.push(entry(&row.team, cause, Some(before), Some(row)));
+    }
+    for row in old {
+        if !new_rows.contains_key(row.team.as_str()) {
+            entries.push(entry(&row.team, AuditCause::Withdrawn, Some(row), None));
+        }
+    }
+    entries
+}
+
+/// Append the changes between the `old` and `new` table of `league` to its
+/// `<league>-standings-audit` ConfigMap, keeping the last [`MAX_AUDIT_ENTRIES`] entries.
+/// Callers must hold the league lock, the trail is read and written back whole.
+pub async fn record_table_changes(
+    client: &Client,
+    league: &TheLeague,
+    old: &[TableRow],
+    new: &[TableRow],
+) -> Result<(), kube::Error> {
+    let entries = audit_entries(old, new, Utc::now());
+    if entries.is_empty() {
+        return Ok(());
+    }
+    let namespace = league.namespace().unwrap_or_default();
+    let name = audit_configmap_name(&league.name_any());
+    let configmap_api: Api<ConfigMap> = Api::namespaced(client.clone(), &namespace);
+
+    let existing = configmap_api
+        .get_opt(&name)
+        .await?
+        .and_then(|c| c.data)
+        .and_then(|mut data| data.remove(AUDIT_KEY))
+        .unwrap_or_default();
+    let mut lines: Vec<String> = existing.lines().map(str::to_string).collect();
+    for entry in &entries {
+        lines.push(serde_json::to_string(entry).map_err(kube::Error::SerdeError)?);
+    }
+    let excess = lines.len().saturating_sub(MAX_AUDIT_ENTRIES);
+    lines.drain(..excess);
+
+    let mut configmap = ConfigMap {
+        metadata: ObjectMeta {
+            name: Some(name.clone()),
+            namespace: Some(namespace.clone()),
+            // Owned by the league so the trail is garbage-collected with it
+            owner_references: league.controller_owner_ref(&()).map(|o| vec![o]),
+            ..Default::default()
+        },
+        data: Some(BTreeMap::from([(
+            AUDIT_KEY.to_string(),
+            lines.join("\n") + "\n",
+        )])),
+        ..Default::default()
+    };
+    propagation::propagated(league).apply_to(&mut configmap.metadata);
+    configmap_api
+        .patch(&name, &apply_params(), &Patch::Apply(&configmap))
+        .await?;
+    info!(
+        "Recorded {} standings changes of league '{}/{}'",
+        entries.len(),
+        namespace,
+        league.name_any()
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(team: &str, rank: u32, played: u32, points: u32) -> TableRow {
+        TableRow {
+            rank,
+            team: team.to_string(),
+            played,
+            points,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_audit_entries_record_what_changed() {
+        let time = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let old = [
+            row("Tigers", 1, 2, 6),
+            row("Sharks", 2, 2, 3),
+            row("Eagles", 3, 2, 0),
+        ];
+        let new = [
+            row("Sharks", 1, 3, 6),
+            row("Tigers", 2, 2, 6),
+            row("Bears", 3, 0, 0),
+        ];
+        let entries = audit_entries(&old, &new, time);
+        let causes: Vec<_> = entries.iter().map(|e| (e.team.as_str(), e.cause)).collect();
+        assert_eq!(
+            causes,
+            [
+                ("Sharks", AuditCause::ResultApplied),
+                ("Tigers", AuditCause::Recomputed),
+                ("Bears", AuditCause::Joined),
+                ("Eagles", AuditCause::Withdrawn),
+            ]
+        );
+        assert_eq!(entries[0].time, "2023-11-14T22:13:20Z");
+        assert_eq!(
+            (entries[0].points_before, entries[0].points_after),
+            (Some(3), Some(6))
+        );
+        assert_eq!(
+            (entries[3].rank_before, entries[3].rank_after),
+            (Some(3), None)
+        );
+
+        assert!(audit_entries(&new, &new, time).is_empty());
+    }
+}
@@ -0,0 +1,64 @@
+//! Reflector caches of the controllers, shared with the read-only HTTP API so it can answer
+//! from memory instead of querying the API server on every request.
+
+use crate::api::v1alpha1::game_result_types::GameResult;
+use crate::api::v1alpha1::the_league_types::TheLeague;
+
+use futures::FutureExt;
+use k8s_openapi::api::core::v1::ConfigMap;
+use kube::runtime::reflector::Store;
+use std::sync::{Arc, OnceLock};
+
+/// Stores of the controllers, set once each controller is created
+#[derive(Clone, Default)]
+pub struct Caches {
+    leagues: Arc<OnceLock<Store<TheLeague>>>,
+    results: Arc<OnceLock<Store<GameResult>>>,
+    policy: Arc<OnceLock<Store<ConfigMap>>>,
+}
+
+impl Caches {
+    /// Share the store of the TheLeague controller
+    pub fn share_leagues(&self, store: Store<TheLeague>) {
+        let _ = self.leagues.set(store);
+    }
+
+    /// Share the store of the GameResult controller
+    pub fn share_results(&self, store: Store<GameResult>) {
+        let _ = self.results.set(store);
+    }
+
+    /// Share the store of the validation policy ConfigMap
+    pub fn share_policy(&self, store: Store<ConfigMap>) {
+        let _ = self.policy.set(store);
+    }
+
+    /// Store of the leagues, or None until the initial list of the TheLeague controller
+    /// has completed
+    pub fn leagues(&self) -> Option<&Store<TheLeague>> {
+        self.leagues.get().filter(|store| synced(store))
+    }
+
+    /// Store of the results, or None until the initial list of the GameResult controller
+    /// has completed
+    pub fn results(&self) -> Option<&Store<GameResult>> {
+        self.results.get().filter(|store| synced(store))
+    }
+
+    /// Store of the validation policy ConfigMap, or None until its initial list has completed
+    pub fn policy(&self) -> Option<&Store<ConfigMap>> {
+        self.policy.get().filter(|store| synced(store))
+    }
+
+    /// Stores of the leagues and of the results, or None until both are synced
+    pub fn synced(&self) -> Option<(&Store<TheLeague>, &Store<GameResult>)> {
+        Some((self.leagues()?, self.results()?))
+    }
+}
+
+fn synced<K: kube::Resource + Clone>(store: &Store<K>) -> bool
+where
+    K::DynamicType: Eq + std::hash::Hash + Clone,
+{
+    matches!(store.wait_until_ready().now_or_never(), Some(Ok(())))
+}
@@ -0,0 +1,124 @@
+use crate::api::v1alpha1::the_league_types::{TableRow, TheLeague};
+use crate::controller::{apply_params, delete_params, propagation};
+use crate::standings::{self, TableEntry};
+
+use k8s_openapi::api::core::v1::ConfigMap;
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+use kube::api::Patch;
+use kube::{Api, Client, Resource, ResourceExt};
+use std::collections::BTreeMap;
+use tracing::info;
+
+/// Key of the JSON encoded table in the exported ConfigMap
+pub const STANDINGS_JSON_KEY: &str = "standings.json";
+/// Key of the plain-text table in the exported ConfigMap
+pub const STANDINGS_TEXT_KEY: &str = "standings.txt";
+
+/// Name of the ConfigMap a league exports its standings to
+pub fn standings_configmap_name(league_name: &str) -> String {
+    format!("{}-standings", league_name)
+}
+
+/// Keep the `<league>-standings` ConfigMap in sync with the table when the league opted in
+/// with `exportConfigMap`, and remove it again once the league opts out. A ConfigMap of that
+/// name the league doesn't own is left alone. The JSON table is
+/// written from `rows`, which carry the branding of the teams.
+pub async fn sync_standings_configmap(
+    client: &Client,
+    league: &TheLeague,
+    table: &[TableEntry],
+    rows: &[TableRow],
+) -> Result<(), kube::Error> {
+    let namespace = league.namespace().unwrap_or_default();
+    let name = standings_configmap_name(&league.name_any());
+    let configmap_api: Api<ConfigMap> = Api::namespaced(client.clone(), &namespace);
+
+    if !league.spec.export_config_map {
+        let exported = configmap_api
+            .get_opt(&name)
+            .await?
+            .is_some_and(|configmap| exported_by(&configmap, league));
+        if !exported {
+            return Ok(());
+        }
+        return match configmap_api.delete(&name, &delete_params()).await {
+            Ok(_) => {
+                info!("Removed standings ConfigMap '{}/{}'", namespace, name);
+                Ok(())
+            }
+            Err(kube::Error::Api(e)) if e.code == 404 => Ok(()),
+            Err(e) => Err(e),
+        };
+    }
+
+    let mut data = BTreeMap::new();
+    data.insert(
+        STANDINGS_JSON_KEY.to_string(),
+        serde_json::to_string_pretty(rows).map_err(kube::Error::SerdeError)?,
+    );
+    data.insert(
+        STANDINGS_TEXT_KEY.to_string(),
+        standings::render_table(table),
+    );
+
+    let mut configmap = ConfigMap {
+        metadata: ObjectMeta {
+            name: Some(name.clone()),
+            namespace: Some(namespace),
+            // Owned by the league so the export is garbage-collected with it
+            owner_references: league.controller_owner_ref(&()).map(|o| vec![o]),
+            ..Default::default()
+        },
+        data: Some(data),
+        ..Default::default()
+    };
+    propagation::propagated(league).apply_to(&mut configmap.metadata);
+    configmap_api
+        .patch(&name, &apply_params(), &Patch::Apply(&configmap))
+        .await?;
+    Ok(())
+}
+
+/// Whether `configmap` is the export of `league`, i.e. the league is its controller
+fn exported_by(configmap: &ConfigMap, league: &TheLeague) -> bool {
+    let Some(uid) = league.uid() else {
+        return false;
+    };
+    configmap
+        .owner_references()
+        .iter()
+        .any(|owner| owner.controller == Some(true) && owner.uid == uid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_only_configmaps_owned_by_the_league_are_exports() {
+        let league: TheLeague = serde_json::from_value(json!({
+            "apiVersion": "bexxmodd.com/v1alpha1",
+            "kind": "TheLeague",
+            "metadata": {"name": "premier", "namespace": "default", "uid": "league-uid"},
+            "spec": {"maxTeams": 4, "teams": []}
+        }))
+        .unwrap();
+        let mut configmap = ConfigMap {
+            metadata: ObjectMeta {
+                name: Some(standings_configmap_name("premier")),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert!(!exported_by(&configmap, &league));
+
+        let mut owner = league.controller_owner_ref(&()).unwrap();
+        configmap.metadata.owner_references = Some(vec![owner.clone()]);
+        assert!(exported_by(&configmap, &league));
+
+        owner.uid = "other-uid".to_string();
+        configmap.metadata.owner_references = Some(vec![owner]);
+        assert!(!exported_by(&configmap, &league));
+    }
+}
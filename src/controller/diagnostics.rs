@@ -0,0 +1,55 @@
+//! Runtime diagnostics of the controller process, served on `/debug/runtime` of the admin
+//! server, on localhost, to look into stalls in production.
+//!
+//! The scheduler of a kube controller doesn't expose its queue, so the reconciliations
+//! running per kind stand in for the queue depth. With the `tokio-console` feature (built with
+//! `RUSTFLAGS="--cfg tokio_unstable"`) the tasks can also be inspected live with tokio-console.
+
+use crate::controller::Context;
+use crate::controller::metrics::KindLabels;
+
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Snapshot of the Tokio runtime, the reconciliations and the reflector stores
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RuntimeReport {
+    /// Worker threads of the runtime
+    pub workers: usize,
+    /// Tasks spawned on the runtime that haven't completed
+    pub alive_tasks: usize,
+    /// Tasks waiting in the global queue of the runtime
+    pub global_queue_depth: usize,
+    /// Reconciliations running per kind
+    pub reconciles_in_flight: BTreeMap<&'static str, i64>,
+    /// Objects in the store of each controller per kind
+    pub store_sizes: BTreeMap<&'static str, usize>,
+}
+
+/// Snapshot of the runtime the caller runs on and of the controllers sharing `context`
+pub fn report(context: &Context) -> RuntimeReport {
+    let runtime = tokio::runtime::Handle::current().metrics();
+    let store_sizes = context.readiness.store_sizes();
+    let reconciles_in_flight = store_sizes
+        .keys()
+        .map(|kind| {
+            let labels = KindLabels {
+                kind: kind.to_string(),
+            };
+            let in_flight = context
+                .metrics
+                .controller
+                .reconciles_in_flight
+                .get_or_create(&labels);
+            (*kind, in_flight.get())
+        })
+        .collect();
+    RuntimeReport {
+        workers: runtime.num_workers(),
+        alive_tasks: runtime.num_alive_tasks(),
+        global_queue_depth: runtime.global_queue_depth(),
+        reconciles_in_flight,
+        store_sizes,
+    }
+}
@@ -0,0 +1,260 @@
+use crate::api::v1alpha1::game_result_types::GameResult;
+use crate::controller::{gameresult_controller, references, seasons};
+use crate::standings;
+
+use kube::ResourceExt;
+use kube::runtime::reflector::ObjectRef;
+use std::sync::Arc;
+
+/// Condition type set to True on results that duplicate an earlier result of the same fixture
+pub const DUPLICATE_CONDITION: &str = "Duplicate";
+
+/// Annotation on a GameResult naming the result (in the same namespace) it supersedes
+pub const REPLACES_ANNOTATION: &str = "bexxmodd.com/replaces";
+
+/// Whether two results record the same fixture: same league, round and pairing of teams,
+/// in either home/away orientation, counted in the same season. Playoff series and tournament
+/// replays play a pairing several times in a round, so only league games can record the same
+/// fixture.
+pub fn same_fixture(a: &GameResult, b: &GameResult) -> bool {
+    let [a_home, a_away] = &a.spec.teams;
+    let [b_home, b_away] = &b.spec.teams;
+    references::league_namespace(a.namespace(), &a.spec.league_namespace)
+        == references::league_namespace(b.namespace(), &b.spec.league_namespace)
+        && a.spec.league_name == b.spec.league_name
+        && a.round() == b.round()
+        && seasons::counted_season(&a.spec) == seasons::counted_season(&b.spec)
+        && a.spec.is_league_game()
+        && b.spec.is_league_game()
+        && ((a_home == b_home && a_away == b_away) || (a_home == b_away && a_away == b_home))
+}
+
+/// Results of `results` to recheck when `changed` is created, updated or deleted: the other
+/// results of its league with the same pairing of teams. Any round is matched, so moving a
+/// result to another round also rechecks the results of the fixture it left.
+pub fn fixture_peers(
+    results: Vec<Arc<GameResult>>,
+    changed: &GameResult,
+) -> Vec<ObjectRef<GameResult>> {
+    let pairing = |r: &GameResult| {
+        let mut teams = r.spec.teams.clone();
+        teams.sort();
+        teams
+    };
+    let league = |r: &GameResult| {
+        (
+            references::league_namespace(r.namespace(), &r.spec.league_namespace),
+            r.spec.league_name.clone(),
+        )
+    };
+    results
+        .into_iter()
+        .filter(|r| !is_same_object(r, changed))
+        .filter(|r| league(r) == league(changed) && pairing(r) == pairing(changed))
+        .map(|r| ObjectRef::from_obj(r.as_ref()))
+        .collect()
+}
+
+/// Whether `by` carries a `bexxmodd.com/replaces` annotation naming `result`
+fn replaces(by: &GameResult, result: &GameResult) -> bool {
+    by.namespace() == result.namespace()
+        && by
+            .annotations()
+            .get(REPLACES_ANNOTATION)
+            .is_some_and(|name| *name == result.name_any())
+}
+
+fn is_same_object(a: &GameResult, b: &GameResult) -> bool {
+    a.namespace() == b.namespace() && a.name_any() == b.name_any()
+}
+
+fn qualified_name(result: &GameResult) -> String {
+    format!(
+        "{}/{}",
+        result.namespace().unwrap_or_default(),
+        result.name_any()
+    )
+}
+
+/// Check `result` against the other results of its league.
+///
+/// Returns the (reason, message) of the Duplicate condition when the result must be excluded
+/// from the standings: either a later result replaced it via `bexxmodd.com/replaces`, or an
+/// older accepted result of the same fixture exists that hasn't been replaced. Age is the
+/// creation timestamp, with the qualified name as tiebreak so exactly one result of a fixture
+/// wins. A rejected result never counts as the original, so it can't hold back a valid one.
+///
+/// Postponed and abandoned games never decide a fixture, so they neither are duplicates
+/// nor make the replayed game one.
+pub fn duplicate_of(result: &GameResult, results: &[GameResult]) -> Option<(String, String)> {
+    let decided = |r: &GameResult| standings::outcome_scores(&r.spec.result).is_some();
+    if !decided(result) {
+        return None;
+    }
+    let peers: Vec<&GameResult> = results
+        .iter()
+        .filter(|r| !is_same_object(r, result) && r.metadata.deletion_timestamp.is_none())
+        .filter(|r| same_fixture(r, result) && decided(r))
+        .collect();
+
+    if let Some(replacement) = peers.iter().find(|p| replaces(p, result)) {
+        return Some((
+            "Replaced".to_string(),
+            format!("replaced by '{}'", qualified_name(replacement)),
+        ));
+    }
+
+    let age = |r: &GameResult| {
+        (
+            r.metadata.creation_timestamp.as_ref().map(|t| t.0),
+            qualified_name(r),
+        )
+    };
+    peers
+        .iter()
+        .filter(|p| !replaces(result, p) && !peers.iter().any(|q| replaces(q, p)))
+        .filter(|p| age(p) < age(result) && gameresult_controller::is_accepted(p))
+        .min_by_key(|p| age(p))
+        .map(|original| {
+            (
+                "DuplicateResult".to_string(),
+                format!(
+                    "same league, round and teams as '{}'; set the '{}' annotation to supersede it",
+                    qualified_name(original),
+                    REPLACES_ANNOTATION
+                ),
+            )
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::v1alpha1::game_result_types::{GameOutcome, GameResultSpec};
+    use k8s_openapi::apimachinery::pkg::apis::meta::v1::Time;
+    use k8s_openapi::chrono::{TimeZone, Utc};
+    use serde_json::json;
+
+    fn result(name: &str, created: i64, teams: [&str; 2]) -> GameResult {
+        let [home, away] = teams;
+        let mut result = GameResult::new(name, GameResultSpec::game(home, away));
+        result.metadata.namespace = Some("default".to_string());
+        result.metadata.creation_timestamp = Some(Time(Utc.timestamp_opt(created, 0).unwrap()));
+        set_rejected(&mut result, false);
+        result
+    }
+
+    fn set_rejected(result: &mut GameResult, rejected: bool) {
+        result.status = serde_json::from_value(json!({
+            "conditions": [{
+                "type": "Rejected",
+                "status": if rejected { "True" } else { "False" },
+                "reason": "Validated",
+                "message": "",
+                "lastTransitionTime": "2024-08-17T17:00:00Z",
+            }],
+        }))
+        .unwrap();
+    }
+
+    #[test]
+    fn test_newer_result_of_same_fixture_is_duplicate() {
+        let original = result("a", 100, ["Tigers", "Sharks"]);
+        let resubmitted = result("b", 200, ["Sharks", "Tigers"]);
+        let other = result("c", 50, ["Tigers", "Eagles"]);
+        let all = vec![original.clone(), resubmitted.clone(), other.clone()];
+
+        assert_eq!(duplicate_of(&original, &all), None);
+        assert_eq!(duplicate_of(&other, &all), None);
+        let (reason, message) = duplicate_of(&resubmitted, &all).unwrap();
+        assert_eq!(reason, "DuplicateResult");
+        assert!(message.contains("default/a"));
+    }
+
+    #[test]
+    fn test_rejected_result_is_never_the_original() {
+        let mut invalid = result("a", 100, ["Tigers", "Sharks"]);
+        set_rejected(&mut invalid, true);
+        let valid = result("b", 200, ["Tigers", "Sharks"]);
+        let all = vec![invalid, valid.clone()];
+
+        assert_eq!(duplicate_of(&valid, &all), None);
+    }
+
+    #[test]
+    fn test_deleting_a_result_rechecks_its_fixture() {
+        let winner = result("a", 100, ["Tigers", "Sharks"]);
+        let mut moved = result("b", 200, ["Sharks", "Tigers"]);
+        moved.spec.round_number = 2;
+        let other = result("c", 50, ["Tigers", "Eagles"]);
+        // The deleted winner is gone from the store, its peers are still there
+        let store = vec![Arc::new(moved), Arc::new(other)];
+
+        let peers = fixture_peers(store, &winner);
+        assert_eq!(peers, vec![ObjectRef::new("b").within("default")]);
+    }
+
+    #[test]
+    fn test_replaces_annotation_supersedes_original() {
+        let original = result("a", 100, ["Tigers", "Sharks"]);
+        let mut correction = result("b", 200, ["Tigers", "Sharks"]);
+        correction
+            .annotations_mut()
+            .insert(REPLACES_ANNOTATION.to_string(), "a".to_string());
+        let all = vec![original.clone(), correction.clone()];
+
+        assert_eq!(duplicate_of(&correction, &all), None);
+        let (reason, _) = duplicate_of(&original, &all).unwrap();
+        assert_eq!(reason, "Replaced");
+    }
+
+    #[test]
+    fn test_different_round_is_not_duplicate() {
+        let first = result("a", 100, ["Tigers", "Sharks"]);
+        let mut second_leg = result("b", 200, ["Sharks", "Tigers"]);
+        second_leg.spec.round_number = 2;
+        let all = vec![first, second_leg.clone()];
+
+        assert_eq!(duplicate_of(&second_leg, &all), None);
+    }
+
+    #[test]
+    fn test_unstamped_result_counts_in_the_first_season() {
+        let unstamped = result("a", 100, ["Tigers", "Sharks"]);
+        let mut first_season = result("b", 200, ["Sharks", "Tigers"]);
+        first_season.spec.season = Some(1);
+        let mut next_season = result("c", 300, ["Tigers", "Sharks"]);
+        next_season.spec.season = Some(2);
+
+        let all = vec![unstamped.clone(), first_season.clone()];
+        assert!(duplicate_of(&first_season, &all).is_some());
+        let all = vec![unstamped, next_season.clone()];
+        assert_eq!(duplicate_of(&next_season, &all), None);
+    }
+
+    #[test]
+    fn test_corrected_round_is_the_round_of_the_fixture() {
+        let original = result("a", 100, ["Tigers", "Sharks"]);
+        let mut misfiled = result("b", 200, ["Tigers", "Sharks"]);
+        misfiled.spec.round_number = 4;
+        let all = vec![original.clone(), misfiled.clone()];
+        assert_eq!(duplicate_of(&misfiled, &all), None);
+
+        misfiled.status.as_mut().unwrap().corrected_round = Some(1);
+        let all = vec![original, misfiled.clone()];
+        assert!(duplicate_of(&misfiled, &all).is_some());
+    }
+
+    #[test]
+    fn test_replay_of_postponed_game_is_not_duplicate() {
+        let mut postponed = result("a", 100, ["Tigers", "Sharks"]);
+        postponed.spec.result = GameOutcome::Postponed {
+            rescheduled_to: None,
+        };
+        let replay = result("b", 200, ["Tigers", "Sharks"]);
+        let all = vec![postponed.clone(), replay.clone()];
+
+        assert_eq!(duplicate_of(&replay, &all), None);
+        assert_eq!(duplicate_of(&postponed, &all), None);
+    }
+}
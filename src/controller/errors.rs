@@ -0,0 +1,262 @@
+//! Aggregation of reconcile errors.
+//!
+//! A permanently broken object fails every time it is requeued, every few seconds. The first
+//! failure of an object is logged right away; identical failures of the same object are
+//! counted and summarized in one line per [`SUMMARY_INTERVAL`] instead of each being logged.
+//!
+//! New failures at or above the reporting threshold are also handed to the [`ErrorReporter`]s
+//! of the log, e.g. Sentry with the `sentry` feature, so new failure modes raise an alert.
+
+use crate::controller::metrics::KindLabels;
+use crate::controller::{Context, reconcile_id};
+
+use kube::runtime::finalizer;
+use kube::{Resource, ResourceExt};
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::sync::{Arc, Mutex};
+use tokio::time::{Duration, Instant};
+use tracing::warn;
+
+/// Interval between the log lines of an object failing with the same error
+pub const SUMMARY_INTERVAL: Duration = Duration::from_secs(300);
+
+/// Severity of a reconcile error, ordered from the least severe
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// Expected to go away on a retry, e.g. a conflicting write or a throttled request
+    Warning,
+    /// Anything else
+    #[default]
+    Error,
+}
+
+impl Severity {
+    /// Lowest severity reported to the [`ErrorReporter`]s, from `ERROR_REPORT_SEVERITY`
+    /// (`warning` or `error`, the default)
+    pub fn threshold() -> Severity {
+        match std::env::var("ERROR_REPORT_SEVERITY").as_deref() {
+            Ok("warning") => Severity::Warning,
+            _ => Severity::Error,
+        }
+    }
+}
+
+/// Errors of the reconcilers that know how severe they are
+pub trait Classify {
+    fn severity(&self) -> Severity;
+}
+
+impl Classify for kube::Error {
+    fn severity(&self) -> Severity {
+        match self {
+            // Conflicts, throttling and objects deleted under the reconciler are retried fine
+            kube::Error::Api(response) if matches!(response.code, 404 | 409 | 429) => {
+                Severity::Warning
+            }
+            _ => Severity::Error,
+        }
+    }
+}
+
+impl<E: Classify + std::error::Error> Classify for finalizer::Error<E> {
+    fn severity(&self) -> Severity {
+        match self {
+            finalizer::Error::ApplyFailed(err) | finalizer::Error::CleanupFailed(err) => {
+                err.severity()
+            }
+            finalizer::Error::AddFinalizer(err) | finalizer::Error::RemoveFinalizer(err) => {
+                err.severity()
+            }
+            finalizer::Error::UnnamedObject | finalizer::Error::InvalidFinalizer => Severity::Error,
+        }
+    }
+}
+
+/// A new failure of a reconciliation, with the object it failed on
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ErrorReport {
+    pub kind: &'static str,
+    pub namespace: String,
+    pub name: String,
+    pub uid: Option<String>,
+    pub reconcile_id: Option<u64>,
+    pub severity: Severity,
+    pub error: String,
+}
+
+/// Destination of the new reconcile failures, e.g. an error tracker
+pub trait ErrorReporter: Send + Sync {
+    fn report(&self, report: &ErrorReport);
+}
+
+/// Failing object and the error it fails with
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+struct ErrorKey {
+    kind: &'static str,
+    namespace: String,
+    name: String,
+    error: String,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Logged {
+    at: Instant,
+    suppressed: u64,
+}
+
+/// Reconcile errors logged recently, shared by the controllers
+#[derive(Clone, Default)]
+pub struct ErrorLog {
+    logged: Arc<Mutex<HashMap<ErrorKey, Logged>>>,
+    reporters: Vec<Arc<dyn ErrorReporter>>,
+    threshold: Severity,
+}
+
+impl ErrorLog {
+    /// Also hand the new failures of at least `threshold` severity to `reporter`
+    pub fn with_reporter(
+        mut self,
+        reporter: impl ErrorReporter + 'static,
+        threshold: Severity,
+    ) -> Self {
+        self.reporters.push(Arc::new(reporter));
+        self.threshold = threshold;
+        self
+    }
+
+    /// Record a failure at `now`. Returns the number of identical failures suppressed since
+    /// it was last logged if it is to be logged, or None if it is suppressed too.
+    fn record(&self, key: ErrorKey, now: Instant) -> Option<u64> {
+        let mut logged = self.logged.lock().unwrap_or_else(|e| e.into_inner());
+        // Objects that recovered or were deleted stop failing; forget them.
+        logged.retain(|_, l| now.duration_since(l.at) < SUMMARY_INTERVAL * 2);
+        match logged.get_mut(&key) {
+            Some(l) if now.duration_since(l.at) < SUMMARY_INTERVAL => {
+                l.suppressed += 1;
+                None
+            }
+            previous => {
+                let suppressed = previous.map(|l| l.suppressed).unwrap_or_default();
+                logged.insert(
+                    key,
+                    Logged {
+                        at: now,
+                        suppressed: 0,
+                    },
+                );
+                Some(suppressed)
+            }
+        }
+    }
+}
+
+/// Log the failed reconciliation of the `kind` object `object`, unless it failed the same
+/// way less than [`SUMMARY_INTERVAL`] ago; suppressed failures are counted in the metrics.
+/// A new failure is also handed to the reporters if it is severe enough.
+pub fn report<K, E>(ctx: &Context, kind: &'static str, object: &K, err: &E)
+where
+    K: Resource,
+    E: Display + Classify,
+{
+    let key = ErrorKey {
+        kind,
+        namespace: object.namespace().unwrap_or_default(),
+        name: object.name_any(),
+        error: err.to_string(),
+    };
+    let Some(suppressed) = ctx.errors.record(key, Instant::now()) else {
+        ctx.metrics
+            .controller
+            .suppressed_errors
+            .get_or_create(&KindLabels {
+                kind: kind.to_string(),
+            })
+            .inc();
+        return;
+    };
+    let namespace = object.namespace().unwrap_or_default();
+    if suppressed == 0 {
+        warn!(kind, namespace, name = %object.name_any(), "Reconcile failed: {}", err);
+        let severity = err.severity();
+        if severity >= ctx.errors.threshold {
+            let report = ErrorReport {
+                kind,
+                namespace,
+                name: object.name_any(),
+                uid: object.uid(),
+                reconcile_id: reconcile_id(),
+                severity,
+                error: err.to_string(),
+            };
+            for reporter in &ctx.errors.reporters {
+                reporter.report(&report);
+            }
+        }
+    } else {
+        warn!(
+            kind,
+            namespace,
+            name = %object.name_any(),
+            suppressed,
+            "Reconcile failed: {} (failed the same way {} more times in the last {}s)",
+            err,
+            suppressed,
+            SUMMARY_INTERVAL.as_secs()
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(name: &str, error: &str) -> ErrorKey {
+        ErrorKey {
+            kind: "TheLeague",
+            namespace: "default".to_string(),
+            name: name.to_string(),
+            error: error.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_conflicts_are_less_severe() {
+        let api_error = |code| {
+            kube::Error::Api(kube::core::ErrorResponse {
+                status: "Failure".to_string(),
+                message: String::new(),
+                reason: String::new(),
+                code,
+            })
+        };
+        assert_eq!(api_error(409).severity(), Severity::Warning);
+        assert_eq!(api_error(403).severity(), Severity::Error);
+        let finalizer_error = finalizer::Error::<kube::Error>::ApplyFailed(api_error(429));
+        assert_eq!(finalizer_error.severity(), Severity::Warning);
+        assert!(Severity::Error >= Severity::Warning);
+    }
+
+    #[test]
+    fn test_repeated_errors_are_summarized() {
+        let errors = ErrorLog::default();
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+
+        assert_eq!(errors.record(key("premier", "forbidden"), at(0)), Some(0));
+        assert_eq!(errors.record(key("premier", "forbidden"), at(5)), None);
+        assert_eq!(errors.record(key("premier", "forbidden"), at(10)), None);
+        // Another object, or another error of the same object, is logged right away.
+        assert_eq!(errors.record(key("serie-a", "forbidden"), at(10)), Some(0));
+        assert_eq!(errors.record(key("premier", "not found"), at(10)), Some(0));
+
+        assert_eq!(errors.record(key("premier", "forbidden"), at(300)), Some(2));
+        assert_eq!(errors.record(key("premier", "forbidden"), at(305)), None);
+
+        // An object failing again long after it recovered starts over.
+        assert_eq!(
+            errors.record(key("premier", "forbidden"), at(3600)),
+            Some(0)
+        );
+    }
+}
@@ -0,0 +1,127 @@
+use crate::api::v1alpha1::game_result_types::{GameOutcome, GameResult, GameResultSpec};
+use crate::api::v1alpha1::the_league_types::TheLeague;
+use crate::controller::{duplicates, post_params};
+use crate::schedule::{self, Schedule};
+
+use kube::api::ListParams;
+use kube::{Api, Client, Resource, ResourceExt};
+use tracing::info;
+
+/// Create a GameResult with a Pending outcome for every fixture of `schedule`, the schedule of
+/// `league`, that doesn't have one yet. Existing results are never touched, so scorekeeper edits are kept.
+///
+/// `time` is set to the kickoff of the fixture for leagues with a calendar, and left unset
+/// otherwise; it is expected to be set with the outcome.
+/// Returns the number of results created.
+pub async fn prepopulate_results(
+    client: &Client,
+    league: &TheLeague,
+    schedule: &Schedule,
+) -> Result<usize, kube::Error> {
+    let namespace = league.namespace().unwrap_or_default();
+    let league_name = league.name_any();
+    let result_api: Api<GameResult> = Api::namespaced(client.clone(), &namespace);
+
+    let existing = result_api.list(&ListParams::default()).await?.items;
+    let mut created = 0;
+    for result in missing_results(league, schedule, &existing) {
+        match result_api.create(&post_params(), &result).await {
+            Ok(_) => created += 1,
+            // Created since the list
+            Err(kube::Error::Api(e)) if e.code == 409 => {}
+            Err(e) => return Err(e),
+        }
+    }
+
+    if created > 0 {
+        info!(
+            "Created {} pending GameResults for league '{}/{}'",
+            created, namespace, league_name
+        );
+    }
+    Ok(created)
+}
+
+/// Pending results of the fixtures of `schedule` in the current season of `league` that have
+/// no result in `existing`, the results of the league namespace, neither under the name the
+/// controller gives them nor filed by a scorekeeper under another name.
+pub fn missing_results(
+    league: &TheLeague,
+    schedule: &Schedule,
+    existing: &[GameResult],
+) -> Vec<GameResult> {
+    let namespace = league.namespace();
+    let league_name = league.name_any();
+    let season = league.status.as_ref().and_then(|s| s.current_season);
+    schedule
+        .fixtures()
+        .map(|fixture| {
+            let name = schedule::fixture_name(&league_name, season, fixture);
+            let mut result = GameResult::new(
+                &name,
+                GameResultSpec {
+                    league_name: league_name.clone(),
+                    league_namespace: None,
+                    round_number: fixture.round,
+                    season,
+                    playoff: None,
+                    tournament: None,
+                    teams: [fixture.home.clone(), fixture.away.clone()],
+                    time: fixture.kickoff.clone(),
+                    originally_scheduled_at: None,
+                    result: GameOutcome::Pending {},
+                    scorers: Vec::new(),
+                    assists: Vec::new(),
+                    cards: Vec::new(),
+                    venue: None,
+                    attendance: None,
+                },
+            );
+            result.metadata.namespace = namespace.clone();
+            result.metadata.owner_references = league.owner_ref(&()).map(|o| vec![o]);
+            result
+        })
+        .filter(|result| {
+            !existing
+                .iter()
+                .any(|r| r.name_any() == result.name_any() || duplicates::same_fixture(r, result))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_only_fixtures_without_a_result_are_created() {
+        let league: TheLeague = serde_json::from_value(json!({
+            "apiVersion": "bexxmodd.com/v1alpha1",
+            "kind": "TheLeague",
+            "metadata": {"name": "premier", "namespace": "default"},
+            "spec": {
+                "maxTeams": 4,
+                "teams": [{"name": "Tigers"}, {"name": "Sharks"}, {"name": "Eagles"}],
+            }
+        }))
+        .unwrap();
+        let schedule = schedule::league_schedule(&league.spec).unwrap();
+        let all = missing_results(&league, &schedule, &[]);
+        assert_eq!(all.len(), 3);
+        assert!(all.iter().all(|r| r.spec.time.is_none()));
+
+        // One fixture was pre-created, another filed by hand under a name of its own
+        let precreated = all[0].clone();
+        let [home, away] = all[1].spec.teams.clone();
+        let mut filed = GameResult::new(
+            "tigers-sharks",
+            GameResultSpec::game(&away, &home).in_round(all[1].spec.round_number),
+        );
+        filed.metadata.namespace = Some("default".to_string());
+
+        let missing = missing_results(&league, &schedule, &[precreated, filed]);
+        assert_eq!(missing.len(), 1);
+        assert_eq!(missing[0].name_any(), all[2].name_any());
+    }
+}
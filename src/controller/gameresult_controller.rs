@@ -0,0 +1,908 @@
+use crate::api::conditions::{ConditionStatus, Conditions};
+use crate::api::v1alpha1::game_result_types::{GameOutcome, GameResult, GameResultSpec};
+use crate::api::v1alpha1::the_league_types::{Enforcement, NotificationEvent, TheLeague};
+use crate::controller::duplicates::{self, DUPLICATE_CONDITION};
+use crate::controller::notifications::{self, Notification};
+use crate::controller::references::{self, LEAGUE_RESOLVED_CONDITION, LeagueResolution};
+use crate::controller::stamps;
+use crate::controller::{
+    Context, await_spec_change, errors, patch_params, teams, traced, validation_policy, watched_api,
+};
+use crate::standings;
+use crate::validation;
+
+use futures::channel::mpsc;
+use futures::{StreamExt, TryStreamExt};
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::Time;
+use k8s_openapi::chrono;
+use kube::api::{ListParams, Patch};
+use kube::runtime::events::{Event, EventType};
+use kube::runtime::reflector::{self, ObjectRef, Store};
+use kube::runtime::{WatchStreamExt, controller::Controller as KubeController, watcher};
+use kube::{Api, Client, Resource, ResourceExt, runtime::controller::Action};
+use serde_json::json;
+use std::sync::Arc;
+use tokio::time::Duration;
+use tracing::{info, warn};
+
+/// Condition type set to True on results that fail validation and are excluded from standings
+pub const REJECTED_CONDITION: &str = "Rejected";
+
+/// Condition type set to True on results whose league does not exist
+pub const ORPHAN_RESULT_CONDITION: &str = "OrphanResult";
+
+/// Condition type set to True on pending results whose kickoff has passed
+pub const AWAITING_RESULT_CONDITION: &str = "AwaitingResult";
+
+/// Annotation marking a spec change of an applied result as a deliberate correction.
+/// Its value is recorded in the ResultCorrected event and the controller removes it once
+/// the corrected result has been applied.
+pub const RECOMPUTE_ANNOTATION: &str = "bexxmodd.com/recompute";
+
+/// Shortest and longest requeue delay for a result whose league does not exist (yet)
+const ORPHAN_MIN_BACKOFF: Duration = Duration::from_secs(10);
+const ORPHAN_MAX_BACKOFF: Duration = Duration::from_secs(600);
+
+/// Requeue delay for a result that has been orphaned for `orphaned_for`.
+///
+/// Grows with the time the result has been orphaned, so a league created right after its
+/// results is picked up quickly while long-standing orphans are only rechecked occasionally.
+/// (Creating the league also triggers a reconcile through the league watch.)
+pub fn orphan_backoff(orphaned_for: Duration) -> Duration {
+    (orphaned_for / 2).clamp(ORPHAN_MIN_BACKOFF, ORPHAN_MAX_BACKOFF)
+}
+
+/// Periodic resync of results that are not waiting for their kickoff
+const RESYNC_INTERVAL: Duration = Duration::from_secs(3600);
+
+/// Delay until the next reconcile of a result. Games still to be played are rechecked right
+/// at their kickoff, so the AwaitingResult reminder follows the current schedule.
+pub fn next_requeue(spec: &GameResultSpec, now: chrono::DateTime<chrono::Utc>) -> Duration {
+    let Some(kickoff) = spec.time.as_ref() else {
+        return RESYNC_INTERVAL;
+    };
+    if !matches!(spec.result, GameOutcome::Pending {}) {
+        return RESYNC_INTERVAL;
+    }
+    (kickoff.0 - now)
+        .to_std()
+        .map_or(RESYNC_INTERVAL, |until_kickoff| {
+            until_kickoff.clamp(Duration::from_secs(1), RESYNC_INTERVAL)
+        })
+}
+
+/// The kickoff a result was moved away from, if its `time` changed since the controller last
+/// observed it. Results already counted in the standings are corrected, not rescheduled.
+fn rescheduled_from(result: &GameResult) -> Option<Time> {
+    let status = result.status.as_ref()?;
+    let previous = status.scheduled_time.as_ref()?;
+    let time = result.spec.time.as_ref()?;
+    (status.applied_generation.is_none() && previous != time).then(|| previous.clone())
+}
+
+/// The current season of the league, for results of a league running in seasons that
+/// don't name their season yet. Results already counted in the standings are left alone:
+/// their spec can't change outside a correction, and they count in the first season.
+fn unstamped_season(result: &GameResult, league: &TheLeague) -> Option<u32> {
+    if league.spec.season_policy.is_none()
+        || result.spec.season.is_some()
+        || applied_generation(result).is_some()
+    {
+        return None;
+    }
+    league.status.as_ref().and_then(|s| s.current_season)
+}
+
+/// Whether a result has been validated against its league at its current generation,
+/// is not a duplicate, and may therefore be counted in the standings.
+pub fn is_accepted(result: &GameResult) -> bool {
+    let conditions = result
+        .status
+        .as_ref()
+        .map(|s| s.conditions.as_slice())
+        .unwrap_or_default();
+    is_accepted_with(&Conditions::new(conditions, result.metadata.generation))
+}
+
+fn is_accepted_with(conditions: &Conditions) -> bool {
+    conditions.is_false(REJECTED_CONDITION)
+        && conditions.is_current(REJECTED_CONDITION)
+        && !conditions.is_true(DUPLICATE_CONDITION)
+}
+
+/// Generation of the spec last counted in the standings, if any
+pub fn applied_generation(result: &GameResult) -> Option<i64> {
+    result.status.as_ref().and_then(|s| s.applied_generation)
+}
+
+/// Whether a result carries the correction annotation
+pub fn has_recompute_annotation(result: &GameResult) -> bool {
+    result.annotations().contains_key(RECOMPUTE_ANNOTATION)
+}
+
+/// Violation reported for a spec change to an applied result outside the correction path
+pub fn immutable_spec_violation() -> validation::Violation {
+    validation::Violation::new(
+        "ImmutableSpec",
+        format!(
+            "spec changed after the result was applied to the standings; \
+             set the '{}' annotation to correct it",
+            RECOMPUTE_ANNOTATION
+        ),
+    )
+}
+
+/// Controller validating GameResults against the league they reference
+pub struct GameResultReconciler {
+    context: Arc<Context>,
+    controller: KubeController<GameResult>,
+}
+
+impl GameResultReconciler {
+    /// Create a new GameResultReconciler
+    pub fn new(context: Arc<Context>) -> Self {
+        let result_api: Api<GameResult> = watched_api(context.client.clone());
+        let league_api: Api<TheLeague> = watched_api(context.client.clone());
+
+        // Creating, replacing or deleting a result can change which result of its fixture
+        // counts: the other results of the same fixture are rechecked. The peers are found on
+        // the watch of the results themselves, which also passes deleted results, so deleting
+        // the counted result of a fixture lets its duplicate take over.
+        let (store, writer) = reflector::store();
+        let (recheck, rechecks) = mpsc::unbounded();
+        let peers = store.clone();
+        let results = watcher(result_api, watcher::Config::default())
+            .default_backoff()
+            .reflect(writer)
+            .touched_objects()
+            .inspect_ok(move |result| {
+                for peer in duplicates::fixture_peers(peers.state(), result) {
+                    let _ = recheck.unbounded_send(peer);
+                }
+            });
+        let controller = KubeController::for_stream(results, store.clone());
+        context.readiness.track("GameResult", controller.store());
+        context.caches.share_results(controller.store());
+        // Roster changes can turn results valid or invalid: revalidate every result of the league.
+        let controller = controller.reconcile_on(rechecks).watches(
+            league_api,
+            watcher::Config::default(),
+            move |league| {
+                let namespace = league.namespace().unwrap_or_default();
+                let name = league.name_any();
+                store
+                    .state()
+                    .into_iter()
+                    .filter(|r| {
+                        references::league_namespace(r.namespace(), &r.spec.league_namespace)
+                            == namespace
+                            && r.spec.league_name == name
+                    })
+                    .map(|r| ObjectRef::from_obj(r.as_ref()))
+                    .collect::<Vec<_>>()
+            },
+        );
+
+        Self {
+            context,
+            controller,
+        }
+    }
+
+    /// Reconcile a GameResult resource (static method)
+    pub async fn reconcile(
+        result: Arc<GameResult>,
+        ctx: Arc<Context>,
+    ) -> Result<Action, kube::Error> {
+        let name = result.name_any();
+        let namespace = result.namespace().unwrap_or_default();
+        let league_ns =
+            references::league_namespace(result.namespace(), &result.spec.league_namespace);
+        let league_name = &result.spec.league_name;
+        info!(
+            "reconcile request: gameresult {} of league {}",
+            name, league_name
+        );
+
+        let generation = result.metadata.generation;
+        let mut conditions = Conditions::new(
+            result
+                .status
+                .as_ref()
+                .map(|s| s.conditions.as_slice())
+                .unwrap_or_default(),
+            generation,
+        );
+        let mut applied = applied_generation(&result);
+        let mut applied_points = result.status.as_ref().and_then(|s| s.applied_points);
+        let mut newly_applied = None;
+        let mut corrected_round = result.status.as_ref().and_then(|s| s.corrected_round);
+        let mut changed = false;
+
+        let action =
+            match references::resolve_league(&ctx.client, &namespace, &league_ns, league_name)
+                .await?
+            {
+                LeagueResolution::Resolved(league) => {
+                    conditions.set(
+                        LEAGUE_RESOLVED_CONDITION,
+                        ConditionStatus::True,
+                        "LeagueFound",
+                        "league reference resolved",
+                    );
+                    conditions.set(
+                        ORPHAN_RESULT_CONDITION,
+                        ConditionStatus::False,
+                        "LeagueFound",
+                        "league reference resolved",
+                    );
+
+                    let tampered = applied.is_some()
+                        && applied != generation
+                        && !has_recompute_annotation(&result);
+                    if tampered {
+                        // The reconciler can't restore the old spec: take the result out of the
+                        // standings until it's corrected through the documented path.
+                        let violation = immutable_spec_violation();
+                        warn!("GameResult '{}' rejected: {}", name, violation.message);
+                        conditions.set(
+                            REJECTED_CONDITION,
+                            ConditionStatus::True,
+                            &violation.reason,
+                            &violation.message,
+                        );
+                    } else {
+                        if let Some(season) = unstamped_season(&result, &league) {
+                            return stamp_season(&result, &ctx, season).await;
+                        }
+                        if let Some(previous) = rescheduled_from(&result)
+                            && let Some(kickoff) = &result.spec.time
+                        {
+                            return reschedule(&result, &ctx, previous, kickoff).await;
+                        }
+
+                        // Rosters of referenced Teams count like embedded ones; a missing
+                        // Team leaves an empty roster and is reported on the league.
+                        let (league_spec, _) = teams::resolve_teams(&ctx.client, &league).await?;
+
+                        // Lenient leagues count a game filed under the wrong round in the round
+                        // scheduling its pairing instead of rejecting it. The correction is
+                        // recorded in the status; the spec stays as submitted.
+                        let correction = validation::corrected_round(&result.spec, &league_spec);
+                        if let Some(round) = correction
+                            && corrected_round != correction
+                        {
+                            publish_round_correction(&result, &ctx, round).await?;
+                        }
+                        changed |= corrected_round != correction;
+                        corrected_round = correction;
+                        let mut spec = result.spec.clone();
+                        spec.round_number = correction.unwrap_or(spec.round_number);
+
+                        let violations = validation::validate_game_result(&spec, &league_spec);
+                        match validation::summarize(&violations) {
+                            Some((reason, message)) => {
+                                info!("GameResult '{}' rejected: {}", name, message);
+                                conditions.set(
+                                    REJECTED_CONDITION,
+                                    ConditionStatus::True,
+                                    &reason,
+                                    &message,
+                                );
+                            }
+                            None => {
+                                conditions.set(
+                                    REJECTED_CONDITION,
+                                    ConditionStatus::False,
+                                    "Valid",
+                                    "result is consistent with its league",
+                                );
+                                // Owner references cannot cross namespaces.
+                                if league_ns == namespace {
+                                    ensure_league_owner(&ctx, &result, &league).await?;
+                                }
+                            }
+                        }
+
+                        // Duplicates the validation policy doesn't enforce are reported but
+                        // still counted in the standings.
+                        let policy = validation_policy::current(&ctx.client, &ctx.caches).await;
+                        let duplicate = match policy.duplicate_result {
+                            Enforcement::Off => None,
+                            _ => {
+                                let results = match ctx.caches.results() {
+                                    Some(store) => {
+                                        cached_league_results(store, &league_ns, &league)
+                                    }
+                                    None => {
+                                        league_results(&ctx.client, &league_ns, &league).await?
+                                    }
+                                };
+                                duplicates::duplicate_of(&result, &results)
+                            }
+                        };
+                        match duplicate {
+                            Some((reason, message))
+                                if policy.duplicate_result == Enforcement::Enforce =>
+                            {
+                                info!("GameResult '{}' is a duplicate: {}", name, message);
+                                conditions.set(
+                                    DUPLICATE_CONDITION,
+                                    ConditionStatus::True,
+                                    &reason,
+                                    &message,
+                                );
+                            }
+                            Some((_, message)) => {
+                                info!(
+                                    "GameResult '{}' is a tolerated duplicate: {}",
+                                    name, message
+                                );
+                                conditions.set(
+                                    DUPLICATE_CONDITION,
+                                    ConditionStatus::False,
+                                    "NotEnforced",
+                                    &format!("{}; duplicates are not enforced", message),
+                                );
+                            }
+                            None if policy.duplicate_result == Enforcement::Off => {
+                                conditions.remove(DUPLICATE_CONDITION);
+                            }
+                            None => {
+                                conditions.set(
+                                    DUPLICATE_CONDITION,
+                                    ConditionStatus::False,
+                                    "Unique",
+                                    "no other result records this fixture",
+                                );
+                            }
+                        }
+
+                        let now = chrono::Utc::now();
+                        if let Some(kickoff) = &result.spec.time
+                            && matches!(result.spec.result, GameOutcome::Pending {})
+                            && kickoff.0 <= now
+                        {
+                            let message = format!(
+                                "game kicked off at {}, the result is not recorded yet",
+                                kickoff.0.to_rfc3339()
+                            );
+                            let became_due = conditions.set(
+                                AWAITING_RESULT_CONDITION,
+                                ConditionStatus::True,
+                                "KickoffPassed",
+                                &message,
+                            );
+                            if became_due {
+                                let event = Event {
+                                    type_: EventType::Normal,
+                                    reason: "ResultDue".to_string(),
+                                    note: Some(message),
+                                    action: "AwaitResult".to_string(),
+                                    secondary: None,
+                                };
+                                ctx.publish(&event, &result.object_ref(&())).await?;
+                            }
+                        } else {
+                            conditions.set(
+                                AWAITING_RESULT_CONDITION,
+                                ConditionStatus::False,
+                                "NotDue",
+                                "the result is recorded or the game has not kicked off yet",
+                            );
+                        }
+
+                        // Only completed games count in the standings; pending, postponed and
+                        // abandoned results may still be filled in freely.
+                        let counted = standings::outcome_scores(&result.spec.result).is_some();
+                        if counted && is_accepted_with(&conditions) && applied != generation {
+                            applied = generation;
+                            changed = true;
+                            newly_applied = Some((league.clone(), applied_points));
+                            applied_points = standings::points_awarded(
+                                &result.spec.result,
+                                &league.spec.points_policy,
+                            )
+                            .map(|(home, away)| [home, away]);
+                        }
+                    }
+                    Action::requeue(next_requeue(&result.spec, chrono::Utc::now()))
+                }
+                LeagueResolution::Unresolved { reason, message } => {
+                    conditions.set(
+                        LEAGUE_RESOLVED_CONDITION,
+                        ConditionStatus::False,
+                        &reason,
+                        &message,
+                    );
+                    if reason != "LeagueNotFound" {
+                        Action::requeue(Duration::from_secs(60))
+                    } else {
+                        let became_orphan = conditions.set(
+                            ORPHAN_RESULT_CONDITION,
+                            ConditionStatus::True,
+                            &reason,
+                            &message,
+                        );
+                        if became_orphan {
+                            warn!("GameResult '{}' is orphaned: {}", name, message);
+                            let event = Event {
+                                type_: EventType::Warning,
+                                reason: ORPHAN_RESULT_CONDITION.to_string(),
+                                note: Some(message),
+                                action: "ResolveLeague".to_string(),
+                                secondary: None,
+                            };
+                            ctx.publish(&event, &result.object_ref(&())).await?;
+                        }
+
+                        let orphaned_since = conditions
+                            .get(ORPHAN_RESULT_CONDITION)
+                            .map(|c| c.last_transition_time.0)
+                            .unwrap_or_else(chrono::Utc::now);
+                        let orphaned_for = (chrono::Utc::now() - orphaned_since)
+                            .to_std()
+                            .unwrap_or_default();
+                        Action::requeue(orphan_backoff(orphaned_for))
+                    }
+                }
+            };
+
+        let scheduled_time = result
+            .status
+            .as_ref()
+            .and_then(|s| s.scheduled_time.as_ref());
+        changed |= scheduled_time != result.spec.time.as_ref();
+        let score = standings::outcome_scores(&result.spec.result)
+            .map(|(home, away)| format!("{}-{}", home, away));
+        changed |= result.status.as_ref().and_then(|s| s.score.as_ref()) != score.as_ref();
+        let result_api: Api<GameResult> = Api::namespaced(ctx.client.clone(), &namespace);
+        if changed || conditions.changed() {
+            let patch = Patch::Merge(json!({
+                "status": {
+                    "conditions": conditions,
+                    "appliedGeneration": applied,
+                    "appliedPoints": applied_points,
+                    "correctedRound": corrected_round,
+                    "scheduledTime": result.spec.time,
+                    "score": score,
+                }
+            }));
+            result_api
+                .patch_status(&name, &patch_params(), &patch)
+                .await?;
+        }
+
+        // Only announce an applied result once its status records it, so a failed status
+        // write doesn't announce it again on the retry.
+        if let Some((league, previous_points)) = newly_applied {
+            publish_applied(&result, &ctx, &league, previous_points).await?;
+        }
+        if applied.is_some() && applied == generation && has_recompute_annotation(&result) {
+            finish_correction(&result, &ctx).await?;
+        }
+
+        stamps::stamp_reconciled(&result_api, &name, result.status.as_ref()).await?;
+        Ok(action)
+    }
+
+    /// Handle errors that occur during reconciliation (static method)
+    pub fn error_policy(object: Arc<GameResult>, err: &kube::Error, ctx: Arc<Context>) -> Action {
+        errors::report(&ctx, "GameResult", object.as_ref(), err);
+        Action::requeue(Duration::from_secs(5))
+    }
+
+    pub fn stream(self) -> impl futures::Future<Output = ()> {
+        let context = self.context.clone();
+        let metrics = &self.context.metrics.controller;
+        let results = self.controller.shutdown_on_signal().run(
+            |object, ctx| {
+                let reconcile = traced(
+                    "GameResult",
+                    object.as_ref(),
+                    GameResultReconciler::reconcile(object.clone(), ctx),
+                );
+                metrics.measure("GameResult", reconcile)
+            },
+            GameResultReconciler::error_policy,
+            context,
+        );
+        self.context
+            .heartbeat
+            .beating("GameResult", results)
+            .for_each(move |result| {
+                metrics.observe("GameResult", &result);
+                futures::future::ready(())
+            })
+    }
+}
+
+/// Change in the points of the home and away team when a spec awarding `awarded` replaces
+/// one awarding `previous` in the standings
+pub fn points_change(awarded: (u32, u32), previous: Option<[u32; 2]>) -> (i64, i64) {
+    let [previous_home, previous_away] = previous.unwrap_or_default();
+    (
+        i64::from(awarded.0) - i64::from(previous_home),
+        i64::from(awarded.1) - i64::from(previous_away),
+    )
+}
+
+/// Publish the score of a result applied to the standings, and the change in points it makes
+/// to the standings, as an event on both the result and its league. A correction changes the
+/// points of the `previous` applied spec, if any.
+async fn publish_applied(
+    result: &GameResult,
+    ctx: &Context,
+    league: &TheLeague,
+    previous: Option<[u32; 2]>,
+) -> Result<(), kube::Error> {
+    let spec = &result.spec;
+    let [home, away] = &spec.teams;
+    let (Some((score_home, score_away)), Some((points_home, points_away))) = (
+        standings::outcome_scores(&spec.result),
+        standings::points_awarded(&spec.result, &league.spec.points_policy),
+    ) else {
+        return Ok(());
+    };
+    let (points_home, points_away) = points_change((points_home, points_away), previous);
+    let note = format!(
+        "round {}: {} {}-{} {}; {} {:+} pts, {} {:+} pts",
+        spec.round_number, home, score_home, score_away, away, home, points_home, away, points_away
+    );
+    let event = Event {
+        type_: EventType::Normal,
+        reason: "ResultApplied".to_string(),
+        note: Some(note),
+        action: "ApplyResult".to_string(),
+        secondary: None,
+    };
+    ctx.publish(&event, &result.object_ref(&())).await?;
+    ctx.publish(&event, &league.object_ref(&())).await?;
+
+    let notification = Notification {
+        event: NotificationEvent::ResultRecorded,
+        message: format!(
+            "{} {}-{} {} (round {})",
+            home, score_home, score_away, away, spec.round_number
+        ),
+        details: json!({
+            "result": result.name_any(),
+            "round": spec.round_number,
+            "teams": spec.teams,
+            "score": [score_home, score_away],
+            "points": [points_home, points_away],
+        }),
+    };
+    notifications::notify(&ctx.client, league, notification).await;
+    Ok(())
+}
+
+/// Record an applied correction as an event and remove the correction annotation,
+/// so the next spec change needs a new one.
+async fn finish_correction(result: &GameResult, ctx: &Context) -> Result<(), kube::Error> {
+    let name = result.name_any();
+    let note = result
+        .annotations()
+        .get(RECOMPUTE_ANNOTATION)
+        .cloned()
+        .unwrap_or_default();
+    info!(
+        "GameResult '{}' corrected to generation {:?}: {}",
+        name, result.metadata.generation, note
+    );
+    let event = Event {
+        type_: EventType::Normal,
+        reason: "ResultCorrected".to_string(),
+        note: Some(format!(
+            "generation {:?} applied to the standings: {}",
+            result.metadata.generation, note
+        )),
+        action: "Recompute".to_string(),
+        secondary: None,
+    };
+    ctx.publish(&event, &result.object_ref(&())).await?;
+
+    let result_api: Api<GameResult> =
+        Api::namespaced(ctx.client.clone(), &result.namespace().unwrap_or_default());
+    let patch = Patch::Merge(json!({
+        "metadata": { "annotations": { RECOMPUTE_ANNOTATION: null } }
+    }));
+    result_api.patch(&name, &patch_params(), &patch).await?;
+    Ok(())
+}
+
+/// Add `league` to the owner references of a validated result, so the result is
+/// garbage-collected together with its league.
+async fn ensure_league_owner(
+    ctx: &Context,
+    result: &GameResult,
+    league: &TheLeague,
+) -> Result<(), kube::Error> {
+    let Some(owner) = league.owner_ref(&()) else {
+        return Ok(());
+    };
+    let mut owners = result.owner_references().to_vec();
+    if owners.iter().any(|o| o.uid == owner.uid) {
+        return Ok(());
+    }
+    owners.push(owner);
+
+    let name = result.name_any();
+    let result_api: Api<GameResult> =
+        Api::namespaced(ctx.client.clone(), &result.namespace().unwrap_or_default());
+    let patch = Patch::Merge(json!({ "metadata": { "ownerReferences": owners } }));
+    result_api.patch(&name, &patch_params(), &patch).await?;
+    info!(
+        "GameResult '{}' is now owned by league '{}'",
+        name,
+        league.name_any()
+    );
+    Ok(())
+}
+
+/// Namespaces the results of `league` may live in: the league namespace and its allowed
+/// namespaces
+fn result_namespaces(league_ns: &str, league: &TheLeague) -> Vec<String> {
+    let mut namespaces = vec![league_ns.to_string()];
+    namespaces.extend(league.spec.allowed_namespaces.iter().cloned());
+    namespaces.sort();
+    namespaces.dedup();
+    namespaces
+}
+
+/// Whether `result` references `league` from one of the `namespaces` its results may live in
+fn references_league(
+    result: &GameResult,
+    namespaces: &[String],
+    league_ns: &str,
+    league: &TheLeague,
+) -> bool {
+    result
+        .namespace()
+        .is_some_and(|ns| namespaces.contains(&ns))
+        && references::league_namespace(result.namespace(), &result.spec.league_namespace)
+            == league_ns
+        && result.spec.league_name == league.name_any()
+}
+
+/// All results referencing `league`, from the league namespace and its allowed namespaces
+pub async fn league_results(
+    client: &Client,
+    league_ns: &str,
+    league: &TheLeague,
+) -> Result<Vec<GameResult>, kube::Error> {
+    let namespaces = result_namespaces(league_ns, league);
+    let mut results = Vec::new();
+    for ns in &namespaces {
+        let result_api: Api<GameResult> = Api::namespaced(client.clone(), ns);
+        results.extend(
+            result_api
+                .list(&ListParams::default())
+                .await?
+                .items
+                .into_iter()
+                .filter(|r| references_league(r, &namespaces, league_ns, league)),
+        );
+    }
+    Ok(results)
+}
+
+/// All results referencing `league` in the reflector `store` of the results, from the league
+/// namespace and its allowed namespaces
+pub fn cached_league_results(
+    store: &Store<GameResult>,
+    league_ns: &str,
+    league: &TheLeague,
+) -> Vec<GameResult> {
+    let namespaces = result_namespaces(league_ns, league);
+    store
+        .state()
+        .into_iter()
+        .filter(|r| references_league(r, &namespaces, league_ns, league))
+        .map(|r| r.as_ref().clone())
+        .collect()
+}
+
+/// Assign a result to the current season of its league.
+/// The spec update triggers a fresh reconcile of the result.
+async fn stamp_season(
+    result: &GameResult,
+    ctx: &Context,
+    season: u32,
+) -> Result<Action, kube::Error> {
+    let name = result.name_any();
+    info!("GameResult '{}' assigned to season {}", name, season);
+    let result_api: Api<GameResult> =
+        Api::namespaced(ctx.client.clone(), &result.namespace().unwrap_or_default());
+    let patch = Patch::Merge(json!({ "spec": { "season": season } }));
+    result_api.patch(&name, &patch_params(), &patch).await?;
+    Ok(await_spec_change())
+}
+
+/// Record that a game was moved away from `previous` to `kickoff`: remember the new kickoff,
+/// publish the change as an event and keep the first kickoff in `originallyScheduledAt`.
+/// The status is written first, so the reconcile the spec update triggers, which requeues for
+/// the new kickoff, doesn't see the move again.
+async fn reschedule(
+    result: &GameResult,
+    ctx: &Context,
+    previous: Time,
+    kickoff: &Time,
+) -> Result<Action, kube::Error> {
+    let name = result.name_any();
+    let namespace = result.namespace().unwrap_or_default();
+    let message = format!(
+        "kickoff moved from {} to {}",
+        previous.0.to_rfc3339(),
+        kickoff.0.to_rfc3339()
+    );
+    info!("GameResult '{}': {}", name, message);
+
+    let result_api: Api<GameResult> = Api::namespaced(ctx.client.clone(), &namespace);
+    let patch = Patch::Merge(json!({ "status": { "scheduledTime": kickoff } }));
+    result_api
+        .patch_status(&name, &patch_params(), &patch)
+        .await?;
+
+    let event = Event {
+        type_: EventType::Normal,
+        reason: "Rescheduled".to_string(),
+        note: Some(message),
+        action: "Reschedule".to_string(),
+        secondary: None,
+    };
+    ctx.publish(&event, &result.object_ref(&())).await?;
+
+    if result.spec.originally_scheduled_at.is_none() {
+        let patch = Patch::Merge(json!({ "spec": { "originallyScheduledAt": previous } }));
+        result_api.patch(&name, &patch_params(), &patch).await?;
+        return Ok(await_spec_change());
+    }
+    Ok(Action::requeue(next_requeue(
+        &result.spec,
+        chrono::Utc::now(),
+    )))
+}
+
+/// Publish that a result counts in `round`, the round its pairing is scheduled in, as an event.
+async fn publish_round_correction(
+    result: &GameResult,
+    ctx: &Context,
+    round: u32,
+) -> Result<(), kube::Error> {
+    let message = format!(
+        "round {} does not schedule '{}' vs '{}', counted in round {}",
+        result.spec.round_number, result.spec.teams[0], result.spec.teams[1], round
+    );
+    info!("GameResult '{}': {}", result.name_any(), message);
+    let event = Event {
+        type_: EventType::Normal,
+        reason: "RoundCorrected".to_string(),
+        note: Some(message),
+        action: "CorrectRound".to_string(),
+        secondary: None,
+    };
+    ctx.publish(&event, &result.object_ref(&())).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::v1alpha1::game_result_types::{DecidedBy, GameResultStatus};
+    use crate::api::v1alpha1::the_league_types::{
+        NextSeason, SeasonPolicy, TheLeagueSpec, TheLeagueStatus,
+    };
+    use crate::controller::seasons;
+
+    fn spec(result: GameOutcome, time: chrono::DateTime<chrono::Utc>) -> GameResultSpec {
+        GameResultSpec::game("Tigers", "Sharks")
+            .with_result(result)
+            .at(time)
+    }
+
+    #[test]
+    fn test_next_requeue_follows_kickoff_of_pending_games() {
+        let now = chrono::Utc::now();
+        let kickoff = now + chrono::Duration::minutes(20);
+        assert_eq!(
+            next_requeue(&spec(GameOutcome::Pending {}, kickoff), now),
+            Duration::from_secs(20 * 60)
+        );
+        // Far away and past kickoffs fall back to the periodic resync.
+        let far = now + chrono::Duration::days(3);
+        assert_eq!(
+            next_requeue(&spec(GameOutcome::Pending {}, far), now),
+            RESYNC_INTERVAL
+        );
+        let past = now - chrono::Duration::minutes(5);
+        assert_eq!(
+            next_requeue(&spec(GameOutcome::Pending {}, past), now),
+            RESYNC_INTERVAL
+        );
+        let played = GameOutcome::WinnerHomeTeam {
+            score_home: 1,
+            score_away: 0,
+            decided_by: DecidedBy::Regulation,
+            shootout: None,
+        };
+        assert_eq!(next_requeue(&spec(played, kickoff), now), RESYNC_INTERVAL);
+    }
+
+    #[test]
+    fn test_orphan_backoff_grows_and_is_bounded() {
+        assert_eq!(orphan_backoff(Duration::ZERO), ORPHAN_MIN_BACKOFF);
+        assert_eq!(
+            orphan_backoff(Duration::from_secs(120)),
+            Duration::from_secs(60)
+        );
+        assert_eq!(
+            orphan_backoff(Duration::from_secs(7 * 24 * 3600)),
+            ORPHAN_MAX_BACKOFF
+        );
+    }
+
+    #[test]
+    fn test_corrections_report_the_change_in_points() {
+        assert_eq!(points_change((3, 0), None), (3, 0));
+        // A 1-1 draw corrected to a home win
+        assert_eq!(points_change((3, 0), Some([1, 1])), (2, -1));
+        assert_eq!(points_change((1, 1), Some([1, 1])), (0, 0));
+    }
+
+    #[test]
+    fn test_league_results_are_read_from_the_store() {
+        let mut league =
+            TheLeague::new("premier", TheLeagueSpec::with_teams(&["Tigers", "Sharks"]));
+        league.spec.allowed_namespaces = vec!["scores".to_string()];
+        let result = |namespace: &str, name: &str, league_ns: Option<&str>| {
+            let mut result = GameResult::new(name, GameResultSpec::game("Tigers", "Sharks"));
+            result.metadata.namespace = Some(namespace.to_string());
+            result.spec.league_namespace = league_ns.map(str::to_string);
+            result
+        };
+        let (store, mut writer) = reflector::store();
+        writer.apply_watcher_event(&watcher::Event::Init);
+        for result in [
+            result("leagues", "home", None),
+            result("scores", "allowed", Some("leagues")),
+            // Not an allowed namespace of the league
+            result("elsewhere", "foreign", Some("leagues")),
+            result("scores", "other-league", None),
+        ] {
+            writer.apply_watcher_event(&watcher::Event::InitApply(result));
+        }
+        writer.apply_watcher_event(&watcher::Event::InitDone);
+
+        let mut names: Vec<String> = cached_league_results(&store, "leagues", &league)
+            .iter()
+            .map(|r| r.name_any())
+            .collect();
+        names.sort();
+        assert_eq!(names, ["allowed", "home"]);
+    }
+
+    #[test]
+    fn test_applied_results_are_not_stamped_with_the_season() {
+        let mut league =
+            TheLeague::new("premier", TheLeagueSpec::with_teams(&["Tigers", "Sharks"]));
+        league.spec.season_policy = Some(SeasonPolicy {
+            duration_days: None,
+            next: NextSeason::Manual,
+        });
+        league.status = Some(TheLeagueStatus {
+            current_season: Some(2),
+            ..Default::default()
+        });
+        let mut result = GameResult::new("a", GameResultSpec::game("Tigers", "Sharks"));
+        assert_eq!(unstamped_season(&result, &league), Some(2));
+
+        // Counted before the league ran in seasons: stamping it would change an applied spec.
+        result.metadata.generation = Some(1);
+        result.status = Some(GameResultStatus {
+            applied_generation: Some(1),
+            ..Default::default()
+        });
+        assert_eq!(unstamped_season(&result, &league), None);
+        assert_eq!(seasons::counted_season(&result.spec), seasons::FIRST_SEASON);
+    }
+}
@@ -0,0 +1,125 @@
+//! Heartbeat of the reconcile loops, reported on `/healthz`.
+//!
+//! Every controller beats when its stream yields a result: a reconcile, a requeue or a watch
+//! error. A tick is interleaved with the results so an idle controller keeps beating too; a
+//! loop that stopped being polled, because it ended or its task is wedged, stops beating and
+//! the liveness probe fails once its last beat is older than the deadline.
+
+use futures::{Stream, StreamExt, stream};
+use std::collections::BTreeMap;
+use std::future::ready;
+use std::sync::{Arc, Mutex};
+use tokio::time::{Duration, Instant};
+
+/// Interval of the ticks keeping an idle controller beating
+pub const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Age of the last beat after which a controller counts as wedged, unless
+/// `LIVENESS_DEADLINE_SECONDS` is set
+pub const DEFAULT_LIVENESS_DEADLINE: Duration = Duration::from_secs(120);
+
+/// Age of the last beat after which a controller counts as wedged
+pub fn liveness_deadline() -> Duration {
+    std::env::var("LIVENESS_DEADLINE_SECONDS")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .map(Duration::from_secs)
+        .unwrap_or(DEFAULT_LIVENESS_DEADLINE)
+}
+
+/// Last beat of each controller, shared by the controllers and the probe server
+#[derive(Clone, Default)]
+pub struct Heartbeat {
+    beats: Arc<Mutex<BTreeMap<&'static str, Instant>>>,
+}
+
+impl Heartbeat {
+    /// Record that the `kind` controller is alive
+    pub fn beat(&self, kind: &'static str) {
+        self.beats
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(kind, Instant::now());
+    }
+
+    /// Controllers whose last beat is older than `deadline`
+    pub fn stalled(&self, deadline: Duration) -> Vec<&'static str> {
+        self.beats
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .filter(|(_, beat)| beat.elapsed() > deadline)
+            .map(|(kind, _)| *kind)
+            .collect()
+    }
+
+    /// The `results` of the `kind` controller, beating on each of them and on a tick every
+    /// [`HEARTBEAT_INTERVAL`] until the results end
+    pub fn beating<S: Stream>(
+        &self,
+        kind: &'static str,
+        results: S,
+    ) -> impl Stream<Item = S::Item> + use<S> {
+        self.beat(kind);
+        let ticks = stream::unfold(
+            tokio::time::interval(HEARTBEAT_INTERVAL),
+            |mut interval| async move {
+                interval.tick().await;
+                Some((Beat::Tick, interval))
+            },
+        );
+        let results = results
+            .map(Beat::Result)
+            .chain(stream::once(ready(Beat::Done)));
+        let heartbeat = self.clone();
+        stream::select(results, ticks)
+            .take_while(|beat| ready(!matches!(beat, Beat::Done)))
+            .filter_map(move |beat| {
+                heartbeat.beat(kind);
+                ready(match beat {
+                    Beat::Result(result) => Some(result),
+                    Beat::Tick | Beat::Done => None,
+                })
+            })
+    }
+}
+
+enum Beat<T> {
+    Result(T),
+    Tick,
+    Done,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(start_paused = true)]
+    async fn test_controllers_stall_without_beats() {
+        let heartbeat = Heartbeat::default();
+        let deadline = Duration::from_secs(60);
+
+        let results: Vec<u32> = heartbeat
+            .beating("TheLeague", stream::iter([1, 2]))
+            .collect()
+            .await;
+        assert_eq!(results, [1, 2]);
+        assert!(heartbeat.stalled(deadline).is_empty());
+
+        // The stream ended, nothing beats for the TheLeague controller anymore.
+        tokio::time::advance(Duration::from_secs(61)).await;
+        heartbeat.beat("Standing");
+        assert_eq!(heartbeat.stalled(deadline), ["TheLeague"]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_idle_controllers_keep_beating() {
+        let heartbeat = Heartbeat::default();
+        let beating = heartbeat.beating("TheLeague", stream::pending::<()>());
+        tokio::pin!(beating);
+
+        let idle = tokio::time::timeout(Duration::from_secs(300), beating.next()).await;
+        assert!(idle.is_err());
+        assert!(heartbeat.stalled(HEARTBEAT_INTERVAL * 2).is_empty());
+    }
+}
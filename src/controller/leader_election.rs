@@ -0,0 +1,204 @@
+//! Leader election of the controller replicas over a Lease, equivalent to the leader election
+//! of controller-runtime.
+//!
+//! With `LEADER_ELECT=true` a replica only starts its controllers once it holds the Lease
+//! [`LEASE_NAME`] in its namespace, renews it every [`RETRY_PERIOD`] and exits when it can't
+//! for [`RENEW_DEADLINE`], so a standby replica takes over once the Lease expires after
+//! [`LEASE_DURATION`]. The probe, metrics and webhook servers run on every replica, and a
+//! standby reports ready while it waits, see [`LeaseState`](super::readiness::LeaseState).
+//!
+//! generate-rbac grants the leader election role exactly [`LEASE_VERBS`] on
+//! [`LEASE_RESOURCE`], the requests made here.
+
+use k8s_openapi::api::coordination::v1::{Lease, LeaseSpec};
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::{MicroTime, ObjectMeta};
+use k8s_openapi::chrono::{DateTime, Utc};
+use kube::api::PostParams;
+use kube::{Api, Client};
+use std::sync::OnceLock;
+use tokio::time::{Duration, Instant, sleep};
+use tracing::{info, warn};
+
+/// Name of the Lease the replicas elect their leader with
+pub const LEASE_NAME: &str = "theleague.bexxmodd.com";
+
+/// API group of Leases
+pub const LEASE_GROUP: &str = "coordination.k8s.io";
+
+/// Resource of Leases
+pub const LEASE_RESOURCE: &str = "leases";
+
+/// Verbs of the requests of [`LeaderElector`] on its Lease
+pub const LEASE_VERBS: [&str; 3] = ["get", "create", "update"];
+
+/// Time after its last renewal the Lease can be taken by another replica
+pub const LEASE_DURATION: Duration = Duration::from_secs(15);
+
+/// Time the leader keeps trying to renew the Lease before giving up its leadership
+pub const RENEW_DEADLINE: Duration = Duration::from_secs(10);
+
+/// Interval of the attempts to acquire or renew the Lease
+pub const RETRY_PERIOD: Duration = Duration::from_secs(2);
+
+/// Whether the replicas elect a leader, set with LEADER_ELECT=true
+pub fn enabled() -> bool {
+    static ENABLED: OnceLock<bool> = OnceLock::new();
+    *ENABLED
+        .get_or_init(|| std::env::var("LEADER_ELECT").is_ok_and(|v| v.eq_ignore_ascii_case("true")))
+}
+
+/// Identity of this replica in the Lease: its pod name, else its hostname
+pub fn identity() -> String {
+    std::env::var("POD_NAME")
+        .or_else(|_| std::env::var("HOSTNAME"))
+        .unwrap_or_else(|_| super::CONTROLLER_NAME.to_string())
+}
+
+/// Whether `identity` may take the Lease held as `spec` at `now`: it is free, held by
+/// `identity` already, or its holder didn't renew it within its duration.
+pub fn can_acquire(spec: &LeaseSpec, identity: &str, now: DateTime<Utc>) -> bool {
+    let Some(holder) = spec.holder_identity.as_deref().filter(|h| !h.is_empty()) else {
+        return true;
+    };
+    if holder == identity {
+        return true;
+    }
+    let duration = spec
+        .lease_duration_seconds
+        .map_or(LEASE_DURATION.as_secs() as i64, i64::from);
+    match &spec.renew_time {
+        Some(renewed) => now > renewed.0 + k8s_openapi::chrono::Duration::seconds(duration),
+        None => true,
+    }
+}
+
+/// LeaderElector acquires and renews the Lease of the replicas as `identity`.
+pub struct LeaderElector {
+    api: Api<Lease>,
+    identity: String,
+}
+
+impl LeaderElector {
+    /// Elector of the Lease in `namespace`, held as `identity`
+    pub fn new(client: Client, namespace: &str, identity: String) -> Self {
+        LeaderElector {
+            api: Api::namespaced(client, namespace),
+            identity,
+        }
+    }
+
+    /// Acquire the Lease, or renew it if this replica holds it. Whether this replica holds the
+    /// Lease afterwards.
+    pub async fn try_acquire_or_renew(&self) -> Result<bool, kube::Error> {
+        let now = Utc::now();
+        let Some(mut lease) = self.api.get_opt(LEASE_NAME).await? else {
+            let lease = Lease {
+                metadata: ObjectMeta {
+                    name: Some(LEASE_NAME.to_string()),
+                    ..Default::default()
+                },
+                spec: Some(LeaseSpec {
+                    holder_identity: Some(self.identity.clone()),
+                    lease_duration_seconds: Some(LEASE_DURATION.as_secs() as i32),
+                    acquire_time: Some(MicroTime(now)),
+                    renew_time: Some(MicroTime(now)),
+                    lease_transitions: Some(0),
+                    ..Default::default()
+                }),
+            };
+            return match self.api.create(&PostParams::default(), &lease).await {
+                Ok(_) => Ok(true),
+                // Another replica created it first
+                Err(kube::Error::Api(e)) if e.code == 409 => Ok(false),
+                Err(e) => Err(e),
+            };
+        };
+
+        let spec = lease.spec.get_or_insert_with(LeaseSpec::default);
+        if !can_acquire(spec, &self.identity, now) {
+            return Ok(false);
+        }
+        if spec.holder_identity.as_deref() != Some(&self.identity) {
+            spec.holder_identity = Some(self.identity.clone());
+            spec.acquire_time = Some(MicroTime(now));
+            spec.lease_transitions = Some(spec.lease_transitions.unwrap_or(0) + 1);
+        }
+        spec.lease_duration_seconds = Some(LEASE_DURATION.as_secs() as i32);
+        spec.renew_time = Some(MicroTime(now));
+        // The resourceVersion of the Lease read makes the update fail if another replica
+        // updated it in the meantime
+        match self
+            .api
+            .replace(LEASE_NAME, &PostParams::default(), &lease)
+            .await
+        {
+            Ok(_) => Ok(true),
+            Err(kube::Error::Api(e)) if e.code == 409 => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Wait until this replica holds the Lease
+    pub async fn acquire(&self) {
+        info!(
+            "Waiting to acquire lease {} as {}",
+            LEASE_NAME, self.identity
+        );
+        loop {
+            match self.try_acquire_or_renew().await {
+                Ok(true) => break,
+                Ok(false) => {}
+                Err(e) => warn!("Failed to acquire lease {}: {}", LEASE_NAME, e),
+            }
+            sleep(RETRY_PERIOD).await;
+        }
+        info!("Acquired lease {} as {}", LEASE_NAME, self.identity);
+    }
+
+    /// Renew the Lease held by this replica until it is lost, either to another replica or
+    /// because it couldn't be renewed for [`RENEW_DEADLINE`]
+    pub async fn hold(&self) {
+        let mut renewed = Instant::now();
+        loop {
+            sleep(RETRY_PERIOD).await;
+            match self.try_acquire_or_renew().await {
+                Ok(true) => renewed = Instant::now(),
+                Ok(false) => {
+                    warn!("Lease {} was taken by another replica", LEASE_NAME);
+                    return;
+                }
+                Err(e) => warn!("Failed to renew lease {}: {}", LEASE_NAME, e),
+            }
+            if renewed.elapsed() > RENEW_DEADLINE {
+                warn!(
+                    "Lease {} not renewed within {:?}",
+                    LEASE_NAME, RENEW_DEADLINE
+                );
+                return;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn held_by(holder: &str, renewed: i64) -> LeaseSpec {
+        LeaseSpec {
+            holder_identity: Some(holder.to_string()),
+            lease_duration_seconds: Some(15),
+            renew_time: Some(MicroTime(DateTime::from_timestamp(renewed, 0).unwrap())),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_can_acquire_free_own_or_expired_leases() {
+        let now = DateTime::from_timestamp(1_700_000_020, 0).unwrap();
+        assert!(can_acquire(&LeaseSpec::default(), "a", now));
+        assert!(can_acquire(&held_by("a", 1_700_000_019), "a", now));
+        assert!(!can_acquire(&held_by("b", 1_700_000_010), "a", now));
+        assert!(can_acquire(&held_by("b", 1_700_000_000), "a", now));
+    }
+}
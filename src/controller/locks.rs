@@ -0,0 +1,72 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::sync::{Mutex as AsyncMutex, OwnedMutexGuard};
+
+/// LeagueKey identifies a league by (namespace, name).
+type LeagueKey = (String, String);
+
+/// LeagueLocks is a keyed async mutex with one lock per league.
+///
+/// Table-wide computations (ranking, tiebreaks) take the lock of their league so
+/// concurrent reconciles of the same league are serialized, while different
+/// leagues still recompute in parallel.
+#[derive(Clone, Default)]
+pub struct LeagueLocks {
+    locks: Arc<Mutex<HashMap<LeagueKey, Arc<AsyncMutex<()>>>>>,
+}
+
+impl LeagueLocks {
+    /// Wait for and acquire the lock of the league `namespace/name`.
+    /// The lock is released when the returned guard is dropped.
+    pub async fn lock(&self, namespace: &str, name: &str) -> OwnedMutexGuard<()> {
+        let lock = {
+            let mut locks = self.locks.lock().unwrap_or_else(|e| e.into_inner());
+            // Forget locks nobody is holding or waiting for so the map doesn't grow forever.
+            locks.retain(|_, lock| Arc::strong_count(lock) > 1);
+            locks
+                .entry((namespace.to_string(), name.to_string()))
+                .or_default()
+                .clone()
+        };
+        lock.lock_owned().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_same_league_is_serialized() {
+        let locks = LeagueLocks::default();
+        let guard = locks.lock("default", "premier").await;
+
+        let other = locks.clone();
+        let waiter = tokio::spawn(async move { other.lock("default", "premier").await });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!waiter.is_finished(), "second lock must wait for the first");
+
+        drop(guard);
+        tokio::time::timeout(Duration::from_secs(1), waiter)
+            .await
+            .expect("lock should be acquired once released")
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_different_leagues_are_independent() {
+        let locks = LeagueLocks::default();
+        let _premier = locks.lock("default", "premier").await;
+
+        tokio::time::timeout(
+            Duration::from_secs(1),
+            locks.lock("default", "championship"),
+        )
+        .await
+        .expect("other league must not be blocked");
+        tokio::time::timeout(Duration::from_secs(1), locks.lock("other", "premier"))
+            .await
+            .expect("same league name in another namespace must not be blocked");
+    }
+}
@@ -0,0 +1,428 @@
+use crate::api::v1alpha1::game_result_types::{GameOutcome, GameResult};
+use crate::api::v1alpha1::match_types::{Match, MatchPhase, MatchSpec, MatchStatus};
+use crate::api::v1alpha1::referee_types::Referee;
+use crate::api::v1alpha1::the_league_types::TheLeague;
+use crate::api::v1alpha1::venue_types::Venue;
+use crate::controller::propagation;
+use crate::controller::stamps::Stamped;
+use crate::controller::venues::{self, BookingRequest};
+use crate::controller::{delete_params, patch_params, post_params, referees, seasons, teams};
+use crate::schedule::{self, Fixture, Schedule};
+use crate::standings;
+
+use k8s_openapi::chrono;
+use kube::api::{ListParams, Patch};
+use kube::{Api, Client, Resource, ResourceExt};
+use serde_json::json;
+use std::collections::HashSet;
+use tracing::info;
+
+/// Whether `result` records the game of `fixture`
+fn records(result: &GameResult, fixture: &MatchSpec) -> bool {
+    let [home, away] = &result.spec.teams;
+    result.spec.league_name == fixture.league_name
+        && result.spec.is_league_game()
+        && result.round() == fixture.round
+        && fixture
+            .season
+            .is_none_or(|s| seasons::counted_season(&result.spec) == s)
+        && ((*home == fixture.home && *away == fixture.away)
+            || (*home == fixture.away && *away == fixture.home))
+}
+
+/// Status of the match `spec` given the counted results of its league.
+///
+/// A result with a final score makes the match Played. Otherwise the latest postponed result
+/// makes it Postponed, and a pending result only links it. Matches without results keep
+/// their scheduled kickoff.
+pub fn match_status(spec: &MatchSpec, results: &[GameResult]) -> MatchStatus {
+    let games: Vec<&GameResult> = results.iter().filter(|r| records(r, spec)).collect();
+    let linked = |phase, result: &GameResult, kickoff| MatchStatus {
+        phase,
+        kickoff,
+        result: Some(result.name_any()),
+        referee: None,
+        venue: None,
+        ..Default::default()
+    };
+
+    if let Some(played) = games
+        .iter()
+        .find(|r| standings::outcome_scores(&r.spec.result).is_some())
+    {
+        return linked(MatchPhase::Played, played, played.spec.time.clone());
+    }
+    if let Some(postponed) = games
+        .iter()
+        .rev()
+        .find(|r| matches!(r.spec.result, GameOutcome::Postponed { .. }))
+    {
+        let rescheduled_to = match &postponed.spec.result {
+            GameOutcome::Postponed { rescheduled_to } => rescheduled_to.clone(),
+            _ => None,
+        };
+        return linked(MatchPhase::Postponed, postponed, rescheduled_to);
+    }
+    match games.last() {
+        Some(pending) => linked(
+            MatchPhase::Scheduled,
+            pending,
+            pending.spec.time.clone().or_else(|| spec.kickoff.clone()),
+        ),
+        None => MatchStatus {
+            kickoff: spec.kickoff.clone(),
+            ..Default::default()
+        },
+    }
+}
+
+/// The round of `matches` in progress at `now`: the earliest round with a match still to be
+/// played, once a match of it is played or due to kick off. None between rounds.
+pub fn round_in_progress(matches: &[Match], now: chrono::DateTime<chrono::Utc>) -> Option<u32> {
+    let played = |m: &Match| {
+        m.status
+            .as_ref()
+            .is_some_and(|s| s.phase == MatchPhase::Played)
+    };
+    let round = matches
+        .iter()
+        .filter(|m| !played(m))
+        .map(|m| m.spec.round)
+        .min()?;
+    let kickoff = |m: &Match| {
+        m.status
+            .as_ref()
+            .and_then(|s| s.kickoff.clone())
+            .or_else(|| m.spec.kickoff.clone())
+    };
+    matches
+        .iter()
+        .filter(|m| m.spec.round == round)
+        .any(|m| played(m) || kickoff(m).is_some_and(|k| k.0 <= now))
+        .then_some(round)
+}
+
+/// The Matches of `league` in its namespace
+pub async fn league_matches(
+    api: &Api<Match>,
+    league_name: &str,
+) -> Result<Vec<Match>, kube::Error> {
+    Ok(api
+        .list(&ListParams::default())
+        .await?
+        .items
+        .into_iter()
+        .filter(|m| m.spec.league_name == league_name)
+        .collect())
+}
+
+/// Create a Match for every fixture of `schedule`, the current schedule of `league`, and
+/// delete the Matches of fixtures that are no longer scheduled and were never played. Matches
+/// still to be played follow the kickoff of their fixture when the calendar of the league
+/// changes, and every Match carries the labels and annotations the league propagates.
+/// Returns the number of Matches created.
+pub async fn sync_matches(
+    client: &Client,
+    league: &TheLeague,
+    schedule: &Schedule,
+) -> Result<usize, kube::Error> {
+    let namespace = league.namespace().unwrap_or_default();
+    let league_name = league.name_any();
+    let match_api: Api<Match> = Api::namespaced(client.clone(), &namespace);
+    let season = league.status.as_ref().and_then(|s| s.current_season);
+
+    let fixtures: Vec<Fixture> = schedule.fixtures().cloned().collect();
+    let scheduled: HashSet<String> = fixtures
+        .iter()
+        .map(|f| schedule::fixture_name(&league_name, season, f))
+        .collect();
+
+    let existing = league_matches(&match_api, &league_name).await?;
+    for stale in existing.iter().filter(|m| {
+        m.spec.season == season
+            && !scheduled.contains(&m.name_any())
+            && m.status
+                .as_ref()
+                .is_none_or(|s| s.phase != MatchPhase::Played)
+    }) {
+        info!(
+            "Deleting Match '{}' no longer in the schedule",
+            stale.name_any()
+        );
+        match match_api.delete(&stale.name_any(), &delete_params()).await {
+            Ok(_) => {}
+            Err(kube::Error::Api(e)) if e.code == 404 => {}
+            Err(e) => return Err(e),
+        }
+    }
+
+    let propagated = propagation::propagated(league);
+    for current in existing
+        .iter()
+        .filter(|m| m.metadata.deletion_timestamp.is_none())
+    {
+        propagation::sync_metadata(&match_api, current, &propagated).await?;
+    }
+
+    let mut created = 0;
+    for fixture in &fixtures {
+        let name = schedule::fixture_name(&league_name, season, fixture);
+        if let Some(current) = existing.iter().find(|m| m.name_any() == name) {
+            let played = current
+                .status
+                .as_ref()
+                .is_some_and(|s| s.phase == MatchPhase::Played);
+            if !played && current.spec.kickoff != fixture.kickoff {
+                let patch = Patch::Merge(json!({ "spec": { "kickoff": fixture.kickoff } }));
+                match_api.patch(&name, &patch_params(), &patch).await?;
+            }
+            continue;
+        }
+        let mut scheduled_match = Match::new(
+            &name,
+            MatchSpec {
+                league_name: league_name.clone(),
+                season,
+                round: fixture.round,
+                home: fixture.home.clone(),
+                away: fixture.away.clone(),
+                kickoff: fixture.kickoff.clone(),
+            },
+        );
+        scheduled_match.metadata.owner_references = league.owner_ref(&()).map(|o| vec![o]);
+        propagated.apply_to(&mut scheduled_match.metadata);
+        match match_api.create(&post_params(), &scheduled_match).await {
+            Ok(_) => created += 1,
+            Err(kube::Error::Api(e)) if e.code == 409 => {}
+            Err(e) => return Err(e),
+        }
+    }
+
+    if created > 0 {
+        info!(
+            "Created {} Matches for league '{}/{}'",
+            created, namespace, league_name
+        );
+    }
+    Ok(created)
+}
+
+/// Link the Matches of `league` to the counted `results`, update their phase, assign the
+/// Referees of the namespace to them and book their Venues.
+pub async fn sync_match_status(
+    client: &Client,
+    league: &TheLeague,
+    results: &[GameResult],
+) -> Result<(), kube::Error> {
+    let namespace = league.namespace().unwrap_or_default();
+    let league_name = league.name_any();
+    let match_api: Api<Match> = Api::namespaced(client.clone(), &namespace);
+    let referee_api: Api<Referee> = Api::namespaced(client.clone(), &namespace);
+    let venue_api: Api<Venue> = Api::namespaced(client.clone(), &namespace);
+    let referees = referee_api.list(&ListParams::default()).await?.items;
+    let venues = venue_api.list(&ListParams::default()).await?.items;
+    let (spec, _) = teams::resolve_teams(client, league).await?;
+
+    let (scheduled_matches, other_matches): (Vec<Match>, Vec<Match>) = match_api
+        .list(&ListParams::default())
+        .await?
+        .items
+        .into_iter()
+        .partition(|m| m.spec.league_name == league_name);
+    let mut statuses: Vec<MatchStatus> = scheduled_matches
+        .iter()
+        .map(|m| MatchStatus {
+            referee: m.status.as_ref().and_then(|s| s.referee.clone()),
+            ..match_status(&m.spec, results)
+        })
+        .collect();
+    let assigned = referees::assign_referees(
+        &scheduled_matches
+            .iter()
+            .map(|m| &m.spec)
+            .zip(&statuses)
+            .collect::<Vec<_>>(),
+        &referees,
+    );
+    for (status, referee) in statuses.iter_mut().zip(assigned) {
+        status.referee = referee;
+    }
+
+    // A match is played at the venue of its result, or else at the home venue of its home
+    // team. Played and already booked matches keep their venue first.
+    let requested = |m: &Match, status: &MatchStatus| {
+        let result = results
+            .iter()
+            .find(|r| status.result.as_ref() == Some(&r.name_any()));
+        result.and_then(|r| r.spec.venue.clone()).or_else(|| {
+            spec.teams
+                .iter()
+                .find(|t| t.name == m.spec.home)
+                .and_then(|t| t.location.clone())
+        })
+    };
+    let venue_names: Vec<Option<String>> = scheduled_matches
+        .iter()
+        .zip(&statuses)
+        .map(|(m, status)| requested(m, status))
+        .collect();
+    let match_names: Vec<String> = scheduled_matches.iter().map(|m| m.name_any()).collect();
+    let mut order: Vec<usize> = (0..scheduled_matches.len()).collect();
+    order.sort_by_key(|&i| {
+        let previous = scheduled_matches[i].status.as_ref();
+        (
+            statuses[i].phase != MatchPhase::Played,
+            previous.is_none_or(|s| s.venue.is_none()),
+            statuses[i].kickoff.as_ref().map(|k| k.0),
+        )
+    });
+    let requests: Vec<BookingRequest> = order
+        .iter()
+        .map(|&i| BookingRequest {
+            fixture: &match_names[i],
+            venue: venue_names[i].as_deref(),
+            kickoff: statuses[i].kickoff.as_ref(),
+        })
+        .collect();
+    let booked: Vec<BookingRequest> = other_matches
+        .iter()
+        .filter_map(|m| {
+            let status = m.status.as_ref()?;
+            Some(BookingRequest {
+                fixture: m.metadata.name.as_deref().unwrap_or_default(),
+                venue: status.venue.as_deref(),
+                kickoff: status.kickoff.as_ref(),
+            })
+        })
+        .collect();
+    let bookings = venues::book_venues(&requests, &booked, &venues);
+    let mut conflicts = Vec::new();
+    for (&i, booking) in order.iter().zip(bookings) {
+        match booking {
+            Ok(venue) => statuses[i].venue = venue,
+            Err(conflict) => conflicts.push(format!("{}: {}", match_names[i], conflict)),
+        }
+    }
+    venues::report_conflicts(client, league, !venues.is_empty(), &conflicts).await?;
+
+    for (scheduled_match, mut status) in scheduled_matches.iter().zip(statuses) {
+        if scheduled_match
+            .status
+            .clone()
+            .map(Stamped::unstamped)
+            .as_ref()
+            == Some(&status)
+        {
+            continue;
+        }
+        status.stamp(chrono::Utc::now());
+        let mut patch = json!({ "status": status });
+        // A referee or venue that is no longer assigned has to be removed, not left out of
+        // the patch.
+        patch["status"]["referee"] = json!(status.referee);
+        patch["status"]["venue"] = json!(status.venue);
+        match_api
+            .patch_status(
+                &scheduled_match.name_any(),
+                &patch_params(),
+                &Patch::Merge(patch),
+            )
+            .await?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::v1alpha1::game_result_types::{DecidedBy, GameResultSpec};
+    use k8s_openapi::apimachinery::pkg::apis::meta::v1::Time;
+    use k8s_openapi::chrono::Utc;
+
+    fn result(name: &str, teams: [&str; 2], outcome: GameOutcome) -> GameResult {
+        GameResult::new(
+            name,
+            GameResultSpec::game(teams[0], teams[1]).with_result(outcome),
+        )
+    }
+
+    #[test]
+    fn test_match_status_follows_results_of_the_fixture() {
+        let spec = MatchSpec {
+            league_name: "premier".to_string(),
+            season: None,
+            round: 1,
+            home: "Tigers".to_string(),
+            away: "Sharks".to_string(),
+            kickoff: None,
+        };
+        assert_eq!(match_status(&spec, &[]), MatchStatus::default());
+
+        let when = Time(Utc::now());
+        let mut results = vec![
+            result("other", ["Eagles", "Bears"], GameOutcome::Draw { score: 0 }),
+            result(
+                "postponed",
+                ["Tigers", "Sharks"],
+                GameOutcome::Postponed {
+                    rescheduled_to: Some(when.clone()),
+                },
+            ),
+        ];
+        let status = match_status(&spec, &results);
+        assert_eq!(status.phase, MatchPhase::Postponed);
+        assert_eq!(status.kickoff, Some(when));
+        assert_eq!(status.result.as_deref(), Some("postponed"));
+
+        results.push(result(
+            "replay",
+            ["Sharks", "Tigers"],
+            GameOutcome::WinnerAwayTeam {
+                score_home: 0,
+                score_away: 1,
+                decided_by: DecidedBy::Regulation,
+                shootout: None,
+            },
+        ));
+        let status = match_status(&spec, &results);
+        assert_eq!(status.phase, MatchPhase::Played);
+        assert_eq!(status.result.as_deref(), Some("replay"));
+    }
+
+    #[test]
+    fn test_round_in_progress_starts_at_the_first_kickoff() {
+        let now = Utc::now();
+        let fixture = |round: u32, kickoff: chrono::DateTime<Utc>, phase: MatchPhase| {
+            let mut m = Match::new(
+                &format!("premier-{}-{}", round, kickoff.timestamp()),
+                MatchSpec {
+                    league_name: "premier".to_string(),
+                    season: None,
+                    round,
+                    home: "Tigers".to_string(),
+                    away: "Sharks".to_string(),
+                    kickoff: Some(Time(kickoff)),
+                },
+            );
+            m.status = Some(MatchStatus {
+                phase,
+                ..Default::default()
+            });
+            m
+        };
+        let hour = chrono::Duration::hours(1);
+        let mut matches = vec![
+            fixture(1, now - hour * 48, MatchPhase::Played),
+            fixture(2, now + hour, MatchPhase::Scheduled),
+            fixture(2, now + hour * 3, MatchPhase::Scheduled),
+        ];
+        assert_eq!(round_in_progress(&matches, now), None);
+        assert_eq!(round_in_progress(&matches, now + hour), Some(2));
+
+        matches[1].status.as_mut().unwrap().phase = MatchPhase::Played;
+        assert_eq!(round_in_progress(&matches, now), Some(2));
+
+        matches[2].status.as_mut().unwrap().phase = MatchPhase::Played;
+        assert_eq!(round_in_progress(&matches, now), None);
+    }
+}
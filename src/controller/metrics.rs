@@ -0,0 +1,301 @@
+//! Metrics of the reconcilers: reconciliations, their duration and failures, and watcher
+//! restarts, all per reconciled kind, and gauges of the leagues themselves.
+
+use crate::standings::TableEntry;
+
+use kube::runtime::controller;
+use prometheus_client::encoding::EncodeLabelSet;
+use prometheus_client::metrics::counter::Counter;
+use prometheus_client::metrics::family::Family;
+use prometheus_client::metrics::gauge::Gauge;
+use prometheus_client::metrics::histogram::{Histogram, exponential_buckets};
+use prometheus_client::registry::Registry;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Mutex;
+use tokio::time::Instant;
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+pub struct KindLabels {
+    pub kind: String,
+}
+
+/// Reconciliations, their duration and failures, and watcher restarts per kind
+#[derive(Clone)]
+pub struct ControllerMetrics {
+    pub reconciliations: Family<KindLabels, Counter>,
+    pub reconciles_in_flight: Family<KindLabels, Gauge>,
+    pub reconcile_duration: Family<KindLabels, Histogram>,
+    pub reconcile_errors: Family<KindLabels, Counter>,
+    pub suppressed_errors: Family<KindLabels, Counter>,
+    pub watcher_restarts: Family<KindLabels, Counter>,
+}
+
+impl Default for ControllerMetrics {
+    fn default() -> Self {
+        Self {
+            reconciliations: Family::default(),
+            reconciles_in_flight: Family::default(),
+            // 5ms up to about 40s, recomputing a large league's standings takes a while.
+            reconcile_duration: Family::new_with_constructor(|| {
+                Histogram::new(exponential_buckets(0.005, 2.0, 14))
+            }),
+            reconcile_errors: Family::default(),
+            suppressed_errors: Family::default(),
+            watcher_restarts: Family::default(),
+        }
+    }
+}
+
+impl ControllerMetrics {
+    pub fn register(&self, registry: &mut Registry) {
+        registry.register(
+            "reconciliations",
+            "Reconciliations per kind",
+            self.reconciliations.clone(),
+        );
+        registry.register(
+            "reconciles_in_flight",
+            "Reconciliations running per kind",
+            self.reconciles_in_flight.clone(),
+        );
+        registry.register(
+            "reconcile_duration_seconds",
+            "Duration of the reconciliations per kind",
+            self.reconcile_duration.clone(),
+        );
+        registry.register(
+            "reconcile_errors",
+            "Failed reconciliations per kind",
+            self.reconcile_errors.clone(),
+        );
+        registry.register(
+            "reconcile_errors_suppressed",
+            "Failed reconciliations not logged, repeating an error logged recently, per kind",
+            self.suppressed_errors.clone(),
+        );
+        registry.register(
+            "watcher_restarts",
+            "Restarts of the watches feeding the controllers per kind",
+            self.watcher_restarts.clone(),
+        );
+    }
+
+    /// Run the reconciliation `reconcile` of a `kind` object, counting and timing it
+    pub async fn measure<T, E>(
+        &self,
+        kind: &str,
+        reconcile: impl Future<Output = Result<T, E>>,
+    ) -> Result<T, E> {
+        let labels = KindLabels {
+            kind: kind.to_string(),
+        };
+        self.reconciliations.get_or_create(&labels).inc();
+        let in_flight = self.reconciles_in_flight.get_or_create(&labels).clone();
+        in_flight.inc();
+        let start = Instant::now();
+        let result = reconcile.await;
+        in_flight.dec();
+        self.reconcile_duration
+            .get_or_create(&labels)
+            .observe(start.elapsed().as_secs_f64());
+        if result.is_err() {
+            self.reconcile_errors.get_or_create(&labels).inc();
+        }
+        result
+    }
+
+    /// Count the watcher failures among the results of the `kind` controller. The watcher
+    /// restarts the watch after each of them.
+    pub fn observe<T, E1, E2>(&self, kind: &str, result: &Result<T, controller::Error<E1, E2>>)
+    where
+        E1: std::error::Error + 'static,
+        E2: std::error::Error + 'static,
+    {
+        if let Err(controller::Error::QueueError(_)) = result {
+            self.watcher_restarts
+                .get_or_create(&KindLabels {
+                    kind: kind.to_string(),
+                })
+                .inc();
+        }
+    }
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+pub struct LeagueLabels {
+    pub namespace: String,
+    pub league: String,
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+pub struct TeamLabels {
+    pub namespace: String,
+    pub league: String,
+    pub team: String,
+}
+
+/// Teams and games played per league, and points and rank per team, as of the last
+/// recomputation of each league table
+#[derive(Default)]
+pub struct LeagueMetrics {
+    pub teams: Family<LeagueLabels, Gauge>,
+    pub games_played: Family<LeagueLabels, Gauge>,
+    pub standing_points: Family<TeamLabels, Gauge>,
+    pub standing_rank: Family<TeamLabels, Gauge>,
+
+    /// Teams exported per league, to drop the series of teams that left
+    exported: Mutex<HashMap<LeagueLabels, Vec<String>>>,
+}
+
+impl LeagueMetrics {
+    pub fn register(&self, registry: &mut Registry) {
+        registry.register("teams", "Teams per league", self.teams.clone());
+        registry.register(
+            "games_played_total",
+            "League games played in the current season per league",
+            self.games_played.clone(),
+        );
+        registry.register(
+            "standing_points",
+            "Points of each team in its league table",
+            self.standing_points.clone(),
+        );
+        registry.register(
+            "standing_rank",
+            "Rank of each team in its league table",
+            self.standing_rank.clone(),
+        );
+    }
+
+    /// Export the `table` of the league `namespace/league` after `games` league games
+    pub fn set_table(&self, namespace: &str, league: &str, games: usize, table: &[TableEntry]) {
+        let labels = LeagueLabels {
+            namespace: namespace.to_string(),
+            league: league.to_string(),
+        };
+        self.teams.get_or_create(&labels).set(table.len() as i64);
+        self.games_played.get_or_create(&labels).set(games as i64);
+
+        let teams: Vec<String> = table.iter().map(|e| e.team.clone()).collect();
+        let previous = self
+            .exported
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(labels.clone(), teams.clone())
+            .unwrap_or_default();
+        for team in previous.iter().filter(|t| !teams.contains(t)) {
+            self.remove_team(&labels, team);
+        }
+        for entry in table {
+            let team = team_labels(&labels, &entry.team);
+            self.standing_points
+                .get_or_create(&team)
+                .set(entry.points.into());
+            self.standing_rank
+                .get_or_create(&team)
+                .set(entry.rank.into());
+        }
+    }
+
+    /// Drop every series of the league `namespace/league`, once it no longer exists
+    pub fn remove_league(&self, namespace: &str, league: &str) {
+        let labels = LeagueLabels {
+            namespace: namespace.to_string(),
+            league: league.to_string(),
+        };
+        self.teams.remove(&labels);
+        self.games_played.remove(&labels);
+        let previous = self
+            .exported
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(&labels);
+        for team in previous.unwrap_or_default() {
+            self.remove_team(&labels, &team);
+        }
+    }
+
+    fn remove_team(&self, league: &LeagueLabels, team: &str) {
+        let team = team_labels(league, team);
+        self.standing_points.remove(&team);
+        self.standing_rank.remove(&team);
+    }
+}
+
+fn team_labels(league: &LeagueLabels, team: &str) -> TeamLabels {
+    TeamLabels {
+        namespace: league.namespace.clone(),
+        league: league.league.clone(),
+        team: team.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_reconciliations_are_counted_per_kind() {
+        let metrics = ControllerMetrics::default();
+        let labels = KindLabels {
+            kind: "TheLeague".to_string(),
+        };
+
+        let ok: Result<(), kube::Error> = metrics.measure("TheLeague", async { Ok(()) }).await;
+        assert!(ok.is_ok());
+        let failed: Result<(), std::fmt::Error> = metrics
+            .measure("TheLeague", async { Err(std::fmt::Error) })
+            .await;
+        assert!(failed.is_err());
+
+        assert_eq!(metrics.reconciliations.get_or_create(&labels).get(), 2);
+        assert_eq!(metrics.reconcile_errors.get_or_create(&labels).get(), 1);
+        assert_eq!(metrics.reconciles_in_flight.get_or_create(&labels).get(), 0);
+    }
+
+    #[test]
+    fn test_league_gauges_follow_the_table() {
+        let metrics = LeagueMetrics::default();
+        let entry = |team: &str, rank, points| TableEntry {
+            team: team.to_string(),
+            rank,
+            points,
+            ..Default::default()
+        };
+        let league = LeagueLabels {
+            namespace: "default".to_string(),
+            league: "premier".to_string(),
+        };
+        let team = |team: &str| team_labels(&league, team);
+
+        metrics.set_table(
+            "default",
+            "premier",
+            3,
+            &[entry("Arsenal", 1, 6), entry("Chelsea", 2, 3)],
+        );
+        assert_eq!(metrics.teams.get_or_create(&league).get(), 2);
+        assert_eq!(metrics.games_played.get_or_create(&league).get(), 3);
+        assert_eq!(
+            metrics
+                .standing_points
+                .get_or_create(&team("Arsenal"))
+                .get(),
+            6
+        );
+        assert_eq!(
+            metrics.standing_rank.get_or_create(&team("Chelsea")).get(),
+            2
+        );
+
+        // Chelsea withdrew: its series are dropped rather than left at their last value.
+        metrics.set_table("default", "premier", 3, &[entry("Arsenal", 1, 6)]);
+        assert!(!metrics.standing_points.remove(&team("Chelsea")));
+        assert!(metrics.standing_points.remove(&team("Arsenal")));
+
+        metrics.remove_league("default", "premier");
+        assert!(!metrics.teams.remove(&league));
+        assert!(!metrics.standing_rank.remove(&team("Arsenal")));
+    }
+}
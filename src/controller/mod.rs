@@ -1,3 +1,285 @@
+pub mod audit;
+pub mod caches;
+pub mod configmap_export;
+pub mod diagnostics;
+pub mod duplicates;
+pub mod errors;
+pub mod fixtures;
+pub mod gameresult_controller;
+pub mod heartbeat;
+pub mod leader_election;
+pub mod locks;
+pub mod matches;
+pub mod metrics;
+pub mod notifications;
+pub mod player_controller;
+pub mod players;
+pub mod playoff_controller;
+pub mod propagation;
+pub mod readiness;
+pub mod referees;
+pub mod references;
+pub mod seasons;
+#[cfg(feature = "sentry")]
+pub mod sentry_reporter;
+pub mod stamps;
+pub mod standing_controller;
+pub mod suspensions;
+pub mod teams;
 pub mod theleague_controller;
+pub mod tournament_controller;
+pub mod transfer_controller;
+pub mod validation_policy;
+pub mod venues;
 
-pub use theleague_controller::{Context, Reconciler};
+use crate::controller::caches::Caches;
+use crate::controller::errors::ErrorLog;
+use crate::controller::heartbeat::Heartbeat;
+use crate::controller::readiness::Readiness;
+use crate::metrics::Metrics;
+
+use k8s_openapi::NamespaceResourceScope;
+use kube::api::{DeleteParams, PatchParams, PostParams};
+use k8s_openapi::api::core::v1::ObjectReference;
+use kube::runtime::controller::Action;
+use kube::runtime::events::{Event, Recorder};
+use kube::{Api, Client, Resource, ResourceExt};
+use std::fmt::Display;
+use std::future::Future;
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use tracing::{Instrument, Span, debug, field, info, info_span};
+
+pub use gameresult_controller::GameResultReconciler;
+pub use locks::LeagueLocks;
+pub use player_controller::PlayerReconciler;
+pub use playoff_controller::PlayoffReconciler;
+pub use standing_controller::StandingReconciler;
+pub use theleague_controller::Reconciler;
+pub use tournament_controller::TournamentReconciler;
+pub use transfer_controller::TransferReconciler;
+
+/// Name the controllers report as in events
+pub const CONTROLLER_NAME: &str = "theleague-controller";
+
+/// Field manager used for server-side apply by the controllers
+pub const FIELD_MANAGER: &str = CONTROLLER_NAME;
+
+/// Port the probe server, serving the probes and the HTTP API, listens on by default
+pub const PROBE_PORT: u16 = 8080;
+
+/// Port the metrics server listens on by default
+pub const METRICS_PORT: u16 = 8081;
+
+/// Port the admin server, serving the log filter and the runtime diagnostics, listens on by
+/// default, on localhost only
+pub const ADMIN_PORT: u16 = 8082;
+
+/// Context shared between the controllers and the worker threads
+#[derive(Clone)]
+pub struct Context {
+    /// Kubernetes client
+    pub client: Client,
+
+    /// Per-league locks serializing table-wide computations (ranking, tiebreaks)
+    pub locks: LeagueLocks,
+
+    /// Event recorder for publishing Kubernetes events
+    pub recorder: Recorder,
+
+    /// Metrics of the process, shared with the webhook server
+    pub metrics: &'static Metrics,
+
+    /// Readiness of the process, held back until the controllers' initial lists complete
+    pub readiness: Readiness,
+
+    /// Heartbeat of the reconcile loops, checked by the liveness probe
+    pub heartbeat: Heartbeat,
+
+    /// Reconcile errors logged recently, to summarize repeated ones
+    pub errors: ErrorLog,
+
+    /// Reflector caches of the controllers, read by the standings API
+    pub caches: Caches,
+}
+
+impl Context {
+    /// Publish `event` about the object `reference`. The event is logged in the span of the
+    /// reconciliation publishing it, so it can be matched with the logs of that reconciliation
+    /// by its reconcile id, while its note stays the same across reconciliations and repeated
+    /// events are aggregated. Nothing is published in dry-run.
+    pub async fn publish(
+        &self,
+        event: &Event,
+        reference: &ObjectReference,
+    ) -> Result<(), kube::Error> {
+        if dry_run() {
+            debug!(reason = %event.reason, "Dry run, not publishing event");
+            return Ok(());
+        }
+        info!(
+            reason = %event.reason,
+            "Publishing event: {}",
+            event.note.as_deref().unwrap_or_default()
+        );
+        self.recorder.publish(event, reference).await
+    }
+}
+
+/// Api used by the controllers to watch `K`.
+///
+/// Equivalent to cache.Options.DefaultNamespaces in Go: if WATCH_NAMESPACE is set,
+/// watch only that namespace; otherwise watch all namespaces.
+pub fn watched_api<K>(client: Client) -> Api<K>
+where
+    K: Resource<Scope = NamespaceResourceScope>,
+    <K as Resource>::DynamicType: Default,
+{
+    match std::env::var("WATCH_NAMESPACE") {
+        Ok(namespace) if !namespace.is_empty() => {
+            info!("Watching {} in namespace: {}", K::kind(&Default::default()), namespace);
+            Api::namespaced(client, &namespace)
+        }
+        _ => {
+            info!("Watching {} in all namespaces", K::kind(&Default::default()));
+            Api::all(client)
+        }
+    }
+}
+
+/// Whether the controllers only dry-run their writes, set with DRY_RUN=true.
+///
+/// The API server admits and validates every write as usual but persists nothing, so the
+/// controllers can be tried against a live cluster without changing it.
+pub fn dry_run() -> bool {
+    static DRY_RUN: OnceLock<bool> = OnceLock::new();
+    *DRY_RUN.get_or_init(|| {
+        std::env::var("DRY_RUN").is_ok_and(|v| v.eq_ignore_ascii_case("true"))
+    })
+}
+
+/// Interval of the reconciles of an object whose spec the controller changed in dry-run
+pub const DRY_RUN_REQUEUE: Duration = Duration::from_secs(300);
+
+/// Action after the controller changed the spec of the object reconciled: waiting for the
+/// reconcile the change triggers, or in dry-run, where no change is persisted, requeueing.
+pub fn await_spec_change() -> Action {
+    if dry_run() {
+        Action::requeue(DRY_RUN_REQUEUE)
+    } else {
+        Action::await_change()
+    }
+}
+
+/// Params of the controllers' patches, attributed to `FIELD_MANAGER`
+pub fn patch_params() -> PatchParams {
+    PatchParams {
+        field_manager: Some(FIELD_MANAGER.to_string()),
+        dry_run: dry_run(),
+        ..Default::default()
+    }
+}
+
+/// Params of the controllers' server-side applies, taking over conflicting fields
+pub fn apply_params() -> PatchParams {
+    let mut params = PatchParams::apply(FIELD_MANAGER).force();
+    params.dry_run = dry_run();
+    params
+}
+
+/// Params of the objects created or replaced by the controllers
+pub fn post_params() -> PostParams {
+    PostParams {
+        dry_run: dry_run(),
+        field_manager: Some(FIELD_MANAGER.to_string()),
+    }
+}
+
+/// Params of the objects deleted by the controllers
+pub fn delete_params() -> DeleteParams {
+    DeleteParams {
+        dry_run: dry_run(),
+        ..Default::default()
+    }
+}
+
+tokio::task_local! {
+    /// Id of the reconciliation running on the current task
+    static RECONCILE_ID: u64;
+}
+
+/// Id of the reconciliation running on the current task, if any
+pub fn reconcile_id() -> Option<u64> {
+    RECONCILE_ID.try_with(|id| *id).ok()
+}
+
+/// Run the reconciliation `reconcile` of the `kind` object `object` in a `reconcile` span
+/// naming the object, its UID and generation, numbered with a reconcile id unique to the
+/// process, and recording whether it succeeded. Every log line of the reconciliation carries
+/// the fields of the span, the API calls are traced as child spans by the kube client and the
+/// events it publishes through [`Context::publish`] are logged with the reconcile id.
+pub fn traced<K, T, E, F>(
+    kind: &'static str,
+    object: &K,
+    reconcile: F,
+) -> impl Future<Output = F::Output> + use<K, T, E, F>
+where
+    K: Resource,
+    E: Display,
+    F: Future<Output = Result<T, E>>,
+{
+    static NEXT_RECONCILE_ID: AtomicU64 = AtomicU64::new(0);
+    let id = NEXT_RECONCILE_ID.fetch_add(1, Ordering::Relaxed);
+    let span = info_span!(
+        "reconcile",
+        otel.name = format!("reconcile {}", kind),
+        reconcile_id = id,
+        kind,
+        name = %object.name_any(),
+        namespace = object.namespace().unwrap_or_default(),
+        uid = object.uid().unwrap_or_default(),
+        generation = object.meta().generation,
+        outcome = field::Empty,
+        error = field::Empty,
+    );
+    let reconcile = async move {
+        let result = reconcile.await;
+        let span = Span::current();
+        match &result {
+            Ok(_) => span.record("outcome", "success"),
+            Err(e) => span
+                .record("outcome", "error")
+                .record("error", field::display(e)),
+        };
+        result
+    };
+    RECONCILE_ID.scope(id, reconcile).instrument(span)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use k8s_openapi::api::core::v1::ConfigMap;
+    use kube::core::ObjectMeta;
+
+    #[tokio::test]
+    async fn test_reconcile_id_is_set_while_reconciling() {
+        let object = ConfigMap {
+            metadata: ObjectMeta {
+                name: Some("round-1".to_string()),
+                uid: Some("4b1c6f0e".to_string()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert_eq!(reconcile_id(), None);
+
+        let reconcile = || async { Ok::<_, kube::Error>(reconcile_id()) };
+        let first = traced("ConfigMap", &object, reconcile()).await.unwrap();
+        let second = traced("ConfigMap", &object, reconcile()).await.unwrap();
+        assert!(first.is_some());
+        assert_ne!(first, second);
+        assert_eq!(reconcile_id(), None);
+    }
+}
@@ -0,0 +1,318 @@
+use crate::api::v1alpha1::the_league_types::{
+    NotificationEvent, NotificationFormat, NotificationTarget, TableRow, TheLeague,
+};
+
+use http_body_util::Full;
+use hyper::body::Bytes;
+use hyper::header::{AUTHORIZATION, CONTENT_TYPE};
+use hyper::{Request, StatusCode};
+use hyper_util::client::legacy::Client as HttpClient;
+use hyper_util::rt::TokioExecutor;
+use k8s_openapi::api::core::v1::Secret;
+use kube::{Api, Client, ResourceExt};
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use tokio::time::Duration;
+use tracing::{info, warn};
+
+/// Number of delivery attempts per notification and target
+const MAX_ATTEMPTS: u32 = 4;
+
+/// Delay before the first retry of a failed delivery; doubled for every further retry
+const RETRY_BASE_DELAY: Duration = Duration::from_secs(2);
+
+/// Label a Secret must carry, set to "true", for its keys to be sent as notification tokens.
+/// Without it a league could make the controller read and send any Secret of its namespace.
+pub const TOKEN_SECRET_LABEL: &str = "bexxmodd.com/notification-token";
+
+/// Hosts notifications are sent to when `NOTIFICATION_ALLOWED_HOSTS` is not set
+const DEFAULT_ALLOWED_HOSTS: [&str; 3] = ["hooks.slack.com", "discord.com", "discordapp.com"];
+
+/// Notification is a league event delivered to the notification targets of the league.
+#[derive(Debug, Clone)]
+pub struct Notification {
+    /// Event is the kind of league event.
+    pub event: NotificationEvent,
+
+    /// Message is a human readable summary of the event.
+    pub message: String,
+
+    /// Details is the machine readable data of the event, included in Generic payloads.
+    pub details: Value,
+}
+
+impl Notification {
+    /// JSON payload of the notification for league `namespace/league` in `format`.
+    pub fn payload(&self, namespace: &str, league: &str, format: NotificationFormat) -> Value {
+        match format {
+            NotificationFormat::Generic => json!({
+                "event": self.event,
+                "league": league,
+                "namespace": namespace,
+                "message": self.message,
+                "details": self.details,
+            }),
+            NotificationFormat::Slack => json!({
+                "text": format!("[{}] {}", league, self.message),
+            }),
+            NotificationFormat::Discord => json!({
+                "content": format!("[{}] {}", league, self.message),
+            }),
+        }
+    }
+}
+
+/// Whether `target` subscribed to `event`
+fn subscribed(target: &NotificationTarget, event: NotificationEvent) -> bool {
+    target.events.is_empty() || target.events.contains(&event)
+}
+
+/// Delay before retry number `retry` (1-based) of a failed delivery
+pub fn retry_delay(retry: u32) -> Duration {
+    RETRY_BASE_DELAY * 2u32.saturating_pow(retry.saturating_sub(1))
+}
+
+/// Hosts notifications may be sent to: the comma-separated `NOTIFICATION_ALLOWED_HOSTS`, or
+/// the Slack and Discord webhook hosts by default
+fn allowed_hosts() -> Vec<String> {
+    match std::env::var("NOTIFICATION_ALLOWED_HOSTS") {
+        Ok(hosts) => hosts
+            .split(',')
+            .map(|host| host.trim().to_ascii_lowercase())
+            .filter(|host| !host.is_empty())
+            .collect(),
+        Err(_) => DEFAULT_ALLOWED_HOSTS
+            .iter()
+            .map(|h| h.to_string())
+            .collect(),
+    }
+}
+
+/// Check that `url` is an HTTPS URL whose host is one of `allowed` or a subdomain of one, so
+/// a league can't make the controller post to the cluster network or its metadata endpoints.
+pub fn check_url(url: &str, allowed: &[String]) -> Result<(), String> {
+    let uri: hyper::Uri = url.parse().map_err(|e| format!("invalid URL: {}", e))?;
+    if uri.scheme_str() != Some("https") {
+        return Err("URL is not HTTPS".to_string());
+    }
+    let host = uri
+        .host()
+        .ok_or_else(|| "URL has no host".to_string())?
+        .to_ascii_lowercase();
+    let host = host.trim_end_matches('.');
+    let permitted = allowed.iter().any(|allowed| {
+        host == allowed
+            || host
+                .strip_suffix(allowed.as_str())
+                .is_some_and(|prefix| prefix.ends_with('.'))
+    });
+    if !permitted {
+        return Err(format!(
+            "host '{}' is not an allowed notification host",
+            host
+        ));
+    }
+    Ok(())
+}
+
+/// Teams whose rank differs between two tables, as (team, old rank, new rank) in new table order.
+/// Teams new to the table are not reported.
+pub fn rank_changes(old: &[TableRow], new: &[TableRow]) -> Vec<(String, u32, u32)> {
+    let old_ranks: HashMap<&str, u32> = old.iter().map(|r| (r.team.as_str(), r.rank)).collect();
+    new.iter()
+        .filter_map(|row| {
+            let old_rank = *old_ranks.get(row.team.as_str())?;
+            (old_rank != row.rank).then(|| (row.team.clone(), old_rank, row.rank))
+        })
+        .collect()
+}
+
+/// Send `notification` to every notification target of `league` subscribed to its event.
+///
+/// Deliveries run in the background and are retried with exponential backoff, so a slow or
+/// unavailable endpoint never holds up a reconcile. Failures are logged and otherwise ignored.
+/// Nothing is sent in dry-run.
+pub async fn notify(client: &Client, league: &TheLeague, notification: Notification) {
+    let namespace = league.namespace().unwrap_or_default();
+    let league_name = league.name_any();
+    if super::dry_run() {
+        info!(
+            "Dry run, not sending {:?} notification of league '{}/{}'",
+            notification.event, namespace, league_name
+        );
+        return;
+    }
+    let allowed = allowed_hosts();
+    for target in &league.spec.notifications {
+        if !subscribed(target, notification.event) {
+            continue;
+        }
+        if let Err(e) = check_url(&target.url, &allowed) {
+            warn!(
+                "Skipping {:?} notification of league '{}/{}' to {}: {}",
+                notification.event, namespace, league_name, target.url, e
+            );
+            continue;
+        }
+        let token = match &target.secret_ref {
+            Some(selector) => {
+                match secret_value(client, &namespace, &selector.name, &selector.key).await {
+                    Ok(token) => Some(token),
+                    Err(e) => {
+                        warn!(
+                            "Skipping {:?} notification of league '{}/{}' to {}: {}",
+                            notification.event, namespace, league_name, target.url, e
+                        );
+                        continue;
+                    }
+                }
+            }
+            None => None,
+        };
+        let body = notification
+            .payload(&namespace, &league_name, target.format)
+            .to_string();
+        let url = target.url.clone();
+        let event = notification.event;
+        let league_ref = format!("{}/{}", namespace, league_name);
+        tokio::spawn(async move {
+            match deliver(&url, token.as_deref(), body).await {
+                Ok(()) => info!(
+                    "Sent {:?} notification of league '{}' to {}",
+                    event, league_ref, url
+                ),
+                Err(e) => warn!(
+                    "Failed to send {:?} notification of league '{}' to {}: {}",
+                    event, league_ref, url, e
+                ),
+            }
+        });
+    }
+}
+
+/// Value of `key` in Secret `namespace/name`, which must opt in with [`TOKEN_SECRET_LABEL`]
+async fn secret_value(
+    client: &Client,
+    namespace: &str,
+    name: &str,
+    key: &str,
+) -> Result<String, String> {
+    let secret_api: Api<Secret> = Api::namespaced(client.clone(), namespace);
+    let secret = secret_api
+        .get(name)
+        .await
+        .map_err(|e| format!("reading Secret '{}': {}", name, e))?;
+    if secret.labels().get(TOKEN_SECRET_LABEL).map(String::as_str) != Some("true") {
+        return Err(format!(
+            "Secret '{}' is not labelled {}=true",
+            name, TOKEN_SECRET_LABEL
+        ));
+    }
+    let value = secret
+        .data
+        .and_then(|mut data| data.remove(key))
+        .ok_or_else(|| format!("Secret '{}' has no key '{}'", name, key))?;
+    String::from_utf8(value.0)
+        .map(|token| token.trim().to_string())
+        .map_err(|_| format!("key '{}' of Secret '{}' is not UTF-8", key, name))
+}
+
+/// POST `body` to `url`, retrying server errors, throttling and connection failures.
+async fn deliver(url: &str, token: Option<&str>, body: String) -> Result<(), String> {
+    let connector = hyper_rustls::HttpsConnectorBuilder::new()
+        .with_provider_and_native_roots(rustls::crypto::ring::default_provider())
+        .map_err(|e| format!("loading root certificates: {}", e))?
+        .https_only()
+        .enable_http1()
+        .build();
+    let http: HttpClient<_, Full<Bytes>> =
+        HttpClient::builder(TokioExecutor::new()).build(connector);
+    let body = Bytes::from(body);
+
+    let mut last_error = String::new();
+    for attempt in 1..=MAX_ATTEMPTS {
+        if attempt > 1 {
+            tokio::time::sleep(retry_delay(attempt - 1)).await;
+        }
+        let mut request = Request::post(url).header(CONTENT_TYPE, "application/json");
+        if let Some(token) = token {
+            request = request.header(AUTHORIZATION, format!("Bearer {}", token));
+        }
+        let request = request
+            .body(Full::new(body.clone()))
+            .map_err(|e| format!("invalid request: {}", e))?;
+        match http.request(request).await {
+            Ok(response) if response.status().is_success() => return Ok(()),
+            // Other client errors won't go away by retrying.
+            Ok(response)
+                if response.status().is_client_error()
+                    && response.status() != StatusCode::TOO_MANY_REQUESTS =>
+            {
+                return Err(format!("endpoint answered {}", response.status()));
+            }
+            Ok(response) => last_error = format!("endpoint answered {}", response.status()),
+            Err(e) => last_error = e.to_string(),
+        }
+    }
+    Err(format!("{} after {} attempts", last_error, MAX_ATTEMPTS))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(team: &str, rank: u32) -> TableRow {
+        TableRow {
+            rank,
+            team: team.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_rank_changes_report_moved_teams_only() {
+        let old = [row("Tigers", 1), row("Sharks", 2), row("Eagles", 3)];
+        let new = [
+            row("Sharks", 1),
+            row("Tigers", 2),
+            row("Eagles", 3),
+            row("Bears", 4),
+        ];
+        assert_eq!(
+            rank_changes(&old, &new),
+            vec![("Sharks".to_string(), 2, 1), ("Tigers".to_string(), 1, 2)]
+        );
+    }
+
+    #[test]
+    fn test_payload_formats_and_retry_delays() {
+        let notification = Notification {
+            event: NotificationEvent::LeagueCompleted,
+            message: "Tigers win the league".to_string(),
+            details: json!({"champion": "Tigers"}),
+        };
+        let generic = notification.payload("default", "premier", NotificationFormat::Generic);
+        assert_eq!(generic["event"], "LeagueCompleted");
+        assert_eq!(generic["details"]["champion"], "Tigers");
+        let slack = notification.payload("default", "premier", NotificationFormat::Slack);
+        assert_eq!(slack, json!({"text": "[premier] Tigers win the league"}));
+
+        assert_eq!(retry_delay(1), Duration::from_secs(2));
+        assert_eq!(retry_delay(3), Duration::from_secs(8));
+    }
+
+    #[test]
+    fn test_check_url_allows_https_to_allowed_hosts_only() {
+        let allowed = vec!["hooks.slack.com".to_string(), "example.com".to_string()];
+        assert!(check_url("https://hooks.slack.com/services/T0/B0/x", &allowed).is_ok());
+        assert!(check_url("https://scores.example.com/hook", &allowed).is_ok());
+        assert!(check_url("https://Example.COM./hook", &allowed).is_ok());
+
+        assert!(check_url("http://hooks.slack.com/services", &allowed).is_err());
+        assert!(check_url("https://notexample.com/hook", &allowed).is_err());
+        assert!(check_url("https://example.com.evil.io/hook", &allowed).is_err());
+        assert!(check_url("https://169.254.169.254/latest", &allowed).is_err());
+        assert!(check_url("https://kubernetes.default.svc/api", &allowed).is_err());
+        assert!(check_url("not a url", &allowed).is_err());
+    }
+}
@@ -0,0 +1,151 @@
+use crate::api::v1alpha1::game_result_types::{GameResult, GameResultSpec};
+use crate::api::v1alpha1::player_types::Player;
+use crate::api::v1alpha1::team_types::Team;
+use crate::controller::stamps::Stamped;
+use crate::controller::{
+    Context, errors, gameresult_controller, patch_params, players, references, teams, traced,
+    watched_api,
+};
+
+use futures::StreamExt;
+use k8s_openapi::chrono;
+use kube::api::{ListParams, Patch};
+use kube::runtime::reflector::ObjectRef;
+use kube::runtime::{controller::Controller as KubeController, watcher};
+use kube::{Api, ResourceExt, runtime::controller::Action};
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::time::Duration;
+use tracing::info;
+
+/// Controller aggregating the career statistics of Players from the accepted GameResults of
+/// their namespace
+pub struct PlayerReconciler {
+    context: Arc<Context>,
+    controller: KubeController<Player>,
+}
+
+impl PlayerReconciler {
+    /// Create a new PlayerReconciler
+    pub fn new(context: Arc<Context>) -> Self {
+        let player_api: Api<Player> = watched_api(context.client.clone());
+        let result_api: Api<GameResult> = watched_api(context.client.clone());
+
+        let controller = KubeController::new(player_api, watcher::Config::default());
+        context.readiness.track("Player", controller.store());
+        // A result wakes up the Players it records statistics of, and the ones with
+        // statistics in its league already.
+        let players = controller.store();
+        let caches = context.caches.clone();
+        let controller =
+            controller.watches(result_api, watcher::Config::default(), move |result| {
+                let namespace =
+                    references::league_namespace(result.namespace(), &result.spec.league_namespace);
+                let league = caches.leagues().and_then(|leagues| {
+                    leagues.get(&ObjectRef::new(&result.spec.league_name).within(&namespace))
+                });
+                players
+                    .state()
+                    .into_iter()
+                    .filter(|p| p.namespace().as_deref() == Some(namespace.as_str()))
+                    .filter(|p| {
+                        players::affected_by(p, league.as_ref().map(|l| &l.spec), &result.spec)
+                    })
+                    .map(|p| ObjectRef::from_obj(p.as_ref()))
+                    .collect::<Vec<_>>()
+            });
+
+        Self {
+            context,
+            controller,
+        }
+    }
+
+    /// Reconcile a Player resource (static method)
+    pub async fn reconcile(player: Arc<Player>, ctx: Arc<Context>) -> Result<Action, kube::Error> {
+        let name = player.name_any();
+        let namespace = player.namespace().unwrap_or_default();
+        info!("reconcile request: player {}", name);
+
+        // The leagues and results are read from the caches of their controllers.
+        let Some((league_store, result_store)) = ctx.caches.synced() else {
+            return Ok(Action::requeue(Duration::from_secs(5)));
+        };
+        let results: Vec<Arc<GameResult>> = result_store
+            .state()
+            .into_iter()
+            .filter(|r| {
+                references::league_namespace(r.namespace(), &r.spec.league_namespace) == namespace
+                    && gameresult_controller::is_accepted(r)
+            })
+            .collect();
+        let specs: Vec<&GameResultSpec> = results.iter().map(|r| &r.spec).collect();
+
+        let leagues: Vec<_> = league_store
+            .state()
+            .into_iter()
+            .filter(|l| l.namespace().as_deref() == Some(namespace.as_str()))
+            .collect();
+        // The Teams are only listed once, for the leagues referencing some.
+        let team_objects: Vec<Team> = if leagues
+            .iter()
+            .flat_map(|l| &l.spec.teams)
+            .any(|t| t.team_ref.is_some())
+        {
+            let team_api: Api<Team> = Api::namespaced(ctx.client.clone(), &namespace);
+            team_api.list(&ListParams::default()).await?.items
+        } else {
+            Vec::new()
+        };
+        let leagues: HashMap<String, _> = leagues
+            .iter()
+            .map(|league| {
+                let mut spec = league.spec.clone();
+                teams::apply_team_refs(&mut spec, &team_objects);
+                (league.name_any(), spec)
+            })
+            .collect();
+
+        let mut status = players::career_stats(&name, &player.spec, &leagues, &specs);
+        if player.status.clone().map(Stamped::unstamped).as_ref() != Some(&status) {
+            status.stamp(chrono::Utc::now());
+            let player_api: Api<Player> = Api::namespaced(ctx.client.clone(), &namespace);
+            let patch = Patch::Merge(json!({ "status": status }));
+            player_api
+                .patch_status(&name, &patch_params(), &patch)
+                .await?;
+        }
+        Ok(Action::requeue(Duration::from_secs(3600)))
+    }
+
+    /// Handle errors that occur during reconciliation (static method)
+    pub fn error_policy(object: Arc<Player>, err: &kube::Error, ctx: Arc<Context>) -> Action {
+        errors::report(&ctx, "Player", object.as_ref(), err);
+        Action::requeue(Duration::from_secs(5))
+    }
+
+    pub fn stream(self) -> impl futures::Future<Output = ()> {
+        let context = self.context.clone();
+        let metrics = &self.context.metrics.controller;
+        let results = self.controller.shutdown_on_signal().run(
+            |object, ctx| {
+                let reconcile = traced(
+                    "Player",
+                    object.as_ref(),
+                    PlayerReconciler::reconcile(object.clone(), ctx),
+                );
+                metrics.measure("Player", reconcile)
+            },
+            PlayerReconciler::error_policy,
+            context,
+        );
+        self.context
+            .heartbeat
+            .beating("Player", results)
+            .for_each(move |result| {
+                metrics.observe("Player", &result);
+                futures::future::ready(())
+            })
+    }
+}
@@ -0,0 +1,227 @@
+use crate::api::v1alpha1::game_result_types::{CardColor, GameResultSpec};
+use crate::api::v1alpha1::player_types::{Player, PlayerSeasonStats, PlayerSpec, PlayerStatus};
+use crate::api::v1alpha1::the_league_types::{Player as RosterEntry, TheLeagueSpec};
+
+use kube::ResourceExt;
+use std::collections::{BTreeMap, HashMap};
+
+/// Whether the player `first_name last_name` of `team` in `league` is the Player `name`.
+///
+/// A roster entry referencing a Player decides; otherwise the names must match.
+fn is_player(
+    name: &str,
+    player: &PlayerSpec,
+    league: Option<&TheLeagueSpec>,
+    team: &str,
+    first_name: &str,
+    last_name: &str,
+) -> bool {
+    let entry: Option<&RosterEntry> = league
+        .into_iter()
+        .flat_map(|l| &l.teams)
+        .filter(|t| t.name == team)
+        .flat_map(|t| &t.players)
+        .find(|p| p.first_name == first_name && p.last_name == last_name);
+    match entry.and_then(|e| e.player_ref.as_deref()) {
+        Some(player_ref) => player_ref == name,
+        None => player.first_name == first_name && player.last_name == last_name,
+    }
+}
+
+/// A statistic recorded for a player in a result
+enum Stat {
+    Goal,
+    Assist,
+    Card(CardColor),
+}
+
+/// The statistics recorded in `result`, with the team and name of the player each is for
+fn recorded_stats(
+    result: &GameResultSpec,
+) -> impl Iterator<Item = (&String, &String, &String, Stat)> {
+    let scorers = result
+        .scorers
+        .iter()
+        .map(|s| (&s.team, &s.first_name, &s.last_name, Stat::Goal));
+    let assists = result
+        .assists
+        .iter()
+        .map(|a| (&a.team, &a.first_name, &a.last_name, Stat::Assist));
+    let cards = result.cards.iter().map(|c| {
+        (
+            &c.team,
+            &c.first_name,
+            &c.last_name,
+            Stat::Card(c.color.clone()),
+        )
+    });
+    scorers.chain(assists).chain(cards)
+}
+
+/// Whether a change of `result`, a game of `league`, may change the career statistics of
+/// `player`: the result records a statistic of the player, or the player has statistics in
+/// the league already, which the change may have taken away.
+pub fn affected_by(
+    player: &Player,
+    league: Option<&TheLeagueSpec>,
+    result: &GameResultSpec,
+) -> bool {
+    let name = player.name_any();
+    let in_league = player
+        .status
+        .iter()
+        .flat_map(|s| &s.seasons)
+        .any(|s| s.league == result.league_name);
+    in_league
+        || recorded_stats(result).any(|(team, first_name, last_name, _)| {
+            is_player(&name, &player.spec, league, team, first_name, last_name)
+        })
+}
+
+/// Career statistics of the Player `name` over `results`, broken down per league, season and
+/// team. `leagues` maps league names to their specs with resolved rosters.
+pub fn career_stats(
+    name: &str,
+    player: &PlayerSpec,
+    leagues: &HashMap<String, TheLeagueSpec>,
+    results: &[&GameResultSpec],
+) -> PlayerStatus {
+    let mut seasons: BTreeMap<(String, Option<u32>, String), PlayerSeasonStats> = BTreeMap::new();
+    for result in results {
+        let league = leagues.get(&result.league_name);
+        for (team, first_name, last_name, stat) in recorded_stats(result) {
+            if !is_player(name, player, league, team, first_name, last_name) {
+                continue;
+            }
+            let stats = seasons
+                .entry((result.league_name.clone(), result.season, team.clone()))
+                .or_insert_with(|| PlayerSeasonStats {
+                    league: result.league_name.clone(),
+                    season: result.season,
+                    team: team.clone(),
+                    ..Default::default()
+                });
+            match stat {
+                Stat::Goal => stats.goals += 1,
+                Stat::Assist => stats.assists += 1,
+                Stat::Card(CardColor::Yellow) => stats.yellow_cards += 1,
+                Stat::Card(CardColor::Red) => stats.red_cards += 1,
+            }
+        }
+    }
+
+    let seasons: Vec<PlayerSeasonStats> = seasons.into_values().collect();
+    PlayerStatus {
+        goals: seasons.iter().map(|s| s.goals).sum(),
+        assists: seasons.iter().map(|s| s.assists).sum(),
+        yellow_cards: seasons.iter().map(|s| s.yellow_cards).sum(),
+        red_cards: seasons.iter().map(|s| s.red_cards).sum(),
+        seasons,
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::v1alpha1::game_result_types::{Card, GameOutcome, PlayerStat};
+
+    fn result(league: &str, season: Option<u32>, scorer: (&str, &str, &str)) -> GameResultSpec {
+        let (team, first_name, last_name) = scorer;
+        let mut spec = GameResultSpec::game("Tigers", "Sharks")
+            .in_league(league)
+            .in_season(season)
+            .with_result(GameOutcome::Draw { score: 1 })
+            .with_cards(vec![Card {
+                team: team.to_string(),
+                first_name: first_name.to_string(),
+                last_name: last_name.to_string(),
+                color: CardColor::Yellow,
+                minute: None,
+            }]);
+        spec.scorers = vec![PlayerStat {
+            team: team.to_string(),
+            first_name: first_name.to_string(),
+            last_name: last_name.to_string(),
+            minute: None,
+        }];
+        spec
+    }
+
+    fn league(team: &str, player_ref: &str) -> TheLeagueSpec {
+        let mut league = TheLeagueSpec::with_teams(&[team]);
+        league.teams[0].players = vec![RosterEntry {
+            player_ref: Some(player_ref.to_string()),
+            ..RosterEntry::named("Alex", "Morgan")
+        }];
+        league
+    }
+
+    #[test]
+    fn test_career_stats_follow_player_refs_across_seasons() {
+        let player = PlayerSpec {
+            first_name: "Alex".to_string(),
+            last_name: "Morgan".to_string(),
+        };
+        // Two players named Alex Morgan, told apart by the Player their roster entry references.
+        let leagues = HashMap::from([
+            ("premier".to_string(), league("Tigers", "alex-morgan")),
+            ("cup".to_string(), league("Sharks", "alex-morgan-2")),
+        ]);
+        let results = [
+            result("premier", Some(1), ("Tigers", "Alex", "Morgan")),
+            result("premier", Some(2), ("Tigers", "Alex", "Morgan")),
+            result("premier", Some(2), ("Tigers", "Sam", "Lee")),
+            result("cup", None, ("Sharks", "Alex", "Morgan")),
+            // Leagues without the player on a roster fall back to the name.
+            result("friendlies", None, ("Eagles", "Alex", "Morgan")),
+        ];
+        let specs: Vec<&GameResultSpec> = results.iter().collect();
+
+        let status = career_stats("alex-morgan", &player, &leagues, &specs);
+        assert_eq!(status.goals, 3);
+        assert_eq!(status.yellow_cards, 3);
+        let seasons: Vec<(&str, Option<u32>)> = status
+            .seasons
+            .iter()
+            .map(|s| (s.league.as_str(), s.season))
+            .collect();
+        assert_eq!(
+            seasons,
+            vec![
+                ("friendlies", None),
+                ("premier", Some(1)),
+                ("premier", Some(2))
+            ]
+        );
+
+        let namesake = career_stats("alex-morgan-2", &player, &leagues, &specs);
+        assert_eq!(namesake.goals, 2);
+        assert_eq!(namesake.seasons[0].league, "cup");
+    }
+
+    #[test]
+    fn test_results_affect_their_players_and_the_players_of_their_league() {
+        let mut player = Player::new(
+            "alex-morgan",
+            PlayerSpec {
+                first_name: "Alex".to_string(),
+                last_name: "Morgan".to_string(),
+            },
+        );
+        let league = league("Tigers", "alex-morgan");
+        let scored = result("premier", None, ("Tigers", "Alex", "Morgan"));
+        let other = result("premier", None, ("Tigers", "Sam", "Lee"));
+        assert!(affected_by(&player, Some(&league), &scored));
+        assert!(!affected_by(&player, Some(&league), &other));
+
+        // A result may take away the statistics the player has in its league.
+        player.status = Some(career_stats(
+            "alex-morgan",
+            &player.spec,
+            &HashMap::from([("premier".to_string(), league.clone())]),
+            &[&scored],
+        ));
+        assert!(affected_by(&player, Some(&league), &other));
+    }
+}
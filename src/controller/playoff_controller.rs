@@ -0,0 +1,176 @@
+use crate::api::v1alpha1::game_result_types::{GameResult, GameResultSpec};
+use crate::api::v1alpha1::playoff_types::{Playoff, PlayoffPhase, PlayoffStatus};
+use crate::api::v1alpha1::the_league_types::TheLeague;
+use crate::bracket;
+use crate::controller::stamps::{self, Stamped};
+use crate::controller::{
+    Context, errors, gameresult_controller, patch_params, references, traced, watched_api,
+};
+
+use futures::StreamExt;
+use kube::api::{ListParams, Patch};
+use kube::runtime::events::{Event, EventType};
+use kube::runtime::reflector::ObjectRef;
+use kube::runtime::{controller::Controller as KubeController, watcher};
+use kube::{Api, Resource, ResourceExt, runtime::controller::Action};
+use serde_json::json;
+use std::sync::Arc;
+use tokio::time::Duration;
+use tracing::info;
+
+/// Controller seeding Playoffs from completed leagues and advancing their brackets
+pub struct PlayoffReconciler {
+    context: Arc<Context>,
+    controller: KubeController<Playoff>,
+}
+
+impl PlayoffReconciler {
+    /// Create a new PlayoffReconciler
+    pub fn new(context: Arc<Context>) -> Self {
+        let playoff_api: Api<Playoff> = watched_api(context.client.clone());
+        let result_api: Api<GameResult> = watched_api(context.client.clone());
+        let league_api: Api<TheLeague> = watched_api(context.client.clone());
+
+        let controller = KubeController::new(playoff_api, watcher::Config::default());
+        context.readiness.track("Playoff", controller.store());
+        // A playoff game wakes up the Playoff it references.
+        let controller = controller.watches(result_api, watcher::Config::default(), |result| {
+            let playoff = result.spec.playoff.as_ref()?;
+            let namespace =
+                references::league_namespace(result.namespace(), &result.spec.league_namespace);
+            Some(ObjectRef::new(playoff).within(&namespace))
+        });
+        // The completion of a league seeds its Playoffs.
+        let store = controller.store();
+        let controller =
+            controller.watches(league_api, watcher::Config::default(), move |league| {
+                store
+                    .state()
+                    .into_iter()
+                    .filter(|p| {
+                        p.namespace() == league.namespace()
+                            && p.spec.league_name == league.name_any()
+                    })
+                    .map(|p| ObjectRef::from_obj(p.as_ref()))
+                    .collect::<Vec<_>>()
+            });
+
+        Self {
+            context,
+            controller,
+        }
+    }
+
+    /// Reconcile a Playoff resource (static method)
+    pub async fn reconcile(
+        playoff: Arc<Playoff>,
+        ctx: Arc<Context>,
+    ) -> Result<Action, kube::Error> {
+        let name = playoff.name_any();
+        let namespace = playoff.namespace().unwrap_or_default();
+        info!("reconcile request: playoff {}", name);
+
+        let playoff_api: Api<Playoff> = Api::namespaced(ctx.client.clone(), &namespace);
+        let league_api: Api<TheLeague> = Api::namespaced(ctx.client.clone(), &namespace);
+        let league = league_api.get_opt(&playoff.spec.league_name).await?;
+        let current = playoff.status.clone().unwrap_or_default().unstamped();
+
+        // The seeds are taken once, from the final table of the completed league.
+        let mut seeds = current.seeds.clone();
+        if seeds.is_empty()
+            && let Some(status) = league.as_ref().and_then(|l| l.status.as_ref())
+            && status.completed
+        {
+            seeds = status
+                .table
+                .iter()
+                .take(playoff.spec.qualifiers as usize)
+                .map(|row| row.team.clone())
+                .collect();
+        }
+
+        let status = if seeds.is_empty() {
+            PlayoffStatus::default()
+        } else {
+            let result_api: Api<GameResult> = Api::namespaced(ctx.client.clone(), &namespace);
+            let results: Vec<GameResult> = result_api
+                .list(&ListParams::default())
+                .await?
+                .items
+                .into_iter()
+                .filter(|r| {
+                    r.spec.playoff.as_deref() == Some(name.as_str())
+                        && r.spec.league_name == playoff.spec.league_name
+                        && gameresult_controller::is_accepted(r)
+                })
+                .collect();
+            let specs: Vec<&GameResultSpec> = results.iter().map(|r| &r.spec).collect();
+            let (series, champion) = bracket::bracket(&seeds, &specs, playoff.spec.best_of);
+            PlayoffStatus {
+                phase: match champion {
+                    Some(_) => PlayoffPhase::Completed,
+                    None => PlayoffPhase::InProgress,
+                },
+                seeds,
+                series,
+                champion,
+                ..Default::default()
+            }
+        };
+
+        if status != current {
+            if let Some(champion) = status
+                .champion
+                .as_ref()
+                .filter(|_| current.champion.is_none())
+            {
+                info!("Playoff '{}/{}' won by {}", namespace, name, champion);
+                let event = Event {
+                    type_: EventType::Normal,
+                    reason: "PlayoffCompleted".to_string(),
+                    note: Some(format!("{} won the playoff", champion)),
+                    action: "Completed".to_string(),
+                    secondary: None,
+                };
+                ctx.publish(&event, &playoff.object_ref(&())).await?;
+            }
+            let patch = Patch::Merge(json!({ "status": status }));
+            playoff_api
+                .patch_status(&name, &patch_params(), &patch)
+                .await?;
+        }
+
+        stamps::stamp_reconciled(&playoff_api, &name, playoff.status.as_ref()).await?;
+        Ok(Action::await_change())
+    }
+
+    /// Handle errors that occur during reconciliation (static method)
+    pub fn error_policy(object: Arc<Playoff>, err: &kube::Error, ctx: Arc<Context>) -> Action {
+        errors::report(&ctx, "Playoff", object.as_ref(), err);
+        Action::requeue(Duration::from_secs(5))
+    }
+
+    pub fn stream(self) -> impl futures::Future<Output = ()> {
+        let context = self.context.clone();
+        let metrics = &self.context.metrics.controller;
+        let results = self.controller.shutdown_on_signal().run(
+            |object, ctx| {
+                let reconcile = traced(
+                    "Playoff",
+                    object.as_ref(),
+                    PlayoffReconciler::reconcile(object.clone(), ctx),
+                );
+                metrics.measure("Playoff", reconcile)
+            },
+            PlayoffReconciler::error_policy,
+            context,
+        );
+        self.context
+            .heartbeat
+            .beating("Playoff", results)
+            .for_each(move |result| {
+                metrics.observe("Playoff", &result);
+                futures::future::ready(())
+            })
+    }
+}
@@ -0,0 +1,225 @@
+use crate::api::v1alpha1::the_league_types::TheLeague;
+use crate::controller::patch_params;
+
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+use kube::api::Patch;
+use kube::{Api, Resource, ResourceExt};
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value, json};
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+
+/// Annotation listing the label keys a child resource carries from its league, so labels
+/// that stop being propagated can be removed again
+pub const PROPAGATED_LABELS_ANNOTATION: &str = "bexxmodd.com/propagated-labels";
+
+/// Annotation listing the annotation keys a child resource carries from its league
+pub const PROPAGATED_ANNOTATIONS_ANNOTATION: &str = "bexxmodd.com/propagated-annotations";
+
+/// The labels and annotations of a league its child resources carry
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Propagated {
+    pub labels: BTreeMap<String, String>,
+    pub annotations: BTreeMap<String, String>,
+}
+
+/// The labels and annotations of `league` selected by its propagation policy
+pub fn propagated(league: &TheLeague) -> Propagated {
+    let Some(policy) = &league.spec.propagation else {
+        return Propagated::default();
+    };
+    Propagated {
+        labels: select(league.labels(), &policy.labels),
+        annotations: select(league.annotations(), &policy.annotations),
+    }
+}
+
+/// The entries of `source` matching one of `keys`; keys ending in `*` match by prefix
+fn select(source: &BTreeMap<String, String>, keys: &[String]) -> BTreeMap<String, String> {
+    source
+        .iter()
+        .filter(|(key, _)| {
+            keys.iter().any(|k| match k.strip_suffix('*') {
+                Some(prefix) => key.starts_with(prefix),
+                None => *key == k,
+            })
+        })
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect()
+}
+
+/// Keys recorded in the bookkeeping annotation `annotation` of `meta`
+fn recorded_keys(meta: &ObjectMeta, annotation: &str) -> Vec<String> {
+    meta.annotations
+        .as_ref()
+        .and_then(|a| a.get(annotation))
+        .map(|keys| {
+            keys.split(',')
+                .filter(|k| !k.is_empty())
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+impl Propagated {
+    /// Add the propagated labels and annotations to the metadata of a new child resource
+    pub fn apply_to(&self, meta: &mut ObjectMeta) {
+        if !self.labels.is_empty() {
+            meta.labels
+                .get_or_insert_default()
+                .extend(self.labels.clone());
+        }
+        let mut annotations = self.annotations.clone();
+        for (annotation, keys) in self.bookkeeping() {
+            if let Some(keys) = keys {
+                annotations.insert(annotation.to_string(), keys);
+            }
+        }
+        if !annotations.is_empty() {
+            meta.annotations.get_or_insert_default().extend(annotations);
+        }
+    }
+
+    /// The bookkeeping annotations recording the propagated keys; None when nothing is propagated
+    fn bookkeeping(&self) -> [(&'static str, Option<String>); 2] {
+        let keys = |map: &BTreeMap<String, String>| {
+            (!map.is_empty()).then(|| map.keys().cloned().collect::<Vec<_>>().join(","))
+        };
+        [
+            (PROPAGATED_LABELS_ANNOTATION, keys(&self.labels)),
+            (PROPAGATED_ANNOTATIONS_ANNOTATION, keys(&self.annotations)),
+        ]
+    }
+
+    /// Merge patch bringing the metadata `meta` of an existing child resource in line with the
+    /// propagated labels and annotations, removing the ones no longer propagated. None when the
+    /// metadata is already current.
+    pub fn metadata_patch(&self, meta: &ObjectMeta) -> Option<Value> {
+        let empty = BTreeMap::new();
+        let current_labels = meta.labels.as_ref().unwrap_or(&empty);
+        let current_annotations = meta.annotations.as_ref().unwrap_or(&empty);
+
+        let mut labels = Map::new();
+        for key in recorded_keys(meta, PROPAGATED_LABELS_ANNOTATION) {
+            if !self.labels.contains_key(&key) && current_labels.contains_key(&key) {
+                labels.insert(key, Value::Null);
+            }
+        }
+        for (key, value) in &self.labels {
+            if current_labels.get(key) != Some(value) {
+                labels.insert(key.clone(), json!(value));
+            }
+        }
+
+        let mut annotations = Map::new();
+        for key in recorded_keys(meta, PROPAGATED_ANNOTATIONS_ANNOTATION) {
+            if !self.annotations.contains_key(&key) && current_annotations.contains_key(&key) {
+                annotations.insert(key, Value::Null);
+            }
+        }
+        for (key, value) in &self.annotations {
+            if current_annotations.get(key) != Some(value) {
+                annotations.insert(key.clone(), json!(value));
+            }
+        }
+        for (annotation, keys) in self.bookkeeping() {
+            if current_annotations.get(annotation) != keys.as_ref() {
+                annotations.insert(annotation.to_string(), json!(keys));
+            }
+        }
+
+        if labels.is_empty() && annotations.is_empty() {
+            return None;
+        }
+        let mut metadata = Map::new();
+        if !labels.is_empty() {
+            metadata.insert("labels".to_string(), Value::Object(labels));
+        }
+        if !annotations.is_empty() {
+            metadata.insert("annotations".to_string(), Value::Object(annotations));
+        }
+        Some(json!({ "metadata": metadata }))
+    }
+}
+
+/// Update the labels and annotations `child` carries from its league when they changed
+pub async fn sync_metadata<K>(
+    api: &Api<K>,
+    child: &K,
+    propagated: &Propagated,
+) -> Result<(), kube::Error>
+where
+    K: Resource + Clone + DeserializeOwned + Debug,
+{
+    let Some(patch) = propagated.metadata_patch(child.meta()) else {
+        return Ok(());
+    };
+    api.patch(&child.name_any(), &patch_params(), &Patch::Merge(patch))
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::v1alpha1::the_league_types::{Propagation, TheLeagueSpec};
+
+    fn league(labels: &[(&str, &str)], policy: Option<Propagation>) -> TheLeague {
+        let mut spec: TheLeagueSpec =
+            serde_json::from_value(json!({ "maxTeams": 2, "teams": [] })).unwrap();
+        spec.propagation = policy;
+        let mut league = TheLeague::new("premier", spec);
+        league.metadata.labels = Some(
+            labels
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+        );
+        league
+    }
+
+    #[test]
+    fn test_selected_labels_are_propagated_and_removed_again() {
+        let policy = Propagation {
+            labels: vec!["cost-center".to_string(), "teams.example.com/*".to_string()],
+            annotations: Vec::new(),
+        };
+        let labels = [
+            ("cost-center", "sports"),
+            ("teams.example.com/home", "tigers"),
+            ("app", "the-league"),
+        ];
+        let propagated = propagated(&league(&labels, Some(policy.clone())));
+        assert_eq!(
+            propagated.labels.keys().collect::<Vec<_>>(),
+            ["cost-center", "teams.example.com/home"]
+        );
+
+        let mut meta = ObjectMeta::default();
+        propagated.apply_to(&mut meta);
+        assert_eq!(propagated.metadata_patch(&meta), None);
+        assert_eq!(
+            meta.annotations.as_ref().unwrap()[PROPAGATED_LABELS_ANNOTATION],
+            "cost-center,teams.example.com/home"
+        );
+
+        // The cost center label is dropped from the league: it's removed from the child too.
+        let updated = super::propagated(&league(&labels[1..], Some(policy)));
+        assert_eq!(
+            updated.metadata_patch(&meta),
+            Some(json!({
+                "metadata": {
+                    "labels": { "cost-center": null },
+                    "annotations": { PROPAGATED_LABELS_ANNOTATION: "teams.example.com/home" },
+                }
+            }))
+        );
+
+        // Without a policy nothing is propagated, and nothing is touched on children that
+        // never carried league metadata.
+        let none = super::propagated(&league(&labels, None));
+        assert_eq!(none, Propagated::default());
+        assert_eq!(none.metadata_patch(&ObjectMeta::default()), None);
+    }
+}
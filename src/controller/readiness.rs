@@ -0,0 +1,221 @@
+//! Readiness of the controller process, reported on `/readyz`.
+//!
+//! The process is ready once the CRDs it watches are served by the API server and, on a
+//! replica running its controllers, the initial list of every controller's watch has
+//! completed. With leader election a standby waiting for the Lease doesn't watch anything, so
+//! it is ready as soon as the CRDs are served; the leader is ready once its lists completed.
+
+use crate::api::v1alpha1::{
+    game_result_types::GameResult, playoff_types::Playoff, referee_types::Referee,
+    standing_types::Standing, team_types::Team, the_league_types::TheLeague,
+    tournament_types::Tournament, transfer_types::Transfer, venue_types::Venue,
+};
+
+use kube::core::GroupVersion;
+use kube::runtime::reflector::Store;
+use kube::{Client, Resource, discovery};
+use std::collections::{BTreeMap, BTreeSet};
+use std::hash::Hash;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tracing::info;
+
+/// Kinds watched by the controllers, which must be served before the process is ready
+pub fn watched_kinds() -> [String; 9] {
+    [
+        TheLeague::kind(&()).into_owned(),
+        Standing::kind(&()).into_owned(),
+        GameResult::kind(&()).into_owned(),
+        Playoff::kind(&()).into_owned(),
+        Tournament::kind(&()).into_owned(),
+        Transfer::kind(&()).into_owned(),
+        Team::kind(&()).into_owned(),
+        Referee::kind(&()).into_owned(),
+        Venue::kind(&()).into_owned(),
+    ]
+}
+
+/// LeaseState is the leader election state of the replica.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum LeaseState {
+    /// NotRequired means leader election is off, so the replica runs its controllers.
+    #[default]
+    NotRequired,
+
+    /// Waiting means the replica is a standby waiting to acquire the Lease.
+    Waiting,
+
+    /// Held means the replica holds the Lease and runs its controllers.
+    Held,
+}
+
+/// Number of objects in a controller's store
+type StoreSize = Box<dyn Fn() -> usize + Send>;
+
+/// Readiness of the process, shared by the controllers and the probe server
+#[derive(Clone, Default)]
+pub struct Readiness {
+    inner: Arc<Inner>,
+}
+
+#[derive(Default)]
+struct Inner {
+    /// Whether the watched CRDs were found served, checked until they are
+    crds_served: AtomicBool,
+    /// Leader election state of the replica
+    lease: Mutex<LeaseState>,
+    /// Kinds whose initial watch list hasn't completed yet
+    syncing: Mutex<BTreeSet<&'static str>>,
+    /// Number of objects in the store of each controller
+    store_sizes: Mutex<BTreeMap<&'static str, StoreSize>>,
+}
+
+impl Readiness {
+    /// Hold the process unready until the initial list of the `kind` controller's `store`
+    /// has completed. Must be called from within the Tokio runtime.
+    pub fn track<K>(&self, kind: &'static str, store: Store<K>)
+    where
+        K: Resource + Clone + Send + Sync + 'static,
+        K::DynamicType: Eq + Hash + Clone + Send + Sync,
+    {
+        self.inner
+            .syncing
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(kind);
+        let reader = store.clone();
+        self.inner
+            .store_sizes
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(kind, Box::new(move || reader.state().len()));
+        let readiness = self.clone();
+        tokio::spawn(async move {
+            // Fails only when the controller is dropped, leaving the process unready.
+            if store.wait_until_ready().await.is_ok() {
+                info!("Initial list of {} completed", kind);
+                readiness
+                    .inner
+                    .syncing
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .remove(kind);
+            }
+        });
+    }
+
+    /// Record the leader election state of the replica
+    pub fn set_lease_state(&self, state: LeaseState) {
+        *self.inner.lease.lock().unwrap_or_else(|e| e.into_inner()) = state;
+    }
+
+    /// Number of objects in the store of each tracked controller, by kind
+    pub fn store_sizes(&self) -> BTreeMap<&'static str, usize> {
+        let store_sizes = self
+            .inner
+            .store_sizes
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        store_sizes
+            .iter()
+            .map(|(kind, size)| (*kind, size()))
+            .collect()
+    }
+
+    /// Ok once the process is ready, or what it is still waiting for
+    pub async fn check(&self, client: &Client) -> Result<(), String> {
+        if !self.inner.crds_served.load(Ordering::Relaxed) {
+            let missing = missing_kinds(client).await?;
+            if !missing.is_empty() {
+                return Err(format!("CRDs not installed: {}", missing.join(", ")));
+            }
+            self.inner.crds_served.store(true, Ordering::Relaxed);
+        }
+        let lease = *self.inner.lease.lock().unwrap_or_else(|e| e.into_inner());
+        if lease == LeaseState::Waiting {
+            // The controllers of a standby don't run, so their lists never complete
+            return Ok(());
+        }
+        let syncing = self.inner.syncing.lock().unwrap_or_else(|e| e.into_inner());
+        if !syncing.is_empty() {
+            let syncing: Vec<&str> = syncing.iter().copied().collect();
+            return Err(format!("waiting for initial lists: {}", syncing.join(", ")));
+        }
+        Ok(())
+    }
+}
+
+/// Watched kinds the API server doesn't serve, failing when the API server is unreachable
+async fn missing_kinds(client: &Client) -> Result<Vec<String>, String> {
+    let version = TheLeague::version(&());
+    let gv = GroupVersion::gv(&TheLeague::group(&()), &version);
+    let served: Vec<String> = match discovery::pinned_group(client, &gv).await {
+        Ok(group) => group
+            .versioned_resources(&version)
+            .into_iter()
+            .map(|(resource, _)| resource.kind)
+            .collect(),
+        // Discovery of a group version without any CRD installed fails with a 404.
+        Err(kube::Error::Api(e)) if e.code == 404 => Vec::new(),
+        Err(e) => return Err(format!("API server unreachable: {}", e)),
+    };
+    Ok(watched_kinds()
+        .into_iter()
+        .filter(|kind| !served.contains(kind))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kube::runtime::reflector::store;
+    use kube::runtime::watcher::Event;
+
+    #[tokio::test]
+    async fn test_unready_until_initial_list() {
+        let readiness = Readiness::default();
+        readiness.inner.crds_served.store(true, Ordering::Relaxed);
+        let (reader, mut writer) = store::<TheLeague>();
+        readiness.track("TheLeague", reader);
+        assert_eq!(
+            readiness
+                .inner
+                .syncing
+                .lock()
+                .unwrap()
+                .iter()
+                .copied()
+                .collect::<Vec<_>>(),
+            ["TheLeague"]
+        );
+
+        writer.apply_watcher_event(&Event::Init);
+        writer.apply_watcher_event(&Event::InitDone);
+        for _ in 0..10 {
+            if readiness.inner.syncing.lock().unwrap().is_empty() {
+                break;
+            }
+            tokio::task::yield_now().await;
+        }
+        assert!(readiness.inner.syncing.lock().unwrap().is_empty());
+        assert_eq!(readiness.store_sizes(), BTreeMap::from([("TheLeague", 0)]));
+    }
+
+    #[tokio::test]
+    async fn test_standby_is_ready_while_waiting_for_the_lease() {
+        let client =
+            Client::try_from(kube::Config::new("http://127.0.0.1:1".parse().unwrap())).unwrap();
+        let readiness = Readiness::default();
+        readiness.inner.crds_served.store(true, Ordering::Relaxed);
+        let (reader, _writer) = store::<TheLeague>();
+        readiness.track("TheLeague", reader);
+        assert!(readiness.check(&client).await.is_err());
+
+        readiness.set_lease_state(LeaseState::Waiting);
+        assert_eq!(readiness.check(&client).await, Ok(()));
+
+        // The new leader is ready once its lists completed
+        readiness.set_lease_state(LeaseState::Held);
+        assert!(readiness.check(&client).await.is_err());
+    }
+}
@@ -0,0 +1,157 @@
+use crate::api::v1alpha1::match_types::{MatchPhase, MatchSpec, MatchStatus};
+use crate::api::v1alpha1::referee_types::{Referee, RefereeSpec};
+
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::Time;
+use kube::ResourceExt;
+use std::collections::{HashMap, HashSet};
+
+/// Whether `referee` can officiate a match kicking off at `kickoff`
+pub fn is_available(referee: &RefereeSpec, kickoff: Option<&Time>) -> bool {
+    if referee.availability.is_empty() {
+        return true;
+    }
+    kickoff.is_some_and(|k| referee.availability.iter().any(|w| w.contains(k)))
+}
+
+/// Referees of `matches` (with their previous assignment in `status.referee`), in order.
+///
+/// Matches are assigned round by round. A referee officiates at most one match per round,
+/// never officiates the same team in two consecutive matches of that team and is only
+/// assigned within its availability. Previous assignments are kept while they still meet
+/// these rules, and always once the match is played; otherwise the available referee with
+/// the fewest matches so far is picked.
+pub fn assign_referees(
+    matches: &[(&MatchSpec, &MatchStatus)],
+    referees: &[Referee],
+) -> Vec<Option<String>> {
+    let mut order: Vec<usize> = (0..matches.len()).collect();
+    order.sort_by_key(|&i| (matches[i].0.season.unwrap_or_default(), matches[i].0.round));
+
+    let mut assigned = vec![None; matches.len()];
+    let mut load: HashMap<&str, u32> = HashMap::new();
+    let mut last_referee: HashMap<&str, &str> = HashMap::new();
+    let mut busy: HashSet<&str> = HashSet::new();
+    let mut current_round = None;
+    for i in order {
+        let (spec, status) = matches[i];
+        let round = (spec.season, spec.round);
+        if current_round != Some(round) {
+            current_round = Some(round);
+            busy.clear();
+        }
+
+        let eligible = |referee: &Referee| {
+            let name = referee.name_any();
+            !busy.contains(name.as_str())
+                && last_referee.get(spec.home.as_str()) != Some(&name.as_str())
+                && last_referee.get(spec.away.as_str()) != Some(&name.as_str())
+                && is_available(&referee.spec, status.kickoff.as_ref())
+        };
+        let previous = status.referee.as_deref().and_then(|name| {
+            let referee = referees.iter().find(|r| r.name_any() == name);
+            if status.phase == MatchPhase::Played || referee.is_some_and(eligible) {
+                Some(name)
+            } else {
+                None
+            }
+        });
+        let referee = previous.or_else(|| {
+            referees
+                .iter()
+                .filter(|r| eligible(r))
+                .min_by_key(|r| {
+                    let name = r.name_any();
+                    (load.get(name.as_str()).copied().unwrap_or_default(), name)
+                })
+                .map(|r| r.metadata.name.as_deref().unwrap_or_default())
+        });
+
+        if let Some(referee) = referee {
+            busy.insert(referee);
+            *load.entry(referee).or_default() += 1;
+            last_referee.insert(spec.home.as_str(), referee);
+            last_referee.insert(spec.away.as_str(), referee);
+        }
+        assigned[i] = referee.map(str::to_string);
+    }
+    assigned
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::v1alpha1::referee_types::AvailabilityWindow;
+    use k8s_openapi::chrono::{Duration, Utc};
+
+    fn fixture(round: u32, home: &str, away: &str) -> MatchSpec {
+        MatchSpec {
+            league_name: "premier".to_string(),
+            season: None,
+            round,
+            home: home.to_string(),
+            away: away.to_string(),
+            kickoff: None,
+        }
+    }
+
+    fn referee(name: &str, availability: Vec<AvailabilityWindow>) -> Referee {
+        Referee::new(
+            name,
+            RefereeSpec {
+                first_name: "Pat".to_string(),
+                last_name: "Smith".to_string(),
+                availability,
+            },
+        )
+    }
+
+    #[test]
+    fn test_referees_rotate_between_consecutive_matches_of_a_team() {
+        let specs = [
+            fixture(1, "Tigers", "Sharks"),
+            fixture(1, "Eagles", "Bears"),
+            fixture(2, "Tigers", "Eagles"),
+            fixture(2, "Sharks", "Bears"),
+        ];
+        let status = MatchStatus::default();
+        let matches: Vec<_> = specs.iter().map(|s| (s, &status)).collect();
+        let referees = [referee("ref-a", Vec::new()), referee("ref-b", Vec::new())];
+
+        let assigned = assign_referees(&matches, &referees);
+        assert_eq!(assigned[0].as_deref(), Some("ref-a"));
+        assert_eq!(assigned[1].as_deref(), Some("ref-b"));
+        // Both referees already officiated one of the teams of each round 2 match.
+        assert_eq!(assigned[2], None);
+        assert_eq!(assigned[3], None);
+    }
+
+    #[test]
+    fn test_unavailable_referees_are_replaced() {
+        let now = Utc::now();
+        let away = AvailabilityWindow {
+            start: Time(now + Duration::days(7)),
+            end: Time(now + Duration::days(14)),
+        };
+        let referees = [referee("ref-a", vec![away]), referee("ref-b", Vec::new())];
+        let spec = fixture(1, "Tigers", "Sharks");
+        let status = MatchStatus {
+            kickoff: Some(Time(now)),
+            referee: Some("ref-a".to_string()),
+            ..Default::default()
+        };
+        assert!(!is_available(&referees[0].spec, None));
+        assert_eq!(
+            assign_referees(&[(&spec, &status)], &referees),
+            vec![Some("ref-b".to_string())]
+        );
+
+        let played = MatchStatus {
+            phase: MatchPhase::Played,
+            ..status
+        };
+        assert_eq!(
+            assign_referees(&[(&spec, &played)], &referees),
+            vec![Some("ref-a".to_string())]
+        );
+    }
+}
@@ -0,0 +1,97 @@
+use crate::api::v1alpha1::the_league_types::TheLeague;
+
+use kube::{Api, Client};
+
+/// Condition type reporting whether the referenced league could be resolved
+pub const LEAGUE_RESOLVED_CONDITION: &str = "LeagueResolved";
+
+/// The namespace a `leagueName` reference points into: the explicit `leagueNamespace`
+/// when set, otherwise the namespace of the referencing object.
+pub fn league_namespace(
+    own_namespace: Option<String>,
+    league_namespace: &Option<String>,
+) -> String {
+    match league_namespace {
+        Some(namespace) if !namespace.is_empty() => namespace.clone(),
+        _ => own_namespace.unwrap_or_default(),
+    }
+}
+
+/// Outcome of resolving a league reference
+pub enum LeagueResolution {
+    /// The league exists and may be referenced from the object's namespace
+    Resolved(Box<TheLeague>),
+
+    /// The reference cannot be used; `reason` and `message` are meant for a status condition
+    Unresolved { reason: String, message: String },
+}
+
+/// Resolve the league `league_ns/league_name` referenced from an object in `from_namespace`.
+///
+/// Same-namespace references only require the league to exist. Cross-namespace references
+/// additionally require the league to list `from_namespace` in `spec.allowedNamespaces`: the
+/// owners of the league grant the namespace, whoever created the reference there.
+pub async fn resolve_league(
+    client: &Client,
+    from_namespace: &str,
+    league_ns: &str,
+    league_name: &str,
+) -> Result<LeagueResolution, kube::Error> {
+    let cross_namespace = from_namespace != league_ns;
+    let league_api: Api<TheLeague> = Api::namespaced(client.clone(), league_ns);
+    let league = match league_api.get_opt(league_name).await {
+        Ok(league) => league,
+        Err(kube::Error::Api(e)) if e.code == 403 => {
+            return Ok(LeagueResolution::Unresolved {
+                reason: "Forbidden".to_string(),
+                message: format!(
+                    "controller is not allowed to read TheLeague resources in namespace '{}'",
+                    league_ns
+                ),
+            });
+        }
+        Err(e) => return Err(e),
+    };
+    let Some(league) = league else {
+        return Ok(LeagueResolution::Unresolved {
+            reason: "LeagueNotFound".to_string(),
+            message: format!("TheLeague '{}/{}' does not exist", league_ns, league_name),
+        });
+    };
+
+    if cross_namespace
+        && !league
+            .spec
+            .allowed_namespaces
+            .iter()
+            .any(|ns| ns == from_namespace)
+    {
+        return Ok(LeagueResolution::Unresolved {
+            reason: "NamespaceNotAllowed".to_string(),
+            message: format!(
+                "TheLeague '{}/{}' does not allow references from namespace '{}'",
+                league_ns, league_name, from_namespace
+            ),
+        });
+    }
+
+    Ok(LeagueResolution::Resolved(Box::new(league)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_league_namespace_defaults_to_own_namespace() {
+        assert_eq!(league_namespace(Some("teams".to_string()), &None), "teams");
+        assert_eq!(
+            league_namespace(Some("teams".to_string()), &Some(String::new())),
+            "teams"
+        );
+        assert_eq!(
+            league_namespace(Some("teams".to_string()), &Some("leagues".to_string())),
+            "leagues"
+        );
+    }
+}
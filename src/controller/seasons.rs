@@ -0,0 +1,172 @@
+use crate::api::v1alpha1::game_result_types::GameResultSpec;
+use crate::api::v1alpha1::season_types::{Season, SeasonPhase, SeasonSpec, SeasonStatus};
+use crate::api::v1alpha1::the_league_types::{NextSeason, SeasonPolicy, TheLeague};
+use crate::controller::stamps::Stamped;
+use crate::controller::{patch_params, post_params};
+
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::Time;
+use k8s_openapi::chrono::{self, DateTime, Utc};
+use kube::api::{ListParams, Patch};
+use kube::{Api, Client, Resource, ResourceExt};
+use serde_json::json;
+use tracing::info;
+
+/// Number of the first season of a league
+pub const FIRST_SEASON: u32 = 1;
+
+/// Season a result counts in. Results recorded before the league ran in seasons keep their
+/// spec as applied and count in the first season.
+pub fn counted_season(spec: &GameResultSpec) -> u32 {
+    spec.season.unwrap_or(FIRST_SEASON)
+}
+
+/// Name of Season `number` of a league, e.g. `premier-season-2`
+pub fn season_name(league: &str, number: u32) -> String {
+    format!("{}-season-{}", league, number)
+}
+
+/// Whether a season is over: every fixture has a result or its end date has passed.
+pub fn season_over(season: &SeasonSpec, league_completed: bool, now: DateTime<Utc>) -> bool {
+    league_completed || season.end_date.as_ref().is_some_and(|end| end.0 <= now)
+}
+
+/// Keep the Seasons of a league with a season policy in line with the league: create the
+/// first Season, archive the current Season once it's over, start the next one when the
+/// policy asks for it, and follow Seasons started by organizers.
+pub async fn sync_seasons(client: &Client, league: &TheLeague) -> Result<(), kube::Error> {
+    let Some(policy) = &league.spec.season_policy else {
+        return Ok(());
+    };
+    let namespace = league.namespace().unwrap_or_default();
+    let league_name = league.name_any();
+    let season_api: Api<Season> = Api::namespaced(client.clone(), &namespace);
+    let league_api: Api<TheLeague> = Api::namespaced(client.clone(), &namespace);
+
+    let seasons: Vec<Season> = season_api
+        .list(&ListParams::default())
+        .await?
+        .items
+        .into_iter()
+        .filter(|s| s.spec.league_name == league_name)
+        .collect();
+    let status = league.status.clone().unwrap_or_default();
+
+    let active = seasons
+        .iter()
+        .filter(|s| {
+            s.status
+                .as_ref()
+                .is_none_or(|st| st.phase == SeasonPhase::Active)
+        })
+        .max_by_key(|s| s.spec.number);
+    let mut current = match active {
+        Some(season) => season.spec.number,
+        None if seasons.is_empty() => {
+            start_season(&season_api, league, policy, FIRST_SEASON).await?;
+            FIRST_SEASON
+        }
+        // Every season is archived: wait for the organizers to start the next one.
+        None => return Ok(()),
+    };
+
+    // The table and completion of the league only describe the current season once the
+    // league has switched to it.
+    if let Some(season) = active
+        && status.current_season == Some(current)
+        && season_over(&season.spec, status.completed, chrono::Utc::now())
+    {
+        let champion = status.table.first().map(|row| row.team.clone());
+        let now = chrono::Utc::now();
+        let mut archived = SeasonStatus {
+            phase: SeasonPhase::Completed,
+            table: status.table.clone(),
+            champion: champion.clone(),
+            completed_at: Some(Time(now)),
+            ..Default::default()
+        };
+        archived.stamp(now);
+        let patch = Patch::Merge(json!({ "status": archived }));
+        season_api
+            .patch_status(&season.name_any(), &patch_params(), &patch)
+            .await?;
+        info!(
+            "Archived season {} of league '{}/{}' (champion: {})",
+            current,
+            namespace,
+            league_name,
+            champion.as_deref().unwrap_or("none")
+        );
+
+        if policy.next != NextSeason::Automatic {
+            return Ok(());
+        }
+        current += 1;
+        start_season(&season_api, league, policy, current).await?;
+    }
+
+    if status.current_season != Some(current) {
+        // A new season starts with nothing played; the Standing controller recomputes the table.
+        let patch = Patch::Merge(json!({
+            "status": { "currentSeason": current, "completed": false }
+        }));
+        league_api
+            .patch_status(&league_name, &patch_params(), &patch)
+            .await?;
+    }
+    Ok(())
+}
+
+/// Create Season `number` of `league`, starting now.
+async fn start_season(
+    season_api: &Api<Season>,
+    league: &TheLeague,
+    policy: &SeasonPolicy,
+    number: u32,
+) -> Result<(), kube::Error> {
+    let league_name = league.name_any();
+    let now = chrono::Utc::now();
+    let mut season = Season::new(
+        &season_name(&league_name, number),
+        SeasonSpec {
+            league_name: league_name.clone(),
+            number,
+            start_date: Time(now),
+            end_date: policy
+                .duration_days
+                .map(|days| Time(now + chrono::Duration::days(i64::from(days)))),
+        },
+    );
+    season.metadata.owner_references = league.owner_ref(&()).map(|o| vec![o]);
+
+    match season_api.create(&post_params(), &season).await {
+        Ok(_) => {
+            info!("Started season {} of league '{}'", number, league_name);
+            Ok(())
+        }
+        Err(kube::Error::Api(e)) if e.code == 409 => Ok(()),
+        Err(e) => Err(e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_season_over_on_completion_or_end_date() {
+        let now = chrono::Utc::now();
+        let mut season = SeasonSpec {
+            league_name: "premier".to_string(),
+            number: 1,
+            start_date: Time(now - chrono::Duration::days(30)),
+            end_date: None,
+        };
+        assert!(!season_over(&season, false, now));
+        assert!(season_over(&season, true, now));
+
+        season.end_date = Some(Time(now + chrono::Duration::days(1)));
+        assert!(!season_over(&season, false, now));
+        season.end_date = Some(Time(now - chrono::Duration::days(1)));
+        assert!(season_over(&season, false, now));
+    }
+}
@@ -0,0 +1,54 @@
+//! Reporting of panics and reconcile errors to Sentry, with the `sentry` feature.
+//!
+//! Nothing is reported unless `SENTRY_DSN` is set. Panics are captured by the panic
+//! integration of the client, reconcile errors by [`SentryReporter`] with the failing object
+//! attached as tags so the events of an object or a reconciliation can be looked up.
+
+use crate::controller::errors::{ErrorReport, ErrorReporter, Severity};
+use crate::version;
+
+use sentry::{ClientInitGuard, ClientOptions, Level};
+
+/// Start the Sentry client if `SENTRY_DSN` is set. The guard flushes the pending events when
+/// dropped and must be kept alive until the process exits.
+pub fn init() -> Option<ClientInitGuard> {
+    std::env::var("SENTRY_DSN").ok()?;
+    let guard = sentry::init(ClientOptions {
+        release: Some(format!("{}+{}", version::VERSION, version::GIT_SHA).into()),
+        ..Default::default()
+    });
+    guard.is_enabled().then_some(guard)
+}
+
+/// Reporter of the reconcile errors to the Sentry client started by [`init`]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SentryReporter;
+
+impl ErrorReporter for SentryReporter {
+    fn report(&self, report: &ErrorReport) {
+        sentry::with_scope(
+            |scope| {
+                scope.set_tag("kind", report.kind);
+                scope.set_tag("namespace", &report.namespace);
+                scope.set_tag("name", &report.name);
+                if let Some(uid) = &report.uid {
+                    scope.set_tag("uid", uid);
+                }
+                if let Some(id) = report.reconcile_id {
+                    scope.set_tag("reconcile_id", id);
+                }
+            },
+            || {
+                let level = match report.severity {
+                    Severity::Warning => Level::Warning,
+                    Severity::Error => Level::Error,
+                };
+                let message = format!(
+                    "Reconcile of {} '{}/{}' failed: {}",
+                    report.kind, report.namespace, report.name, report.error
+                );
+                sentry::capture_message(&message, level);
+            },
+        );
+    }
+}
@@ -0,0 +1,141 @@
+//! lastReconciled and controllerVersion stamps of the statuses.
+//!
+//! Every status records when the controller last reconciled its resource and which build did,
+//! so operators can tell whether a resource is actively managed.
+
+use crate::api::v1alpha1::game_result_types::GameResultStatus;
+use crate::api::v1alpha1::match_types::MatchStatus;
+use crate::api::v1alpha1::player_types::PlayerStatus;
+use crate::api::v1alpha1::playoff_types::PlayoffStatus;
+use crate::api::v1alpha1::season_types::SeasonStatus;
+use crate::api::v1alpha1::standing_types::StandingStatus;
+use crate::api::v1alpha1::team_types::TeamStatus;
+use crate::api::v1alpha1::the_league_types::TheLeagueStatus;
+use crate::api::v1alpha1::tournament_types::TournamentStatus;
+use crate::api::v1alpha1::transfer_types::TransferStatus;
+use crate::controller::patch_params;
+
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::Time;
+use k8s_openapi::chrono::{DateTime, Duration, Utc};
+use kube::Api;
+use kube::api::Patch;
+use serde::de::DeserializeOwned;
+use serde_json::json;
+use std::fmt::Debug;
+
+/// Version of the controller build stamped into the statuses
+pub const CONTROLLER_VERSION: &str = crate::version::VERSION;
+
+/// Minimum age of a stamp before a reconcile renews it. Renewing it on every reconcile would
+/// retrigger the reconcile through the watch on the resource itself.
+const STAMP_INTERVAL_SECS: i64 = 60;
+
+/// A status carrying lastReconciled and controllerVersion stamps
+pub trait Stamped: Sized {
+    /// Time of the last successful reconcile
+    fn last_reconciled(&self) -> Option<&Time>;
+
+    /// Version of the controller build that last reconciled the resource
+    fn controller_version(&self) -> Option<&str>;
+
+    /// Stamp the status as reconciled at `now` by this build
+    fn stamp(&mut self, now: DateTime<Utc>);
+
+    /// The status without its stamps, to compare it with a freshly computed status
+    fn unstamped(self) -> Self;
+
+    /// Whether the stamps are older than the stamp interval or from another build at `now`
+    fn stamp_due(&self, now: DateTime<Utc>) -> bool {
+        self.controller_version() != Some(CONTROLLER_VERSION)
+            || self
+                .last_reconciled()
+                .is_none_or(|t| now - t.0 >= Duration::seconds(STAMP_INTERVAL_SECS))
+    }
+}
+
+macro_rules! impl_stamped {
+    ($($status:ty),* $(,)?) => {
+        $(
+            impl Stamped for $status {
+                fn last_reconciled(&self) -> Option<&Time> {
+                    self.last_reconciled.as_ref()
+                }
+
+                fn controller_version(&self) -> Option<&str> {
+                    self.controller_version.as_deref()
+                }
+
+                fn stamp(&mut self, now: DateTime<Utc>) {
+                    self.last_reconciled = Some(Time(now));
+                    self.controller_version = Some(CONTROLLER_VERSION.to_string());
+                }
+
+                fn unstamped(self) -> Self {
+                    Self {
+                        last_reconciled: None,
+                        controller_version: None,
+                        ..self
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_stamped!(
+    GameResultStatus,
+    MatchStatus,
+    PlayerStatus,
+    PlayoffStatus,
+    SeasonStatus,
+    StandingStatus,
+    TeamStatus,
+    TheLeagueStatus,
+    TournamentStatus,
+    TransferStatus,
+);
+
+/// Record a successful reconcile of the `K` named `name` with the `status` it was read with,
+/// unless its stamps are recent.
+pub async fn stamp_reconciled<K, S>(
+    api: &Api<K>,
+    name: &str,
+    status: Option<&S>,
+) -> Result<(), kube::Error>
+where
+    K: Clone + DeserializeOwned + Debug,
+    S: Stamped,
+{
+    let now = Utc::now();
+    if status.is_some_and(|s| !s.stamp_due(now)) {
+        return Ok(());
+    }
+    let patch = Patch::Merge(json!({
+        "status": {
+            "lastReconciled": Time(now),
+            "controllerVersion": CONTROLLER_VERSION,
+        }
+    }));
+    api.patch_status(name, &patch_params(), &patch).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stamp_is_renewed_after_the_interval_or_on_upgrade() {
+        let now = Utc::now();
+        let mut status = TeamStatus::default();
+        assert!(status.stamp_due(now));
+
+        status.stamp(now);
+        assert!(!status.stamp_due(now + Duration::seconds(STAMP_INTERVAL_SECS - 1)));
+        assert!(status.stamp_due(now + Duration::seconds(STAMP_INTERVAL_SECS)));
+
+        status.controller_version = Some("0.0.0".to_string());
+        assert!(status.stamp_due(now));
+        assert_eq!(status.unstamped(), TeamStatus::default());
+    }
+}
@@ -0,0 +1,567 @@
+use crate::api::conditions::{ConditionStatus, Conditions};
+use crate::api::v1alpha1::game_result_types::{GameResult, GameResultSpec};
+use crate::api::v1alpha1::referee_types::Referee;
+use crate::api::v1alpha1::standing_types::{Standing, StandingResolution, StandingStatus};
+use crate::api::v1alpha1::the_league_types::{
+    LeaguePhase, NotificationEvent, TableRow, TheLeague, WithdrawalPolicy,
+};
+use crate::api::v1alpha1::venue_types::Venue;
+use crate::controller::notifications::{self, Notification};
+use crate::controller::references::{self, LEAGUE_RESOLVED_CONDITION, LeagueResolution};
+use crate::controller::stamps::{self, Stamped};
+use crate::controller::{
+    Context, audit, configmap_export, errors, gameresult_controller, matches, patch_params,
+    propagation, seasons, suspensions, teams, traced, watched_api,
+};
+use crate::schedule;
+use crate::standings::{self, TableEntry, TableOptions};
+
+use futures::StreamExt;
+use kube::api::{ListParams, Patch};
+use kube::runtime::finalizer::{self, Event as FinalizerEvent, finalizer};
+use kube::runtime::reflector::{ObjectRef, Store};
+use kube::runtime::{controller::Controller as KubeController, watcher};
+use kube::{Api, Client, ResourceExt, runtime::controller::Action};
+use serde_json::json;
+use std::collections::HashSet;
+use std::sync::Arc;
+use tokio::time::Duration;
+use tracing::{info, info_span, instrument};
+
+/// Finalizer removing a withdrawn team from its league table before the Standing goes away
+pub const STANDING_FINALIZER: &str = "standings.bexxmodd.com/withdrawal";
+
+/// Controller keeping every Standing of a league in sync with its GameResults
+pub struct StandingReconciler {
+    context: Arc<Context>,
+    controller: KubeController<Standing>,
+}
+
+impl StandingReconciler {
+    /// Create a new StandingReconciler
+    pub fn new(context: Arc<Context>) -> Self {
+        let standing_api: Api<Standing> = watched_api(context.client.clone());
+        let result_api: Api<GameResult> = watched_api(context.client.clone());
+        let league_api: Api<TheLeague> = watched_api(context.client.clone());
+        let referee_api: Api<Referee> = watched_api(context.client.clone());
+        let venue_api: Api<Venue> = watched_api(context.client.clone());
+
+        let controller = KubeController::new(standing_api, watcher::Config::default());
+        context.readiness.track("Standing", controller.store());
+        let store = controller.store();
+        // A new or changed GameResult only needs to wake up the Standings of the two teams
+        // involved: any reconcile recomputes the whole league table.
+        let controller =
+            controller.watches(result_api, watcher::Config::default(), move |result| {
+                let league_ns =
+                    references::league_namespace(result.namespace(), &result.spec.league_namespace);
+                store
+                    .state()
+                    .into_iter()
+                    .filter(|s| {
+                        references::league_namespace(s.namespace(), &s.spec.league_namespace)
+                            == league_ns
+                            && s.spec.league_name == result.spec.league_name
+                            && result.spec.teams.contains(&s.spec.team_name)
+                    })
+                    .map(|s| ObjectRef::from_obj(s.as_ref()))
+                    .collect::<Vec<_>>()
+            });
+        // League settings (withdrawal policy, exports) affect the table: wake up one of its Standings.
+        let store = controller.store();
+        let controller =
+            controller.watches(league_api, watcher::Config::default(), move |league| {
+                let namespace = league.namespace().unwrap_or_default();
+                let name = league.name_any();
+                store
+                    .find(|s| {
+                        references::league_namespace(s.namespace(), &s.spec.league_namespace)
+                            == namespace
+                            && s.spec.league_name == name
+                    })
+                    .map(|s| ObjectRef::from_obj(s.as_ref()))
+            });
+        // Referees and Venues are shared by the leagues of their namespace: wake up one
+        // Standing of each of them.
+        let store = controller.store();
+        let controller =
+            controller.watches(referee_api, watcher::Config::default(), move |referee| {
+                one_standing_per_league(&store, &referee.namespace().unwrap_or_default())
+            });
+        let store = controller.store();
+        let controller = controller.watches(venue_api, watcher::Config::default(), move |venue| {
+            one_standing_per_league(&store, &venue.namespace().unwrap_or_default())
+        });
+
+        Self {
+            context,
+            controller,
+        }
+    }
+
+    /// Reconcile a Standing resource (static method)
+    pub async fn reconcile(
+        standing: Arc<Standing>,
+        ctx: Arc<Context>,
+    ) -> Result<Action, finalizer::Error<kube::Error>> {
+        let namespace = standing.namespace().unwrap_or_default();
+        let standing_api: Api<Standing> = Api::namespaced(ctx.client.clone(), &namespace);
+        info!(
+            "reconcile request: standing {} of league {}",
+            standing.name_any(),
+            standing.spec.league_name
+        );
+
+        finalizer(&standing_api, STANDING_FINALIZER, standing, |event| async {
+            match event {
+                FinalizerEvent::Apply(standing) => Self::apply(standing, ctx.clone()).await,
+                FinalizerEvent::Cleanup(standing) => Self::cleanup(standing, ctx.clone()).await,
+            }
+        })
+        .await
+    }
+
+    /// Validate the league reference of a live Standing and recompute its league
+    async fn apply(standing: Arc<Standing>, ctx: Arc<Context>) -> Result<Action, kube::Error> {
+        let namespace = standing.namespace().unwrap_or_default();
+        let league_ns =
+            references::league_namespace(standing.namespace(), &standing.spec.league_namespace);
+        let league_name = &standing.spec.league_name;
+
+        match references::resolve_league(&ctx.client, &namespace, &league_ns, league_name).await? {
+            LeagueResolution::Resolved(_) => {
+                set_league_condition(
+                    &ctx.client,
+                    &standing,
+                    ConditionStatus::True,
+                    "LeagueFound",
+                    "league reference resolved",
+                )
+                .await?;
+            }
+            LeagueResolution::Unresolved { reason, message } => {
+                info!(
+                    "Standing '{}' has an invalid league reference: {}",
+                    standing.name_any(),
+                    message
+                );
+                set_league_condition(
+                    &ctx.client,
+                    &standing,
+                    ConditionStatus::False,
+                    &reason,
+                    &message,
+                )
+                .await?;
+                // The league may still be created (or allow this namespace) later.
+                return Ok(Action::requeue(Duration::from_secs(60)));
+            }
+        }
+
+        // Ranks depend on every team of the league, so only one recompute per league may run at a time.
+        let _guard = ctx.locks.lock(&league_ns, league_name).await;
+        recompute_league(&ctx.client, &league_ns, league_name).await?;
+
+        let standing_api: Api<Standing> = Api::namespaced(ctx.client.clone(), &namespace);
+        stamps::stamp_reconciled(
+            &standing_api,
+            &standing.name_any(),
+            standing.status.as_ref(),
+        )
+        .await?;
+        Ok(Action::requeue(Duration::from_secs(3600)))
+    }
+
+    /// Withdraw the team of a deleted Standing from its league.
+    ///
+    /// The recompute sees the Standing as terminating, drops its row from the league table
+    /// and records the team as withdrawn so opponents' records follow the withdrawal policy.
+    async fn cleanup(standing: Arc<Standing>, ctx: Arc<Context>) -> Result<Action, kube::Error> {
+        let league_ns =
+            references::league_namespace(standing.namespace(), &standing.spec.league_namespace);
+        let league_name = &standing.spec.league_name;
+        info!(
+            "Standing '{}' deleted, withdrawing team '{}' from league '{}'",
+            standing.name_any(),
+            standing.spec.team_name,
+            league_name
+        );
+
+        let _guard = ctx.locks.lock(&league_ns, league_name).await;
+        recompute_league(&ctx.client, &league_ns, league_name).await?;
+
+        Ok(Action::await_change())
+    }
+
+    /// Handle errors that occur during reconciliation (static method)
+    pub fn error_policy(
+        object: Arc<Standing>,
+        err: &finalizer::Error<kube::Error>,
+        ctx: Arc<Context>,
+    ) -> Action {
+        errors::report(&ctx, "Standing", object.as_ref(), err);
+        Action::requeue(Duration::from_secs(5))
+    }
+
+    pub fn stream(self) -> impl futures::Future<Output = ()> {
+        let context = self.context.clone();
+        let metrics = &self.context.metrics.controller;
+        let results = self.controller.shutdown_on_signal().run(
+            |object, ctx| {
+                let reconcile = traced(
+                    "Standing",
+                    object.as_ref(),
+                    StandingReconciler::reconcile(object.clone(), ctx),
+                );
+                metrics.measure("Standing", reconcile)
+            },
+            StandingReconciler::error_policy,
+            context,
+        );
+        self.context
+            .heartbeat
+            .beating("Standing", results)
+            .for_each(move |result| {
+                metrics.observe("Standing", &result);
+                futures::future::ready(())
+            })
+    }
+}
+
+/// One Standing of every league in `namespace`
+fn one_standing_per_league(store: &Store<Standing>, namespace: &str) -> Vec<ObjectRef<Standing>> {
+    let mut leagues = HashSet::new();
+    store
+        .state()
+        .into_iter()
+        .filter(|s| {
+            references::league_namespace(s.namespace(), &s.spec.league_namespace) == namespace
+                && leagues.insert(s.spec.league_name.clone())
+        })
+        .map(|s| ObjectRef::from_obj(s.as_ref()))
+        .collect()
+}
+
+/// Recompute the table of a league and write the result into the status of each of its
+/// Standings and into the embedded table of the league itself. The Standings also get the
+/// labels and annotations the league propagates.
+///
+/// Standings and results are collected from the league namespace and from every namespace
+/// listed in `spec.allowedNamespaces`. Standings that are being deleted are treated as
+/// withdrawn teams. Changes of the table are appended to the audit trail of the league, and
+/// the table is exported as the league gauges of the process metrics. Callers must hold the
+/// league lock from [`Context::locks`].
+#[instrument(skip(client))]
+pub async fn recompute_league(
+    client: &Client,
+    namespace: &str,
+    league_name: &str,
+) -> Result<Vec<TableEntry>, kube::Error> {
+    let league_api: Api<TheLeague> = Api::namespaced(client.clone(), namespace);
+    let league = league_api.get_opt(league_name).await?;
+
+    let mut namespaces = vec![namespace.to_string()];
+    if let Some(league) = &league {
+        namespaces.extend(league.spec.allowed_namespaces.iter().cloned());
+    }
+    namespaces.sort();
+    namespaces.dedup();
+
+    let mut standings = Vec::new();
+    let mut withdrawing = Vec::new();
+    let mut results = Vec::new();
+    for ns in &namespaces {
+        let standing_api: Api<Standing> = Api::namespaced(client.clone(), ns);
+        let result_api: Api<GameResult> = Api::namespaced(client.clone(), ns);
+        for standing in standing_api.list(&ListParams::default()).await?.items {
+            let league_ns =
+                references::league_namespace(standing.namespace(), &standing.spec.league_namespace);
+            if league_ns != namespace || standing.spec.league_name != league_name {
+                continue;
+            }
+            if standing.metadata.deletion_timestamp.is_none() {
+                standings.push(standing);
+            } else {
+                withdrawing.push(standing);
+            }
+        }
+        results.extend(
+            result_api
+                .list(&ListParams::default())
+                .await?
+                .items
+                .into_iter()
+                .filter(|r| {
+                    references::league_namespace(r.namespace(), &r.spec.league_namespace)
+                        == namespace
+                        && r.spec.league_name == league_name
+                        && gameresult_controller::is_accepted(r)
+                })
+                // Corrected results complete the fixture of the round they count in.
+                .map(|mut r| {
+                    r.spec.round_number = r.round();
+                    r
+                }),
+        );
+    }
+
+    let teams: Vec<String> = standings.iter().map(|s| s.spec.team_name.clone()).collect();
+
+    // A team re-joining the league with a new Standing is no longer withdrawn.
+    let mut withdrawn: Vec<String> = league
+        .as_ref()
+        .and_then(|l| l.status.as_ref())
+        .map(|s| s.withdrawn_teams.clone())
+        .unwrap_or_default();
+    withdrawn.extend(withdrawing.iter().map(|s| s.spec.team_name.clone()));
+    withdrawn.retain(|team| !teams.contains(team));
+    withdrawn.sort();
+    withdrawn.dedup();
+
+    let mut options = TableOptions::new(table_resolution(&standings));
+    if let Some(league) = &league {
+        options.points = league.spec.points_policy.clone();
+    }
+    let policy = league
+        .as_ref()
+        .map(|l| l.spec.withdrawal_policy.clone())
+        .unwrap_or_default();
+    if policy == WithdrawalPolicy::KeepResults {
+        options.kept_opponents = withdrawn.clone();
+    }
+
+    // Leagues running in seasons only count the results of the current season, and playoff
+    // and tournament games never count for the table.
+    let season = league
+        .as_ref()
+        .and_then(|l| l.status.as_ref())
+        .and_then(|s| s.current_season);
+    let specs: Vec<&GameResultSpec> = results
+        .iter()
+        .map(|r| &r.spec)
+        .filter(|spec| season.is_none_or(|s| seasons::counted_season(spec) == s))
+        .filter(|spec| spec.is_league_game())
+        .collect();
+    let (table, (league_attendance, team_attendance)) =
+        info_span!("compute_table", teams = teams.len(), results = specs.len()).in_scope(|| {
+            (
+                standings::compute_table(&teams, &specs, &options),
+                standings::compute_attendance(&teams, &specs, &options),
+            )
+        });
+
+    // Branding is taken from the league entries, with referenced Teams filling in the gaps.
+    let league_spec = match &league {
+        Some(league) => Some(teams::resolve_teams(client, league).await?.0),
+        None => None,
+    };
+    let branding = |team: &str| {
+        league_spec
+            .as_ref()
+            .and_then(|spec| spec.teams.iter().find(|t| t.name == team))
+            .and_then(|t| t.branding())
+    };
+
+    if let Some(league) = &league {
+        let propagated = propagation::propagated(league);
+        for standing in &standings {
+            let standing_api: Api<Standing> =
+                Api::namespaced(client.clone(), &standing.namespace().unwrap_or_default());
+            propagation::sync_metadata(&standing_api, standing, &propagated).await?;
+        }
+    }
+
+    for standing in &standings {
+        let Some(entry) = table.iter().find(|e| e.team == standing.spec.team_name) else {
+            continue;
+        };
+        // Conditions and stamps are owned by the reconcile of each Standing, never written here.
+        let mut current = standing.status.clone().unwrap_or_default().unstamped();
+        current.conditions.clear();
+        let attendance = team_attendance
+            .get(&standing.spec.team_name)
+            .copied()
+            .unwrap_or_default();
+        let status = StandingStatus {
+            rank: entry.rank,
+            played: entry.played,
+            points: entry.points,
+            wins: entry.wins,
+            losses: entry.losses,
+            draws: entry.draws,
+            goals_for: entry.goals_for,
+            goals_against: entry.goals_against,
+            total_attendance: attendance.total,
+            average_attendance: attendance.average(),
+            branding: branding(&entry.team),
+            conditions: Vec::new(),
+            last_reconciled: None,
+            controller_version: None,
+        };
+        if status == current {
+            continue;
+        }
+        let standing_api: Api<Standing> =
+            Api::namespaced(client.clone(), &standing.namespace().unwrap_or_default());
+        let patch = Patch::Merge(json!({ "status": status }));
+        standing_api
+            .patch_status(&standing.name_any(), &patch_params(), &patch)
+            .await?;
+    }
+
+    if let Some(league) = &league {
+        let rows: Vec<TableRow> = table
+            .iter()
+            .map(|entry| TableRow {
+                branding: branding(&entry.team),
+                ..TableRow::from(entry)
+            })
+            .collect();
+        let divisions = standings::division_tables(&rows, &league.spec.divisions);
+        let current = league.status.clone().unwrap_or_default();
+        // A league whose schedule can't be computed is reported by its own reconciler.
+        let completed = schedule::league_schedule(&league.spec).is_ok_and(|schedule| {
+            schedule.round_count() > 0 && schedule.outstanding_fixtures(&specs).is_empty()
+        });
+        let phase = if completed {
+            LeaguePhase::Completed
+        } else if rows.iter().any(|row| row.played > 0) {
+            LeaguePhase::InProgress
+        } else {
+            LeaguePhase::Pending
+        };
+        if current.table != rows
+            || current.divisions != divisions
+            || current.withdrawn_teams != withdrawn
+            || current.completed != completed
+            || current.phase != phase
+            || current.total_attendance != league_attendance.total
+            || current.average_attendance != league_attendance.average()
+        {
+            let patch = Patch::Merge(json!({
+                "status": {
+                    "table": rows,
+                    "divisions": divisions,
+                    "withdrawnTeams": withdrawn,
+                    "completed": completed,
+                    "phase": phase,
+                    "totalAttendance": league_attendance.total,
+                    "averageAttendance": league_attendance.average(),
+                }
+            }));
+            league_api
+                .patch_status(league_name, &patch_params(), &patch)
+                .await?;
+        }
+        audit::record_table_changes(client, league, &current.table, &rows).await?;
+        let just_completed = completed && !current.completed;
+        notify_table_changes(client, league, &current.table, &rows, just_completed).await;
+        configmap_export::sync_standings_configmap(client, league, &table, &rows).await?;
+        suspensions::sync_suspensions(client, league, &specs).await?;
+        matches::sync_match_status(client, league, &results).await?;
+        crate::metrics::metrics()
+            .leagues
+            .set_table(namespace, league_name, specs.len(), &table);
+    } else {
+        crate::metrics::metrics()
+            .leagues
+            .remove_league(namespace, league_name);
+    }
+
+    info!(
+        "Recomputed table of league '{}/{}' ({} teams, {} results)",
+        namespace,
+        league_name,
+        table.len(),
+        results.len()
+    );
+    Ok(table)
+}
+
+/// Set the LeagueResolved condition of a Standing, keeping the transition time when the status is unchanged
+async fn set_league_condition(
+    client: &Client,
+    standing: &Standing,
+    status: ConditionStatus,
+    reason: &str,
+    message: &str,
+) -> Result<(), kube::Error> {
+    let mut conditions = Conditions::new(
+        standing
+            .status
+            .as_ref()
+            .map(|s| s.conditions.as_slice())
+            .unwrap_or_default(),
+        standing.metadata.generation,
+    );
+    if !conditions.set(LEAGUE_RESOLVED_CONDITION, status, reason, message) {
+        return Ok(());
+    }
+
+    let standing_api: Api<Standing> =
+        Api::namespaced(client.clone(), &standing.namespace().unwrap_or_default());
+    let patch = Patch::Merge(json!({ "status": { "conditions": conditions } }));
+    standing_api
+        .patch_status(&standing.name_any(), &patch_params(), &patch)
+        .await?;
+    Ok(())
+}
+
+/// Notify the league's notification targets about teams moving in the table and, when
+/// `just_completed`, about the completion of the league.
+async fn notify_table_changes(
+    client: &Client,
+    league: &TheLeague,
+    old: &[TableRow],
+    new: &[TableRow],
+    just_completed: bool,
+) {
+    // The first table of a league is not a change.
+    let changes = if old.is_empty() {
+        Vec::new()
+    } else {
+        notifications::rank_changes(old, new)
+    };
+    if !changes.is_empty() {
+        let message = changes
+            .iter()
+            .map(|(team, from, to)| format!("{} {} -> {}", team, from, to))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let details: Vec<_> = changes
+            .iter()
+            .map(|(team, from, to)| json!({ "team": team, "from": from, "to": to }))
+            .collect();
+        let notification = Notification {
+            event: NotificationEvent::RanksChanged,
+            message: format!("Rank changes: {}", message),
+            details: json!({ "changes": details }),
+        };
+        notifications::notify(client, league, notification).await;
+    }
+
+    if just_completed && let Some(leader) = new.first() {
+        let notification = Notification {
+            event: NotificationEvent::LeagueCompleted,
+            message: format!(
+                "League completed, {} finishes first with {} points",
+                leader.team, leader.points
+            ),
+            details: json!({ "table": new }),
+        };
+        notifications::notify(client, league, notification).await;
+    }
+}
+
+/// The tie-breaker used for a whole table: Head2Head only when every Standing of the league asks for it.
+fn table_resolution(standings: &[Standing]) -> StandingResolution {
+    if !standings.is_empty()
+        && standings
+            .iter()
+            .all(|s| s.spec.resolution == StandingResolution::Head2Head)
+    {
+        StandingResolution::Head2Head
+    } else {
+        StandingResolution::GoalDifference
+    }
+}
@@ -0,0 +1,63 @@
+use crate::api::v1alpha1::game_result_types::GameResultSpec;
+use crate::api::v1alpha1::suspension_types::Suspension;
+use crate::api::v1alpha1::the_league_types::TheLeague;
+use crate::controller::post_params;
+use crate::discipline;
+
+use kube::api::ListParams;
+use kube::{Api, Client, Resource, ResourceExt};
+use std::collections::HashSet;
+use tracing::info;
+
+/// Create a Suspension for every suspension the players of the league have earned with the
+/// cards in `results`, according to the league's suspension policy.
+///
+/// Existing Suspensions are never touched, so the `served` flag set by organizers is kept.
+/// Returns the number of Suspensions created.
+pub async fn sync_suspensions(
+    client: &Client,
+    league: &TheLeague,
+    results: &[&GameResultSpec],
+) -> Result<usize, kube::Error> {
+    let Some(policy) = &league.spec.suspension_policy else {
+        return Ok(0);
+    };
+    let namespace = league.namespace().unwrap_or_default();
+    let league_name = league.name_any();
+    let suspension_api: Api<Suspension> = Api::namespaced(client.clone(), &namespace);
+
+    let existing: HashSet<String> = suspension_api
+        .list(&ListParams::default())
+        .await?
+        .items
+        .iter()
+        .map(|s| s.name_any())
+        .collect();
+    let mut created = 0;
+    for due in discipline::due_suspensions(&discipline::card_tally(results), policy) {
+        if existing.contains(&due.name(&league_name)) {
+            continue;
+        }
+        let mut suspension = Suspension::new(&due.name(&league_name), due.spec(&league_name));
+        suspension.metadata.owner_references = league.owner_ref(&()).map(|o| vec![o]);
+
+        match suspension_api.create(&post_params(), &suspension).await {
+            Ok(_) => {
+                info!(
+                    "Suspended {} {} of team '{}' in league '{}/{}' ({:?})",
+                    due.player.first_name,
+                    due.player.last_name,
+                    due.player.team,
+                    namespace,
+                    league_name,
+                    due.reason
+                );
+                created += 1;
+            }
+            // Created since the list
+            Err(kube::Error::Api(e)) if e.code == 409 => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(created)
+}
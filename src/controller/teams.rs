@@ -0,0 +1,125 @@
+use crate::api::v1alpha1::team_types::Team as TeamObject;
+use crate::api::v1alpha1::the_league_types::{TheLeague, TheLeagueSpec};
+
+use kube::api::ListParams;
+use kube::{Api, Client, ResourceExt};
+
+/// Condition type reporting whether every team reference of a league could be resolved
+pub const TEAMS_RESOLVED_CONDITION: &str = "TeamsResolved";
+
+/// Condition type reporting whether every player of a league is registered to a single team
+/// and wears a jersey number unique within that team
+pub const ROSTERS_VALID_CONDITION: &str = "RostersValid";
+
+/// Condition type reporting whether the captain of every team of a league is on its roster
+pub const CAPTAINS_VALID_CONDITION: &str = "CaptainsValid";
+
+/// Condition type reporting whether the roster of every team of a league is within the roster
+/// size limits. Leagues don't go live while it is False.
+pub const ROSTER_SIZES_VALID_CONDITION: &str = "RosterSizesValid";
+
+/// Condition type reporting whether every player of a league meets its eligibility rules
+pub const PLAYERS_ELIGIBLE_CONDITION: &str = "PlayersEligible";
+
+/// Whether a team entry of `league` references the Team `team_name`
+pub fn references_team(league: &TheLeagueSpec, team_name: &str) -> bool {
+    league
+        .teams
+        .iter()
+        .any(|t| t.team_ref.as_deref() == Some(team_name))
+}
+
+/// Fill in the rosters of the referenced teams of `league` from `teams`.
+/// Returns the names of the referenced Teams that don't exist.
+pub fn apply_team_refs(league: &mut TheLeagueSpec, teams: &[TeamObject]) -> Vec<String> {
+    let mut missing = Vec::new();
+    for entry in &mut league.teams {
+        let Some(team_ref) = &entry.team_ref else {
+            continue;
+        };
+        let Some(team) = teams.iter().find(|t| &t.name_any() == team_ref) else {
+            missing.push(team_ref.clone());
+            continue;
+        };
+        entry.players = team.spec.players.clone();
+        if entry.description.is_none() {
+            entry.description = team.spec.description.clone();
+        }
+        if entry.location.is_none() {
+            entry.location = team.spec.home_venue.clone();
+        }
+        if entry.abbreviation.is_none() {
+            entry.abbreviation = team.spec.abbreviation.clone();
+        }
+        if entry.colors.is_empty() {
+            entry.colors = team.spec.colors.clone();
+        }
+        if entry.logo_url.is_none() {
+            entry.logo_url = team.spec.logo_url.clone();
+        }
+        if entry.captain.is_none() {
+            entry.captain = team.spec.captain.clone();
+        }
+    }
+    missing
+}
+
+/// Spec of `league` with the rosters of its referenced teams resolved, and the names of the
+/// referenced Teams that don't exist. Leagues embedding every roster need no lookup.
+pub async fn resolve_teams(
+    client: &Client,
+    league: &TheLeague,
+) -> Result<(TheLeagueSpec, Vec<String>), kube::Error> {
+    let mut spec = league.spec.clone();
+    if spec.teams.iter().all(|t| t.team_ref.is_none()) {
+        return Ok((spec, Vec::new()));
+    }
+    let team_api: Api<TeamObject> =
+        Api::namespaced(client.clone(), &league.namespace().unwrap_or_default());
+    let teams = team_api.list(&ListParams::default()).await?.items;
+    let missing = apply_team_refs(&mut spec, &teams);
+    Ok((spec, missing))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::v1alpha1::team_types::TeamSpec;
+    use crate::api::v1alpha1::the_league_types::{Player, Team};
+
+    fn entry(name: &str, team_ref: Option<&str>) -> Team {
+        Team {
+            team_ref: team_ref.map(str::to_string),
+            ..Team::named(name)
+        }
+    }
+
+    #[test]
+    fn test_apply_team_refs_resolves_rosters_and_reports_missing_teams() {
+        let mut league = TheLeagueSpec::with_teams(&[]).with_team_entries(vec![
+            entry("Tigers", Some("tigers")),
+            entry("Sharks", Some("sharks")),
+            entry("Eagles", None),
+        ]);
+        let tigers = TeamObject::new(
+            "tigers",
+            TeamSpec {
+                description: None,
+                home_venue: Some("Riverside Park".to_string()),
+                abbreviation: Some("TIG".to_string()),
+                colors: Vec::new(),
+                logo_url: None,
+                captain: None,
+                players: vec![Player::named("Alex", "Morgan")],
+            },
+        );
+
+        assert!(references_team(&league, "tigers"));
+        assert!(!references_team(&league, "eagles"));
+        assert_eq!(apply_team_refs(&mut league, &[tigers]), vec!["sharks"]);
+        assert_eq!(league.teams[0].players.len(), 1);
+        assert_eq!(league.teams[0].location.as_deref(), Some("Riverside Park"));
+        assert_eq!(league.teams[0].abbreviation.as_deref(), Some("TIG"));
+        assert!(league.teams[1].players.is_empty());
+    }
+}
@@ -1,21 +1,32 @@
-use crate::api::v1alpha1::the_league_types::{TheLeague, TheLeagueStatus};
+use crate::api::conditions::{ConditionStatus, Conditions};
+use crate::api::v1alpha1::team_types::Team;
+use crate::api::v1alpha1::the_league_types::{Enforcement, TheLeague, TheLeagueStatus};
+use crate::controller::teams::{
+    self, CAPTAINS_VALID_CONDITION, PLAYERS_ELIGIBLE_CONDITION, ROSTER_SIZES_VALID_CONDITION,
+    ROSTERS_VALID_CONDITION, TEAMS_RESOLVED_CONDITION,
+};
+use crate::controller::stamps;
+use crate::controller::{
+    Context, errors, fixtures, matches, patch_params, seasons, traced, validation_policy,
+    watched_api,
+};
+use crate::schedule;
+use crate::validation;
 
 use futures::StreamExt;
-use k8s_openapi::apimachinery::pkg::apis::meta::v1;
-use k8s_openapi::chrono;
+use kube::runtime::events::{Event, EventType};
+use kube::runtime::reflector::ObjectRef;
 use kube::runtime::{controller::Controller as KubeController, watcher};
-use kube::{Api, Client, ResourceExt, runtime::controller::Action};
+use kube::{Api, Resource, ResourceExt, runtime::controller::Action};
+use k8s_openapi::chrono::Utc;
 use kube::api;
+use serde_json::json;
 use std::sync::Arc;
 use tokio::time::Duration;
 use tracing::{info, error};
 
-/// Context shared between the controller and the worker threads
-#[derive(Clone)]
-pub struct Context {
-    /// Kubernetes client
-    pub client: Client,
-}
+/// Condition type set to True on leagues whose schedule can't be built
+pub const INVALID_SCHEDULE_CONDITION: &str = "InvalidSchedule";
 
 /// Controller for managing TheLeague resources
 pub struct Reconciler {
@@ -27,17 +38,7 @@ impl Reconciler {
     /// Create a new TheLeagueController
     pub fn new(context: Arc<Context>) -> Self {
         // Configure default namespace(s) - equivalent to cache.Options.DefaultNamespaces in Go
-        // If WATCH_NAMESPACE is set, watch only that namespace; otherwise watch all namespaces
-        let league_api: Api<TheLeague> = match std::env::var("WATCH_NAMESPACE") {
-            Ok(namespace) if !namespace.is_empty() => {
-                info!("Watching namespace: {}", namespace);
-                Api::namespaced(context.client.clone(), &namespace)
-            }
-            _ => {
-                info!("Watching all namespaces");
-                Api::all(context.client.clone())
-            }
-        };
+        let league_api: Api<TheLeague> = watched_api(context.client.clone());
 
         // Configure watcher with cache options (equivalent to cache.Options in Go)
         // You can customize the watcher config here, e.g.:
@@ -51,6 +52,20 @@ impl Reconciler {
             // .backoff(backoff::ExponentialBackoff::default())
             ;
         let controller = KubeController::new(league_api, watcher_config);
+        context.readiness.track("TheLeague", controller.store());
+        context.caches.share_leagues(controller.store());
+        let leagues = controller.store();
+        // Roster changes of a Team are picked up by the leagues referencing it.
+        let team_api: Api<Team> = watched_api(context.client.clone());
+        let controller = controller.watches(team_api, watcher::Config::default(), move |team| {
+            leagues
+                .state()
+                .into_iter()
+                .filter(|l| l.namespace() == team.namespace())
+                .filter(|l| teams::references_team(&l.spec, &team.name_any()))
+                .map(|l| ObjectRef::from_obj(l.as_ref()))
+                .collect::<Vec<_>>()
+        });
         Self {
             context,
             controller,
@@ -83,47 +98,277 @@ impl Reconciler {
                 return Err(e)
             }
         };
-        let current_conditions = league.status.as_ref().map(|s| &s.conditions).unwrap_or(&vec![]);
-        if !current_conditions.is_empty() {
+        let current_conditions = league.status.as_ref().map(|s| s.conditions.as_slice()).unwrap_or_default();
+        let league = if current_conditions.is_empty() {
             // 1. Define initial status condition
-            let initial_condition = v1::Condition {
-                type_: String::from("Processing"),
-                status: "Unknown".to_string(), // Equivalent to metav1.ConditionUnknown
-                reason: String::from("Reconciling"),
-                message: "Starting reconciliation".to_string(),
-                // Required timestamp and generation fields
-                last_transition_time:v1::Time(chrono::Utc::now()),
-                observed_generation: league.metadata.generation, 
-            };
+            let mut conditions = Conditions::new(&[], league.metadata.generation);
+            conditions.set(
+                "Processing",
+                ConditionStatus::Unknown,
+                "Reconciling",
+                "Starting reconciliation",
+            );
 
             // 2. Create the initial status object for patching
             let initial_status = TheLeagueStatus {
                 live: false, 
-                conditions: vec![initial_condition],
+                conditions: conditions.into_vec(),
+                ..Default::default()
             };
 
-            //     // 3. Patch Status: Equivalent to Go's `r.Status().Update()`
-            // let status_patch = api::Patch::Merge(TheLeague {
-            //     status: Some(initial_status),
-            //     // Ensure other fields are defaulted/ignored during the status patch
-            //     ..TheLeague::new(&name, )
-            // });
+            // 3. Patch Status: Equivalent to Go's `r.Status().Update()`
+            let status_patch = api::Patch::Merge(json!({ "status": initial_status }));
+            league_api
+                .patch_status(&name, &patch_params(), &status_patch)
+                .await?
+        } else {
+            league
+        };
+
+        let rosters_complete = sync_rosters(&ctx, &league).await?;
+        seasons::sync_seasons(&ctx.client, &league).await?;
+        let schedule = schedule::league_schedule(&league.spec);
+        report_schedule(&ctx, &league, schedule.as_ref().err()).await?;
+        // The Matches and results are kept as they are until the schedule is fixed.
+        if let Ok(schedule) = schedule {
+            matches::sync_matches(&ctx.client, &league, &schedule).await?;
+            if league.spec.pre_populate_results {
+                fixtures::prepopulate_results(&ctx.client, &league, &schedule).await?;
+            }
+        }
+
+        if league.status.as_ref().is_some_and(|s| s.live) != rosters_complete {
+            let status_patch =
+                api::Patch::Merge(json!({ "status": { "live": rosters_complete } }));
+            league_api
+                .patch_status(&name, &patch_params(), &status_patch)
+                .await?;
         }
 
+        stamps::stamp_reconciled(&league_api, &name, league.status.as_ref()).await?;
         Ok(Action::requeue(Duration::from_secs(3600)))
     }
 
     /// Handle errors that occur during reconciliation (static method)
-    pub fn error_policy(_object: Arc<TheLeague>, err: &kube::Error, _ctx: Arc<Context>) -> Action {
-        info!("error policy: {}", err);
+    pub fn error_policy(object: Arc<TheLeague>, err: &kube::Error, ctx: Arc<Context>) -> Action {
+        errors::report(&ctx, "TheLeague", object.as_ref(), err);
         Action::requeue(Duration::from_secs(5))
     }
 
     pub fn stream(self) -> impl futures::Future<Output = ()> {
         let context = self.context.clone();
-        self.controller
+        let metrics = &self.context.metrics.controller;
+        let results = self
+            .controller
             .shutdown_on_signal()
-            .run(Reconciler::reconcile, Reconciler::error_policy, context)
-            .for_each(|_| futures::future::ready(()))
+            .run(
+                |object, ctx| {
+                    let reconcile = traced(
+                        "TheLeague",
+                        object.as_ref(),
+                        Reconciler::reconcile(object.clone(), ctx),
+                    );
+                    metrics.measure("TheLeague", reconcile)
+                },
+                Reconciler::error_policy,
+                context,
+            );
+        self.context
+            .heartbeat
+            .beating("TheLeague", results)
+            .for_each(move |result| {
+                metrics.observe("TheLeague", &result);
+                futures::future::ready(())
+            })
+    }
+}
+
+/// Resolve the Team references of `league`, check that no player is registered to two of its
+/// teams, that every captain is on its roster, that the rosters are within the size limits and
+/// that every player meets the eligibility rules, reporting the outcome in the TeamsResolved,
+/// RostersValid, CaptainsValid, RosterSizesValid and PlayersEligible conditions. Rules the
+/// validation policy turns off are not reported. Returns whether the league may go live, i.e.
+/// the rosters are within the size limits or the policy doesn't enforce them.
+async fn sync_rosters(ctx: &Context, league: &TheLeague) -> Result<bool, kube::Error> {
+    let (spec, missing) = teams::resolve_teams(&ctx.client, league).await?;
+    let policy = validation_policy::current(&ctx.client, &ctx.caches).await;
+    let mut conditions = Conditions::new(
+        league
+            .status
+            .as_ref()
+            .map(|s| s.conditions.as_slice())
+            .unwrap_or_default(),
+        league.metadata.generation,
+    );
+
+    if league.spec.teams.iter().any(|t| t.team_ref.is_some()) {
+        if missing.is_empty() {
+            conditions.set(
+                TEAMS_RESOLVED_CONDITION,
+                ConditionStatus::True,
+                "TeamsFound",
+                "every team reference resolved",
+            );
+        } else {
+            conditions.set(
+                TEAMS_RESOLVED_CONDITION,
+                ConditionStatus::False,
+                "TeamNotFound",
+                &format!("referenced Teams do not exist: {}", missing.join(", ")),
+            );
+        }
+    }
+    match validation::summarize(&validation::validate_rosters(&spec)) {
+        Some((reason, message)) => conditions.set(
+            ROSTERS_VALID_CONDITION,
+            ConditionStatus::False,
+            &reason,
+            &message,
+        ),
+        None => conditions.set(
+            ROSTERS_VALID_CONDITION,
+            ConditionStatus::True,
+            "RostersValid",
+            "every player is registered to a single team with a unique jersey number",
+        ),
+    };
+    match validation::summarize(&validation::validate_captains(&spec)) {
+        Some((reason, message)) => conditions.set(
+            CAPTAINS_VALID_CONDITION,
+            ConditionStatus::False,
+            &reason,
+            &message,
+        ),
+        None => conditions.set(
+            CAPTAINS_VALID_CONDITION,
+            ConditionStatus::True,
+            "CaptainsOnRoster",
+            "every captain is on the roster of their team",
+        ),
+    };
+
+    let size_violations = match policy.roster_size {
+        Enforcement::Off => Vec::new(),
+        _ => validation::validate_roster_sizes(&spec),
+    };
+    let has_limits =
+        spec.min_players_per_team.is_some() || spec.max_players_per_team.is_some();
+    if policy.roster_size == Enforcement::Off {
+        conditions.remove(ROSTER_SIZES_VALID_CONDITION);
+    } else if has_limits || conditions.has(ROSTER_SIZES_VALID_CONDITION) {
+        match validation::summarize(&size_violations) {
+            Some((reason, message)) => conditions.set(
+                ROSTER_SIZES_VALID_CONDITION,
+                ConditionStatus::False,
+                &reason,
+                &message,
+            ),
+            None => conditions.set(
+                ROSTER_SIZES_VALID_CONDITION,
+                ConditionStatus::True,
+                "RosterSizesValid",
+                "every roster is within the roster size limits",
+            ),
+        };
+    }
+    if policy.eligibility == Enforcement::Off {
+        conditions.remove(PLAYERS_ELIGIBLE_CONDITION);
+    } else if spec.eligibility.is_some() || conditions.has(PLAYERS_ELIGIBLE_CONDITION) {
+        let today = Utc::now().date_naive();
+        match validation::summarize(&validation::validate_eligibility(&spec, today)) {
+            Some((reason, message)) => conditions.set(
+                PLAYERS_ELIGIBLE_CONDITION,
+                ConditionStatus::False,
+                &reason,
+                &message,
+            ),
+            None => conditions.set(
+                PLAYERS_ELIGIBLE_CONDITION,
+                ConditionStatus::True,
+                "PlayersEligible",
+                "every player meets the eligibility rules",
+            ),
+        };
+    }
+
+    let may_go_live = size_violations.is_empty() || policy.roster_size != Enforcement::Enforce;
+    if !conditions.changed() {
+        return Ok(may_go_live);
+    }
+    let league_api: Api<TheLeague> =
+        Api::namespaced(ctx.client.clone(), &league.namespace().unwrap_or_default());
+    let patch = api::Patch::Merge(json!({ "status": { "conditions": conditions } }));
+    league_api
+        .patch_status(&league.name_any(), &patch_params(), &patch)
+        .await?;
+    Ok(may_go_live)
+}
+
+/// Record `error` in the InvalidSchedule condition of `conditions`, or clear the condition
+/// once the schedule builds. Returns whether the condition changed.
+fn track_schedule(conditions: &mut Conditions, error: Option<&schedule::ScheduleError>) -> bool {
+    match error {
+        Some(e) => conditions.set(
+            INVALID_SCHEDULE_CONDITION,
+            ConditionStatus::True,
+            "ScheduleError",
+            &e.to_string(),
+        ),
+        None => conditions.remove(INVALID_SCHEDULE_CONDITION),
+    }
+}
+
+/// Report whether the schedule of `league` can be built in its InvalidSchedule condition,
+/// publishing the error as an event when it's first reported.
+async fn report_schedule(
+    ctx: &Context,
+    league: &TheLeague,
+    error: Option<&schedule::ScheduleError>,
+) -> Result<(), kube::Error> {
+    let mut conditions = Conditions::new(
+        league
+            .status
+            .as_ref()
+            .map(|s| s.conditions.as_slice())
+            .unwrap_or_default(),
+        league.metadata.generation,
+    );
+    if !track_schedule(&mut conditions, error) {
+        return Ok(());
+    }
+    if let Some(e) = error {
+        let event = Event {
+            type_: EventType::Warning,
+            reason: INVALID_SCHEDULE_CONDITION.to_string(),
+            note: Some(e.to_string()),
+            action: "Schedule".to_string(),
+            secondary: None,
+        };
+        ctx.publish(&event, &league.object_ref(&())).await?;
+    }
+    let league_api: Api<TheLeague> =
+        Api::namespaced(ctx.client.clone(), &league.namespace().unwrap_or_default());
+    let patch = api::Patch::Merge(json!({ "status": { "conditions": conditions } }));
+    league_api
+        .patch_status(&league.name_any(), &patch_params(), &patch)
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_invalid_schedule_condition_is_cleared_once_the_schedule_builds() {
+        let mut conditions = Conditions::new(&[], Some(1));
+        let error = schedule::ScheduleError("kickoffs run past the last date".to_string());
+        assert!(track_schedule(&mut conditions, Some(&error)));
+        assert!(conditions.is_true(INVALID_SCHEDULE_CONDITION));
+        assert!(!track_schedule(&mut conditions, Some(&error)));
+
+        assert!(track_schedule(&mut conditions, None));
+        assert!(!conditions.has(INVALID_SCHEDULE_CONDITION));
+        assert!(!track_schedule(&mut conditions, None));
     }
 }
@@ -0,0 +1,231 @@
+use crate::api::conditions::{ConditionStatus, Conditions};
+use crate::api::v1alpha1::game_result_types::{GameResult, GameResultSpec};
+use crate::api::v1alpha1::the_league_types::TheLeague;
+use crate::api::v1alpha1::tournament_types::{
+    DrawMethod, Tournament, TournamentPhase, TournamentStatus,
+};
+use crate::bracket;
+use crate::controller::stamps::{self, Stamped};
+use crate::controller::{
+    Context, errors, gameresult_controller, patch_params, references, traced, watched_api,
+};
+use crate::validation;
+
+use futures::StreamExt;
+use kube::api::{ListParams, Patch};
+use kube::runtime::events::{Event, EventType};
+use kube::runtime::reflector::ObjectRef;
+use kube::runtime::{controller::Controller as KubeController, watcher};
+use kube::{Api, Resource, ResourceExt, runtime::controller::Action};
+use serde_json::json;
+use std::collections::hash_map::RandomState;
+use std::hash::BuildHasher;
+use std::sync::Arc;
+use tokio::time::Duration;
+use tracing::info;
+
+/// Condition type reporting whether every game of a tournament follows its tiebreak rules
+pub const GAMES_VALID_CONDITION: &str = "GamesValid";
+
+/// Controller making the draw of Tournaments and advancing their brackets
+pub struct TournamentReconciler {
+    context: Arc<Context>,
+    controller: KubeController<Tournament>,
+}
+
+impl TournamentReconciler {
+    /// Create a new TournamentReconciler
+    pub fn new(context: Arc<Context>) -> Self {
+        let tournament_api: Api<Tournament> = watched_api(context.client.clone());
+        let result_api: Api<GameResult> = watched_api(context.client.clone());
+        let league_api: Api<TheLeague> = watched_api(context.client.clone());
+
+        let controller = KubeController::new(tournament_api, watcher::Config::default());
+        context.readiness.track("Tournament", controller.store());
+        // A tournament game wakes up the Tournament it references.
+        let controller = controller.watches(result_api, watcher::Config::default(), |result| {
+            let tournament = result.spec.tournament.as_ref()?;
+            let namespace =
+                references::league_namespace(result.namespace(), &result.spec.league_namespace);
+            Some(ObjectRef::new(tournament).within(&namespace))
+        });
+        // Tournaments waiting for their league make the draw once it exists.
+        let store = controller.store();
+        let controller =
+            controller.watches(league_api, watcher::Config::default(), move |league| {
+                store
+                    .state()
+                    .into_iter()
+                    .filter(|t| {
+                        t.namespace() == league.namespace()
+                            && t.spec.league_name == league.name_any()
+                            && t.status.as_ref().is_none_or(|s| s.entrants.is_empty())
+                    })
+                    .map(|t| ObjectRef::from_obj(t.as_ref()))
+                    .collect::<Vec<_>>()
+            });
+
+        Self {
+            context,
+            controller,
+        }
+    }
+
+    /// Reconcile a Tournament resource (static method)
+    pub async fn reconcile(
+        tournament: Arc<Tournament>,
+        ctx: Arc<Context>,
+    ) -> Result<Action, kube::Error> {
+        let name = tournament.name_any();
+        let namespace = tournament.namespace().unwrap_or_default();
+        info!("reconcile request: tournament {}", name);
+        let tournament_api: Api<Tournament> = Api::namespaced(ctx.client.clone(), &namespace);
+        let current = tournament.status.clone().unwrap_or_default().unstamped();
+
+        // The draw is made once and kept, so a random draw stays put.
+        let mut entrants = current.entrants.clone();
+        if entrants.is_empty() {
+            let league_api: Api<TheLeague> = Api::namespaced(ctx.client.clone(), &namespace);
+            if let Some(league) = league_api.get_opt(&tournament.spec.league_name).await? {
+                entrants = make_draw(&tournament, &league);
+            }
+        }
+
+        let mut conditions = Conditions::new(&current.conditions, tournament.metadata.generation);
+        let mut status = TournamentStatus::default();
+        if !entrants.is_empty() {
+            let result_api: Api<GameResult> = Api::namespaced(ctx.client.clone(), &namespace);
+            let results: Vec<GameResult> = result_api
+                .list(&ListParams::default())
+                .await?
+                .items
+                .into_iter()
+                .filter(|r| {
+                    r.spec.tournament.as_deref() == Some(name.as_str())
+                        && r.spec.league_name == tournament.spec.league_name
+                        && gameresult_controller::is_accepted(r)
+                })
+                .collect();
+
+            // Games breaking the tiebreak rules don't count until they're corrected.
+            let mut invalid = Vec::new();
+            let mut specs: Vec<&GameResultSpec> = Vec::new();
+            for result in &results {
+                match validation::validate_knockout_game(&result.spec, tournament.spec.tiebreak) {
+                    Some(violation) => {
+                        invalid.push(format!("{}: {}", result.name_any(), violation.message))
+                    }
+                    None => specs.push(&result.spec),
+                }
+            }
+            if invalid.is_empty() {
+                conditions.set(
+                    GAMES_VALID_CONDITION,
+                    ConditionStatus::True,
+                    "GamesValid",
+                    "every game follows the tiebreak rules",
+                );
+            } else {
+                conditions.set(
+                    GAMES_VALID_CONDITION,
+                    ConditionStatus::False,
+                    "TiebreakNotAllowed",
+                    &invalid.join("; "),
+                );
+            }
+
+            let (series, champion) = bracket::bracket(&entrants, &specs, 1);
+            status.phase = match champion {
+                Some(_) => TournamentPhase::Completed,
+                None => TournamentPhase::InProgress,
+            };
+            status.entrants = entrants;
+            status.series = series;
+            status.champion = champion;
+        }
+        status.conditions = conditions.into_vec();
+
+        if status != current {
+            if let Some(champion) = status
+                .champion
+                .as_ref()
+                .filter(|_| current.champion.is_none())
+            {
+                info!("Tournament '{}/{}' won by {}", namespace, name, champion);
+                let event = Event {
+                    type_: EventType::Normal,
+                    reason: "TournamentCompleted".to_string(),
+                    note: Some(format!("{} won the tournament", champion)),
+                    action: "Completed".to_string(),
+                    secondary: None,
+                };
+                ctx.publish(&event, &tournament.object_ref(&())).await?;
+            }
+            let patch = Patch::Merge(json!({ "status": status }));
+            tournament_api
+                .patch_status(&name, &patch_params(), &patch)
+                .await?;
+        }
+
+        stamps::stamp_reconciled(&tournament_api, &name, tournament.status.as_ref()).await?;
+
+        Ok(Action::await_change())
+    }
+
+    /// Handle errors that occur during reconciliation (static method)
+    pub fn error_policy(object: Arc<Tournament>, err: &kube::Error, ctx: Arc<Context>) -> Action {
+        errors::report(&ctx, "Tournament", object.as_ref(), err);
+        Action::requeue(Duration::from_secs(5))
+    }
+
+    pub fn stream(self) -> impl futures::Future<Output = ()> {
+        let context = self.context.clone();
+        let metrics = &self.context.metrics.controller;
+        let results = self.controller.shutdown_on_signal().run(
+            |object, ctx| {
+                let reconcile = traced(
+                    "Tournament",
+                    object.as_ref(),
+                    TournamentReconciler::reconcile(object.clone(), ctx),
+                );
+                metrics.measure("Tournament", reconcile)
+            },
+            TournamentReconciler::error_policy,
+            context,
+        );
+        self.context
+            .heartbeat
+            .beating("Tournament", results)
+            .for_each(move |result| {
+                metrics.observe("Tournament", &result);
+                futures::future::ready(())
+            })
+    }
+}
+
+/// Draw the teams of `tournament` among the teams of `league`.
+fn make_draw(tournament: &Tournament, league: &TheLeague) -> Vec<String> {
+    let teams: Vec<String> = league
+        .spec
+        .teams
+        .iter()
+        .map(|t| t.name.clone())
+        .filter(|t| tournament.spec.teams.is_empty() || tournament.spec.teams.contains(t))
+        .collect();
+    match tournament.spec.draw {
+        DrawMethod::Seeded => {
+            let table = league
+                .status
+                .as_ref()
+                .map(|s| s.table.as_slice())
+                .unwrap_or_default();
+            bracket::seeded_draw(&teams, table)
+        }
+        DrawMethod::Random => {
+            let hasher = RandomState::new();
+            let mut teams = teams;
+            teams.sort_by_cached_key(|team| hasher.hash_one(team));
+            teams
+        }
+    }
+}
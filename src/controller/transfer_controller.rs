@@ -0,0 +1,379 @@
+use crate::api::v1alpha1::player_types::{Player, PlayerSpec};
+use crate::api::v1alpha1::team_types::{RosterMove, Team};
+use crate::api::v1alpha1::the_league_types::{
+    ClosedPeriod, Player as RosterEntry, TheLeague, TransferPolicy,
+};
+use crate::api::v1alpha1::transfer_types::{Transfer, TransferPhase, TransferStatus};
+use crate::controller::stamps::{self, Stamped};
+use crate::controller::{Context, errors, patch_params, post_params, teams, traced, watched_api};
+
+use futures::StreamExt;
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::Time;
+use k8s_openapi::chrono::{self, DateTime, Utc};
+use kube::api::{ListParams, Patch};
+use kube::runtime::events::{Event, EventType};
+use kube::runtime::{controller::Controller as KubeController, watcher};
+use kube::{Api, Resource, ResourceExt, runtime::controller::Action};
+use serde_json::json;
+use std::sync::Arc;
+use tokio::time::Duration;
+use tracing::info;
+
+/// The closed period of `policy` that `date` falls in, if any
+pub fn closed_period<'a>(policy: &'a TransferPolicy, date: &Time) -> Option<&'a ClosedPeriod> {
+    policy
+        .closed_periods
+        .iter()
+        .find(|p| p.start.0 <= date.0 && date.0 < p.end.0)
+}
+
+/// Whether the roster `entry` stands for the Player `name`: a reference decides, otherwise
+/// the names must match.
+fn is_entry_of(entry: &RosterEntry, name: &str, player: &PlayerSpec) -> bool {
+    match &entry.player_ref {
+        Some(player_ref) => player_ref == name,
+        None => entry.first_name == player.first_name && entry.last_name == player.last_name,
+    }
+}
+
+/// Move the Player `name` from the roster `from` to the roster `to`.
+///
+/// Returns whether `from` and `to` changed, or None when the player is on neither roster.
+/// Moving a player that already moved changes nothing, so an interrupted transfer can be
+/// completed by running it again.
+pub fn move_player(
+    from: &mut Vec<RosterEntry>,
+    to: &mut Vec<RosterEntry>,
+    name: &str,
+    player: &PlayerSpec,
+) -> Option<(bool, bool)> {
+    let previous = from.iter().find(|e| is_entry_of(e, name, player)).cloned();
+    let before = from.len();
+    from.retain(|e| !is_entry_of(e, name, player));
+    let left = from.len() != before;
+    let on_new_team = to.iter().any(|e| is_entry_of(e, name, player));
+    if !left && !on_new_team {
+        return None;
+    }
+    if !on_new_team {
+        to.push(RosterEntry {
+            first_name: player.first_name.clone(),
+            last_name: player.last_name.clone(),
+            player_ref: Some(name.to_string()),
+            position: previous.as_ref().and_then(|e| e.position),
+            // The jersey number may be taken on the new team.
+            jersey_number: None,
+            date_of_birth: previous.and_then(|e| e.date_of_birth),
+            registration_date: None,
+        });
+    }
+    Some((left, !on_new_team))
+}
+
+/// Controller moving players between Teams according to Transfers
+pub struct TransferReconciler {
+    context: Arc<Context>,
+    controller: KubeController<Transfer>,
+}
+
+impl TransferReconciler {
+    /// Create a new TransferReconciler
+    pub fn new(context: Arc<Context>) -> Self {
+        let transfer_api: Api<Transfer> = watched_api(context.client.clone());
+        let controller = KubeController::new(transfer_api, watcher::Config::default());
+        context.readiness.track("Transfer", controller.store());
+        Self {
+            context,
+            controller,
+        }
+    }
+
+    /// Reconcile a Transfer resource (static method)
+    pub async fn reconcile(
+        transfer: Arc<Transfer>,
+        ctx: Arc<Context>,
+    ) -> Result<Action, kube::Error> {
+        let name = transfer.name_any();
+        let namespace = transfer.namespace().unwrap_or_default();
+        info!("reconcile request: transfer {}", name);
+        let current = transfer.status.clone().unwrap_or_default().unstamped();
+        if current.phase != TransferPhase::Pending {
+            let transfer_api: Api<Transfer> = Api::namespaced(ctx.client.clone(), &namespace);
+            stamps::stamp_reconciled(&transfer_api, &name, transfer.status.as_ref()).await?;
+            return Ok(Action::await_change());
+        }
+        let spec = &transfer.spec;
+
+        let player_api: Api<Player> = Api::namespaced(ctx.client.clone(), &namespace);
+        let team_api: Api<Team> = Api::namespaced(ctx.client.clone(), &namespace);
+        let Some(player) = player_api.get_opt(&spec.player).await? else {
+            let message = format!("Player '{}' does not exist", spec.player);
+            return reject(&transfer, &ctx, "PlayerNotFound", message).await;
+        };
+        if spec.from_team == spec.to_team {
+            let message = format!("the player already plays for '{}'", spec.to_team);
+            return reject(&transfer, &ctx, "SameTeam", message).await;
+        }
+        let (Some(mut from), Some(mut to)) = (
+            team_api.get_opt(&spec.from_team).await?,
+            team_api.get_opt(&spec.to_team).await?,
+        ) else {
+            let message = format!(
+                "Teams '{}' and '{}' must both exist",
+                spec.from_team, spec.to_team
+            );
+            return reject(&transfer, &ctx, "TeamNotFound", message).await;
+        };
+
+        // Every league of either team must have its transfer window open.
+        let league_api: Api<TheLeague> = Api::namespaced(ctx.client.clone(), &namespace);
+        for league in league_api.list(&ListParams::default()).await?.items {
+            if !teams::references_team(&league.spec, &spec.from_team)
+                && !teams::references_team(&league.spec, &spec.to_team)
+            {
+                continue;
+            }
+            let closed = league
+                .spec
+                .transfer_policy
+                .as_ref()
+                .and_then(|policy| closed_period(policy, &spec.effective_date));
+            if let Some(period) = closed {
+                let message = format!(
+                    "the transfer window of league '{}' is closed from {} until {}",
+                    league.name_any(),
+                    period.start.0.to_rfc3339(),
+                    period.end.0.to_rfc3339()
+                );
+                return reject(&transfer, &ctx, "TransferWindowClosed", message).await;
+            }
+        }
+
+        let now = chrono::Utc::now();
+        if spec.effective_date.0 > now {
+            let status = TransferStatus {
+                phase: TransferPhase::Pending,
+                message: Some(format!(
+                    "takes effect at {}",
+                    spec.effective_date.0.to_rfc3339()
+                )),
+                completed_at: None,
+                ..Default::default()
+            };
+            patch_status(&transfer, &ctx, &current, &status).await?;
+            return Ok(Action::requeue(until(spec.effective_date.0, now)));
+        }
+
+        let Some((left, joined)) = move_player(
+            &mut from.spec.players,
+            &mut to.spec.players,
+            &spec.player,
+            &player.spec,
+        ) else {
+            let message = format!(
+                "Player '{}' is not on the roster of '{}'",
+                spec.player, spec.from_team
+            );
+            return reject(&transfer, &ctx, "PlayerNotOnRoster", message).await;
+        };
+        // Replacing with the read resourceVersion makes a concurrent roster edit fail the
+        // update with a conflict instead of being overwritten; the transfer is then retried.
+        // The new team is updated first so the player is never on neither roster.
+        if joined {
+            team_api.replace(&spec.to_team, &post_params(), &to).await?;
+        }
+        if left {
+            team_api
+                .replace(&spec.from_team, &post_params(), &from)
+                .await?;
+        }
+
+        let roster_move = RosterMove {
+            transfer: name.clone(),
+            player: spec.player.clone(),
+            from_team: spec.from_team.clone(),
+            to_team: spec.to_team.clone(),
+            effective_date: spec.effective_date.clone(),
+        };
+        for team in [&from, &to] {
+            let mut status = team.status.clone().unwrap_or_default();
+            if status.history.iter().any(|m| m.transfer == name) {
+                continue;
+            }
+            status.history.push(roster_move.clone());
+            status.stamp(now);
+            let patch = Patch::Merge(json!({ "status": status }));
+            team_api
+                .patch_status(&team.name_any(), &patch_params(), &patch)
+                .await?;
+        }
+
+        let message = format!(
+            "{} moved from '{}' to '{}'",
+            spec.player, spec.from_team, spec.to_team
+        );
+        info!("Transfer '{}/{}': {}", namespace, name, message);
+        let event = Event {
+            type_: EventType::Normal,
+            reason: "TransferCompleted".to_string(),
+            note: Some(message.clone()),
+            action: "Transfer".to_string(),
+            secondary: None,
+        };
+        ctx.publish(&event, &transfer.object_ref(&())).await?;
+        let status = TransferStatus {
+            phase: TransferPhase::Completed,
+            message: Some(message),
+            completed_at: Some(Time(now)),
+            ..Default::default()
+        };
+        patch_status(&transfer, &ctx, &current, &status).await?;
+        Ok(Action::await_change())
+    }
+
+    /// Handle errors that occur during reconciliation (static method)
+    pub fn error_policy(object: Arc<Transfer>, err: &kube::Error, ctx: Arc<Context>) -> Action {
+        errors::report(&ctx, "Transfer", object.as_ref(), err);
+        Action::requeue(Duration::from_secs(5))
+    }
+
+    pub fn stream(self) -> impl futures::Future<Output = ()> {
+        let context = self.context.clone();
+        let metrics = &self.context.metrics.controller;
+        let results = self.controller.shutdown_on_signal().run(
+            |object, ctx| {
+                let reconcile = traced(
+                    "Transfer",
+                    object.as_ref(),
+                    TransferReconciler::reconcile(object.clone(), ctx),
+                );
+                metrics.measure("Transfer", reconcile)
+            },
+            TransferReconciler::error_policy,
+            context,
+        );
+        self.context
+            .heartbeat
+            .beating("Transfer", results)
+            .for_each(move |result| {
+                metrics.observe("Transfer", &result);
+                futures::future::ready(())
+            })
+    }
+}
+
+/// Time left until `at`
+fn until(at: DateTime<Utc>, now: DateTime<Utc>) -> Duration {
+    (at - now).to_std().unwrap_or_default()
+}
+
+/// Write `status` to `transfer` unless it's already `current`, and record the reconcile.
+async fn patch_status(
+    transfer: &Transfer,
+    ctx: &Context,
+    current: &TransferStatus,
+    status: &TransferStatus,
+) -> Result<(), kube::Error> {
+    let transfer_api: Api<Transfer> = Api::namespaced(
+        ctx.client.clone(),
+        &transfer.namespace().unwrap_or_default(),
+    );
+    if status != current {
+        let patch = Patch::Merge(json!({ "status": status }));
+        transfer_api
+            .patch_status(&transfer.name_any(), &patch_params(), &patch)
+            .await?;
+    }
+    stamps::stamp_reconciled(
+        &transfer_api,
+        &transfer.name_any(),
+        transfer.status.as_ref(),
+    )
+    .await
+}
+
+/// Mark `transfer` as rejected for `reason` and leave the rosters untouched.
+async fn reject(
+    transfer: &Transfer,
+    ctx: &Context,
+    reason: &str,
+    message: String,
+) -> Result<Action, kube::Error> {
+    info!("Transfer '{}' rejected: {}", transfer.name_any(), message);
+    let event = Event {
+        type_: EventType::Warning,
+        reason: reason.to_string(),
+        note: Some(message.clone()),
+        action: "Transfer".to_string(),
+        secondary: None,
+    };
+    ctx.publish(&event, &transfer.object_ref(&())).await?;
+    let current = transfer.status.clone().unwrap_or_default().unstamped();
+    let status = TransferStatus {
+        phase: TransferPhase::Rejected,
+        message: Some(message),
+        completed_at: None,
+        ..Default::default()
+    };
+    patch_status(transfer, ctx, &current, &status).await?;
+    Ok(Action::await_change())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::v1alpha1::the_league_types::{Date, Position};
+
+    fn entry(first_name: &str, player_ref: Option<&str>) -> RosterEntry {
+        RosterEntry {
+            first_name: first_name.to_string(),
+            last_name: "Morgan".to_string(),
+            player_ref: player_ref.map(str::to_string),
+            position: Some(Position::Forward),
+            jersey_number: Some(9),
+            date_of_birth: Some(Date("2001-05-17".to_string())),
+            registration_date: None,
+        }
+    }
+
+    #[test]
+    fn test_move_player_is_idempotent() {
+        let player = PlayerSpec {
+            first_name: "Alex".to_string(),
+            last_name: "Morgan".to_string(),
+        };
+        let mut from = vec![entry("Alex", None), entry("Sam", None)];
+        let mut to = vec![entry("Jo", None)];
+
+        assert_eq!(
+            move_player(&mut from, &mut to, "alex-morgan", &player),
+            Some((true, true))
+        );
+        assert_eq!(from.len(), 1);
+        assert_eq!(to[1].player_ref.as_deref(), Some("alex-morgan"));
+        assert_eq!(to[1].position, Some(Position::Forward));
+        assert_eq!(to[1].jersey_number, None);
+        assert_eq!(to[1].date_of_birth, Some(Date("2001-05-17".to_string())));
+        assert_eq!(
+            move_player(&mut from, &mut to, "alex-morgan", &player),
+            Some((false, false))
+        );
+        assert_eq!(
+            move_player(&mut from, &mut to, "alex-morgan-2", &player),
+            None
+        );
+    }
+
+    #[test]
+    fn test_closed_period_contains_the_effective_date() {
+        let now = chrono::Utc::now();
+        let policy = TransferPolicy {
+            closed_periods: vec![ClosedPeriod {
+                start: Time(now - chrono::Duration::days(1)),
+                end: Time(now + chrono::Duration::days(1)),
+            }],
+        };
+        assert!(closed_period(&policy, &Time(now)).is_some());
+        assert!(closed_period(&policy, &Time(now + chrono::Duration::days(2))).is_none());
+    }
+}
@@ -0,0 +1,119 @@
+use crate::controller::caches::Caches;
+use crate::validation::ValidationPolicy;
+use crate::webhook;
+
+use futures::StreamExt;
+use k8s_openapi::api::core::v1::ConfigMap;
+use kube::runtime::reflector::{self, ObjectRef, Store};
+use kube::runtime::{WatchStreamExt, watcher};
+use kube::{Api, Client};
+use tracing::warn;
+
+/// Name of the ConfigMap in the controller namespace holding the validation policy
+pub const VALIDATION_POLICY_CONFIGMAP: &str = "theleague-validation-policy";
+
+/// Namespace the controller runs in, where its validation policy lives
+fn controller_namespace() -> String {
+    std::env::var("POD_NAMESPACE").unwrap_or_else(|_| webhook::DEFAULT_NAMESPACE.to_string())
+}
+
+/// The validation policy of the cluster. Without the ConfigMap every rule is enforced; an
+/// unreadable or invalid policy is logged and falls back to enforcing every rule too.
+pub async fn load(client: &Client) -> ValidationPolicy {
+    let namespace = controller_namespace();
+    let api: Api<ConfigMap> = Api::namespaced(client.clone(), &namespace);
+    let configmap = match api.get_opt(VALIDATION_POLICY_CONFIGMAP).await {
+        Ok(configmap) => configmap,
+        Err(e) => {
+            warn!(
+                "Failed to get validation policy '{}/{}', enforcing every rule: {}",
+                namespace, VALIDATION_POLICY_CONFIGMAP, e
+            );
+            None
+        }
+    };
+    parse(&namespace, configmap.as_ref())
+}
+
+/// The validation policy as read by the reconcilers: from the reflected ConfigMap once it's
+/// synced, from the API server until then.
+pub async fn current(client: &Client, caches: &Caches) -> ValidationPolicy {
+    match caches.policy() {
+        Some(store) => from_store(store),
+        None => load(client).await,
+    }
+}
+
+/// The validation policy in the reflected `store`
+fn from_store(store: &Store<ConfigMap>) -> ValidationPolicy {
+    let namespace = controller_namespace();
+    let configmap = store.get(&ObjectRef::new(VALIDATION_POLICY_CONFIGMAP).within(&namespace));
+    parse(&namespace, configmap.as_deref())
+}
+
+/// Reflector of the validation policy ConfigMap, and the stream keeping its store up to date
+pub fn reflect(client: &Client) -> (Store<ConfigMap>, impl futures::Future<Output = ()>) {
+    let namespace = controller_namespace();
+    let api: Api<ConfigMap> = Api::namespaced(client.clone(), &namespace);
+    let config = watcher::Config::default()
+        .fields(&format!("metadata.name={}", VALIDATION_POLICY_CONFIGMAP));
+    let (store, writer) = reflector::store();
+    let stream = reflector::reflector(writer, watcher(api, config))
+        .default_backoff()
+        .for_each(move |event| {
+            if let Err(e) = event {
+                warn!(
+                    "Failed to watch validation policy '{}/{}': {}",
+                    namespace, VALIDATION_POLICY_CONFIGMAP, e
+                );
+            }
+            futures::future::ready(())
+        });
+    (store, stream)
+}
+
+fn parse(namespace: &str, configmap: Option<&ConfigMap>) -> ValidationPolicy {
+    let Some(data) = configmap.and_then(|c| c.data.as_ref()) else {
+        return ValidationPolicy::default();
+    };
+    ValidationPolicy::from_data(data).unwrap_or_else(|e| {
+        warn!(
+            "Invalid validation policy '{}/{}', enforcing every rule: {}",
+            namespace, VALIDATION_POLICY_CONFIGMAP, e
+        );
+        ValidationPolicy::default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::v1alpha1::the_league_types::Enforcement;
+    use kube::api::ObjectMeta;
+
+    #[test]
+    fn test_policy_is_read_from_the_reflected_configmap() {
+        let (store, mut writer) = reflector::store();
+        writer.apply_watcher_event(&watcher::Event::Init);
+        writer.apply_watcher_event(&watcher::Event::InitDone);
+        assert_eq!(from_store(&store), ValidationPolicy::default());
+
+        let configmap = ConfigMap {
+            metadata: ObjectMeta {
+                name: Some(VALIDATION_POLICY_CONFIGMAP.to_string()),
+                namespace: Some(controller_namespace()),
+                ..Default::default()
+            },
+            data: Some(
+                [(
+                    ValidationPolicy::DUPLICATE_RESULT.to_string(),
+                    "Off".to_string(),
+                )]
+                .into(),
+            ),
+            ..Default::default()
+        };
+        writer.apply_watcher_event(&watcher::Event::Apply(configmap));
+        assert_eq!(from_store(&store).duplicate_result, Enforcement::Off);
+    }
+}
@@ -0,0 +1,195 @@
+use crate::api::conditions::{ConditionStatus, Conditions};
+use crate::api::v1alpha1::the_league_types::TheLeague;
+use crate::api::v1alpha1::venue_types::Venue;
+use crate::controller::patch_params;
+use crate::schedule;
+
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::Time;
+use kube::api::Patch;
+use kube::{Api, Client, ResourceExt};
+use serde_json::json;
+
+/// Condition type set to True on leagues with Matches that can't be booked at their venue
+pub const SCHEDULING_CONFLICT_CONDITION: &str = "SchedulingConflict";
+
+pub use crate::schedule::MATCH_SLOT_MINUTES;
+
+/// The Venue that `venue` stands for: the Venue of that name or of its slug
+pub fn find_venue<'a>(venues: &'a [Venue], venue: &str) -> Option<&'a Venue> {
+    venues.iter().find(|v| {
+        let name = v.name_any();
+        name == venue || name == schedule::slug(venue)
+    })
+}
+
+/// A match to book at its venue
+#[derive(Debug, Clone, Copy)]
+pub struct BookingRequest<'a> {
+    /// Fixture is the name of the Match.
+    pub fixture: &'a str,
+    /// Venue is the venue the match is played at, if known.
+    pub venue: Option<&'a str>,
+    /// Kickoff is the time of the match, if known.
+    pub kickoff: Option<&'a Time>,
+}
+
+/// Book the venues of `requests` in order, on top of the already `booked` matches.
+///
+/// A request is booked at its Venue (`Ok(Some(venue))`) unless the venue isn't available at
+/// the kickoff or another match kicking off less than a slot apart already holds it, in which
+/// case it's refused with the reason (`Err`). Matches at no known Venue get `Ok(None)`.
+/// Matches without a kickoff are booked without checking the time.
+pub fn book_venues(
+    requests: &[BookingRequest],
+    booked: &[BookingRequest],
+    venues: &[Venue],
+) -> Vec<Result<Option<String>, String>> {
+    let mut taken: Vec<(String, Time, String)> = booked
+        .iter()
+        .filter_map(|b| {
+            let venue = find_venue(venues, b.venue?)?;
+            Some((venue.name_any(), b.kickoff?.clone(), b.fixture.to_string()))
+        })
+        .collect();
+
+    let mut bookings = Vec::new();
+    for request in requests {
+        let Some(venue) = request.venue.and_then(|v| find_venue(venues, v)) else {
+            bookings.push(Ok(None));
+            continue;
+        };
+        let name = venue.name_any();
+        let Some(kickoff) = request.kickoff else {
+            bookings.push(Ok(Some(name)));
+            continue;
+        };
+        let available = venue.spec.availability.is_empty()
+            || venue.spec.availability.iter().any(|w| w.contains(kickoff));
+        let clash = taken.iter().find(|(venue, at, _)| {
+            *venue == name && (at.0 - kickoff.0).num_minutes().abs() < MATCH_SLOT_MINUTES
+        });
+        bookings.push(if !available {
+            Err(format!(
+                "venue '{}' is not available at {}",
+                name,
+                kickoff.0.to_rfc3339()
+            ))
+        } else if let Some((_, _, other)) = clash {
+            Err(format!(
+                "venue '{}' is already booked by {} at {}",
+                name,
+                other,
+                kickoff.0.to_rfc3339()
+            ))
+        } else {
+            taken.push((name.clone(), kickoff.clone(), request.fixture.to_string()));
+            Ok(Some(name))
+        });
+    }
+    bookings
+}
+
+/// Report the venue `conflicts` of the Matches of `league` in its SchedulingConflict
+/// condition. Leagues are left alone until their namespace has Venues.
+pub async fn report_conflicts(
+    client: &Client,
+    league: &TheLeague,
+    has_venues: bool,
+    conflicts: &[String],
+) -> Result<(), kube::Error> {
+    let mut conditions = Conditions::new(
+        league
+            .status
+            .as_ref()
+            .map(|s| s.conditions.as_slice())
+            .unwrap_or_default(),
+        league.metadata.generation,
+    );
+    if !has_venues && !conditions.has(SCHEDULING_CONFLICT_CONDITION) {
+        return Ok(());
+    }
+    let changed = if conflicts.is_empty() {
+        conditions.set(
+            SCHEDULING_CONFLICT_CONDITION,
+            ConditionStatus::False,
+            "NoConflicts",
+            "every match is booked at its venue",
+        )
+    } else {
+        conditions.set(
+            SCHEDULING_CONFLICT_CONDITION,
+            ConditionStatus::True,
+            "VenueConflict",
+            &conflicts.join("; "),
+        )
+    };
+    if !changed {
+        return Ok(());
+    }
+    let league_api: Api<TheLeague> =
+        Api::namespaced(client.clone(), &league.namespace().unwrap_or_default());
+    let patch = Patch::Merge(json!({ "status": { "conditions": conditions } }));
+    league_api
+        .patch_status(&league.name_any(), &patch_params(), &patch)
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::v1alpha1::referee_types::AvailabilityWindow;
+    use crate::api::v1alpha1::venue_types::VenueSpec;
+    use k8s_openapi::chrono::{Duration, Utc};
+
+    #[test]
+    fn test_double_bookings_are_refused() {
+        let now = Utc::now();
+        let venues = [
+            Venue::new(
+                "riverside-park",
+                VenueSpec {
+                    address: None,
+                    availability: Vec::new(),
+                },
+            ),
+            Venue::new(
+                "north-stadium",
+                VenueSpec {
+                    address: None,
+                    availability: vec![AvailabilityWindow {
+                        start: Time(now + Duration::days(1)),
+                        end: Time(now + Duration::days(2)),
+                    }],
+                },
+            ),
+        ];
+        let (kickoff, soon, later) = (
+            Time(now),
+            Time(now + Duration::minutes(90)),
+            Time(now + Duration::minutes(180)),
+        );
+        let request = |fixture, venue, kickoff| BookingRequest {
+            fixture,
+            venue: Some(venue),
+            kickoff: Some(kickoff),
+        };
+        let booked = [request("cup-final", "riverside-park", &kickoff)];
+        let requests = [
+            request("round-1-a", "Riverside Park", &soon),
+            request("round-1-b", "Riverside Park", &later),
+            request("round-1-c", "north-stadium", &kickoff),
+            request("round-1-d", "Town Green", &kickoff),
+        ];
+
+        let bookings = book_venues(&requests, &booked, &venues);
+        assert!(bookings[0].as_ref().is_err_and(|e| e.contains("cup-final")));
+        assert_eq!(bookings[1], Ok(Some("riverside-park".to_string())));
+        assert!(
+            bookings[2]
+                .as_ref()
+                .is_err_and(|e| e.contains("not available"))
+        );
+        assert_eq!(bookings[3], Ok(None));
+    }
+}
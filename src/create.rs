@@ -0,0 +1,312 @@
+//! Creation of leagues for `leaguectl create league`, interactively or from a file.
+//!
+//! The interactive mode prompts for the size of the league, its matchups and points policy,
+//! and its teams with their rosters, offering the defaults of the CRD. Answers the CRD schema
+//! would reject, such as malformed names, are asked again right away; the rules of the
+//! admission webhook are checked on the whole league with [`validation::validate_league`]
+//! before it is applied.
+//!
+//! [`validation::validate_league`]: crate::validation::validate_league
+
+use crate::api::v1alpha1::the_league_types::{PointsPolicy, TheLeagueSpec};
+use crate::webhook::normalize_team_name;
+
+use serde_json::{Value, json};
+use std::io::{self, BufRead, Write};
+use std::str::FromStr;
+
+/// Prompter asks questions on `output` and reads the answers from `input`, a line each.
+pub struct Prompter<R, W> {
+    input: R,
+    output: W,
+}
+
+impl<R: BufRead, W: Write> Prompter<R, W> {
+    pub fn new(input: R, output: W) -> Self {
+        Prompter { input, output }
+    }
+
+    /// Ask `question` until `parse` accepts the trimmed answer, which is `default` when empty
+    pub fn ask<T>(
+        &mut self,
+        question: &str,
+        default: Option<&str>,
+        parse: impl Fn(&str) -> Result<T, String>,
+    ) -> io::Result<T> {
+        loop {
+            match default {
+                Some(default) => write!(self.output, "{} [{}]: ", question, default)?,
+                None => write!(self.output, "{}: ", question)?,
+            }
+            self.output.flush()?;
+            let mut answer = String::new();
+            if self.input.read_line(&mut answer)? == 0 {
+                return Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "input ended before the league was complete",
+                ));
+            }
+            let answer = match answer.trim() {
+                "" => default.unwrap_or(""),
+                answer => answer,
+            };
+            match parse(answer) {
+                Ok(value) => return Ok(value),
+                Err(e) => writeln!(self.output, "  {}", e)?,
+            }
+        }
+    }
+
+    /// Ask the yes or no `question`, answered with `default` when empty
+    pub fn confirm(&mut self, question: &str, default: bool) -> io::Result<bool> {
+        let hint = if default { "Y/n" } else { "y/N" };
+        self.ask(
+            &format!("{} [{}]", question, hint),
+            None,
+            |answer| match answer.to_lowercase().as_str() {
+                "" => Ok(default),
+                "y" | "yes" => Ok(true),
+                "n" | "no" => Ok(false),
+                _ => Err("answer y or n".to_string()),
+            },
+        )
+    }
+}
+
+/// Parse a number within `min..=max`
+fn number<T>(answer: &str, min: T, max: T) -> Result<T, String>
+where
+    T: FromStr + PartialOrd + std::fmt::Display,
+{
+    match answer.parse::<T>() {
+        Ok(n) if n >= min && n <= max => Ok(n),
+        _ => Err(format!("expected a number from {} to {}", min, max)),
+    }
+}
+
+/// Parse an optional number of at least `min`, empty for none
+fn optional_number(answer: &str, min: u32) -> Result<Option<u32>, String> {
+    if answer.is_empty() {
+        return Ok(None);
+    }
+    number(answer, min, u32::MAX).map(Some)
+}
+
+/// Whether `name` matches the pattern of the CRD for player names: letters, joined by single
+/// apostrophes or hyphens
+fn valid_player_name(name: &str) -> bool {
+    name.split(['\'', '-'])
+        .all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_alphabetic()))
+}
+
+/// Parse the team name `answer`, with its whitespace normalized as the mutating webhook does,
+/// empty for no further team
+fn team_name(answer: &str, taken: &[String]) -> Result<Option<String>, String> {
+    let name = normalize_team_name(answer);
+    if name.is_empty() {
+        return Ok(None);
+    }
+    if !name.chars().all(|c| c.is_ascii_alphanumeric() || c == ' ') {
+        return Err("team names may only hold letters, digits and spaces".to_string());
+    }
+    if taken.contains(&name) {
+        return Err(format!("team '{}' is already registered", name));
+    }
+    Ok(Some(name))
+}
+
+/// Parse the player `answer` given as `First Last`, empty for no further player
+fn player_name(answer: &str) -> Result<Option<(String, String)>, String> {
+    let parts: Vec<&str> = answer.split_whitespace().collect();
+    match parts[..] {
+        [] => Ok(None),
+        [first, last] if valid_player_name(first) && valid_player_name(last) => {
+            Ok(Some((first.to_string(), last.to_string())))
+        }
+        _ => Err("expected the first and the last name of the player, e.g. Ada Lovelace".into()),
+    }
+}
+
+/// Prompt for a league and build its spec from the answers
+pub fn prompt_league<R: BufRead, W: Write>(
+    prompter: &mut Prompter<R, W>,
+) -> io::Result<TheLeagueSpec> {
+    let max_teams: u8 = prompter.ask("Maximum number of teams", Some("8"), |a| number(a, 2, 8))?;
+    let matchups: u32 = prompter.ask("Times each pair of teams meets", Some("1"), |a| {
+        number(a, 1, u32::MAX)
+    })?;
+
+    let defaults = PointsPolicy::default();
+    let mut points = Vec::new();
+    for (question, default) in [
+        ("Points for a win", defaults.win),
+        ("Points for a draw", defaults.draw),
+        ("Points for a loss", defaults.loss),
+        ("Points for an overtime win", defaults.overtime_win),
+        ("Points for an overtime loss", defaults.overtime_loss),
+    ] {
+        let default = default.to_string();
+        points.push(prompter.ask(question, Some(&default), |a| number(a, 0, u32::MAX))?);
+    }
+
+    let min_players = prompter.ask("Minimum players per team (empty for none)", None, |a| {
+        optional_number(a, 1)
+    })?;
+    let max_players = prompter.ask("Maximum players per team (empty for none)", None, |a| {
+        optional_number(a, min_players.unwrap_or(1))
+    })?;
+
+    let mut names: Vec<String> = Vec::new();
+    let mut teams = Vec::new();
+    while names.len() < usize::from(max_teams) {
+        let question = format!("Name of team {} (empty to finish)", names.len() + 1);
+        let Some(name) = prompter.ask(&question, None, |a| team_name(a, &names))? else {
+            if names.len() >= 2 {
+                break;
+            }
+            writeln!(prompter.output, "  a league needs at least 2 teams")?;
+            continue;
+        };
+        let location = prompter.ask(&format!("Location of {} (optional)", name), None, |a| {
+            Ok((!a.is_empty()).then(|| a.to_string()))
+        })?;
+
+        let mut players: Vec<(String, String)> = Vec::new();
+        while max_players.is_none_or(|max| players.len() < max as usize) {
+            let question = format!("Player of {} as First Last (empty to finish)", name);
+            match prompter.ask(&question, None, player_name)? {
+                Some(player) => players.push(player),
+                None => break,
+            }
+        }
+        let captain = if players.is_empty() {
+            None
+        } else {
+            let roster: Vec<String> = players
+                .iter()
+                .map(|(f, l)| format!("{} {}", f, l))
+                .collect();
+            prompter.ask(
+                &format!("Captain of {} (optional)", name),
+                None,
+                |a| match a.split_whitespace().collect::<Vec<_>>().join(" ") {
+                    captain if captain.is_empty() => Ok(None),
+                    captain if roster.contains(&captain) => Ok(Some(captain)),
+                    captain => Err(format!("{} is not on the roster of {}", captain, name)),
+                },
+            )?
+        };
+
+        let players: Vec<Value> = players
+            .iter()
+            .map(|(first, last)| json!({"firstName": first, "lastName": last}))
+            .collect();
+        teams.push(json!({
+            "name": name,
+            "location": location,
+            "captain": captain,
+            "players": players,
+        }));
+        names.push(name);
+    }
+
+    let spec = json!({
+        "maxTeams": max_teams,
+        "matchups": matchups,
+        "teams": teams,
+        "minPlayersPerTeam": min_players,
+        "maxPlayersPerTeam": max_players,
+        "pointsPolicy": {
+            "win": points[0],
+            "draw": points[1],
+            "loss": points[2],
+            "overtimeWin": points[3],
+            "overtimeLoss": points[4],
+        },
+    });
+    serde_json::from_value(spec).map_err(io::Error::other)
+}
+
+/// Parse the league of the YAML or JSON `content`, a TheLeague manifest or only its spec
+pub fn parse_league(content: &str) -> Result<TheLeagueSpec, serde_yaml::Error> {
+    let mut document: serde_yaml::Value = serde_yaml::from_str(content)?;
+    if let Some(spec) = document.get_mut("spec") {
+        document = std::mem::take(spec);
+    }
+    serde_yaml::from_value(document)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn answered(answers: &str) -> (io::Result<TheLeagueSpec>, String) {
+        let mut output = Vec::new();
+        let league = prompt_league(&mut Prompter::new(answers.as_bytes(), &mut output));
+        (league, String::from_utf8(output).unwrap())
+    }
+
+    #[test]
+    fn test_prompt_league_builds_the_spec_from_the_answers() {
+        let answers = concat!(
+            // Size, matchups, points policy and roster sizes
+            "4\n2\n\n\n\n2\n1\n\n2\n",
+            // The first team, with a malformed player asked again and a captain
+            "  Red   Tigers \nTbilisi\nAda Lovelace\nAlan\nAlan Turing\nAda  Lovelace\n",
+            // Only one team so far, a duplicate name, then a team without players
+            "\nRed Tigers\nSharks\n\n\n\n",
+        );
+        let (league, output) = answered(answers);
+        let league = league.unwrap();
+
+        assert_eq!(league.max_teams, 4);
+        assert_eq!(league.matchups, 2);
+        assert_eq!(league.points_policy.win, 3);
+        assert_eq!(league.points_policy.overtime_win, 2);
+        assert_eq!(league.points_policy.overtime_loss, 1);
+        assert_eq!(league.min_players_per_team, None);
+        assert_eq!(league.max_players_per_team, Some(2));
+        assert!(league.strict_schedule);
+
+        let names: Vec<&str> = league.teams.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, ["Red Tigers", "Sharks"]);
+        let tigers = &league.teams[0];
+        assert_eq!(tigers.location.as_deref(), Some("Tbilisi"));
+        assert_eq!(tigers.captain.as_deref(), Some("Ada Lovelace"));
+        let roster: Vec<&str> = tigers
+            .players
+            .iter()
+            .map(|p| p.last_name.as_str())
+            .collect();
+        assert_eq!(roster, ["Lovelace", "Turing"]);
+        assert!(league.teams[1].players.is_empty());
+
+        assert!(output.contains("expected the first and the last name"));
+        assert!(output.contains("a league needs at least 2 teams"));
+        assert!(output.contains("team 'Red Tigers' is already registered"));
+    }
+
+    #[test]
+    fn test_prompt_league_fails_when_the_input_ends() {
+        let (league, _) = answered("8\n1\n");
+        assert_eq!(league.unwrap_err().kind(), io::ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn test_parse_league_accepts_a_manifest_or_a_spec() {
+        let spec = "maxTeams: 2\nteams:\n- name: Tigers\n- name: Sharks\n";
+        let manifest = format!(
+            "apiVersion: bexxmodd.com/v1alpha1\nkind: TheLeague\nmetadata:\n  name: premier\n\
+             spec:\n{}",
+            spec.lines()
+                .map(|l| format!("  {}\n", l))
+                .collect::<String>()
+        );
+        for content in [spec.to_string(), manifest] {
+            let league = parse_league(&content).unwrap();
+            assert_eq!(league.teams.len(), 2);
+            assert_eq!(league.matchups, 1);
+        }
+        assert!(parse_league("teams: []\n").is_err());
+    }
+}
@@ -0,0 +1,215 @@
+//! Disciplinary records: cards per player and the suspensions they lead to.
+//!
+//! Everything in here is pure so it can be shared by the controllers and the webhooks.
+
+use std::collections::BTreeMap;
+
+use crate::api::v1alpha1::game_result_types::{CardColor, GameResultSpec};
+use crate::api::v1alpha1::suspension_types::{SuspensionReason, SuspensionSpec};
+use crate::api::v1alpha1::the_league_types::SuspensionPolicy;
+use crate::schedule;
+
+/// PlayerKey identifies a player of a team by name.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PlayerKey {
+    pub team: String,
+    pub first_name: String,
+    pub last_name: String,
+}
+
+/// CardTally is the number of cards a player was shown.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CardTally {
+    pub yellow: u32,
+    pub red: u32,
+}
+
+/// Count the cards of every player over `results`.
+pub fn card_tally(results: &[&GameResultSpec]) -> BTreeMap<PlayerKey, CardTally> {
+    let mut tally: BTreeMap<PlayerKey, CardTally> = BTreeMap::new();
+    for card in results.iter().flat_map(|r| &r.cards) {
+        let entry = tally
+            .entry(PlayerKey {
+                team: card.team.clone(),
+                first_name: card.first_name.clone(),
+                last_name: card.last_name.clone(),
+            })
+            .or_default();
+        match card.color {
+            CardColor::Yellow => entry.yellow += 1,
+            CardColor::Red => entry.red += 1,
+        }
+    }
+    tally
+}
+
+/// DueSuspension is a suspension a player has earned under the suspension policy.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DueSuspension {
+    pub player: PlayerKey,
+    pub reason: SuspensionReason,
+
+    /// Number counts the suspensions of the player for the same reason, starting at 1.
+    pub number: u32,
+}
+
+impl DueSuspension {
+    /// Name of the Suspension recording this suspension,
+    /// e.g. `premier-tigers-alex-morgan-yellowcards-1`.
+    pub fn name(&self, league: &str) -> String {
+        format!(
+            "{}-{}-{}-{}-{}-{}",
+            schedule::slug(league),
+            schedule::slug(&self.player.team),
+            schedule::slug(&self.player.first_name),
+            schedule::slug(&self.player.last_name),
+            schedule::slug(&format!("{:?}", self.reason)),
+            self.number
+        )
+    }
+
+    /// Spec of the Suspension recording this suspension.
+    pub fn spec(&self, league: &str) -> SuspensionSpec {
+        SuspensionSpec {
+            league_name: league.to_string(),
+            team: self.player.team.clone(),
+            first_name: self.player.first_name.clone(),
+            last_name: self.player.last_name.clone(),
+            reason: self.reason,
+            served: false,
+        }
+    }
+}
+
+/// Every suspension earned with the cards in `tally`: one per red card and one per
+/// `yellow_card_limit` yellow cards.
+pub fn due_suspensions(
+    tally: &BTreeMap<PlayerKey, CardTally>,
+    policy: &SuspensionPolicy,
+) -> Vec<DueSuspension> {
+    let limit = policy.yellow_card_limit.max(1);
+    let mut due = Vec::new();
+    for (player, cards) in tally {
+        let counts = [
+            (SuspensionReason::YellowCards, cards.yellow / limit),
+            (SuspensionReason::RedCard, cards.red),
+        ];
+        for (reason, count) in counts {
+            due.extend((1..=count).map(|number| DueSuspension {
+                player: player.clone(),
+                reason,
+                number,
+            }));
+        }
+    }
+    due
+}
+
+/// Warnings for every scorer of `result` with an unserved suspension in `suspensions`.
+pub fn suspended_scorer_warnings(
+    result: &GameResultSpec,
+    suspensions: &[SuspensionSpec],
+) -> Vec<String> {
+    let mut warnings: Vec<String> = result
+        .scorers
+        .iter()
+        .filter(|scorer| {
+            suspensions.iter().any(|s| {
+                !s.served
+                    && s.league_name == result.league_name
+                    && s.team == scorer.team
+                    && s.first_name == scorer.first_name
+                    && s.last_name == scorer.last_name
+            })
+        })
+        .map(|scorer| {
+            format!(
+                "scorer {} {} of team '{}' is suspended",
+                scorer.first_name, scorer.last_name, scorer.team
+            )
+        })
+        .collect();
+    warnings.dedup();
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::v1alpha1::game_result_types::{Card, PlayerStat};
+
+    fn card(last_name: &str, color: CardColor) -> Card {
+        Card {
+            team: "Tigers".to_string(),
+            first_name: "Alex".to_string(),
+            last_name: last_name.to_string(),
+            minute: None,
+            color,
+        }
+    }
+
+    fn game(cards: Vec<Card>) -> GameResultSpec {
+        GameResultSpec::game("Tigers", "Sharks").with_cards(cards)
+    }
+
+    #[test]
+    fn test_due_suspensions_per_red_card_and_yellow_limit() {
+        let games = [
+            game(vec![
+                card("Morgan", CardColor::Yellow),
+                card("Reed", CardColor::Red),
+            ]),
+            game(vec![card("Morgan", CardColor::Yellow)]),
+            game(vec![
+                card("Morgan", CardColor::Yellow),
+                card("Reed", CardColor::Yellow),
+            ]),
+        ];
+        let refs: Vec<&GameResultSpec> = games.iter().collect();
+        let tally = card_tally(&refs);
+        assert_eq!(tally.len(), 2);
+
+        let due = due_suspensions(
+            &tally,
+            &SuspensionPolicy {
+                yellow_card_limit: 2,
+            },
+        );
+        let names: Vec<String> = due.iter().map(|d| d.name("premier")).collect();
+        assert_eq!(
+            names,
+            vec![
+                "premier-tigers-alex-morgan-yellowcards-1",
+                "premier-tigers-alex-reed-redcard-1",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_suspended_scorer_warnings_skip_served_suspensions() {
+        let mut result = game(Vec::new());
+        result.scorers = vec![PlayerStat {
+            team: "Tigers".to_string(),
+            first_name: "Alex".to_string(),
+            last_name: "Morgan".to_string(),
+            minute: Some(12),
+        }];
+        let due = DueSuspension {
+            player: PlayerKey {
+                team: "Tigers".to_string(),
+                first_name: "Alex".to_string(),
+                last_name: "Morgan".to_string(),
+            },
+            reason: SuspensionReason::RedCard,
+            number: 1,
+        };
+        let mut suspension = due.spec("premier");
+
+        let warnings = suspended_scorer_warnings(&result, std::slice::from_ref(&suspension));
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("Alex Morgan"));
+
+        suspension.served = true;
+        assert!(suspended_scorer_warnings(&result, &[suspension]).is_empty());
+    }
+}
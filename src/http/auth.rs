@@ -0,0 +1,356 @@
+//! Authentication and authorization of the HTTP API of the probe server.
+//!
+//! Requests for league data carry a Kubernetes bearer token, e.g. of a ServiceAccount, which
+//! is checked with a TokenReview. What a request reads or writes is mapped to the verbs of the
+//! corresponding resources and checked with a SubjectAccessReview, so the HTTP API grants what
+//! the viewer, editor and admin roles of `config/rbac` grant with kubectl. Reads can be opened
+//! to callers without a token with `API_ANONYMOUS_READS=true`, e.g. for a public scoreboard or
+//! the dashboard. Only the probes and `/version` are open to anyone; requests to a route
+//! without an access rule here are denied, so a new route is never served unauthenticated by
+//! mistake. Metrics are served by their own server.
+
+use crate::api::v1alpha1::game_result_types::GameResult;
+use crate::api::v1alpha1::match_types::Match;
+use crate::api::v1alpha1::standing_types::Standing;
+use crate::api::v1alpha1::the_league_types::TheLeague;
+use crate::api::v1alpha1::venue_types::Venue;
+use crate::controller::Context;
+use crate::http::standings_api::LeaguesQuery;
+
+use axum::extract::{Query, Request, State};
+use axum::http::{HeaderMap, Method, StatusCode, Uri, header};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use k8s_openapi::api::authentication::v1::{TokenReview, TokenReviewSpec, UserInfo};
+use k8s_openapi::api::authorization::v1::{
+    ResourceAttributes, SubjectAccessReview, SubjectAccessReviewSpec,
+};
+use kube::api::PostParams;
+use kube::{Api, Client, Resource};
+use std::sync::{Arc, OnceLock};
+use tracing::debug;
+
+/// Whether requests only reading league data are served without a bearer token
+pub fn anonymous_reads() -> bool {
+    static ANONYMOUS_READS: OnceLock<bool> = OnceLock::new();
+    *ANONYMOUS_READS.get_or_init(|| {
+        std::env::var("API_ANONYMOUS_READS").is_ok_and(|v| v.eq_ignore_ascii_case("true"))
+    })
+}
+
+/// Access is a verb on a resource a request needs to be allowed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Access {
+    pub verb: &'static str,
+    pub group: String,
+    pub resource: String,
+    /// Namespace of the objects, or None for all namespaces
+    pub namespace: Option<String>,
+    /// Name of the object, or None for all objects
+    pub name: Option<String>,
+}
+
+impl Access {
+    fn new<K: Resource<DynamicType = ()>>(
+        verb: &'static str,
+        namespace: Option<&str>,
+        name: Option<&str>,
+    ) -> Self {
+        Access {
+            verb,
+            group: K::group(&()).into_owned(),
+            resource: K::plural(&()).into_owned(),
+            namespace: namespace.map(str::to_string),
+            name: name.map(str::to_string),
+        }
+    }
+
+    /// Whether the access only reads
+    pub fn is_read(&self) -> bool {
+        matches!(self.verb, "get" | "list" | "watch")
+    }
+}
+
+/// Paths open to anyone: the probes and the build of the controller
+const PUBLIC_PATHS: [&str; 3] = ["healthz", "readyz", "version"];
+
+/// Accesses a request with `method` on `uri` needs, empty if it is open to anyone, or None if
+/// the route has no access rule and the request is denied
+pub fn required_access(method: &Method, uri: &Uri) -> Option<Vec<Access>> {
+    let segments: Vec<&str> = uri.path().trim_matches('/').split('/').collect();
+    let access = match (method, segments.as_slice()) {
+        (&Method::GET | &Method::HEAD, [path]) if PUBLIC_PATHS.contains(path) => Vec::new(),
+        (&Method::GET, ["api", "v1", "leagues"]) => {
+            let namespace = Query::<LeaguesQuery>::try_from_uri(uri)
+                .ok()
+                .and_then(|q| q.0.namespace);
+            vec![Access::new::<TheLeague>("list", namespace.as_deref(), None)]
+        }
+        (&Method::GET, ["api", "v1", "leagues", namespace, name, "standings"]) => {
+            vec![Access::new::<TheLeague>("get", Some(namespace), Some(name))]
+        }
+        // The page shows the results and the standings of the league too.
+        (&Method::GET, ["ui", "leagues", namespace, name]) => vec![
+            Access::new::<TheLeague>("get", Some(namespace), Some(name)),
+            Access::new::<GameResult>("list", Some(namespace), None),
+            Access::new::<Standing>("list", Some(namespace), None),
+        ],
+        (&Method::GET, ["api", "v1", "leagues", namespace, name, "schedule.ics"]) => vec![
+            Access::new::<TheLeague>("get", Some(namespace), Some(name)),
+            Access::new::<Match>("list", Some(namespace), None),
+            Access::new::<Venue>("list", Some(namespace), None),
+        ],
+        (&Method::GET, ["api", "v1", "leagues", namespace, name, "results"]) => vec![
+            Access::new::<TheLeague>("get", Some(namespace), Some(name)),
+            Access::new::<GameResult>("list", Some(namespace), None),
+        ],
+        (&Method::POST, ["api", "v1", "leagues", namespace, _, "results"]) => {
+            vec![Access::new::<GameResult>("create", Some(namespace), None)]
+        }
+        (_, ["api", "graphql"]) => vec![
+            Access::new::<TheLeague>("list", None, None),
+            Access::new::<GameResult>("list", None, None),
+        ],
+        (&Method::GET, ["ui"]) | (&Method::GET, ["api", "openapi.json"]) => {
+            vec![Access::new::<TheLeague>("list", None, None)]
+        }
+        _ => return None,
+    };
+    Some(access)
+}
+
+/// Bearer token of the `Authorization` header of a request
+pub fn bearer_token(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(header::AUTHORIZATION)?
+        .to_str()
+        .ok()?
+        .strip_prefix("Bearer ")
+        .map(str::trim)
+        .filter(|token| !token.is_empty())
+}
+
+/// Middleware authenticating the caller of a request and checking it is allowed the
+/// [`required_access`] of the request. The [`UserInfo`] of the caller is added to the
+/// extensions of the request for the handlers.
+pub async fn require_access(
+    State(context): State<Arc<Context>>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let Some(accesses) = required_access(request.method(), request.uri()) else {
+        let reason = format!(
+            "no access rule for {} {}",
+            request.method(),
+            request.uri().path()
+        );
+        return (StatusCode::FORBIDDEN, reason).into_response();
+    };
+    if accesses.is_empty() {
+        return next.run(request).await;
+    }
+    let user = match bearer_token(request.headers()) {
+        None if anonymous_reads() && accesses.iter().all(Access::is_read) => {
+            return next.run(request).await;
+        }
+        None => {
+            let reason = "a bearer token is required".to_string();
+            return (StatusCode::UNAUTHORIZED, reason).into_response();
+        }
+        Some(token) => match authenticate(&context.client, token).await {
+            Ok(user) => user,
+            Err(rejection) => return rejection.into_response(),
+        },
+    };
+    for access in &accesses {
+        if let Err(rejection) = authorize(&context.client, &user, access).await {
+            return rejection.into_response();
+        }
+    }
+    debug!(user = ?user.username, "Authorized {} {}", request.method(), request.uri());
+    request.extensions_mut().insert(user);
+    next.run(request).await
+}
+
+/// User owning `token`, checked with a TokenReview
+async fn authenticate(client: &Client, token: &str) -> Result<UserInfo, (StatusCode, String)> {
+    let review = TokenReview {
+        spec: TokenReviewSpec {
+            token: Some(token.to_string()),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let review = Api::<TokenReview>::all(client.clone())
+        .create(&PostParams::default(), &review)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::BAD_GATEWAY,
+                format!("token review failed: {}", e),
+            )
+        })?;
+    let status = review.status.unwrap_or_default();
+    match status.user {
+        Some(user) if status.authenticated == Some(true) => Ok(user),
+        _ => Err((
+            StatusCode::UNAUTHORIZED,
+            status
+                .error
+                .unwrap_or_else(|| "invalid bearer token".to_string()),
+        )),
+    }
+}
+
+/// Check with a SubjectAccessReview that `user` is allowed `access`
+async fn authorize(
+    client: &Client,
+    user: &UserInfo,
+    access: &Access,
+) -> Result<(), (StatusCode, String)> {
+    let review = SubjectAccessReview {
+        spec: SubjectAccessReviewSpec {
+            user: user.username.clone(),
+            groups: user.groups.clone(),
+            uid: user.uid.clone(),
+            extra: user.extra.clone(),
+            resource_attributes: Some(ResourceAttributes {
+                group: Some(access.group.clone()),
+                resource: Some(access.resource.clone()),
+                verb: Some(access.verb.to_string()),
+                namespace: access.namespace.clone(),
+                name: access.name.clone(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let review = Api::<SubjectAccessReview>::all(client.clone())
+        .create(&PostParams::default(), &review)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::BAD_GATEWAY,
+                format!("access review failed: {}", e),
+            )
+        })?;
+    if review.status.is_some_and(|s| s.allowed) {
+        return Ok(());
+    }
+    let scope = match &access.namespace {
+        Some(namespace) => format!("in namespace '{}'", namespace),
+        None => "in all namespaces".to_string(),
+    };
+    Err((
+        StatusCode::FORBIDDEN,
+        format!(
+            "'{}' may not {} {} {}",
+            user.username.as_deref().unwrap_or_default(),
+            access.verb,
+            access.resource,
+            scope
+        ),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    #[test]
+    fn test_required_access_follows_the_crd_verbs() {
+        let access = |method: Method, uri: &str| {
+            required_access(&method, &uri.parse().unwrap()).map(|accesses| {
+                accesses
+                    .into_iter()
+                    .map(|a| (a.verb, a.resource, a.namespace, a.name))
+                    .collect::<Vec<_>>()
+            })
+        };
+        let some = |s: &str| Some(s.to_string());
+
+        assert_eq!(
+            access(Method::GET, "/api/v1/leagues?namespace=default"),
+            Some(vec![(
+                "list",
+                "theleagues".to_string(),
+                some("default"),
+                None
+            )])
+        );
+        assert_eq!(
+            access(Method::GET, "/api/v1/leagues/default/premier/standings"),
+            Some(vec![(
+                "get",
+                "theleagues".to_string(),
+                some("default"),
+                some("premier")
+            )])
+        );
+        assert_eq!(
+            access(Method::POST, "/api/v1/leagues/default/premier/results"),
+            Some(vec![(
+                "create",
+                "gameresults".to_string(),
+                some("default"),
+                None
+            )])
+        );
+        assert_eq!(
+            access(Method::GET, "/api/v1/leagues/default/premier/schedule.ics").map(|a| a.len()),
+            Some(3)
+        );
+        assert_eq!(
+            access(Method::GET, "/api/v1/leagues/default/premier/results").map(|a| a.len()),
+            Some(2)
+        );
+        assert_eq!(access(Method::GET, "/ui").map(|a| a.len()), Some(1));
+        assert_eq!(
+            access(Method::GET, "/ui/leagues/default/premier"),
+            Some(vec![
+                (
+                    "get",
+                    "theleagues".to_string(),
+                    some("default"),
+                    some("premier")
+                ),
+                ("list", "gameresults".to_string(), some("default"), None),
+                ("list", "standings".to_string(), some("default"), None),
+            ])
+        );
+        assert_eq!(
+            access(Method::POST, "/api/graphql").map(|a| a.len()),
+            Some(2)
+        );
+        assert_eq!(access(Method::GET, "/healthz"), Some(vec![]));
+        assert_eq!(access(Method::GET, "/readyz"), Some(vec![]));
+        assert_eq!(access(Method::GET, "/version"), Some(vec![]));
+        assert_eq!(
+            access(Method::GET, "/api/openapi.json").map(|a| a.len()),
+            Some(1)
+        );
+        // Routes without a rule, or methods a route doesn't serve, are denied
+        assert_eq!(access(Method::GET, "/loglevel"), None);
+        assert_eq!(access(Method::PUT, "/loglevel"), None);
+        assert_eq!(access(Method::GET, "/debug/runtime"), None);
+        assert_eq!(access(Method::POST, "/healthz"), None);
+    }
+
+    #[test]
+    fn test_bearer_token() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(bearer_token(&headers), None);
+        headers.insert(
+            header::AUTHORIZATION,
+            HeaderValue::from_static("Basic Zm9v"),
+        );
+        assert_eq!(bearer_token(&headers), None);
+        headers.insert(
+            header::AUTHORIZATION,
+            HeaderValue::from_static("Bearer abc"),
+        );
+        assert_eq!(bearer_token(&headers), Some("abc"));
+    }
+}
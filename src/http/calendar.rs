@@ -0,0 +1,260 @@
+//! iCalendar feed of the fixtures of a league, served on
+//! `GET /api/v1/leagues/{namespace}/{name}/schedule.ics` of the probe server so players can
+//! subscribe to the fixture list from the calendar of their phone.
+//!
+//! Every Match with a kickoff is an event at its rescheduled or scheduled kickoff, located at
+//! the Venue booked for it. Calendar apps poll subscriptions rarely, so the Matches and Venues
+//! are listed from the API server on each request rather than cached.
+
+use crate::api::v1alpha1::match_types::{Match, MatchPhase};
+use crate::api::v1alpha1::the_league_types::TheLeague;
+use crate::api::v1alpha1::venue_types::Venue;
+use crate::controller::{Context, matches};
+use crate::http::standings_api;
+
+use axum::extract::{Path, State};
+use axum::http::{StatusCode, header};
+use axum::response::IntoResponse;
+use k8s_openapi::chrono::{DateTime, Duration, Utc};
+use kube::api::ListParams;
+use kube::runtime::reflector::ObjectRef;
+use kube::{Api, ResourceExt};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::Arc;
+
+/// Length of the events of the matches
+pub const MATCH_DURATION: Duration = Duration::hours(2);
+
+/// Content type of the feed
+pub const CONTENT_TYPE: &str = "text/calendar; charset=utf-8";
+
+/// Calendar of the fixtures of a league
+#[utoipa::path(
+    get,
+    path = "/api/v1/leagues/{namespace}/{name}/schedule.ics",
+    params(
+        ("namespace" = String, Path, description = "Namespace of the league"),
+        ("name" = String, Path, description = "Name of the league"),
+    ),
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "iCalendar of the matches", content_type = "text/calendar"),
+        (status = 401, description = "The bearer token is missing or invalid", body = String),
+        (status = 403, description = "The caller may not list the matches", body = String),
+        (status = 404, description = "The league doesn't exist", body = String),
+    )
+)]
+pub async fn league_schedule(
+    State(context): State<Arc<Context>>,
+    Path((namespace, name)): Path<(String, String)>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let leagues = context
+        .caches
+        .leagues()
+        .ok_or_else(standings_api::caches_syncing)?;
+    if leagues
+        .get(&ObjectRef::<TheLeague>::new(&name).within(&namespace))
+        .is_none()
+    {
+        let reason = format!("league '{}/{}' not found", namespace, name);
+        return Err((StatusCode::NOT_FOUND, reason));
+    }
+    let bad_gateway = |e: kube::Error| (StatusCode::BAD_GATEWAY, e.to_string());
+    let match_api: Api<Match> = Api::namespaced(context.client.clone(), &namespace);
+    let fixtures = matches::league_matches(&match_api, &name)
+        .await
+        .map_err(bad_gateway)?;
+    let venue_api: Api<Venue> = Api::namespaced(context.client.clone(), &namespace);
+    let venues = venue_api
+        .list(&ListParams::default())
+        .await
+        .map_err(bad_gateway)?
+        .items;
+    let calendar = league_calendar(&namespace, &name, &fixtures, &venues, Utc::now());
+    Ok(([(header::CONTENT_TYPE, CONTENT_TYPE)], calendar))
+}
+
+/// iCalendar of the `fixtures` of the league `namespace/name`, located at their `venues`,
+/// stamped at `now`. Matches without a kickoff, e.g. postponed ones not rescheduled yet, are
+/// left out.
+pub fn league_calendar(
+    namespace: &str,
+    name: &str,
+    fixtures: &[Match],
+    venues: &[Venue],
+    now: DateTime<Utc>,
+) -> String {
+    let addresses: BTreeMap<String, Option<&str>> = venues
+        .iter()
+        .map(|v| (v.name_any(), v.spec.address.as_deref()))
+        .collect();
+    let mut fixtures: Vec<(&Match, DateTime<Utc>)> = fixtures
+        .iter()
+        .filter_map(|m| {
+            let status = m.status.as_ref();
+            let kickoff = status
+                .and_then(|s| s.kickoff.as_ref())
+                .or(m.spec.kickoff.as_ref())?;
+            let postponed = status.is_some_and(|s| s.phase == MatchPhase::Postponed);
+            (!postponed || status.is_some_and(|s| s.kickoff.is_some())).then_some((m, kickoff.0))
+        })
+        .collect();
+    fixtures.sort_by_key(|(m, kickoff)| (*kickoff, m.spec.round));
+
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//bexxmodd.com//TheLeague//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+        format!("X-WR-CALNAME:{}", escape(name)),
+    ];
+    for (fixture, kickoff) in fixtures {
+        let venue = fixture.status.as_ref().and_then(|s| s.venue.as_deref());
+        lines.push("BEGIN:VEVENT".to_string());
+        lines.push(format!(
+            "UID:{}.{}@bexxmodd.com",
+            fixture.name_any(),
+            namespace
+        ));
+        lines.push(format!("DTSTAMP:{}", timestamp(now)));
+        lines.push(format!("DTSTART:{}", timestamp(kickoff)));
+        lines.push(format!("DTEND:{}", timestamp(kickoff + MATCH_DURATION)));
+        lines.push(format!(
+            "SUMMARY:{}",
+            escape(&format!("{} vs {}", fixture.spec.home, fixture.spec.away))
+        ));
+        lines.push(format!(
+            "DESCRIPTION:{}",
+            escape(&format!("Round {} of {}", fixture.spec.round, name))
+        ));
+        if let Some(venue) = venue {
+            let location = match addresses.get(venue).copied().flatten() {
+                Some(address) => format!("{}, {}", venue, address),
+                None => venue.to_string(),
+            };
+            lines.push(format!("LOCATION:{}", escape(&location)));
+        }
+        lines.push("END:VEVENT".to_string());
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    let mut calendar = String::new();
+    for line in lines {
+        let _ = write!(calendar, "{}\r\n", fold(&line));
+    }
+    calendar
+}
+
+fn timestamp(time: DateTime<Utc>) -> String {
+    time.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+/// `text` escaped as an iCalendar TEXT value
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' | ';' | ',' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// `line` folded into lines of at most 75 octets, continued with a space
+fn fold(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len());
+    let mut octets = 0;
+    for c in line.chars() {
+        if octets + c.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            octets = 1;
+        }
+        folded.push(c);
+        octets += c.len_utf8();
+    }
+    folded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::v1alpha1::match_types::{MatchSpec, MatchStatus};
+    use crate::api::v1alpha1::venue_types::VenueSpec;
+    use k8s_openapi::apimachinery::pkg::apis::meta::v1::Time;
+
+    fn fixture(name: &str, kickoff: Option<i64>, status: Option<MatchStatus>) -> Match {
+        let mut m = Match::new(
+            name,
+            MatchSpec {
+                league_name: "premier".to_string(),
+                season: None,
+                round: 1,
+                home: "Tigers".to_string(),
+                away: "Sharks".to_string(),
+                kickoff: kickoff.map(|s| Time(DateTime::from_timestamp(s, 0).unwrap())),
+            },
+        );
+        m.status = status;
+        m
+    }
+
+    #[test]
+    fn test_league_calendar_lists_the_matches_with_a_kickoff() {
+        let venue = Venue::new(
+            "arena",
+            VenueSpec {
+                address: Some("1 Main St, Springfield".to_string()),
+                availability: Vec::new(),
+            },
+        );
+        let booked = MatchStatus {
+            venue: Some("arena".to_string()),
+            ..Default::default()
+        };
+        let postponed = MatchStatus {
+            phase: MatchPhase::Postponed,
+            ..Default::default()
+        };
+        let fixtures = [
+            fixture(
+                "premier-r1-tigers-sharks",
+                Some(1_700_000_000),
+                Some(booked),
+            ),
+            fixture("premier-r2-sharks-tigers", None, None),
+            fixture(
+                "premier-r3-tigers-sharks",
+                Some(1_700_500_000),
+                Some(postponed),
+            ),
+        ];
+        let now = DateTime::from_timestamp(1_690_000_000, 0).unwrap();
+        let calendar = league_calendar("default", "premier", &fixtures, &[venue], now);
+
+        assert!(calendar.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert!(calendar.ends_with("END:VCALENDAR\r\n"));
+        assert_eq!(calendar.matches("BEGIN:VEVENT").count(), 1);
+        assert!(calendar.contains("UID:premier-r1-tigers-sharks.default@bexxmodd.com\r\n"));
+        assert!(calendar.contains("DTSTART:20231114T221320Z\r\n"));
+        assert!(calendar.contains("DTEND:20231115T001320Z\r\n"));
+        assert!(calendar.contains("SUMMARY:Tigers vs Sharks\r\n"));
+        assert!(calendar.contains("LOCATION:arena\\, 1 Main St\\, Springfield\r\n"));
+    }
+
+    #[test]
+    fn test_fold_long_lines() {
+        let line = format!("SUMMARY:{}", "x".repeat(100));
+        let folded = fold(&line);
+        let parts: Vec<&str> = folded.split("\r\n ").collect();
+        assert_eq!(parts.len(), 2);
+        assert_eq!(parts[0].len(), 75);
+        assert_eq!(folded.replace("\r\n ", ""), line);
+    }
+}
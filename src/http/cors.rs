@@ -0,0 +1,58 @@
+//! Cross-origin access to the HTTP API of the probe server, so a scoreboard page served from
+//! another origin can call it from the browser.
+//!
+//! Origins are allowed with `API_CORS_ORIGINS`, a comma-separated list of origins such as
+//! `https://scores.example.com`, or `*` for any origin. Without it no cross-origin request is
+//! allowed, as before.
+
+use axum::http::{HeaderValue, Method, header};
+use tower_http::cors::{AllowOrigin, CorsLayer};
+use tracing::warn;
+
+/// Origins allowed to call the HTTP API, parsed from `origins`; None if no origin is allowed
+pub fn allowed_origins(origins: &str) -> Option<AllowOrigin> {
+    let origins: Vec<&str> = origins
+        .split(',')
+        .map(str::trim)
+        .filter(|origin| !origin.is_empty())
+        .collect();
+    if origins.contains(&"*") {
+        return Some(AllowOrigin::any());
+    }
+    let origins: Vec<HeaderValue> = origins
+        .into_iter()
+        .filter_map(|origin| match HeaderValue::from_str(origin) {
+            Ok(value) => Some(value),
+            Err(_) => {
+                warn!("Ignoring invalid CORS origin '{}'", origin);
+                None
+            }
+        })
+        .collect();
+    (!origins.is_empty()).then(|| AllowOrigin::list(origins))
+}
+
+/// Layer answering the CORS requests of the origins of `API_CORS_ORIGINS`, if any are set
+pub fn layer() -> Option<CorsLayer> {
+    let origins = allowed_origins(&std::env::var("API_CORS_ORIGINS").ok()?)?;
+    Some(
+        CorsLayer::new()
+            .allow_origin(origins)
+            .allow_methods([Method::GET, Method::POST])
+            .allow_headers([header::AUTHORIZATION, header::CONTENT_TYPE])
+            .expose_headers([header::RETRY_AFTER]),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_allowed_origins() {
+        assert!(allowed_origins("").is_none());
+        assert!(allowed_origins(" , ").is_none());
+        assert!(allowed_origins("https://scores.example.com, *").is_some());
+        assert!(allowed_origins("https://scores.example.com,http://localhost:3000").is_some());
+    }
+}
@@ -0,0 +1,197 @@
+//! Server-rendered standings dashboard, served on `/ui` of the probe server for leagues that
+//! don't build a frontend of their own.
+//!
+//! `/ui` lists the leagues and `/ui/leagues/{namespace}/{name}` shows the table of a league
+//! with its recent results and upcoming fixtures. The pages are plain HTML rendered from the
+//! reflector caches, with no script and the styles inlined.
+
+use crate::api::v1alpha1::game_result_types::{GameResult, GameResultSpec};
+use crate::api::v1alpha1::the_league_types::TheLeague;
+use crate::http::standings_api::{self, LeagueStandings, LeagueSummary};
+use crate::schedule::{self, Fixture};
+use crate::standings;
+
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::Time;
+use maud::{DOCTYPE, Markup, html};
+use std::sync::Arc;
+
+/// Results and fixtures listed on the page of a league
+pub const DASHBOARD_GAMES: usize = 10;
+
+const STYLE: &str = "body{font-family:system-ui,sans-serif;margin:2rem auto;max-width:60rem;\
+    padding:0 1rem;color:#222}table{border-collapse:collapse;width:100%;margin-bottom:2rem}\
+    th,td{padding:.3rem .6rem;border-bottom:1px solid #ddd;text-align:right}\
+    th:nth-child(2),td:nth-child(2){text-align:left}a{color:#0b5cad}";
+
+/// LeagueDashboard is the content of the page of a league.
+#[derive(Debug, Clone)]
+pub struct LeagueDashboard {
+    pub standings: LeagueStandings,
+    /// Recent is the latest completed games counted in the table, most recent first.
+    pub recent: Vec<GameResultSpec>,
+    /// Upcoming is the next fixtures of the schedule without a completed game.
+    pub upcoming: Vec<Fixture>,
+}
+
+/// Content of the page of `league`, whose games are in `results`
+pub fn league_dashboard(league: &TheLeague, results: &[Arc<GameResult>]) -> LeagueDashboard {
+    let status = league.status.clone().unwrap_or_default();
+    let counted = standings_api::counted_results(league, &status, results);
+    let mut recent: Vec<GameResultSpec> = counted
+        .iter()
+        .filter(|spec| standings::outcome_scores(&spec.result).is_some())
+        .map(|spec| (*spec).clone())
+        .collect();
+    recent.sort_by_key(|spec| std::cmp::Reverse(spec.time.as_ref().map(|t| t.0)));
+    recent.truncate(DASHBOARD_GAMES);
+    let mut upcoming = schedule::league_schedule(&league.spec)
+        .map(|schedule| schedule.outstanding_fixtures(&counted))
+        .unwrap_or_default();
+    upcoming.truncate(DASHBOARD_GAMES);
+    LeagueDashboard {
+        standings: standings_api::league_standings(league, results),
+        recent,
+        upcoming,
+    }
+}
+
+fn page(title: &str, content: Markup) -> Markup {
+    html! {
+        (DOCTYPE)
+        html lang="en" {
+            head {
+                meta charset="utf-8";
+                meta name="viewport" content="width=device-width, initial-scale=1";
+                title { (title) }
+                style { (STYLE) }
+            }
+            body { (content) }
+        }
+    }
+}
+
+fn date(time: &Time) -> String {
+    time.0.format("%Y-%m-%d %H:%M").to_string()
+}
+
+/// Page listing `leagues`
+pub fn leagues_page(leagues: &[LeagueSummary]) -> Markup {
+    page(
+        "Leagues",
+        html! {
+            h1 { "Leagues" }
+            @if leagues.is_empty() {
+                p { "No leagues yet." }
+            } @else {
+                table {
+                    tr { th { "Namespace" } th { "League" } th { "Teams" } th { "Phase" }
+                        th { "Games" } th { "Leader" } }
+                    @for league in leagues {
+                        tr {
+                            td { (league.namespace) }
+                            td {
+                                a href={ "/ui/leagues/" (league.namespace) "/" (league.name) } {
+                                    (league.name)
+                                }
+                            }
+                            td { (league.teams) }
+                            td { (format!("{:?}", league.phase)) }
+                            td {
+                                (league.games_played)
+                                @if let Some(total) = league.games_total { " / " (total) }
+                            }
+                            td { (league.leader.as_deref().unwrap_or("-")) }
+                        }
+                    }
+                }
+            }
+        },
+    )
+}
+
+/// Page of a league
+pub fn league_page(dashboard: &LeagueDashboard) -> Markup {
+    let standings = &dashboard.standings;
+    page(
+        &standings.name,
+        html! {
+            p { a href="/ui" { "All leagues" } }
+            h1 {
+                (standings.name)
+                @if let Some(season) = standings.season { " - season " (season) }
+            }
+            h2 { "Table" }
+            table {
+                tr { th { "#" } th { "Team" } th { "P" } th { "W" } th { "D" } th { "L" }
+                    th { "GD" } th { "Pts" } th { "Form" } }
+                @for row in &standings.table {
+                    tr {
+                        td { (row.rank) } td { (row.team) } td { (row.played) }
+                        td { (row.wins) } td { (row.draws) } td { (row.losses) }
+                        td { (row.goal_difference) } td { strong { (row.points) } }
+                        td { (row.form) }
+                    }
+                }
+            }
+            h2 { "Recent results" }
+            @if dashboard.recent.is_empty() {
+                p { "No results yet." }
+            } @else {
+                table {
+                    tr { th { "Round" } th { "Game" } th { "Score" } th { "Played" } }
+                    @for spec in &dashboard.recent {
+                        @let (home, away) = standings::outcome_scores(&spec.result)
+                            .unwrap_or_default();
+                        tr {
+                            td { (spec.round_number) }
+                            td { (spec.teams[0]) " - " (spec.teams[1]) }
+                            td { (home) " - " (away) }
+                            td { (spec.time.as_ref().map(date).unwrap_or_default()) }
+                        }
+                    }
+                }
+            }
+            h2 { "Upcoming fixtures" }
+            @if dashboard.upcoming.is_empty() {
+                p { "No fixtures left." }
+            } @else {
+                table {
+                    tr { th { "Round" } th { "Game" } th { "Kickoff" } }
+                    @for fixture in &dashboard.upcoming {
+                        @let kickoff =
+                            fixture.rescheduled_to.as_ref().or(fixture.kickoff.as_ref());
+                        tr {
+                            td { (fixture.round) }
+                            td { (fixture.home) " - " (fixture.away) }
+                            td { (kickoff.map(date).unwrap_or_else(|| "-".to_string())) }
+                        }
+                    }
+                }
+            }
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::v1alpha1::the_league_types::LeaguePhase;
+
+    #[test]
+    fn test_leagues_page_links_and_escapes_the_leagues() {
+        let leagues = [LeagueSummary {
+            namespace: "default".to_string(),
+            name: "premier".to_string(),
+            teams: 2,
+            phase: LeaguePhase::InProgress,
+            games_played: 1,
+            games_total: Some(2),
+            leader: Some("<Tigers>".to_string()),
+        }];
+        let html = leagues_page(&leagues).into_string();
+        assert!(html.contains(r#"<a href="/ui/leagues/default/premier">premier</a>"#));
+        assert!(html.contains("1 / 2"));
+        assert!(html.contains("&lt;Tigers&gt;"));
+        assert!(leagues_page(&[]).into_string().contains("No leagues yet."));
+    }
+}
@@ -0,0 +1,401 @@
+//! GraphQL API over the leagues, their teams, standings, fixtures and results, served on
+//! `/api/graphql` of the probe server with the `graphql` feature.
+//!
+//! Like the REST API of [`standings_api`], every query is
+//! answered from the reflector caches; relations between the objects are resolved in memory,
+//! so a frontend fetches a league with its table and latest results in a single request.
+
+use crate::api::v1alpha1::game_result_types::{GameOutcome, GameResult};
+use crate::api::v1alpha1::the_league_types::TheLeague;
+use crate::controller::caches::Caches;
+use crate::controller::{gameresult_controller, references};
+use crate::http::standings_api::{self, StandingsRow};
+use crate::schedule::{self, Fixture};
+use crate::standings;
+
+use async_graphql::{EmptyMutation, EmptySubscription, Error, Object, Result, Schema};
+use async_graphql_axum::GraphQL;
+use axum::Router;
+use axum::routing::post_service;
+use k8s_openapi::chrono::SecondsFormat;
+use kube::ResourceExt;
+use std::sync::Arc;
+
+/// Schema of the GraphQL API
+pub type LeagueSchema = Schema<Query, EmptyMutation, EmptySubscription>;
+
+/// Schema answering from `caches`
+pub fn schema(caches: Caches) -> LeagueSchema {
+    Schema::build(Query, EmptyMutation, EmptySubscription)
+        .data(caches)
+        .finish()
+}
+
+/// Router serving the schema on `/api/graphql`
+pub fn router(caches: Caches) -> Router {
+    Router::new().route("/api/graphql", post_service(GraphQL::new(schema(caches))))
+}
+
+/// Leagues of the caches at the time of a query, with their results
+fn snapshot(ctx: &async_graphql::Context<'_>) -> Result<Vec<League>> {
+    let (leagues, results) = ctx
+        .data::<Caches>()?
+        .synced()
+        .ok_or_else(|| Error::new("the league caches are still syncing"))?;
+    let results = results.state();
+    Ok(leagues
+        .state()
+        .into_iter()
+        .map(|league| League::new(league, &results))
+        .collect())
+}
+
+pub struct Query;
+
+#[Object]
+impl Query {
+    /// Leagues sorted by namespace and name, or those of `namespace` if given
+    async fn leagues(
+        &self,
+        ctx: &async_graphql::Context<'_>,
+        namespace: Option<String>,
+    ) -> Result<Vec<League>> {
+        let mut leagues: Vec<League> = snapshot(ctx)?
+            .into_iter()
+            .filter(|l| namespace.is_none() || l.league.namespace() == namespace)
+            .collect();
+        leagues.sort_by_key(|l| (l.league.namespace(), l.league.name_any()));
+        Ok(leagues)
+    }
+
+    /// League `name` of `namespace`
+    async fn league(
+        &self,
+        ctx: &async_graphql::Context<'_>,
+        namespace: String,
+        name: String,
+    ) -> Result<Option<League>> {
+        Ok(snapshot(ctx)?.into_iter().find(|l| {
+            l.league.namespace().as_deref() == Some(namespace.as_str())
+                && l.league.name_any() == name
+        }))
+    }
+}
+
+/// A league with the results filed for it
+#[derive(Clone)]
+pub struct League {
+    league: Arc<TheLeague>,
+    results: Arc<[Arc<GameResult>]>,
+}
+
+impl League {
+    fn new(league: Arc<TheLeague>, results: &[Arc<GameResult>]) -> Self {
+        let namespace = league.namespace().unwrap_or_default();
+        let results = results
+            .iter()
+            .filter(|r| {
+                references::league_namespace(r.namespace(), &r.spec.league_namespace) == namespace
+                    && r.spec.league_name == league.name_any()
+            })
+            .cloned()
+            .collect();
+        League { league, results }
+    }
+
+    fn result_nodes(&self, team: Option<&str>) -> Vec<ResultNode> {
+        self.results
+            .iter()
+            .filter(|r| team.is_none_or(|team| r.spec.teams.iter().any(|t| t == team)))
+            .map(|r| ResultNode {
+                result: r.clone(),
+                league: self.clone(),
+            })
+            .collect()
+    }
+
+    fn fixture_nodes(&self, round: Option<u32>, team: Option<&str>) -> Result<Vec<FixtureNode>> {
+        Ok(schedule::league_schedule(&self.league.spec)?
+            .fixtures()
+            .filter(|f| round.is_none_or(|round| f.round == round))
+            .filter(|f| team.is_none_or(|team| f.involves(team)))
+            .map(|f| FixtureNode {
+                fixture: f.clone(),
+                league: self.clone(),
+            })
+            .collect())
+    }
+
+    fn table(&self) -> Vec<StandingsRow> {
+        standings_api::league_standings(&self.league, &self.results).table
+    }
+}
+
+#[Object]
+impl League {
+    async fn namespace(&self) -> String {
+        self.league.namespace().unwrap_or_default()
+    }
+
+    async fn name(&self) -> String {
+        self.league.name_any()
+    }
+
+    /// Phase of the league: Pending, InProgress or Completed
+    async fn phase(&self) -> String {
+        let phase = self.league.status.clone().unwrap_or_default().phase;
+        format!("{:?}", phase)
+    }
+
+    /// Current season, for leagues running in seasons
+    async fn season(&self) -> Option<u32> {
+        self.league.status.as_ref().and_then(|s| s.current_season)
+    }
+
+    async fn teams(&self) -> Vec<TeamNode> {
+        self.league
+            .spec
+            .teams
+            .iter()
+            .map(|team| TeamNode {
+                name: team.name.clone(),
+                abbreviation: team.abbreviation.clone(),
+                location: team.location.clone(),
+                league: self.clone(),
+            })
+            .collect()
+    }
+
+    /// Table of the league, sorted by rank
+    async fn standings(&self) -> Vec<StandingsRow> {
+        self.table()
+    }
+
+    /// Fixtures of the schedule in round order, of `round` and of `team` if given
+    async fn fixtures(&self, round: Option<u32>, team: Option<String>) -> Result<Vec<FixtureNode>> {
+        self.fixture_nodes(round, team.as_deref())
+    }
+
+    /// Results filed for the league, of `team` if given
+    async fn results(&self, team: Option<String>) -> Vec<ResultNode> {
+        self.result_nodes(team.as_deref())
+    }
+}
+
+/// A team of a league
+pub struct TeamNode {
+    name: String,
+    abbreviation: Option<String>,
+    location: Option<String>,
+    league: League,
+}
+
+#[Object(name = "Team")]
+impl TeamNode {
+    async fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn abbreviation(&self) -> Option<&str> {
+        self.abbreviation.as_deref()
+    }
+
+    async fn location(&self) -> Option<&str> {
+        self.location.as_deref()
+    }
+
+    /// Row of the team in the table, once it has a Standing
+    async fn standing(&self) -> Option<StandingsRow> {
+        self.league
+            .table()
+            .into_iter()
+            .find(|row| row.team == self.name)
+    }
+
+    async fn fixtures(&self) -> Result<Vec<FixtureNode>> {
+        self.league.fixture_nodes(None, Some(&self.name))
+    }
+
+    async fn results(&self) -> Vec<ResultNode> {
+        self.league.result_nodes(Some(&self.name))
+    }
+
+    async fn league(&self) -> &League {
+        &self.league
+    }
+}
+
+/// A fixture of the schedule of a league
+pub struct FixtureNode {
+    fixture: Fixture,
+    league: League,
+}
+
+#[Object(name = "Fixture")]
+impl FixtureNode {
+    async fn round(&self) -> u32 {
+        self.fixture.round
+    }
+
+    async fn home(&self) -> &str {
+        &self.fixture.home
+    }
+
+    async fn away(&self) -> &str {
+        &self.fixture.away
+    }
+
+    /// Scheduled kickoff in RFC 3339, for leagues with a calendar
+    async fn kickoff(&self) -> Option<String> {
+        let kickoff = self
+            .fixture
+            .rescheduled_to
+            .as_ref()
+            .or(self.fixture.kickoff.as_ref());
+        kickoff.map(|t| t.0.to_rfc3339_opts(SecondsFormat::Secs, true))
+    }
+
+    /// Latest result filed for the fixture
+    async fn result(&self) -> Option<ResultNode> {
+        self.league
+            .result_nodes(None)
+            .into_iter()
+            .filter(|r| {
+                r.result.round() == self.fixture.round
+                    && self
+                        .fixture
+                        .involves_pair(&r.result.spec.teams[0], &r.result.spec.teams[1])
+            })
+            .max_by_key(|r| r.result.spec.time.as_ref().map(|t| t.0))
+    }
+}
+
+/// A GameResult of a league
+pub struct ResultNode {
+    result: Arc<GameResult>,
+    league: League,
+}
+
+#[Object(name = "Result")]
+impl ResultNode {
+    async fn name(&self) -> String {
+        self.result.name_any()
+    }
+
+    async fn round(&self) -> u32 {
+        self.result.round()
+    }
+
+    async fn home(&self) -> &str {
+        &self.result.spec.teams[0]
+    }
+
+    async fn away(&self) -> &str {
+        &self.result.spec.teams[1]
+    }
+
+    /// Outcome of the game: WinnerHomeTeam, WinnerAwayTeam, Draw, Postponed, Abandoned or
+    /// Pending
+    async fn outcome(&self) -> &str {
+        match self.result.spec.result {
+            GameOutcome::WinnerHomeTeam { .. } => "WinnerHomeTeam",
+            GameOutcome::WinnerAwayTeam { .. } => "WinnerAwayTeam",
+            GameOutcome::Draw { .. } => "Draw",
+            GameOutcome::Postponed { .. } => "Postponed",
+            GameOutcome::Abandoned { .. } => "Abandoned",
+            GameOutcome::Pending {} => "Pending",
+        }
+    }
+
+    /// Goals of the home team, for completed games
+    async fn score_home(&self) -> Option<u32> {
+        standings::outcome_scores(&self.result.spec.result).map(|(home, _)| home)
+    }
+
+    /// Goals of the away team, for completed games
+    async fn score_away(&self) -> Option<u32> {
+        standings::outcome_scores(&self.result.spec.result).map(|(_, away)| away)
+    }
+
+    /// Time the game was played in RFC 3339, unset until the kickoff is known
+    async fn time(&self) -> Option<String> {
+        self.result
+            .spec
+            .time
+            .as_ref()
+            .map(|t| t.0.to_rfc3339_opts(SecondsFormat::Secs, true))
+    }
+
+    /// Whether the result passed validation and counts for the league
+    async fn accepted(&self) -> bool {
+        gameresult_controller::is_accepted(&self.result)
+    }
+
+    async fn league(&self) -> &League {
+        &self.league
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::v1alpha1::game_result_types::GameResultSpec;
+    use crate::api::v1alpha1::the_league_types::TheLeagueSpec;
+    use kube::runtime::{reflector, watcher};
+    use serde_json::json;
+
+    fn synced<K>(objects: Vec<K>) -> reflector::Store<K>
+    where
+        K: kube::Resource + Clone + 'static,
+        K::DynamicType: Default + Eq + std::hash::Hash + Clone,
+    {
+        let (store, mut writer) = reflector::store();
+        writer.apply_watcher_event(&watcher::Event::Init);
+        for object in objects {
+            writer.apply_watcher_event(&watcher::Event::InitApply(object));
+        }
+        writer.apply_watcher_event(&watcher::Event::InitDone);
+        store
+    }
+
+    #[tokio::test]
+    async fn test_league_with_its_results() {
+        let spec: TheLeagueSpec = serde_json::from_value(json!({
+            "maxTeams": 2,
+            "teams": [{ "name": "Tigers" }, { "name": "Sharks" }],
+        }))
+        .unwrap();
+        let mut league = TheLeague::new("premier", spec);
+        league.metadata.namespace = Some("default".to_string());
+        let mut result = GameResult::new(
+            "premier-r1",
+            GameResultSpec::game("Tigers", "Sharks").with_score(2, 1),
+        );
+        result.metadata.namespace = Some("default".to_string());
+
+        let caches = Caches::default();
+        caches.share_leagues(synced(vec![league]));
+        caches.share_results(synced(vec![result]));
+        let response = schema(caches)
+            .execute(
+                r#"{
+                    league(namespace: "default", name: "premier") {
+                        name
+                        teams { name results { scoreHome scoreAway } }
+                        fixtures(round: 1) { home away result { name outcome } }
+                    }
+                }"#,
+            )
+            .await;
+        assert!(response.errors.is_empty(), "{:?}", response.errors);
+        let data = response.data.into_json().unwrap();
+        assert_eq!(data["league"]["name"], "premier");
+        assert_eq!(
+            data["league"]["teams"][0]["results"],
+            json!([{ "scoreHome": 2, "scoreAway": 1 }])
+        );
+        let fixture = &data["league"]["fixtures"][0];
+        assert_eq!(fixture["result"]["name"], "premier-r1");
+        assert_eq!(fixture["result"]["outcome"], "WinnerHomeTeam");
+    }
+}
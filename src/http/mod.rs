@@ -0,0 +1,20 @@
+//! HTTP API served next to the probes of the controller.
+//!
+//! The REST API reads the leagues and their results from the reflector caches of the
+//! reconcilers and answers with their standings, results and calendars; results are submitted
+//! through it as GameResults. The dashboard renders the same data as HTML pages and, with the
+//! `graphql` feature, the GraphQL API serves it on `/api/graphql`. Every route is
+//! authenticated and authorized against the RBAC of the CRDs by [`auth`], rate limited per
+//! client by [`ratelimit`] and opened to other origins by [`cors`].
+
+pub mod auth;
+pub mod calendar;
+pub mod cors;
+pub mod dashboard;
+#[cfg(feature = "graphql")]
+pub mod graphql;
+pub mod openapi;
+pub mod pagination;
+pub mod ratelimit;
+pub mod standings_api;
+pub mod submissions;
@@ -0,0 +1,73 @@
+//! OpenAPI document of the HTTP API, served on `/api/openapi.json` of the probe server so
+//! client SDKs can be generated for scoreboards and scorekeeping apps. Reading it takes the
+//! access of listing the leagues, or `API_ANONYMOUS_READS`.
+
+use crate::http::{calendar, standings_api, submissions};
+
+use axum::Json;
+use utoipa::openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme};
+use utoipa::{Modify, OpenApi};
+
+/// OpenAPI document of the standings and results endpoints
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "TheLeague API"),
+    paths(
+        standings_api::list_leagues,
+        standings_api::get_league_standings,
+        standings_api::list_league_results,
+        calendar::league_schedule,
+        submissions::submit_result,
+    ),
+    modifiers(&BearerAuth)
+)]
+pub struct ApiDoc;
+
+/// Declares the Kubernetes bearer tokens the results are submitted with
+struct BearerAuth;
+
+impl Modify for BearerAuth {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        if let Some(components) = openapi.components.as_mut() {
+            components.add_security_scheme(
+                "bearer",
+                SecurityScheme::Http(HttpBuilder::new().scheme(HttpAuthScheme::Bearer).build()),
+            );
+        }
+    }
+}
+
+/// Serve the OpenAPI document
+pub async fn document() -> Json<utoipa::openapi::OpenApi> {
+    Json(ApiDoc::openapi())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_document_describes_the_api() {
+        let document = serde_json::to_value(ApiDoc::openapi()).unwrap();
+        let paths = document["paths"].as_object().unwrap();
+        assert!(paths.contains_key("/api/v1/leagues"));
+        assert!(paths.contains_key("/api/v1/leagues/{namespace}/{name}/standings"));
+        assert!(paths.contains_key("/api/v1/leagues/{namespace}/{name}/results"));
+        assert!(paths.contains_key("/api/v1/leagues/{namespace}/{name}/schedule.ics"));
+
+        let schemas = &document["components"]["schemas"];
+        for schema in [
+            "LeagueSummary",
+            "LeagueStandings",
+            "LeagueResults",
+            "StandingsRow",
+            "ResultSubmission",
+        ] {
+            assert!(schemas.get(schema).is_some(), "{} is missing", schema);
+        }
+        assert_eq!(
+            document["components"]["securitySchemes"]["bearer"]["scheme"],
+            "bearer"
+        );
+    }
+}
@@ -0,0 +1,140 @@
+//! Pagination, sorting and filtering of the list endpoints of the HTTP API.
+//!
+//! Lists follow the conventions of the Kubernetes API: `limit` caps a page and the `continue`
+//! token of a page fetches the next one. The token is the key of the last item of the page,
+//! so pages stay consistent while the caches change underneath; a token whose item was
+//! deleted in the meantime expires with 410 Gone and the list has to be restarted.
+//! `sortBy` names the field to sort by, descending with a leading `-`.
+
+use axum::http::StatusCode;
+use serde::Deserialize;
+use std::cmp::Ordering;
+use utoipa::IntoParams;
+
+/// Largest page served, whatever the limit asked for
+pub const MAX_LIMIT: usize = 1000;
+
+/// Query of a list endpoint
+#[derive(Deserialize, Debug, Default, Clone, IntoParams)]
+#[serde(rename_all = "camelCase")]
+pub struct ListQuery {
+    /// Most items of the page, at most 1000
+    pub limit: Option<usize>,
+    /// Token of the previous page to continue the list from
+    #[serde(rename = "continue")]
+    pub continue_token: Option<String>,
+    /// Field to sort by, descending with a leading `-`, e.g. `-time`
+    pub sort_by: Option<String>,
+    /// Only the items of this team
+    pub team: Option<String>,
+}
+
+/// Page is a page of a list with the token of the next page, if any.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub continue_token: Option<String>,
+}
+
+/// Sorting of a list by a field, descending if `descending`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SortBy<'a> {
+    pub field: &'a str,
+    pub descending: bool,
+}
+
+impl<'a> SortBy<'a> {
+    /// Sorting of `sort_by`, `default` if None. Fails with 400 Bad Request unless the field
+    /// is one of `fields`.
+    pub fn parse(
+        sort_by: Option<&'a str>,
+        default: &'a str,
+        fields: &[&str],
+    ) -> Result<Self, (StatusCode, String)> {
+        let sort_by = sort_by.unwrap_or(default);
+        let (field, descending) = match sort_by.strip_prefix('-') {
+            Some(field) => (field, true),
+            None => (sort_by, false),
+        };
+        if !fields.contains(&field) {
+            let reason = format!(
+                "cannot sort by '{}', expected one of: {}",
+                field,
+                fields.join(", ")
+            );
+            return Err((StatusCode::BAD_REQUEST, reason));
+        }
+        Ok(SortBy { field, descending })
+    }
+
+    /// `ordering` of two items in this direction
+    pub fn apply(&self, ordering: Ordering) -> Ordering {
+        if self.descending {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    }
+}
+
+/// The page of `items`, already sorted and unique by `key`, after the item of
+/// `continue_token` with at most `limit` items
+pub fn paginate<T>(
+    items: Vec<T>,
+    key: impl Fn(&T) -> &str,
+    limit: Option<usize>,
+    continue_token: Option<&str>,
+) -> Result<Page<T>, (StatusCode, String)> {
+    let start = match continue_token {
+        Some(token) => match items.iter().position(|item| key(item) == token) {
+            Some(last) => last + 1,
+            None => {
+                let reason = "the continue token has expired, restart the list".to_string();
+                return Err((StatusCode::GONE, reason));
+            }
+        },
+        None => 0,
+    };
+    let limit = limit.unwrap_or(MAX_LIMIT).clamp(1, MAX_LIMIT);
+    let more = items.len() > start + limit;
+    let items: Vec<T> = items.into_iter().skip(start).take(limit).collect();
+    let continue_token = more
+        .then(|| items.last().map(|item| key(item).to_string()))
+        .flatten();
+    Ok(Page {
+        items,
+        continue_token,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_paginate_continues_after_the_last_item() {
+        let items = vec!["a", "b", "c", "d", "e"];
+        let first = paginate(items.clone(), |s| *s, Some(2), None).unwrap();
+        assert_eq!(first.items, ["a", "b"]);
+        assert_eq!(first.continue_token.as_deref(), Some("b"));
+
+        let last = paginate(items.clone(), |s| *s, Some(3), Some("b")).unwrap();
+        assert_eq!(last.items, ["c", "d", "e"]);
+        assert_eq!(last.continue_token, None);
+
+        let removed = vec!["a", "c", "d", "e"];
+        let gone = paginate(removed, |s| *s, Some(2), Some("b")).unwrap_err();
+        assert_eq!(gone.0, StatusCode::GONE);
+    }
+
+    #[test]
+    fn test_sort_by() {
+        let fields = ["rank", "points"];
+        let sort_by = SortBy::parse(Some("-points"), "rank", &fields).unwrap();
+        assert_eq!(sort_by.field, "points");
+        assert_eq!(sort_by.apply(1.cmp(&2)), Ordering::Greater);
+        assert_eq!(SortBy::parse(None, "rank", &fields).unwrap().field, "rank");
+        let error = SortBy::parse(Some("name"), "rank", &fields).unwrap_err();
+        assert_eq!(error.0, StatusCode::BAD_REQUEST);
+    }
+}
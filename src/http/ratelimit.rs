@@ -0,0 +1,177 @@
+//! Rate limiting of the HTTP API of the probe server, so a public scoreboard can't overload
+//! the controller or the API server with the reviews of its requests.
+//!
+//! Every client has a token bucket holding up to `API_RATE_BURST` requests, refilled with
+//! `API_RATE_LIMIT` requests per second. Clients are told apart by their IP address: requests
+//! are limited before their token is reviewed, so a token can't pick its own bucket. A request finding the bucket of its client empty is
+//! answered with 429 Too Many Requests and a `Retry-After` header. `API_RATE_LIMIT=0` turns
+//! the limit off. The probes open to anyone aren't limited.
+
+use crate::http::auth;
+
+use axum::extract::{ConnectInfo, Request, State};
+use axum::http::{HeaderValue, StatusCode, header};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use tokio::time::{Duration, Instant};
+
+/// Requests per second of a client, unless `API_RATE_LIMIT` is set
+pub const DEFAULT_RATE: f64 = 10.0;
+
+/// Requests a client can burst, unless `API_RATE_BURST` is set
+pub const DEFAULT_BURST: f64 = 20.0;
+
+/// Clients tracked before the buckets of idle clients are dropped
+const MAX_CLIENTS: usize = 10_000;
+
+/// Bucket of a client
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+/// RateLimiter keeps a token bucket per client, shared by the requests of the probe server.
+#[derive(Clone)]
+pub struct RateLimiter {
+    rate: f64,
+    burst: f64,
+    buckets: Arc<Mutex<HashMap<String, Bucket>>>,
+}
+
+impl RateLimiter {
+    /// Limiter refilling `rate` requests per second into buckets of `burst` requests
+    pub fn new(rate: f64, burst: f64) -> Self {
+        RateLimiter {
+            rate,
+            burst: burst.max(1.0),
+            buckets: Arc::default(),
+        }
+    }
+
+    /// Limiter of `API_RATE_LIMIT` and `API_RATE_BURST`, or None if the limit is off
+    pub fn from_env() -> Option<Self> {
+        let var = |name: &str, default: f64| {
+            std::env::var(name)
+                .ok()
+                .and_then(|s| s.parse::<f64>().ok())
+                .filter(|v| v.is_finite() && *v >= 0.0)
+                .unwrap_or(default)
+        };
+        let rate = var("API_RATE_LIMIT", DEFAULT_RATE);
+        (rate > 0.0).then(|| RateLimiter::new(rate, var("API_RATE_BURST", DEFAULT_BURST)))
+    }
+
+    /// Take a request of `client` from its bucket at `now`, or the time until the bucket holds
+    /// a request again if it is empty
+    pub fn acquire(&self, client: &str, now: Instant) -> Result<(), Duration> {
+        let mut buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+        if buckets.len() >= MAX_CLIENTS && !buckets.contains_key(client) {
+            // Buckets refilled to the burst are those of idle clients, as good as new
+            let (rate, burst) = (self.rate, self.burst);
+            buckets.retain(|_, b| b.tokens + elapsed(b, now) * rate < burst);
+        }
+        let bucket = buckets.entry(client.to_string()).or_insert(Bucket {
+            tokens: self.burst,
+            updated: now,
+        });
+        bucket.tokens = (bucket.tokens + elapsed(bucket, now) * self.rate).min(self.burst);
+        bucket.updated = now;
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - bucket.tokens) / self.rate))
+        }
+    }
+}
+
+fn elapsed(bucket: &Bucket, now: Instant) -> f64 {
+    now.saturating_duration_since(bucket.updated).as_secs_f64()
+}
+
+/// Client of a request: its IP address. Its bearer token isn't authenticated yet, so keying
+/// by it would let a client dodge the limit by sending a new random token with every request.
+fn client(request: &Request) -> String {
+    match request.extensions().get::<ConnectInfo<SocketAddr>>() {
+        Some(ConnectInfo(addr)) => format!("ip:{}", addr.ip()),
+        None => "unknown".to_string(),
+    }
+}
+
+/// Middleware limiting the requests of each client to the league API, GraphQL and dashboard
+pub async fn limit_rate(
+    State(limiter): State<RateLimiter>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let public = auth::required_access(request.method(), request.uri())
+        .is_some_and(|accesses| accesses.is_empty());
+    if public {
+        return next.run(request).await;
+    }
+    match limiter.acquire(&client(&request), Instant::now()) {
+        Ok(()) => next.run(request).await,
+        Err(retry_after) => {
+            let seconds = retry_after.as_secs_f64().ceil().max(1.0) as u64;
+            let mut response =
+                (StatusCode::TOO_MANY_REQUESTS, "rate limit exceeded").into_response();
+            response
+                .headers_mut()
+                .insert(header::RETRY_AFTER, HeaderValue::from(seconds));
+            response
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clients_are_keyed_by_ip_not_token() {
+        let request = |token: &str| {
+            let mut request = Request::builder()
+                .uri("/api/v1/leagues")
+                .header(header::AUTHORIZATION, format!("Bearer {}", token))
+                .body(axum::body::Body::empty())
+                .unwrap();
+            request
+                .extensions_mut()
+                .insert(ConnectInfo(SocketAddr::from(([203, 0, 113, 7], 40000))));
+            request
+        };
+        assert_eq!(client(&request("random-1")), "ip:203.0.113.7");
+        assert_eq!(client(&request("random-1")), client(&request("random-2")));
+    }
+
+    #[test]
+    fn test_acquire_refills_the_bucket_of_each_client() {
+        let limiter = RateLimiter::new(2.0, 3.0);
+        let now = Instant::now();
+        for _ in 0..3 {
+            assert_eq!(limiter.acquire("a", now), Ok(()));
+        }
+        assert_eq!(limiter.acquire("a", now), Err(Duration::from_millis(500)));
+        // Other clients have their own bucket
+        assert_eq!(limiter.acquire("b", now), Ok(()));
+        // Half a second refills a request, but never more than the burst
+        assert_eq!(
+            limiter.acquire("a", now + Duration::from_millis(500)),
+            Ok(())
+        );
+        assert!(
+            limiter
+                .acquire("a", now + Duration::from_millis(500))
+                .is_err()
+        );
+        let later = now + Duration::from_secs(60);
+        for _ in 0..3 {
+            assert_eq!(limiter.acquire("a", later), Ok(()));
+        }
+        assert!(limiter.acquire("a", later).is_err());
+    }
+}
@@ -0,0 +1,718 @@
+//! Leagues, their tables and results for scoreboards, served on `/api/v1/leagues`,
+//! `/api/v1/leagues/{namespace}/{name}/standings` and
+//! `/api/v1/leagues/{namespace}/{name}/results` of the probe server and described in its
+//! OpenAPI document.
+//!
+//! The table is the one the standings controller ranked into the league status, completed
+//! with the goal difference and the recent form of each team from the accepted results. Both
+//! are read from the reflector caches, so serving a scoreboard never queries the API server.
+//! The table and the results are paginated, sorted and filtered by team as described in
+//! [`pagination`].
+
+use crate::api::v1alpha1::game_result_types::{GameResult, GameResultSpec};
+use crate::api::v1alpha1::standing_types::Standing;
+use crate::api::v1alpha1::the_league_types::{
+    LeaguePhase, TheLeague, TheLeagueStatus, WithdrawalPolicy,
+};
+use crate::controller::{Context, gameresult_controller, references, seasons};
+use crate::http::pagination::{self, ListQuery, SortBy};
+use crate::{schedule, standings};
+
+use axum::Json;
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use k8s_openapi::chrono::{DateTime, Utc};
+use kube::ResourceExt;
+use kube::runtime::reflector::ObjectRef;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use utoipa::{IntoParams, ToSchema};
+
+/// Games making up the form of a team
+pub const FORM_GAMES: usize = 5;
+
+/// Fields the table can be sorted by
+pub const STANDINGS_SORT_FIELDS: [&str; 4] = ["rank", "points", "goalDifference", "team"];
+
+/// Fields the results can be sorted by
+pub const RESULTS_SORT_FIELDS: [&str; 2] = ["time", "round"];
+
+/// StandingsRow is one team of the served league table.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq, ToSchema)]
+#[cfg_attr(feature = "graphql", derive(async_graphql::SimpleObject))]
+#[cfg_attr(feature = "graphql", graphql(name = "Standing"))]
+#[serde(rename_all = "camelCase")]
+pub struct StandingsRow {
+    pub rank: u32,
+    pub team: String,
+    pub played: u32,
+    pub wins: u32,
+    pub draws: u32,
+    pub losses: u32,
+    pub goals_for: u32,
+    pub goals_against: u32,
+    pub goal_difference: i64,
+    pub points: u32,
+    /// Form is the outcome of the last [`FORM_GAMES`] games of the team, oldest first, e.g.
+    /// `WWDLW`.
+    pub form: String,
+}
+
+/// LeagueStandings is the served table of a league, sorted by rank.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct LeagueStandings {
+    pub namespace: String,
+    pub name: String,
+    /// Season is the current season of leagues running in seasons.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub season: Option<u32>,
+    pub table: Vec<StandingsRow>,
+    /// Continue is the token of the next page of the table, if any.
+    #[serde(rename = "continue", skip_serializing_if = "Option::is_none")]
+    pub continue_token: Option<String>,
+}
+
+/// LeagueResult is one accepted game of the served results of a league.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct LeagueResult {
+    /// Name is the name of the GameResult.
+    pub name: String,
+    pub round: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub season: Option<u32>,
+    pub home: String,
+    pub away: String,
+    /// ScoreHome and ScoreAway are the final score, unless the game was postponed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub score_home: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub score_away: Option<u32>,
+    /// Time is the kickoff, unless it isn't known yet.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time: Option<DateTime<Utc>>,
+}
+
+/// LeagueResults is a page of the served results of a league.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct LeagueResults {
+    pub namespace: String,
+    pub name: String,
+    pub results: Vec<LeagueResult>,
+    /// Continue is the token of the next page of the results, if any.
+    #[serde(rename = "continue", skip_serializing_if = "Option::is_none")]
+    pub continue_token: Option<String>,
+}
+
+/// LeagueSummary is one league of the served list of leagues.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct LeagueSummary {
+    pub namespace: String,
+    pub name: String,
+    /// Teams is the number of teams in the table.
+    pub teams: usize,
+    pub phase: LeaguePhase,
+    /// GamesPlayed is the number of completed games counted in the table.
+    pub games_played: usize,
+    /// GamesTotal is the number of fixtures of the schedule, for leagues with a schedule.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub games_total: Option<usize>,
+    /// Leader is the team ranked first, once a game has been counted.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub leader: Option<String>,
+}
+
+/// Reason to answer requests with until the caches are synced
+pub fn caches_syncing() -> (StatusCode, String) {
+    (
+        StatusCode::SERVICE_UNAVAILABLE,
+        "the league caches are still syncing".to_string(),
+    )
+}
+
+/// Query of the list of leagues
+#[derive(Deserialize, Debug, IntoParams)]
+pub struct LeaguesQuery {
+    /// Namespace to list the leagues of, all namespaces if omitted
+    pub namespace: Option<String>,
+}
+
+/// Leagues and their progress, e.g. `curl localhost:8080/api/v1/leagues?namespace=default`
+#[utoipa::path(
+    get,
+    path = "/api/v1/leagues",
+    params(LeaguesQuery),
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Leagues by namespace and name", body = [LeagueSummary]),
+        (status = 401, description = "The bearer token is missing or invalid", body = String),
+        (status = 403, description = "The caller may not list TheLeagues", body = String),
+        (status = 503, description = "The caches are still syncing", body = String),
+    )
+)]
+pub async fn list_leagues(
+    State(context): State<Arc<Context>>,
+    Query(query): Query<LeaguesQuery>,
+) -> Result<Json<Vec<LeagueSummary>>, (StatusCode, String)> {
+    let (leagues, results) = context.caches.synced().ok_or_else(caches_syncing)?;
+    Ok(Json(league_summaries(
+        &leagues.state(),
+        &results.state(),
+        query.namespace.as_deref(),
+    )))
+}
+
+/// Table of a league for scoreboards, sorted by `rank`, `points`, `goalDifference` or `team`
+#[utoipa::path(
+    get,
+    path = "/api/v1/leagues/{namespace}/{name}/standings",
+    params(
+        ("namespace" = String, Path, description = "Namespace of the league"),
+        ("name" = String, Path, description = "Name of the league"),
+        ListQuery,
+    ),
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Table of the league", body = LeagueStandings),
+        (status = 401, description = "The bearer token is missing or invalid", body = String),
+        (status = 403, description = "The caller may not get the league", body = String),
+        (status = 400, description = "The table can't be sorted by the field", body = String),
+        (status = 404, description = "The league doesn't exist", body = String),
+        (status = 410, description = "The continue token has expired", body = String),
+        (status = 503, description = "The caches are still syncing", body = String),
+    )
+)]
+pub async fn get_league_standings(
+    State(context): State<Arc<Context>>,
+    Path((namespace, name)): Path<(String, String)>,
+    Query(query): Query<ListQuery>,
+) -> Result<Json<LeagueStandings>, (StatusCode, String)> {
+    let (leagues, results) = context.caches.synced().ok_or_else(caches_syncing)?;
+    let Some(league) = leagues.get(&ObjectRef::new(&name).within(&namespace)) else {
+        let reason = format!("league '{}/{}' not found", namespace, name);
+        return Err((StatusCode::NOT_FOUND, reason));
+    };
+    let standings = league_standings(&league, &results.state());
+    Ok(Json(standings_page(standings, &query)?))
+}
+
+/// Accepted results of a league, most recent first unless sorted by `time` or `round`
+#[utoipa::path(
+    get,
+    path = "/api/v1/leagues/{namespace}/{name}/results",
+    params(
+        ("namespace" = String, Path, description = "Namespace of the league"),
+        ("name" = String, Path, description = "Name of the league"),
+        ListQuery,
+    ),
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Page of the results of the league", body = LeagueResults),
+        (status = 400, description = "The results can't be sorted by the field", body = String),
+        (status = 401, description = "The bearer token is missing or invalid", body = String),
+        (status = 403, description = "The caller may not list GameResults", body = String),
+        (status = 404, description = "The league doesn't exist", body = String),
+        (status = 410, description = "The continue token has expired", body = String),
+        (status = 503, description = "The caches are still syncing", body = String),
+    )
+)]
+pub async fn list_league_results(
+    State(context): State<Arc<Context>>,
+    Path((namespace, name)): Path<(String, String)>,
+    Query(query): Query<ListQuery>,
+) -> Result<Json<LeagueResults>, (StatusCode, String)> {
+    let (leagues, results) = context.caches.synced().ok_or_else(caches_syncing)?;
+    let Some(league) = leagues.get(&ObjectRef::new(&name).within(&namespace)) else {
+        let reason = format!("league '{}/{}' not found", namespace, name);
+        return Err((StatusCode::NOT_FOUND, reason));
+    };
+    let page = results_page(league_results(&league, &results.state()), &query)?;
+    Ok(Json(LeagueResults {
+        namespace,
+        name,
+        results: page.items,
+        continue_token: page.continue_token,
+    }))
+}
+
+/// The page of the table of `standings` asked for by `query`
+pub fn standings_page(
+    mut standings: LeagueStandings,
+    query: &ListQuery,
+) -> Result<LeagueStandings, (StatusCode, String)> {
+    let sort_by = SortBy::parse(query.sort_by.as_deref(), "rank", &STANDINGS_SORT_FIELDS)?;
+    let mut table = std::mem::take(&mut standings.table);
+    table.retain(|row| query.team.as_ref().is_none_or(|team| &row.team == team));
+    table.sort_by(|a, b| {
+        let ordering = match sort_by.field {
+            "points" => a.points.cmp(&b.points),
+            "goalDifference" => a.goal_difference.cmp(&b.goal_difference),
+            "team" => a.team.cmp(&b.team),
+            _ => a.rank.cmp(&b.rank),
+        };
+        sort_by.apply(ordering).then_with(|| a.team.cmp(&b.team))
+    });
+    let page = pagination::paginate(
+        table,
+        |row| &row.team,
+        query.limit,
+        query.continue_token.as_deref(),
+    )?;
+    standings.table = page.items;
+    standings.continue_token = page.continue_token;
+    Ok(standings)
+}
+
+/// The page of `results` asked for by `query`, most recent first by default
+pub fn results_page(
+    mut results: Vec<LeagueResult>,
+    query: &ListQuery,
+) -> Result<pagination::Page<LeagueResult>, (StatusCode, String)> {
+    let sort_by = SortBy::parse(query.sort_by.as_deref(), "-time", &RESULTS_SORT_FIELDS)?;
+    results.retain(|r| {
+        query
+            .team
+            .as_ref()
+            .is_none_or(|team| &r.home == team || &r.away == team)
+    });
+    results.sort_by(|a, b| {
+        let ordering = match sort_by.field {
+            "round" => (a.season, a.round).cmp(&(b.season, b.round)),
+            _ => a.time.cmp(&b.time),
+        };
+        sort_by.apply(ordering).then_with(|| a.name.cmp(&b.name))
+    });
+    pagination::paginate(
+        results,
+        |r| &r.name,
+        query.limit,
+        query.continue_token.as_deref(),
+    )
+}
+
+/// Accepted results of `league` in `results`, which may contain the results of any league,
+/// over all its seasons
+pub fn league_results(league: &TheLeague, results: &[Arc<GameResult>]) -> Vec<LeagueResult> {
+    accepted_results(league, results)
+        .map(|r| {
+            let scores = standings::outcome_scores(&r.spec.result);
+            let [home, away] = r.spec.teams.clone();
+            LeagueResult {
+                name: r.name_any(),
+                round: r.round(),
+                season: r.spec.season,
+                home,
+                away,
+                score_home: scores.map(|(home, _)| home),
+                score_away: scores.map(|(_, away)| away),
+                time: r.spec.time.as_ref().map(|t| t.0),
+            }
+        })
+        .collect()
+}
+
+/// Summaries of `leagues`, or of those in `namespace` if given, sorted by namespace and name.
+/// Their games are counted in `results`, which may contain the results of any league.
+pub fn league_summaries(
+    leagues: &[Arc<TheLeague>],
+    results: &[Arc<GameResult>],
+    namespace: Option<&str>,
+) -> Vec<LeagueSummary> {
+    let mut summaries: Vec<LeagueSummary> = leagues
+        .iter()
+        .filter(|l| namespace.is_none_or(|ns| l.namespace().as_deref() == Some(ns)))
+        .map(|league| {
+            let status = league.status.clone().unwrap_or_default();
+            let games_played = counted_results(league, &status, results)
+                .into_iter()
+                .filter(|spec| standings::outcome_scores(&spec.result).is_some())
+                .count();
+            let schedule = schedule::league_schedule(&league.spec).unwrap_or_default();
+            let leader = status
+                .table
+                .iter()
+                .find(|row| row.rank == 1 && status.phase != LeaguePhase::Pending)
+                .map(|row| row.team.clone());
+            LeagueSummary {
+                namespace: league.namespace().unwrap_or_default(),
+                name: league.name_any(),
+                teams: status.table.len(),
+                phase: status.phase,
+                games_played,
+                games_total: (schedule.round_count() > 0).then(|| schedule.fixtures().count()),
+                leader,
+            }
+        })
+        .collect();
+    summaries.sort_by(|a, b| (&a.namespace, &a.name).cmp(&(&b.namespace, &b.name)));
+    summaries
+}
+
+/// Table of `league` with the form of its teams in `results`, which may contain the results
+/// of any league; only the accepted league games of its current season count.
+pub fn league_standings(league: &TheLeague, results: &[Arc<GameResult>]) -> LeagueStandings {
+    let status = league.status.clone().unwrap_or_default();
+    let specs = counted_results(league, &status, results);
+
+    let mut table: Vec<StandingsRow> = status
+        .table
+        .iter()
+        .map(|row| StandingsRow {
+            rank: row.rank,
+            team: row.team.clone(),
+            played: row.played,
+            wins: row.wins,
+            draws: row.draws,
+            losses: row.losses,
+            goals_for: row.goals_for,
+            goals_against: row.goals_against,
+            goal_difference: i64::from(row.goals_for) - i64::from(row.goals_against),
+            points: row.points,
+            form: standings::team_form(&row.team, &specs, FORM_GAMES),
+        })
+        .collect();
+    table.sort_by_key(|row| row.rank);
+
+    LeagueStandings {
+        namespace: league.namespace().unwrap_or_default(),
+        name: league.name_any(),
+        season: status.current_season,
+        table,
+        continue_token: None,
+    }
+}
+
+/// Table of `league` from the statuses of `standings`, which may contain the Standings of any
+/// league, with the form of its teams in `results`. Standings not ranked yet are left out.
+pub fn standings_from_statuses(
+    league: &TheLeague,
+    standings: &[Standing],
+    results: &[Arc<GameResult>],
+) -> LeagueStandings {
+    let status = league.status.clone().unwrap_or_default();
+    let specs = counted_results(league, &status, results);
+    let namespace = league.namespace().unwrap_or_default();
+    let name = league.name_any();
+
+    let mut table: Vec<StandingsRow> = standings
+        .iter()
+        .filter(|s| {
+            references::league_namespace(s.namespace(), &s.spec.league_namespace) == namespace
+                && s.spec.league_name == name
+        })
+        .filter_map(|s| {
+            let standing = s.status.as_ref().filter(|status| status.rank > 0)?;
+            Some(StandingsRow {
+                rank: standing.rank,
+                team: s.spec.team_name.clone(),
+                played: standing.played,
+                wins: standing.wins,
+                draws: standing.draws,
+                losses: standing.losses,
+                goals_for: standing.goals_for,
+                goals_against: standing.goals_against,
+                goal_difference: i64::from(standing.goals_for) - i64::from(standing.goals_against),
+                points: standing.points,
+                form: standings::team_form(&s.spec.team_name, &specs, FORM_GAMES),
+            })
+        })
+        .collect();
+    table.sort_by_key(|row| row.rank);
+
+    LeagueStandings {
+        namespace,
+        name,
+        season: status.current_season,
+        table,
+        continue_token: None,
+    }
+}
+
+/// Results in `results` counted in the table of `league`: its accepted league games of the
+/// current season, between teams of the table
+pub fn counted_results<'a>(
+    league: &TheLeague,
+    status: &TheLeagueStatus,
+    results: &'a [Arc<GameResult>],
+) -> Vec<&'a GameResultSpec> {
+    // Games against withdrawn teams only count if the league keeps their results.
+    let counts = |team: &String| {
+        status.table.iter().any(|row| &row.team == team)
+            || (league.spec.withdrawal_policy == WithdrawalPolicy::KeepResults
+                && status.withdrawn_teams.contains(team))
+    };
+    accepted_results(league, results)
+        .map(|r| &r.spec)
+        .filter(|spec| spec.is_league_game())
+        .filter(|spec| {
+            status
+                .current_season
+                .is_none_or(|s| seasons::counted_season(spec) == s)
+        })
+        .filter(|spec| spec.teams.iter().all(counts))
+        .collect()
+}
+
+/// Accepted results of `league` in `results`
+fn accepted_results<'a>(
+    league: &TheLeague,
+    results: &'a [Arc<GameResult>],
+) -> impl Iterator<Item = &'a Arc<GameResult>> {
+    let namespace = league.namespace().unwrap_or_default();
+    let name = league.name_any();
+    results.iter().filter(move |r| {
+        references::league_namespace(r.namespace(), &r.spec.league_namespace) == namespace
+            && r.spec.league_name == name
+            && gameresult_controller::is_accepted(r)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::v1alpha1::standing_types::{StandingSpec, StandingStatus};
+    use crate::api::v1alpha1::the_league_types::{TableRow, TheLeagueSpec, TheLeagueStatus};
+    use k8s_openapi::apimachinery::pkg::apis::meta::v1::Time;
+    use k8s_openapi::chrono::Utc;
+    use serde_json::json;
+
+    fn result(name: &str, teams: [&str; 2], score: (u32, u32), accepted: bool) -> Arc<GameResult> {
+        let spec = GameResultSpec::game(teams[0], teams[1]).with_score(score.0, score.1);
+        let mut result = GameResult::new(name, spec);
+        result.metadata.namespace = Some("default".to_string());
+        result.status = serde_json::from_value(json!({
+            "conditions": [{
+                "type": "Rejected",
+                "status": if accepted { "False" } else { "True" },
+                "reason": "Validated",
+                "message": "",
+                "lastTransitionTime": "2024-08-17T17:00:00Z",
+            }],
+        }))
+        .unwrap();
+        Arc::new(result)
+    }
+
+    fn row(team: &str, rank: u32, wins: u32, draws: u32, goals: (u32, u32)) -> TableRow {
+        TableRow {
+            rank,
+            team: team.to_string(),
+            played: wins + draws,
+            wins,
+            draws,
+            goals_for: goals.0,
+            goals_against: goals.1,
+            points: wins * 3 + draws,
+            ..Default::default()
+        }
+    }
+
+    fn league(namespace: &str, name: &str, phase: LeaguePhase, table: Vec<TableRow>) -> TheLeague {
+        let spec: TheLeagueSpec =
+            serde_json::from_value(json!({ "maxTeams": 2, "teams": [] })).unwrap();
+        let mut league = TheLeague::new(name, spec);
+        league.metadata.namespace = Some(namespace.to_string());
+        league.status = Some(TheLeagueStatus {
+            phase,
+            table,
+            ..Default::default()
+        });
+        league
+    }
+
+    #[test]
+    fn test_league_standings_from_the_caches() {
+        let table = vec![
+            row("Sharks", 2, 0, 1, (1, 1)),
+            row("Tigers", 1, 1, 1, (3, 1)),
+        ];
+        let league = league("default", "premier", LeaguePhase::InProgress, table);
+        let results = [
+            result("first", ["Tigers", "Sharks"], (1, 1), true),
+            result("second", ["Tigers", "Eagles"], (2, 0), true),
+            result("rejected", ["Sharks", "Tigers"], (5, 0), false),
+        ];
+
+        let standings = league_standings(&league, &results);
+        assert_eq!(standings.name, "premier");
+        let teams: Vec<_> = standings
+            .table
+            .iter()
+            .map(|r| (r.rank, r.team.as_str(), r.goal_difference, r.form.as_str()))
+            .collect();
+        // Eagles aren't in the table, their game doesn't count for the form.
+        assert_eq!(teams, [(1, "Tigers", 2, "D"), (2, "Sharks", 0, "D")]);
+    }
+
+    #[test]
+    fn test_standings_from_statuses() {
+        let table = vec![
+            row("Tigers", 1, 1, 0, (2, 0)),
+            row("Sharks", 2, 0, 0, (0, 2)),
+        ];
+        let league = league("default", "premier", LeaguePhase::InProgress, table);
+        let standing = |team: &str, league_name: &str, status: Option<StandingStatus>| {
+            let mut standing = Standing::new(
+                &format!("{}-{}", league_name, team.to_lowercase()),
+                StandingSpec {
+                    league_name: league_name.to_string(),
+                    league_namespace: None,
+                    team_name: team.to_string(),
+                    resolution: Default::default(),
+                },
+            );
+            standing.metadata.namespace = Some("default".to_string());
+            standing.status = status;
+            standing
+        };
+        let ranked = |rank: u32, wins: u32, goals: (u32, u32)| StandingStatus {
+            rank,
+            played: 1,
+            points: wins * 3,
+            wins,
+            losses: 1 - wins,
+            goals_for: goals.0,
+            goals_against: goals.1,
+            ..Default::default()
+        };
+        let standings = [
+            standing("Sharks", "premier", Some(ranked(2, 0, (0, 2)))),
+            standing("Tigers", "premier", Some(ranked(1, 1, (2, 0)))),
+            standing("Eagles", "premier", None),
+            standing("Tigers", "cup", Some(ranked(1, 1, (9, 0)))),
+        ];
+        let results = [result("first", ["Tigers", "Sharks"], (2, 0), true)];
+
+        let table = standings_from_statuses(&league, &standings, &results).table;
+        let teams: Vec<_> = table
+            .iter()
+            .map(|r| (r.rank, r.team.as_str(), r.goal_difference, r.form.as_str()))
+            .collect();
+        assert_eq!(teams, [(1, "Tigers", 2, "W"), (2, "Sharks", -2, "L")]);
+    }
+
+    #[test]
+    fn test_league_summaries_per_namespace() {
+        let table = vec![
+            row("Tigers", 1, 0, 1, (1, 1)),
+            row("Sharks", 2, 0, 1, (1, 1)),
+        ];
+        let leagues = [
+            Arc::new(league("default", "premier", LeaguePhase::InProgress, table)),
+            Arc::new(league("default", "cup", LeaguePhase::Pending, Vec::new())),
+            Arc::new(league(
+                "amateurs",
+                "sunday",
+                LeaguePhase::Pending,
+                Vec::new(),
+            )),
+        ];
+        let results = [
+            result("first", ["Tigers", "Sharks"], (1, 1), true),
+            result("rejected", ["Sharks", "Tigers"], (5, 0), false),
+        ];
+
+        let names = |summaries: &[LeagueSummary]| -> Vec<String> {
+            summaries
+                .iter()
+                .map(|s| format!("{}/{}", s.namespace, s.name))
+                .collect()
+        };
+        let all = league_summaries(&leagues, &results, None);
+        assert_eq!(
+            names(&all),
+            ["amateurs/sunday", "default/cup", "default/premier"]
+        );
+        let default = league_summaries(&leagues, &results, Some("default"));
+        assert_eq!(names(&default), ["default/cup", "default/premier"]);
+
+        let premier = &default[1];
+        assert_eq!(premier.teams, 2);
+        assert_eq!(premier.games_played, 1);
+        assert_eq!(premier.leader.as_deref(), Some("Tigers"));
+        assert_eq!(default[0].leader, None);
+    }
+
+    #[test]
+    fn test_standings_page_sorts_and_filters_the_table() {
+        let table = vec![
+            row("Tigers", 1, 2, 0, (5, 1)),
+            row("Sharks", 2, 1, 1, (2, 2)),
+            row("Eagles", 3, 0, 1, (0, 4)),
+        ];
+        let league = league("default", "premier", LeaguePhase::InProgress, table);
+        let standings = league_standings(&league, &[]);
+        let teams = |standings: &LeagueStandings| -> Vec<String> {
+            standings.table.iter().map(|r| r.team.clone()).collect()
+        };
+
+        let query = ListQuery {
+            limit: Some(2),
+            sort_by: Some("-goalDifference".to_string()),
+            ..Default::default()
+        };
+        let first = standings_page(standings.clone(), &query).unwrap();
+        assert_eq!(teams(&first), ["Tigers", "Sharks"]);
+        assert_eq!(first.continue_token.as_deref(), Some("Sharks"));
+        let query = ListQuery {
+            continue_token: first.continue_token,
+            ..query
+        };
+        let second = standings_page(standings.clone(), &query).unwrap();
+        assert_eq!(teams(&second), ["Eagles"]);
+        assert_eq!(second.continue_token, None);
+
+        let query = ListQuery {
+            team: Some("Sharks".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            teams(&standings_page(standings, &query).unwrap()),
+            ["Sharks"]
+        );
+    }
+
+    #[test]
+    fn test_results_page_most_recent_first() {
+        let table = vec![
+            row("Tigers", 1, 1, 0, (2, 0)),
+            row("Sharks", 2, 0, 1, (1, 1)),
+        ];
+        let league = league("default", "premier", LeaguePhase::InProgress, table);
+        let mut later = (*result("later", ["Sharks", "Eagles"], (1, 1), true)).clone();
+        later.spec.time = Some(Time(Utc::now() + k8s_openapi::chrono::Duration::hours(1)));
+        let results = [
+            result("earlier", ["Tigers", "Sharks"], (2, 0), true),
+            Arc::new(later),
+            result("rejected", ["Sharks", "Tigers"], (5, 0), false),
+        ];
+        let names = |page: &pagination::Page<LeagueResult>| -> Vec<String> {
+            page.items.iter().map(|r| r.name.clone()).collect()
+        };
+
+        let all = league_results(&league, &results);
+        let page = results_page(all.clone(), &ListQuery::default()).unwrap();
+        assert_eq!(names(&page), ["later", "earlier"]);
+        assert_eq!(page.items[1].score_home, Some(2));
+
+        let query = ListQuery {
+            team: Some("Tigers".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            names(&results_page(all.clone(), &query).unwrap()),
+            ["earlier"]
+        );
+        let query = ListQuery {
+            sort_by: Some("name".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            results_page(all, &query).unwrap_err().0,
+            StatusCode::BAD_REQUEST
+        );
+    }
+}
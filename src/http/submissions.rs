@@ -0,0 +1,217 @@
+//! Submission of results over HTTP, served on `POST /api/v1/leagues/{namespace}/{name}/results`
+//! of the probe server so scorekeepers can post results from an app without kubectl.
+//!
+//! The caller is authenticated and authorized by the [`auth`](crate::http::auth)
+//! middleware, so submitting a result requires the same RBAC as creating the GameResult with
+//! kubectl. The GameResult is then created with the controller's client.
+
+use crate::api::v1alpha1::game_result_types::{
+    DecidedBy, GameResult, GameResultSpec, ShootoutScore,
+};
+use crate::api::v1alpha1::the_league_types::TheLeague;
+use crate::controller::{Context, post_params};
+use crate::http::standings_api;
+use crate::{import, schedule, validation};
+
+use axum::Json;
+use axum::extract::{Extension, Path, State};
+use axum::http::StatusCode;
+use k8s_openapi::api::authentication::v1::UserInfo;
+use k8s_openapi::chrono::{DateTime, Utc};
+use kube::runtime::reflector::ObjectRef;
+use kube::{Api, Resource};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use tracing::info;
+use utoipa::ToSchema;
+
+/// Annotation recording the user who submitted a result over HTTP
+pub const SUBMITTED_BY_ANNOTATION: &str = "bexxmodd.com/submitted-by";
+
+/// ResultSubmission is the payload of a submitted result.
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ResultSubmission {
+    pub round_number: u32,
+    pub home: String,
+    pub away: String,
+    pub score_home: u32,
+    pub score_away: u32,
+    /// DecidedBy is how a game that wasn't drawn was decided; regulation time by default.
+    #[serde(default)]
+    pub decided_by: DecidedBy,
+    /// Shootout is the score of the shootout of a game decided by one.
+    #[serde(default)]
+    pub shootout: Option<ShootoutScore>,
+    /// Time is when the game was played; the time of the submission by default.
+    #[serde(default)]
+    pub time: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub attendance: Option<u32>,
+}
+
+impl ResultSubmission {
+    /// Spec of the GameResult of the submission in the league `league_name`, played at `now`
+    /// unless the submission gives its time. Built like the results of a CSV import.
+    pub fn to_spec(&self, league_name: &str, now: DateTime<Utc>) -> GameResultSpec {
+        let result = import::outcome(
+            self.score_home,
+            self.score_away,
+            self.decided_by,
+            self.shootout.clone(),
+        );
+        GameResultSpec {
+            attendance: self.attendance,
+            ..import::result_spec(
+                league_name,
+                self.round_number,
+                [self.home.clone(), self.away.clone()],
+                result,
+                self.time.unwrap_or(now),
+            )
+        }
+    }
+}
+
+/// Result submitted by a scorekeeper, authenticated with a Kubernetes bearer token
+#[utoipa::path(
+    post,
+    path = "/api/v1/leagues/{namespace}/{name}/results",
+    params(
+        ("namespace" = String, Path, description = "Namespace of the league"),
+        ("name" = String, Path, description = "Name of the league"),
+    ),
+    request_body = ResultSubmission,
+    security(("bearer" = [])),
+    responses(
+        (status = 201, description = "The created GameResult", content_type = "application/json"),
+        (status = 401, description = "The bearer token is missing or invalid", body = String),
+        (status = 403, description = "The caller may not create GameResults", body = String),
+        (status = 404, description = "The league doesn't exist", body = String),
+        (status = 409, description = "The result of the game exists already", body = String),
+        (status = 422, description = "The result is invalid for the league", body = String),
+    )
+)]
+pub async fn submit_result(
+    State(context): State<Arc<Context>>,
+    Path((namespace, name)): Path<(String, String)>,
+    Extension(user): Extension<UserInfo>,
+    Json(submission): Json<ResultSubmission>,
+) -> Result<(StatusCode, Json<GameResult>), (StatusCode, String)> {
+    let username = user.username.unwrap_or_default();
+    let created = submit(&context, &namespace, &name, &username, &submission).await?;
+    Ok((StatusCode::CREATED, Json(created)))
+}
+
+/// Create the GameResult of `submission` in the league `namespace/name` on behalf of the
+/// authorized user `username`. Fails with the HTTP status and reason to answer the request
+/// with.
+pub async fn submit(
+    ctx: &Context,
+    namespace: &str,
+    name: &str,
+    username: &str,
+    submission: &ResultSubmission,
+) -> Result<GameResult, (StatusCode, String)> {
+    let league = ctx
+        .caches
+        .leagues()
+        .ok_or_else(standings_api::caches_syncing)?
+        .get(&ObjectRef::<TheLeague>::new(name).within(namespace))
+        .ok_or_else(|| {
+            let reason = format!("league '{}/{}' not found", namespace, name);
+            (StatusCode::NOT_FOUND, reason)
+        })?;
+    let spec = submission.to_spec(name, Utc::now());
+    let violations = validation::validate_game_result(&spec, &league.spec);
+    if let Some((reason, message)) = validation::summarize(&violations) {
+        return Err((
+            StatusCode::UNPROCESSABLE_ENTITY,
+            format!("{}: {}", reason, message),
+        ));
+    }
+
+    let result_name =
+        schedule::result_name(name, spec.round_number, &spec.teams[0], &spec.teams[1]);
+    let mut result = GameResult::new(&result_name, spec);
+    result.meta_mut().namespace = Some(namespace.to_string());
+    result.meta_mut().annotations = Some(BTreeMap::from([(
+        SUBMITTED_BY_ANNOTATION.to_string(),
+        username.to_string(),
+    )]));
+    let result_api: Api<GameResult> = Api::namespaced(ctx.client.clone(), namespace);
+    let created = result_api
+        .create(&post_params(), &result)
+        .await
+        .map_err(|e| match e {
+            kube::Error::Api(response) => (
+                StatusCode::from_u16(response.code).unwrap_or(StatusCode::BAD_GATEWAY),
+                response.message,
+            ),
+            e => (StatusCode::BAD_GATEWAY, e.to_string()),
+        })?;
+    info!(
+        "Created GameResult '{}/{}' submitted by '{}'",
+        namespace, result_name, username
+    );
+    Ok(created)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::v1alpha1::game_result_types::GameOutcome;
+    use k8s_openapi::apimachinery::pkg::apis::meta::v1::Time;
+
+    #[test]
+    fn test_submission_to_spec() {
+        let submission: ResultSubmission = serde_json::from_value(serde_json::json!({
+            "roundNumber": 3,
+            "home": "Tigers",
+            "away": "Sharks",
+            "scoreHome": 1,
+            "scoreAway": 2,
+        }))
+        .unwrap();
+        let now = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let spec = submission.to_spec("premier", now);
+        assert_eq!(spec.league_name, "premier");
+        assert_eq!(spec.round_number, 3);
+        assert_eq!(spec.teams, ["Tigers".to_string(), "Sharks".to_string()]);
+        assert_eq!(spec.time, Some(Time(now)));
+        assert!(matches!(
+            spec.result,
+            GameOutcome::WinnerAwayTeam {
+                score_home: 1,
+                score_away: 2,
+                decided_by: DecidedBy::Regulation,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_shootout_submission_with_a_level_score() {
+        let submission: ResultSubmission = serde_json::from_value(serde_json::json!({
+            "roundNumber": 3,
+            "home": "Tigers",
+            "away": "Sharks",
+            "scoreHome": 2,
+            "scoreAway": 2,
+            "decidedBy": "Shootout",
+            "shootout": { "home": 3, "away": 4 },
+        }))
+        .unwrap();
+        let now = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        assert!(matches!(
+            submission.to_spec("premier", now).result,
+            GameOutcome::WinnerAwayTeam {
+                score_home: 2,
+                score_away: 2,
+                decided_by: DecidedBy::Shootout,
+                shootout: Some(ShootoutScore { home: 3, away: 4 }),
+            }
+        ));
+    }
+}
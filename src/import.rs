@@ -0,0 +1,363 @@
+//! Bulk import of GameResults from CSV.
+//!
+//! The expected format is one game per line: `round,home,away,home_score,away_score,time`,
+//! with `time` in RFC3339. A leading header line starting with `round` is skipped.
+
+use crate::api::v1alpha1::game_result_types::{
+    DecidedBy, GameOutcome, GameResult, GameResultSpec, PlayerStat, ShootoutScore,
+};
+use crate::api::v1alpha1::the_league_types::TheLeagueSpec;
+use crate::schedule;
+use crate::validation;
+
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::Time;
+use k8s_openapi::chrono::{DateTime, Utc};
+use kube::api::{Patch, PatchParams};
+use kube::{Api, Client, ResourceExt};
+use std::cmp::Ordering;
+use std::fmt;
+
+/// Field manager used by the importer for server-side apply
+pub const IMPORT_FIELD_MANAGER: &str = "leaguectl";
+
+/// Number of GameResults applied concurrently
+pub const IMPORT_BATCH_SIZE: usize = 20;
+
+/// RowError is a problem with a single CSV line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RowError {
+    /// Line is the 1-based line number in the input.
+    pub line: usize,
+
+    /// Message explains what is wrong with the line.
+    pub message: String,
+}
+
+impl fmt::Display for RowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+/// ParsedRow is a GameResult built from a CSV line.
+#[derive(Debug, Clone)]
+pub struct ParsedRow {
+    /// Line is the 1-based line number in the input.
+    pub line: usize,
+
+    /// Result is the GameResult to create.
+    pub result: GameResult,
+}
+
+/// Parse the CSV `input` into GameResults for `league_name`.
+///
+/// Lines that fail to parse, or fail validation against `league` when given, are reported as
+/// errors; the remaining lines are returned so a partially broken file can still be imported.
+pub fn parse_results_csv(
+    input: &str,
+    league_name: &str,
+    league: Option<&TheLeagueSpec>,
+) -> (Vec<ParsedRow>, Vec<RowError>) {
+    let mut rows = Vec::new();
+    let mut errors = Vec::new();
+
+    for (index, raw) in input.lines().enumerate() {
+        let line = index + 1;
+        let raw = raw.trim();
+        if raw.is_empty() || raw.starts_with('#') || (line == 1 && raw.starts_with("round")) {
+            continue;
+        }
+        let spec = match parse_row(raw, league_name) {
+            Ok(spec) => spec,
+            Err(message) => {
+                errors.push(RowError { line, message });
+                continue;
+            }
+        };
+        let violations = match league {
+            Some(league) => validation::validate_game_result(&spec, league),
+            None => validation::validate_result_consistency(&spec),
+        };
+        if let Some((reason, message)) = validation::summarize(&violations) {
+            errors.push(RowError {
+                line,
+                message: format!("{}: {}", reason, message),
+            });
+            continue;
+        }
+
+        let [home, away] = &spec.teams;
+        let name = schedule::result_name(league_name, spec.round_number, home, away);
+        rows.push(ParsedRow {
+            line,
+            result: GameResult::new(&name, spec),
+        });
+    }
+
+    (rows, errors)
+}
+
+fn parse_row(raw: &str, league_name: &str) -> Result<GameResultSpec, String> {
+    let fields: Vec<&str> = raw.split(',').map(str::trim).collect();
+    let [round, home, away, home_score, away_score, time] = fields[..] else {
+        return Err(format!(
+            "expected 6 fields (round,home,away,home_score,away_score,time), got {}",
+            fields.len()
+        ));
+    };
+
+    let number = |field: &str, value: &str| {
+        value
+            .parse::<u32>()
+            .map_err(|_| format!("{} '{}' is not a non-negative number", field, value))
+    };
+    let round_number = number("round", round)?;
+    let score_home = number("home_score", home_score)?;
+    let score_away = number("away_score", away_score)?;
+    let time = parse_time(time)?;
+    Ok(result_spec(
+        league_name,
+        round_number,
+        [home.to_string(), away.to_string()],
+        outcome(score_home, score_away, DecidedBy::Regulation, None),
+        time,
+    ))
+}
+
+/// The outcome of a game ending `score_home`-`score_away` as `decided_by`. Shootouts are won
+/// by the team leading the `shootout`, other games by the team with the higher score.
+pub fn outcome(
+    score_home: u32,
+    score_away: u32,
+    decided_by: DecidedBy,
+    shootout: Option<ShootoutScore>,
+) -> GameOutcome {
+    let lead = match (&decided_by, &shootout) {
+        (DecidedBy::Shootout, Some(shootout)) => shootout.home.cmp(&shootout.away),
+        _ => score_home.cmp(&score_away),
+    };
+    match lead {
+        Ordering::Greater => GameOutcome::WinnerHomeTeam {
+            score_home,
+            score_away,
+            decided_by,
+            shootout,
+        },
+        Ordering::Less => GameOutcome::WinnerAwayTeam {
+            score_home,
+            score_away,
+            decided_by,
+            shootout,
+        },
+        Ordering::Equal if decided_by == DecidedBy::Regulation => {
+            GameOutcome::Draw { score: score_home }
+        }
+        // Left for the validation to report: no team won the overtime or the shootout
+        Ordering::Equal => GameOutcome::WinnerHomeTeam {
+            score_home,
+            score_away,
+            decided_by,
+            shootout,
+        },
+    }
+}
+
+/// The spec of a league game of `league_name` between `teams` in `round_number`
+pub fn result_spec(
+    league_name: &str,
+    round_number: u32,
+    teams: [String; 2],
+    result: GameOutcome,
+    time: DateTime<Utc>,
+) -> GameResultSpec {
+    GameResultSpec {
+        league_name: league_name.to_string(),
+        league_namespace: None,
+        round_number,
+        season: None,
+        playoff: None,
+        tournament: None,
+        teams,
+        time: Some(Time(time)),
+        originally_scheduled_at: None,
+        result,
+        scorers: Vec::new(),
+        assists: Vec::new(),
+        cards: Vec::new(),
+        venue: None,
+        attendance: None,
+    }
+}
+
+/// Parse the RFC3339 `time`
+pub fn parse_time(time: &str) -> Result<DateTime<Utc>, String> {
+    DateTime::parse_from_rfc3339(time)
+        .map(|t| t.with_timezone(&Utc))
+        .map_err(|e| format!("time '{}' is not RFC3339: {}", time, e))
+}
+
+/// Parse the score `home-away`, e.g. `2-1`
+pub fn parse_score(score: &str) -> Result<(u32, u32), String> {
+    let parse = |goals: &str| goals.trim().parse::<u32>().ok();
+    match score.split_once('-') {
+        Some((home, away)) => parse(home).zip(parse(away)),
+        None => None,
+    }
+    .ok_or_else(|| format!("score '{}' is not of the form home-away, e.g. 2-1", score))
+}
+
+/// Parse the player `Team:First Last`, optionally followed by `:minute`
+pub fn parse_player_stat(player: &str) -> Result<PlayerStat, String> {
+    let invalid = || {
+        format!(
+            "player '{}' is not of the form Team:First Last[:minute]",
+            player
+        )
+    };
+    let mut fields = player.split(':').map(str::trim);
+    let (Some(team), Some(name)) = (fields.next(), fields.next()) else {
+        return Err(invalid());
+    };
+    let minute = match fields.next() {
+        Some(minute) => Some(minute.parse::<u32>().map_err(|_| invalid())?),
+        None => None,
+    };
+    let Some((first_name, last_name)) = name.split_once(' ') else {
+        return Err(invalid());
+    };
+    if team.is_empty() || fields.next().is_some() {
+        return Err(invalid());
+    }
+    Ok(PlayerStat {
+        team: team.to_string(),
+        first_name: first_name.to_string(),
+        last_name: last_name.trim().to_string(),
+        minute,
+    })
+}
+
+/// Create or update the parsed GameResults in `namespace` with server-side apply,
+/// `IMPORT_BATCH_SIZE` at a time. Returns the rows that failed with the API error.
+pub async fn apply_results(
+    client: &Client,
+    namespace: &str,
+    rows: &[ParsedRow],
+    dry_run: bool,
+) -> Vec<RowError> {
+    let api: Api<GameResult> = Api::namespaced(client.clone(), namespace);
+    let mut params = PatchParams::apply(IMPORT_FIELD_MANAGER);
+    params.dry_run = dry_run;
+
+    let mut errors = Vec::new();
+    for batch in rows.chunks(IMPORT_BATCH_SIZE) {
+        let applied = futures::future::join_all(batch.iter().map(|row| {
+            let (api, params) = (&api, &params);
+            async move {
+                api.patch(&row.result.name_any(), params, &Patch::Apply(&row.result))
+                    .await
+            }
+        }))
+        .await;
+        for (row, outcome) in batch.iter().zip(applied) {
+            if let Err(e) = outcome {
+                errors.push(RowError {
+                    line: row.line,
+                    message: e.to_string(),
+                });
+            }
+        }
+    }
+    errors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn league(teams: &[&str]) -> TheLeagueSpec {
+        TheLeagueSpec::with_teams(teams).lenient()
+    }
+
+    #[test]
+    fn test_parse_results_csv() {
+        let input = "round,home,away,home_score,away_score,time\n\
+                     1,Tigers,Sharks,2,1,2025-03-01T15:00:00Z\n\
+                     \n\
+                     1,Eagles,Bears,0,0,2025-03-01T17:00:00+01:00\n";
+        let (rows, errors) = parse_results_csv(input, "premier", None);
+
+        assert!(errors.is_empty());
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].line, 2);
+        assert_eq!(
+            rows[0].result.name_any(),
+            "premier-round-1-tigers-vs-sharks"
+        );
+        assert!(matches!(
+            rows[0].result.spec.result,
+            GameOutcome::WinnerHomeTeam { score_home: 2, .. }
+        ));
+        assert!(matches!(
+            rows[1].result.spec.result,
+            GameOutcome::Draw { score: 0 }
+        ));
+    }
+
+    #[test]
+    fn test_parse_results_csv_reports_row_errors() {
+        let input = "1,Tigers,Sharks,2\n\
+                     x,Tigers,Sharks,2,1,2025-03-01T15:00:00Z\n\
+                     1,Tigers,Sharks,2,1,yesterday\n\
+                     1,Tigers,Ghosts,2,1,2025-03-01T15:00:00Z\n\
+                     1,Tigers,Sharks,2,1,2025-03-01T15:00:00Z\n";
+        let (rows, errors) =
+            parse_results_csv(input, "premier", Some(&league(&["Tigers", "Sharks"])));
+
+        assert_eq!(rows.len(), 1);
+        let lines: Vec<usize> = errors.iter().map(|e| e.line).collect();
+        assert_eq!(lines, vec![1, 2, 3, 4]);
+        assert!(errors[3].message.starts_with("UnknownTeam"));
+    }
+
+    #[test]
+    fn test_outcome_picks_the_winner_variant() {
+        assert!(matches!(
+            outcome(2, 1, DecidedBy::Regulation, None),
+            GameOutcome::WinnerHomeTeam { .. }
+        ));
+        assert!(matches!(
+            outcome(1, 3, DecidedBy::Overtime, None),
+            GameOutcome::WinnerAwayTeam {
+                decided_by: DecidedBy::Overtime,
+                ..
+            }
+        ));
+        assert!(matches!(
+            outcome(1, 1, DecidedBy::Regulation, None),
+            GameOutcome::Draw { score: 1 }
+        ));
+        let shootout = ShootoutScore { home: 3, away: 4 };
+        let decided = outcome(1, 1, DecidedBy::Shootout, Some(shootout));
+        assert!(matches!(decided, GameOutcome::WinnerAwayTeam { .. }));
+    }
+
+    #[test]
+    fn test_parse_score_and_players() {
+        assert_eq!(parse_score("2-1"), Ok((2, 1)));
+        assert_eq!(parse_score(" 0 - 0 "), Ok((0, 0)));
+        assert!(parse_score("2:1").is_err());
+        assert!(parse_score("2-").is_err());
+
+        let scorer = parse_player_stat("Tigers:Ada Lovelace:57").unwrap();
+        assert_eq!(scorer.team, "Tigers");
+        assert_eq!(scorer.first_name, "Ada");
+        assert_eq!(scorer.last_name, "Lovelace");
+        assert_eq!(scorer.minute, Some(57));
+        assert_eq!(
+            parse_player_stat("Tigers:Ada Lovelace").unwrap().minute,
+            None
+        );
+        assert!(parse_player_stat("Ada Lovelace").is_err());
+        assert!(parse_player_stat("Tigers:Ada:57").is_err());
+    }
+}
@@ -1,4 +1,30 @@
 pub mod api;
+pub mod backup;
+pub mod bracket;
+pub mod bundle;
+pub mod controller;
+pub mod create;
+pub mod discipline;
+pub mod http;
+pub mod import;
+pub mod manifests;
+pub mod metrics;
+pub mod schedule;
+pub mod standings;
+pub mod telemetry;
+pub mod validation;
+pub mod version;
+pub mod webhook;
 pub use api::v1alpha1::the_league_types::TheLeague;
 pub use api::v1alpha1::game_result_types::GameResult;
-pub use api::v1alpha1::standing_types::Standing;
\ No newline at end of file
+pub use api::v1alpha1::standing_types::Standing;
+pub use api::v1alpha1::suspension_types::Suspension;
+pub use api::v1alpha1::season_types::Season;
+pub use api::v1alpha1::match_types::Match;
+pub use api::v1alpha1::team_types::Team;
+pub use api::v1alpha1::player_types::Player;
+pub use api::v1alpha1::playoff_types::Playoff;
+pub use api::v1alpha1::tournament_types::Tournament;
+pub use api::v1alpha1::transfer_types::Transfer;
+pub use api::v1alpha1::referee_types::Referee;
+pub use api::v1alpha1::venue_types::Venue;
@@ -1,33 +1,127 @@
-mod api;
-mod controller;
-
 use anyhow::Context as AnyhowContext;
-use axum::{Router, http::StatusCode, routing::get};
-use controller::theleague_controller;
+use axum::{
+    Json, Router,
+    extract::{Path, State},
+    http::{StatusCode, header},
+    middleware,
+    response::Html,
+    routing::get,
+};
 use kube::Client;
-use std::{net::SocketAddr, sync::Arc};
+use kube::runtime::events::Recorder;
+use kube::runtime::reflector::ObjectRef;
+use std::{net::SocketAddr, path::PathBuf, sync::Arc};
+use the_league::controller::caches::Caches;
+use the_league::controller::diagnostics::{self, RuntimeReport};
+use the_league::controller::errors::ErrorLog;
+use the_league::controller::heartbeat::{self, Heartbeat};
+use the_league::controller::leader_election::{self, LeaderElector};
+use the_league::controller::readiness::{LeaseState, Readiness};
+use the_league::controller::{
+    self, GameResultReconciler, LeagueLocks, PlayerReconciler, PlayoffReconciler,
+    StandingReconciler, TournamentReconciler, TransferReconciler, theleague_controller,
+    validation_policy,
+};
+use the_league::http::ratelimit::{self, RateLimiter};
+use the_league::http::{auth, calendar, cors, dashboard, openapi, standings_api, submissions};
+use the_league::telemetry::LogFilter;
+use the_league::version::BuildInfo;
+use the_league::webhook;
 use tokio::net::TcpListener;
-use tracing::{error, info};
+use tracing::{error, info, warn};
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
-    tracing_subscriber::fmt()
-        .with_env_filter("info,kube=trace")
-        .init();
+    let telemetry = the_league::telemetry::init()?;
     info!("Starting TheLeague Controller (Idiomatic kube-rs).");
+    let build = BuildInfo::current();
+    info!(
+        version = build.version,
+        git_sha = build.git_sha,
+        build_timestamp = build.build_timestamp.as_deref().unwrap_or("unknown"),
+        api_versions = %build.api_versions.join(","),
+        "Controller build"
+    );
+    if controller::dry_run() {
+        warn!("DRY_RUN is set: the controllers' writes are validated but not persisted");
+    }
+    if telemetry.exporting() {
+        info!("Exporting reconcile traces over OTLP");
+    }
+
+    let errors = ErrorLog::default();
+    #[cfg(feature = "sentry")]
+    let sentry = controller::sentry_reporter::init();
+    #[cfg(feature = "sentry")]
+    let errors = match &sentry {
+        Some(_) => {
+            info!("Reporting panics and reconcile errors to Sentry");
+            errors.with_reporter(
+                controller::sentry_reporter::SentryReporter,
+                controller::errors::Severity::threshold(),
+            )
+        }
+        None => errors,
+    };
 
     let client = Client::try_default().await?;
-    let context = Arc::new(theleague_controller::Context {
+    let context = Arc::new(controller::Context {
         client: client.clone(),
+        locks: LeagueLocks::default(),
+        recorder: Recorder::new(client.clone(), controller::CONTROLLER_NAME.into()),
+        metrics: the_league::metrics::metrics(),
+        readiness: Readiness::default(),
+        heartbeat: Heartbeat::default(),
+        errors,
+        caches: Caches::default(),
     });
 
-    // Equivalent to mgr.AddHealthzCheck("healthz", healthz.Ping) and mgr.AddReadyzCheck("readyz", healthz.Ping)
+    // Equivalent to mgr.AddHealthzCheck with a check of the reconcile loops' heartbeat, and
+    // mgr.AddReadyzCheck with a check waiting for the CRDs and the informer caches. The league
+    // API, GraphQL and dashboard routes are authorized against the RBAC of the CRDs.
     let app = Router::new()
         .route("/healthz", get(healthz))
-        .route("/readyz", get(readyz));
+        .route("/readyz", get(readyz))
+        .route("/version", get(version))
+        .route("/ui", get(dashboard_leagues))
+        .route("/ui/leagues/{namespace}/{name}", get(dashboard_league))
+        .route("/api/openapi.json", get(openapi::document))
+        .route("/api/v1/leagues", get(standings_api::list_leagues))
+        .route(
+            "/api/v1/leagues/{namespace}/{name}/standings",
+            get(standings_api::get_league_standings),
+        )
+        .route(
+            "/api/v1/leagues/{namespace}/{name}/schedule.ics",
+            get(calendar::league_schedule),
+        )
+        .route(
+            "/api/v1/leagues/{namespace}/{name}/results",
+            get(standings_api::list_league_results).post(submissions::submit_result),
+        )
+        .with_state(context.clone())
+        .merge(graphql_router(&context))
+        .layer(middleware::from_fn_with_state(
+            context.clone(),
+            auth::require_access,
+        ));
+    // Limited per client before the reviews of the authorization, and open to the origins of
+    // API_CORS_ORIGINS so a scoreboard page on another origin can call it
+    let app = match RateLimiter::from_env() {
+        Some(limiter) => app.layer(middleware::from_fn_with_state(
+            limiter,
+            ratelimit::limit_rate,
+        )),
+        None => app,
+    };
+    let app = match cors::layer() {
+        Some(cors) => app.layer(cors),
+        None => app,
+    };
 
     // Default probe address (can be made configurable via env var like in Go)
-    let probe_addr = std::env::var("PROBE_ADDR").unwrap_or_else(|_| "0.0.0.0:8080".to_string());
+    let probe_addr = std::env::var("PROBE_ADDR")
+        .unwrap_or_else(|_| format!("0.0.0.0:{}", controller::PROBE_PORT));
     let addr: SocketAddr = probe_addr
         .parse()
         .with_context(|| format!("Invalid probe address '{}'", probe_addr))?;
@@ -37,12 +131,145 @@ async fn main() -> anyhow::Result<()> {
         .with_context(|| format!("Unable to bind health check server to {}", addr))?;
     info!("Health check server listening on {}", addr);
 
-    let server = axum::serve(listener, app);
+    let server = axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    );
+
+    // Metrics server, equivalent to the metrics server of controller-runtime; kept off the
+    // probe port so it can be exposed to Prometheus separately.
+    let metrics_addr = std::env::var("METRICS_ADDR")
+        .unwrap_or_else(|_| format!("0.0.0.0:{}", controller::METRICS_PORT));
+    let metrics_addr: SocketAddr = metrics_addr
+        .parse()
+        .with_context(|| format!("Invalid metrics address '{}'", metrics_addr))?;
+    let metrics_listener = TcpListener::bind(metrics_addr)
+        .await
+        .with_context(|| format!("Unable to bind metrics server to {}", metrics_addr))?;
+    info!("Metrics server listening on {}", metrics_addr);
+    let metrics_server = axum::serve(
+        metrics_listener,
+        Router::new().route("/metrics", get(metrics)),
+    );
+
+    // Admin server changing the log filter and reporting the runtime internals, bound to
+    // localhost by default so only a shell in the pod or `kubectl port-forward` reaches it,
+    // without going through the API auth.
+    let admin_addr = std::env::var("ADMIN_ADDR")
+        .unwrap_or_else(|_| format!("127.0.0.1:{}", controller::ADMIN_PORT));
+    let admin_addr: SocketAddr = admin_addr
+        .parse()
+        .with_context(|| format!("Invalid admin address '{}'", admin_addr))?;
+    let admin_listener = TcpListener::bind(admin_addr)
+        .await
+        .with_context(|| format!("Unable to bind admin server to {}", admin_addr))?;
+    info!("Admin server listening on {}", admin_addr);
+    let admin_server = axum::serve(
+        admin_listener,
+        Router::new()
+            .route("/loglevel", get(log_level).put(set_log_level))
+            .with_state(telemetry.log_filter())
+            .merge(
+                Router::new()
+                    .route("/debug/runtime", get(debug_runtime))
+                    .with_state(context.clone()),
+            ),
+    );
+
+    // Webhook server, equivalent to mgr.GetWebhookServer() in Go. Serves the mounted
+    // certificate if there is one and issues a self-signed one otherwise; when that fails too
+    // (e.g. running locally) the controller runs without webhooks.
+    let webhook_addr = std::env::var("WEBHOOK_ADDR")
+        .unwrap_or_else(|_| format!("0.0.0.0:{}", webhook::WEBHOOK_PORT));
+    let webhook_addr: SocketAddr = webhook_addr
+        .parse()
+        .with_context(|| format!("Invalid webhook address '{}'", webhook_addr))?;
+    let cert_dir = PathBuf::from(
+        std::env::var("WEBHOOK_CERT_DIR").unwrap_or_else(|_| webhook::DEFAULT_CERT_DIR.to_string()),
+    );
+    let webhook_client = client.clone();
+    let webhook_server = async move {
+        match webhook::serving_cert(&cert_dir) {
+            Some((cert, key)) => webhook::serve(webhook_addr, &cert, &key, webhook_client).await,
+            None => {
+                let namespace = std::env::var("POD_NAMESPACE")
+                    .unwrap_or_else(|_| webhook::DEFAULT_NAMESPACE.to_string());
+                let self_signed_dir = PathBuf::from(webhook::certs::SELF_SIGNED_CERT_DIR);
+                let served = webhook::serve_self_signed(
+                    webhook_addr,
+                    &namespace,
+                    &self_signed_dir,
+                    webhook_client,
+                )
+                .await;
+                if let Err(e) = served {
+                    warn!(
+                        "No serving certificate in {} and none could be issued ({}), \
+                         admission webhooks disabled",
+                        cert_dir.display(),
+                        e
+                    );
+                }
+                futures::future::pending().await
+            }
+        }
+    };
+
+    // The reconcilers read the validation policy from its reflector instead of getting the
+    // ConfigMap on every reconcile.
+    let (policy_store, policy_reflector) = validation_policy::reflect(&client);
+    context.caches.share_policy(policy_store);
 
     info!("Starting reconciliation loop for TheLeague...");
 
     let league_controller = theleague_controller::Reconciler::new(context.clone());
-    let controller_stream = league_controller.stream();
+    let standing_controller = StandingReconciler::new(context.clone());
+    let result_controller = GameResultReconciler::new(context.clone());
+    let playoff_controller = PlayoffReconciler::new(context.clone());
+    let tournament_controller = TournamentReconciler::new(context.clone());
+    let transfer_controller = TransferReconciler::new(context.clone());
+    let player_controller = PlayerReconciler::new(context.clone());
+    let controller_stream = futures::future::join(
+        futures::future::join5(
+            league_controller.stream(),
+            standing_controller.stream(),
+            result_controller.stream(),
+            playoff_controller.stream(),
+            tournament_controller.stream(),
+        ),
+        futures::future::join(transfer_controller.stream(), player_controller.stream()),
+    );
+    // The reflector runs as long as the controllers, which stop on a termination signal.
+    let controller_stream = async move {
+        tokio::select! {
+            _ = controller_stream => {}
+            _ = policy_reflector => {}
+        }
+    };
+    // With leader election only the leader runs the controllers; the servers run on every
+    // replica, so standbys stay live and ready while they wait for the Lease.
+    let leader_elector = leader_election::enabled().then(|| {
+        let namespace = std::env::var("POD_NAMESPACE")
+            .unwrap_or_else(|_| webhook::DEFAULT_NAMESPACE.to_string());
+        context.readiness.set_lease_state(LeaseState::Waiting);
+        LeaderElector::new(client.clone(), &namespace, leader_election::identity())
+    });
+    let readiness = context.readiness.clone();
+    let controllers = async move {
+        let Some(elector) = leader_elector else {
+            controller_stream.await;
+            return;
+        };
+        elector.acquire().await;
+        readiness.set_lease_state(LeaseState::Held);
+        tokio::select! {
+            _ = controller_stream => {}
+            _ = elector.hold() => {
+                error!("Lost leadership, exiting so another replica takes over");
+                std::process::exit(1);
+            }
+        }
+    };
 
     info!("Starting manager");
     tokio::select! {
@@ -54,19 +281,136 @@ async fn main() -> anyhow::Result<()> {
                 info!("Result: {:?}", result)
             }
         }
-        _ = controller_stream => {
+        result = metrics_server => {
+            if let Err(e) = result {
+                error!(error = %e, "Problem running metrics server");
+                std::process::exit(1);
+            }
+        }
+        result = admin_server => {
+            if let Err(e) = result {
+                error!(error = %e, "Problem running admin server");
+                std::process::exit(1);
+            }
+        }
+        result = webhook_server => {
+            if let Err(e) = result {
+                error!(error = %e, "Problem running webhook server");
+                std::process::exit(1);
+            }
+        }
+        _ = controllers => {
             info!("Controller stream ended");
         }
     }
     info!("Done!");
+    telemetry.shutdown();
     Ok(())
 }
 
-// Health check endpoints (equivalent to healthz.Ping in Go)
-async fn healthz() -> (StatusCode, &'static str) {
-    (StatusCode::OK, "ok")
+// Health check endpoints
+async fn healthz(State(context): State<Arc<controller::Context>>) -> (StatusCode, String) {
+    let stalled = context.heartbeat.stalled(heartbeat::liveness_deadline());
+    if stalled.is_empty() {
+        (StatusCode::OK, "ok".to_string())
+    } else {
+        let stalled = stalled.join(", ");
+        error!("Reconcile loops stalled: {}", stalled);
+        (
+            StatusCode::SERVICE_UNAVAILABLE,
+            format!("reconcile loops stalled: {}", stalled),
+        )
+    }
+}
+
+async fn readyz(State(context): State<Arc<controller::Context>>) -> (StatusCode, String) {
+    match context.readiness.check(&context.client).await {
+        Ok(()) => (StatusCode::OK, "ok".to_string()),
+        Err(reason) => (StatusCode::SERVICE_UNAVAILABLE, reason),
+    }
+}
+
+// Build of the running controller
+async fn version() -> Json<BuildInfo> {
+    Json(BuildInfo::current())
+}
+
+// Tokio runtime, reconciliations and reflector stores, to diagnose stalls
+async fn debug_runtime(State(context): State<Arc<controller::Context>>) -> Json<RuntimeReport> {
+    Json(diagnostics::report(&context))
+}
+
+// Dashboard listing the leagues
+async fn dashboard_leagues(
+    State(context): State<Arc<controller::Context>>,
+) -> Result<Html<String>, (StatusCode, String)> {
+    let (leagues, results) = context.caches.synced().ok_or_else(standings_api::caches_syncing)?;
+    let leagues = standings_api::league_summaries(&leagues.state(), &results.state(), None);
+    Ok(Html(dashboard::leagues_page(&leagues).into_string()))
+}
+
+// Dashboard of a league: its table, recent results and upcoming fixtures
+async fn dashboard_league(
+    State(context): State<Arc<controller::Context>>,
+    Path((namespace, name)): Path<(String, String)>,
+) -> Result<Html<String>, (StatusCode, String)> {
+    let (leagues, results) = context.caches.synced().ok_or_else(standings_api::caches_syncing)?;
+    let Some(league) = leagues.get(&ObjectRef::new(&name).within(&namespace)) else {
+        let reason = format!("league '{}/{}' not found", namespace, name);
+        return Err((StatusCode::NOT_FOUND, reason));
+    };
+    let dashboard = dashboard::league_dashboard(&league, &results.state());
+    Ok(Html(dashboard::league_page(&dashboard).into_string()))
+}
+
+// GraphQL API over the leagues, with the `graphql` feature
+#[cfg(feature = "graphql")]
+fn graphql_router(context: &controller::Context) -> Router {
+    the_league::http::graphql::router(context.caches.clone())
+}
+
+#[cfg(not(feature = "graphql"))]
+fn graphql_router(_: &controller::Context) -> Router {
+    Router::new()
 }
 
-async fn readyz() -> (StatusCode, &'static str) {
-    (StatusCode::OK, "ok")
+// Log filter of the process, e.g. `curl -X PUT -d debug localhost:8082/loglevel` in the pod
+async fn log_level(State(log_filter): State<LogFilter>) -> String {
+    log_filter.current()
+}
+
+async fn set_log_level(
+    State(log_filter): State<LogFilter>,
+    directives: String,
+) -> (StatusCode, String) {
+    match log_filter.set(&directives) {
+        Ok(()) => {
+            let current = log_filter.current();
+            info!("Log filter set to '{}'", current);
+            (StatusCode::OK, current)
+        }
+        Err(e) => (
+            StatusCode::BAD_REQUEST,
+            format!("invalid log filter: {}", e),
+        ),
+    }
+}
+
+// Prometheus metrics, equivalent to the metrics endpoint of controller-runtime
+async fn metrics() -> (StatusCode, [(header::HeaderName, &'static str); 1], String) {
+    match the_league::metrics::metrics().encode() {
+        Ok(text) => (
+            StatusCode::OK,
+            [(
+                header::CONTENT_TYPE,
+                "application/openmetrics-text; version=1.0.0; charset=utf-8",
+            )],
+            text,
+        ),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            [(header::CONTENT_TYPE, "text/plain")],
+            e.to_string(),
+        ),
+    }
 }
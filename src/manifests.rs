@@ -0,0 +1,249 @@
+//! Manifests generated into `config/`, read in the order of their kustomizations and moved into
+//! the namespace of an install, the way `kubectl apply -k config/` would. Shared by the install
+//! and uninstall binaries, which resolve each object to the Api serving it through discovery.
+
+use anyhow::Context;
+use kube::api::DynamicObject;
+use kube::core::GroupVersionKind;
+use kube::discovery::{self, ApiCapabilities, ApiResource, Scope};
+use kube::{Api, Client, ResourceExt};
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// Directories of the config directory holding the CRDs, the RBAC, and the manifests of the
+/// webhooks and the Deployment of the controller
+pub const CRD_DIR: &str = "crds/standard";
+pub const RBAC_DIR: &str = "rbac";
+pub const DEPLOY_DIRS: [&str; 2] = ["webhook", "manager"];
+
+/// The manifests listed by the kustomization of `dir`, in its order, reading the directories
+/// it lists too. Namespaces are left out: the install creates its own.
+pub fn read_manifests(dir: &Path) -> anyhow::Result<Vec<DynamicObject>> {
+    let path = dir.join("kustomization.yaml");
+    let content =
+        fs::read_to_string(&path).with_context(|| format!("reading {}", path.display()))?;
+    let kustomization: serde_yaml::Value = serde_yaml::from_str(&content)?;
+    let mut objects = Vec::new();
+    for resource in kustomization["resources"]
+        .as_sequence()
+        .into_iter()
+        .flatten()
+    {
+        let Some(resource) = resource.as_str() else {
+            continue;
+        };
+        let path = dir.join(resource);
+        if path.is_dir() {
+            objects.extend(read_manifests(&path)?);
+            continue;
+        }
+        let content =
+            fs::read_to_string(&path).with_context(|| format!("reading {}", path.display()))?;
+        for document in serde_yaml::Deserializer::from_str(&content) {
+            let object = DynamicObject::deserialize(document)
+                .with_context(|| format!("parsing {}", path.display()))?;
+            if kind(&object) != "Namespace" {
+                objects.push(object);
+            }
+        }
+    }
+    Ok(objects)
+}
+
+/// Kind of `object`
+pub fn kind(object: &DynamicObject) -> &str {
+    object.types.as_ref().map_or("", |t| t.kind.as_str())
+}
+
+/// Move `object` into `namespace` if it is `namespaced`, and point the references of its
+/// bindings and webhooks to the ServiceAccount and Services there
+pub fn into_namespace(object: &mut DynamicObject, namespace: &str, namespaced: bool) {
+    if namespaced {
+        object.metadata.namespace = Some(namespace.to_string());
+    }
+    let set_service_namespace = |client_config: Option<&mut Value>| {
+        if let Some(service) = client_config
+            .and_then(|c| c.get_mut("service"))
+            .and_then(Value::as_object_mut)
+        {
+            service.insert("namespace".to_string(), namespace.into());
+        }
+    };
+    match kind(object) {
+        "RoleBinding" | "ClusterRoleBinding" => {
+            let subjects = object
+                .data
+                .get_mut("subjects")
+                .and_then(Value::as_array_mut);
+            for subject in subjects.into_iter().flatten() {
+                if subject["kind"] == "ServiceAccount"
+                    && let Some(subject) = subject.as_object_mut()
+                {
+                    subject.insert("namespace".to_string(), namespace.into());
+                }
+            }
+        }
+        "ValidatingWebhookConfiguration" | "MutatingWebhookConfiguration" => {
+            let webhooks = object
+                .data
+                .get_mut("webhooks")
+                .and_then(Value::as_array_mut);
+            for webhook in webhooks.into_iter().flatten() {
+                set_service_namespace(webhook.get_mut("clientConfig"));
+            }
+        }
+        "CustomResourceDefinition" => {
+            let conversion = object
+                .data
+                .pointer_mut("/spec/conversion/webhook/clientConfig");
+            set_service_namespace(conversion);
+        }
+        _ => {}
+    }
+}
+
+/// An object of the manifests with the Api serving it
+pub struct Prepared {
+    pub api: Api<DynamicObject>,
+    pub object: DynamicObject,
+    pub namespaced: bool,
+}
+
+impl Prepared {
+    /// Kind and name of the object, as printed to the user
+    pub fn describe(&self) -> String {
+        format!("{} {}", kind(&self.object), self.object.name_any())
+    }
+}
+
+/// Resolver prepares the objects of the manifests for the cluster of `client`, discovering
+/// the Api of each kind once.
+pub struct Resolver {
+    client: Client,
+    namespace: String,
+    discovered: HashMap<String, (ApiResource, ApiCapabilities)>,
+}
+
+impl Resolver {
+    /// Resolver of the objects installed into `namespace`
+    pub fn new(client: Client, namespace: &str) -> Self {
+        Resolver {
+            client,
+            namespace: namespace.to_string(),
+            discovered: HashMap::new(),
+        }
+    }
+
+    /// `object` in the namespace of the install, with the Api serving its kind
+    pub async fn prepare(&mut self, mut object: DynamicObject) -> anyhow::Result<Prepared> {
+        let types = object.types.clone().unwrap_or_default();
+        let key = format!("{}/{}", types.api_version, types.kind);
+        if !self.discovered.contains_key(&key) {
+            let gvk = GroupVersionKind::try_from(&types)?;
+            let discovered = discovery::pinned_kind(&self.client, &gvk)
+                .await
+                .with_context(|| format!("discovering {}", key))?;
+            self.discovered.insert(key.clone(), discovered);
+        }
+        let (resource, capabilities) = &self.discovered[&key];
+        let namespaced = capabilities.scope == Scope::Namespaced;
+        into_namespace(&mut object, &self.namespace, namespaced);
+        let api = if namespaced {
+            Api::namespaced_with(self.client.clone(), &self.namespace, resource)
+        } else {
+            Api::all_with(self.client.clone(), resource)
+        };
+        Ok(Prepared {
+            api,
+            object,
+            namespaced,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn object(json: Value) -> DynamicObject {
+        serde_json::from_value(json).unwrap()
+    }
+
+    #[test]
+    fn test_read_manifests_follows_the_kustomizations() {
+        let temp_dir = TempDir::new().unwrap();
+        let nested = temp_dir.path().join("nested");
+        fs::create_dir_all(&nested).unwrap();
+        let kustomization = "resources:\n- b.yaml\n- nested\n- a.yaml\n";
+        fs::write(temp_dir.path().join("kustomization.yaml"), kustomization).unwrap();
+        fs::write(nested.join("kustomization.yaml"), "resources:\n- c.yaml\n").unwrap();
+        let manifest = |name: &str| {
+            format!(
+                "apiVersion: v1\nkind: ConfigMap\nmetadata:\n  name: {}\n",
+                name
+            )
+        };
+        fs::write(temp_dir.path().join("a.yaml"), manifest("a")).unwrap();
+        fs::write(temp_dir.path().join("b.yaml"), manifest("b")).unwrap();
+        let namespace = "apiVersion: v1\nkind: Namespace\nmetadata:\n  name: leagues\n";
+        fs::write(
+            nested.join("c.yaml"),
+            format!("{}---\n{}", manifest("c"), namespace),
+        )
+        .unwrap();
+
+        let objects = read_manifests(temp_dir.path()).unwrap();
+        let names: Vec<String> = objects.iter().map(|o| o.name_any()).collect();
+        assert_eq!(names, ["b", "c", "a"]);
+
+        // The committed manifests are readable too
+        let rbac = read_manifests(&Path::new("config").join(RBAC_DIR)).unwrap();
+        assert!(rbac.iter().any(|o| kind(o) == "ServiceAccount"));
+    }
+
+    #[test]
+    fn test_into_namespace_moves_the_references() {
+        let mut binding = object(serde_json::json!({
+            "apiVersion": "rbac.authorization.k8s.io/v1",
+            "kind": "ClusterRoleBinding",
+            "metadata": {"name": "manager-role"},
+            "roleRef": {
+                "apiGroup": "rbac.authorization.k8s.io",
+                "kind": "ClusterRole",
+                "name": "manager-role",
+            },
+            "subjects": [{"kind": "ServiceAccount", "name": "theleague-controller-manager"}],
+        }));
+        into_namespace(&mut binding, "leagues", false);
+        assert_eq!(binding.metadata.namespace, None);
+        assert_eq!(binding.data["subjects"][0]["namespace"], "leagues");
+
+        let mut webhooks = object(serde_json::json!({
+            "apiVersion": "admissionregistration.k8s.io/v1",
+            "kind": "ValidatingWebhookConfiguration",
+            "metadata": {"name": "theleague-validating-webhook-configuration"},
+            "webhooks": [{
+                "name": "vgameresult.bexxmodd.com",
+                "clientConfig": {"service": {
+                    "name": "theleague-webhook-service",
+                    "namespace": "theleague-system",
+                }},
+            }],
+        }));
+        into_namespace(&mut webhooks, "leagues", false);
+        let service = &webhooks.data["webhooks"][0]["clientConfig"]["service"];
+        assert_eq!(service["namespace"], "leagues");
+
+        let mut account = object(serde_json::json!({
+            "apiVersion": "v1",
+            "kind": "ServiceAccount",
+            "metadata": {"name": "bot"},
+        }));
+        into_namespace(&mut account, "leagues", true);
+        assert_eq!(account.metadata.namespace.as_deref(), Some("leagues"));
+    }
+}
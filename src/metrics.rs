@@ -0,0 +1,54 @@
+//! Prometheus metrics of the controller process, served on `/metrics` of the metrics server.
+
+use crate::controller::metrics::{ControllerMetrics, LeagueMetrics};
+use crate::webhook::metrics::WebhookMetrics;
+
+use prometheus_client::registry::Registry;
+use std::sync::LazyLock;
+
+/// Metrics of the controller process and the registry exposing them
+pub struct Metrics {
+    /// Reconciliations and watcher restarts of the controllers
+    pub controller: ControllerMetrics,
+
+    /// Gauges of the league tables, updated when a table is recomputed
+    pub leagues: LeagueMetrics,
+
+    /// Admission decisions and latencies of the webhook server
+    pub webhook: WebhookMetrics,
+
+    registry: Registry,
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        let mut registry = Registry::with_prefix("theleague");
+        let controller = ControllerMetrics::default();
+        controller.register(&mut registry);
+        let leagues = LeagueMetrics::default();
+        leagues.register(&mut registry);
+        let webhook = WebhookMetrics::default();
+        webhook.register(&mut registry);
+        Self {
+            controller,
+            leagues,
+            webhook,
+            registry,
+        }
+    }
+}
+
+impl Metrics {
+    /// The metrics in the Prometheus text exposition format
+    pub fn encode(&self) -> Result<String, std::fmt::Error> {
+        let mut text = String::new();
+        prometheus_client::encoding::text::encode(&mut text, &self.registry)?;
+        Ok(text)
+    }
+}
+
+/// The metrics of this process
+pub fn metrics() -> &'static Metrics {
+    static METRICS: LazyLock<Metrics> = LazyLock::new(Metrics::default);
+    &METRICS
+}
@@ -0,0 +1,657 @@
+//! Round-robin fixture generation.
+//!
+//! The schedule is derived deterministically from the league spec (team order and
+//! matchups), so every component computing it gets the same rounds.
+
+use crate::api::v1alpha1::game_result_types::{GameOutcome, GameResultSpec};
+use crate::api::v1alpha1::the_league_types::{ScheduleCalendar, TheLeagueSpec};
+use crate::standings;
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::Time;
+use k8s_openapi::chrono::{Datelike, Days, Duration, NaiveDate, Weekday};
+use std::collections::HashMap;
+use std::fmt;
+
+/// Minutes between the kickoffs of games on the same day, which is also the time a game
+/// occupies its venue
+pub const MATCH_SLOT_MINUTES: i64 = 120;
+
+/// ScheduleError is returned for leagues whose schedule can't be computed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScheduleError(pub String);
+
+impl fmt::Display for ScheduleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "cannot schedule league: {}", self.0)
+    }
+}
+
+impl std::error::Error for ScheduleError {}
+
+/// Fixture is a single scheduled game.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fixture {
+    /// Round is the 1-based round number the game is scheduled in.
+    pub round: u32,
+    /// Home is the team listed first in a GameResult's `teams`.
+    pub home: String,
+    /// Away is the team listed second in a GameResult's `teams`.
+    pub away: String,
+
+    /// RescheduledTo is the new date of a fixture whose game was postponed, if one was given.
+    pub rescheduled_to: Option<Time>,
+
+    /// Kickoff is the time the fixture is scheduled for, for leagues with a calendar.
+    pub kickoff: Option<Time>,
+}
+
+impl Fixture {
+    /// Whether this fixture is between `a` and `b`, in either home/away orientation.
+    pub fn involves_pair(&self, a: &str, b: &str) -> bool {
+        (self.home == a && self.away == b) || (self.home == b && self.away == a)
+    }
+
+    /// Whether `team` plays in this fixture.
+    pub fn involves(&self, team: &str) -> bool {
+        self.home == team || self.away == team
+    }
+}
+
+/// Schedule is the ordered list of rounds of a league.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Schedule {
+    pub rounds: Vec<Vec<Fixture>>,
+}
+
+impl Schedule {
+    /// Number of rounds in the schedule.
+    pub fn round_count(&self) -> u32 {
+        self.rounds.len() as u32
+    }
+
+    /// Fixtures of the 1-based round `number`, if it exists.
+    pub fn round(&self, number: u32) -> Option<&[Fixture]> {
+        let index = usize::try_from(number).ok()?.checked_sub(1)?;
+        self.rounds.get(index).map(|r| r.as_slice())
+    }
+
+    /// All fixtures in round order.
+    pub fn fixtures(&self) -> impl Iterator<Item = &Fixture> {
+        self.rounds.iter().flatten()
+    }
+
+    /// Fixtures that still need a completed game, in round order.
+    ///
+    /// A fixture is done once a result with a final score exists for its round and pairing.
+    /// Postponed and abandoned games leave the fixture open; a postponed fixture is
+    /// regenerated with the date it was rescheduled to.
+    pub fn outstanding_fixtures(&self, results: &[&GameResultSpec]) -> Vec<Fixture> {
+        let of_fixture = |f: &Fixture| {
+            results
+                .iter()
+                .filter(|r| r.round_number == f.round)
+                .filter(|r| f.involves_pair(&r.teams[0], &r.teams[1]))
+                .collect::<Vec<_>>()
+        };
+        self.fixtures()
+            .filter_map(|f| {
+                let games = of_fixture(f);
+                if games
+                    .iter()
+                    .any(|r| standings::outcome_scores(&r.result).is_some())
+                {
+                    return None;
+                }
+                let rescheduled_to = games.iter().rev().find_map(|r| match &r.result {
+                    GameOutcome::Postponed { rescheduled_to } => rescheduled_to.clone(),
+                    _ => None,
+                });
+                Some(Fixture {
+                    rescheduled_to,
+                    ..f.clone()
+                })
+            })
+            .collect()
+    }
+
+    /// Append the schedules `legs` of disjoint groups of teams, playing their rounds in
+    /// parallel after the rounds of this schedule.
+    pub fn append_parallel(&mut self, legs: Vec<Schedule>) {
+        let count = legs.iter().map(|l| l.rounds.len()).max().unwrap_or(0);
+        let mut rounds = vec![Vec::new(); count];
+        for leg in legs {
+            for (round, fixtures) in rounds.iter_mut().zip(leg.rounds) {
+                round.extend(fixtures);
+            }
+        }
+        for mut fixtures in rounds {
+            let number = self.round_count() + 1;
+            for fixture in &mut fixtures {
+                fixture.round = number;
+            }
+            self.rounds.push(fixtures);
+        }
+    }
+
+    /// Rounds in which `a` and `b` are scheduled to meet.
+    pub fn rounds_of_pair(&self, a: &str, b: &str) -> Vec<u32> {
+        self.fixtures()
+            .filter(|f| f.involves_pair(a, b))
+            .map(|f| f.round)
+            .collect()
+    }
+}
+
+/// Name of the GameResult recording a fixture, e.g. `premier-round-3-tigers-vs-sharks`.
+pub fn result_name(league: &str, round: u32, home: &str, away: &str) -> String {
+    format!(
+        "{}-round-{}-{}-vs-{}",
+        slug(league),
+        round,
+        slug(home),
+        slug(away)
+    )
+}
+
+/// Name of the GameResult or Match of `fixture` in `season`, e.g.
+/// `premier-round-3-tigers-vs-sharks-season-2`. The first season keeps the plain
+/// names of leagues without seasons.
+pub fn fixture_name(league: &str, season: Option<u32>, fixture: &Fixture) -> String {
+    let name = result_name(league, fixture.round, &fixture.home, &fixture.away);
+    match season {
+        Some(number) if number > 1 => format!("{}-season-{}", name, number),
+        _ => name,
+    }
+}
+
+/// Lowercase `s` and join its alphanumeric parts with dashes, for use in object names.
+pub fn slug(s: &str) -> String {
+    s.to_lowercase()
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// The schedule of a league: a (balanced) round robin over its teams in spec order, followed by the
+/// extra meetings of the teams of each division, with kickoff times when the league has a
+/// calendar. The divisions play their extra meetings in the same rounds.
+///
+/// Fails when the teams of a division meet more often than can be counted, or the calendar runs
+/// past the last date kickoff times can be represented for.
+pub fn league_schedule(league: &TheLeagueSpec) -> Result<Schedule, ScheduleError> {
+    let teams: Vec<String> = league.teams.iter().map(|t| t.name.clone()).collect();
+    let generate = if league.balance_home_away {
+        balanced_round_robin
+    } else {
+        round_robin
+    };
+    let extra_meetings = league
+        .matchups
+        .checked_mul(league.intra_division_ratio.saturating_sub(1))
+        .ok_or_else(|| ScheduleError("too many meetings between the teams of a division".into()))?;
+    let mut schedule = generate(&teams, league.matchups);
+    if !league.divisions.is_empty() && extra_meetings > 0 {
+        let divisions = division_teams(league);
+        let mut legs: Vec<Schedule> = divisions
+            .iter()
+            .map(|teams| generate(teams, extra_meetings))
+            .collect();
+        // Keep alternating home and away after an odd number of meetings.
+        if league.matchups % 2 == 1 {
+            for fixture in legs.iter_mut().flat_map(|l| l.rounds.iter_mut().flatten()) {
+                std::mem::swap(&mut fixture.home, &mut fixture.away);
+            }
+        }
+        schedule.append_parallel(legs);
+    }
+    if let Some(calendar) = &league.schedule {
+        assign_kickoffs(&mut schedule, calendar)?;
+    }
+    Ok(schedule)
+}
+
+/// The registered teams of each division of `league`, in spec order. A team listed in more
+/// than one division only counts for the first.
+pub fn division_teams(league: &TheLeagueSpec) -> Vec<Vec<String>> {
+    let mut assigned: Vec<&str> = Vec::new();
+    league
+        .divisions
+        .iter()
+        .map(|division| {
+            division
+                .teams
+                .iter()
+                .filter(|team| league.teams.iter().any(|t| &&t.name == team))
+                .filter(|team| {
+                    let first = !assigned.contains(&team.as_str());
+                    assigned.push(team);
+                    first
+                })
+                .cloned()
+                .collect()
+        })
+        .collect()
+}
+
+/// Assign a kickoff time to every fixture of `schedule` following `calendar`.
+///
+/// Rounds are played in order on the match days from the start date that aren't blacked
+/// out, each starting on a day after the previous round ended plus the rest days. A round
+/// with more games than fit on a day continues on the next match day.
+///
+/// Fails when the calendar runs past the last representable date.
+pub fn assign_kickoffs(
+    schedule: &mut Schedule,
+    calendar: &ScheduleCalendar,
+) -> Result<(), ScheduleError> {
+    let start = calendar.start_date.0;
+    let match_days: Vec<Weekday> = calendar.match_days.iter().map(|&d| d.into()).collect();
+    let blackouts: Vec<NaiveDate> = calendar
+        .blackout_dates
+        .iter()
+        .filter_map(|d| d.date())
+        .collect();
+    let out_of_range =
+        || ScheduleError("the calendar runs past the last representable date".into());
+    let after = |day: NaiveDate, days: u64| {
+        day.checked_add_days(Days::new(days))
+            .ok_or_else(out_of_range)
+    };
+    // Terminates as match days always include a weekday and blackouts are finite.
+    let next_match_day = |mut day: NaiveDate| -> Result<NaiveDate, ScheduleError> {
+        while (!match_days.is_empty() && !match_days.contains(&day.weekday()))
+            || blackouts.contains(&day)
+        {
+            day = after(day, 1)?;
+        }
+        Ok(day)
+    };
+    let games_per_day = calendar
+        .games_per_day
+        .filter(|n| *n > 0)
+        .unwrap_or(u32::MAX);
+    let rest_days = u64::from(calendar.min_rest_days.unwrap_or(0));
+
+    let mut day = next_match_day(start.date_naive())?;
+    for round in &mut schedule.rounds {
+        let mut games = 0;
+        for fixture in round.iter_mut() {
+            if games == games_per_day {
+                day = next_match_day(after(day, 1)?)?;
+                games = 0;
+            }
+            let kickoff = start
+                .checked_add_signed(day - start.date_naive())
+                .and_then(|t| {
+                    t.checked_add_signed(Duration::minutes(MATCH_SLOT_MINUTES * i64::from(games)))
+                })
+                .ok_or_else(out_of_range)?;
+            fixture.kickoff = Some(Time(kickoff));
+            games += 1;
+        }
+        day = next_match_day(after(day, 1 + rest_days)?)?;
+    }
+    Ok(())
+}
+
+/// Generate a round-robin schedule using the circle method.
+///
+/// Every pair of teams meets `matchups` times. With an odd number of teams one team
+/// has a bye each round. Repeated matchups swap home and away with respect to the
+/// previous meeting.
+pub fn round_robin(teams: &[String], matchups: u32) -> Schedule {
+    if teams.len() < 2 || matchups == 0 {
+        return Schedule::default();
+    }
+    expand_legs(&circle_rounds(teams), matchups, false)
+}
+
+/// Generate a round-robin schedule like [`round_robin`] in which no team plays more than two
+/// consecutive home or away games.
+///
+/// The orientation of the circle method is rebalanced game by game, preferring to break
+/// runs of home or away games. Legs swapping home and away play the rounds in reverse
+/// order, so every team alternates at the boundary between two legs and repeated matchups
+/// still alternate home and away.
+pub fn balanced_round_robin(teams: &[String], matchups: u32) -> Schedule {
+    if teams.len() < 2 || matchups == 0 {
+        return Schedule::default();
+    }
+    let mut single_leg = circle_rounds(teams);
+    balance_home_away(&mut single_leg);
+    expand_legs(&single_leg, matchups, true)
+}
+
+/// The (home, away) pairs of every round of a single leg of the circle method
+fn circle_rounds(teams: &[String]) -> Vec<Vec<(&String, &String)>> {
+    // None is the bye slot for odd team counts.
+    let mut slots: Vec<Option<&String>> = teams.iter().map(Some).collect();
+    if slots.len() % 2 == 1 {
+        slots.push(None);
+    }
+    let n = slots.len();
+
+    let mut single_leg: Vec<Vec<(&String, &String)>> = Vec::with_capacity(n - 1);
+    for round in 0..n - 1 {
+        let mut pairs = Vec::with_capacity(n / 2);
+        for i in 0..n / 2 {
+            let (first, second) = (slots[i], slots[n - 1 - i]);
+            let (Some(first), Some(second)) = (first, second) else {
+                continue;
+            };
+            // Alternate the orientation of the fixed team so it isn't always at home.
+            if i == 0 && round % 2 == 1 {
+                pairs.push((second, first));
+            } else {
+                pairs.push((first, second));
+            }
+        }
+        single_leg.push(pairs);
+        // Keep the first slot fixed and rotate the others clockwise.
+        slots[1..].rotate_right(1);
+    }
+    single_leg
+}
+
+/// Reorient the games of `single_leg` so no team plays three home or away games in a row.
+///
+/// Each game is oriented against the runs of home (positive) or away (negative) games its
+/// teams are on; the circle method orientation is kept when both orientations are equal.
+fn balance_home_away<'a>(single_leg: &mut [Vec<(&'a String, &'a String)>]) {
+    let mut runs: HashMap<&'a String, i32> = HashMap::new();
+    let cost = |run: i32, home: bool| match (run, home) {
+        (r, true) if r >= 2 => 10,
+        (r, false) if r <= -2 => 10,
+        (r, true) if r > 0 => 1,
+        (r, false) if r < 0 => 1,
+        _ => 0,
+    };
+    for round in single_leg.iter_mut() {
+        for pair in round.iter_mut() {
+            let (home, away) = *pair;
+            let home_run = runs.get(home).copied().unwrap_or(0);
+            let away_run = runs.get(away).copied().unwrap_or(0);
+            let kept = cost(home_run, true) + cost(away_run, false);
+            let swapped = cost(away_run, true) + cost(home_run, false);
+            let (home_run, away_run) = if swapped < kept {
+                *pair = (away, home);
+                (away_run, home_run)
+            } else {
+                (home_run, away_run)
+            };
+            runs.insert(pair.0, home_run.max(0) + 1);
+            runs.insert(pair.1, away_run.min(0) - 1);
+        }
+    }
+}
+
+/// Repeat `single_leg` `matchups` times, swapping home and away every other leg. With
+/// `reverse_swapped` the swapped legs play the rounds in reverse order.
+fn expand_legs(
+    single_leg: &[Vec<(&String, &String)>],
+    matchups: u32,
+    reverse_swapped: bool,
+) -> Schedule {
+    let mut rounds = Vec::with_capacity(single_leg.len().saturating_mul(matchups as usize));
+    for leg in 0..matchups {
+        let swapped = leg % 2 == 1;
+        let order: Vec<&Vec<(&String, &String)>> = if swapped && reverse_swapped {
+            single_leg.iter().rev().collect()
+        } else {
+            single_leg.iter().collect()
+        };
+        for pairs in order {
+            let round = rounds.len() as u32 + 1;
+            let fixtures = pairs
+                .iter()
+                .map(|(home, away)| {
+                    let (home, away) = if swapped { (away, home) } else { (home, away) };
+                    Fixture {
+                        round,
+                        home: home.to_string(),
+                        away: away.to_string(),
+                        rescheduled_to: None,
+                        kickoff: None,
+                    }
+                })
+                .collect();
+            rounds.push(fixtures);
+        }
+    }
+
+    Schedule { rounds }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use k8s_openapi::chrono::Utc;
+
+    fn teams(n: usize) -> Vec<String> {
+        (0..n).map(|i| format!("T{}", i)).collect()
+    }
+
+    #[test]
+    fn test_every_pair_meets_matchups_times() {
+        for n in 2..=8 {
+            for matchups in 1..=3 {
+                let names = teams(n);
+                let schedule = round_robin(&names, matchups);
+
+                let expected_rounds = if n % 2 == 0 { n - 1 } else { n } as u32 * matchups;
+                assert_eq!(schedule.round_count(), expected_rounds);
+
+                let mut meetings: HashMap<(String, String), u32> = HashMap::new();
+                for f in schedule.fixtures() {
+                    let key = if f.home < f.away {
+                        (f.home.clone(), f.away.clone())
+                    } else {
+                        (f.away.clone(), f.home.clone())
+                    };
+                    *meetings.entry(key).or_default() += 1;
+                }
+                assert_eq!(meetings.len(), n * (n - 1) / 2);
+                assert!(meetings.values().all(|m| *m == matchups));
+            }
+        }
+    }
+
+    #[test]
+    fn test_teams_play_at_most_once_per_round() {
+        let names = teams(7);
+        let schedule = round_robin(&names, 2);
+        for round in &schedule.rounds {
+            for team in &names {
+                assert!(round.iter().filter(|f| f.involves(team)).count() <= 1);
+            }
+        }
+    }
+
+    #[test]
+    fn test_kickoffs_follow_the_calendar() {
+        use crate::api::v1alpha1::the_league_types::{Date, MatchDay};
+        use k8s_openapi::chrono::{TimeZone, Utc};
+
+        // Saturday 2025-03-01 15:00, with Saturday 2025-03-08 blacked out.
+        let start = Utc.with_ymd_and_hms(2025, 3, 1, 15, 0, 0).unwrap();
+        let calendar = ScheduleCalendar {
+            start_date: Time(start),
+            match_days: vec![MatchDay::Saturday, MatchDay::Sunday],
+            games_per_day: Some(1),
+            min_rest_days: Some(1),
+            blackout_dates: vec![Date("2025-03-08".to_string())],
+        };
+        let mut schedule = round_robin(&teams(4), 1);
+        assign_kickoffs(&mut schedule, &calendar).unwrap();
+
+        let kickoffs: Vec<String> = schedule
+            .fixtures()
+            .map(|f| {
+                f.kickoff
+                    .as_ref()
+                    .unwrap()
+                    .0
+                    .format("%a %d %H:%M")
+                    .to_string()
+            })
+            .collect();
+        assert_eq!(
+            kickoffs,
+            vec![
+                "Sat 01 15:00",
+                "Sun 02 15:00",
+                "Sun 09 15:00",
+                "Sat 15 15:00",
+                "Sat 22 15:00",
+                "Sun 23 15:00",
+            ]
+        );
+
+        let calendar = ScheduleCalendar {
+            match_days: Vec::new(),
+            games_per_day: None,
+            min_rest_days: None,
+            blackout_dates: Vec::new(),
+            ..calendar
+        };
+        assign_kickoffs(&mut schedule, &calendar).unwrap();
+        let round = schedule.round(2).unwrap();
+        assert_eq!(round[0].kickoff, Some(Time(start + Duration::days(1))));
+        assert_eq!(
+            round[1].kickoff,
+            Some(Time(
+                start + Duration::days(1) + Duration::minutes(MATCH_SLOT_MINUTES)
+            ))
+        );
+    }
+
+    #[test]
+    fn test_kickoffs_past_the_last_date_are_an_error() {
+        use k8s_openapi::chrono::DateTime;
+
+        let calendar = ScheduleCalendar {
+            start_date: Time(DateTime::<Utc>::MAX_UTC - Duration::days(30)),
+            match_days: Vec::new(),
+            games_per_day: None,
+            min_rest_days: Some(365),
+            blackout_dates: Vec::new(),
+        };
+        let mut schedule = round_robin(&teams(4), 1);
+        assert!(assign_kickoffs(&mut schedule, &calendar).is_err());
+    }
+
+    #[test]
+    fn test_division_teams_meet_more_often() {
+        use crate::api::v1alpha1::the_league_types::{Division, Team};
+
+        let team = |name: &str| -> Team {
+            serde_json::from_value(serde_json::json!({"name": name})).unwrap()
+        };
+        let mut league: TheLeagueSpec = serde_json::from_value(serde_json::json!({
+            "maxTeams": 8,
+            "matchups": 1,
+            "teams": []
+        }))
+        .unwrap();
+        league.teams = teams(6).iter().map(|n| team(n)).collect();
+        league.divisions = vec![
+            Division {
+                name: "North".to_string(),
+                teams: vec!["T0".to_string(), "T1".to_string(), "T2".to_string()],
+            },
+            Division {
+                name: "South".to_string(),
+                teams: vec!["T3".to_string(), "T4".to_string(), "T5".to_string()],
+            },
+        ];
+
+        let schedule = league_schedule(&league).unwrap();
+        // 5 rounds between all teams, then 3 rounds of both divisions in parallel.
+        assert_eq!(schedule.round_count(), 8);
+        assert_eq!(schedule.rounds_of_pair("T0", "T1").len(), 2);
+        assert_eq!(schedule.rounds_of_pair("T3", "T5").len(), 2);
+        assert_eq!(schedule.rounds_of_pair("T0", "T3").len(), 1);
+        for round in &schedule.rounds {
+            for team in teams(6) {
+                assert!(round.iter().filter(|f| f.involves(&team)).count() <= 1);
+            }
+        }
+        let meetings: Vec<&Fixture> = schedule
+            .fixtures()
+            .filter(|f| f.involves_pair("T0", "T1"))
+            .collect();
+        assert_eq!(meetings[0].home, meetings[1].away);
+
+        league.intra_division_ratio = 1;
+        assert_eq!(league_schedule(&league).unwrap().round_count(), 5);
+
+        league.matchups = 2;
+        league.intra_division_ratio = u32::MAX;
+        assert!(league_schedule(&league).is_err());
+    }
+
+    #[test]
+    fn test_balanced_schedule_has_no_three_game_runs() {
+        for n in 2..=8 {
+            for matchups in 1..=3 {
+                let names = teams(n);
+                let schedule = balanced_round_robin(&names, matchups);
+                for team in &names {
+                    let venues: Vec<bool> = schedule
+                        .fixtures()
+                        .filter(|f| f.involves(team))
+                        .map(|f| &f.home == team)
+                        .collect();
+                    assert!(
+                        venues.windows(3).all(|w| !(w[0] == w[1] && w[1] == w[2])),
+                        "{} teams, {} matchups: {} plays {:?}",
+                        n,
+                        matchups,
+                        team,
+                        venues
+                    );
+                }
+                let pairs: Vec<(&String, &String)> = schedule
+                    .fixtures()
+                    .filter(|f| f.involves_pair("T0", "T1"))
+                    .map(|f| (&f.home, &f.away))
+                    .collect();
+                assert!(pairs.windows(2).all(|w| w[0].0 == w[1].1));
+            }
+        }
+    }
+
+    #[test]
+    fn test_second_leg_swaps_home_and_away() {
+        let names = teams(4);
+        let schedule = round_robin(&names, 2);
+        let first = &schedule.round(1).unwrap()[0];
+        let second = &schedule.round(4).unwrap()[0];
+        assert_eq!(first.home, second.away);
+        assert_eq!(first.away, second.home);
+    }
+
+    #[test]
+    fn test_postponed_fixture_stays_outstanding() {
+        let schedule = round_robin(&teams(2), 1);
+        let when = Time(Utc::now());
+        let mut game = GameResultSpec::game("T1", "T0").with_result(GameOutcome::Postponed {
+            rescheduled_to: Some(when.clone()),
+        });
+        let outstanding = schedule.outstanding_fixtures(&[&game]);
+        assert_eq!(outstanding.len(), 1);
+        assert_eq!(outstanding[0].rescheduled_to, Some(when));
+
+        game.result = GameOutcome::Draw { score: 1 };
+        assert!(schedule.outstanding_fixtures(&[&game]).is_empty());
+    }
+
+    #[test]
+    fn test_round_lookup() {
+        let schedule = round_robin(&teams(4), 1);
+        assert!(schedule.round(0).is_none());
+        assert!(schedule.round(4).is_none());
+        assert_eq!(schedule.round(1).unwrap().len(), 2);
+        assert_eq!(schedule.rounds_of_pair("T0", "T1").len(), 1);
+    }
+}
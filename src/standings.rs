@@ -0,0 +1,695 @@
+//! Standings engine: turns the GameResults of a league into a ranked table.
+//!
+//! Everything in here is pure so it can be shared by the controllers and the CLI tools.
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use crate::api::v1alpha1::game_result_types::{DecidedBy, GameOutcome, GameResultSpec};
+use crate::api::v1alpha1::standing_types::StandingResolution;
+use crate::api::v1alpha1::the_league_types::{Division, DivisionTable, PointsPolicy, TableRow};
+
+/// TableEntry is one computed row of the league table.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TableEntry {
+    /// Team is the name of the team this row belongs to.
+    pub team: String,
+    /// Rank is the 1-based position of the team in the table.
+    pub rank: u32,
+    pub played: u32,
+    pub wins: u32,
+    pub draws: u32,
+    pub losses: u32,
+    pub goals_for: u32,
+    pub goals_against: u32,
+    pub points: u32,
+}
+
+impl TableEntry {
+    fn new(team: &str) -> Self {
+        Self {
+            team: team.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// GoalDifference is goals scored minus goals conceded.
+    pub fn goal_difference(&self) -> i64 {
+        i64::from(self.goals_for) - i64::from(self.goals_against)
+    }
+
+    fn record(&mut self, scored: u32, conceded: u32, verdict: Verdict, policy: &PointsPolicy) {
+        self.played += 1;
+        self.goals_for += scored;
+        self.goals_against += conceded;
+        self.points += verdict.points(policy);
+        match verdict {
+            Verdict::Win { .. } => self.wins += 1,
+            Verdict::Draw => self.draws += 1,
+            Verdict::Loss { .. } => self.losses += 1,
+        }
+    }
+}
+
+impl From<&TableEntry> for TableRow {
+    fn from(entry: &TableEntry) -> Self {
+        TableRow {
+            rank: entry.rank,
+            team: entry.team.clone(),
+            played: entry.played,
+            wins: entry.wins,
+            draws: entry.draws,
+            losses: entry.losses,
+            goals_for: entry.goals_for,
+            goals_against: entry.goals_against,
+            points: entry.points,
+            branding: None,
+        }
+    }
+}
+
+/// Verdict is the outcome of a game from the point of view of one team.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Verdict {
+    Win { overtime: bool },
+    Draw,
+    Loss { overtime: bool },
+}
+
+impl Verdict {
+    fn reversed(self) -> Self {
+        match self {
+            Verdict::Win { overtime } => Verdict::Loss { overtime },
+            Verdict::Draw => Verdict::Draw,
+            Verdict::Loss { overtime } => Verdict::Win { overtime },
+        }
+    }
+
+    fn points(self, policy: &PointsPolicy) -> u32 {
+        match self {
+            Verdict::Win { overtime: false } => policy.win,
+            Verdict::Win { overtime: true } => policy.overtime_win,
+            Verdict::Draw => policy.draw,
+            Verdict::Loss { overtime: false } => policy.loss,
+            Verdict::Loss { overtime: true } => policy.overtime_loss,
+        }
+    }
+}
+
+/// Verdict of a completed game for the home team, or None if the game doesn't count.
+fn home_verdict(outcome: &GameOutcome) -> Option<Verdict> {
+    let overtime = |decided_by: &DecidedBy| *decided_by != DecidedBy::Regulation;
+    match outcome {
+        GameOutcome::WinnerHomeTeam { decided_by, .. } => Some(Verdict::Win {
+            overtime: overtime(decided_by),
+        }),
+        GameOutcome::WinnerAwayTeam { decided_by, .. } => Some(Verdict::Loss {
+            overtime: overtime(decided_by),
+        }),
+        GameOutcome::Draw { .. } => Some(Verdict::Draw),
+        GameOutcome::Postponed { .. } | GameOutcome::Abandoned { .. } | GameOutcome::Pending {} => {
+            None
+        }
+    }
+}
+
+/// TableOptions controls how a table is computed.
+#[derive(Debug, Clone)]
+pub struct TableOptions {
+    /// Resolution is the tie-breaker applied to teams level on points.
+    pub resolution: StandingResolution,
+
+    /// KeptOpponents are withdrawn teams whose games still count for their opponents.
+    /// They never appear in the table themselves.
+    pub kept_opponents: Vec<String>,
+
+    /// Points is the points policy of the league.
+    pub points: PointsPolicy,
+}
+
+impl TableOptions {
+    pub fn new(resolution: StandingResolution) -> Self {
+        Self {
+            resolution,
+            kept_opponents: Vec::new(),
+            points: PointsPolicy::default(),
+        }
+    }
+}
+
+/// Returns the (home, away) scores recorded by an outcome, or None for games that were not
+/// completed (postponed, abandoned or still pending) and therefore don't count in the table.
+/// Shootout goals are not part of the score.
+pub fn outcome_scores(outcome: &GameOutcome) -> Option<(u32, u32)> {
+    match outcome {
+        GameOutcome::WinnerHomeTeam {
+            score_home,
+            score_away,
+            ..
+        }
+        | GameOutcome::WinnerAwayTeam {
+            score_home,
+            score_away,
+            ..
+        } => Some((*score_home, *score_away)),
+        GameOutcome::Draw { score } => Some((*score, *score)),
+        GameOutcome::Postponed { .. } | GameOutcome::Abandoned { .. } | GameOutcome::Pending {} => {
+            None
+        }
+    }
+}
+
+/// Returns the (home, away) points a single result awards under `policy`,
+/// or None for games that don't count in the table.
+pub fn points_awarded(outcome: &GameOutcome, policy: &PointsPolicy) -> Option<(u32, u32)> {
+    let verdict = home_verdict(outcome)?;
+    Some((verdict.points(policy), verdict.reversed().points(policy)))
+}
+
+/// Form of `team` over its last `games` completed games in `results`, oldest first: one of
+/// `W`, `D` or `L` per game, whether it was decided in regulation or not.
+pub fn team_form(team: &str, results: &[&GameResultSpec], games: usize) -> String {
+    let mut played: Vec<(&GameResultSpec, Verdict)> = results
+        .iter()
+        .copied()
+        .filter_map(|r| {
+            let verdict = home_verdict(&r.result)?;
+            if r.teams[0] == team {
+                Some((r, verdict))
+            } else if r.teams[1] == team {
+                Some((r, verdict.reversed()))
+            } else {
+                None
+            }
+        })
+        .collect();
+    played.sort_by_key(|(r, _)| r.time.as_ref().map(|t| t.0));
+    played[played.len().saturating_sub(games)..]
+        .iter()
+        .map(|(_, verdict)| match verdict {
+            Verdict::Win { .. } => 'W',
+            Verdict::Draw => 'D',
+            Verdict::Loss { .. } => 'L',
+        })
+        .collect()
+}
+
+/// Compute the ranked table for `teams` from the given results.
+///
+/// Results involving a team that is not part of `teams` are ignored (unless that team is
+/// listed in [`TableOptions::kept_opponents`]), so a withdrawn or misspelled team can never
+/// end up in the table. Games that were not completed are ignored as well.
+pub fn compute_table(
+    teams: &[String],
+    results: &[&GameResultSpec],
+    options: &TableOptions,
+) -> Vec<TableEntry> {
+    let mut entries: HashMap<&str, TableEntry> = teams
+        .iter()
+        .map(|team| (team.as_str(), TableEntry::new(team)))
+        .collect();
+
+    let counted = counted_results(teams, results, options);
+
+    for result in &counted {
+        let (Some((home, away)), Some(verdict)) =
+            (outcome_scores(&result.result), home_verdict(&result.result))
+        else {
+            continue;
+        };
+        if let Some(entry) = entries.get_mut(result.teams[0].as_str()) {
+            entry.record(home, away, verdict, &options.points);
+        }
+        if let Some(entry) = entries.get_mut(result.teams[1].as_str()) {
+            entry.record(away, home, verdict.reversed(), &options.points);
+        }
+    }
+
+    let mut table: Vec<TableEntry> = entries.into_values().collect();
+    table.sort_by(compare_overall);
+
+    if options.resolution == StandingResolution::Head2Head {
+        resolve_head_to_head(&mut table, &counted, &options.points);
+    }
+
+    for (i, entry) in table.iter_mut().enumerate() {
+        entry.rank = i as u32 + 1;
+    }
+    table
+}
+
+/// Split the ranked overall table `rows` into the tables of `divisions`, keeping the overall
+/// order and ranking every team within its division.
+pub fn division_tables(rows: &[TableRow], divisions: &[Division]) -> Vec<DivisionTable> {
+    divisions
+        .iter()
+        .map(|division| DivisionTable {
+            name: division.name.clone(),
+            table: rows
+                .iter()
+                .filter(|row| division.teams.contains(&row.team))
+                .enumerate()
+                .map(|(i, row)| TableRow {
+                    rank: i as u32 + 1,
+                    ..row.clone()
+                })
+                .collect(),
+        })
+        .collect()
+}
+
+/// Render the table as aligned plain text, one team per line.
+pub fn render_table(table: &[TableEntry]) -> String {
+    let width = table
+        .iter()
+        .map(|e| e.team.chars().count())
+        .max()
+        .unwrap_or(0)
+        .max("Team".len());
+    let mut out = format!(
+        "{:>4}  {:<width$}  {:>3} {:>3} {:>3} {:>3} {:>4} {:>4} {:>4} {:>4}\n",
+        "Rank", "Team", "P", "W", "D", "L", "GF", "GA", "GD", "Pts"
+    );
+    for e in table {
+        out.push_str(&format!(
+            "{:>4}  {:<width$}  {:>3} {:>3} {:>3} {:>3} {:>4} {:>4} {:>4} {:>4}\n",
+            e.rank,
+            e.team,
+            e.played,
+            e.wins,
+            e.draws,
+            e.losses,
+            e.goals_for,
+            e.goals_against,
+            e.goal_difference(),
+            e.points
+        ));
+    }
+    out
+}
+
+/// Attendance aggregates the reported spectator numbers of a set of games.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Attendance {
+    /// Total is the number of spectators over all games.
+    pub total: u64,
+    /// Games is the number of games with a reported attendance.
+    pub games: u32,
+}
+
+impl Attendance {
+    fn add(&mut self, spectators: u32) {
+        self.total += u64::from(spectators);
+        self.games += 1;
+    }
+
+    /// Average is the mean attendance per game with a reported attendance, rounded down.
+    pub fn average(&self) -> u64 {
+        self.total.checked_div(u64::from(self.games)).unwrap_or(0)
+    }
+}
+
+/// The completed games of `results` counted in the table of `teams`: both teams are in the
+/// table, or the opponent withdrew and `options` keeps its results.
+fn counted_results<'a>(
+    teams: &[String],
+    results: &[&'a GameResultSpec],
+    options: &TableOptions,
+) -> Vec<&'a GameResultSpec> {
+    results
+        .iter()
+        .copied()
+        .filter(|r| outcome_scores(&r.result).is_some())
+        .filter(|r| {
+            r.teams
+                .iter()
+                .all(|t| teams.contains(t) || options.kept_opponents.contains(t))
+        })
+        .collect()
+}
+
+/// Aggregate the attendance of the `results` counted in the table of `teams`, for the whole
+/// league and per home team. Games without a reported attendance are left out, so they don't
+/// lower the averages.
+pub fn compute_attendance(
+    teams: &[String],
+    results: &[&GameResultSpec],
+    options: &TableOptions,
+) -> (Attendance, HashMap<String, Attendance>) {
+    let mut league = Attendance::default();
+    let mut per_team: HashMap<String, Attendance> = HashMap::new();
+    for result in counted_results(teams, results, options) {
+        let Some(spectators) = result.attendance else {
+            continue;
+        };
+        league.add(spectators);
+        per_team
+            .entry(result.teams[0].clone())
+            .or_default()
+            .add(spectators);
+    }
+    (league, per_team)
+}
+
+/// Default ordering: points, goal difference, goals scored, then team name for stability.
+fn compare_overall(a: &TableEntry, b: &TableEntry) -> Ordering {
+    b.points
+        .cmp(&a.points)
+        .then_with(|| b.goal_difference().cmp(&a.goal_difference()))
+        .then_with(|| b.goals_for.cmp(&a.goals_for))
+        .then_with(|| a.team.cmp(&b.team))
+}
+
+/// Re-order every group of teams level on points by their mini-table of mutual games.
+fn resolve_head_to_head(
+    table: &mut [TableEntry],
+    results: &[&GameResultSpec],
+    policy: &PointsPolicy,
+) {
+    let mut start = 0;
+    while start < table.len() {
+        let points = table[start].points;
+        let end = start
+            + table[start..]
+                .iter()
+                .take_while(|e| e.points == points)
+                .count();
+        if end - start > 1 {
+            let group: Vec<String> = table[start..end].iter().map(|e| e.team.clone()).collect();
+            let mutual: Vec<&GameResultSpec> = results
+                .iter()
+                .copied()
+                .filter(|r| r.teams.iter().all(|t| group.contains(t)))
+                .collect();
+            let options = TableOptions {
+                points: policy.clone(),
+                ..TableOptions::new(StandingResolution::GoalDifference)
+            };
+            let mini: HashMap<String, (u32, i64)> = compute_table(&group, &mutual, &options)
+                .into_iter()
+                .map(|e| (e.team.clone(), (e.points, e.goal_difference())))
+                .collect();
+            table[start..end].sort_by(|a, b| {
+                mini[&b.team]
+                    .cmp(&mini[&a.team])
+                    .then_with(|| compare_overall(a, b))
+            });
+        }
+        start = end;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::v1alpha1::game_result_types::ShootoutScore;
+    use k8s_openapi::apimachinery::pkg::apis::meta::v1::Time;
+    use k8s_openapi::chrono::Utc;
+
+    fn game(home: &str, away: &str, score_home: u32, score_away: u32) -> GameResultSpec {
+        GameResultSpec::game(home, away).with_score(score_home, score_away)
+    }
+
+    fn teams(names: &[&str]) -> Vec<String> {
+        names.iter().map(|n| n.to_string()).collect()
+    }
+
+    #[test]
+    fn test_compute_table_points_and_records() {
+        let games = [
+            game("A", "B", 2, 0),
+            game("B", "C", 1, 1),
+            game("C", "A", 0, 3),
+        ];
+        let refs: Vec<&GameResultSpec> = games.iter().collect();
+        let table = compute_table(
+            &teams(&["A", "B", "C"]),
+            &refs,
+            &TableOptions::new(StandingResolution::GoalDifference),
+        );
+
+        assert_eq!(table[0].team, "A");
+        assert_eq!(table[0].points, 6);
+        assert_eq!(table[0].wins, 2);
+        assert_eq!(table[0].goal_difference(), 5);
+        assert_eq!(table[1].team, "B");
+        assert_eq!(table[1].points, 1);
+        assert_eq!(table[2].team, "C");
+        assert_eq!(table[2].draws, 1);
+        assert_eq!(table[2].losses, 1);
+        let ranks: Vec<u32> = table.iter().map(|e| e.rank).collect();
+        assert_eq!(ranks, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_compute_table_ignores_postponed_and_abandoned_games() {
+        let mut postponed = game("A", "B", 0, 0);
+        postponed.result = GameOutcome::Postponed {
+            rescheduled_to: None,
+        };
+        let mut abandoned = game("B", "A", 0, 0);
+        abandoned.result = GameOutcome::Abandoned { minute: 60 };
+        let games = [postponed, abandoned, game("A", "B", 1, 0)];
+        let refs: Vec<&GameResultSpec> = games.iter().collect();
+        let table = compute_table(
+            &teams(&["A", "B"]),
+            &refs,
+            &TableOptions::new(StandingResolution::GoalDifference),
+        );
+
+        assert_eq!(table[0].team, "A");
+        assert_eq!(table[0].played, 1);
+        assert_eq!(table[0].points, PointsPolicy::default().win);
+        assert_eq!(table[1].played, 1);
+        assert_eq!(table[1].draws, 0);
+    }
+
+    #[test]
+    fn test_points_policy_rewards_overtime_losses() {
+        let mut overtime = game("A", "B", 3, 2);
+        overtime.result = GameOutcome::WinnerHomeTeam {
+            score_home: 3,
+            score_away: 2,
+            decided_by: DecidedBy::Overtime,
+            shootout: None,
+        };
+        let mut shootout = game("C", "A", 1, 1);
+        shootout.result = GameOutcome::WinnerAwayTeam {
+            score_home: 1,
+            score_away: 1,
+            decided_by: DecidedBy::Shootout,
+            shootout: Some(ShootoutScore { home: 2, away: 3 }),
+        };
+        let games = [overtime, shootout, game("B", "C", 4, 0)];
+        let refs: Vec<&GameResultSpec> = games.iter().collect();
+        let options = TableOptions {
+            points: PointsPolicy {
+                win: 2,
+                draw: 1,
+                loss: 0,
+                overtime_win: 2,
+                overtime_loss: 1,
+            },
+            ..TableOptions::new(StandingResolution::GoalDifference)
+        };
+        let table = compute_table(&teams(&["A", "B", "C"]), &refs, &options);
+        let by_team = |team: &str| table.iter().find(|e| e.team == team).unwrap();
+
+        assert_eq!(by_team("A").points, 4);
+        assert_eq!(by_team("A").wins, 2);
+        assert_eq!(by_team("A").goals_for, 4);
+        assert_eq!(by_team("B").points, 3);
+        assert_eq!(by_team("B").losses, 1);
+        assert_eq!(by_team("C").points, 1);
+        assert_eq!(by_team("C").draws, 0);
+    }
+
+    #[test]
+    fn test_compute_table_ignores_unknown_teams() {
+        let games = [game("A", "Ghosts", 5, 0), game("A", "B", 0, 1)];
+        let refs: Vec<&GameResultSpec> = games.iter().collect();
+        let table = compute_table(
+            &teams(&["A", "B"]),
+            &refs,
+            &TableOptions::new(StandingResolution::GoalDifference),
+        );
+
+        assert_eq!(table[0].team, "B");
+        assert_eq!(table[1].played, 1);
+        assert_eq!(table[1].goals_for, 0);
+    }
+
+    #[test]
+    fn test_head_to_head_overrides_goal_difference() {
+        // A and B finish level on points; B has the better goal difference but lost to A.
+        let games = [
+            game("A", "B", 1, 0),
+            game("B", "C", 5, 0),
+            game("A", "D", 0, 0),
+            game("B", "D", 0, 0),
+        ];
+        let refs: Vec<&GameResultSpec> = games.iter().collect();
+        let names = teams(&["A", "B", "C", "D"]);
+
+        let by_gd = compute_table(
+            &names,
+            &refs,
+            &TableOptions::new(StandingResolution::GoalDifference),
+        );
+        let order: Vec<&str> = by_gd.iter().map(|e| e.team.as_str()).collect();
+        assert_eq!(order, vec!["B", "A", "D", "C"]);
+
+        let by_h2h = compute_table(
+            &names,
+            &refs,
+            &TableOptions::new(StandingResolution::Head2Head),
+        );
+        let order: Vec<&str> = by_h2h.iter().map(|e| e.team.as_str()).collect();
+        assert_eq!(order, vec!["A", "B", "D", "C"]);
+    }
+
+    #[test]
+    fn test_kept_opponents_count_for_remaining_teams() {
+        let games = [game("A", "Withdrawn", 2, 0), game("A", "B", 0, 0)];
+        let refs: Vec<&GameResultSpec> = games.iter().collect();
+        let names = teams(&["A", "B"]);
+
+        let expunged = compute_table(
+            &names,
+            &refs,
+            &TableOptions::new(StandingResolution::GoalDifference),
+        );
+        assert_eq!(expunged.iter().find(|e| e.team == "A").unwrap().points, 1);
+
+        let mut options = TableOptions::new(StandingResolution::GoalDifference);
+        options.kept_opponents = teams(&["Withdrawn"]);
+        let kept = compute_table(&names, &refs, &options);
+        assert_eq!(kept.len(), 2);
+        assert_eq!(kept[0].team, "A");
+        assert_eq!(kept[0].points, 4);
+        assert_eq!(kept[0].played, 2);
+    }
+
+    #[test]
+    fn test_render_table_aligns_columns() {
+        let games = [game("Tigers", "B", 2, 1)];
+        let refs: Vec<&GameResultSpec> = games.iter().collect();
+        let table = compute_table(
+            &teams(&["Tigers", "B"]),
+            &refs,
+            &TableOptions::new(StandingResolution::GoalDifference),
+        );
+
+        let text = render_table(&table);
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("Rank  Team"));
+        assert!(lines[1].starts_with("   1  Tigers"));
+        assert!(lines[2].starts_with("   2  B     "));
+        assert!(lines.iter().all(|l| l.len() == lines[0].len()));
+    }
+
+    #[test]
+    fn test_compute_attendance_per_home_team() {
+        let mut games = [
+            game("A", "B", 1, 0),
+            game("B", "A", 2, 2),
+            game("A", "C", 0, 1),
+            game("C", "B", 3, 0),
+        ];
+        games[0].attendance = Some(1200);
+        games[1].attendance = Some(800);
+        games[2].attendance = Some(1001);
+        let mut postponed = game("C", "A", 0, 0);
+        postponed.result = GameOutcome::Postponed {
+            rescheduled_to: None,
+        };
+        postponed.attendance = Some(500);
+        let mut friendly = game("A", "Z", 1, 1);
+        friendly.attendance = Some(9000);
+        let refs: Vec<&GameResultSpec> = games.iter().chain([&postponed, &friendly]).collect();
+
+        let options = TableOptions::new(StandingResolution::GoalDifference);
+        let (league, per_team) = compute_attendance(&teams(&["A", "B", "C"]), &refs, &options);
+        assert_eq!((league.total, league.games), (3001, 3));
+        assert_eq!(league.average(), 1000);
+        assert_eq!((per_team["A"].total, per_team["A"].games), (2201, 2));
+        assert_eq!(per_team["A"].average(), 1100);
+        assert_eq!(per_team["B"].total, 800);
+        assert!(!per_team.contains_key("C"));
+    }
+
+    #[test]
+    fn test_points_awarded_per_result() {
+        let policy = PointsPolicy::default();
+        assert_eq!(
+            points_awarded(&game("A", "B", 0, 2).result, &policy),
+            Some((0, 3))
+        );
+        assert_eq!(
+            points_awarded(&game("A", "B", 1, 1).result, &policy),
+            Some((1, 1))
+        );
+        assert_eq!(points_awarded(&GameOutcome::Pending {}, &policy), None);
+    }
+
+    #[test]
+    fn test_division_tables_rank_within_the_division() {
+        let games = [
+            game("A", "B", 3, 0),
+            game("C", "D", 2, 0),
+            game("B", "D", 1, 0),
+        ];
+        let refs: Vec<&GameResultSpec> = games.iter().collect();
+        let table = compute_table(
+            &teams(&["A", "B", "C", "D"]),
+            &refs,
+            &TableOptions::new(Default::default()),
+        );
+        let rows: Vec<TableRow> = table.iter().map(TableRow::from).collect();
+        let divisions = [
+            Division {
+                name: "North".to_string(),
+                teams: teams(&["B", "C"]),
+            },
+            Division {
+                name: "South".to_string(),
+                teams: teams(&["A", "D"]),
+            },
+        ];
+
+        let tables = division_tables(&rows, &divisions);
+        let ranked = |i: usize| -> Vec<(u32, &str)> {
+            tables[i]
+                .table
+                .iter()
+                .map(|r| (r.rank, r.team.as_str()))
+                .collect()
+        };
+        assert_eq!(tables[0].name, "North");
+        assert_eq!(ranked(0), vec![(1, "C"), (2, "B")]);
+        assert_eq!(ranked(1), vec![(1, "A"), (2, "D")]);
+    }
+
+    #[test]
+    fn test_team_form_is_the_last_games_oldest_first() {
+        let kickoff = |days: i64| Time(Utc::now() - k8s_openapi::chrono::Duration::days(days));
+        let mut games = [
+            game("A", "B", 1, 0),
+            game("C", "A", 2, 2),
+            game("A", "D", 0, 3),
+            game("B", "C", 1, 0),
+            game("D", "A", 0, 1),
+        ];
+        for (i, days) in [4, 3, 2, 1, 0].into_iter().enumerate() {
+            games[i].time = Some(kickoff(days));
+        }
+        games[4].result = GameOutcome::Pending {};
+        let refs: Vec<&GameResultSpec> = games.iter().rev().collect();
+
+        assert_eq!(team_form("A", &refs, 5), "WDL");
+        assert_eq!(team_form("A", &refs, 2), "DL");
+        assert_eq!(team_form("B", &refs, 5), "LW");
+        assert_eq!(team_form("E", &refs, 5), "");
+    }
+}
@@ -0,0 +1,179 @@
+//! Logging and trace export of the controller process.
+//!
+//! Logs always go to stdout, as text or as JSON with `LOG_FORMAT=json`, filtered by
+//! `RUST_LOG` or [`DEFAULT_FILTER`]; the filter can be swapped at runtime through
+//! [`LogFilter`], served on `/loglevel` of the admin server. JSON logs carry the fields of the
+//! current span, so logs of a reconcile have its kind, name, namespace and reconcile id.
+//!
+//! Traces are exported over OTLP (gRPC) only when `OTEL_EXPORTER_OTLP_ENDPOINT` is set; the
+//! exporter reads the other standard `OTEL_*` variables itself. Every reconcile runs in a
+//! `reconcile` span, the HTTP spans of the kube client and the standings computation nest
+//! below it.
+
+use anyhow::Context;
+use opentelemetry::trace::TracerProvider as _;
+use opentelemetry_otlp::SpanExporter;
+use opentelemetry_sdk::Resource;
+use opentelemetry_sdk::trace::SdkTracerProvider;
+use std::str::FromStr;
+use tracing::warn;
+use tracing_subscriber::Layer;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{EnvFilter, Registry, reload};
+
+/// Service name of the exported traces unless `OTEL_SERVICE_NAME` is set
+pub const DEFAULT_SERVICE_NAME: &str = "theleague-controller";
+
+/// Filter of the logs and spans unless `RUST_LOG` is set
+pub const DEFAULT_FILTER: &str = "info,kube=trace";
+
+/// Output format of the logs, set with `LOG_FORMAT`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LogFormat {
+    /// Text lines for humans
+    #[default]
+    Text,
+    /// One JSON object per line for log aggregators
+    Json,
+}
+
+impl FromStr for LogFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "text" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            _ => anyhow::bail!("unknown log format '{}', expected text or json", s),
+        }
+    }
+}
+
+/// Filter of the logs and spans of the process, which can be replaced while it runs
+#[derive(Clone)]
+pub struct LogFilter {
+    handle: reload::Handle<EnvFilter, Registry>,
+}
+
+impl LogFilter {
+    /// Reloadable filter layer starting out with `directives`, and its handle
+    fn new(directives: &str) -> anyhow::Result<(reload::Layer<EnvFilter, Registry>, Self)> {
+        let (layer, handle) = reload::Layer::new(EnvFilter::try_new(directives)?);
+        Ok((layer, Self { handle }))
+    }
+
+    /// Directives of the current filter
+    pub fn current(&self) -> String {
+        self.handle
+            .with_current(|filter| filter.to_string())
+            .unwrap_or_default()
+    }
+
+    /// Replace the filter with `directives`, like `debug` or `info,the_league=debug`
+    pub fn set(&self, directives: &str) -> anyhow::Result<()> {
+        let filter = EnvFilter::try_new(directives.trim())?;
+        self.handle.reload(filter)?;
+        Ok(())
+    }
+}
+
+/// Trace exporter and log filter of the process, flushed by [`Telemetry::shutdown`]
+pub struct Telemetry {
+    provider: Option<SdkTracerProvider>,
+    log_filter: LogFilter,
+}
+
+impl Telemetry {
+    /// Handle to the log filter of the process
+    pub fn log_filter(&self) -> LogFilter {
+        self.log_filter.clone()
+    }
+
+    /// Whether traces are exported
+    pub fn exporting(&self) -> bool {
+        self.provider.is_some()
+    }
+
+    /// Flush the spans not exported yet
+    pub fn shutdown(self) {
+        if let Some(provider) = self.provider
+            && let Err(e) = provider.shutdown()
+        {
+            warn!("Failed to flush traces: {}", e);
+        }
+    }
+}
+
+/// Install the global subscriber logging in the `LOG_FORMAT`, exporting traces when an OTLP endpoint is configured.
+/// Must be called from within the Tokio runtime.
+pub fn init() -> anyhow::Result<Telemetry> {
+    let provider = match std::env::var("OTEL_EXPORTER_OTLP_ENDPOINT") {
+        Ok(_) => Some(tracer_provider()?),
+        Err(_) => None,
+    };
+    let otel = provider.as_ref().map(|provider| {
+        tracing_opentelemetry::layer().with_tracer(provider.tracer(env!("CARGO_PKG_NAME")))
+    });
+    let directives = std::env::var("RUST_LOG").unwrap_or_else(|_| DEFAULT_FILTER.to_string());
+    let (filter, log_filter) = LogFilter::new(&directives)
+        .with_context(|| format!("Invalid log filter '{}'", directives))?;
+    let format = match std::env::var("LOG_FORMAT") {
+        Ok(format) => format.parse()?,
+        Err(_) => LogFormat::default(),
+    };
+    let text = (format == LogFormat::Text).then(tracing_subscriber::fmt::layer);
+    let json = (format == LogFormat::Json).then(|| {
+        tracing_subscriber::fmt::layer()
+            .json()
+            .flatten_event(true)
+            .with_current_span(true)
+            .with_span_list(false)
+    });
+    // The filter only applies to the logs and traces; tokio-console needs the runtime's own
+    // trace events, which are never logged.
+    let layers = Layer::and_then(Layer::and_then(text, json), otel).with_filter(filter);
+    let subscriber = tracing_subscriber::registry().with(layers);
+    #[cfg(feature = "tokio-console")]
+    let subscriber = subscriber.with(console_subscriber::spawn());
+    subscriber.init();
+    Ok(Telemetry {
+        provider,
+        log_filter,
+    })
+}
+
+fn tracer_provider() -> anyhow::Result<SdkTracerProvider> {
+    let exporter = SpanExporter::builder().with_tonic().build()?;
+    let service_name =
+        std::env::var("OTEL_SERVICE_NAME").unwrap_or_else(|_| DEFAULT_SERVICE_NAME.to_string());
+    Ok(SdkTracerProvider::builder()
+        .with_batch_exporter(exporter)
+        .with_resource(Resource::builder().with_service_name(service_name).build())
+        .build())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_log_format_from_str() {
+        assert_eq!("text".parse::<LogFormat>().unwrap(), LogFormat::Text);
+        assert_eq!("JSON".parse::<LogFormat>().unwrap(), LogFormat::Json);
+        assert!("logfmt".parse::<LogFormat>().is_err());
+    }
+
+    #[test]
+    fn test_log_filter_is_replaced_at_runtime() {
+        let (layer, log_filter) = LogFilter::new(DEFAULT_FILTER).unwrap();
+        let _guard = tracing_subscriber::registry().with(layer).set_default();
+        assert_eq!(log_filter.current(), "kube=trace,info");
+
+        log_filter.set("debug\n").unwrap();
+        assert_eq!(log_filter.current(), "debug");
+
+        assert!(log_filter.set("the_league=loud").is_err());
+        assert_eq!(log_filter.current(), "debug");
+    }
+}
@@ -0,0 +1,1054 @@
+//! Validation rules for league resources.
+//!
+//! The rules are pure functions over the specs so the reconcilers and any admission
+//! path apply exactly the same checks.
+
+use crate::api::v1alpha1::game_result_types::{DecidedBy, GameOutcome, GameResultSpec};
+use crate::api::v1alpha1::the_league_types::{Enforcement, TheLeagueSpec};
+use crate::api::v1alpha1::tournament_types::KnockoutTiebreak;
+use crate::schedule;
+
+use k8s_openapi::chrono::NaiveDate;
+use std::collections::BTreeMap;
+
+/// Violation is a single failed validation rule, shaped for a status condition.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    /// Reason is a CamelCase machine-readable identifier of the rule.
+    pub reason: String,
+
+    /// Message is a human readable explanation of the failure.
+    pub message: String,
+}
+
+impl Violation {
+    pub fn new(reason: &str, message: impl Into<String>) -> Self {
+        Self {
+            reason: reason.to_string(),
+            message: message.into(),
+        }
+    }
+}
+
+/// ValidationPolicy sets how the configurable rules are enforced, by the admission webhooks
+/// and the reconcilers alike. It is read from a ConfigMap mapping the rule keys to
+/// `Enforce`, `Warn` or `Off`; rules missing from it are enforced.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ValidationPolicy {
+    /// DuplicateResult covers results recording a fixture that already has a result.
+    pub duplicate_result: Enforcement,
+
+    /// RosterSize covers the minPlayersPerTeam and maxPlayersPerTeam limits. The CRD schema
+    /// still caps embedded rosters at maxPlayersPerTeam, which no policy can relax.
+    pub roster_size: Enforcement,
+
+    /// Eligibility covers the eligibility rules of the league.
+    pub eligibility: Enforcement,
+}
+
+impl ValidationPolicy {
+    /// Key of the duplicate result rule
+    pub const DUPLICATE_RESULT: &str = "duplicateResult";
+
+    /// Key of the roster size rule
+    pub const ROSTER_SIZE: &str = "rosterSize";
+
+    /// Key of the eligibility rule
+    pub const ELIGIBILITY: &str = "eligibility";
+
+    /// Parse the policy from the data of its ConfigMap. Unknown keys and levels are errors,
+    /// so a typo can't silently leave a rule enforced.
+    pub fn from_data(data: &BTreeMap<String, String>) -> Result<Self, String> {
+        let mut policy = Self::default();
+        for (key, value) in data {
+            let enforcement = match value.trim() {
+                "Enforce" => Enforcement::Enforce,
+                "Warn" => Enforcement::Warn,
+                "Off" => Enforcement::Off,
+                other => {
+                    return Err(format!(
+                        "rule '{}' has unknown enforcement '{}', expected Enforce, Warn or Off",
+                        key, other
+                    ));
+                }
+            };
+            match key.as_str() {
+                Self::DUPLICATE_RESULT => policy.duplicate_result = enforcement,
+                Self::ROSTER_SIZE => policy.roster_size = enforcement,
+                Self::ELIGIBILITY => policy.eligibility = enforcement,
+                other => return Err(format!("unknown rule '{}'", other)),
+            }
+        }
+        Ok(policy)
+    }
+}
+
+/// Summarize violations into a single (reason, message) pair for a condition.
+/// The reason of the first violation wins; all messages are kept.
+pub fn summarize(violations: &[Violation]) -> Option<(String, String)> {
+    let first = violations.first()?;
+    let message = violations
+        .iter()
+        .map(|v| v.message.as_str())
+        .collect::<Vec<_>>()
+        .join("; ");
+    Some((first.reason.clone(), message))
+}
+
+/// Validate the parts of a GameResult that don't depend on its league.
+///
+/// These checks need no API access, so the admission webhook runs them on every write.
+pub fn validate_result_consistency(result: &GameResultSpec) -> Vec<Violation> {
+    let mut violations = Vec::new();
+
+    if result.teams[0] == result.teams[1] {
+        violations.push(Violation::new(
+            "IdenticalTeams",
+            format!("team '{}' cannot play against itself", result.teams[0]),
+        ));
+    }
+
+    match &result.result {
+        GameOutcome::WinnerHomeTeam {
+            score_home,
+            score_away,
+            decided_by,
+            shootout,
+        } => violations.extend(validate_winner(
+            "WinnerHomeTeam",
+            (*score_home, *score_away),
+            *decided_by,
+            shootout.as_ref().map(|s| (s.home, s.away)),
+        )),
+        GameOutcome::WinnerAwayTeam {
+            score_home,
+            score_away,
+            decided_by,
+            shootout,
+        } => violations.extend(validate_winner(
+            "WinnerAwayTeam",
+            (*score_away, *score_home),
+            *decided_by,
+            shootout.as_ref().map(|s| (s.away, s.home)),
+        )),
+        _ => {}
+    }
+
+    violations
+}
+
+/// Check that a winner variant is backed by its scores. `score` and `shootout` are given as
+/// (winner, loser).
+fn validate_winner(
+    variant: &str,
+    score: (u32, u32),
+    decided_by: DecidedBy,
+    shootout: Option<(u32, u32)>,
+) -> Option<Violation> {
+    let (winner, loser) = score;
+    match (decided_by, shootout) {
+        (DecidedBy::Shootout, None) => Some(Violation::new(
+            "InconsistentScore",
+            format!("{} decided by Shootout requires a shootout score", variant),
+        )),
+        (DecidedBy::Shootout, Some(_)) if winner != loser => Some(Violation::new(
+            "InconsistentScore",
+            format!(
+                "{} decided by Shootout requires a level score, got {}-{}",
+                variant, winner, loser
+            ),
+        )),
+        (DecidedBy::Shootout, Some((shots_winner, shots_loser))) if shots_winner <= shots_loser => {
+            Some(Violation::new(
+                "InconsistentScore",
+                format!(
+                    "{} requires the winner to lead the shootout, got {}-{}",
+                    variant, shots_winner, shots_loser
+                ),
+            ))
+        }
+        (DecidedBy::Shootout, Some(_)) => None,
+        (decided_by, Some(_)) => Some(Violation::new(
+            "InconsistentScore",
+            format!(
+                "{} decided by {:?} must not have a shootout score",
+                variant, decided_by
+            ),
+        )),
+        (_, None) if winner <= loser => Some(Violation::new(
+            "InconsistentScore",
+            format!(
+                "{} requires the winner to have the higher score, got {}-{}",
+                variant, winner, loser
+            ),
+        )),
+        (_, None) => None,
+    }
+}
+
+/// Validate a GameResult against the league it belongs to.
+pub fn validate_game_result(result: &GameResultSpec, league: &TheLeagueSpec) -> Vec<Violation> {
+    let mut violations = validate_result_consistency(result);
+
+    let consistency_violations = violations.len();
+    for team in &result.teams {
+        if !league.teams.iter().any(|t| &t.name == team) {
+            violations.push(Violation::new(
+                "UnknownTeam",
+                format!(
+                    "team '{}' is not registered in league '{}'",
+                    team, result.league_name
+                ),
+            ));
+        }
+    }
+    // Schedule and roster checks need two distinct, registered teams.
+    if result.teams[0] == result.teams[1] || violations.len() > consistency_violations {
+        return violations;
+    }
+
+    // Playoff and tournament rounds follow their bracket, not the league schedule.
+    if result.is_league_game() {
+        violations.extend(validate_round(result, league));
+    }
+    violations.extend(validate_players(result, league));
+    violations
+}
+
+/// Check that every scorer, assist and card references a player on the roster of one of the
+/// two teams of the game.
+fn validate_players(result: &GameResultSpec, league: &TheLeagueSpec) -> Vec<Violation> {
+    let scorers = result
+        .scorers
+        .iter()
+        .map(|s| ("scorer", &s.team, &s.first_name, &s.last_name));
+    let assists = result
+        .assists
+        .iter()
+        .map(|a| ("assist", &a.team, &a.first_name, &a.last_name));
+    let cards = result
+        .cards
+        .iter()
+        .map(|c| ("card", &c.team, &c.first_name, &c.last_name));
+
+    let mut violations = Vec::new();
+    for (kind, team, first_name, last_name) in scorers.chain(assists).chain(cards) {
+        if !result.teams.contains(team) {
+            violations.push(Violation::new(
+                "PlayerTeamNotInGame",
+                format!(
+                    "{} {} {} plays for '{}', which is not part of this game",
+                    kind, first_name, last_name, team
+                ),
+            ));
+            continue;
+        }
+        let on_roster = league
+            .teams
+            .iter()
+            .filter(|t| &t.name == team)
+            .flat_map(|t| &t.players)
+            .any(|p| &p.first_name == first_name && &p.last_name == last_name);
+        if !on_roster {
+            violations.push(Violation::new(
+                "UnknownPlayer",
+                format!(
+                    "{} {} {} is not on the roster of '{}'",
+                    kind, first_name, last_name, team
+                ),
+            ));
+        }
+    }
+    violations
+}
+
+/// Check that the round exists in the league schedule and that the pairing is scheduled in it.
+fn validate_round(result: &GameResultSpec, league: &TheLeagueSpec) -> Option<Violation> {
+    let schedule = match schedule::league_schedule(league) {
+        Ok(schedule) => schedule,
+        Err(e) => return Some(Violation::new("InvalidSchedule", e.to_string())),
+    };
+    let [home, away] = &result.teams;
+    let Some(fixtures) = schedule.round(result.round_number) else {
+        return Some(Violation::new(
+            "RoundOutOfRange",
+            format!(
+                "round {} is outside the league schedule of {} rounds",
+                result.round_number,
+                schedule.round_count()
+            ),
+        ));
+    };
+    if fixtures.iter().any(|f| f.involves_pair(home, away)) {
+        return None;
+    }
+    let scheduled: Vec<String> = schedule
+        .rounds_of_pair(home, away)
+        .iter()
+        .map(|r| r.to_string())
+        .collect();
+    Some(Violation::new(
+        "FixtureNotScheduled",
+        format!(
+            "'{}' vs '{}' is not scheduled in round {} (scheduled in rounds: {})",
+            home,
+            away,
+            result.round_number,
+            scheduled.join(", ")
+        ),
+    ))
+}
+
+/// The round a result should be moved to when its league doesn't enforce a strict schedule.
+///
+/// Returns None when the league is strict, the round already matches, or the pairing
+/// isn't scheduled at all. Otherwise returns the scheduled round of the pairing closest to
+/// the submitted one.
+pub fn corrected_round(result: &GameResultSpec, league: &TheLeagueSpec) -> Option<u32> {
+    if league.strict_schedule || !result.is_league_game() {
+        return None;
+    }
+    let schedule = schedule::league_schedule(league).ok()?;
+    let [home, away] = &result.teams;
+    let rounds = schedule.rounds_of_pair(home, away);
+    if rounds.contains(&result.round_number) {
+        return None;
+    }
+    rounds
+        .into_iter()
+        .min_by_key(|r| r.abs_diff(result.round_number))
+}
+
+/// Check that a knockout game is decided the way the `tiebreak` of its tournament allows.
+/// Games that haven't been played are not checked.
+pub fn validate_knockout_game(
+    result: &GameResultSpec,
+    tiebreak: KnockoutTiebreak,
+) -> Option<Violation> {
+    let decided_by = match &result.result {
+        GameOutcome::WinnerHomeTeam { decided_by, .. }
+        | GameOutcome::WinnerAwayTeam { decided_by, .. } => decided_by,
+        GameOutcome::Draw { .. } if tiebreak != KnockoutTiebreak::Replay => {
+            return Some(Violation::new(
+                "KnockoutDraw",
+                "knockout games must have a winner unless the tournament replays draws",
+            ));
+        }
+        _ => return None,
+    };
+    let allowed = match tiebreak {
+        KnockoutTiebreak::ExtraTimeAndShootout => true,
+        KnockoutTiebreak::Shootout => *decided_by != DecidedBy::Overtime,
+        KnockoutTiebreak::Replay => *decided_by == DecidedBy::Regulation,
+    };
+    (!allowed).then(|| {
+        Violation::new(
+            "TiebreakNotAllowed",
+            format!(
+                "game decided by {:?} is not allowed with the {:?} tiebreak",
+                decided_by, tiebreak
+            ),
+        )
+    })
+}
+
+/// Check that no player is registered to more than one team of the league and that jersey
+/// numbers are unique within each team.
+///
+/// Roster entries referencing a Player are the same player when they reference the same
+/// Player; other entries are the same player when their names match.
+pub fn validate_rosters(league: &TheLeagueSpec) -> Vec<Violation> {
+    let mut violations = validate_multiple_teams(league);
+    violations.extend(validate_jersey_numbers(league));
+    violations
+}
+
+fn validate_multiple_teams(league: &TheLeagueSpec) -> Vec<Violation> {
+    let mut teams_of: BTreeMap<String, Vec<&str>> = BTreeMap::new();
+    for team in &league.teams {
+        for player in &team.players {
+            let identity = match &player.player_ref {
+                Some(player_ref) => format!("Player '{}'", player_ref),
+                None => format!("{} {}", player.first_name, player.last_name),
+            };
+            let teams = teams_of.entry(identity).or_default();
+            if !teams.contains(&team.name.as_str()) {
+                teams.push(&team.name);
+            }
+        }
+    }
+    teams_of
+        .into_iter()
+        .filter(|(_, teams)| teams.len() > 1)
+        .map(|(player, teams)| {
+            Violation::new(
+                "PlayerOnMultipleTeams",
+                format!("{} is registered to teams {}", player, teams.join(", ")),
+            )
+        })
+        .collect()
+}
+
+/// Check `league` the way the admission webhook does, handling the rules configurable by
+/// `policy` as it says: the rosters, divisions and schedule, and the captains, roster sizes
+/// and eligibility of the embedded rosters as of `today`. The violations denying the league, and
+/// the ones only warned about.
+pub fn validate_league(
+    league: &TheLeagueSpec,
+    policy: &ValidationPolicy,
+    today: NaiveDate,
+) -> (Vec<Violation>, Vec<Violation>) {
+    let mut embedded = league.clone();
+    embedded.teams.retain(|t| t.team_ref.is_none());
+    let mut violations = validate_rosters(league);
+    violations.extend(validate_captains(&embedded));
+    violations.extend(validate_divisions(league));
+    if let Err(e) = schedule::league_schedule(league) {
+        violations.push(Violation::new("InvalidSchedule", e.to_string()));
+    }
+    let mut warnings = Vec::new();
+    for (enforcement, found) in [
+        (policy.roster_size, validate_roster_sizes(&embedded)),
+        (policy.eligibility, validate_eligibility(&embedded, today)),
+    ] {
+        match enforcement {
+            Enforcement::Enforce => violations.extend(found),
+            Enforcement::Warn => warnings.extend(found),
+            Enforcement::Off => {}
+        }
+    }
+    (violations, warnings)
+}
+
+/// Check that the divisions of a league only list registered teams, each in one division.
+pub fn validate_divisions(league: &TheLeagueSpec) -> Vec<Violation> {
+    let mut violations = Vec::new();
+    let mut division_of: BTreeMap<&str, &str> = BTreeMap::new();
+    for division in &league.divisions {
+        for team in &division.teams {
+            if !league.teams.iter().any(|t| &t.name == team) {
+                violations.push(Violation::new(
+                    "UnknownTeam",
+                    format!(
+                        "team '{}' of division '{}' is not registered in the league",
+                        team, division.name
+                    ),
+                ));
+            } else if let Some(other) = division_of.insert(team, &division.name) {
+                violations.push(Violation::new(
+                    "TeamInMultipleDivisions",
+                    format!(
+                        "team '{}' is in divisions '{}' and '{}'",
+                        team, other, division.name
+                    ),
+                ));
+            }
+        }
+    }
+    violations
+}
+
+/// Check that the teams of a league with a roster lock don't change from `old` to `new` while
+/// `round_in_progress` is being played.
+pub fn validate_roster_lock(
+    old: &TheLeagueSpec,
+    new: &TheLeagueSpec,
+    round_in_progress: Option<u32>,
+) -> Vec<Violation> {
+    let (Some(lock), Some(round)) = (&new.roster_lock, round_in_progress) else {
+        return Vec::new();
+    };
+    if lock.enforcement == Enforcement::Off {
+        return Vec::new();
+    }
+    if !teams_changed(old, new) {
+        return Vec::new();
+    }
+    vec![Violation::new(
+        "RosterLocked",
+        format!(
+            "teams can't change while round {} is in progress; wait until every match of it \
+             is played",
+            round
+        ),
+    )]
+}
+
+/// Whether the teams of a league changed from `old` to `new`
+pub fn teams_changed(old: &TheLeagueSpec, new: &TheLeagueSpec) -> bool {
+    serde_json::to_value(&old.teams).ok() != serde_json::to_value(&new.teams).ok()
+}
+
+/// Check that the roster of every team is within the roster size limits of the league.
+pub fn validate_roster_sizes(league: &TheLeagueSpec) -> Vec<Violation> {
+    let mut violations = Vec::new();
+    for team in &league.teams {
+        let size = team.players.len() as u32;
+        if let Some(min) = league.min_players_per_team.filter(|min| size < *min) {
+            violations.push(Violation::new(
+                "TooFewPlayers",
+                format!(
+                    "team '{}' has {} players, at least {} are required",
+                    team.name, size, min
+                ),
+            ));
+        }
+        if let Some(max) = league.max_players_per_team.filter(|max| size > *max) {
+            violations.push(Violation::new(
+                "TooManyPlayers",
+                format!(
+                    "team '{}' has {} players, at most {} are allowed",
+                    team.name, size, max
+                ),
+            ));
+        }
+    }
+    violations
+}
+
+/// Check that the captain of every team is a player on its roster, either by `First Last`
+/// name or by the Player the roster entry references.
+pub fn validate_captains(league: &TheLeagueSpec) -> Vec<Violation> {
+    league
+        .teams
+        .iter()
+        .filter_map(|team| {
+            let captain = team.captain.as_deref()?;
+            let on_roster = team.players.iter().any(|p| {
+                p.player_ref.as_deref() == Some(captain)
+                    || format!("{} {}", p.first_name, p.last_name) == captain
+            });
+            (!on_roster).then(|| {
+                Violation::new(
+                    "CaptainNotOnRoster",
+                    format!(
+                        "captain '{}' of team '{}' is not on its roster",
+                        captain, team.name
+                    ),
+                )
+            })
+        })
+        .collect()
+}
+
+/// Check that every player meets the eligibility rules of the league. Ages are taken on the
+/// start date of the schedule, or on `today` for leagues without a schedule calendar.
+pub fn validate_eligibility(league: &TheLeagueSpec, today: NaiveDate) -> Vec<Violation> {
+    let Some(rules) = &league.eligibility else {
+        return Vec::new();
+    };
+    let on = league
+        .schedule
+        .as_ref()
+        .map(|s| s.start_date.0.date_naive())
+        .unwrap_or(today);
+    let deadline = rules.registration_deadline.as_ref().and_then(|d| d.date());
+    let has_age_limit = rules.min_age.is_some() || rules.max_age.is_some();
+
+    let mut violations = Vec::new();
+    for team in &league.teams {
+        for player in &team.players {
+            let who = format!(
+                "{} {} of team '{}'",
+                player.first_name, player.last_name, team.name
+            );
+            match player.date_of_birth.as_ref().map(|d| d.date()) {
+                Some(Some(born)) if has_age_limit => {
+                    let age = on.years_since(born).unwrap_or_default();
+                    if let Some(min) = rules.min_age.filter(|min| age < *min) {
+                        violations.push(Violation::new(
+                            "PlayerTooYoung",
+                            format!("{} is {}, the minimum age is {}", who, age, min),
+                        ));
+                    }
+                    if let Some(max) = rules.max_age.filter(|max| age > *max) {
+                        violations.push(Violation::new(
+                            "PlayerTooOld",
+                            format!("{} is {}, the maximum age is {}", who, age, max),
+                        ));
+                    }
+                }
+                Some(None) => violations.push(Violation::new(
+                    "InvalidDate",
+                    format!("{} has an invalid dateOfBirth", who),
+                )),
+                None if has_age_limit => violations.push(Violation::new(
+                    "DateOfBirthRequired",
+                    format!("{} has no dateOfBirth to check the age limits", who),
+                )),
+                _ => {}
+            }
+            match player.registration_date.as_ref().map(|d| d.date()) {
+                Some(Some(registered)) if deadline.is_some_and(|d| registered > d) => violations
+                    .push(Violation::new(
+                        "RegisteredAfterDeadline",
+                        format!(
+                            "{} was registered on {}, after the deadline of {}",
+                            who,
+                            registered,
+                            deadline.unwrap_or_default()
+                        ),
+                    )),
+                Some(None) => violations.push(Violation::new(
+                    "InvalidDate",
+                    format!("{} has an invalid registrationDate", who),
+                )),
+                _ => {}
+            }
+        }
+    }
+    violations
+}
+
+fn validate_jersey_numbers(league: &TheLeagueSpec) -> Vec<Violation> {
+    let mut violations = Vec::new();
+    for team in &league.teams {
+        let mut players_of: BTreeMap<u8, Vec<String>> = BTreeMap::new();
+        for player in &team.players {
+            if let Some(number) = player.jersey_number {
+                players_of
+                    .entry(number)
+                    .or_default()
+                    .push(format!("{} {}", player.first_name, player.last_name));
+            }
+        }
+        violations.extend(
+            players_of
+                .into_iter()
+                .filter(|(_, players)| players.len() > 1)
+                .map(|(number, players)| {
+                    Violation::new(
+                        "DuplicateJerseyNumber",
+                        format!(
+                            "jersey number {} is worn by more than one player of team '{}': {}",
+                            number,
+                            team.name,
+                            players.join(", ")
+                        ),
+                    )
+                }),
+        );
+    }
+    violations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::v1alpha1::game_result_types::{
+        Card, CardColor, GameOutcome, PlayerStat, ShootoutScore,
+    };
+    use crate::api::v1alpha1::the_league_types::{
+        Date, Division, Eligibility, Enforcement, Player, RosterLock,
+    };
+
+    fn league(teams: &[&str]) -> TheLeagueSpec {
+        TheLeagueSpec::with_teams(teams).with_one_player_rosters()
+    }
+
+    fn result(home: &str, away: &str) -> GameResultSpec {
+        result_in_round(home, away, 1)
+    }
+
+    fn result_in_round(home: &str, away: &str, round_number: u32) -> GameResultSpec {
+        GameResultSpec::game(home, away).in_round(round_number)
+    }
+
+    #[test]
+    fn test_registered_teams_are_valid() {
+        let violations =
+            validate_game_result(&result("Tigers", "Sharks"), &league(&["Tigers", "Sharks"]));
+        assert!(violations.is_empty());
+    }
+
+    #[test]
+    fn test_unknown_teams_are_rejected() {
+        let violations =
+            validate_game_result(&result("Tigers", "Ghosts"), &league(&["Tigers", "Sharks"]));
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].reason, "UnknownTeam");
+        assert!(violations[0].message.contains("Ghosts"));
+    }
+
+    #[test]
+    fn test_round_must_exist_in_schedule() {
+        let league = league(&["A", "B", "C", "D"]);
+        let violations = validate_game_result(&result_in_round("A", "B", 4), &league);
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].reason, "RoundOutOfRange");
+    }
+
+    #[test]
+    fn test_pairing_must_be_scheduled_in_round() {
+        let league = league(&["A", "B", "C", "D"]);
+        let schedule = schedule::league_schedule(&league).unwrap();
+        let round = schedule.rounds_of_pair("A", "B")[0];
+        let other_round = if round == 1 { 2 } else { 1 };
+
+        assert!(validate_game_result(&result_in_round("B", "A", round), &league).is_empty());
+        let violations = validate_game_result(&result_in_round("A", "B", other_round), &league);
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].reason, "FixtureNotScheduled");
+    }
+
+    #[test]
+    fn test_corrected_round_only_for_lenient_leagues() {
+        let mut league = league(&["A", "B", "C", "D"]);
+        let scheduled = schedule::league_schedule(&league)
+            .unwrap()
+            .rounds_of_pair("A", "B")[0];
+        let wrong = if scheduled == 1 { 2 } else { 1 };
+
+        assert_eq!(
+            corrected_round(&result_in_round("A", "B", wrong), &league),
+            None
+        );
+        league.strict_schedule = false;
+        assert_eq!(
+            corrected_round(&result_in_round("A", "B", wrong), &league),
+            Some(scheduled)
+        );
+        assert_eq!(
+            corrected_round(&result_in_round("A", "B", scheduled), &league),
+            None
+        );
+    }
+
+    #[test]
+    fn test_player_stats_must_reference_rosters() {
+        let league = league(&["Tigers", "Sharks"]);
+        let stat = |team: &str, last_name: &str| PlayerStat {
+            team: team.to_string(),
+            first_name: "Alex".to_string(),
+            last_name: last_name.to_string(),
+            minute: Some(10),
+        };
+
+        let mut game = result("Tigers", "Sharks");
+        game.scorers = vec![stat("Tigers", "Tigers")];
+        game.assists = vec![stat("Sharks", "Sharks")];
+        game.cards = vec![Card {
+            team: "Sharks".to_string(),
+            first_name: "Alex".to_string(),
+            last_name: "Sharks".to_string(),
+            minute: None,
+            color: CardColor::Yellow,
+        }];
+        assert!(validate_game_result(&game, &league).is_empty());
+
+        game.scorers = vec![stat("Tigers", "Sharks"), stat("Eagles", "Eagles")];
+        let violations = validate_game_result(&game, &league);
+        let reasons: Vec<&str> = violations.iter().map(|v| v.reason.as_str()).collect();
+        assert_eq!(reasons, vec!["UnknownPlayer", "PlayerTeamNotInGame"]);
+    }
+
+    #[test]
+    fn test_identical_teams_are_rejected() {
+        let violations = validate_game_result(&result("Tigers", "Tigers"), &league(&["Tigers"]));
+        let reasons: Vec<&str> = violations.iter().map(|v| v.reason.as_str()).collect();
+        assert_eq!(reasons, vec!["IdenticalTeams"]);
+    }
+
+    #[test]
+    fn test_winner_must_have_higher_score() {
+        let league = league(&["Tigers", "Sharks"]);
+        let mut game = result("Tigers", "Sharks");
+
+        game.result = GameOutcome::WinnerHomeTeam {
+            score_home: 2,
+            score_away: 1,
+            decided_by: DecidedBy::Regulation,
+            shootout: None,
+        };
+        assert!(validate_game_result(&game, &league).is_empty());
+
+        game.result = GameOutcome::WinnerHomeTeam {
+            score_home: 1,
+            score_away: 1,
+            decided_by: DecidedBy::Overtime,
+            shootout: None,
+        };
+        let violations = validate_result_consistency(&game);
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].reason, "InconsistentScore");
+
+        game.result = GameOutcome::WinnerAwayTeam {
+            score_home: 3,
+            score_away: 0,
+            decided_by: DecidedBy::Regulation,
+            shootout: None,
+        };
+        let violations = validate_game_result(&game, &league);
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].reason, "InconsistentScore");
+    }
+
+    #[test]
+    fn test_shootout_requires_level_score_and_shootout_winner() {
+        let mut game = result("Tigers", "Sharks");
+        let shootout =
+            |score_home, score_away, shots_home, shots_away| GameOutcome::WinnerAwayTeam {
+                score_home,
+                score_away,
+                decided_by: DecidedBy::Shootout,
+                shootout: Some(ShootoutScore {
+                    home: shots_home,
+                    away: shots_away,
+                }),
+            };
+
+        game.result = shootout(2, 2, 3, 4);
+        assert!(validate_result_consistency(&game).is_empty());
+        game.result = shootout(2, 3, 3, 4);
+        assert_eq!(validate_result_consistency(&game).len(), 1);
+        game.result = shootout(2, 2, 4, 3);
+        assert_eq!(validate_result_consistency(&game).len(), 1);
+    }
+
+    #[test]
+    fn test_summarize_joins_messages() {
+        let violations = vec![
+            Violation::new("UnknownTeam", "first"),
+            Violation::new("Other", "second"),
+        ];
+        assert_eq!(
+            summarize(&violations),
+            Some(("UnknownTeam".to_string(), "first; second".to_string()))
+        );
+        assert_eq!(summarize(&[]), None);
+    }
+
+    #[test]
+    fn test_players_may_only_play_for_one_team() {
+        let mut spec = league(&["Tigers", "Sharks", "Eagles"]);
+        assert!(validate_rosters(&spec).is_empty());
+
+        spec.teams[1].players[0].last_name = "Tigers".to_string();
+        spec.teams[2].players[0].player_ref = Some("sam-lee".to_string());
+        spec.teams[0].players.push(Player {
+            first_name: "Sam".to_string(),
+            last_name: "Lee".to_string(),
+            player_ref: Some("sam-lee".to_string()),
+            position: None,
+            jersey_number: None,
+            date_of_birth: None,
+            registration_date: None,
+        });
+        let violations = validate_rosters(&spec);
+        assert_eq!(violations.len(), 2);
+        assert_eq!(violations[0].reason, "PlayerOnMultipleTeams");
+        assert_eq!(
+            violations[0].message,
+            "Alex Tigers is registered to teams Tigers, Sharks"
+        );
+        assert_eq!(
+            violations[1].message,
+            "Player 'sam-lee' is registered to teams Tigers, Eagles"
+        );
+    }
+
+    #[test]
+    fn test_jersey_numbers_are_unique_within_a_team() {
+        let mut spec = league(&["Tigers", "Sharks"]);
+        spec.teams[0].players[0].jersey_number = Some(9);
+        spec.teams[1].players[0].jersey_number = Some(9);
+        spec.teams[0].players.push(Player {
+            first_name: "Sam".to_string(),
+            last_name: "O'Neil".to_string(),
+            player_ref: None,
+            position: None,
+            jersey_number: Some(10),
+            date_of_birth: None,
+            registration_date: None,
+        });
+        assert!(validate_rosters(&spec).is_empty());
+
+        spec.teams[0].players[1].jersey_number = Some(9);
+        let violations = validate_rosters(&spec);
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].reason, "DuplicateJerseyNumber");
+        assert!(violations[0].message.contains("Alex Tigers, Sam O'Neil"));
+    }
+
+    #[test]
+    fn test_captain_must_be_on_the_roster() {
+        let mut spec = league(&["Tigers", "Sharks"]);
+        spec.teams[0].captain = Some("Alex Tigers".to_string());
+        spec.teams[1].players[0].player_ref = Some("alex-sharks".to_string());
+        spec.teams[1].captain = Some("alex-sharks".to_string());
+        assert!(validate_captains(&spec).is_empty());
+
+        spec.teams[0].players.clear();
+        let violations = validate_captains(&spec);
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].reason, "CaptainNotOnRoster");
+        assert_eq!(
+            violations[0].message,
+            "captain 'Alex Tigers' of team 'Tigers' is not on its roster"
+        );
+    }
+
+    #[test]
+    fn test_divisions_list_registered_teams_once() {
+        let mut spec = league(&["Tigers", "Sharks", "Eagles"]);
+        spec.divisions = vec![
+            Division {
+                name: "North".to_string(),
+                teams: vec!["Tigers".to_string(), "Bears".to_string()],
+            },
+            Division {
+                name: "South".to_string(),
+                teams: vec!["Sharks".to_string(), "Tigers".to_string()],
+            },
+        ];
+        let reasons: Vec<String> = validate_divisions(&spec)
+            .into_iter()
+            .map(|v| v.reason)
+            .collect();
+        assert_eq!(reasons, vec!["UnknownTeam", "TeamInMultipleDivisions"]);
+    }
+
+    #[test]
+    fn test_roster_sizes_are_limited() {
+        let mut spec = league(&["Tigers", "Sharks"]);
+        spec.min_players_per_team = Some(1);
+        spec.max_players_per_team = Some(1);
+        assert!(validate_roster_sizes(&spec).is_empty());
+
+        spec.teams[0].players.clear();
+        let extra = spec.teams[1].players[0].clone();
+        spec.teams[1].players.push(extra);
+        let violations = validate_roster_sizes(&spec);
+        assert_eq!(violations.len(), 2);
+        assert_eq!(
+            violations[0].message,
+            "team 'Tigers' has 0 players, at least 1 are required"
+        );
+        assert_eq!(violations[1].reason, "TooManyPlayers");
+    }
+
+    #[test]
+    fn test_validate_league_follows_the_policy() {
+        let mut spec = league(&["Tigers", "Sharks"]);
+        spec.min_players_per_team = Some(2);
+        let today = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+
+        let (violations, warnings) = validate_league(&spec, &ValidationPolicy::default(), today);
+        assert_eq!(violations.len(), 2);
+        assert!(warnings.is_empty());
+
+        let policy = ValidationPolicy {
+            roster_size: Enforcement::Warn,
+            ..Default::default()
+        };
+        let (violations, warnings) = validate_league(&spec, &policy, today);
+        assert!(violations.is_empty());
+        assert_eq!(warnings[0].reason, "TooFewPlayers");
+    }
+
+    #[test]
+    fn test_teams_are_locked_during_a_round() {
+        let old = league(&["Tigers", "Sharks"]);
+        let mut new = old.clone();
+        new.teams[0].players[0].first_name = "Sam".to_string();
+        assert!(validate_roster_lock(&old, &new, Some(3)).is_empty());
+
+        new.roster_lock = Some(RosterLock::default());
+        assert!(validate_roster_lock(&old, &new, None).is_empty());
+        let violations = validate_roster_lock(&old, &new, Some(3));
+        assert_eq!(violations[0].reason, "RosterLocked");
+        assert!(violations[0].message.contains("round 3"));
+
+        // Changes outside the teams, like the lock itself, are always allowed.
+        assert!(validate_roster_lock(&old, &old.clone(), Some(3)).is_empty());
+
+        new.roster_lock = Some(RosterLock {
+            enforcement: Enforcement::Off,
+        });
+        assert!(validate_roster_lock(&old, &new, Some(3)).is_empty());
+    }
+
+    #[test]
+    fn test_validation_policy_from_configmap_data() {
+        let data = |pairs: &[(&str, &str)]| -> BTreeMap<String, String> {
+            pairs
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect()
+        };
+        assert_eq!(
+            ValidationPolicy::from_data(&data(&[])),
+            Ok(ValidationPolicy::default())
+        );
+
+        let policy =
+            ValidationPolicy::from_data(&data(&[("rosterSize", "Warn"), ("eligibility", "Off")]))
+                .unwrap();
+        assert_eq!(policy.duplicate_result, Enforcement::Enforce);
+        assert_eq!(policy.roster_size, Enforcement::Warn);
+        assert_eq!(policy.eligibility, Enforcement::Off);
+
+        assert!(ValidationPolicy::from_data(&data(&[("rosterSize", "Ignore")])).is_err());
+        assert!(ValidationPolicy::from_data(&data(&[("rosterSizes", "Warn")])).is_err());
+    }
+
+    #[test]
+    fn test_players_must_be_eligible() {
+        let mut spec = league(&["Tigers", "Sharks"]);
+        let today = NaiveDate::from_ymd_opt(2025, 6, 1).unwrap();
+        assert!(validate_eligibility(&spec, today).is_empty());
+
+        spec.eligibility = Some(Eligibility {
+            min_age: Some(18),
+            max_age: Some(23),
+            registration_deadline: Some(Date("2025-03-01".to_string())),
+        });
+        spec.teams[0].players[0].date_of_birth = Some(Date("2007-06-02".to_string()));
+        spec.teams[0].players[0].registration_date = Some(Date("2025-03-02".to_string()));
+        spec.teams[1].players[0].date_of_birth = Some(Date("2007-06-01".to_string()));
+        spec.teams[1].players[0].registration_date = Some(Date("2025-03-01".to_string()));
+        let violations = validate_eligibility(&spec, today);
+        let reasons: Vec<&str> = violations.iter().map(|v| v.reason.as_str()).collect();
+        assert_eq!(reasons, vec!["PlayerTooYoung", "RegisteredAfterDeadline"]);
+        assert_eq!(
+            violations[0].message,
+            "Alex Tigers of team 'Tigers' is 17, the minimum age is 18"
+        );
+
+        spec.teams[1].players[0].date_of_birth = None;
+        assert_eq!(
+            validate_eligibility(&spec, today)[2].reason,
+            "DateOfBirthRequired"
+        );
+    }
+
+    #[test]
+    fn test_knockout_games_follow_the_tiebreak() {
+        let mut game = result("Tigers", "Sharks");
+        assert_eq!(
+            validate_knockout_game(&game, KnockoutTiebreak::Shootout).map(|v| v.reason),
+            Some("KnockoutDraw".to_string())
+        );
+        assert_eq!(
+            validate_knockout_game(&game, KnockoutTiebreak::Replay),
+            None
+        );
+
+        game.result = GameOutcome::WinnerHomeTeam {
+            score_home: 2,
+            score_away: 1,
+            decided_by: DecidedBy::Overtime,
+            shootout: None,
+        };
+        assert_eq!(
+            validate_knockout_game(&game, KnockoutTiebreak::ExtraTimeAndShootout),
+            None
+        );
+        for tiebreak in [KnockoutTiebreak::Shootout, KnockoutTiebreak::Replay] {
+            assert_eq!(
+                validate_knockout_game(&game, tiebreak).map(|v| v.reason),
+                Some("TiebreakNotAllowed".to_string())
+            );
+        }
+    }
+}
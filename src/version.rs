@@ -0,0 +1,70 @@
+//! Build metadata of the controller, served on `/version` of the probe server.
+//!
+//! The git SHA and build timestamp are set by `build.rs`; `GIT_SHA` and `SOURCE_DATE_EPOCH`
+//! override them for builds without the repository.
+
+use crate::api::{v1alpha1, v1alpha2};
+
+use k8s_openapi::chrono::DateTime;
+use kube::Resource;
+use serde::Serialize;
+
+/// Version of the controller crate
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Commit the controller was built from, or `unknown`
+pub const GIT_SHA: &str = env!("GIT_SHA");
+
+/// Build time of the controller in seconds since the Unix epoch
+const BUILD_TIMESTAMP: &str = env!("BUILD_TIMESTAMP");
+
+/// Build of the running controller and the API versions it serves
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildInfo {
+    /// Version of the controller crate
+    pub version: &'static str,
+    /// Commit the controller was built from
+    pub git_sha: &'static str,
+    /// Build time in RFC 3339, or None if unknown
+    pub build_timestamp: Option<String>,
+    /// API versions of the CRDs, oldest first
+    pub api_versions: Vec<String>,
+}
+
+impl BuildInfo {
+    /// Build of this binary
+    pub fn current() -> Self {
+        let build_timestamp = BUILD_TIMESTAMP
+            .parse()
+            .ok()
+            .and_then(|secs| DateTime::from_timestamp(secs, 0))
+            .map(|t| t.to_rfc3339());
+        Self {
+            version: VERSION,
+            git_sha: GIT_SHA,
+            build_timestamp,
+            api_versions: vec![
+                v1alpha1::the_league_types::TheLeague::api_version(&()).into_owned(),
+                v1alpha2::the_league_types::TheLeague::api_version(&()).into_owned(),
+            ],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_info() {
+        let info = BuildInfo::current();
+        assert_eq!(info.version, env!("CARGO_PKG_VERSION"));
+        assert!(!info.git_sha.is_empty());
+        assert!(info.build_timestamp.is_some());
+        assert_eq!(
+            info.api_versions,
+            ["bexxmodd.com/v1alpha1", "bexxmodd.com/v1alpha2"]
+        );
+    }
+}
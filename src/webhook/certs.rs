@@ -0,0 +1,426 @@
+//! Self-signed serving certificates for the webhook server.
+//!
+//! When no serving certificate is mounted, the controller issues its own: a CA and a serving
+//! certificate for the webhook Service, kept in a Secret so every replica serves the same one.
+//! The CA is injected as caBundle into the webhook configurations and the conversion webhooks
+//! of the CRDs, and the serving certificate is renewed well before it expires.
+
+use super::{CONVERTED_CRDS, MUTATING_WEBHOOK_NAME, VALIDATING_WEBHOOK_NAME, WEBHOOK_SERVICE_NAME};
+
+use anyhow::Context as _;
+use axum_server::tls_rustls::RustlsConfig;
+use k8s_openapi::ByteString;
+use k8s_openapi::api::admissionregistration::v1::{
+    MutatingWebhookConfiguration, ValidatingWebhookConfiguration,
+};
+use k8s_openapi::api::core::v1::Secret;
+use k8s_openapi::apiextensions_apiserver::pkg::apis::apiextensions::v1::CustomResourceDefinition;
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+use k8s_openapi::chrono::{DateTime, Datelike, Duration, Utc};
+use kube::api::{Patch, PatchParams, PostParams};
+use kube::{Api, Client};
+use rcgen::{
+    BasicConstraints, Certificate, CertificateParams, DistinguishedName, DnType,
+    ExtendedKeyUsagePurpose, IsCa, KeyPair, KeyUsagePurpose,
+};
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+
+/// Name of the Secret holding the self-signed CA and serving certificate
+pub const CERT_SECRET_NAME: &str = "theleague-webhook-certs";
+
+/// Default directory the self-signed serving certificate is written to. Kept apart from
+/// [`super::DEFAULT_CERT_DIR`], which holds a mounted certificate.
+pub const SELF_SIGNED_CERT_DIR: &str = "/tmp/k8s-webhook-server/self-signed-certs";
+
+/// Annotation of the Secret recording when its serving certificate expires
+const NOT_AFTER_ANNOTATION: &str = "bexxmodd.com/not-after";
+
+/// Annotation of the Secret recording when its CA expires
+const CA_NOT_AFTER_ANNOTATION: &str = "bexxmodd.com/ca-not-after";
+
+/// Validity of the CA
+const CA_VALIDITY_DAYS: i64 = 3650;
+
+/// Validity of a serving certificate
+const CERT_VALIDITY_DAYS: i64 = 365;
+
+/// How long before its expiry a serving certificate is renewed
+const RENEW_BEFORE_DAYS: i64 = 30;
+
+/// How often the serving certificate is checked for renewal
+const CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(3600);
+
+/// A CA and a serving certificate signed by it, all PEM encoded
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CertBundle {
+    pub ca_cert: String,
+    pub ca_key: String,
+    pub ca_not_after: DateTime<Utc>,
+    pub cert: String,
+    pub key: String,
+    pub not_after: DateTime<Utc>,
+}
+
+/// The DNS names the webhook Service is reached by in `namespace`
+pub fn service_dns_names(namespace: &str) -> Vec<String> {
+    vec![
+        WEBHOOK_SERVICE_NAME.to_string(),
+        format!("{}.{}", WEBHOOK_SERVICE_NAME, namespace),
+        format!("{}.{}.svc", WEBHOOK_SERVICE_NAME, namespace),
+        format!("{}.{}.svc.cluster.local", WEBHOOK_SERVICE_NAME, namespace),
+    ]
+}
+
+/// Make `params` valid from the day before `now`, allowing for clock skew, until `not_after`
+fn set_validity(params: &mut CertificateParams, now: DateTime<Utc>, not_after: DateTime<Utc>) {
+    let day = |t: DateTime<Utc>| rcgen::date_time_ymd(t.year(), t.month() as u8, t.day() as u8);
+    params.not_before = day(now - Duration::days(1));
+    params.not_after = day(not_after);
+}
+
+/// Parameters of the CA, valid until `not_after`. The same parameters with the same key sign
+/// serving certificates that verify against a CA issued earlier.
+fn ca_params(
+    now: DateTime<Utc>,
+    not_after: DateTime<Utc>,
+) -> Result<CertificateParams, rcgen::Error> {
+    let mut params = CertificateParams::new(Vec::<String>::new())?;
+    let mut name = DistinguishedName::new();
+    name.push(DnType::CommonName, "theleague-webhook-ca");
+    params.distinguished_name = name;
+    params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+    params.key_usages = vec![KeyUsagePurpose::KeyCertSign, KeyUsagePurpose::CrlSign];
+    set_validity(&mut params, now, not_after);
+    Ok(params)
+}
+
+impl CertBundle {
+    /// Issue a new CA and a serving certificate for the webhook Service in `namespace`
+    pub fn generate(namespace: &str, now: DateTime<Utc>) -> Result<Self, rcgen::Error> {
+        let ca_key = KeyPair::generate()?;
+        let ca_not_after = now + Duration::days(CA_VALIDITY_DAYS);
+        let ca = ca_params(now, ca_not_after)?.self_signed(&ca_key)?;
+        let mut bundle = Self {
+            ca_cert: ca.pem(),
+            ca_key: ca_key.serialize_pem(),
+            ca_not_after,
+            cert: String::new(),
+            key: String::new(),
+            not_after: now,
+        };
+        bundle.issue(&ca, &ca_key, namespace, now)?;
+        Ok(bundle)
+    }
+
+    /// Renew the serving certificate, keeping the CA unless it expires before the new serving
+    /// certificate would
+    pub fn renew(&self, namespace: &str, now: DateTime<Utc>) -> Result<Self, rcgen::Error> {
+        if self.ca_not_after < now + Duration::days(CERT_VALIDITY_DAYS) {
+            return Self::generate(namespace, now);
+        }
+        let ca_key = KeyPair::from_pem(&self.ca_key)?;
+        let ca = ca_params(now, self.ca_not_after)?.self_signed(&ca_key)?;
+        let mut bundle = self.clone();
+        bundle.issue(&ca, &ca_key, namespace, now)?;
+        Ok(bundle)
+    }
+
+    /// Issue the serving certificate signed by `ca`
+    fn issue(
+        &mut self,
+        ca: &Certificate,
+        ca_key: &KeyPair,
+        namespace: &str,
+        now: DateTime<Utc>,
+    ) -> Result<(), rcgen::Error> {
+        let key = KeyPair::generate()?;
+        let mut params = CertificateParams::new(service_dns_names(namespace))?;
+        params.distinguished_name.push(
+            DnType::CommonName,
+            format!("{}.{}.svc", WEBHOOK_SERVICE_NAME, namespace),
+        );
+        params.key_usages = vec![
+            KeyUsagePurpose::DigitalSignature,
+            KeyUsagePurpose::KeyEncipherment,
+        ];
+        params.extended_key_usages = vec![ExtendedKeyUsagePurpose::ServerAuth];
+        self.not_after = now + Duration::days(CERT_VALIDITY_DAYS);
+        set_validity(&mut params, now, self.not_after);
+        self.cert = params.signed_by(&key, ca, ca_key)?.pem();
+        self.key = key.serialize_pem();
+        Ok(())
+    }
+
+    /// Whether the serving certificate is due for renewal at `now`
+    pub fn renewal_due(&self, now: DateTime<Utc>) -> bool {
+        self.not_after - now < Duration::days(RENEW_BEFORE_DAYS)
+    }
+
+    /// The bundle as the data of the Secret named `name` in `namespace`
+    pub fn to_secret(&self, name: &str, namespace: &str) -> Secret {
+        let data = [
+            ("ca.crt", &self.ca_cert),
+            ("ca.key", &self.ca_key),
+            ("tls.crt", &self.cert),
+            ("tls.key", &self.key),
+        ]
+        .into_iter()
+        .map(|(key, pem)| (key.to_string(), ByteString(pem.clone().into_bytes())))
+        .collect();
+        Secret {
+            metadata: ObjectMeta {
+                name: Some(name.to_string()),
+                namespace: Some(namespace.to_string()),
+                annotations: Some(BTreeMap::from([
+                    (
+                        NOT_AFTER_ANNOTATION.to_string(),
+                        self.not_after.to_rfc3339(),
+                    ),
+                    (
+                        CA_NOT_AFTER_ANNOTATION.to_string(),
+                        self.ca_not_after.to_rfc3339(),
+                    ),
+                ])),
+                ..Default::default()
+            },
+            type_: Some("kubernetes.io/tls".to_string()),
+            data: Some(data),
+            ..Default::default()
+        }
+    }
+
+    /// The bundle stored in `secret`, or None when it is incomplete
+    pub fn from_secret(secret: &Secret) -> Option<Self> {
+        let data = secret.data.as_ref()?;
+        let pem = |key: &str| String::from_utf8(data.get(key)?.0.clone()).ok();
+        let annotations = secret.metadata.annotations.as_ref()?;
+        let time = |key: &str| {
+            DateTime::parse_from_rfc3339(annotations.get(key)?)
+                .ok()
+                .map(|t| t.with_timezone(&Utc))
+        };
+        Some(Self {
+            ca_cert: pem("ca.crt")?,
+            ca_key: pem("ca.key")?,
+            ca_not_after: time(CA_NOT_AFTER_ANNOTATION)?,
+            cert: pem("tls.crt")?,
+            key: pem("tls.key")?,
+            not_after: time(NOT_AFTER_ANNOTATION)?,
+        })
+    }
+
+    /// Write the serving certificate and key to `cert_dir` as `tls.crt` and `tls.key`
+    pub fn write_to(&self, cert_dir: &Path) -> std::io::Result<(PathBuf, PathBuf)> {
+        fs::create_dir_all(cert_dir)?;
+        let cert = cert_dir.join("tls.crt");
+        let key = cert_dir.join("tls.key");
+        fs::write(&cert, &self.cert)?;
+        fs::write(&key, &self.key)?;
+        Ok((cert, key))
+    }
+}
+
+/// The current certificate bundle of the Secret in `namespace`, issuing or renewing it when
+/// needed. Written to `cert_dir`, with its CA injected where the API server needs it.
+pub async fn ensure(
+    client: &Client,
+    namespace: &str,
+    cert_dir: &Path,
+) -> anyhow::Result<CertBundle> {
+    let now = Utc::now();
+    let secrets: Api<Secret> = Api::namespaced(client.clone(), namespace);
+    let existing = secrets.get_opt(CERT_SECRET_NAME).await?;
+    let current = existing.as_ref().and_then(CertBundle::from_secret);
+
+    let bundle = match &current {
+        Some(bundle) if !bundle.renewal_due(now) => bundle.clone(),
+        _ => {
+            let next = match &current {
+                Some(bundle) => {
+                    info!(
+                        "Renewing the webhook serving certificate expiring at {}",
+                        bundle.not_after
+                    );
+                    bundle.renew(namespace, now)?
+                }
+                None => {
+                    info!("Issuing a self-signed webhook serving certificate");
+                    CertBundle::generate(namespace, now)?
+                }
+            };
+            let mut secret = next.to_secret(CERT_SECRET_NAME, namespace);
+            let stored = match &existing {
+                Some(existing) => {
+                    secret.metadata.resource_version = existing.metadata.resource_version.clone();
+                    secrets
+                        .replace(CERT_SECRET_NAME, &PostParams::default(), &secret)
+                        .await
+                }
+                None => secrets.create(&PostParams::default(), &secret).await,
+            };
+            match stored {
+                Ok(_) => next,
+                // Another replica stored its certificate first: serve that one.
+                Err(kube::Error::Api(e)) if e.code == 409 => {
+                    let secret = secrets.get(CERT_SECRET_NAME).await?;
+                    CertBundle::from_secret(&secret)
+                        .context("webhook certificate Secret is incomplete")?
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+    };
+
+    bundle.write_to(cert_dir)?;
+    // Trust the previous CA as well while the API server may still hold certificates of it.
+    let mut ca_bundle = bundle.ca_cert.clone();
+    if let Some(previous) = current.filter(|c| c.ca_cert != bundle.ca_cert) {
+        ca_bundle.push_str(&previous.ca_cert);
+    }
+    inject_ca_bundle(client, ca_bundle.as_bytes()).await;
+    Ok(bundle)
+}
+
+/// Set `ca_bundle` as the caBundle of the webhook configurations and of the conversion
+/// webhooks of the CRDs. Resources that don't exist are skipped, and failures are logged: the
+/// API server keeps using the previous caBundle.
+async fn inject_ca_bundle(client: &Client, ca_bundle: &[u8]) {
+    let ca = ByteString(ca_bundle.to_vec());
+
+    let validating: Api<ValidatingWebhookConfiguration> = Api::all(client.clone());
+    match validating.get_opt(VALIDATING_WEBHOOK_NAME).await {
+        Ok(Some(mut config)) => {
+            let webhooks = config.webhooks.iter_mut().flatten();
+            if set_ca_bundles(webhooks.map(|w| &mut w.client_config.ca_bundle), &ca)
+                && let Err(e) = validating
+                    .replace(VALIDATING_WEBHOOK_NAME, &PostParams::default(), &config)
+                    .await
+            {
+                warn!(
+                    "Failed to inject the caBundle into {}: {}",
+                    VALIDATING_WEBHOOK_NAME, e
+                );
+            }
+        }
+        Ok(None) => {}
+        Err(e) => warn!("Failed to get {}: {}", VALIDATING_WEBHOOK_NAME, e),
+    }
+
+    let mutating: Api<MutatingWebhookConfiguration> = Api::all(client.clone());
+    match mutating.get_opt(MUTATING_WEBHOOK_NAME).await {
+        Ok(Some(mut config)) => {
+            let webhooks = config.webhooks.iter_mut().flatten();
+            if set_ca_bundles(webhooks.map(|w| &mut w.client_config.ca_bundle), &ca)
+                && let Err(e) = mutating
+                    .replace(MUTATING_WEBHOOK_NAME, &PostParams::default(), &config)
+                    .await
+            {
+                warn!(
+                    "Failed to inject the caBundle into {}: {}",
+                    MUTATING_WEBHOOK_NAME, e
+                );
+            }
+        }
+        Ok(None) => {}
+        Err(e) => warn!("Failed to get {}: {}", MUTATING_WEBHOOK_NAME, e),
+    }
+
+    let crds: Api<CustomResourceDefinition> = Api::all(client.clone());
+    for name in CONVERTED_CRDS {
+        let patch = Patch::Merge(json!({
+            "spec": { "conversion": { "webhook": { "clientConfig": { "caBundle": ca } } } }
+        }));
+        match crds.get_opt(name).await {
+            Ok(Some(crd)) if conversion_ca_bundle(&crd).is_some_and(|c| c != Some(&ca)) => {
+                if let Err(e) = crds.patch(name, &PatchParams::default(), &patch).await {
+                    warn!("Failed to inject the caBundle into CRD {}: {}", name, e);
+                }
+            }
+            Ok(_) => {}
+            Err(e) => warn!("Failed to get CRD {}: {}", name, e),
+        }
+    }
+}
+
+/// Set every caBundle of `bundles` to `ca`. Returns whether any changed.
+fn set_ca_bundles<'a>(
+    bundles: impl Iterator<Item = &'a mut Option<ByteString>>,
+    ca: &ByteString,
+) -> bool {
+    let mut changed = false;
+    for bundle in bundles {
+        if bundle.as_ref() != Some(ca) {
+            *bundle = Some(ca.clone());
+            changed = true;
+        }
+    }
+    changed
+}
+
+/// The caBundle of the conversion webhook of `crd`; None when it has no conversion webhook
+fn conversion_ca_bundle(crd: &CustomResourceDefinition) -> Option<Option<&ByteString>> {
+    let webhook = crd.spec.conversion.as_ref()?.webhook.as_ref()?;
+    Some(webhook.client_config.as_ref()?.ca_bundle.as_ref())
+}
+
+/// Renew the serving certificate in `cert_dir` before it expires, reloading `config` with the
+/// renewed one. Runs until the process exits.
+pub async fn keep_renewed(
+    client: Client,
+    namespace: String,
+    cert_dir: PathBuf,
+    config: RustlsConfig,
+) {
+    let mut served = None;
+    loop {
+        tokio::time::sleep(CHECK_INTERVAL).await;
+        match ensure(&client, &namespace, &cert_dir).await {
+            Ok(bundle) if served.as_ref() != Some(&bundle.cert) => {
+                let (cert, key) = (cert_dir.join("tls.crt"), cert_dir.join("tls.key"));
+                match config.reload_from_pem_file(cert, key).await {
+                    Ok(()) => served = Some(bundle.cert),
+                    Err(e) => warn!("Failed to reload the webhook serving certificate: {}", e),
+                }
+            }
+            Ok(_) => {}
+            Err(e) => warn!("Failed to renew the webhook serving certificate: {}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serving_certificate_is_renewed_under_the_same_ca() {
+        let now = Utc::now();
+        let bundle = CertBundle::generate("league-system", now).unwrap();
+        assert!(bundle.cert.starts_with("-----BEGIN CERTIFICATE-----"));
+        assert!(!bundle.renewal_due(now));
+        let due = now + Duration::days(CERT_VALIDITY_DAYS - RENEW_BEFORE_DAYS + 1);
+        assert!(bundle.renewal_due(due));
+
+        let secret = bundle.to_secret(CERT_SECRET_NAME, "league-system");
+        assert_eq!(CertBundle::from_secret(&secret).unwrap(), bundle);
+
+        let renewed = bundle.renew("league-system", due).unwrap();
+        assert_eq!(renewed.ca_cert, bundle.ca_cert);
+        assert_ne!(renewed.cert, bundle.cert);
+        assert!(!renewed.renewal_due(due));
+
+        // A CA expiring before the renewed certificate would is replaced as well.
+        let expiring_ca = CertBundle {
+            ca_not_after: due + Duration::days(10),
+            ..bundle
+        };
+        assert_ne!(
+            expiring_ca.renew("league-system", due).unwrap().ca_cert,
+            expiring_ca.ca_cert
+        );
+    }
+}
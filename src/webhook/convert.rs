@@ -0,0 +1,202 @@
+use crate::api::v1alpha1;
+use crate::api::v1alpha2;
+use crate::api::v1alpha2::game_result_types::ConversionError;
+
+use axum::Json;
+use kube::core::Status;
+use kube::core::conversion::{ConversionRequest, ConversionResponse, ConversionReview};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use tracing::warn;
+
+const V1ALPHA1: &str = "bexxmodd.com/v1alpha1";
+const V1ALPHA2: &str = "bexxmodd.com/v1alpha2";
+
+/// Convert `object` into `desired_api_version`. Objects already in that version are returned
+/// unchanged.
+pub fn convert_object(object: Value, desired_api_version: &str) -> Result<Value, String> {
+    let api_version = object["apiVersion"].as_str().unwrap_or_default();
+    if api_version == desired_api_version {
+        return Ok(object);
+    }
+    let kind = object["kind"].as_str().unwrap_or_default().to_string();
+    match (kind.as_str(), api_version, desired_api_version) {
+        ("TheLeague", V1ALPHA1, V1ALPHA2) => convert::<
+            v1alpha1::the_league_types::TheLeague,
+            v1alpha2::the_league_types::TheLeague,
+        >(object, |league| Ok(league.into())),
+        ("TheLeague", V1ALPHA2, V1ALPHA1) => convert::<
+            v1alpha2::the_league_types::TheLeague,
+            v1alpha1::the_league_types::TheLeague,
+        >(object, |league| Ok(league.into())),
+        ("Standing", V1ALPHA1, V1ALPHA2) => convert::<
+            v1alpha1::standing_types::Standing,
+            v1alpha2::standing_types::Standing,
+        >(object, |standing| Ok(standing.into())),
+        ("Standing", V1ALPHA2, V1ALPHA1) => convert::<
+            v1alpha2::standing_types::Standing,
+            v1alpha1::standing_types::Standing,
+        >(object, |standing| Ok(standing.into())),
+        ("GameResult", V1ALPHA1, V1ALPHA2) => convert::<
+            v1alpha1::game_result_types::GameResult,
+            v1alpha2::game_result_types::GameResult,
+        >(object, |result| Ok(result.into())),
+        ("GameResult", V1ALPHA2, V1ALPHA1) => convert::<
+            v1alpha2::game_result_types::GameResult,
+            v1alpha1::game_result_types::GameResult,
+        >(object, |result| {
+            result
+                .try_into()
+                .map_err(|e: ConversionError| e.to_string())
+        }),
+        _ => Err(format!(
+            "cannot convert {} from {} to {}",
+            kind, api_version, desired_api_version
+        )),
+    }
+}
+
+/// Deserialize `object` as an `S`, convert it with `conversion` and serialize the result
+fn convert<S, T>(
+    object: Value,
+    conversion: impl FnOnce(S) -> Result<T, String>,
+) -> Result<Value, String>
+where
+    S: DeserializeOwned,
+    T: Serialize,
+{
+    let from: S = serde_json::from_value(object).map_err(|e| e.to_string())?;
+    serde_json::to_value(conversion(from)?).map_err(|e| e.to_string())
+}
+
+/// Convert the objects of a conversion request. A single object that can't be converted fails
+/// the whole request, as the API server requires every object back.
+pub fn review_conversion(request: ConversionRequest) -> ConversionResponse {
+    let desired_api_version = request.desired_api_version.clone();
+    let objects = request.objects.clone();
+    let response = ConversionResponse::for_request(request);
+    match objects
+        .into_iter()
+        .map(|object| convert_object(object, &desired_api_version))
+        .collect()
+    {
+        Ok(converted) => response.success(converted),
+        Err(message) => {
+            warn!("Conversion to {} failed: {}", desired_api_version, message);
+            response.failure(Status::failure(&message, "ConversionFailed"))
+        }
+    }
+}
+
+pub(super) async fn convert_review(Json(review): Json<ConversionReview>) -> Json<ConversionReview> {
+    let request = match ConversionRequest::from_review(review) {
+        Ok(request) => request,
+        Err(e) => {
+            warn!("Invalid conversion review: {}", e);
+            let status = Status::failure(&e.to_string(), "InvalidConversionReview");
+            return Json(ConversionResponse::invalid(status).into_review());
+        }
+    };
+    Json(review_conversion(request).into_review())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn review(desired_api_version: &str, objects: Vec<Value>) -> ConversionRequest {
+        let review: ConversionReview = serde_json::from_value(json!({
+            "apiVersion": "apiextensions.k8s.io/v1",
+            "kind": "ConversionReview",
+            "request": {
+                "uid": "8e4d7c1b-2a3f-4b5c-9d6e-7f8a9b0c1d2e",
+                "desiredAPIVersion": desired_api_version,
+                "objects": objects,
+            }
+        }))
+        .unwrap();
+        ConversionRequest::from_review(review).unwrap()
+    }
+
+    fn game_result(api_version: &str, spec: Value) -> Value {
+        json!({
+            "apiVersion": api_version,
+            "kind": "GameResult",
+            "metadata": {"name": "round-1", "namespace": "default", "uid": "result-uid"},
+            "spec": spec,
+        })
+    }
+
+    #[test]
+    fn test_objects_convert_both_ways() {
+        let league = json!({
+            "apiVersion": V1ALPHA1,
+            "kind": "TheLeague",
+            "metadata": {"name": "premier", "namespace": "default"},
+            "spec": {"maxTeams": 4, "teams": [{"name": "Tigers", "location": "Tbilisi"}]},
+        });
+        let result = game_result(
+            V1ALPHA1,
+            json!({
+                "leagueName": "premier",
+                "roundNumber": 1,
+                "teams": ["Tigers", "Sharks"],
+                "time": "2025-01-01T12:00:00Z",
+                "result": {"Draw": {"score": 1}},
+            }),
+        );
+
+        let standing = json!({
+            "apiVersion": V1ALPHA1,
+            "kind": "Standing",
+            "metadata": {"name": "premier-tigers", "namespace": "default"},
+            "spec": {"leagueName": "premier", "teamName": "Tigers", "resolution": "Head2Head"},
+            "status": {"points": 3, "wins": 1, "losses": 0, "draws": 0},
+        });
+
+        let objects = vec![league.clone(), standing.clone(), result.clone()];
+        let response = review_conversion(review(V1ALPHA2, objects));
+        assert_eq!(
+            response.result.status,
+            Some(kube::core::response::StatusSummary::Success)
+        );
+        let [league2, standing2, result2] =
+            <[Value; 3]>::try_from(response.converted_objects).unwrap();
+        assert_eq!(league2["apiVersion"], V1ALPHA2);
+        assert_eq!(league2["spec"]["teams"][0]["homeVenue"], "Tbilisi");
+        assert_eq!(standing2["spec"]["resolution"], "headToHead");
+        assert_eq!(standing2["status"]["points"], 3);
+        assert_eq!(result2["spec"]["score"]["home"], 1);
+        assert_eq!(result2["metadata"]["uid"], "result-uid");
+
+        let response = review_conversion(review(V1ALPHA1, vec![league2, standing2, result2]));
+        let [league1, standing1, result1] =
+            <[Value; 3]>::try_from(response.converted_objects).unwrap();
+        assert_eq!(league1["spec"]["teams"][0]["location"], "Tbilisi");
+        assert_eq!(standing1["spec"], standing["spec"]);
+        assert_eq!(result1["spec"]["result"], result["spec"]["result"]);
+    }
+
+    #[test]
+    fn test_unconvertible_object_fails_the_request() {
+        let result = game_result(
+            V1ALPHA2,
+            json!({
+                "leagueName": "premier",
+                "roundNumber": 1,
+                "teams": ["Tigers", "Sharks"],
+                "time": "2025-01-01T12:00:00Z",
+                "score": {"home": 1, "away": 0},
+                "interruption": {"postponed": {}},
+            }),
+        );
+        let response = review_conversion(review(V1ALPHA1, vec![result]));
+        assert_eq!(response.result.reason, "ConversionFailed");
+        assert!(response.converted_objects.is_empty());
+
+        let team = json!({"apiVersion": V1ALPHA1, "kind": "Team", "metadata": {}});
+        assert!(convert_object(team, V1ALPHA2).is_err());
+    }
+}
@@ -0,0 +1,251 @@
+//! Metrics and decision logs of the admission webhooks.
+//!
+//! Every validating review is counted per rule it tripped, so alerts can fire on spikes in
+//! denials of a single rule, and every request is timed per webhook path.
+
+use crate::validation::Violation;
+
+use axum::extract::{MatchedPath, Request};
+use axum::middleware::Next;
+use axum::response::Response;
+use kube::Resource;
+use kube::core::admission::{AdmissionRequest, AdmissionResponse};
+use prometheus_client::encoding::{EncodeLabelSet, EncodeLabelValue};
+use prometheus_client::metrics::counter::Counter;
+use prometheus_client::metrics::family::Family;
+use prometheus_client::metrics::histogram::{Histogram, exponential_buckets};
+use prometheus_client::registry::Registry;
+use std::time::Instant;
+use tracing::{debug, info};
+
+/// Rule label of reviews that tripped no rule
+const NO_RULE: &str = "none";
+
+/// Decision of a validating webhook on a rule
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, EncodeLabelValue)]
+pub enum Decision {
+    Allowed,
+    Denied,
+    Warned,
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+pub struct DecisionLabels {
+    pub kind: String,
+    pub rule: String,
+    pub decision: Decision,
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+pub struct LatencyLabels {
+    pub path: String,
+}
+
+/// Admission decisions per rule and request latencies per webhook path
+#[derive(Clone)]
+pub struct WebhookMetrics {
+    pub decisions: Family<DecisionLabels, Counter>,
+    pub latency: Family<LatencyLabels, Histogram>,
+}
+
+impl Default for WebhookMetrics {
+    fn default() -> Self {
+        Self {
+            decisions: Family::default(),
+            // 1ms up to about 4s, the API server gives up on a webhook after 10s by default.
+            latency: Family::new_with_constructor(|| {
+                Histogram::new(exponential_buckets(0.001, 2.0, 13))
+            }),
+        }
+    }
+}
+
+impl WebhookMetrics {
+    pub fn register(&self, registry: &mut Registry) {
+        registry.register(
+            "webhook_decisions",
+            "Admission decisions of the validating webhooks per rule",
+            self.decisions.clone(),
+        );
+        registry.register(
+            "webhook_request_duration_seconds",
+            "Duration of the webhook requests per path",
+            self.latency.clone(),
+        );
+    }
+
+    fn count(&self, kind: &str, rule: &str, decision: Decision) {
+        self.decisions
+            .get_or_create(&DecisionLabels {
+                kind: kind.to_string(),
+                rule: rule.to_string(),
+                decision,
+            })
+            .inc();
+    }
+}
+
+/// Time the webhook requests per matched path
+pub(super) async fn track_latency(request: Request, next: Next) -> Response {
+    let path = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|p| p.as_str().to_string())
+        .unwrap_or_else(|| request.uri().path().to_string());
+    let start = Instant::now();
+    let response = next.run(request).await;
+    crate::metrics::metrics()
+        .webhook
+        .latency
+        .get_or_create(&LatencyLabels { path })
+        .observe(start.elapsed().as_secs_f64());
+    response
+}
+
+/// Answer a validating review: deny with the `violations` if there are any, or allow with
+/// the `warnings`, given as the rule and the warning shown to the client. The decision is
+/// logged with the request UID and counted per rule.
+pub(super) fn decide<T: Resource>(
+    request: &AdmissionRequest<T>,
+    violations: &[Violation],
+    warnings: Vec<(String, String)>,
+) -> AdmissionResponse {
+    decide_with(
+        &crate::metrics::metrics().webhook,
+        request,
+        violations,
+        warnings,
+    )
+}
+
+fn decide_with<T: Resource>(
+    metrics: &WebhookMetrics,
+    request: &AdmissionRequest<T>,
+    violations: &[Violation],
+    warnings: Vec<(String, String)>,
+) -> AdmissionResponse {
+    let mut response = AdmissionResponse::from(request);
+    let kind = request.kind.kind.as_str();
+    let namespace = request.namespace.as_deref().unwrap_or_default();
+    if let Some((reason, message)) = crate::validation::summarize(violations) {
+        let mut rules: Vec<&str> = violations.iter().map(|v| v.reason.as_str()).collect();
+        rules.sort_unstable();
+        rules.dedup();
+        for rule in &rules {
+            metrics.count(kind, rule, Decision::Denied);
+        }
+        info!(
+            uid = %request.uid,
+            namespace,
+            name = %request.name,
+            rule = %reason,
+            rules = %rules.join(","),
+            dry_run = request.dry_run,
+            "Denied {:?} of {} '{}'{}: {}",
+            request.operation,
+            kind,
+            request.name,
+            super::dry_run_note(request.dry_run),
+            message
+        );
+        return response.deny(format!("{}: {}", reason, message));
+    }
+
+    if warnings.is_empty() {
+        metrics.count(kind, NO_RULE, Decision::Allowed);
+        debug!(
+            uid = %request.uid,
+            namespace,
+            name = %request.name,
+            "Allowed {:?} of {} '{}'",
+            request.operation,
+            kind,
+            request.name
+        );
+        return response;
+    }
+    for (rule, warning) in &warnings {
+        metrics.count(kind, rule, Decision::Warned);
+        info!(
+            uid = %request.uid,
+            namespace,
+            name = %request.name,
+            rule = %rule,
+            dry_run = request.dry_run,
+            "Warned on {:?} of {} '{}': {}",
+            request.operation,
+            kind,
+            request.name,
+            warning
+        );
+    }
+    response.warnings = Some(warnings.into_iter().map(|(_, warning)| warning).collect());
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::v1alpha1::the_league_types::TheLeague;
+    use kube::core::admission::AdmissionReview;
+    use serde_json::json;
+
+    fn request() -> AdmissionRequest<TheLeague> {
+        let review: AdmissionReview<TheLeague> = serde_json::from_value(json!({
+            "apiVersion": "admission.k8s.io/v1",
+            "kind": "AdmissionReview",
+            "request": {
+                "uid": "6c3b3a0e-8a55-4b8e-9f0e-0f6a4f6c2b13",
+                "kind": {"group": "bexxmodd.com", "version": "v1alpha1", "kind": "TheLeague"},
+                "resource": {"group": "bexxmodd.com", "version": "v1alpha1", "resource": "theleagues"},
+                "name": "premier",
+                "namespace": "default",
+                "operation": "CREATE",
+                "userInfo": {},
+            }
+        }))
+        .unwrap();
+        review.try_into().unwrap()
+    }
+
+    fn count(metrics: &WebhookMetrics, rule: &str, decision: Decision) -> u64 {
+        metrics
+            .decisions
+            .get_or_create(&DecisionLabels {
+                kind: "TheLeague".to_string(),
+                rule: rule.to_string(),
+                decision,
+            })
+            .get()
+    }
+
+    #[test]
+    fn test_decisions_are_counted_per_rule() {
+        let metrics = WebhookMetrics::default();
+        let request = request();
+
+        assert!(decide_with(&metrics, &request, &[], Vec::new()).allowed);
+        assert_eq!(count(&metrics, NO_RULE, Decision::Allowed), 1);
+
+        let violations = [
+            Violation::new("DuplicateJerseyNumber", "first"),
+            Violation::new("CaptainNotOnRoster", "second"),
+        ];
+        let response = decide_with(&metrics, &request, &violations, Vec::new());
+        assert!(!response.allowed);
+        assert_eq!(
+            count(&metrics, "DuplicateJerseyNumber", Decision::Denied),
+            1
+        );
+        assert_eq!(count(&metrics, "CaptainNotOnRoster", Decision::Denied), 1);
+
+        let warnings = vec![(
+            "RosterLocked".to_string(),
+            "RosterLocked: round 2".to_string(),
+        )];
+        let response = decide_with(&metrics, &request, &[], warnings);
+        assert_eq!(response.warnings.unwrap(), ["RosterLocked: round 2"]);
+        assert_eq!(count(&metrics, "RosterLocked", Decision::Warned), 1);
+        assert_eq!(count(&metrics, NO_RULE, Decision::Allowed), 1);
+    }
+}
@@ -0,0 +1,209 @@
+//! Admission webhooks for league resources.
+//!
+//! The webhooks only deny on checks that need no API access; everything depending on other
+//! objects is left to the reconcilers, which report it through status conditions. Lookups,
+//! like the Suspensions of a league, only ever produce warnings. The roster lock and duplicate
+//! results are the exceptions: team changes are denied while the Matches of the league show a
+//! round in progress, and new results for a fixture that already has one are denied.
+//!
+//! The validation policy ConfigMap sets the duplicate result, roster size and eligibility
+//! rules to Enforce, Warn or Off for the webhooks and the reconcilers alike.
+//!
+//! None of the webhooks has side effects, so they're registered with `sideEffects: None` and
+//! dry-run requests (`kubectl apply --dry-run=server`) are reviewed exactly like real ones.
+//!
+//! The decisions of the validating webhooks are counted per rule and the latency of every
+//! webhook per path; both are served on `/metrics` of the metrics server.
+//!
+//! The mutating webhooks fill in defaults, normalize team names and stamp creation annotations
+//! on the object as submitted, so the reconcilers have less to fix up afterwards. The
+//! conversion webhook converts TheLeagues and GameResults between the served API versions.
+
+pub mod certs;
+mod convert;
+pub mod metrics;
+mod mutate;
+mod validate;
+
+pub use convert::{convert_object, review_conversion};
+pub use mutate::{
+    CREATED_AT_ANNOTATION, CREATED_BY_ANNOTATION, mutate_game_result, mutate_league,
+    mutate_standing, normalize_team_name,
+};
+pub use validate::{review_game_result, review_league};
+
+use axum::{Router, middleware, routing::post};
+use axum_server::tls_rustls::RustlsConfig;
+use kube::Client;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use tracing::info;
+
+/// Path the GameResult validating webhook is served on
+pub const VALIDATE_GAMERESULT_PATH: &str = "/validate-bexxmodd-com-v1alpha1-gameresult";
+
+/// Path the TheLeague validating webhook is served on
+pub const VALIDATE_THELEAGUE_PATH: &str = "/validate-bexxmodd-com-v1alpha1-theleague";
+
+/// Path the GameResult mutating webhook is served on
+pub const MUTATE_GAMERESULT_PATH: &str = "/mutate-bexxmodd-com-v1alpha1-gameresult";
+
+/// Path the Standing mutating webhook is served on
+pub const MUTATE_STANDING_PATH: &str = "/mutate-bexxmodd-com-v1alpha1-standing";
+
+/// Path the TheLeague mutating webhook is served on
+pub const MUTATE_THELEAGUE_PATH: &str = "/mutate-bexxmodd-com-v1alpha1-theleague";
+
+/// Path the conversion webhook is served on
+pub const CONVERT_PATH: &str = "/convert";
+
+/// Name of the Service fronting the webhook server
+pub const WEBHOOK_SERVICE_NAME: &str = "theleague-webhook-service";
+
+/// Name of the ValidatingWebhookConfiguration of the webhooks
+pub const VALIDATING_WEBHOOK_NAME: &str = "theleague-validating-webhook-configuration";
+
+/// Name of the MutatingWebhookConfiguration of the webhooks
+pub const MUTATING_WEBHOOK_NAME: &str = "theleague-mutating-webhook-configuration";
+
+/// CRDs whose versions are converted by the conversion webhook
+pub const CONVERTED_CRDS: [&str; 3] = [
+    "theleagues.bexxmodd.com",
+    "standings.bexxmodd.com",
+    "gameresults.bexxmodd.com",
+];
+
+/// Default namespace the controller and its webhook Service are deployed to
+pub const DEFAULT_NAMESPACE: &str = "theleague-system";
+
+/// Default directory of the serving certificate (`tls.crt` and `tls.key`),
+/// the same location controller-runtime uses.
+pub const DEFAULT_CERT_DIR: &str = "/tmp/k8s-webhook-server/serving-certs";
+
+/// Port the webhook server listens on by default, targeted by the webhook Service
+pub const WEBHOOK_PORT: u16 = 9443;
+
+/// An admission webhook endpoint served by [`router`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AdmissionEndpoint {
+    /// Kind of the admitted resource
+    pub kind: &'static str,
+    /// Plural name of the admitted resource
+    pub resource: &'static str,
+    /// Path the endpoint is served on
+    pub path: &'static str,
+    /// Operations sent to the endpoint
+    pub operations: &'static [&'static str],
+}
+
+/// Validating webhook endpoints served by [`router`]
+pub const VALIDATING_ENDPOINTS: [AdmissionEndpoint; 2] = [
+    AdmissionEndpoint {
+        kind: "GameResult",
+        resource: "gameresults",
+        path: VALIDATE_GAMERESULT_PATH,
+        operations: &["CREATE", "UPDATE"],
+    },
+    AdmissionEndpoint {
+        kind: "TheLeague",
+        resource: "theleagues",
+        path: VALIDATE_THELEAGUE_PATH,
+        operations: &["CREATE", "UPDATE"],
+    },
+];
+
+/// Mutating webhook endpoints served by [`router`]
+pub const MUTATING_ENDPOINTS: [AdmissionEndpoint; 3] = [
+    AdmissionEndpoint {
+        kind: "GameResult",
+        resource: "gameresults",
+        path: MUTATE_GAMERESULT_PATH,
+        operations: &["CREATE", "UPDATE"],
+    },
+    AdmissionEndpoint {
+        kind: "Standing",
+        resource: "standings",
+        path: MUTATE_STANDING_PATH,
+        operations: &["CREATE", "UPDATE"],
+    },
+    AdmissionEndpoint {
+        kind: "TheLeague",
+        resource: "theleagues",
+        path: MUTATE_THELEAGUE_PATH,
+        operations: &["CREATE", "UPDATE"],
+    },
+];
+
+/// Router with all webhook endpoints
+pub fn router(client: Client) -> Router {
+    Router::new()
+        .route(
+            VALIDATE_GAMERESULT_PATH,
+            post(validate::validate_game_result),
+        )
+        .route(VALIDATE_THELEAGUE_PATH, post(validate::validate_league))
+        .route(
+            MUTATE_GAMERESULT_PATH,
+            post(mutate::mutate_game_result_review),
+        )
+        .route(MUTATE_STANDING_PATH, post(mutate::mutate_standing_review))
+        .route(MUTATE_THELEAGUE_PATH, post(mutate::mutate_league_review))
+        .route(CONVERT_PATH, post(convert::convert_review))
+        .route_layer(middleware::from_fn(metrics::track_latency))
+        .with_state(client)
+}
+
+/// Suffix marking the logs of dry-run reviews, whose objects are never persisted
+fn dry_run_note(dry_run: bool) -> &'static str {
+    if dry_run { " (dry run)" } else { "" }
+}
+
+/// Certificate and key file of the serving certificate in `cert_dir`,
+/// or None if either is missing.
+pub fn serving_cert(cert_dir: &Path) -> Option<(PathBuf, PathBuf)> {
+    let cert = cert_dir.join("tls.crt");
+    let key = cert_dir.join("tls.key");
+    (cert.is_file() && key.is_file()).then_some((cert, key))
+}
+
+/// Serve the webhooks over HTTPS on `addr` until the process exits.
+pub async fn serve(
+    addr: SocketAddr,
+    cert: &Path,
+    key: &Path,
+    client: Client,
+) -> anyhow::Result<()> {
+    // Idempotent: fails only when a provider was already installed.
+    let _ = rustls::crypto::ring::default_provider().install_default();
+    let config = RustlsConfig::from_pem_file(cert, key).await?;
+    serve_with(addr, config, client).await
+}
+
+/// Serve the webhooks over HTTPS on `addr` with a self-signed certificate until the process
+/// exits. The certificate is issued or loaded from its Secret in `namespace`, written to
+/// `cert_dir` and renewed before it expires.
+pub async fn serve_self_signed(
+    addr: SocketAddr,
+    namespace: &str,
+    cert_dir: &Path,
+    client: Client,
+) -> anyhow::Result<()> {
+    let _ = rustls::crypto::ring::default_provider().install_default();
+    let bundle = certs::ensure(&client, namespace, cert_dir).await?;
+    let config = RustlsConfig::from_pem(bundle.cert.into_bytes(), bundle.key.into_bytes()).await?;
+    tokio::spawn(certs::keep_renewed(
+        client.clone(),
+        namespace.to_string(),
+        cert_dir.to_path_buf(),
+        config.clone(),
+    ));
+    serve_with(addr, config, client).await
+}
+
+async fn serve_with(addr: SocketAddr, config: RustlsConfig, client: Client) -> anyhow::Result<()> {
+    info!("Webhook server listening on {}", addr);
+    axum_server::bind_rustls(addr, config)
+        .serve(router(client).into_make_service())
+        .await?;
+    Ok(())
+}
@@ -0,0 +1,395 @@
+use crate::api::v1alpha1::game_result_types::GameResult;
+use crate::api::v1alpha1::standing_types::StandingResolution;
+use crate::api::v1alpha1::the_league_types::{PointsPolicy, TheLeague};
+use crate::controller::references;
+
+use axum::Json;
+use axum::extract::State;
+use k8s_openapi::chrono::{SecondsFormat, Utc};
+use kube::core::DynamicObject;
+use kube::core::admission::{AdmissionRequest, AdmissionResponse, AdmissionReview, Operation};
+use kube::{Api, Client, Resource};
+use serde_json::{Value, json};
+use tracing::warn;
+
+/// Annotation recording the user that created a league resource
+pub const CREATED_BY_ANNOTATION: &str = "bexxmodd.com/created-by";
+
+/// Annotation recording when a league resource was created
+pub const CREATED_AT_ANNOTATION: &str = "bexxmodd.com/created-at";
+
+/// `name` with surrounding whitespace trimmed and inner runs of whitespace collapsed to a
+/// single space
+pub fn normalize_team_name(name: &str) -> String {
+    name.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// JSON patch operations mutating an object as it was submitted
+#[derive(Debug, Default)]
+struct Mutations(Vec<Value>);
+
+impl Mutations {
+    fn add(&mut self, path: &str, value: Value) {
+        self.0
+            .push(json!({ "op": "add", "path": path, "value": value }));
+    }
+
+    /// Set the field at `path` of `object` to `value` when it is missing
+    fn default_to(&mut self, object: &Value, path: &str, value: Value) {
+        if object.pointer(path).is_none() {
+            self.add(path, value);
+        }
+    }
+
+    /// Normalize the team name at `path` of `object`, if there is one
+    fn normalize_team_name(&mut self, object: &Value, path: &str) {
+        let Some(name) = object.pointer(path).and_then(Value::as_str) else {
+            return;
+        };
+        let normalized = normalize_team_name(name);
+        if normalized != name {
+            self.0
+                .push(json!({ "op": "replace", "path": path, "value": normalized }));
+        }
+    }
+
+    /// Normalize every team name at `field` of the items of the array at `path` of `object`;
+    /// an empty `field` normalizes the items themselves
+    fn normalize_team_names(&mut self, object: &Value, path: &str, field: &str) {
+        let count = object
+            .pointer(path)
+            .and_then(Value::as_array)
+            .map_or(0, Vec::len);
+        for i in 0..count {
+            self.normalize_team_name(object, &format!("{}/{}{}", path, i, field));
+        }
+    }
+
+    /// Record who created `object` and when, on creation only
+    fn stamp_creation<K>(&mut self, request: &AdmissionRequest<K>, object: &Value)
+    where
+        K: Resource,
+    {
+        if request.operation != Operation::Create {
+            return;
+        }
+        let mut stamps = vec![(
+            CREATED_AT_ANNOTATION,
+            Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true),
+        )];
+        if let Some(user) = &request.user_info.username {
+            stamps.push((CREATED_BY_ANNOTATION, user.clone()));
+        }
+        if object.pointer("/metadata/annotations").is_none() {
+            let annotations: serde_json::Map<_, _> = stamps
+                .into_iter()
+                .map(|(key, value)| (key.to_string(), json!(value)))
+                .collect();
+            self.add("/metadata/annotations", Value::Object(annotations));
+            return;
+        }
+        for (key, value) in stamps {
+            let key = key.replace('~', "~0").replace('/', "~1");
+            self.add(&format!("/metadata/annotations/{}", key), json!(value));
+        }
+    }
+
+    /// `response` carrying the mutations as a JSON patch, if there are any
+    fn into_response(self, response: AdmissionResponse) -> AdmissionResponse {
+        if self.0.is_empty() {
+            return response;
+        }
+        let patch: json_patch::Patch = match serde_json::from_value(Value::Array(self.0)) {
+            Ok(patch) => patch,
+            Err(e) => return AdmissionResponse::invalid(e.to_string()),
+        };
+        match response.with_patch(patch) {
+            Ok(response) => response,
+            Err(e) => AdmissionResponse::invalid(e.to_string()),
+        }
+    }
+}
+
+/// The object of `request` as submitted, or None for deletions
+fn submitted(request: &AdmissionRequest<DynamicObject>) -> Option<Value> {
+    request
+        .object
+        .as_ref()
+        .and_then(|o| serde_json::to_value(o).ok())
+}
+
+/// Mutate a TheLeague on admission: fill in the default points policy, normalize the team
+/// names of the teams and divisions and stamp the creation annotations. Team names are only
+/// normalized on creation, like those of a result, so an update never renames a team its
+/// results and standings refer to.
+pub fn mutate_league(request: &AdmissionRequest<DynamicObject>) -> AdmissionResponse {
+    let response = AdmissionResponse::from(request);
+    let Some(object) = submitted(request) else {
+        return response;
+    };
+    let mut mutations = Mutations::default();
+    mutations.default_to(
+        &object,
+        "/spec/pointsPolicy",
+        json!(PointsPolicy::default()),
+    );
+    if request.operation == Operation::Create {
+        mutations.normalize_team_names(&object, "/spec/teams", "/name");
+        let divisions = object
+            .pointer("/spec/divisions")
+            .and_then(Value::as_array)
+            .map_or(0, Vec::len);
+        for i in 0..divisions {
+            mutations.normalize_team_names(&object, &format!("/spec/divisions/{}/teams", i), "");
+        }
+    }
+    mutations.stamp_creation(request, &object);
+    mutations.into_response(response)
+}
+
+/// Mutate a Standing on admission: fill in the default resolution and stamp the creation
+/// annotations.
+pub fn mutate_standing(request: &AdmissionRequest<DynamicObject>) -> AdmissionResponse {
+    let response = AdmissionResponse::from(request);
+    let Some(object) = submitted(request) else {
+        return response;
+    };
+    let mut mutations = Mutations::default();
+    mutations.default_to(
+        &object,
+        "/spec/resolution",
+        json!(StandingResolution::default()),
+    );
+    mutations.stamp_creation(request, &object);
+    mutations.into_response(response)
+}
+
+/// Mutate a GameResult on admission: make it owned by its `league`, when it was found in the
+/// namespace of the result, and stamp the creation annotations. Team names are only
+/// normalized on creation, as the spec of a result counted in the standings is immutable.
+pub fn mutate_game_result(
+    request: &AdmissionRequest<DynamicObject>,
+    league: Option<&TheLeague>,
+) -> AdmissionResponse {
+    let response = AdmissionResponse::from(request);
+    let Some(object) = submitted(request) else {
+        return response;
+    };
+    let mut mutations = Mutations::default();
+    if request.operation == Operation::Create {
+        mutations.normalize_team_names(&object, "/spec/teams", "");
+        for stats in ["scorers", "assists", "cards"] {
+            mutations.normalize_team_names(&object, &format!("/spec/{}", stats), "/team");
+        }
+    }
+    if let Some(owner) = league.and_then(|l| l.owner_ref(&())) {
+        match object
+            .pointer("/metadata/ownerReferences")
+            .and_then(Value::as_array)
+        {
+            Some(owners) if owners.iter().any(|o| o["uid"] == owner.uid.as_str()) => {}
+            Some(_) => mutations.add("/metadata/ownerReferences/-", json!(owner)),
+            None => mutations.add("/metadata/ownerReferences", json!([owner])),
+        }
+    }
+    mutations.stamp_creation(request, &object);
+    mutations.into_response(response)
+}
+
+/// The league of the GameResult `object`, when it lives in the namespace of the result.
+/// Owner references cannot cross namespaces; lookup errors are logged and leave the owner
+/// reference to the reconciler.
+async fn owning_league(client: Client, object: &DynamicObject) -> Option<TheLeague> {
+    let result: GameResult = serde_json::from_value(serde_json::to_value(object).ok()?).ok()?;
+    let namespace = result.metadata.namespace.clone()?;
+    let league_ns =
+        references::league_namespace(Some(namespace.clone()), &result.spec.league_namespace);
+    if league_ns != namespace {
+        return None;
+    }
+    let api: Api<TheLeague> = Api::namespaced(client, &namespace);
+    match api.get_opt(&result.spec.league_name).await {
+        Ok(league) => league,
+        Err(e) => {
+            warn!(
+                "Failed to get league '{}' in namespace '{}': {}",
+                result.spec.league_name, namespace, e
+            );
+            None
+        }
+    }
+}
+
+pub(super) async fn mutate_league_review(
+    Json(review): Json<AdmissionReview<DynamicObject>>,
+) -> Json<AdmissionReview<DynamicObject>> {
+    let request: AdmissionRequest<DynamicObject> = match review.try_into() {
+        Ok(request) => request,
+        Err(e) => {
+            warn!("Invalid TheLeague admission review: {}", e);
+            return Json(AdmissionResponse::invalid(e.to_string()).into_review());
+        }
+    };
+    Json(mutate_league(&request).into_review())
+}
+
+pub(super) async fn mutate_standing_review(
+    Json(review): Json<AdmissionReview<DynamicObject>>,
+) -> Json<AdmissionReview<DynamicObject>> {
+    let request: AdmissionRequest<DynamicObject> = match review.try_into() {
+        Ok(request) => request,
+        Err(e) => {
+            warn!("Invalid Standing admission review: {}", e);
+            return Json(AdmissionResponse::invalid(e.to_string()).into_review());
+        }
+    };
+    Json(mutate_standing(&request).into_review())
+}
+
+pub(super) async fn mutate_game_result_review(
+    State(client): State<Client>,
+    Json(review): Json<AdmissionReview<DynamicObject>>,
+) -> Json<AdmissionReview<DynamicObject>> {
+    let request: AdmissionRequest<DynamicObject> = match review.try_into() {
+        Ok(request) => request,
+        Err(e) => {
+            warn!("Invalid GameResult admission review: {}", e);
+            return Json(AdmissionResponse::invalid(e.to_string()).into_review());
+        }
+    };
+    let league = match &request.object {
+        Some(object) => owning_league(client, object).await,
+        None => None,
+    };
+    Json(mutate_game_result(&request, league.as_ref()).into_review())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use json_patch::Patch;
+
+    fn review(kind: &str, resource: &str, object: Value) -> AdmissionRequest<DynamicObject> {
+        let review: AdmissionReview<DynamicObject> = serde_json::from_value(json!({
+            "apiVersion": "admission.k8s.io/v1",
+            "kind": "AdmissionReview",
+            "request": {
+                "uid": "3f1c2b4a-9d8e-4f7a-b6c5-1e2d3c4b5a69",
+                "kind": {"group": "bexxmodd.com", "version": "v1alpha1", "kind": kind},
+                "resource": {"group": "bexxmodd.com", "version": "v1alpha1", "resource": resource},
+                "name": object["metadata"]["name"],
+                "namespace": "default",
+                "operation": "CREATE",
+                "userInfo": {"username": "alex@example.com"},
+                "object": object,
+            }
+        }))
+        .unwrap();
+        review.try_into().unwrap()
+    }
+
+    /// `object` with the patch of `response` applied
+    fn patched(mut object: Value, response: &AdmissionResponse) -> Value {
+        let patch: Patch = serde_json::from_slice(response.patch.as_ref().unwrap()).unwrap();
+        json_patch::patch(&mut object, &patch).unwrap();
+        object
+    }
+
+    #[test]
+    fn test_team_names_are_normalized() {
+        assert_eq!(normalize_team_name("  Red \t Tigers "), "Red Tigers");
+        assert_eq!(normalize_team_name("Sharks"), "Sharks");
+    }
+
+    #[test]
+    fn test_league_is_defaulted_and_normalized() {
+        let object = json!({
+            "apiVersion": "bexxmodd.com/v1alpha1",
+            "kind": "TheLeague",
+            "metadata": {"name": "premier", "namespace": "default"},
+            "spec": {
+                "maxTeams": 4,
+                "teams": [{"name": " Red  Tigers"}, {"name": "Sharks"}],
+                "divisions": [{"name": "North", "teams": ["Red Tigers ", "Sharks"]}],
+            }
+        });
+        let response = mutate_league(&review("TheLeague", "theleagues", object.clone()));
+        assert!(response.allowed);
+        let league = patched(object, &response);
+        assert_eq!(league["spec"]["teams"][0]["name"], "Red Tigers");
+        assert_eq!(league["spec"]["divisions"][0]["teams"][0], "Red Tigers");
+        assert_eq!(
+            league["spec"]["pointsPolicy"],
+            json!(PointsPolicy::default())
+        );
+        let annotations = &league["metadata"]["annotations"];
+        assert_eq!(annotations[CREATED_BY_ANNOTATION], "alex@example.com");
+        assert!(annotations[CREATED_AT_ANNOTATION].is_string());
+    }
+
+    #[test]
+    fn test_league_team_names_are_only_normalized_on_creation() {
+        let object = json!({
+            "apiVersion": "bexxmodd.com/v1alpha1",
+            "kind": "TheLeague",
+            "metadata": {"name": "premier", "namespace": "default"},
+            "spec": {
+                "maxTeams": 4,
+                "pointsPolicy": PointsPolicy::default(),
+                "teams": [{"name": " Red  Tigers"}, {"name": "Sharks"}],
+            }
+        });
+        let mut request = review("TheLeague", "theleagues", object.clone());
+        request.operation = Operation::Update;
+        let response = mutate_league(&request);
+        assert!(response.allowed);
+        assert!(response.patch.is_none());
+    }
+
+    #[test]
+    fn test_game_result_is_owned_by_its_league() {
+        let object = json!({
+            "apiVersion": "bexxmodd.com/v1alpha1",
+            "kind": "GameResult",
+            "metadata": {
+                "name": "round-1",
+                "namespace": "default",
+                "annotations": {"note": "derby"},
+            },
+            "spec": {
+                "leagueName": "premier",
+                "roundNumber": 1,
+                "teams": ["Tigers", "Sharks  "],
+                "time": "2025-01-01T12:00:00Z",
+                "result": {"WinnerHomeTeam": {"score_home": 1, "score_away": 0}},
+                "scorers": [{"team": " Tigers", "firstName": "Alex", "lastName": "Morgan"}],
+            }
+        });
+        let mut league: TheLeague = serde_json::from_value(json!({
+            "apiVersion": "bexxmodd.com/v1alpha1",
+            "kind": "TheLeague",
+            "metadata": {"name": "premier", "namespace": "default", "uid": "league-uid"},
+            "spec": {"maxTeams": 4, "teams": []}
+        }))
+        .unwrap();
+
+        let request = review("GameResult", "gameresults", object.clone());
+        let result = patched(object.clone(), &mutate_game_result(&request, Some(&league)));
+        assert_eq!(result["spec"]["teams"], json!(["Tigers", "Sharks"]));
+        assert_eq!(result["spec"]["scorers"][0]["team"], "Tigers");
+        assert_eq!(
+            result["metadata"]["ownerReferences"][0]["uid"],
+            "league-uid"
+        );
+        assert_eq!(result["metadata"]["annotations"]["note"], "derby");
+        assert_eq!(
+            result["metadata"]["annotations"][CREATED_BY_ANNOTATION],
+            "alex@example.com"
+        );
+
+        // Without a uid the league can't own anything.
+        league.metadata.uid = None;
+        let result = patched(object, &mutate_game_result(&request, Some(&league)));
+        assert!(result["metadata"].get("ownerReferences").is_none());
+    }
+}
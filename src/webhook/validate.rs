@@ -0,0 +1,496 @@
+use crate::api::v1alpha1::game_result_types::GameResult;
+use crate::api::v1alpha1::match_types::Match;
+use crate::api::v1alpha1::suspension_types::{Suspension, SuspensionSpec};
+use crate::api::v1alpha1::the_league_types::{Enforcement, TheLeague};
+use crate::controller::{
+    duplicates, gameresult_controller, matches, references, validation_policy,
+};
+use crate::discipline;
+use crate::validation::{self, ValidationPolicy, Violation};
+use crate::webhook::metrics;
+
+use axum::Json;
+use axum::extract::State;
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::Time;
+use k8s_openapi::chrono::Utc;
+use kube::api::ListParams;
+use kube::core::DynamicObject;
+use kube::core::admission::{AdmissionRequest, AdmissionResponse, AdmissionReview, Operation};
+use kube::{Api, Client};
+use tracing::warn;
+
+/// Rule name of the warnings about suspended scorers
+const SUSPENDED_SCORER_RULE: &str = "SuspendedScorer";
+
+/// Decide on an admission request for a GameResult.
+///
+/// `suspensions` are the Suspensions of the result's league; suspended scorers produce
+/// warnings but never deny the request. `duplicate` is the violation of a new result
+/// recording a fixture that already has a result, handled as the `policy` says.
+pub fn review_game_result(
+    request: &AdmissionRequest<GameResult>,
+    suspensions: &[SuspensionSpec],
+    duplicate: Option<Violation>,
+    policy: &ValidationPolicy,
+) -> AdmissionResponse {
+    // Deletions carry no new object and are always allowed.
+    let Some(result) = &request.object else {
+        return AdmissionResponse::from(request);
+    };
+    let mut violations = validation::validate_result_consistency(&result.spec);
+    if let Some(old) = &request.old_object
+        && changes_applied_spec(old, result)
+    {
+        violations.push(gameresult_controller::immutable_spec_violation());
+    }
+    let mut warnings = discipline::suspended_scorer_warnings(&result.spec, suspensions)
+        .into_iter()
+        .map(|warning| (SUSPENDED_SCORER_RULE.to_string(), warning))
+        .collect();
+    let duplicate = duplicate.into_iter().collect();
+    enforce(
+        policy.duplicate_result,
+        duplicate,
+        &mut violations,
+        &mut warnings,
+    );
+    metrics::decide(request, &violations, warnings)
+}
+
+/// Add the `found` violations of a rule with the given `enforcement` to the `violations`
+/// denying the request or to its `warnings`, or drop them if the rule is off.
+fn enforce(
+    enforcement: Enforcement,
+    found: Vec<Violation>,
+    violations: &mut Vec<Violation>,
+    warnings: &mut Vec<(String, String)>,
+) {
+    match enforcement {
+        Enforcement::Enforce => violations.extend(found),
+        Enforcement::Warn => warnings.extend(
+            found
+                .into_iter()
+                .map(|v| (v.reason.clone(), format!("{}: {}", v.reason, v.message))),
+        ),
+        Enforcement::Off => {}
+    }
+}
+
+/// The violation of a new `result` recording a fixture its league already has a result for.
+/// Lookup errors are logged and treated as no duplicate; the reconciler reports it then.
+async fn new_duplicate(client: &Client, result: &GameResult) -> Option<Violation> {
+    let namespace = references::league_namespace(
+        result.metadata.namespace.clone(),
+        &result.spec.league_namespace,
+    );
+    let api: Api<TheLeague> = Api::namespaced(client.clone(), &namespace);
+    let results = match api.get_opt(&result.spec.league_name).await {
+        Ok(Some(league)) => {
+            gameresult_controller::league_results(client, &namespace, &league).await
+        }
+        Ok(None) => return None,
+        Err(e) => Err(e),
+    };
+    match results {
+        Ok(results) => {
+            // The new result is younger than every existing one.
+            let mut created = result.clone();
+            created.metadata.creation_timestamp = Some(Time(Utc::now()));
+            duplicates::duplicate_of(&created, &results)
+                .map(|(reason, message)| Violation::new(&reason, message))
+        }
+        Err(e) => {
+            warn!(
+                "Failed to list the GameResults of league '{}/{}': {}",
+                namespace, result.spec.league_name, e
+            );
+            None
+        }
+    }
+}
+
+/// The Suspensions in the namespace of the league `result` belongs to.
+/// Lookup errors are logged and treated as no Suspensions, as they only produce warnings.
+async fn league_suspensions(client: Client, result: &GameResult) -> Vec<SuspensionSpec> {
+    let namespace = references::league_namespace(
+        result.metadata.namespace.clone(),
+        &result.spec.league_namespace,
+    );
+    let api: Api<Suspension> = Api::namespaced(client, &namespace);
+    match api.list(&ListParams::default()).await {
+        Ok(list) => list.items.into_iter().map(|s| s.spec).collect(),
+        Err(e) => {
+            warn!(
+                "Failed to list Suspensions in namespace '{}': {}",
+                namespace, e
+            );
+            Vec::new()
+        }
+    }
+}
+
+/// Whether an update changes the spec of a result already counted in the standings
+/// without going through the correction path.
+fn changes_applied_spec(old: &GameResult, new: &GameResult) -> bool {
+    gameresult_controller::applied_generation(old).is_some()
+        && !gameresult_controller::has_recompute_annotation(new)
+        && serde_json::to_value(&old.spec).ok() != serde_json::to_value(&new.spec).ok()
+}
+
+pub(super) async fn validate_game_result(
+    State(client): State<Client>,
+    Json(review): Json<AdmissionReview<GameResult>>,
+) -> Json<AdmissionReview<DynamicObject>> {
+    let request: AdmissionRequest<GameResult> = match review.try_into() {
+        Ok(request) => request,
+        Err(e) => {
+            warn!("Invalid GameResult admission review: {}", e);
+            return Json(AdmissionResponse::invalid(e.to_string()).into_review());
+        }
+    };
+    let policy = validation_policy::load(&client).await;
+    let duplicate = match &request.object {
+        Some(result)
+            if request.operation == Operation::Create
+                && policy.duplicate_result != Enforcement::Off =>
+        {
+            new_duplicate(&client, result).await
+        }
+        _ => None,
+    };
+    let suspensions = match &request.object {
+        Some(result) if !result.spec.scorers.is_empty() => league_suspensions(client, result).await,
+        _ => Vec::new(),
+    };
+    Json(review_game_result(&request, &suspensions, duplicate, &policy).into_review())
+}
+
+/// Decide on an admission request for a TheLeague.
+///
+/// Only the embedded rosters are checked; the rosters of referenced Teams are validated by
+/// the league reconciler once they are resolved. Divisions may only list registered teams.
+/// Leagues with a roster lock can't change their teams while `round_in_progress` is played,
+/// or only get a warning when the lock is not enforced. Roster sizes and eligibility are
+/// handled as the `policy` says.
+pub fn review_league(
+    request: &AdmissionRequest<TheLeague>,
+    round_in_progress: Option<u32>,
+    policy: &ValidationPolicy,
+) -> AdmissionResponse {
+    let Some(league) = &request.object else {
+        return AdmissionResponse::from(request);
+    };
+    let (mut violations, warned) =
+        validation::validate_league(&league.spec, policy, Utc::now().date_naive());
+    let mut warnings = warned
+        .into_iter()
+        .map(|v| (v.reason.clone(), format!("{}: {}", v.reason, v.message)))
+        .collect();
+    if let (Some(old), Some(lock)) = (&request.old_object, &league.spec.roster_lock) {
+        let locked = validation::validate_roster_lock(&old.spec, &league.spec, round_in_progress);
+        enforce(lock.enforcement, locked, &mut violations, &mut warnings);
+    }
+    metrics::decide(request, &violations, warnings)
+}
+
+/// The round in progress of the league of an update changing the teams of a league with a
+/// roster lock. Lookup errors are logged and treated as no round in progress.
+async fn locking_round(client: Client, request: &AdmissionRequest<TheLeague>) -> Option<u32> {
+    let (Some(league), Some(old)) = (&request.object, &request.old_object) else {
+        return None;
+    };
+    if league.spec.roster_lock.is_none() || !validation::teams_changed(&old.spec, &league.spec) {
+        return None;
+    }
+    let namespace = league.metadata.namespace.clone().unwrap_or_default();
+    let api: Api<Match> = Api::namespaced(client, &namespace);
+    let season = old.status.as_ref().and_then(|s| s.current_season);
+    match matches::league_matches(&api, &request.name).await {
+        Ok(all) => {
+            let current: Vec<Match> = all
+                .into_iter()
+                .filter(|m| m.spec.season == season)
+                .collect();
+            matches::round_in_progress(&current, Utc::now())
+        }
+        Err(e) => {
+            warn!(
+                "Failed to list the Matches of league '{}' in namespace '{}': {}",
+                request.name, namespace, e
+            );
+            None
+        }
+    }
+}
+
+pub(super) async fn validate_league(
+    State(client): State<Client>,
+    Json(review): Json<AdmissionReview<TheLeague>>,
+) -> Json<AdmissionReview<DynamicObject>> {
+    let request: AdmissionRequest<TheLeague> = match review.try_into() {
+        Ok(request) => request,
+        Err(e) => {
+            warn!("Invalid TheLeague admission review: {}", e);
+            return Json(AdmissionResponse::invalid(e.to_string()).into_review());
+        }
+    };
+    let policy = validation_policy::load(&client).await;
+    let round_in_progress = locking_round(client, &request).await;
+    Json(review_league(&request, round_in_progress, &policy).into_review())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn game_result(result: serde_json::Value) -> serde_json::Value {
+        json!({
+            "apiVersion": "bexxmodd.com/v1alpha1",
+            "kind": "GameResult",
+            "metadata": {"name": "round-1", "namespace": "default"},
+            "spec": {
+                "leagueName": "premier",
+                "roundNumber": 1,
+                "teams": ["Tigers", "Sharks"],
+                "time": "2025-01-01T12:00:00Z",
+                "result": result,
+            }
+        })
+    }
+
+    fn request(result: serde_json::Value) -> AdmissionRequest<GameResult> {
+        review("CREATE", game_result(result), None)
+    }
+
+    fn review(
+        operation: &str,
+        object: serde_json::Value,
+        old_object: Option<serde_json::Value>,
+    ) -> AdmissionRequest<GameResult> {
+        let review: AdmissionReview<GameResult> = serde_json::from_value(json!({
+            "apiVersion": "admission.k8s.io/v1",
+            "kind": "AdmissionReview",
+            "request": {
+                "uid": "0df28fbd-5f5f-4e6a-9ed5-3d8f4c3e0a11",
+                "kind": {"group": "bexxmodd.com", "version": "v1alpha1", "kind": "GameResult"},
+                "resource": {"group": "bexxmodd.com", "version": "v1alpha1", "resource": "gameresults"},
+                "name": "round-1",
+                "namespace": "default",
+                "operation": operation,
+                "userInfo": {},
+                "object": object,
+                "oldObject": old_object,
+            }
+        }))
+        .unwrap();
+        review.try_into().unwrap()
+    }
+
+    #[test]
+    fn test_inconsistent_score_is_denied() {
+        let response = review_game_result(
+            &request(json!({"WinnerHomeTeam": {"score_home": 0, "score_away": 2}})),
+            &[],
+            None,
+            &ValidationPolicy::default(),
+        );
+        assert!(!response.allowed);
+        assert!(response.result.message.contains("InconsistentScore"));
+
+        let response = review_game_result(
+            &request(json!({"WinnerAwayTeam": {"score_home": 0, "score_away": 2}})),
+            &[],
+            None,
+            &ValidationPolicy::default(),
+        );
+        assert!(response.allowed);
+    }
+
+    #[test]
+    fn test_applied_spec_changes_need_recompute_annotation() {
+        let mut old = game_result(json!({"Draw": {"score": 1}}));
+        old["status"] = json!({"appliedGeneration": 1});
+        let mut new = game_result(json!({"Draw": {"score": 2}}));
+
+        let policy = ValidationPolicy::default();
+        let update = review("UPDATE", new.clone(), Some(old.clone()));
+        let response = review_game_result(&update, &[], None, &policy);
+        assert!(!response.allowed);
+        assert!(response.result.message.contains("ImmutableSpec"));
+
+        new["metadata"]["annotations"] =
+            json!({ gameresult_controller::RECOMPUTE_ANNOTATION: "score typo" });
+        let response = review_game_result(&review("UPDATE", new, Some(old)), &[], None, &policy);
+        assert!(response.allowed);
+    }
+
+    #[test]
+    fn test_suspended_scorer_is_allowed_with_warning() {
+        let mut object = game_result(json!({"WinnerHomeTeam": {"score_home": 1, "score_away": 0}}));
+        object["spec"]["scorers"] =
+            json!([{"team": "Tigers", "firstName": "Alex", "lastName": "Morgan"}]);
+        let suspension: SuspensionSpec = serde_json::from_value(json!({
+            "leagueName": "premier",
+            "team": "Tigers",
+            "firstName": "Alex",
+            "lastName": "Morgan",
+            "reason": "RedCard",
+        }))
+        .unwrap();
+
+        let response = review_game_result(
+            &review("CREATE", object, None),
+            &[suspension],
+            None,
+            &ValidationPolicy::default(),
+        );
+        assert!(response.allowed);
+        assert_eq!(response.warnings.unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_duplicate_result_follows_the_policy() {
+        let request = request(json!({"Draw": {"score": 1}}));
+        let duplicate = || {
+            Some(Violation::new(
+                "Duplicate",
+                "duplicates 'default/round-1-a'",
+            ))
+        };
+        let mut policy = ValidationPolicy::default();
+
+        let response = review_game_result(&request, &[], duplicate(), &policy);
+        assert!(!response.allowed);
+        assert!(response.result.message.starts_with("Duplicate: "));
+
+        policy.duplicate_result = Enforcement::Warn;
+        let response = review_game_result(&request, &[], duplicate(), &policy);
+        assert!(response.allowed);
+        assert_eq!(response.warnings.unwrap().len(), 1);
+
+        policy.duplicate_result = Enforcement::Off;
+        let response = review_game_result(&request, &[], duplicate(), &policy);
+        assert!(response.allowed);
+        assert!(response.warnings.is_none());
+    }
+
+    #[test]
+    fn test_invalid_rosters_are_denied() {
+        let player = |first: &str, number: u8| json!({"firstName": first, "lastName": "Morgan", "jerseyNumber": number});
+        let league = |second: u8| {
+            json!({
+                "apiVersion": "bexxmodd.com/v1alpha1",
+                "kind": "TheLeague",
+                "metadata": {"name": "premier", "namespace": "default"},
+                "spec": {
+                    "maxTeams": 4,
+                    "matchups": 1,
+                    "teams": [{
+                        "name": "Tigers",
+                        "players": [player("Alex", 9), player("Sam", second)]
+                    }]
+                }
+            })
+        };
+        let review = |object: serde_json::Value| -> AdmissionRequest<TheLeague> {
+            let review: AdmissionReview<TheLeague> = serde_json::from_value(json!({
+                "apiVersion": "admission.k8s.io/v1",
+                "kind": "AdmissionReview",
+                "request": {
+                    "uid": "6c3b3a0e-8a55-4b8e-9f0e-0f6a4f6c2b11",
+                    "kind": {"group": "bexxmodd.com", "version": "v1alpha1", "kind": "TheLeague"},
+                    "resource": {"group": "bexxmodd.com", "version": "v1alpha1", "resource": "theleagues"},
+                    "name": "premier",
+                    "namespace": "default",
+                    "operation": "CREATE",
+                    "userInfo": {},
+                    "object": object,
+                }
+            }))
+            .unwrap();
+            review.try_into().unwrap()
+        };
+
+        let policy = ValidationPolicy::default();
+        assert!(review_league(&review(league(10)), None, &policy).allowed);
+        let response = review_league(&review(league(9)), None, &policy);
+        assert!(!response.allowed);
+        assert!(response.result.message.contains("DuplicateJerseyNumber"));
+
+        // Dry-run requests are reviewed like real ones.
+        let mut request = review(league(9));
+        request.dry_run = true;
+        assert!(!review_league(&request, None, &policy).allowed);
+
+        let mut object = league(10);
+        object["spec"]["teams"][0]["captain"] = json!("Jo Morgan");
+        let response = review_league(&review(object), None, &policy);
+        assert!(response.result.message.contains("CaptainNotOnRoster"));
+
+        // Roster sizes are enforced, only warned about or skipped as the policy says.
+        let mut object = league(10);
+        object["spec"]["minPlayersPerTeam"] = json!(3);
+        let response = review_league(&review(object.clone()), None, &policy);
+        assert!(response.result.message.contains("TooFewPlayers"));
+        let warn = ValidationPolicy {
+            roster_size: Enforcement::Warn,
+            ..Default::default()
+        };
+        let response = review_league(&review(object.clone()), None, &warn);
+        assert!(response.allowed);
+        assert!(response.warnings.unwrap()[0].starts_with("TooFewPlayers"));
+        let off = ValidationPolicy {
+            roster_size: Enforcement::Off,
+            ..Default::default()
+        };
+        let response = review_league(&review(object), None, &off);
+        assert!(response.allowed && response.warnings.is_none());
+    }
+
+    #[test]
+    fn test_roster_lock_denies_or_warns_during_a_round() {
+        let league = |team: &str, enforcement: &str| {
+            json!({
+                "apiVersion": "bexxmodd.com/v1alpha1",
+                "kind": "TheLeague",
+                "metadata": {"name": "premier", "namespace": "default"},
+                "spec": {
+                    "maxTeams": 4,
+                    "matchups": 1,
+                    "rosterLock": {"enforcement": enforcement},
+                    "teams": [{"name": "Tigers"}, {"name": team}]
+                }
+            })
+        };
+        let review = |enforcement: &str| -> AdmissionRequest<TheLeague> {
+            let review: AdmissionReview<TheLeague> = serde_json::from_value(json!({
+                "apiVersion": "admission.k8s.io/v1",
+                "kind": "AdmissionReview",
+                "request": {
+                    "uid": "6c3b3a0e-8a55-4b8e-9f0e-0f6a4f6c2b12",
+                    "kind": {"group": "bexxmodd.com", "version": "v1alpha1", "kind": "TheLeague"},
+                    "resource": {"group": "bexxmodd.com", "version": "v1alpha1", "resource": "theleagues"},
+                    "name": "premier",
+                    "namespace": "default",
+                    "operation": "UPDATE",
+                    "userInfo": {},
+                    "object": league("Lions", enforcement),
+                    "oldObject": league("Bears", enforcement),
+                }
+            }))
+            .unwrap();
+            review.try_into().unwrap()
+        };
+
+        let policy = ValidationPolicy::default();
+        assert!(review_league(&review("Enforce"), None, &policy).allowed);
+        let response = review_league(&review("Enforce"), Some(2), &policy);
+        assert!(!response.allowed);
+        assert!(response.result.message.contains("RosterLocked"));
+
+        let response = review_league(&review("Warn"), Some(2), &policy);
+        assert!(response.allowed);
+        assert!(response.warnings.unwrap()[0].starts_with("RosterLocked"));
+    }
+}